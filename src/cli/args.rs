@@ -1,13 +1,32 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
+use chrono::NaiveDateTime;
+use clap::builder::TypedValueParser;
 use clap::{Arg, ArgAction, ArgMatches, Command, ValueHint};
 
+use crate::config::{
+    BinaryFormat, BooleanStyle, CommaStyle, DisplayTimeZone, IsolationLevel, JsonContractVersion,
+    KeywordCase,
+};
+use crate::db::pool;
+
 #[derive(Debug, Clone)]
 pub struct OutputFlags {
     pub json: bool,
     pub markdown: bool,
     pub pretty: bool,
+    /// `--expanded`: one name/value block per row instead of a wide table.
+    pub expanded: bool,
+    /// `--no-color`: no ANSI styling on stdout or stderr, like `NO_COLOR`.
+    pub no_color: bool,
+    /// `--fields`: columns to keep, in order, for list commands.
+    pub fields: Vec<String>,
+    /// `--sort`: `column[:desc]` keys, most significant first.
+    pub sort: Vec<String>,
+    /// `--filter`: `column op value` clauses, all of which must match.
+    pub filters: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,9 +40,38 @@ pub struct CliArgs {
     pub user: Option<String>,
     pub password: Option<String>,
     pub timeout_ms: Option<u64>,
+    /// `--isolation-level`: overrides the profile's `session.isolationLevel`.
+    pub isolation_level: Option<IsolationLevel>,
+    /// `--lock-timeout`: overrides the profile's `session.lockTimeout`.
+    pub lock_timeout_ms: Option<i64>,
+    /// `--run-id`: CONTEXT_INFO for every connection this run opens.
+    pub run_id: Option<String>,
     pub allow_write: bool,
+    /// `--yes`: skip the confirmation asked before writes to production profiles.
+    pub yes: bool,
     pub encrypt: Option<bool>,
     pub trust_cert: Option<bool>,
+    pub time_zone: Option<DisplayTimeZone>,
+    /// `--source-tz`: zone `datetime`/`datetime2` values were written in.
+    pub source_time_zone: Option<DisplayTimeZone>,
+    /// `--binary`: how binary cells are written, overriding `settings.output.binary`.
+    pub binary: Option<BinaryFormat>,
+    /// `--max-value-bytes`: cap on text and binary cells; 0 lifts a configured cap.
+    pub max_value_bytes: Option<usize>,
+    /// `--null-display`: text for NULL cells in tables.
+    pub null_display: Option<String>,
+    /// `--booleans`: `true-false` or `yes-no` in tables.
+    pub booleans: Option<BooleanStyle>,
+    /// `--max-cell-width`: query-table cell cap; 0 means never truncate cells.
+    pub max_cell_width: Option<usize>,
+    /// `--max-output-chars`: query-table output cap; 0 means never truncate output.
+    pub max_output_chars: Option<usize>,
+    /// `--refresh`: ask the server again instead of using the metadata cache.
+    pub refresh: bool,
+    /// `--contract`: JSON contract version, overriding `settings.output.json.contractVersion`.
+    pub contract: Option<JsonContractVersion>,
+    /// Subcommand names as typed, aliases resolved (`trace start`); reported in JSON v2 `meta`.
+    pub command_path: String,
     pub output: OutputFlags,
     pub verbose: u8,
     pub quiet: bool,
@@ -33,36 +81,90 @@ pub struct CliArgs {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommandKind {
-    Help { all: bool, command: Option<String> },
+    Help {
+        all: bool,
+        /// `--schema`: print JSON Schema instead of help text.
+        schema: bool,
+        command: Option<String>,
+    },
     Status(StatusArgs),
     Databases(DatabasesArgs),
     Tables(TablesArgs),
     Describe(DescribeArgs),
     Sql(SqlArgs),
     TableData(TableDataArgs),
+    Profile(ProfileArgs),
     Columns(ColumnsArgs),
     Update(UpdateArgs),
     Indexes(IndexesArgs),
     ForeignKeys(ForeignKeysArgs),
+    FkCheck(FkCheckArgs),
     StoredProcs(StoredProcsArgs),
     Sessions(SessionsArgs),
     QueryStats(QueryStatsArgs),
+    Trace(TraceArgs),
     Backups(BackupsArgs),
+    Assemblies(AssembliesArgs),
+    Changes(ChangesArgs),
+    Partitions(PartitionsArgs),
+    Errorlog(ErrorlogArgs),
+    Ag(AgArgs),
+    Cdc(CdcArgs),
+    Replication(ReplicationArgs),
     Compare(CompareArgs),
+    Verify(VerifyArgs),
+    Assert(AssertArgs),
+    Permissions(PermissionsArgs),
+    SchemaApply(SchemaApplyArgs),
+    Migrate(MigrateArgs),
+    Seed(SeedArgs),
+    Serve(ServeArgs),
+    SchemaExport(SchemaExportArgs),
+    GrantScript(GrantScriptArgs),
+    Docs(DocsArgs),
+    Fmt(FmtArgs),
+    Lint(LintArgs),
     Init(InitArgs),
     Config(ConfigArgs),
+    Profiles(ProfilesArgs),
     Completions(CompletionsArgs),
     Integrations(IntegrationsArgs),
 }
 
+impl CommandKind {
+    /// Commands shell prompts and completion scripts call constantly: they never read config,
+    /// and startup skips logging setup and the update check for them.
+    pub fn is_fast_path(&self) -> bool {
+        matches!(self, CommandKind::Help { .. } | CommandKind::Completions(_))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct StatusArgs;
+pub struct StatusArgs {
+    pub deep: bool,
+    /// `--diagnose`: check DNS, TCP, pre-login, TLS, login, and database access in turn.
+    pub diagnose: bool,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DatabasesArgs {
     pub name: Option<String>,
     pub owner: Option<String>,
     pub include_system: bool,
+    /// `--details`: sizes, compatibility level, and last backups.
+    pub details: bool,
+    /// `--foreach`: read-only SQL to run in each matching database instead of listing them.
+    pub foreach: Option<String>,
+    /// `--create-script <db>`: script the database's schema for creating an empty copy.
+    pub create_script: Option<String>,
+    /// `--include-files`: start the script with CREATE DATABASE, its files and filegroups.
+    pub include_files: bool,
+    /// `--include-security`: users, roles, role memberships, and permissions.
+    pub include_security: bool,
+    /// `--include-data`: tables whose rows are scripted after the tables are created.
+    pub include_data: Vec<String>,
+    /// `--out`: create script destination; stdout when absent or `-`.
+    pub out: Option<PathBuf>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
 }
@@ -75,6 +177,11 @@ pub struct TablesArgs {
     pub with_counts: bool,
     pub summary: bool,
     pub describe: bool,
+    /// Connections used in parallel by `--describe`.
+    pub concurrency: usize,
+    /// Run the referential integrity audit instead of listing tables.
+    pub orphaned: bool,
+    pub modified_since: Option<ModifiedSince>,
     pub limit: Option<String>,
     pub offset: Option<u64>,
 }
@@ -89,8 +196,12 @@ pub struct DescribeArgs {
     pub no_indexes: bool,
     pub no_triggers: bool,
     pub no_ddl: bool,
+    pub ddl_full: bool,
     pub include_fks: bool,
     pub include_constraints: bool,
+    pub permissions: bool,
+    /// Cap on the objects a wildcard name expands to.
+    pub max_objects: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -99,11 +210,30 @@ pub struct SqlArgs {
     pub file: Option<PathBuf>,
     pub stdin: bool,
     pub params: Vec<String>,
+    /// `--params-file`: named parameters from a YAML or JSON mapping.
+    pub params_file: Option<PathBuf>,
     pub max_rows: Option<u64>,
     pub csv: Option<PathBuf>,
+    /// `--out`: extra files to write results to, format from each extension.
+    pub out: Vec<PathBuf>,
+    pub to: Option<String>,
+    pub storage_tier: Option<String>,
     pub dry_run: bool,
     pub continue_on_error: bool,
     pub no_truncate: bool,
+    /// `--save-blobs`: write large text and binary cells here and show their paths instead.
+    pub save_blobs: Option<PathBuf>,
+    /// `--format-sql`: print the SQL reformatted instead of running it.
+    pub format_sql: bool,
+    pub style: FormatStyleArgs,
+}
+
+/// `--keyword-case`/`--commas`/`--indent`: per-run overrides of `settings.formatting`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatStyleArgs {
+    pub keyword_case: Option<KeywordCase>,
+    pub commas: Option<CommaStyle>,
+    pub indent: Option<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -116,8 +246,66 @@ pub struct TableDataArgs {
     pub limit: Option<u64>,
     pub offset: Option<u64>,
     pub params: Vec<String>,
+    /// `--params-file`: named parameters from a YAML or JSON mapping.
+    pub params_file: Option<PathBuf>,
     pub csv: Option<PathBuf>,
+    /// `--out`: extra files to write results to, format from each extension.
+    pub out: Vec<PathBuf>,
+    pub to: Option<String>,
+    pub storage_tier: Option<String>,
     pub no_truncate: bool,
+    pub follow: Option<String>,
+    pub interval_ms: u64,
+    pub from_now: bool,
+    /// `--as-of`: read a system-versioned table as it was at this UTC time.
+    pub as_of: Option<NaiveDateTime>,
+    /// `--history`: read the temporal table's history table instead.
+    pub history: bool,
+    /// `--sample`: pick representative rows instead of a page.
+    pub sample: Option<Sample>,
+    /// `--save-blobs`: write large text and binary cells here and show their paths instead.
+    pub save_blobs: Option<PathBuf>,
+    pub paginate: Paginate,
+    /// `--key`: comma-separated keyset columns; the primary key when not given.
+    pub key: Option<String>,
+    /// `--after`: keyset continuation token from a previous page's `nextKey`.
+    pub after: Option<String>,
+}
+
+/// `table-data --paginate` modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Paginate {
+    /// `OFFSET ... FETCH NEXT`, driven by `--offset`.
+    #[default]
+    Offset,
+    /// `WHERE key > last ORDER BY key`, resumed with `--after`.
+    Keyset,
+}
+
+/// `table-data --sample` modes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sample {
+    /// `random:N`: N rows in random order.
+    Random(u64),
+    /// `first:N`: the N rows with the lowest key.
+    First(u64),
+    /// `last:N`: the N rows with the highest key.
+    Last(u64),
+    /// `stratified:Column[:N]`: up to N random rows for each value of the column.
+    Stratified { column: String, per_value: u64 },
+}
+
+impl std::fmt::Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sample::Random(n) => write!(f, "random:{}", n),
+            Sample::First(n) => write!(f, "first:{}", n),
+            Sample::Last(n) => write!(f, "last:{}", n),
+            Sample::Stratified { column, per_value } => {
+                write!(f, "stratified:{}:{}", column, per_value)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -139,6 +327,8 @@ pub struct IndexesArgs {
     pub table: Option<String>,
     pub schema: Option<String>,
     pub show_usage: bool,
+    pub recommend_compression: bool,
+    pub max_objects: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -146,6 +336,17 @@ pub struct ForeignKeysArgs {
     pub table: Option<String>,
     pub schema: Option<String>,
     pub direction: Option<String>,
+    pub graph: Option<String>,
+    pub depth: u32,
+    pub max_objects: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FkCheckArgs {
+    pub table: Option<String>,
+    pub schema: Option<String>,
+    /// `--all`: scan trusted constraints too, not only disabled or untrusted ones.
+    pub all: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -157,7 +358,15 @@ pub struct StoredProcsArgs {
     pub offset: Option<u64>,
     pub exec: Option<String>,
     pub args: Option<String>,
+    pub params: Vec<String>,
     pub no_truncate: bool,
+    pub grep: Option<String>,
+    pub context: u32,
+    pub history: Option<String>,
+    pub days: u32,
+    pub modified_since: Option<ModifiedSince>,
+    /// YAML file of procedure smoke tests, each run inside a rolled-back transaction.
+    pub test: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -167,6 +376,10 @@ pub struct SessionsArgs {
     pub host: Option<String>,
     pub status: Option<String>,
     pub limit: Option<u64>,
+    /// CPU, I/O, memory, tempdb, and transaction columns per session.
+    pub resource_usage: bool,
+    /// `--top N`: the N heaviest sessions, implying `--resource-usage`.
+    pub top: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -174,6 +387,95 @@ pub struct QueryStatsArgs {
     pub database: Option<String>,
     pub order: Option<String>,
     pub limit: Option<u64>,
+    /// Write a replay workload file (`-` for stdout) instead of the summary table.
+    pub export_workload: Option<PathBuf>,
+    /// Rank stored procedures (`sys.dm_exec_procedure_stats`) instead of statements.
+    pub procs: bool,
+    pub schema: Option<String>,
+    pub name: Option<String>,
+    /// Plan cache health and memory grants instead of the query ranking.
+    pub plan_cache: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceArgs {
+    pub command: TraceCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceCommand {
+    Start(TraceStartArgs),
+    Tail(TraceTailArgs),
+    Stop(TraceStopArgs),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStartArgs {
+    pub name: String,
+    pub events: Vec<String>,
+    /// Predicate such as `duration > 500ms and database = Sales`.
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceTailArgs {
+    pub name: String,
+    pub interval_ms: u64,
+    /// Print the events buffered so far and exit instead of polling.
+    pub once: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStopArgs {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrateArgs {
+    /// `--dir`, overriding `settings.migrations.directory`.
+    pub dir: Option<PathBuf>,
+    pub command: MigrateCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrateCommand {
+    /// Create an empty, timestamp-versioned migration file.
+    New {
+        name: String,
+    },
+    Status,
+    Up(MigrateUpArgs),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrateUpArgs {
+    /// List what would run, and the batches, without executing anything.
+    pub dry_run: bool,
+    /// Stop after this version instead of applying everything pending.
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedArgs {
+    pub command: SeedCommand,
+    /// Seed files, or directories of `.yaml`/`.yml`/`.json` seed files.
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedCommand {
+    /// Write the delta (needs `--allow-write`).
+    Apply,
+    /// Show the delta; exit 3 when tables are out of sync.
+    Diff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServeArgs {
+    /// Listen address (default: settings.serve.bind).
+    pub bind: Option<String>,
+    /// HTTP port; `serve` reads the global `--port` as its own (default: settings.serve.port).
+    pub port: Option<u16>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -182,6 +484,87 @@ pub struct BackupsArgs {
     pub since: Option<u64>,
     pub backup_type: Option<String>,
     pub limit: Option<u64>,
+    pub to: Option<String>,
+    pub storage_tier: Option<String>,
+    /// `--check`: evaluate every database against the backup-age thresholds.
+    pub check: bool,
+    /// `--max-full-age`, in minutes.
+    pub max_full_age: Option<u64>,
+    /// `--max-log-age`, in minutes; only full and bulk-logged recovery databases are checked.
+    pub max_log_age: Option<u64>,
+    /// `--history <db>`: the backup chain since the database's latest full backup.
+    pub history: Option<String>,
+    /// `--restore-script <db>`: write a RESTORE script from msdb history.
+    pub restore_script: Option<String>,
+    /// `--stop-at`: point in time for the restore script, server local time.
+    pub stop_at: Option<NaiveDateTime>,
+    /// `--out`: restore script destination; stdout when absent or `-`.
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorlogArgs {
+    /// Only entries newer than this many minutes.
+    pub last_minutes: Option<u64>,
+    pub grep: Option<String>,
+    pub archive: u32,
+    pub min_severity: Option<u32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// `ag status`; the only subcommand today, and the default when none is given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgArgs {
+    pub group: Option<String>,
+}
+
+/// `cdc status`, the default subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdcArgs {
+    pub table: Option<String>,
+}
+
+/// `replication status`, the default subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicationArgs {
+    pub publication: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembliesArgs {
+    pub name: Option<String>,
+    pub include_system: bool,
+}
+
+/// Objects created or modified since a point in time, grouped by type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangesArgs {
+    pub since: ModifiedSince,
+    pub schema: Option<String>,
+    pub object_type: Option<String>,
+    pub include_system: bool,
+}
+
+/// `--modified-since`/`--since`: a window back from now or a fixed server-local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifiedSince {
+    /// `7d`, `12h`, `30m`, or bare minutes.
+    Minutes(u64),
+    /// `2024-01-01` or `2024-01-01T09:30`.
+    At(NaiveDateTime),
+}
+
+/// Without `table`, lists partitioned tables; with it, the table's partitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionsArgs {
+    pub table: Option<String>,
+    /// `--script-split`: boundary value to add.
+    pub script_split: Option<String>,
+    /// `--script-merge`: boundary value to remove.
+    pub script_merge: Option<String>,
+    /// Filegroup for the partition a split creates.
+    pub filegroup: Option<String>,
 }
 
 /// Arguments for schema drift comparison between two connections.
@@ -190,19 +573,176 @@ pub struct CompareArgs {
     pub source: Option<String>,
     pub target: String,
     pub source_connection: Option<String>,
+    /// Compare a dacpac's model instead of a source database.
+    pub source_dacpac: Option<PathBuf>,
+    /// Scratch database the dacpac's model is built in.
+    pub shadow_database: Option<String>,
     pub target_connection: Option<String>,
     pub schemas: Option<Vec<String>>,
     pub object: Option<String>,
+    /// `--out`: directory for per-module patch files from `--object`.
+    pub out: Option<PathBuf>,
+    /// `--patch`: write the `--object` diff as a `git apply`-ready patch (`-` for stdout).
+    pub patch: Option<PathBuf>,
     pub summary: bool,
     pub pretty: bool,
     pub ignore_whitespace: bool,
     pub strip_comments: bool,
+    /// Run definitions through the SQL formatter before comparing them.
+    pub format_sql: bool,
     pub side_by_side: bool,
     pub gui_diff: bool,
     pub apply_script: bool,
     pub apply_path: Option<String>,
     pub include_drops: bool,
     pub compact: bool,
+    /// Per-query limit for snapshot fetches; `None` waits indefinitely.
+    pub query_timeout_ms: Option<u64>,
+    /// Accepted-drift rules; defaults to the nearest `.sscli-compare-ignore`.
+    pub ignore_file: Option<PathBuf>,
+    /// Categories to compare (`modules`, `tables`, `indexes`, `constraints`); empty means all.
+    pub only: Vec<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+/// Arguments for row count/checksum verification between two connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyArgs {
+    pub source: Option<String>,
+    pub target: String,
+    pub source_connection: Option<String>,
+    pub target_connection: Option<String>,
+    pub tables: Vec<String>,
+    pub checksum: bool,
+    pub chunk_size: Option<u64>,
+}
+
+/// A query and the result it must produce, for CI checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertArgs {
+    pub sql: Option<String>,
+    pub file: Option<PathBuf>,
+    pub params: Vec<String>,
+    pub expect: Expectation,
+    /// `--name`: label for the assertion in output.
+    pub name: Option<String>,
+}
+
+/// What `assert` checks. Bounds are kept as the text given; clap has already checked they parse
+/// as numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// `--expect`: the first column of the first row equals this (numerically when both parse).
+    Equals(String),
+    /// `--expect-lt`: that value is a number below this.
+    LessThan(String),
+    /// `--expect-gt`: that value is a number above this.
+    GreaterThan(String),
+    /// `--expect-empty`: the query returns no rows.
+    Empty,
+}
+
+/// `permissions check`, the default subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionsArgs {
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileArgs {
+    pub table: Option<String>,
+    pub schema: Option<String>,
+    /// Columns to profile; defaults to all of them.
+    pub columns: Vec<String>,
+    /// Most frequent values reported per column.
+    pub top: u32,
+    /// Tables with more rows than this get approximate distinct counts and sampled top values.
+    pub exact_threshold: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaApplyArgs {
+    /// Directory of CREATE scripts describing the desired state.
+    pub dir: PathBuf,
+    /// Schemas to reconcile; defaults to every schema the model creates objects in.
+    pub schemas: Vec<String>,
+    pub shadow_database: Option<String>,
+    pub include_drops: bool,
+    pub execute: bool,
+    pub allow_destructive: bool,
+    /// Write the plan here (`-` for stdout) instead of printing it after the summary.
+    pub script: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaExportArgs {
+    /// Package to write.
+    pub dacpac: PathBuf,
+    /// Model directory to build and export instead of the connected database.
+    pub dir: Option<PathBuf>,
+    /// Schemas to export; defaults to every schema with user objects.
+    pub schemas: Vec<String>,
+    pub shadow_database: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantScriptArgs {
+    /// Database role (or user) receiving the grants; a missing role is created.
+    pub principal: String,
+    /// Permissions to grant on each schema, e.g. `select`, `execute`.
+    pub allow: Vec<String>,
+    /// Schemas to grant on; defaults to the profile's default schemas.
+    pub schemas: Vec<String>,
+    /// Users or roles added to the principal role.
+    pub members: Vec<String>,
+    /// Compare with the principal's current permissions and emit only what is missing.
+    pub missing_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsArgs {
+    pub command: DocsCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocsCommand {
+    /// Write one page per table plus an index with the ER diagram.
+    Generate {
+        /// Schemas to document; defaults to the profile's default schemas.
+        schemas: Vec<String>,
+        out: PathBuf,
+        html: bool,
+    },
+    /// Add, replace, or (with empty text) drop the `MS_Description` of a table, view, or column.
+    SetDescription { target: String, description: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FmtArgs {
+    pub sql: Option<String>,
+    pub file: Option<PathBuf>,
+    pub stdin: bool,
+    /// Rewrite `--file` in place instead of printing.
+    pub write: bool,
+    /// Exit 3 when the input is not already formatted; print nothing else.
+    pub check: bool,
+    pub style: FormatStyleArgs,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintArgs {
+    pub sql: Option<String>,
+    pub files: Vec<PathBuf>,
+    pub stdin: bool,
+    /// `schema.name` of modules to lint from the database; `*`/`?` wildcards allowed.
+    pub object: Option<String>,
+    /// Look up column types in the connected database to flag implicit conversions.
+    pub check_types: bool,
+    /// Lowest severity (`high`, `medium`, `low`) that makes the run exit 3.
+    pub fail_on: String,
+    /// Rule names to skip.
+    pub disable: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -213,11 +753,56 @@ pub struct InitArgs {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct ConfigArgs;
+pub struct ConfigArgs {
+    /// `config connection-string`; `None` shows the resolved settings.
+    pub connection_string: Option<ConnectionStringArgs>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionStringArgs {
+    /// `ado`, `jdbc`, `odbc`, or `sqlcmd`; `None` prints all four.
+    pub format: Option<String>,
+    /// Replace the password with `********` (the default; `--no-redact` turns it off).
+    pub redact: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfilesArgs {
+    pub command: ProfileCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileCommand {
+    List,
+    Add(ProfileEditArgs),
+    Edit(ProfileEditArgs),
+    Remove(ProfileNameArgs),
+    Test(ProfileTestArgs),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEditArgs {
+    pub name: String,
+    pub password_env: Option<String>,
+    pub default_schemas: Option<Vec<String>>,
+    pub set_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileNameArgs {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileTestArgs {
+    pub name: Option<String>,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompletionsArgs {
     pub shell: Option<String>,
+    /// `--list`: print `tables`, `schemas`, or `objects` names for the generated scripts.
+    pub list: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -230,6 +815,7 @@ pub enum IntegrationCommand {
     Help,
     Skills(IntegrationInstallArgs),
     Gemini(IntegrationInstallArgs),
+    Vscode(IntegrationVscodeArgs),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -238,6 +824,12 @@ pub struct IntegrationInstallArgs {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrationVscodeArgs {
+    /// Replace `.vscode` files that are not plain JSON (e.g. have comments) instead of failing.
+    pub force: bool,
+}
+
 pub fn build_cli(show_all: bool) -> Command {
     let mut cmd = Command::new("sscli")
         .about("SQL Server CLI tool for database inspection")
@@ -256,31 +848,98 @@ pub fn build_cli(show_all: bool) -> Command {
     cmd = cmd.subcommand(command_describe(show_all));
     cmd = cmd.subcommand(command_sql(show_all));
     cmd = cmd.subcommand(command_table_data(show_all));
+    cmd = cmd.subcommand(command_profile(show_all));
     cmd = cmd.subcommand(command_columns(show_all));
     cmd = cmd.subcommand(command_update(show_all));
     cmd = cmd.subcommand(command_init(show_all));
     cmd = cmd.subcommand(command_config(show_all));
+    cmd = cmd.subcommand(command_profiles(show_all));
 
     cmd = cmd.subcommand(command_indexes(show_all));
     cmd = cmd.subcommand(command_foreign_keys(show_all));
+    cmd = cmd.subcommand(command_fk_check(show_all));
     cmd = cmd.subcommand(command_stored_procs(show_all));
     cmd = cmd.subcommand(command_completions(show_all));
     cmd = cmd.subcommand(command_sessions(show_all));
     cmd = cmd.subcommand(command_query_stats(show_all));
+    cmd = cmd.subcommand(command_trace(show_all));
     cmd = cmd.subcommand(command_backups(show_all));
+    cmd = cmd.subcommand(command_assemblies(show_all));
+    cmd = cmd.subcommand(command_changes(show_all));
+    cmd = cmd.subcommand(command_partitions(show_all));
+    cmd = cmd.subcommand(command_errorlog(show_all));
+    cmd = cmd.subcommand(command_ag(show_all));
+    cmd = cmd.subcommand(command_cdc(show_all));
+    cmd = cmd.subcommand(command_replication(show_all));
     cmd = cmd.subcommand(command_compare(show_all));
+    cmd = cmd.subcommand(command_verify(show_all));
+    cmd = cmd.subcommand(command_assert(show_all));
+    cmd = cmd.subcommand(command_permissions(show_all));
+    cmd = cmd.subcommand(command_schema(show_all));
+    cmd = cmd.subcommand(command_security(show_all));
+    cmd = cmd.subcommand(command_docs(show_all));
+    cmd = cmd.subcommand(command_fmt(show_all));
+    cmd = cmd.subcommand(command_lint(show_all));
+    cmd = cmd.subcommand(command_migrate(show_all));
+    cmd = cmd.subcommand(command_seed(show_all));
+    cmd = cmd.subcommand(command_serve(show_all));
     cmd = cmd.subcommand(command_integrations(show_all));
 
     cmd
 }
 
+/// The tree a fast-path command was parsed with, and the `show_all` it was built with, so
+/// `help` and `completions` print from it instead of building the tree a second time.
+static PARSED_CLI: Mutex<Option<(bool, Command)>> = Mutex::new(None);
+
 pub fn parse_args() -> CliArgs {
-    let matches = build_cli(false)
-        .try_get_matches_from(rewrite_bare_sql_shorthand(std::env::args_os().collect()))
+    let argv = rewrite_bare_sql_shorthand(std::env::args_os().collect());
+    let show_all = wants_full_tree(&argv);
+    let mut cli = build_cli(show_all);
+    let matches = cli
+        .try_get_matches_from_mut(argv)
         .unwrap_or_else(|err| err.exit());
+    let args = parse_matches(&matches);
+    if args.command.is_fast_path() {
+        if let Ok(mut slot) = PARSED_CLI.lock() {
+            *slot = Some((show_all, cli));
+        }
+    }
+    args
+}
+
+/// Parse `argv` the way `parse_args` parses the process arguments, for tests that need `CliArgs`.
+#[cfg(test)]
+pub(crate) fn parse_args_from(argv: Vec<OsString>) -> CliArgs {
+    let matches = build_cli(false)
+        .try_get_matches_from(rewrite_bare_sql_shorthand(argv))
+        .expect("clap should parse input");
     parse_matches(&matches)
 }
 
+/// `build_cli(show_all)`, reusing the tree `parse_args` kept when it was built the same way.
+pub fn cli_tree(show_all: bool) -> Command {
+    let cached = PARSED_CLI.lock().ok().and_then(|mut slot| slot.take());
+    match cached {
+        Some((built_all, cli)) if built_all == show_all => cli,
+        _ => build_cli(show_all),
+    }
+}
+
+/// `completions` and `help --all` need advanced commands unhidden, so parse with that tree.
+fn wants_full_tree(argv: &[OsString]) -> bool {
+    let words: Vec<_> = argv
+        .iter()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy())
+        .collect();
+    match words.iter().find(|word| is_known_command(word)) {
+        Some(command) if command == "completions" => true,
+        Some(command) if command == "help" => words.iter().any(|word| word == "--all"),
+        _ => false,
+    }
+}
+
 fn rewrite_bare_sql_shorthand(argv: Vec<OsString>) -> Vec<OsString> {
     if argv.len() <= 1 {
         return argv;
@@ -365,9 +1024,13 @@ fn is_known_global_flag(arg: &str) -> bool {
     matches!(
         arg,
         "--allow-write"
+            | "--yes"
+            | "-y"
             | "--json"
             | "--markdown"
             | "--pretty"
+            | "--expanded"
+            | "--no-color"
             | "--pretty-print"
             | "-v"
             | "--verbose"
@@ -387,7 +1050,7 @@ fn is_known_short_bundle(arg: &str) -> bool {
         && arg.len() > 2
         && arg[1..]
             .chars()
-            .all(|ch| matches!(ch, 'v' | 'q' | 'h' | 'V'))
+            .all(|ch| matches!(ch, 'v' | 'q' | 'h' | 'V' | 'y'))
 }
 
 fn is_global_long_option_with_value(arg: &str) -> bool {
@@ -404,6 +1067,11 @@ fn is_global_long_option_with_value(arg: &str) -> bool {
         "--timeout=",
         "--encrypt=",
         "--trust-cert=",
+        "--tz=",
+        "--contract=",
+        "--fields=",
+        "--sort=",
+        "--filter=",
     ]
     .iter()
     .any(|prefix| arg.starts_with(prefix))
@@ -428,24 +1096,40 @@ fn is_global_option_requiring_separate_value(arg: &str) -> bool {
             | "--timeout"
             | "--encrypt"
             | "--trust-cert"
+            | "--tz"
+            | "--contract"
+            | "--fields"
+            | "--sort"
+            | "--filter"
     )
 }
 
 fn is_known_sql_flag(arg: &str) -> bool {
     matches!(
         arg,
-        "--stdin" | "--dry-run" | "--continue-on-error" | "--no-truncate"
+        "--stdin" | "--dry-run" | "--continue-on-error" | "--no-truncate" | "--format-sql"
     )
 }
 
 fn is_sql_long_option_with_value(arg: &str) -> bool {
-    ["--file=", "--param=", "--max-rows=", "--csv="]
-        .iter()
-        .any(|prefix| arg.starts_with(prefix))
+    [
+        "--file=",
+        "--param=",
+        "--max-rows=",
+        "--csv=",
+        "--keyword-case=",
+        "--commas=",
+        "--indent=",
+    ]
+    .iter()
+    .any(|prefix| arg.starts_with(prefix))
 }
 
 fn is_sql_option_requiring_separate_value(arg: &str) -> bool {
-    matches!(arg, "--file" | "--param" | "--max-rows" | "--csv")
+    matches!(
+        arg,
+        "--file" | "--param" | "--max-rows" | "--csv" | "--keyword-case" | "--commas" | "--indent"
+    )
 }
 
 fn is_global_short_option_with_attached_value(arg: &str) -> bool {
@@ -472,18 +1156,45 @@ fn is_known_command(arg: &str) -> bool {
             | "table-data"
             | "data"
             | "head"
+            | "profile"
             | "columns"
             | "update"
             | "upgrade"
             | "indexes"
             | "foreign-keys"
+            | "fk-check"
             | "stored-procs"
             | "sessions"
             | "query-stats"
+            | "trace"
             | "backups"
+            | "assemblies"
+            | "changes"
+            | "partitions"
+            | "errorlog"
+            | "error-log"
+            | "ag"
+            | "availability-groups"
+            | "cdc"
+            | "replication"
+            | "repl"
+            | "clr"
             | "compare"
+            | "verify"
+            | "assert"
+            | "permissions"
+            | "perms"
+            | "schema"
+            | "security"
+            | "docs"
+            | "fmt"
+            | "lint"
+            | "migrate"
+            | "seed"
+            | "serve"
             | "init"
             | "config"
+            | "profiles"
             | "completions"
             | "integrations"
     )
@@ -571,7 +1282,7 @@ fn add_global_args(cmd: Command) -> Command {
             .long("database")
             .value_name("NAME")
             .global(true)
-            .help("Database name (default: master)"),
+            .help("Database name (default: master); `[host].Db` also sets the server"),
     )
     .arg(
         Arg::new("user")
@@ -597,12 +1308,53 @@ fn add_global_args(cmd: Command) -> Command {
             .global(true)
             .help("Connection timeout in milliseconds"),
     )
+    .arg(
+        Arg::new("isolation-level")
+            .long("isolation-level")
+            .value_name("LEVEL")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new([
+                    "read-uncommitted",
+                    "read-committed",
+                    "repeatable-read",
+                    "snapshot",
+                    "serializable",
+                ])
+                .map(|value| IsolationLevel::parse(&value).unwrap_or(IsolationLevel::ReadCommitted)),
+            )
+            .global(true)
+            .help("SET TRANSACTION ISOLATION LEVEL for the session (overrides session.isolationLevel)"),
+    )
+    .arg(
+        Arg::new("lock-timeout")
+            .long("lock-timeout")
+            .value_name("MS")
+            .value_parser(clap::value_parser!(i64).range(-1..))
+            .allow_negative_numbers(true)
+            .global(true)
+            .help("SET LOCK_TIMEOUT in milliseconds; -1 waits indefinitely (overrides session.lockTimeout)"),
+    )
+    .arg(
+        Arg::new("run-id")
+            .long("run-id")
+            .value_name("ID")
+            .global(true)
+            .help("SET CONTEXT_INFO to this id on every connection, to trace the run (env: SSCLI_RUN_ID)"),
+    )
     .arg(
         Arg::new("allow-write")
             .long("allow-write")
             .action(ArgAction::SetTrue)
             .global(true)
-            .help("Deprecated compatibility flag with no effect"),
+            .help("Permit commands that change the server (schema apply, migrate up, seed apply, ...)"),
+    )
+    .arg(
+        Arg::new("yes")
+            .short('y')
+            .long("yes")
+            .action(ArgAction::SetTrue)
+            .global(true)
+            .help("Skip the confirmation before writes to a production-tagged profile"),
     )
     .arg(
         Arg::new("encrypt")
@@ -618,6 +1370,87 @@ fn add_global_args(cmd: Command) -> Command {
             .global(true)
             .help("Trust server certificate"),
     )
+    .arg(
+        Arg::new("tz")
+            .long("tz")
+            .value_name("ZONE")
+            .value_parser(|value: &str| value.parse::<DisplayTimeZone>())
+            .global(true)
+            .help(
+                "Show datetimeoffset values in utc, local, original, +hh:mm, or an IANA zone like America/Chicago",
+            ),
+    )
+    .arg(
+        Arg::new("source-tz")
+            .long("source-tz")
+            .value_name("ZONE")
+            .value_parser(|value: &str| value.parse::<DisplayTimeZone>())
+            .global(true)
+            .help("Zone datetime/datetime2 values were written in, so --tz converts them too"),
+    )
+    .arg(
+        Arg::new("binary")
+            .long("binary")
+            .value_name("FORMAT")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["hex", "base64", "skip"]).map(|value| {
+                    match value.as_str() {
+                        "base64" => BinaryFormat::Base64,
+                        "skip" => BinaryFormat::Skip,
+                        _ => BinaryFormat::Hex,
+                    }
+                }),
+            )
+            .global(true)
+            .help("Write binary values as hex (0x...), base64, or skip them"),
+    )
+    .arg(
+        Arg::new("max-value-bytes")
+            .long("max-value-bytes")
+            .value_name("BYTES")
+            .value_parser(clap::value_parser!(usize))
+            .global(true)
+            .help("Cut text and binary values longer than this, with a marker (0 = no cap)"),
+    )
+    .arg(
+        Arg::new("null-display")
+            .long("null-display")
+            .value_name("TEXT")
+            .global(true)
+            .help("Text shown for NULL in tables (default: —)"),
+    )
+    .arg(
+        Arg::new("booleans")
+            .long("booleans")
+            .value_name("STYLE")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["true-false", "yes-no"]).map(|value| {
+                    if value == "yes-no" {
+                        BooleanStyle::YesNo
+                    } else {
+                        BooleanStyle::TrueFalse
+                    }
+                }),
+            )
+            .global(true)
+            .help("Show booleans in tables as true/false or yes/no"),
+    )
+    .arg(
+        Arg::new("max-cell-width")
+            .long("max-cell-width")
+            .value_name("CHARS")
+            .value_parser(clap::value_parser!(usize))
+            .global(true)
+            .help("Truncate query table cells wider than this (default 140, 0 = never)"),
+    )
+    .arg(
+        Arg::new("max-output-chars")
+            .long("max-output-chars")
+            .value_name("CHARS")
+            .value_parser(clap::value_parser!(usize))
+            .global(true)
+            .help("Truncate query table output longer than this (default 25000, 0 = never)"),
+    )
     .arg(
         Arg::new("json")
             .long("json")
@@ -625,6 +1458,22 @@ fn add_global_args(cmd: Command) -> Command {
             .global(true)
             .help("Output as JSON"),
     )
+    .arg(
+        Arg::new("contract")
+            .long("contract")
+            .value_name("VERSION")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["v1", "v2"]).map(|value| {
+                    if value == "v2" {
+                        JsonContractVersion::V2
+                    } else {
+                        JsonContractVersion::V1
+                    }
+                }),
+            )
+            .global(true)
+            .help("JSON contract version: v1 (per-command shapes) or v2 (meta/data envelope)"),
+    )
     .arg(
         Arg::new("markdown")
             .long("markdown")
@@ -640,6 +1489,46 @@ fn add_global_args(cmd: Command) -> Command {
             .global(true)
             .help("Force pretty-printed table output"),
     )
+    .arg(
+        Arg::new("expanded")
+            .long("expanded")
+            .action(ArgAction::SetTrue)
+            .global(true)
+            .help("Show each row as a block of name/value lines (for wide rows)"),
+    )
+    .arg(
+        Arg::new("no-color")
+            .long("no-color")
+            .action(ArgAction::SetTrue)
+            .global(true)
+            .help("Disable colored output (same as NO_COLOR)"),
+    )
+    .arg(
+        Arg::new("fields")
+            .long("fields")
+            .value_name("NAMES")
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .global(true)
+            .help("Columns to show, in order, in list output (e.g. name,rowCount)"),
+    )
+    .arg(
+        Arg::new("sort")
+            .long("sort")
+            .value_name("COLUMN[:desc]")
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .global(true)
+            .help("Sort list output by columns (e.g. rowCount:desc,name)"),
+    )
+    .arg(
+        Arg::new("filter")
+            .long("filter")
+            .value_name("EXPR")
+            .action(ArgAction::Append)
+            .global(true)
+            .help("Keep list rows matching column op value; ops = != > >= < <= ~ (repeatable)"),
+    )
     .arg(
         Arg::new("verbose")
             .short('v')
@@ -656,6 +1545,13 @@ fn add_global_args(cmd: Command) -> Command {
             .global(true)
             .help("Suppress non-error output"),
     )
+    .arg(
+        Arg::new("refresh")
+            .long("refresh")
+            .action(ArgAction::SetTrue)
+            .global(true)
+            .help("Bypass the metadata cache (settings.metadataCache) and store fresh results"),
+    )
     .arg(
         Arg::new("quiet-target")
             .long("quiet-target")
@@ -674,6 +1570,15 @@ fn command_help() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Show all commands, including advanced ones"),
         )
+        .arg(
+            Arg::new("schema")
+                .long("schema")
+                .action(ArgAction::SetTrue)
+                .help(format!(
+                    "Print the JSON Schema of a command's --json output (per --contract); published for {}, and error",
+                    crate::output::contract::COMMANDS.join(", ")
+                )),
+        )
         .arg(Arg::new("command").value_name("COMMAND"))
 }
 
@@ -713,6 +1618,19 @@ fn command_status(show_all: bool) -> Command {
         &["db-status"],
         show_all,
     )
+    .arg(
+        Arg::new("deep")
+            .long("deep")
+            .action(ArgAction::SetTrue)
+            .help("Full health report: uptime, CPU/memory, tempdb, database states, AG role, error log"),
+    )
+    .arg(
+        Arg::new("diagnose")
+            .long("diagnose")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("deep")
+            .help("Check DNS, TCP, pre-login, TLS, login, and database access one layer at a time"),
+    )
 }
 
 fn command_databases(show_all: bool) -> Command {
@@ -725,6 +1643,67 @@ fn command_databases(show_all: bool) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Include system databases"),
         )
+        .arg(
+            Arg::new("details")
+                .long("details")
+                .action(ArgAction::SetTrue)
+                .help("Add data/log size, compatibility level, and last full/log backup"),
+        )
+        .arg(
+            Arg::new("foreach")
+                .long("foreach")
+                .value_name("SQL")
+                .conflicts_with_all(["details", "limit", "offset"])
+                .help("Run a read-only query in every matching database over one connection"),
+        )
+        .arg(
+            Arg::new("create-script")
+                .long("create-script")
+                .value_name("database")
+                .conflicts_with_all([
+                    "foreach",
+                    "details",
+                    "limit",
+                    "offset",
+                    "name",
+                    "owner",
+                    "include-system",
+                ])
+                .help("Script (never run) the database's schema, in dependency order, to create an empty copy"),
+        )
+        .arg(
+            Arg::new("include-files")
+                .long("include-files")
+                .action(ArgAction::SetTrue)
+                .requires("create-script")
+                .help("Start with CREATE DATABASE, its files, and filegroups"),
+        )
+        .arg(
+            Arg::new("include-security")
+                .long("include-security")
+                .action(ArgAction::SetTrue)
+                .requires("create-script")
+                .help("Add users, roles, role memberships, and permissions"),
+        )
+        .arg(
+            Arg::new("include-data")
+                .long("include-data")
+                .value_name("tables")
+                .action(ArgAction::Append)
+                .use_value_delimiter(true)
+                .value_delimiter(',')
+                .requires("create-script")
+                .help("Also script the rows of these tables (reference data), comma-separated"),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(clap::value_parser!(PathBuf))
+                .requires("create-script")
+                .help("Write the create script to a file (- for stdout)"),
+        )
         .arg(
             Arg::new("limit")
                 .long("limit")
@@ -759,7 +1738,8 @@ fn command_tables(show_all: bool) -> Command {
             Arg::new("summary")
                 .long("summary")
                 .action(ArgAction::SetTrue)
-                .help("Show all tables in a single view"),
+                .conflicts_with_all(["describe", "with-counts", "include-views", "limit", "offset"])
+                .help("Schema overview: totals per schema, largest and widest tables, heaps, tables without a PK"),
         )
         .arg(
             Arg::new("describe")
@@ -767,6 +1747,23 @@ fn command_tables(show_all: bool) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Describe each table (DDL, columns, indexes). Default limit 5, use --limit for more."),
         )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .value_name("n")
+                .value_parser(clap::value_parser!(u64).range(1..=pool::MAX_CONCURRENCY as u64))
+                .requires("describe")
+                .help("Connections used in parallel by --describe (default 4)"),
+        )
+        .arg(
+            Arg::new("orphaned")
+                .long("orphaned")
+                .visible_alias("audit")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["describe", "summary", "with-counts", "include-views"])
+                .help("Audit referential integrity: missing PKs, unindexed/untrusted FKs, *_id columns without FKs"),
+        )
+        .arg(modified_since_arg().conflicts_with_all(["summary", "orphaned"]))
         .arg(Arg::new("limit").short('n').long("limit").value_name("n|all|0"))
         .arg(
             Arg::new("offset")
@@ -779,7 +1776,7 @@ fn command_tables(show_all: bool) -> Command {
 fn command_describe(show_all: bool) -> Command {
     command_core(
         "describe",
-        "Describe any database object (table, view, trigger, proc, function)",
+        "Describe any database object (table, view, trigger, proc, function, sequence, synonym, type), a schema, or a database",
         &["desc"],
         show_all,
     )
@@ -799,8 +1796,11 @@ fn command_describe(show_all: bool) -> Command {
         Arg::new("type")
             .long("type")
             .value_name("TYPE")
-            .value_parser(["table", "view", "trigger", "proc", "function"])
-            .help("Force object type (auto-detected if omitted)"),
+            .value_parser([
+                "table", "view", "trigger", "proc", "function", "sequence", "synonym", "type",
+                "schema", "database",
+            ])
+            .help("Force object type (auto-detected if omitted); `database` describes a database, the current one if no name is given"),
     )
     .arg(
         Arg::new("all")
@@ -834,6 +1834,13 @@ fn command_describe(show_all: bool) -> Command {
             .action(ArgAction::SetTrue)
             .help("Exclude DDL/definition from output"),
     )
+    .arg(
+        Arg::new("ddl-full")
+            .long("ddl-full")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("no-ddl")
+            .help("Script the table with its constraints, indexes and foreign keys (tables only)"),
+    )
     .arg(
         Arg::new("include-fks")
             .long("include-fks")
@@ -846,6 +1853,13 @@ fn command_describe(show_all: bool) -> Command {
             .action(ArgAction::SetTrue)
             .help("Include check/unique constraints (tables only)"),
     )
+    .arg(
+        Arg::new("permissions")
+            .long("permissions")
+            .action(ArgAction::SetTrue)
+            .help("Show principals with permissions on the object, including via role membership"),
+    )
+    .arg(max_objects_arg())
 }
 
 fn command_sql(show_all: bool) -> Command {
@@ -855,7 +1869,7 @@ fn command_sql(show_all: bool) -> Command {
                 .index(1)
                 .allow_hyphen_values(true)
                 .value_name("SQL")
-                .help("SQL statement to execute"),
+                .help("SQL statement to execute (`-` reads stdin)"),
         )
         .arg(
             Arg::new("file")
@@ -877,6 +1891,7 @@ fn command_sql(show_all: bool) -> Command {
                 .value_name("name=value")
                 .action(ArgAction::Append),
         )
+        .arg(params_file_arg())
         .arg(
             Arg::new("max-rows")
                 .short('n')
@@ -889,8 +1904,12 @@ fn command_sql(show_all: bool) -> Command {
                 .short('o')
                 .long("csv")
                 .value_name("file")
-                .value_hint(ValueHint::FilePath),
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with("to"),
         )
+        .arg(out_sink_arg())
+        .arg(save_blobs_arg())
+        .args(export_target_args())
         .arg(
             Arg::new("dry-run")
                 .long("dry-run")
@@ -907,6 +1926,58 @@ fn command_sql(show_all: bool) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Disable output truncation (default: cells >140 chars, total >25KB)"),
         )
+        .arg(
+            Arg::new("format-sql")
+                .long("format-sql")
+                .action(ArgAction::SetTrue)
+                .help("Print the SQL reformatted instead of running it"),
+        )
+        .args(format_style_args())
+}
+
+/// `--keyword-case`, `--commas`, `--indent` for commands that run the SQL formatter.
+fn format_style_args() -> [Arg; 3] {
+    [
+        Arg::new("keyword-case")
+            .long("keyword-case")
+            .value_name("case")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["upper", "lower", "preserve"]).map(
+                    |value| match value.as_str() {
+                        "lower" => KeywordCase::Lower,
+                        "preserve" => KeywordCase::Preserve,
+                        _ => KeywordCase::Upper,
+                    },
+                ),
+            )
+            .help("Keyword case (default: settings.formatting.keywordCase, upper)"),
+        Arg::new("commas")
+            .long("commas")
+            .value_name("style")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["trailing", "leading"]).map(|value| {
+                    if value == "leading" {
+                        CommaStyle::Leading
+                    } else {
+                        CommaStyle::Trailing
+                    }
+                }),
+            )
+            .help("Put list commas at line ends or line starts (default: trailing)"),
+        Arg::new("indent")
+            .long("indent")
+            .value_name("spaces")
+            .value_parser(clap::value_parser!(usize))
+            .help("Spaces per indent level (default: 4)"),
+    ]
+}
+
+fn parse_format_style(matches: &ArgMatches) -> FormatStyleArgs {
+    FormatStyleArgs {
+        keyword_case: matches.get_one::<KeywordCase>("keyword-case").copied(),
+        commas: matches.get_one::<CommaStyle>("commas").copied(),
+        indent: matches.get_one::<usize>("indent").copied(),
+    }
 }
 
 fn command_table_data(show_all: bool) -> Command {
@@ -961,19 +2032,102 @@ fn command_table_data(show_all: bool) -> Command {
             .value_name("name=value")
             .action(ArgAction::Append),
     )
+    .arg(params_file_arg())
     .arg(
         Arg::new("csv")
             .short('o')
             .long("csv")
             .value_name("file")
-            .value_hint(ValueHint::FilePath),
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with("to"),
     )
+    .arg(out_sink_arg())
+    .arg(save_blobs_arg().conflicts_with("follow"))
+    .args(export_target_args())
     .arg(
         Arg::new("no-truncate")
             .long("no-truncate")
             .action(ArgAction::SetTrue)
             .help("Disable output truncation (default: cells >140 chars, total >25KB)"),
     )
+    .arg(
+        Arg::new("follow")
+            .short('f')
+            .long("follow")
+            .value_name("column")
+            .conflicts_with_all(["offset", "order-by", "csv", "out", "to"])
+            .help("Poll for new rows ordered by an ascending column (identity or datetime)"),
+    )
+    .arg(
+        Arg::new("interval")
+            .long("interval")
+            .value_name("duration")
+            .requires("follow")
+            .value_parser(parse_interval_ms)
+            .help("Poll interval for --follow, e.g. 500ms, 2s, 1m (default: 2s)"),
+    )
+    .arg(
+        Arg::new("from-now")
+            .long("from-now")
+            .action(ArgAction::SetTrue)
+            .requires("follow")
+            .help("With --follow, skip existing rows and print only rows added from now on"),
+    )
+    .arg(
+        Arg::new("as-of")
+            .long("as-of")
+            .value_name("datetime")
+            .value_parser(parse_as_of)
+            .conflicts_with_all(["follow", "history"])
+            .help("Read a system-versioned table as of a UTC time (FOR SYSTEM_TIME AS OF)"),
+    )
+    .arg(
+        Arg::new("history")
+            .long("history")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("follow")
+            .help("Read the history table of a system-versioned table"),
+    )
+    .arg(
+        Arg::new("sample")
+            .long("sample")
+            .value_name("mode")
+            .value_parser(parse_sample)
+            .conflicts_with_all(["follow", "limit", "offset", "order-by"])
+            .help("Representative rows: random:N, first:N, last:N (by primary key), stratified:Column[:N]"),
+    )
+    .arg(
+        Arg::new("paginate")
+            .long("paginate")
+            .value_name("mode")
+            .value_parser(
+                clap::builder::PossibleValuesParser::new(["offset", "keyset"]).map(|value| {
+                    if value == "keyset" {
+                        Paginate::Keyset
+                    } else {
+                        Paginate::Offset
+                    }
+                }),
+            )
+            .conflicts_with_all(["follow", "sample"])
+            .help("Page with OFFSET/FETCH (default) or by key: WHERE key > last ORDER BY key"),
+    )
+    .arg(
+        Arg::new("key")
+            .long("key")
+            .value_name("columns")
+            .requires("paginate")
+            .conflicts_with_all(["offset", "order-by"])
+            .help("Keyset columns (default: primary key, which is also appended as a tiebreaker)"),
+    )
+    .arg(
+        Arg::new("after")
+            .long("after")
+            .value_name("token")
+            .requires("paginate")
+            .conflicts_with_all(["offset", "order-by"])
+            .help("Resume keyset paging after a previous page's nextKey"),
+    )
 }
 
 fn command_columns(show_all: bool) -> Command {
@@ -1046,6 +2200,13 @@ fn command_indexes(show_all: bool) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Include usage stats"),
         )
+        .arg(
+            Arg::new("recommend-compression")
+                .long("recommend-compression")
+                .action(ArgAction::SetTrue)
+                .help("Estimate ROW/PAGE compression savings per rowstore index"),
+        )
+        .arg(max_objects_arg())
 }
 
 fn command_foreign_keys(show_all: bool) -> Command {
@@ -1068,6 +2229,53 @@ fn command_foreign_keys(show_all: bool) -> Command {
             .value_name("name"),
     )
     .arg(Arg::new("direction").long("direction").value_name("mode"))
+    .arg(
+        Arg::new("graph")
+            .long("graph")
+            .value_name("format")
+            .value_parser(["dot", "mermaid"])
+            .help("Emit the relationship graph as Graphviz DOT or a Mermaid erDiagram"),
+    )
+    .arg(
+        Arg::new("depth")
+            .long("depth")
+            .value_name("n")
+            .value_parser(clap::value_parser!(u32))
+            .default_value("1")
+            .requires("graph")
+            .help("Foreign key hops to follow from --table when drawing a graph"),
+    )
+    .arg(max_objects_arg())
+}
+
+fn command_fk_check(show_all: bool) -> Command {
+    command_advanced(
+        "fk-check",
+        "Find orphaned rows and script untrusted foreign key fixes",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("table")
+            .short('t')
+            .long("table")
+            .value_name("name")
+            .required_unless_present("schema")
+            .help("Child table whose foreign keys to check (wildcards allowed)"),
+    )
+    .arg(
+        Arg::new("schema")
+            .short('s')
+            .long("schema")
+            .value_name("name")
+            .help("Check every table in this schema"),
+    )
+    .arg(
+        Arg::new("all")
+            .long("all")
+            .action(ArgAction::SetTrue)
+            .help("Also scan trusted foreign keys for orphaned rows"),
+    )
 }
 
 fn command_stored_procs(show_all: bool) -> Command {
@@ -1103,13 +2311,69 @@ fn command_stored_procs(show_all: bool) -> Command {
             .value_parser(clap::value_parser!(u64)),
     )
     .arg(Arg::new("exec").long("exec").value_name("proc"))
-    .arg(Arg::new("args").long("args").value_name("text"))
+    .arg(
+        Arg::new("args")
+            .long("args")
+            .value_name("text")
+            .help("Raw argument text appended to EXEC (no binding or output capture)"),
+    )
+    .arg(
+        Arg::new("param")
+            .long("param")
+            .value_name("name=value")
+            .action(ArgAction::Append)
+            .requires("exec")
+            .conflicts_with("args")
+            .help("Bind a procedure parameter; output parameters are returned automatically"),
+    )
     .arg(
         Arg::new("no-truncate")
             .long("no-truncate")
             .action(ArgAction::SetTrue)
             .help("Disable output truncation (default: cells >140 chars, total >25KB)"),
     )
+    .arg(
+        Arg::new("grep")
+            .long("grep")
+            .value_name("regex")
+            .conflicts_with("exec")
+            .help("Case-insensitive regex searched in procedure definitions"),
+    )
+    .arg(
+        Arg::new("context")
+            .short('C')
+            .long("context")
+            .value_name("n")
+            .default_value("2")
+            .value_parser(clap::value_parser!(u32).range(0..=50))
+            .requires("grep")
+            .help("Definition lines shown around each --grep match"),
+    )
+    .arg(
+        Arg::new("history")
+            .long("history")
+            .value_name("proc")
+            .conflicts_with_all(["exec", "grep"])
+            .help("Execution statistics for one procedure (plan cache and Query Store)"),
+    )
+    .arg(
+        Arg::new("days")
+            .long("days")
+            .value_name("n")
+            .default_value("7")
+            .value_parser(clap::value_parser!(u32).range(1..=90))
+            .requires("history")
+            .help("Query Store days summarized by --history"),
+    )
+    .arg(modified_since_arg().conflicts_with_all(["exec", "history"]))
+    .arg(
+        Arg::new("test")
+            .long("test")
+            .value_name("file")
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with_all(["exec", "grep", "history", "modified-since"])
+            .help("Run the procedure smoke tests in a YAML file, each rolled back afterwards"),
+    )
 }
 
 fn command_sessions(show_all: bool) -> Command {
@@ -1129,6 +2393,20 @@ fn command_sessions(show_all: bool) -> Command {
                 .value_name("n")
                 .value_parser(clap::value_parser!(u64)),
         )
+        .arg(
+            Arg::new("resource-usage")
+                .long("resource-usage")
+                .action(ArgAction::SetTrue)
+                .help("Show CPU, reads, writes, memory, tempdb, and open transactions per session; --sort cpu|reads|writes|memory|tempdb|transaction-age ranks them"),
+        )
+        .arg(
+            Arg::new("top")
+                .long("top")
+                .value_name("n")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .conflicts_with("limit")
+                .help("Only the N heaviest sessions (implies --resource-usage; CPU unless --sort says otherwise)"),
+        )
 }
 
 fn command_query_stats(show_all: bool) -> Command {
@@ -1141,6 +2419,103 @@ fn command_query_stats(show_all: bool) -> Command {
                 .value_name("n")
                 .value_parser(clap::value_parser!(u64)),
         )
+        .arg(
+            Arg::new("export-workload")
+                .long("export-workload")
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("Write query text, parameters, frequency, and timings as a replay workload (`-` for stdout)"),
+        )
+        .arg(
+            Arg::new("proc")
+                .long("proc")
+                .alias("procs")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("export-workload")
+                .help("Rank cached stored procedures instead of statements"),
+        )
+        .arg(
+            Arg::new("schema")
+                .short('s')
+                .long("schema")
+                .value_name("name")
+                .requires("proc")
+                .help("Only procedures in this schema (with --proc)"),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("pattern")
+                .requires("proc")
+                .help("Procedure name LIKE pattern, optionally schema-qualified (with --proc)"),
+        )
+        .arg(
+            Arg::new("plan-cache")
+                .long("plan-cache")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["export-workload", "proc", "order", "database"])
+                .help("Plan cache size by object type, single-use adhoc plans, and top memory grants"),
+        )
+}
+
+const TRACE_SESSION_DEFAULT: &str = "sscli_trace";
+
+fn command_trace(show_all: bool) -> Command {
+    let name = Arg::new("name")
+        .long("name")
+        .value_name("session")
+        .default_value(TRACE_SESSION_DEFAULT)
+        .help("Extended Events session name");
+    command_advanced(
+        "trace",
+        "Lightweight Extended Events tracing into a ring buffer",
+        &["xe"],
+        show_all,
+    )
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("start")
+            .about("Create and start a ring-buffer event session")
+            .arg(name.clone())
+            .arg(
+                Arg::new("events")
+                    .long("events")
+                    .value_name("list")
+                    .value_delimiter(',')
+                    .default_value("rpc_completed,sql_batch_completed")
+                    .help("sqlserver events to capture (comma-separated)"),
+            )
+            .arg(
+                Arg::new("filter")
+                    .long("filter")
+                    .value_name("predicate")
+                    .help("e.g. \"duration > 500ms and database = Sales\" (fields: duration, cpu_time, logical_reads, physical_reads, writes, row_count, database, username, client_app, client_host, session_id)"),
+            ),
+    )
+    .subcommand(
+        Command::new("tail")
+            .about("Stream captured events (NDJSON with --json)")
+            .arg(name.clone())
+            .arg(
+                Arg::new("interval")
+                    .long("interval")
+                    .value_name("duration")
+                    .default_value("2s")
+                    .value_parser(parse_interval_ms)
+                    .help("Poll interval, e.g. 500ms, 2s, 1m"),
+            )
+            .arg(
+                Arg::new("once")
+                    .long("once")
+                    .action(ArgAction::SetTrue)
+                    .help("Print the buffered events and exit"),
+            ),
+    )
+    .subcommand(
+        Command::new("stop")
+            .about("Stop and drop the event session")
+            .arg(name),
+    )
 }
 
 fn command_backups(show_all: bool) -> Command {
@@ -1164,214 +2539,1571 @@ fn command_backups(show_all: bool) -> Command {
             .value_name("n")
             .value_parser(clap::value_parser!(u64)),
     )
-}
-
-fn command_compare(show_all: bool) -> Command {
-    command_advanced(
-        "compare",
-        "Compare two profiles/databases for schema drift",
-        &["diff", "drift"],
-        show_all,
-    )
     .arg(
-        Arg::new("source")
-            .long("source")
-            .visible_alias("left")
-            .value_name("PROFILE")
-            .help("Source/reference profile (defaults to global --profile/default profile)"),
+        Arg::new("check")
+            .long("check")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("history")
+            .help("Check every database's last backups against --max-full-age/--max-log-age; exit 3 on violations"),
     )
     .arg(
-        Arg::new("source-connection")
-            .long("source-connection")
-            .visible_alias("left-connection")
-            .value_name("CONN")
-            .help("Source connection string (overrides profile)"),
+        Arg::new("max-full-age")
+            .long("max-full-age")
+            .value_name("duration")
+            .value_parser(parse_lookback_minutes)
+            .requires("check")
+            .help("Oldest acceptable full backup (e.g. 24h, 7d; default 24h)"),
     )
     .arg(
-        Arg::new("target")
-            .long("target")
-            .visible_alias("right")
-            .value_name("PROFILE")
-            .required(true)
-            .help("Target profile to compare against source"),
+        Arg::new("max-log-age")
+            .long("max-log-age")
+            .value_name("duration")
+            .value_parser(parse_lookback_minutes)
+            .requires("check")
+            .help("Oldest acceptable log backup for full/bulk-logged databases (e.g. 15m)"),
     )
     .arg(
-        Arg::new("target-connection")
-            .long("target-connection")
-            .visible_alias("right-connection")
-            .value_name("CONN")
-            .help("Target connection string (overrides profile)"),
+        Arg::new("history")
+            .long("history")
+            .value_name("database")
+            .help("Backup chain since the latest full backup, with LSNs, devices, and sizes"),
     )
     .arg(
-        Arg::new("schema")
-            .long("schema")
-            .visible_alias("schemas")
-            .value_name("name")
-            .action(ArgAction::Append)
-            .use_value_delimiter(true)
-            .value_delimiter(',')
-            .help("Schemas to include (repeat or comma-separated)"),
+        Arg::new("restore-script")
+            .long("restore-script")
+            .value_name("database")
+            .conflicts_with_all(["check", "history"])
+            .help("Generate (never run) a RESTORE script for the database from msdb history"),
     )
     .arg(
-        Arg::new("object")
-            .long("object")
-            .value_name("schema.name|name")
-            .help("Focus on a single module; emits unified diff"),
+        Arg::new("stop-at")
+            .long("stop-at")
+            .value_name("datetime")
+            .value_parser(parse_stop_at)
+            .requires("restore-script")
+            .help("Restore to this server-local time (YYYY-MM-DD HH:MM[:SS]) with STOPAT"),
     )
     .arg(
-        Arg::new("summary")
-            .long("summary")
-            .action(ArgAction::SetTrue)
-            .help("Emit summary instead of full snapshot"),
+        Arg::new("out")
+            .long("out")
+            .value_name("file")
+            .value_parser(clap::value_parser!(PathBuf))
+            .requires("restore-script")
+            .help("Write the restore script to a file (- for stdout)"),
     )
-    .arg(
-        Arg::new("pretty")
-            .long("pretty")
-            .action(ArgAction::SetTrue)
-            .help("Pretty text summary (with --summary)"),
+    .args(export_target_args())
+}
+
+/// `--max-objects` for commands whose object name may be a wildcard such as `dbo.Order*`.
+fn max_objects_arg() -> Arg {
+    Arg::new("max-objects")
+        .long("max-objects")
+        .value_name("n")
+        .value_parser(clap::value_parser!(usize))
+        .default_value("25")
+        .help("Most objects a wildcard name (* and ?) may expand to")
+}
+
+/// `--params-file` for commands that bind `@name` parameters.
+fn params_file_arg() -> Arg {
+    Arg::new("params-file")
+        .long("params-file")
+        .value_name("file")
+        .value_hint(ValueHint::FilePath)
+        .value_parser(clap::value_parser!(PathBuf))
+        .help("Named parameters from a YAML or JSON mapping; lists expand to IN-lists, --param overrides")
+}
+
+/// `--out` for commands that return result sets; repeat it to write several formats at once.
+fn out_sink_arg() -> Arg {
+    Arg::new("out")
+        .long("out")
+        .value_name("file")
+        .value_hint(ValueHint::FilePath)
+        .value_parser(clap::value_parser!(PathBuf))
+        .action(ArgAction::Append)
+        .help("Also write results to a file, format from its extension (.csv, .json, .jsonl, .md); repeatable")
+}
+
+fn save_blobs_arg() -> Arg {
+    Arg::new("save-blobs")
+        .long("save-blobs")
+        .value_name("dir")
+        .value_hint(ValueHint::DirPath)
+        .value_parser(clap::value_parser!(PathBuf))
+        .help("Write text and binary values over --max-value-bytes (default 8000) to files in this directory and show their paths")
+}
+
+/// `--to`/`--storage-tier` for commands that can upload their rows as CSV to object storage.
+fn export_target_args() -> [Arg; 2] {
+    [
+        Arg::new("to")
+            .long("to")
+            .value_name("url")
+            .help("Upload rows as CSV to azure://container/path or s3://bucket/key (credentials from env)"),
+        Arg::new("storage-tier")
+            .long("storage-tier")
+            .value_name("tier")
+            .value_parser(["hot", "cool", "cold", "archive"])
+            .requires("to")
+            .help("Azure access tier or S3 storage class for --to uploads"),
+    ]
+}
+
+fn command_assemblies(show_all: bool) -> Command {
+    command_advanced(
+        "assemblies",
+        "CLR assemblies, permission sets, and referencing modules",
+        &["clr"],
+        show_all,
     )
     .arg(
-        Arg::new("side-by-side")
-            .long("side-by-side")
-            .action(ArgAction::SetTrue)
-            .help("Colorized split diff (only with --object)"),
+        Arg::new("name")
+            .long("name")
+            .value_name("pattern")
+            .help("Filter assemblies by name (LIKE pattern)"),
     )
     .arg(
-        Arg::new("gui-diff")
-            .long("gui-diff")
+        Arg::new("include-system")
+            .long("include-system")
             .action(ArgAction::SetTrue)
-            .help("Open diff in VS Code (only with --object)"),
+            .help("Include built-in system assemblies"),
     )
-    .arg(
-        Arg::new("ignore-whitespace")
-            .long("ignore-whitespace")
-            .action(ArgAction::SetTrue)
-            .help("Normalize whitespace when comparing definitions"),
+}
+
+fn command_changes(show_all: bool) -> Command {
+    command_advanced(
+        "changes",
+        "Objects created or modified recently, grouped by type",
+        &[],
+        show_all,
     )
     .arg(
-        Arg::new("strip-comments")
-            .long("strip-comments")
+        Arg::new("since")
+            .long("since")
+            .value_name("when")
+            .value_parser(parse_modified_since)
+            .required(true)
+            .help("Start of the window: a duration back from now (7d, 12h) or a date (2024-01-01)"),
+    )
+    .arg(
+        Arg::new("schema")
+            .short('s')
+            .long("schema")
+            .value_name("name"),
+    )
+    .arg(
+        Arg::new("type")
+            .long("type")
+            .value_name("TYPE")
+            .value_parser([
+                "table",
+                "view",
+                "proc",
+                "function",
+                "trigger",
+                "constraint",
+                "other",
+            ])
+            .help("Only objects of this type"),
+    )
+    .arg(
+        Arg::new("include-system")
+            .long("include-system")
             .action(ArgAction::SetTrue)
-            .help("Strip SQL comments before comparing definitions"),
+            .help("Include objects shipped with SQL Server"),
+    )
+}
+
+/// `--modified-since` for listings that can filter on `sys.objects.modify_date`.
+fn modified_since_arg() -> Arg {
+    Arg::new("modified-since")
+        .long("modified-since")
+        .value_name("when")
+        .value_parser(parse_modified_since)
+        .help(
+            "Only objects modified since a duration back from now (7d, 12h) or a date (2024-01-01)",
+        )
+}
+
+fn command_partitions(show_all: bool) -> Command {
+    command_advanced(
+        "partitions",
+        "Partitioned tables: boundaries, rows, compression; split/merge scripts",
+        &[],
+        show_all,
     )
     .arg(
-        Arg::new("apply-script")
-            .long("apply-script")
+        Arg::new("table")
+            .long("table")
+            .value_name("schema.table")
+            .help("Show one table's partitions (default: list partitioned tables)"),
+    )
+    .arg(
+        Arg::new("script-split")
+            .long("script-split")
+            .value_name("value")
+            .requires("table")
+            .conflicts_with("script-merge")
+            .help("Generate (never run) a script adding this boundary"),
+    )
+    .arg(
+        Arg::new("script-merge")
+            .long("script-merge")
+            .value_name("value")
+            .requires("table")
+            .help("Generate (never run) a script removing this boundary"),
+    )
+    .arg(
+        Arg::new("filegroup")
+            .long("filegroup")
+            .value_name("name")
+            .requires("script-split")
+            .help("Filegroup for the new partition (default: the last partition's)"),
+    )
+}
+
+fn command_errorlog(show_all: bool) -> Command {
+    command_advanced(
+        "errorlog",
+        "Read the SQL Server error log",
+        &["error-log"],
+        show_all,
+    )
+    .arg(
+        Arg::new("last")
+            .long("last")
+            .value_name("duration")
+            .value_parser(parse_lookback_minutes)
+            .help("Only entries from the last duration (e.g. 30m, 1h, 2d)"),
+    )
+    .arg(
+        Arg::new("grep")
+            .long("grep")
+            .value_name("regex")
+            .help("Case-insensitive regex matched against the message"),
+    )
+    .arg(
+        Arg::new("archive")
+            .long("archive")
+            .value_name("n")
+            .default_value("0")
+            .value_parser(clap::value_parser!(u32).range(0..=99))
+            .help("Archived log number (0 = current)"),
+    )
+    .arg(
+        Arg::new("min-severity")
+            .long("min-severity")
+            .value_name("n")
+            .value_parser(clap::value_parser!(u32))
+            .help("Only errors at or above this severity"),
+    )
+    .arg(
+        Arg::new("limit")
+            .long("limit")
+            .value_name("n")
+            .value_parser(clap::value_parser!(u64)),
+    )
+    .arg(
+        Arg::new("offset")
+            .long("offset")
+            .value_name("n")
+            .value_parser(clap::value_parser!(u64)),
+    )
+}
+
+fn command_ag(show_all: bool) -> Command {
+    let group = Arg::new("group")
+        .long("group")
+        .value_name("name")
+        .help("Only this availability group");
+    command_advanced(
+        "ag",
+        "Always On availability group health (exit 3 when unhealthy)",
+        &["availability-groups"],
+        show_all,
+    )
+    .arg(group.clone())
+    .subcommand(
+        Command::new("status")
+            .about("Groups, replicas, synchronization state, and redo queues")
+            .arg(group),
+    )
+}
+
+fn command_cdc(show_all: bool) -> Command {
+    let table = Arg::new("table")
+        .long("table")
+        .value_name("name")
+        .help("Only this source table ([schema.]name)");
+    command_advanced(
+        "cdc",
+        "Change data capture tables, jobs, and log scan latency",
+        &[],
+        show_all,
+    )
+    .arg(table.clone())
+    .subcommand(
+        Command::new("status")
+            .about("CDC-enabled tables, capture/cleanup jobs, and scan sessions")
+            .arg(table),
+    )
+}
+
+fn command_replication(show_all: bool) -> Command {
+    let publication = Arg::new("publication")
+        .long("publication")
+        .value_name("name")
+        .help("Only this publication");
+    command_advanced(
+        "replication",
+        "Replication publications, subscriptions, and agent state",
+        &["repl"],
+        show_all,
+    )
+    .arg(publication.clone())
+    .subcommand(
+        Command::new("status")
+            .about("Publication/subscription state from the distribution database")
+            .arg(publication),
+    )
+}
+
+fn command_permissions(show_all: bool) -> Command {
+    let command = Arg::new("command")
+        .long("command")
+        .value_name("name")
+        .help("Only check the rights this command needs, e.g. query-stats");
+    command_advanced(
+        "permissions",
+        "Check the rights each command needs and print GRANTs to request",
+        &["perms"],
+        show_all,
+    )
+    .arg(command.clone())
+    .subcommand(
+        Command::new("check")
+            .about("Evaluate required rights for the current login")
+            .arg(command),
+    )
+}
+
+fn command_security(show_all: bool) -> Command {
+    command_advanced(
+        "security",
+        "Generate least-privilege GRANT scripts for application accounts",
+        &[],
+        show_all,
+    )
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("grant-script")
+            .about("Print the CREATE ROLE and GRANT statements a principal needs (never run)")
+            .arg(
+                Arg::new("principal")
+                    .long("principal")
+                    .required(true)
+                    .value_name("name")
+                    .help("Database role or user to grant to; a missing role is created"),
+            )
+            .arg(
+                Arg::new("allow")
+                    .long("allow")
+                    .required(true)
+                    .value_name("permission")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .value_parser([
+                        "select",
+                        "insert",
+                        "update",
+                        "delete",
+                        "execute",
+                        "references",
+                        "view-definition",
+                    ])
+                    .help("Permissions to grant on each schema (comma-separated)"),
+            )
+            .arg(
+                Arg::new("schemas")
+                    .long("schemas")
+                    .visible_alias("schema")
+                    .value_name("name")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .help("Schemas to grant on (default: the profile's default schemas)"),
+            )
+            .arg(
+                Arg::new("member")
+                    .long("member")
+                    .value_name("user")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .help("Database users to add to the principal role"),
+            )
+            .arg(
+                Arg::new("missing-only")
+                    .long("missing-only")
+                    .action(ArgAction::SetTrue)
+                    .help("Read the principal's current permissions and emit only missing grants"),
+            ),
+    )
+}
+
+fn command_fmt(show_all: bool) -> Command {
+    command_advanced(
+        "fmt",
+        "Reformat T-SQL (keyword case, comma placement, indent)",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("sql")
+            .index(1)
+            .allow_hyphen_values(true)
+            .value_name("SQL")
+            .help("SQL to format (`-` reads stdin)"),
+    )
+    .arg(
+        Arg::new("file")
+            .short('f')
+            .long("file")
             .value_name("path")
-            .num_args(0..=1)
-            .default_missing_value("AUTO")
-            .help("Generate SQL to align target to source (use '-' for stdout; default path auto-generated)"),
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with_all(["sql", "stdin"]),
     )
     .arg(
-        Arg::new("include-drops")
-            .long("include-drops")
+        Arg::new("stdin")
+            .long("stdin")
             .action(ArgAction::SetTrue)
-            .help("Include DROP statements in apply script"),
+            .conflicts_with_all(["sql", "file"]),
     )
     .arg(
-        Arg::new("compact")
-            .long("compact")
+        Arg::new("write")
+            .short('w')
+            .long("write")
             .action(ArgAction::SetTrue)
-            .help("Use compact summary format (old behavior)"),
+            .requires("file")
+            .conflicts_with_all(["sql", "stdin"])
+            .help("Rewrite --file in place"),
+    )
+    .arg(
+        Arg::new("check")
+            .long("check")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("write")
+            .help("Exit 3 if the input is not already formatted"),
     )
+    .args(format_style_args())
 }
 
-fn command_init(show_all: bool) -> Command {
-    command_core("init", "Create config file", &[], show_all)
-        .arg(
-            Arg::new("path")
-                .long("path")
-                .value_name("path")
-                .value_hint(ValueHint::FilePath),
-        )
-        .arg(Arg::new("force").long("force").action(ArgAction::SetTrue))
-        .arg(Arg::new("profile").long("profile").value_name("name"))
+fn command_lint(show_all: bool) -> Command {
+    command_advanced(
+        "lint",
+        "Flag T-SQL anti-patterns in scripts or module definitions",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("sql")
+            .index(1)
+            .allow_hyphen_values(true)
+            .value_name("SQL")
+            .help("SQL to lint (`-` reads stdin)"),
+    )
+    .arg(
+        Arg::new("file")
+            .short('f')
+            .long("file")
+            .value_name("path")
+            .value_hint(ValueHint::FilePath)
+            .action(ArgAction::Append)
+            .conflicts_with_all(["sql", "stdin"])
+            .help("Script to lint; repeat for several files"),
+    )
+    .arg(
+        Arg::new("stdin")
+            .long("stdin")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["sql", "file"]),
+    )
+    .arg(
+        Arg::new("object")
+            .long("object")
+            .value_name("schema.name")
+            .conflicts_with_all(["sql", "file", "stdin"])
+            .help("Lint procedures, views, functions and triggers from the database (wildcards allowed)"),
+    )
+    .arg(
+        Arg::new("check-types")
+            .long("check-types")
+            .action(ArgAction::SetTrue)
+            .help("Connect to look up column types for implicit-conversion checks (implied by --object)"),
+    )
+    .arg(
+        Arg::new("fail-on")
+            .long("fail-on")
+            .value_name("severity")
+            .value_parser(["high", "medium", "low"])
+            .default_value("low")
+            .help("Exit 3 when a finding at or above this severity is reported"),
+    )
+    .arg(
+        Arg::new("disable")
+            .long("disable")
+            .value_name("rule")
+            .value_parser([
+                "select-star",
+                "nolock",
+                "missing-nocount",
+                "non-sargable",
+                "implicit-conversion",
+                "cursor",
+            ])
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .help("Skip a rule (repeat or comma-separated)"),
+    )
 }
 
-fn command_config(show_all: bool) -> Command {
-    command_core("config", "Display resolved config", &[], show_all)
+fn command_docs(show_all: bool) -> Command {
+    command_advanced(
+        "docs",
+        "Generate schema documentation and edit table/column descriptions",
+        &[],
+        show_all,
+    )
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("generate")
+            .about("Write Markdown (or HTML) per table: columns, descriptions, keys, indexes, ER diagram")
+            .arg(
+                Arg::new("schema")
+                    .long("schema")
+                    .visible_alias("schemas")
+                    .value_name("name")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .help("Schemas to document (default: the profile's default schemas)"),
+            )
+            .arg(
+                Arg::new("out")
+                    .long("out")
+                    .value_name("DIR")
+                    .value_hint(ValueHint::DirPath)
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .default_value("docs/db")
+                    .help("Directory to write the pages to"),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(["markdown", "html"])
+                    .default_value("markdown")
+                    .help("Page format"),
+            ),
+    )
+    .subcommand(
+        Command::new("set-description")
+            .about("Set the MS_Description of schema.table or schema.table.column (needs --allow-write)")
+            .arg(
+                Arg::new("target")
+                    .index(1)
+                    .required(true)
+                    .value_name("OBJECT")
+                    .help("table, schema.table, or schema.table.column"),
+            )
+            .arg(
+                Arg::new("description")
+                    .index(2)
+                    .required(true)
+                    .value_name("TEXT")
+                    .help("Description text; an empty string removes it"),
+            ),
+    )
 }
 
-fn command_completions(show_all: bool) -> Command {
-    command_advanced("completions", "Generate shell completions", &[], show_all).arg(
-        Arg::new("shell")
-            .long("shell")
-            .value_name("name")
-            .value_parser(["bash", "zsh", "fish", "powershell", "elvish"]),
+fn command_migrate(show_all: bool) -> Command {
+    let dir = Arg::new("dir")
+        .long("dir")
+        .value_name("DIR")
+        .value_hint(ValueHint::DirPath)
+        .value_parser(clap::value_parser!(PathBuf))
+        .help("Migrations directory (default: settings.migrations.directory, else ./migrations)");
+    command_advanced(
+        "migrate",
+        "Apply versioned .sql migrations and track them in __sscli_migrations",
+        &[],
+        show_all,
+    )
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("new")
+            .about("Create an empty migration file named <timestamp>_<name>.sql")
+            .arg(
+                Arg::new("name")
+                    .index(1)
+                    .required(true)
+                    .value_name("NAME")
+                    .help("Short description, e.g. add_orders_index"),
+            )
+            .arg(dir.clone()),
+    )
+    .subcommand(
+        Command::new("status")
+            .about("List migrations as applied, pending, or changed since they were applied")
+            .arg(dir.clone()),
+    )
+    .subcommand(
+        Command::new("up")
+            .about("Run pending migrations in version order, each in its own transaction (needs --allow-write)")
+            .arg(dir)
+            .arg(
+                Arg::new("dry-run")
+                    .long("dry-run")
+                    .action(ArgAction::SetTrue)
+                    .help("Show the pending migrations and their batches without running them"),
+            )
+            .arg(
+                Arg::new("to")
+                    .long("to")
+                    .value_name("VERSION")
+                    .help("Apply pending migrations up to and including this version"),
+            ),
     )
 }
 
-fn command_integrations(show_all: bool) -> Command {
-    let skills = Command::new("skills")
-        .about("Install agent skills")
-        .subcommand(
-            Command::new("add")
-                .about("Install bundled skill files")
-                .arg(Arg::new("global").long("global").action(ArgAction::SetTrue))
-                .arg(Arg::new("name").long("name").value_name("name")),
-        );
+fn command_seed(show_all: bool) -> Command {
+    let files = Arg::new("files")
+        .index(1)
+        .required(true)
+        .num_args(1..)
+        .value_name("FILE")
+        .value_hint(ValueHint::AnyPath)
+        .value_parser(clap::value_parser!(PathBuf))
+        .help("Seed files (.yaml/.yml/.json) or directories of them");
+    command_advanced(
+        "seed",
+        "Keep lookup tables in sync with seed files of keyed rows",
+        &[],
+        show_all,
+    )
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("diff")
+            .about("Show the INSERT/UPDATE/DELETE each seed needs (exit 3 when out of sync)")
+            .arg(files.clone()),
+    )
+    .subcommand(
+        Command::new("apply")
+            .about("Apply the delta in one transaction (needs --allow-write)")
+            .arg(files),
+    )
+}
 
-    let gemini = Command::new("gemini")
-        .about("Install Gemini extension")
-        .subcommand(
-            Command::new("add")
-                .about("Install bundled Gemini extension")
-                .arg(Arg::new("global").long("global").action(ArgAction::SetTrue))
-                .arg(Arg::new("name").long("name").value_name("name")),
-        );
+fn command_serve(show_all: bool) -> Command {
+    command_advanced(
+        "serve",
+        "Serve read-only JSON endpoints over HTTP; --port is the HTTP port (default 8080)",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("bind")
+            .long("bind")
+            .value_name("ADDR")
+            .help("Listen address (default: settings.serve.bind, else 127.0.0.1)"),
+    )
+}
 
+fn command_schema(show_all: bool) -> Command {
     command_advanced(
-        "integrations",
-        "Optional editor/agent integrations",
-        &["integrate"],
+        "schema",
+        "Deploy a directory of CREATE scripts as the desired schema state, or export a dacpac",
+        &[],
         show_all,
     )
-    .subcommand(skills)
-    .subcommand(gemini)
+    .subcommand_required(true)
+    .subcommand(
+        Command::new("apply")
+            .about("Diff a model directory against the database and apply the migration plan")
+            .arg(
+                Arg::new("dir")
+                    .index(1)
+                    .required(true)
+                    .value_name("DIR")
+                    .value_hint(ValueHint::DirPath)
+                    .help("Directory of .sql files (CREATE statements, GO-separated)"),
+            )
+            .arg(
+                Arg::new("schema")
+                    .long("schema")
+                    .visible_alias("schemas")
+                    .value_name("name")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .help("Schemas to reconcile (default: schemas the model defines objects in)"),
+            )
+            .arg(
+                Arg::new("shadow-database")
+                    .long("shadow-database")
+                    .value_name("name")
+                    .help("Scratch database the model is built in (must not exist; dropped afterwards)"),
+            )
+            .arg(
+                Arg::new("include-drops")
+                    .long("include-drops")
+                    .action(ArgAction::SetTrue)
+                    .help("Drop objects that exist in the database but not in the model"),
+            )
+            .arg(
+                Arg::new("execute")
+                    .long("execute")
+                    .action(ArgAction::SetTrue)
+                    .help("Run the plan in a transaction (default: print it only)"),
+            )
+            .arg(
+                Arg::new("allow-destructive")
+                    .long("allow-destructive")
+                    .action(ArgAction::SetTrue)
+                    .requires("execute")
+                    .help("Execute even when the plan drops objects or may lose data"),
+            )
+            .arg(
+                Arg::new("script")
+                    .long("script")
+                    .value_name("path")
+                    .value_hint(ValueHint::FilePath)
+                    .help("Write the plan to a file ('-' for stdout)"),
+            ),
+    )
+    .subcommand(
+        Command::new("export")
+            .about("Write tables, keys, indexes and constraints as a minimal dacpac for SSDT tooling")
+            .arg(
+                Arg::new("dacpac")
+                    .long("dacpac")
+                    .required(true)
+                    .value_name("path")
+                    .value_hint(ValueHint::FilePath)
+                    .help("Package file to write"),
+            )
+            .arg(
+                Arg::new("dir")
+                    .index(1)
+                    .value_name("DIR")
+                    .value_hint(ValueHint::DirPath)
+                    .help("Build this model directory in a shadow database (needs --allow-write) and export it instead of the connected database"),
+            )
+            .arg(
+                Arg::new("schema")
+                    .long("schema")
+                    .visible_alias("schemas")
+                    .value_name("name")
+                    .action(ArgAction::Append)
+                    .use_value_delimiter(true)
+                    .value_delimiter(',')
+                    .help("Schemas to export (default: schemas with user objects)"),
+            )
+            .arg(
+                Arg::new("shadow-database")
+                    .long("shadow-database")
+                    .value_name("name")
+                    .requires("dir")
+                    .help("Scratch database the model directory is built in (must not exist; dropped afterwards)"),
+            ),
+    )
 }
 
-fn parse_matches(matches: &ArgMatches) -> CliArgs {
-    let config_path = matches.get_one::<String>("config").map(PathBuf::from);
-    let env_file = matches.get_one::<String>("env-file").map(PathBuf::from);
-    let profile = matches.get_one::<String>("profile").cloned();
-    let server = matches.get_one::<String>("server").cloned();
-    let port = matches.get_one::<u16>("port").copied();
-    let database = matches.get_one::<String>("database").cloned();
-    let user = matches.get_one::<String>("user").cloned();
-    let password = matches.get_one::<String>("password").cloned();
-    let timeout_ms = matches.get_one::<u64>("timeout").copied();
-    let allow_write = matches.get_flag("allow-write");
-    let encrypt = matches.get_one::<bool>("encrypt").copied();
-    let trust_cert = matches.get_one::<bool>("trust-cert").copied();
-    let output = OutputFlags {
-        json: matches.get_flag("json"),
-        markdown: matches.get_flag("markdown"),
-        pretty: matches.get_flag("pretty"),
+/// Matches for the default subcommand `name`, falling back to the parent when it is omitted.
+fn default_subcommand<'a>(matches: &'a ArgMatches, name: &str) -> &'a ArgMatches {
+    match matches.subcommand() {
+        Some((sub, sub_m)) if sub == name => sub_m,
+        _ => matches,
+    }
+}
+
+const FOLLOW_INTERVAL_DEFAULT_MS: u64 = 2_000;
+
+/// Parse `500ms`, `2s`, `1m`, or bare seconds into milliseconds.
+fn parse_interval_ms(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim().to_ascii_lowercase();
+    let (digits, factor) = if let Some(ms) = trimmed.strip_suffix("ms") {
+        (ms, 1)
+    } else if let Some(secs) = trimmed.strip_suffix('s') {
+        (secs, 1_000)
+    } else if let Some(mins) = trimmed.strip_suffix('m') {
+        (mins, 60_000)
+    } else {
+        (trimmed.as_str(), 1_000)
     };
-    let verbose = matches.get_count("verbose");
-    let quiet = matches.get_flag("quiet");
-    let quiet_target = matches.get_flag("quiet-target");
+    digits
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .map(|n| n * factor)
+        .ok_or_else(|| format!("Invalid interval '{}'; use e.g. 500ms, 2s, 1m", value))
+}
 
-    let command = match matches.subcommand() {
-        Some(("help", sub_m)) => CommandKind::Help {
-            all: sub_m.get_flag("all"),
-            command: sub_m.get_one::<String>("command").cloned(),
-        },
-        Some(("status", _)) => CommandKind::Status(StatusArgs),
-        Some(("databases", sub_m)) => CommandKind::Databases(DatabasesArgs {
-            name: sub_m.get_one::<String>("name").cloned(),
-            owner: sub_m.get_one::<String>("owner").cloned(),
-            include_system: sub_m.get_flag("include-system"),
-            limit: sub_m.get_one::<u64>("limit").copied(),
-            offset: sub_m.get_one::<u64>("offset").copied(),
-        }),
-        Some(("tables", sub_m)) => CommandKind::Tables(TablesArgs {
-            schema: sub_m.get_one::<String>("schema").cloned(),
-            like: sub_m.get_one::<String>("like").cloned(),
-            include_views: sub_m.get_flag("include-views"),
-            with_counts: sub_m.get_flag("with-counts"),
+/// Parse `2024-05-01 13:45`, with optional seconds and fraction, or the same with a `T`.
+fn parse_stop_at(value: &str) -> Result<NaiveDateTime, String> {
+    let normalized = value.trim().replacen('T', " ", 1);
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
+        .ok_or_else(|| {
+            format!(
+                "Invalid time '{}'; use YYYY-MM-DD HH:MM[:SS] in server local time",
+                value
+            )
+        })
+}
+
+/// Parse `YYYY-MM-DD[THH:MM[:SS[.fff]]]`; a bare date means midnight.
+fn parse_as_of(value: &str) -> Result<NaiveDateTime, String> {
+    let normalized = value.trim().replacen('T', " ", 1);
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(&normalized, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| {
+            format!(
+                "Invalid time '{}'; use YYYY-MM-DD or YYYY-MM-DDTHH:MM[:SS] in UTC",
+                value
+            )
+        })
+}
+
+const SAMPLE_ROWS_MAX: u64 = 10_000;
+const STRATIFIED_PER_VALUE_DEFAULT: u64 = 5;
+
+/// Parse `random:N`, `first:N`, `last:N`, or `stratified:Column[:N]`.
+fn parse_sample(value: &str) -> Result<Sample, String> {
+    let count = |text: &str| {
+        text.trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|n| (1..=SAMPLE_ROWS_MAX).contains(n))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid sample size '{}'; use 1 to {}",
+                    text, SAMPLE_ROWS_MAX
+                )
+            })
+    };
+    let (mode, rest) = value.split_once(':').unwrap_or((value, ""));
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "random" => count(rest).map(Sample::Random),
+        "first" => count(rest).map(Sample::First),
+        "last" => count(rest).map(Sample::Last),
+        "stratified" => {
+            // A trailing `:N` is the per-value count; the column name itself may not hold one.
+            let (column, per_value) = match rest.rsplit_once(':') {
+                Some((column, n)) => (column, count(n)?),
+                None => (rest, STRATIFIED_PER_VALUE_DEFAULT),
+            };
+            let column = column.trim();
+            if column.is_empty() {
+                return Err("stratified sampling needs a column: stratified:Region".to_string());
+            }
+            Ok(Sample::Stratified {
+                column: column.to_string(),
+                per_value,
+            })
+        }
+        _ => Err(format!(
+            "Invalid sample '{}'; use random:N, first:N, last:N, or stratified:Column[:N]",
+            value
+        )),
+    }
+}
+
+/// Parse a date as for `--as-of`, in server local time, or a lookback such as `7d`.
+fn parse_modified_since(value: &str) -> Result<ModifiedSince, String> {
+    if value.contains('-') {
+        return parse_as_of(value)
+            .map(ModifiedSince::At)
+            .map_err(|_| format!("Invalid date '{}'; use YYYY-MM-DD[THH:MM[:SS]]", value));
+    }
+    parse_lookback_minutes(value).map(ModifiedSince::Minutes)
+}
+
+/// Parse `90`, `30m`, `1h`, or `2d` into minutes.
+fn parse_lookback_minutes(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim().to_ascii_lowercase();
+    let (digits, factor) = match trimmed.char_indices().last() {
+        Some((idx, 'm')) => (&trimmed[..idx], 1),
+        Some((idx, 'h')) => (&trimmed[..idx], 60),
+        Some((idx, 'd')) => (&trimmed[..idx], 60 * 24),
+        _ => (trimmed.as_str(), 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .map(|n| n * factor)
+        .ok_or_else(|| format!("Invalid duration '{}'; use e.g. 30m, 1h, 2d", value))
+}
+
+fn command_profile(show_all: bool) -> Command {
+    command_advanced(
+        "profile",
+        "Per-column nulls, distinct counts, min/max, top values, and lengths",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("object")
+            .index(1)
+            .value_name("TABLE")
+            .help("Table name (schema-qualified allowed)"),
+    )
+    .arg(
+        Arg::new("table")
+            .short('t')
+            .long("table")
+            .value_name("name")
+            .conflicts_with("object"),
+    )
+    .arg(
+        Arg::new("schema")
+            .short('s')
+            .long("schema")
+            .value_name("name"),
+    )
+    .arg(
+        Arg::new("columns")
+            .long("columns")
+            .value_name("list")
+            .value_delimiter(',')
+            .help("Only profile these columns"),
+    )
+    .arg(
+        Arg::new("top")
+            .long("top")
+            .value_name("n")
+            .value_parser(clap::value_parser!(u32).range(0..=100))
+            .default_value("5")
+            .help("Most frequent values to show per column (0 to skip)"),
+    )
+    .arg(
+        Arg::new("exact-threshold")
+            .long("exact-threshold")
+            .value_name("rows")
+            .value_parser(clap::value_parser!(u64))
+            .default_value("1000000")
+            .help(
+                "Above this many rows, count distinct values approximately and sample top values",
+            ),
+    )
+}
+
+fn command_assert(show_all: bool) -> Command {
+    let expectations = ["expect", "expect-lt", "expect-gt", "expect-empty"];
+    let others = |own: &str| {
+        expectations
+            .into_iter()
+            .filter(|name| *name != own)
+            .collect::<Vec<_>>()
+    };
+    command_advanced(
+        "assert",
+        "Run a query and exit 3 unless its result meets an expectation",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("sql")
+            .long("sql")
+            .value_name("SQL")
+            .allow_hyphen_values(true)
+            .required_unless_present("file")
+            .help("Query whose first value (or row count) is checked"),
+    )
+    .arg(
+        Arg::new("file")
+            .short('f')
+            .long("file")
+            .value_name("path")
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with("sql"),
+    )
+    .arg(
+        Arg::new("param")
+            .long("param")
+            .value_name("name=value")
+            .action(ArgAction::Append),
+    )
+    .arg(
+        Arg::new("expect")
+            .long("expect")
+            .value_name("value")
+            .allow_hyphen_values(true)
+            .required_unless_present_any(others("expect"))
+            .conflicts_with_all(others("expect"))
+            .help("First value must equal this (`null` for NULL)"),
+    )
+    .arg(
+        Arg::new("expect-lt")
+            .long("expect-lt")
+            .value_name("n")
+            .allow_hyphen_values(true)
+            .value_parser(parse_number)
+            .conflicts_with_all(others("expect-lt"))
+            .help("First value must be a number below this"),
+    )
+    .arg(
+        Arg::new("expect-gt")
+            .long("expect-gt")
+            .value_name("n")
+            .allow_hyphen_values(true)
+            .value_parser(parse_number)
+            .conflicts_with_all(others("expect-gt"))
+            .help("First value must be a number above this"),
+    )
+    .arg(
+        Arg::new("expect-empty")
+            .long("expect-empty")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(others("expect-empty"))
+            .help("Query must return no rows"),
+    )
+    .arg(
+        Arg::new("name")
+            .long("name")
+            .value_name("label")
+            .help("Label shown in output and the failure payload"),
+    )
+}
+
+/// Accept a number as typed, so `--expect-lt 0.5` reports back exactly as given.
+fn parse_number(value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    trimmed
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .map(|_| trimmed.to_string())
+        .ok_or_else(|| format!("'{}' is not a number", value))
+}
+
+fn command_verify(show_all: bool) -> Command {
+    command_advanced(
+        "verify",
+        "Compare row counts and checksums between two profiles",
+        &[],
+        show_all,
+    )
+    .arg(
+        Arg::new("source")
+            .long("source")
+            .visible_alias("source-profile")
+            .value_name("PROFILE")
+            .help("Source profile (defaults to global --profile/default profile)"),
+    )
+    .arg(
+        Arg::new("source-connection")
+            .long("source-connection")
+            .value_name("CONN")
+            .help("Source connection string (overrides profile)"),
+    )
+    .arg(
+        Arg::new("target")
+            .long("target")
+            .visible_alias("target-profile")
+            .value_name("PROFILE")
+            .required(true)
+            .help("Target profile to verify against source"),
+    )
+    .arg(
+        Arg::new("target-connection")
+            .long("target-connection")
+            .value_name("CONN")
+            .help("Target connection string (overrides profile)"),
+    )
+    .arg(
+        Arg::new("tables")
+            .long("tables")
+            .visible_alias("table")
+            .value_name("pattern")
+            .action(ArgAction::Append)
+            .use_value_delimiter(true)
+            .value_delimiter(',')
+            .help("Tables to check, e.g. dbo.*, Orders, sales.Order* (default: all)"),
+    )
+    .arg(
+        Arg::new("checksum")
+            .long("checksum")
+            .action(ArgAction::SetTrue)
+            .help("Also compare CHECKSUM_AGG(BINARY_CHECKSUM(*)) per table"),
+    )
+    .arg(
+        Arg::new("chunk-size")
+            .long("chunk-size")
+            .value_name("rows")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Checksum primary-key ordered chunks of this many rows to locate mismatches"),
+    )
+}
+
+fn command_compare(show_all: bool) -> Command {
+    command_advanced(
+        "compare",
+        "Compare two profiles/databases for schema drift",
+        &["diff", "drift"],
+        show_all,
+    )
+    .arg(
+        Arg::new("source")
+            .long("source")
+            .visible_alias("left")
+            .value_name("PROFILE")
+            .help("Source/reference profile (defaults to global --profile/default profile)"),
+    )
+    .arg(
+        Arg::new("source-connection")
+            .long("source-connection")
+            .visible_alias("left-connection")
+            .value_name("CONN")
+            .help("Source connection string (overrides profile)"),
+    )
+    .arg(
+        Arg::new("source-dacpac")
+            .long("source-dacpac")
+            .value_name("path")
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with_all(["source", "source-connection"])
+            .help("Compare a .dacpac's model (built in a shadow database on the target server; needs --allow-write) instead of a source database"),
+    )
+    .arg(
+        Arg::new("shadow-database")
+            .long("shadow-database")
+            .value_name("name")
+            .requires("source-dacpac")
+            .help("Scratch database the dacpac's model is built in (must not exist; dropped afterwards)"),
+    )
+    .arg(
+        Arg::new("target")
+            .long("target")
+            .visible_alias("right")
+            .value_name("PROFILE")
+            .required(true)
+            .help("Target profile to compare against source"),
+    )
+    .arg(
+        Arg::new("target-connection")
+            .long("target-connection")
+            .visible_alias("right-connection")
+            .value_name("CONN")
+            .help("Target connection string (overrides profile)"),
+    )
+    .arg(
+        Arg::new("schema")
+            .long("schema")
+            .visible_alias("schemas")
+            .value_name("name")
+            .action(ArgAction::Append)
+            .use_value_delimiter(true)
+            .value_delimiter(',')
+            .help("Schemas to include (repeat or comma-separated)"),
+    )
+    .arg(
+        Arg::new("object")
+            .long("object")
+            .value_name("schema.name|name")
+            .help("Focus on one module or table; * and ? diff every matching module (dbo.usp_*)"),
+    )
+    .arg(
+        Arg::new("out")
+            .long("out")
+            .value_name("dir")
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_hint(ValueHint::DirPath)
+            .requires("object")
+            .help("Write one <schema>.<name>.patch per differing module instead of printing"),
+    )
+    .arg(
+        Arg::new("patch")
+            .long("patch")
+            .value_name("file|-")
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+            .requires("object")
+            .conflicts_with_all(["out", "side-by-side", "gui-diff"])
+            .help("Write the --object diff as a git-style patch file (- for stdout)"),
+    )
+    .arg(
+        Arg::new("summary")
+            .long("summary")
+            .action(ArgAction::SetTrue)
+            .help("Emit summary instead of full snapshot"),
+    )
+    .arg(
+        Arg::new("pretty")
+            .long("pretty")
+            .action(ArgAction::SetTrue)
+            .help("Pretty text summary (with --summary)"),
+    )
+    .arg(
+        Arg::new("side-by-side")
+            .long("side-by-side")
+            .action(ArgAction::SetTrue)
+            .help("Colorized split diff (only with --object)"),
+    )
+    .arg(
+        Arg::new("gui-diff")
+            .long("gui-diff")
+            .action(ArgAction::SetTrue)
+            .help("Open diff in VS Code (only with --object)"),
+    )
+    .arg(
+        Arg::new("ignore-whitespace")
+            .long("ignore-whitespace")
+            .action(ArgAction::SetTrue)
+            .help("Normalize whitespace when comparing definitions"),
+    )
+    .arg(
+        Arg::new("strip-comments")
+            .long("strip-comments")
+            .action(ArgAction::SetTrue)
+            .help("Strip SQL comments before comparing definitions"),
+    )
+    .arg(
+        Arg::new("format-sql")
+            .long("format-sql")
+            .action(ArgAction::SetTrue)
+            .help("Reformat definitions with the SQL formatter before comparing them"),
+    )
+    .arg(
+        Arg::new("apply-script")
+            .long("apply-script")
+            .value_name("path")
+            .num_args(0..=1)
+            .default_missing_value("AUTO")
+            .help("Generate SQL to align target to source (use '-' for stdout; default path auto-generated)"),
+    )
+    .arg(
+        Arg::new("include-drops")
+            .long("include-drops")
+            .action(ArgAction::SetTrue)
+            .help("Include DROP statements in apply script"),
+    )
+    .arg(
+        Arg::new("compact")
+            .long("compact")
+            .action(ArgAction::SetTrue)
+            .help("Use compact summary format (old behavior)"),
+    )
+    .arg(
+        Arg::new("query-timeout")
+            .long("query-timeout")
+            .value_name("duration")
+            .value_parser(parse_interval_ms)
+            .help("Fail if any snapshot query runs longer than this, e.g. 90s or 5m"),
+    )
+    .arg(
+        Arg::new("ignore-file")
+            .long("ignore-file")
+            .value_name("path")
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+            .help("Accepted-drift rules (default: nearest .sscli-compare-ignore)"),
+    )
+    .arg(
+        Arg::new("only")
+            .long("only")
+            .value_name("category")
+            .action(ArgAction::Append)
+            .value_delimiter(',')
+            .value_parser(["modules", "tables", "indexes", "constraints"])
+            .help("Only compare these categories (repeat or comma-separated)"),
+    )
+    .arg(
+        Arg::new("include")
+            .long("include")
+            .value_name("pattern")
+            .action(ArgAction::Append)
+            .help("Only objects matching a glob (dbo.Order*) or /regex/ on schema.name"),
+    )
+    .arg(
+        Arg::new("exclude")
+            .long("exclude")
+            .value_name("pattern")
+            .action(ArgAction::Append)
+            .help("Skip objects matching a glob (*_backup) or /regex/ on schema.name"),
+    )
+}
+
+fn command_init(show_all: bool) -> Command {
+    command_core("init", "Create config file", &[], show_all)
+        .arg(
+            Arg::new("path")
+                .long("path")
+                .value_name("path")
+                .value_hint(ValueHint::FilePath),
+        )
+        .arg(Arg::new("force").long("force").action(ArgAction::SetTrue))
+        .arg(Arg::new("profile").long("profile").value_name("name"))
+}
+
+fn command_config(show_all: bool) -> Command {
+    command_core("config", "Display resolved config", &[], show_all)
+        .subcommand(profiles_subcommands(
+            Command::new("profiles").about("Manage profiles"),
+        ))
+        .subcommand(
+            Command::new("connection-string")
+                .about("Print the resolved connection as ADO.NET, JDBC, ODBC, and sqlcmd")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_name("kind")
+                        .value_parser(["ado", "jdbc", "odbc", "sqlcmd"])
+                        .help("Print only this form, unformatted for copying"),
+                )
+                .arg(
+                    Arg::new("redact")
+                        .long("redact")
+                        .action(ArgAction::SetTrue)
+                        .help("Hide the password (default)"),
+                )
+                .arg(
+                    Arg::new("no-redact")
+                        .long("no-redact")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("redact")
+                        .help("Include the password in clear text"),
+                ),
+        )
+}
+
+fn command_profiles(show_all: bool) -> Command {
+    profiles_subcommands(command_advanced(
+        "profiles",
+        "Manage connection profiles in the config file",
+        &[],
+        show_all,
+    ))
+}
+
+fn profiles_subcommands(cmd: Command) -> Command {
+    let edit_args = |cmd: Command| {
+        cmd.arg(Arg::new("name").value_name("NAME").required(true))
+            .arg(
+                Arg::new("password-env")
+                    .long("password-env")
+                    .value_name("VAR")
+                    .help("Environment variable holding the password"),
+            )
+            .arg(
+                Arg::new("default-schemas")
+                    .long("default-schemas")
+                    .value_name("list")
+                    .value_delimiter(',')
+                    .help("Comma-separated default schemas"),
+            )
+            .arg(
+                Arg::new("set-default")
+                    .long("set-default")
+                    .action(ArgAction::SetTrue)
+                    .help("Make this the defaultProfile"),
+            )
+    };
+
+    cmd.subcommand_required(true)
+        .subcommand(Command::new("list").about("List configured profiles"))
+        .subcommand(edit_args(Command::new("add").about(
+            "Add a profile (connection values come from --server/--database/--user/...)",
+        )))
+        .subcommand(edit_args(
+            Command::new("edit").about("Update fields on an existing profile"),
+        ))
+        .subcommand(
+            Command::new("remove")
+                .visible_alias("rm")
+                .about("Remove a profile")
+                .arg(Arg::new("name").value_name("NAME").required(true)),
+        )
+        .subcommand(
+            Command::new("test")
+                .about("Check connectivity for a profile and report latency")
+                .arg(Arg::new("name").value_name("NAME")),
+        )
+}
+
+fn command_completions(show_all: bool) -> Command {
+    command_advanced("completions", "Generate shell completions", &[], show_all)
+        .arg(
+            Arg::new("shell")
+                .long("shell")
+                .value_name("name")
+                .value_parser(["bash", "zsh", "fish", "powershell", "elvish"]),
+        )
+        .arg(
+            Arg::new("list")
+                .long("list")
+                .value_name("KIND")
+                .value_parser(["tables", "schemas", "objects"])
+                .hide(true)
+                .help("Print table, schema, or object names, one per line (used by the scripts)"),
+        )
+}
+
+fn command_integrations(show_all: bool) -> Command {
+    let skills = Command::new("skills")
+        .about("Install agent skills")
+        .subcommand(
+            Command::new("add")
+                .about("Install bundled skill files")
+                .arg(Arg::new("global").long("global").action(ArgAction::SetTrue))
+                .arg(Arg::new("name").long("name").value_name("name")),
+        );
+
+    let gemini = Command::new("gemini")
+        .about("Install Gemini extension")
+        .subcommand(
+            Command::new("add")
+                .about("Install bundled Gemini extension")
+                .arg(Arg::new("global").long("global").action(ArgAction::SetTrue))
+                .arg(Arg::new("name").long("name").value_name("name")),
+        );
+
+    let vscode = Command::new("vscode")
+        .about("Add VS Code tasks and SQLTools connections")
+        .subcommand(
+            Command::new("add")
+                .about("Write .vscode/tasks.json and SQLTools connections from config profiles")
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help("Overwrite .vscode files that are not plain JSON"),
+                ),
+        );
+
+    command_advanced(
+        "integrations",
+        "Optional editor/agent integrations",
+        &["integrate"],
+        show_all,
+    )
+    .subcommand(skills)
+    .subcommand(gemini)
+    .subcommand(vscode)
+}
+
+fn parse_matches(matches: &ArgMatches) -> CliArgs {
+    let config_path = matches.get_one::<String>("config").map(PathBuf::from);
+    let env_file = matches.get_one::<String>("env-file").map(PathBuf::from);
+    let profile = matches.get_one::<String>("profile").cloned();
+    let server = matches.get_one::<String>("server").cloned();
+    let port = matches.get_one::<u16>("port").copied();
+    let database = matches.get_one::<String>("database").cloned();
+    let user = matches.get_one::<String>("user").cloned();
+    let password = matches.get_one::<String>("password").cloned();
+    let timeout_ms = matches.get_one::<u64>("timeout").copied();
+    let isolation_level = matches
+        .get_one::<IsolationLevel>("isolation-level")
+        .copied();
+    let lock_timeout_ms = matches.get_one::<i64>("lock-timeout").copied();
+    let run_id = matches.get_one::<String>("run-id").cloned();
+    let allow_write = matches.get_flag("allow-write");
+    let yes = matches.get_flag("yes");
+    let encrypt = matches.get_one::<bool>("encrypt").copied();
+    let trust_cert = matches.get_one::<bool>("trust-cert").copied();
+    let time_zone = matches.get_one::<DisplayTimeZone>("tz").copied();
+    let source_time_zone = matches.get_one::<DisplayTimeZone>("source-tz").copied();
+    let binary = matches.get_one::<BinaryFormat>("binary").copied();
+    let max_value_bytes = matches.get_one::<usize>("max-value-bytes").copied();
+    let null_display = matches.get_one::<String>("null-display").cloned();
+    let booleans = matches.get_one::<BooleanStyle>("booleans").copied();
+    let max_cell_width = matches.get_one::<usize>("max-cell-width").copied();
+    let max_output_chars = matches.get_one::<usize>("max-output-chars").copied();
+    let contract = matches.get_one::<JsonContractVersion>("contract").copied();
+    let command_path = command_path(matches);
+    let output = OutputFlags {
+        json: matches.get_flag("json"),
+        markdown: matches.get_flag("markdown"),
+        pretty: matches.get_flag("pretty"),
+        expanded: matches.get_flag("expanded"),
+        no_color: matches.get_flag("no-color"),
+        fields: matches
+            .get_many::<String>("fields")
+            .map(|values| {
+                values
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        sort: matches
+            .get_many::<String>("sort")
+            .map(|values| {
+                values
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        filters: matches
+            .get_many::<String>("filter")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+    };
+    let verbose = matches.get_count("verbose");
+    let quiet = matches.get_flag("quiet");
+    let quiet_target = matches.get_flag("quiet-target");
+    let refresh = matches.get_flag("refresh");
+
+    let command = match matches.subcommand() {
+        Some(("help", sub_m)) => CommandKind::Help {
+            all: sub_m.get_flag("all"),
+            schema: sub_m.get_flag("schema"),
+            command: sub_m.get_one::<String>("command").cloned(),
+        },
+        Some(("status", sub_m)) => CommandKind::Status(StatusArgs {
+            deep: sub_m.get_flag("deep"),
+            diagnose: sub_m.get_flag("diagnose"),
+        }),
+        Some(("databases", sub_m)) => CommandKind::Databases(DatabasesArgs {
+            name: sub_m.get_one::<String>("name").cloned(),
+            owner: sub_m.get_one::<String>("owner").cloned(),
+            include_system: sub_m.get_flag("include-system"),
+            details: sub_m.get_flag("details"),
+            foreach: sub_m.get_one::<String>("foreach").cloned(),
+            create_script: sub_m.get_one::<String>("create-script").cloned(),
+            include_files: sub_m.get_flag("include-files"),
+            include_security: sub_m.get_flag("include-security"),
+            include_data: sub_m
+                .get_many::<String>("include-data")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            out: sub_m.get_one::<PathBuf>("out").cloned(),
+            limit: sub_m.get_one::<u64>("limit").copied(),
+            offset: sub_m.get_one::<u64>("offset").copied(),
+        }),
+        Some(("tables", sub_m)) => CommandKind::Tables(TablesArgs {
+            schema: sub_m.get_one::<String>("schema").cloned(),
+            like: sub_m.get_one::<String>("like").cloned(),
+            include_views: sub_m.get_flag("include-views"),
+            with_counts: sub_m.get_flag("with-counts"),
             summary: sub_m.get_flag("summary"),
             describe: sub_m.get_flag("describe"),
+            concurrency: sub_m
+                .get_one::<u64>("concurrency")
+                .map_or(pool::DEFAULT_CONCURRENCY, |n| *n as usize),
+            orphaned: sub_m.get_flag("orphaned"),
+            modified_since: sub_m.get_one::<ModifiedSince>("modified-since").copied(),
             limit: sub_m.get_one::<String>("limit").cloned(),
             offset: sub_m.get_one::<u64>("offset").copied(),
         }),
@@ -1384,22 +4116,94 @@ fn parse_matches(matches: &ArgMatches) -> CliArgs {
             no_indexes: sub_m.get_flag("no-indexes"),
             no_triggers: sub_m.get_flag("no-triggers"),
             no_ddl: sub_m.get_flag("no-ddl"),
+            ddl_full: sub_m.get_flag("ddl-full"),
             include_fks: sub_m.get_flag("include-fks"),
             include_constraints: sub_m.get_flag("include-constraints"),
+            permissions: sub_m.get_flag("permissions"),
+            max_objects: sub_m.get_one::<usize>("max-objects").copied().unwrap_or(25),
         }),
         Some(("sql", sub_m)) => CommandKind::Sql(SqlArgs {
-            sql: sub_m.get_one::<String>("sql").cloned(),
-            file: sub_m.get_one::<String>("file").map(PathBuf::from),
-            stdin: sub_m.get_flag("stdin"),
+            // `-` as the SQL or the file means stdin, as in `cat q.sql | sscli sql -`.
+            sql: sub_m
+                .get_one::<String>("sql")
+                .filter(|sql| sql.as_str() != "-")
+                .cloned(),
+            file: sub_m
+                .get_one::<String>("file")
+                .filter(|path| path.as_str() != "-")
+                .map(PathBuf::from),
+            stdin: sub_m.get_flag("stdin")
+                || sub_m.get_one::<String>("sql").is_some_and(|sql| sql == "-")
+                || sub_m
+                    .get_one::<String>("file")
+                    .is_some_and(|path| path == "-"),
             params: sub_m
                 .get_many::<String>("param")
                 .map(|values| values.cloned().collect())
                 .unwrap_or_default(),
+            params_file: sub_m.get_one::<PathBuf>("params-file").cloned(),
             max_rows: sub_m.get_one::<u64>("max-rows").copied(),
             csv: sub_m.get_one::<String>("csv").map(PathBuf::from),
+            out: sub_m
+                .get_many::<PathBuf>("out")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            to: sub_m.get_one::<String>("to").cloned(),
+            storage_tier: sub_m.get_one::<String>("storage-tier").cloned(),
             dry_run: sub_m.get_flag("dry-run"),
             continue_on_error: sub_m.get_flag("continue-on-error"),
             no_truncate: sub_m.get_flag("no-truncate"),
+            save_blobs: sub_m.get_one::<PathBuf>("save-blobs").cloned(),
+            format_sql: sub_m.get_flag("format-sql"),
+            style: parse_format_style(sub_m),
+        }),
+        Some(("fmt", sub_m)) => CommandKind::Fmt(FmtArgs {
+            sql: sub_m
+                .get_one::<String>("sql")
+                .filter(|sql| sql.as_str() != "-")
+                .cloned(),
+            file: sub_m
+                .get_one::<String>("file")
+                .filter(|path| path.as_str() != "-")
+                .map(PathBuf::from),
+            stdin: sub_m.get_flag("stdin")
+                || sub_m.get_one::<String>("sql").is_some_and(|sql| sql == "-")
+                || sub_m
+                    .get_one::<String>("file")
+                    .is_some_and(|path| path == "-"),
+            write: sub_m.get_flag("write"),
+            check: sub_m.get_flag("check"),
+            style: parse_format_style(sub_m),
+        }),
+        Some(("lint", sub_m)) => CommandKind::Lint(LintArgs {
+            sql: sub_m
+                .get_one::<String>("sql")
+                .filter(|sql| sql.as_str() != "-")
+                .cloned(),
+            files: sub_m
+                .get_many::<String>("file")
+                .map(|values| {
+                    values
+                        .filter(|path| path.as_str() != "-")
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            stdin: sub_m.get_flag("stdin")
+                || sub_m.get_one::<String>("sql").is_some_and(|sql| sql == "-")
+                || sub_m
+                    .get_many::<String>("file")
+                    .is_some_and(|mut values| values.any(|path| path == "-")),
+            object: sub_m.get_one::<String>("object").cloned(),
+            check_types: sub_m.get_flag("check-types"),
+            fail_on: sub_m
+                .get_one::<String>("fail-on")
+                .cloned()
+                .unwrap_or_else(|| "low".to_string()),
+            disable: sub_m
+                .get_many::<String>("disable")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
         }),
         Some(("table-data", sub_m)) => CommandKind::TableData(TableDataArgs {
             table: sub_m
@@ -1416,8 +4220,31 @@ fn parse_matches(matches: &ArgMatches) -> CliArgs {
                 .get_many::<String>("param")
                 .map(|values| values.cloned().collect())
                 .unwrap_or_default(),
+            params_file: sub_m.get_one::<PathBuf>("params-file").cloned(),
             csv: sub_m.get_one::<String>("csv").map(PathBuf::from),
+            out: sub_m
+                .get_many::<PathBuf>("out")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            to: sub_m.get_one::<String>("to").cloned(),
+            storage_tier: sub_m.get_one::<String>("storage-tier").cloned(),
             no_truncate: sub_m.get_flag("no-truncate"),
+            follow: sub_m.get_one::<String>("follow").cloned(),
+            interval_ms: sub_m
+                .get_one::<u64>("interval")
+                .copied()
+                .unwrap_or(FOLLOW_INTERVAL_DEFAULT_MS),
+            from_now: sub_m.get_flag("from-now"),
+            as_of: sub_m.get_one::<NaiveDateTime>("as-of").copied(),
+            history: sub_m.get_flag("history"),
+            sample: sub_m.get_one::<Sample>("sample").cloned(),
+            save_blobs: sub_m.get_one::<PathBuf>("save-blobs").cloned(),
+            paginate: sub_m
+                .get_one::<Paginate>("paginate")
+                .copied()
+                .unwrap_or_default(),
+            key: sub_m.get_one::<String>("key").cloned(),
+            after: sub_m.get_one::<String>("after").cloned(),
         }),
         Some(("columns", sub_m)) => CommandKind::Columns(ColumnsArgs {
             object: sub_m.get_one::<String>("object").cloned(),
@@ -1433,11 +4260,21 @@ fn parse_matches(matches: &ArgMatches) -> CliArgs {
             table: sub_m.get_one::<String>("table").cloned(),
             schema: sub_m.get_one::<String>("schema").cloned(),
             show_usage: sub_m.get_flag("show-usage"),
+            recommend_compression: sub_m.get_flag("recommend-compression"),
+            max_objects: sub_m.get_one::<usize>("max-objects").copied().unwrap_or(25),
         }),
         Some(("foreign-keys", sub_m)) => CommandKind::ForeignKeys(ForeignKeysArgs {
             table: sub_m.get_one::<String>("table").cloned(),
             schema: sub_m.get_one::<String>("schema").cloned(),
             direction: sub_m.get_one::<String>("direction").cloned(),
+            graph: sub_m.get_one::<String>("graph").cloned(),
+            depth: sub_m.get_one::<u32>("depth").copied().unwrap_or(1),
+            max_objects: sub_m.get_one::<usize>("max-objects").copied().unwrap_or(25),
+        }),
+        Some(("fk-check", sub_m)) => CommandKind::FkCheck(FkCheckArgs {
+            table: sub_m.get_one::<String>("table").cloned(),
+            schema: sub_m.get_one::<String>("schema").cloned(),
+            all: sub_m.get_flag("all"),
         }),
         Some(("stored-procs", sub_m)) => CommandKind::StoredProcs(StoredProcsArgs {
             schema: sub_m.get_one::<String>("schema").cloned(),
@@ -1447,7 +4284,17 @@ fn parse_matches(matches: &ArgMatches) -> CliArgs {
             offset: sub_m.get_one::<u64>("offset").copied(),
             exec: sub_m.get_one::<String>("exec").cloned(),
             args: sub_m.get_one::<String>("args").cloned(),
+            params: sub_m
+                .get_many::<String>("param")
+                .map(|vals| vals.cloned().collect())
+                .unwrap_or_default(),
             no_truncate: sub_m.get_flag("no-truncate"),
+            grep: sub_m.get_one::<String>("grep").cloned(),
+            context: sub_m.get_one::<u32>("context").copied().unwrap_or(2),
+            history: sub_m.get_one::<String>("history").cloned(),
+            days: sub_m.get_one::<u32>("days").copied().unwrap_or(7),
+            modified_since: sub_m.get_one::<ModifiedSince>("modified-since").copied(),
+            test: sub_m.get_one::<String>("test").map(PathBuf::from),
         }),
         Some(("sessions", sub_m)) => CommandKind::Sessions(SessionsArgs {
             database: sub_m.get_one::<String>("database").cloned(),
@@ -1455,155 +4302,1703 @@ fn parse_matches(matches: &ArgMatches) -> CliArgs {
             host: sub_m.get_one::<String>("host").cloned(),
             status: sub_m.get_one::<String>("status").cloned(),
             limit: sub_m.get_one::<u64>("limit").copied(),
+            resource_usage: sub_m.get_flag("resource-usage"),
+            top: sub_m.get_one::<u64>("top").copied(),
         }),
         Some(("query-stats", sub_m)) => CommandKind::QueryStats(QueryStatsArgs {
             database: sub_m.get_one::<String>("database").cloned(),
             order: sub_m.get_one::<String>("order").cloned(),
             limit: sub_m.get_one::<u64>("limit").copied(),
+            export_workload: sub_m
+                .get_one::<String>("export-workload")
+                .map(PathBuf::from),
+            procs: sub_m.get_flag("proc"),
+            schema: sub_m.get_one::<String>("schema").cloned(),
+            name: sub_m.get_one::<String>("name").cloned(),
+            plan_cache: sub_m.get_flag("plan-cache"),
+        }),
+        Some(("trace", sub_m)) => CommandKind::Trace(parse_trace(sub_m)),
+        Some(("migrate", sub_m)) => CommandKind::Migrate(parse_migrate(sub_m)),
+        Some(("seed", sub_m)) => {
+            let (name, seed_m) = sub_m.subcommand().expect("clap enforces a seed subcommand");
+            CommandKind::Seed(SeedArgs {
+                command: if name == "apply" {
+                    SeedCommand::Apply
+                } else {
+                    SeedCommand::Diff
+                },
+                files: seed_m
+                    .get_many::<PathBuf>("files")
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default(),
+            })
+        }
+        Some(("serve", sub_m)) => CommandKind::Serve(ServeArgs {
+            bind: sub_m.get_one::<String>("bind").cloned(),
+            port: sub_m.get_one::<u16>("port").copied(),
+        }),
+        Some(("backups", sub_m)) => CommandKind::Backups(BackupsArgs {
+            database: sub_m.get_one::<String>("database").cloned(),
+            since: sub_m.get_one::<u64>("since").copied(),
+            backup_type: sub_m.get_one::<String>("type").cloned(),
+            limit: sub_m.get_one::<u64>("limit").copied(),
+            to: sub_m.get_one::<String>("to").cloned(),
+            storage_tier: sub_m.get_one::<String>("storage-tier").cloned(),
+            check: sub_m.get_flag("check"),
+            max_full_age: sub_m.get_one::<u64>("max-full-age").copied(),
+            max_log_age: sub_m.get_one::<u64>("max-log-age").copied(),
+            history: sub_m.get_one::<String>("history").cloned(),
+            restore_script: sub_m.get_one::<String>("restore-script").cloned(),
+            stop_at: sub_m.get_one::<NaiveDateTime>("stop-at").copied(),
+            out: sub_m.get_one::<PathBuf>("out").cloned(),
+        }),
+        Some(("changes", sub_m)) => CommandKind::Changes(ChangesArgs {
+            since: *sub_m
+                .get_one::<ModifiedSince>("since")
+                .expect("--since is required"),
+            schema: sub_m.get_one::<String>("schema").cloned(),
+            object_type: sub_m.get_one::<String>("type").cloned(),
+            include_system: sub_m.get_flag("include-system"),
+        }),
+        Some(("assemblies", sub_m)) => CommandKind::Assemblies(AssembliesArgs {
+            name: sub_m.get_one::<String>("name").cloned(),
+            include_system: sub_m.get_flag("include-system"),
+        }),
+        Some(("partitions", sub_m)) => CommandKind::Partitions(PartitionsArgs {
+            table: sub_m.get_one::<String>("table").cloned(),
+            script_split: sub_m.get_one::<String>("script-split").cloned(),
+            script_merge: sub_m.get_one::<String>("script-merge").cloned(),
+            filegroup: sub_m.get_one::<String>("filegroup").cloned(),
+        }),
+        Some(("errorlog", sub_m)) => CommandKind::Errorlog(ErrorlogArgs {
+            last_minutes: sub_m.get_one::<u64>("last").copied(),
+            grep: sub_m.get_one::<String>("grep").cloned(),
+            archive: sub_m.get_one::<u32>("archive").copied().unwrap_or(0),
+            min_severity: sub_m.get_one::<u32>("min-severity").copied(),
+            limit: sub_m.get_one::<u64>("limit").copied(),
+            offset: sub_m.get_one::<u64>("offset").copied(),
+        }),
+        Some(("ag", sub_m)) => CommandKind::Ag(AgArgs {
+            group: default_subcommand(sub_m, "status")
+                .get_one::<String>("group")
+                .cloned(),
+        }),
+        Some(("cdc", sub_m)) => CommandKind::Cdc(CdcArgs {
+            table: default_subcommand(sub_m, "status")
+                .get_one::<String>("table")
+                .cloned(),
+        }),
+        Some(("replication", sub_m)) => CommandKind::Replication(ReplicationArgs {
+            publication: default_subcommand(sub_m, "status")
+                .get_one::<String>("publication")
+                .cloned(),
+        }),
+        Some(("compare", sub_m)) => CommandKind::Compare(CompareArgs {
+            source: sub_m.get_one::<String>("source").cloned(),
+            target: sub_m
+                .get_one::<String>("target")
+                .cloned()
+                .expect("clap enforces required target"),
+            source_connection: sub_m.get_one::<String>("source-connection").cloned(),
+            target_connection: sub_m.get_one::<String>("target-connection").cloned(),
+            source_dacpac: sub_m.get_one::<String>("source-dacpac").map(PathBuf::from),
+            shadow_database: sub_m.get_one::<String>("shadow-database").cloned(),
+            schemas: sub_m
+                .get_many::<String>("schema")
+                .map(|values| values.map(|v| v.to_string()).collect()),
+            object: sub_m.get_one::<String>("object").cloned(),
+            out: sub_m.get_one::<PathBuf>("out").cloned(),
+            patch: sub_m.get_one::<PathBuf>("patch").cloned(),
+            summary: sub_m.get_flag("summary"),
+            pretty: sub_m.get_flag("pretty"),
+            ignore_whitespace: sub_m.get_flag("ignore-whitespace"),
+            strip_comments: sub_m.get_flag("strip-comments"),
+            format_sql: sub_m.get_flag("format-sql"),
+            side_by_side: sub_m.get_flag("side-by-side"),
+            gui_diff: sub_m.get_flag("gui-diff"),
+            apply_script: sub_m.contains_id("apply-script"),
+            apply_path: sub_m.get_one::<String>("apply-script").cloned(),
+            include_drops: sub_m.get_flag("include-drops"),
+            compact: sub_m.get_flag("compact"),
+            query_timeout_ms: sub_m.get_one::<u64>("query-timeout").copied(),
+            ignore_file: sub_m.get_one::<PathBuf>("ignore-file").cloned(),
+            only: sub_m
+                .get_many::<String>("only")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            include: sub_m
+                .get_many::<String>("include")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            exclude: sub_m
+                .get_many::<String>("exclude")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+        }),
+        Some(("profile", sub_m)) => CommandKind::Profile(ProfileArgs {
+            table: sub_m
+                .get_one::<String>("table")
+                .or_else(|| sub_m.get_one::<String>("object"))
+                .cloned(),
+            schema: sub_m.get_one::<String>("schema").cloned(),
+            columns: sub_m
+                .get_many::<String>("columns")
+                .map(|values| {
+                    values
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            top: sub_m.get_one::<u32>("top").copied().unwrap_or(5),
+            exact_threshold: sub_m
+                .get_one::<u64>("exact-threshold")
+                .copied()
+                .unwrap_or(1_000_000),
+        }),
+        Some(("assert", sub_m)) => CommandKind::Assert(AssertArgs {
+            sql: sub_m.get_one::<String>("sql").cloned(),
+            file: sub_m.get_one::<String>("file").map(PathBuf::from),
+            params: sub_m
+                .get_many::<String>("param")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            expect: if let Some(bound) = sub_m.get_one::<String>("expect-lt") {
+                Expectation::LessThan(bound.clone())
+            } else if let Some(bound) = sub_m.get_one::<String>("expect-gt") {
+                Expectation::GreaterThan(bound.clone())
+            } else if sub_m.get_flag("expect-empty") {
+                Expectation::Empty
+            } else {
+                Expectation::Equals(
+                    sub_m
+                        .get_one::<String>("expect")
+                        .cloned()
+                        .expect("clap requires an expectation"),
+                )
+            },
+            name: sub_m.get_one::<String>("name").cloned(),
+        }),
+        Some(("verify", sub_m)) => CommandKind::Verify(VerifyArgs {
+            source: sub_m.get_one::<String>("source").cloned(),
+            target: sub_m
+                .get_one::<String>("target")
+                .cloned()
+                .expect("clap enforces required target"),
+            source_connection: sub_m.get_one::<String>("source-connection").cloned(),
+            target_connection: sub_m.get_one::<String>("target-connection").cloned(),
+            tables: sub_m
+                .get_many::<String>("tables")
+                .map(|values| {
+                    values
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            checksum: sub_m.get_flag("checksum") || sub_m.contains_id("chunk-size"),
+            chunk_size: sub_m.get_one::<u64>("chunk-size").copied(),
+        }),
+        Some(("permissions", sub_m)) => CommandKind::Permissions(PermissionsArgs {
+            command: default_subcommand(sub_m, "check")
+                .get_one::<String>("command")
+                .cloned(),
+        }),
+        Some(("schema", sub_m)) => match sub_m.subcommand() {
+            Some(("export", export_m)) => CommandKind::SchemaExport(SchemaExportArgs {
+                dacpac: export_m
+                    .get_one::<String>("dacpac")
+                    .map(PathBuf::from)
+                    .expect("clap enforces required dacpac"),
+                dir: export_m.get_one::<String>("dir").map(PathBuf::from),
+                schemas: export_m
+                    .get_many::<String>("schema")
+                    .map(|values| values.map(|v| v.to_string()).collect())
+                    .unwrap_or_default(),
+                shadow_database: export_m.get_one::<String>("shadow-database").cloned(),
+            }),
+            _ => {
+                let apply_m = sub_m
+                    .subcommand_matches("apply")
+                    .expect("clap enforces a schema subcommand");
+                CommandKind::SchemaApply(SchemaApplyArgs {
+                    dir: apply_m
+                        .get_one::<String>("dir")
+                        .map(PathBuf::from)
+                        .expect("clap enforces required dir"),
+                    schemas: apply_m
+                        .get_many::<String>("schema")
+                        .map(|values| values.map(|v| v.to_string()).collect())
+                        .unwrap_or_default(),
+                    shadow_database: apply_m.get_one::<String>("shadow-database").cloned(),
+                    include_drops: apply_m.get_flag("include-drops"),
+                    execute: apply_m.get_flag("execute"),
+                    allow_destructive: apply_m.get_flag("allow-destructive"),
+                    script: apply_m.get_one::<String>("script").map(PathBuf::from),
+                })
+            }
+        },
+        Some(("security", sub_m)) => {
+            let grant_m = sub_m
+                .subcommand_matches("grant-script")
+                .expect("clap enforces a security subcommand");
+            let values = |id: &str| -> Vec<String> {
+                grant_m
+                    .get_many::<String>(id)
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default()
+            };
+            CommandKind::GrantScript(GrantScriptArgs {
+                principal: grant_m
+                    .get_one::<String>("principal")
+                    .cloned()
+                    .expect("clap enforces required principal"),
+                allow: values("allow"),
+                schemas: values("schemas"),
+                members: values("member"),
+                missing_only: grant_m.get_flag("missing-only"),
+            })
+        }
+        Some(("docs", sub_m)) => CommandKind::Docs(parse_docs(sub_m)),
+        Some(("init", sub_m)) => CommandKind::Init(InitArgs {
+            path: sub_m.get_one::<String>("path").map(PathBuf::from),
+            force: sub_m.get_flag("force"),
+            profile: sub_m.get_one::<String>("profile").cloned(),
+        }),
+        Some(("config", sub_m)) => match sub_m.subcommand() {
+            Some(("profiles", profiles_m)) => CommandKind::Profiles(parse_profiles(profiles_m)),
+            Some(("connection-string", conn_m)) => CommandKind::Config(ConfigArgs {
+                connection_string: Some(ConnectionStringArgs {
+                    format: conn_m.get_one::<String>("format").cloned(),
+                    redact: !conn_m.get_flag("no-redact"),
+                }),
+            }),
+            _ => CommandKind::Config(ConfigArgs::default()),
+        },
+        Some(("profiles", sub_m)) => CommandKind::Profiles(parse_profiles(sub_m)),
+        Some(("completions", sub_m)) => CommandKind::Completions(CompletionsArgs {
+            shell: sub_m.get_one::<String>("shell").cloned(),
+            list: sub_m.get_one::<String>("list").cloned(),
+        }),
+        Some(("integrations", sub_m)) => CommandKind::Integrations(parse_integrations(sub_m)),
+        _ => CommandKind::Help {
+            all: false,
+            schema: false,
+            command: None,
+        },
+    };
+
+    CliArgs {
+        config_path,
+        env_file,
+        profile,
+        server,
+        port,
+        database,
+        user,
+        password,
+        timeout_ms,
+        isolation_level,
+        lock_timeout_ms,
+        run_id,
+        allow_write,
+        yes,
+        encrypt,
+        trust_cert,
+        time_zone,
+        source_time_zone,
+        binary,
+        max_value_bytes,
+        null_display,
+        booleans,
+        max_cell_width,
+        max_output_chars,
+        refresh,
+        contract,
+        command_path,
+        output,
+        verbose,
+        quiet,
+        quiet_target,
+        command,
+    }
+}
+
+fn command_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub_m)) = current.subcommand() {
+        names.push(name);
+        current = sub_m;
+    }
+    names.join(" ")
+}
+
+fn parse_integrations(matches: &ArgMatches) -> IntegrationsArgs {
+    let command = match matches.subcommand() {
+        Some(("skills", sub_m)) => match sub_m.subcommand() {
+            Some(("add", add_m)) => IntegrationCommand::Skills(IntegrationInstallArgs {
+                global: add_m.get_flag("global"),
+                name: add_m.get_one::<String>("name").cloned(),
+            }),
+            _ => IntegrationCommand::Help,
+        },
+        Some(("gemini", sub_m)) => match sub_m.subcommand() {
+            Some(("add", add_m)) => IntegrationCommand::Gemini(IntegrationInstallArgs {
+                global: add_m.get_flag("global"),
+                name: add_m.get_one::<String>("name").cloned(),
+            }),
+            _ => IntegrationCommand::Help,
+        },
+        Some(("vscode", sub_m)) => match sub_m.subcommand() {
+            Some(("add", add_m)) => IntegrationCommand::Vscode(IntegrationVscodeArgs {
+                force: add_m.get_flag("force"),
+            }),
+            _ => IntegrationCommand::Help,
+        },
+        _ => IntegrationCommand::Help,
+    };
+
+    IntegrationsArgs { command }
+}
+
+fn parse_trace(matches: &ArgMatches) -> TraceArgs {
+    let name = |m: &ArgMatches| {
+        m.get_one::<String>("name")
+            .cloned()
+            .unwrap_or_else(|| TRACE_SESSION_DEFAULT.to_string())
+    };
+    let command = match matches.subcommand() {
+        Some(("tail", sub_m)) => TraceCommand::Tail(TraceTailArgs {
+            name: name(sub_m),
+            interval_ms: sub_m
+                .get_one::<u64>("interval")
+                .copied()
+                .unwrap_or(FOLLOW_INTERVAL_DEFAULT_MS),
+            once: sub_m.get_flag("once"),
         }),
-        Some(("backups", sub_m)) => CommandKind::Backups(BackupsArgs {
-            database: sub_m.get_one::<String>("database").cloned(),
-            since: sub_m.get_one::<u64>("since").copied(),
-            backup_type: sub_m.get_one::<String>("type").cloned(),
-            limit: sub_m.get_one::<u64>("limit").copied(),
+        Some(("stop", sub_m)) => TraceCommand::Stop(TraceStopArgs { name: name(sub_m) }),
+        Some(("start", sub_m)) => TraceCommand::Start(TraceStartArgs {
+            name: name(sub_m),
+            events: sub_m
+                .get_many::<String>("events")
+                .map(|values| {
+                    values
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            filter: sub_m.get_one::<String>("filter").cloned(),
         }),
-        Some(("compare", sub_m)) => CommandKind::Compare(CompareArgs {
-            source: sub_m.get_one::<String>("source").cloned(),
+        _ => unreachable!("clap enforces a trace subcommand"),
+    };
+    TraceArgs { command }
+}
+
+fn parse_docs(matches: &ArgMatches) -> DocsArgs {
+    let command = match matches.subcommand() {
+        Some(("set-description", sub_m)) => DocsCommand::SetDescription {
             target: sub_m
                 .get_one::<String>("target")
                 .cloned()
                 .expect("clap enforces required target"),
-            source_connection: sub_m.get_one::<String>("source-connection").cloned(),
-            target_connection: sub_m.get_one::<String>("target-connection").cloned(),
+            description: sub_m
+                .get_one::<String>("description")
+                .cloned()
+                .expect("clap enforces required description"),
+        },
+        Some(("generate", sub_m)) => DocsCommand::Generate {
             schemas: sub_m
                 .get_many::<String>("schema")
-                .map(|values| values.map(|v| v.to_string()).collect()),
-            object: sub_m.get_one::<String>("object").cloned(),
-            summary: sub_m.get_flag("summary"),
-            pretty: sub_m.get_flag("pretty"),
-            ignore_whitespace: sub_m.get_flag("ignore-whitespace"),
-            strip_comments: sub_m.get_flag("strip-comments"),
-            side_by_side: sub_m.get_flag("side-by-side"),
-            gui_diff: sub_m.get_flag("gui-diff"),
-            apply_script: sub_m.contains_id("apply-script"),
-            apply_path: sub_m.get_one::<String>("apply-script").cloned(),
-            include_drops: sub_m.get_flag("include-drops"),
-            compact: sub_m.get_flag("compact"),
-        }),
-        Some(("init", sub_m)) => CommandKind::Init(InitArgs {
-            path: sub_m.get_one::<String>("path").map(PathBuf::from),
-            force: sub_m.get_flag("force"),
-            profile: sub_m.get_one::<String>("profile").cloned(),
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            out: sub_m
+                .get_one::<PathBuf>("out")
+                .cloned()
+                .unwrap_or_else(|| PathBuf::from("docs/db")),
+            html: sub_m
+                .get_one::<String>("format")
+                .is_some_and(|format| format == "html"),
+        },
+        _ => unreachable!("clap enforces a docs subcommand"),
+    };
+    DocsArgs { command }
+}
+
+fn parse_migrate(matches: &ArgMatches) -> MigrateArgs {
+    let (name, sub_m) = matches
+        .subcommand()
+        .expect("clap enforces a migrate subcommand");
+    let command = match name {
+        "new" => MigrateCommand::New {
+            name: sub_m
+                .get_one::<String>("name")
+                .cloned()
+                .expect("clap enforces required name"),
+        },
+        "status" => MigrateCommand::Status,
+        "up" => MigrateCommand::Up(MigrateUpArgs {
+            dry_run: sub_m.get_flag("dry-run"),
+            to: sub_m.get_one::<String>("to").cloned(),
         }),
-        Some(("config", _)) => CommandKind::Config(ConfigArgs),
-        Some(("completions", sub_m)) => CommandKind::Completions(CompletionsArgs {
-            shell: sub_m.get_one::<String>("shell").cloned(),
+        _ => unreachable!("clap enforces a migrate subcommand"),
+    };
+    MigrateArgs {
+        dir: sub_m.get_one::<PathBuf>("dir").cloned(),
+        command,
+    }
+}
+
+fn parse_profiles(matches: &ArgMatches) -> ProfilesArgs {
+    let name = |m: &ArgMatches| m.get_one::<String>("name").cloned().unwrap_or_default();
+    let edit = |m: &ArgMatches| ProfileEditArgs {
+        name: name(m),
+        password_env: m.get_one::<String>("password-env").cloned(),
+        default_schemas: m
+            .get_many::<String>("default-schemas")
+            .map(|values| values.map(|v| v.trim().to_string()).collect()),
+        set_default: m.get_flag("set-default"),
+    };
+
+    let command = match matches.subcommand() {
+        Some(("add", sub_m)) => ProfileCommand::Add(edit(sub_m)),
+        Some(("edit", sub_m)) => ProfileCommand::Edit(edit(sub_m)),
+        Some(("remove", sub_m)) => ProfileCommand::Remove(ProfileNameArgs { name: name(sub_m) }),
+        Some(("test", sub_m)) => ProfileCommand::Test(ProfileTestArgs {
+            name: sub_m.get_one::<String>("name").cloned(),
         }),
-        Some(("integrations", sub_m)) => CommandKind::Integrations(parse_integrations(sub_m)),
-        _ => CommandKind::Help {
-            all: false,
-            command: None,
-        },
+        _ => ProfileCommand::List,
+    };
+
+    ProfilesArgs { command }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    use super::{
+        CommandKind, DocsArgs, DocsCommand, Expectation, MigrateCommand, MigrateUpArgs, Paginate,
+        SeedCommand, build_cli, looks_like_sql, parse_as_of, parse_matches, wants_full_tree,
     };
+    use crate::config::{
+        BooleanStyle, CommaStyle, IsolationLevel, JsonContractVersion, KeywordCase,
+    };
+
+    fn parse_args_from<I, T>(input: I) -> super::CliArgs
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        super::parse_args_from(input.into_iter().map(Into::into).collect())
+    }
+
+    #[test]
+    fn table_data_accepts_positional_object_name() {
+        let matches = build_cli(false)
+            .try_get_matches_from(["sscli", "table-data", "equipment"])
+            .expect("clap should parse positional table-data object");
+        let args = parse_matches(&matches);
+
+        match args.command {
+            CommandKind::TableData(cmd) => {
+                assert_eq!(cmd.table.as_deref(), Some("equipment"));
+            }
+            other => panic!("expected table-data command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn table_data_prefers_flag_over_positional_object_name() {
+        let matches = build_cli(false)
+            .try_get_matches_from([
+                "sscli",
+                "table-data",
+                "positional_name",
+                "--table",
+                "flag_name",
+            ])
+            .expect("clap should parse table-data with positional and --table");
+        let args = parse_matches(&matches);
+
+        match args.command {
+            CommandKind::TableData(cmd) => {
+                assert_eq!(cmd.table.as_deref(), Some("flag_name"));
+            }
+            other => panic!("expected table-data command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_completions_and_help_all_parse_with_the_full_tree() {
+        let argv = |items: &[&str]| items.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(wants_full_tree(&argv(&[
+            "sscli",
+            "--profile",
+            "dev",
+            "completions",
+            "--shell",
+            "zsh"
+        ])));
+        assert!(wants_full_tree(&argv(&["sscli", "help", "--all"])));
+        assert!(!wants_full_tree(&argv(&["sscli", "help"])));
+        assert!(!wants_full_tree(&argv(&[
+            "sscli",
+            "sql",
+            "SELECT 'completions'"
+        ])));
+    }
+
+    #[test]
+    fn expanded_flag_is_global() {
+        let args = parse_args_from(["sscli", "--expanded", "table-data", "--table", "Orders"]);
+        assert!(args.output.expanded);
+        let args = parse_args_from(["sscli", "describe", "dbo.Orders", "--expanded"]);
+        assert!(args.output.expanded);
+    }
+
+    #[test]
+    fn contract_flag_and_command_path() {
+        let args = parse_args_from(["sscli", "--contract", "v2", "trace", "start", "--json"]);
+        assert_eq!(args.contract, Some(JsonContractVersion::V2));
+        assert_eq!(args.command_path, "trace start");
+        let args = parse_args_from(["sscli", "query", "SELECT 1", "--contract=v1"]);
+        assert_eq!(args.contract, Some(JsonContractVersion::V1));
+        assert_eq!(args.command_path, "sql");
+    }
+
+    #[test]
+    fn yes_flag_is_global() {
+        let args = parse_args_from(["sscli", "-y", "sql", "UPDATE t SET a = 1"]);
+        assert!(args.yes);
+        let args = parse_args_from(["sscli", "migrate", "up", "--yes"]);
+        assert!(args.yes);
+        assert!(!parse_args_from(["sscli", "tables"]).yes);
+    }
+
+    #[test]
+    fn fields_flag_is_global_and_comma_separated() {
+        let args = parse_args_from(["sscli", "--fields", "name, rowCount", "tables"]);
+        assert_eq!(args.output.fields, vec!["name", "rowCount"]);
+        assert!(matches!(args.command, CommandKind::Tables(_)));
+
+        let args = parse_args_from(["sscli", "sessions", "--fields=sessionId,status"]);
+        assert_eq!(args.output.fields, vec!["sessionId", "status"]);
+
+        let args = parse_args_from([
+            "sscli",
+            "tables",
+            "--sort",
+            "rowCount:desc,name",
+            "--filter",
+            "rowCount>1000",
+            "--filter",
+            "name~log",
+        ]);
+        assert_eq!(args.output.sort, vec!["rowCount:desc", "name"]);
+        assert_eq!(args.output.filters, vec!["rowCount>1000", "name~log"]);
+
+        // trace start keeps its own --filter for the event predicate.
+        let args = parse_args_from(["sscli", "trace", "start", "--filter", "duration > 1s"]);
+        match args.command {
+            CommandKind::Trace(cmd) => match cmd.command {
+                super::TraceCommand::Start(start) => {
+                    assert_eq!(start.filter.as_deref(), Some("duration > 1s"))
+                }
+                other => panic!("expected trace start, got: {:?}", other),
+            },
+            other => panic!("expected trace command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn table_data_follow_parses_interval() {
+        let args = parse_args_from([
+            "sscli",
+            "table-data",
+            "dbo.AppLog",
+            "--follow",
+            "Id",
+            "--interval",
+            "500ms",
+            "--from-now",
+        ]);
+        match args.command {
+            CommandKind::TableData(cmd) => {
+                assert_eq!(cmd.follow.as_deref(), Some("Id"));
+                assert_eq!(cmd.interval_ms, 500);
+                assert!(cmd.from_now);
+            }
+            other => panic!("expected table-data command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "table-data", "t", "--from-now"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn session_flags_parse_globally() {
+        let args = parse_args_from([
+            "sscli",
+            "tables",
+            "--isolation-level",
+            "read-uncommitted",
+            "--lock-timeout",
+            "-1",
+            "--run-id",
+            "nightly-42",
+        ]);
+        assert_eq!(args.isolation_level, Some(IsolationLevel::ReadUncommitted));
+        assert_eq!(args.lock_timeout_ms, Some(-1));
+        assert_eq!(args.run_id.as_deref(), Some("nightly-42"));
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "tables", "--lock-timeout", "-2"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn table_data_keyset_paging_parses() {
+        let args = parse_args_from([
+            "sscli",
+            "table-data",
+            "Orders",
+            "--paginate",
+            "keyset",
+            "--key",
+            "CreatedAt",
+            "--after",
+            "[\"2026-01-01T00:00:00\",42]",
+        ]);
+        match args.command {
+            CommandKind::TableData(cmd) => {
+                assert_eq!(cmd.paginate, Paginate::Keyset);
+                assert_eq!(cmd.key.as_deref(), Some("CreatedAt"));
+                assert!(cmd.after.is_some());
+            }
+            other => panic!("expected table-data command, got: {:?}", other),
+        }
+        for argv in [
+            ["sscli", "table-data", "t", "--key", "Id"].as_slice(),
+            [
+                "sscli",
+                "table-data",
+                "t",
+                "--paginate",
+                "keyset",
+                "--after",
+                "[1]",
+                "--offset",
+                "5",
+            ]
+            .as_slice(),
+            ["sscli", "table-data", "t", "--paginate", "pages"].as_slice(),
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn describe_accepts_database_type_without_object() {
+        let args = parse_args_from(["sscli", "describe", "--type", "database"]);
+        match args.command {
+            CommandKind::Describe(cmd) => {
+                assert_eq!(cmd.object_type.as_deref(), Some("database"));
+                assert_eq!(cmd.object, None);
+            }
+            other => panic!("expected describe command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn schema_apply_parses_model_dir_and_guards() {
+        let args = parse_args_from([
+            "sscli",
+            "schema",
+            "apply",
+            "db/model",
+            "--schema",
+            "dbo,sales",
+            "--execute",
+            "--allow-destructive",
+        ]);
+        match args.command {
+            CommandKind::SchemaApply(cmd) => {
+                assert_eq!(cmd.dir, std::path::PathBuf::from("db/model"));
+                assert_eq!(cmd.schemas, vec!["dbo".to_string(), "sales".to_string()]);
+                assert!(cmd.execute && cmd.allow_destructive && !cmd.include_drops);
+            }
+            other => panic!("expected schema apply command, got: {:?}", other),
+        }
+        for argv in [
+            ["sscli", "schema", "apply", "m", "--allow-destructive"].as_slice(),
+            ["sscli", "schema"].as_slice(),
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn dacpac_export_and_compare_source_parse() {
+        let args = parse_args_from([
+            "sscli",
+            "schema",
+            "export",
+            "--dacpac",
+            "out/app.dacpac",
+            "db/model",
+        ]);
+        match args.command {
+            CommandKind::SchemaExport(cmd) => {
+                assert_eq!(cmd.dacpac, std::path::PathBuf::from("out/app.dacpac"));
+                assert_eq!(cmd.dir, Some(std::path::PathBuf::from("db/model")));
+                assert!(cmd.schemas.is_empty());
+            }
+            other => panic!("expected schema export command, got: {:?}", other),
+        }
+        let args = parse_args_from([
+            "sscli",
+            "compare",
+            "--source-dacpac",
+            "app.dacpac",
+            "--target",
+            "dev",
+            "--shadow-database",
+            "scratch",
+        ]);
+        match args.command {
+            CommandKind::Compare(cmd) => {
+                assert_eq!(
+                    cmd.source_dacpac,
+                    Some(std::path::PathBuf::from("app.dacpac"))
+                );
+                assert_eq!(cmd.shadow_database.as_deref(), Some("scratch"));
+            }
+            other => panic!("expected compare command, got: {:?}", other),
+        }
+        for argv in [
+            ["sscli", "schema", "export", "db/model"].as_slice(),
+            [
+                "sscli",
+                "schema",
+                "export",
+                "--dacpac",
+                "a.dacpac",
+                "--shadow-database",
+                "x",
+            ]
+            .as_slice(),
+            [
+                "sscli",
+                "compare",
+                "--source-dacpac",
+                "a.dacpac",
+                "--source",
+                "prod",
+                "--target",
+                "dev",
+            ]
+            .as_slice(),
+            [
+                "sscli",
+                "compare",
+                "--target",
+                "dev",
+                "--shadow-database",
+                "x",
+            ]
+            .as_slice(),
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn profile_parses_table_and_columns() {
+        let args = parse_args_from([
+            "sscli",
+            "profile",
+            "--table",
+            "dbo.Customers",
+            "--columns",
+            "Email, Country",
+            "--top",
+            "3",
+        ]);
+        match args.command {
+            CommandKind::Profile(cmd) => {
+                assert_eq!(cmd.table.as_deref(), Some("dbo.Customers"));
+                assert_eq!(cmd.columns, vec!["Email", "Country"]);
+                assert_eq!(cmd.top, 3);
+                assert_eq!(cmd.exact_threshold, 1_000_000);
+            }
+            other => panic!("expected profile command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "profile", "Orders"]).command {
+            CommandKind::Profile(cmd) => assert_eq!(cmd.table.as_deref(), Some("Orders")),
+            other => panic!("expected profile command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stored_procs_exec_collects_params_and_rejects_raw_args() {
+        let args = parse_args_from([
+            "sscli",
+            "procs",
+            "--exec",
+            "dbo.GetOrders",
+            "--param",
+            "CustomerId=42",
+            "--param",
+            "@Status=shipped",
+        ]);
+        match args.command {
+            CommandKind::StoredProcs(cmd) => {
+                assert_eq!(cmd.exec.as_deref(), Some("dbo.GetOrders"));
+                assert_eq!(cmd.params, vec!["CustomerId=42", "@Status=shipped"]);
+            }
+            other => panic!("expected stored-procs command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from([
+                    "sscli", "procs", "--exec", "p", "--args", "1", "--param", "a=1",
+                ])
+                .is_err()
+        );
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "procs", "--param", "a=1"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn trace_subcommands_parse_defaults() {
+        match parse_args_from([
+            "sscli",
+            "trace",
+            "start",
+            "--filter",
+            "duration > 500ms",
+            "--events",
+            "rpc_completed, error_reported",
+        ])
+        .command
+        {
+            CommandKind::Trace(args) => assert_eq!(
+                args.command,
+                super::TraceCommand::Start(super::TraceStartArgs {
+                    name: "sscli_trace".to_string(),
+                    events: vec!["rpc_completed".to_string(), "error_reported".to_string()],
+                    filter: Some("duration > 500ms".to_string()),
+                })
+            ),
+            other => panic!("expected trace command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "trace", "tail", "--name", "slow", "--once"]).command {
+            CommandKind::Trace(args) => assert_eq!(
+                args.command,
+                super::TraceCommand::Tail(super::TraceTailArgs {
+                    name: "slow".to_string(),
+                    interval_ms: 2_000,
+                    once: true,
+                })
+            ),
+            other => panic!("expected trace command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "trace"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sessions_top_takes_the_global_sort() {
+        let args = parse_args_from(["sscli", "sessions", "--top", "5", "--sort", "tempdb"]);
+        assert_eq!(args.output.sort, vec!["tempdb"]);
+        match args.command {
+            CommandKind::Sessions(cmd) => {
+                assert_eq!(cmd.top, Some(5));
+                assert!(!cmd.resource_usage);
+            }
+            other => panic!("expected sessions command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "sessions", "--top", "5", "--limit", "10"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn query_stats_proc_mode_parses_filters() {
+        let args = parse_args_from([
+            "sscli",
+            "query-stats",
+            "--proc",
+            "-s",
+            "sales",
+            "--name",
+            "usp_%",
+            "--order",
+            "duration",
+        ]);
+        match args.command {
+            CommandKind::QueryStats(cmd) => {
+                assert!(cmd.procs);
+                assert_eq!(cmd.schema.as_deref(), Some("sales"));
+                assert_eq!(cmd.name.as_deref(), Some("usp_%"));
+                assert_eq!(cmd.order.as_deref(), Some("duration"));
+            }
+            other => panic!("expected query-stats command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "query-stats", "--name", "usp_%"])
+                .is_err()
+        );
+        let args = parse_args_from(["sscli", "query-stats", "--plan-cache", "--limit", "5"]);
+        assert!(matches!(args.command, CommandKind::QueryStats(cmd) if cmd.plan_cache));
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "query-stats", "--plan-cache", "--proc"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn stored_procs_history_parses_days() {
+        let args = parse_args_from(["sscli", "procs", "--history", "dbo.usp_X", "--days", "30"]);
+        match args.command {
+            CommandKind::StoredProcs(cmd) => {
+                assert_eq!(cmd.history.as_deref(), Some("dbo.usp_X"));
+                assert_eq!(cmd.days, 30);
+            }
+            other => panic!("expected stored-procs command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "procs", "--history", "p", "--exec", "p"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn stored_procs_test_takes_a_file_and_conflicts_with_exec() {
+        match parse_args_from(["sscli", "procs", "--test", "procs.test.yaml"]).command {
+            CommandKind::StoredProcs(cmd) => {
+                assert_eq!(cmd.test, Some(PathBuf::from("procs.test.yaml")));
+            }
+            other => panic!("expected stored-procs command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "procs", "--test", "t.yaml", "--exec", "p"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn stored_procs_grep_parses_context_and_conflicts_with_exec() {
+        let args = parse_args_from(["sscli", "procs", "--grep", "OrderStatus = 5", "-C", "4"]);
+        match args.command {
+            CommandKind::StoredProcs(cmd) => {
+                assert_eq!(cmd.grep.as_deref(), Some("OrderStatus = 5"));
+                assert_eq!(cmd.context, 4);
+            }
+            other => panic!("expected stored-procs command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "procs", "--grep", "x", "--exec", "sp_who"])
+                .is_err()
+        );
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "procs", "--context", "3"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn tables_orphaned_parses_and_conflicts_with_describe() {
+        let args = parse_args_from(["sscli", "tables", "--audit", "--schema", "sales"]);
+        match args.command {
+            CommandKind::Tables(cmd) => {
+                assert!(cmd.orphaned);
+                assert_eq!(cmd.schema.as_deref(), Some("sales"));
+            }
+            other => panic!("expected tables command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "tables", "--orphaned", "--describe"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn foreign_keys_graph_parses_format_and_depth() {
+        let args = parse_args_from([
+            "sscli", "fk", "--table", "Orders", "--graph", "mermaid", "--depth", "2",
+        ]);
+        match args.command {
+            CommandKind::ForeignKeys(cmd) => {
+                assert_eq!(cmd.graph.as_deref(), Some("mermaid"));
+                assert_eq!(cmd.depth, 2);
+            }
+            other => panic!("expected foreign-keys command, got: {:?}", other),
+        }
+        for argv in [
+            ["sscli", "fk", "--table", "Orders", "--graph", "svg"].as_slice(),
+            ["sscli", "fk", "--table", "Orders", "--depth", "2"].as_slice(),
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
 
-    CliArgs {
-        config_path,
-        env_file,
-        profile,
-        server,
-        port,
-        database,
-        user,
-        password,
-        timeout_ms,
-        allow_write,
-        encrypt,
-        trust_cert,
-        output,
-        verbose,
-        quiet,
-        quiet_target,
-        command,
+    #[test]
+    fn wildcard_commands_default_max_objects() {
+        match parse_args_from(["sscli", "describe", "dbo.Order*"]).command {
+            CommandKind::Describe(cmd) => assert_eq!(cmd.max_objects, 25),
+            other => panic!("expected describe command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "indexes", "-t", "sales.*", "--max-objects", "5"]).command {
+            CommandKind::Indexes(cmd) => assert_eq!(cmd.max_objects, 5),
+            other => panic!("expected indexes command, got: {:?}", other),
+        }
     }
-}
 
-fn parse_integrations(matches: &ArgMatches) -> IntegrationsArgs {
-    let command = match matches.subcommand() {
-        Some(("skills", sub_m)) => match sub_m.subcommand() {
-            Some(("add", add_m)) => IntegrationCommand::Skills(IntegrationInstallArgs {
-                global: add_m.get_flag("global"),
-                name: add_m.get_one::<String>("name").cloned(),
-            }),
-            _ => IntegrationCommand::Help,
-        },
-        Some(("gemini", sub_m)) => match sub_m.subcommand() {
-            Some(("add", add_m)) => IntegrationCommand::Gemini(IntegrationInstallArgs {
-                global: add_m.get_flag("global"),
-                name: add_m.get_one::<String>("name").cloned(),
-            }),
-            _ => IntegrationCommand::Help,
-        },
-        _ => IntegrationCommand::Help,
-    };
+    #[test]
+    fn indexes_recommend_compression_flag() {
+        match parse_args_from([
+            "sscli",
+            "indexes",
+            "-t",
+            "Orders",
+            "--recommend-compression",
+        ])
+        .command
+        {
+            CommandKind::Indexes(cmd) => {
+                assert!(cmd.recommend_compression);
+                assert!(!cmd.show_usage);
+            }
+            other => panic!("expected indexes command, got: {:?}", other),
+        }
+    }
 
-    IntegrationsArgs { command }
-}
+    #[test]
+    fn security_grant_script_splits_lists() {
+        match parse_args_from([
+            "sscli",
+            "security",
+            "grant-script",
+            "--principal",
+            "app_reader",
+            "--allow",
+            "select,execute",
+            "--schemas",
+            "dbo,web",
+            "--missing-only",
+        ])
+        .command
+        {
+            CommandKind::GrantScript(cmd) => {
+                assert_eq!(cmd.principal, "app_reader");
+                assert_eq!(cmd.allow, vec!["select", "execute"]);
+                assert_eq!(cmd.schemas, vec!["dbo", "web"]);
+                assert!(cmd.members.is_empty());
+                assert!(cmd.missing_only);
+            }
+            other => panic!("expected grant-script command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from([
+                    "sscli",
+                    "security",
+                    "grant-script",
+                    "--principal",
+                    "x",
+                    "--allow",
+                    "alter"
+                ])
+                .is_err()
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::ffi::OsString;
+    #[test]
+    fn docs_subcommands_parse() {
+        match parse_args_from([
+            "sscli", "docs", "generate", "--schema", "dbo,web", "--format", "html",
+        ])
+        .command
+        {
+            CommandKind::Docs(DocsArgs {
+                command: DocsCommand::Generate { schemas, out, html },
+            }) => {
+                assert_eq!(schemas, vec!["dbo", "web"]);
+                assert_eq!(out, PathBuf::from("docs/db"));
+                assert!(html);
+            }
+            other => panic!("expected docs generate, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "docs", "set-description", "dbo.Orders.Status", ""]).command
+        {
+            CommandKind::Docs(DocsArgs {
+                command:
+                    DocsCommand::SetDescription {
+                        target,
+                        description,
+                    },
+            }) => {
+                assert_eq!(target, "dbo.Orders.Status");
+                assert!(description.is_empty());
+            }
+            other => panic!("expected docs set-description, got: {:?}", other),
+        }
+    }
 
-    use super::{
-        CommandKind, build_cli, looks_like_sql, parse_matches, rewrite_bare_sql_shorthand,
-    };
+    #[test]
+    fn lint_collects_files_and_disabled_rules() {
+        match parse_args_from([
+            "sscli",
+            "lint",
+            "-f",
+            "a.sql",
+            "--file",
+            "b.sql",
+            "--disable",
+            "cursor,select-star",
+            "--fail-on",
+            "medium",
+        ])
+        .command
+        {
+            CommandKind::Lint(cmd) => {
+                assert_eq!(
+                    cmd.files,
+                    vec![PathBuf::from("a.sql"), PathBuf::from("b.sql")]
+                );
+                assert_eq!(cmd.disable, vec!["cursor", "select-star"]);
+                assert_eq!(cmd.fail_on, "medium");
+                assert!(!cmd.stdin);
+            }
+            other => panic!("expected lint, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "lint", "--object", "dbo.usp_*"]).command {
+            CommandKind::Lint(cmd) => {
+                assert_eq!(cmd.object.as_deref(), Some("dbo.usp_*"));
+                assert_eq!(cmd.fail_on, "low");
+            }
+            other => panic!("expected lint, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "lint", "-f", "a.sql", "--disable", "nope"])
+                .is_err()
+        );
+    }
 
-    fn parse_args_from<I, T>(input: I) -> super::CliArgs
-    where
-        I: IntoIterator<Item = T>,
-        T: Into<OsString>,
-    {
-        let matches = build_cli(false)
-            .try_get_matches_from(rewrite_bare_sql_shorthand(
-                input.into_iter().map(Into::into).collect(),
-            ))
-            .expect("clap should parse input");
-        parse_matches(&matches)
+    #[test]
+    fn fmt_and_format_sql_parse_style_flags() {
+        match parse_args_from([
+            "sscli",
+            "fmt",
+            "-f",
+            "script.sql",
+            "--write",
+            "--commas",
+            "leading",
+            "--indent",
+            "2",
+        ])
+        .command
+        {
+            CommandKind::Fmt(cmd) => {
+                assert_eq!(cmd.file, Some(PathBuf::from("script.sql")));
+                assert!(cmd.write);
+                assert!(!cmd.check);
+                assert_eq!(cmd.style.commas, Some(CommaStyle::Leading));
+                assert_eq!(cmd.style.indent, Some(2));
+                assert_eq!(cmd.style.keyword_case, None);
+            }
+            other => panic!("expected fmt, got: {:?}", other),
+        }
+        match parse_args_from([
+            "sscli",
+            "sql",
+            "select 1",
+            "--format-sql",
+            "--keyword-case",
+            "lower",
+        ])
+        .command
+        {
+            CommandKind::Sql(cmd) => {
+                assert!(cmd.format_sql);
+                assert_eq!(cmd.style.keyword_case, Some(KeywordCase::Lower));
+            }
+            other => panic!("expected sql, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "fmt", "select 1", "--write"])
+                .is_err()
+        );
     }
 
     #[test]
-    fn table_data_accepts_positional_object_name() {
-        let matches = build_cli(false)
-            .try_get_matches_from(["sscli", "table-data", "equipment"])
-            .expect("clap should parse positional table-data object");
-        let args = parse_matches(&matches);
+    fn modified_since_takes_durations_and_dates() {
+        match parse_args_from(["sscli", "tables", "--modified-since", "7d"]).command {
+            CommandKind::Tables(cmd) => {
+                assert_eq!(
+                    cmd.modified_since,
+                    Some(super::ModifiedSince::Minutes(7 * 24 * 60))
+                )
+            }
+            other => panic!("expected tables command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "changes", "--since", "2024-01-01"]).command {
+            CommandKind::Changes(cmd) => assert_eq!(
+                cmd.since,
+                super::ModifiedSince::At(
+                    chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                        .expect("valid date")
+                )
+            ),
+            other => panic!("expected changes command, got: {:?}", other),
+        }
+        for argv in [
+            vec!["sscli", "changes"],
+            vec!["sscli", "changes", "--since", "2024-13-01"],
+            vec!["sscli", "tables", "--summary", "--modified-since", "1d"],
+            vec!["sscli", "procs", "--history", "p", "--modified-since", "1d"],
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn table_data_sample_modes_parse() {
+        let sample =
+            |mode: &str| match parse_args_from(["sscli", "data", "Orders", "--sample", mode])
+                .command
+            {
+                CommandKind::TableData(cmd) => cmd.sample,
+                other => panic!("expected table-data command, got: {:?}", other),
+            };
+        assert_eq!(sample("random:1000"), Some(super::Sample::Random(1000)));
+        assert_eq!(sample("last:10"), Some(super::Sample::Last(10)));
+        assert_eq!(
+            sample("stratified:Region"),
+            Some(super::Sample::Stratified {
+                column: "Region".to_string(),
+                per_value: 5
+            })
+        );
+        assert_eq!(
+            sample("stratified:Region:2"),
+            Some(super::Sample::Stratified {
+                column: "Region".to_string(),
+                per_value: 2
+            })
+        );
+        for mode in ["random:0", "random:10001", "top:5", "stratified:"] {
+            assert!(
+                build_cli(false)
+                    .try_get_matches_from(["sscli", "data", "Orders", "--sample", mode])
+                    .is_err()
+            );
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "data", "Orders", "--sample", "first:5", "-n", "5"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn table_display_options_parse() {
+        let args = parse_args_from([
+            "sscli",
+            "tables",
+            "--null-display",
+            "NULL",
+            "--booleans",
+            "yes-no",
+            "--max-cell-width",
+            "0",
+            "--max-output-chars",
+            "0",
+        ]);
+        assert_eq!(args.null_display.as_deref(), Some("NULL"));
+        assert_eq!(args.booleans, Some(BooleanStyle::YesNo));
+        assert_eq!(args.max_cell_width, Some(0));
+        assert_eq!(args.max_output_chars, Some(0));
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "tables", "--booleans", "on-off"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn binary_value_options_parse() {
+        let args = parse_args_from([
+            "sscli",
+            "sql",
+            "SELECT 1",
+            "--binary",
+            "base64",
+            "--max-value-bytes",
+            "4096",
+            "--save-blobs",
+            "./blobs",
+        ]);
+        assert_eq!(args.binary, Some(super::BinaryFormat::Base64));
+        assert_eq!(args.max_value_bytes, Some(4096));
+        match args.command {
+            CommandKind::Sql(cmd) => assert_eq!(cmd.save_blobs, Some(PathBuf::from("./blobs"))),
+            other => panic!("expected sql command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "tables", "--binary", "octal"])
+                .is_err()
+        );
+        assert!(
+            build_cli(false)
+                .try_get_matches_from([
+                    "sscli",
+                    "data",
+                    "Orders",
+                    "--follow",
+                    "Id",
+                    "--save-blobs",
+                    "./blobs"
+                ])
+                .is_err()
+        );
+    }
 
+    #[test]
+    fn table_data_to_parses_storage_tier() {
+        let args = parse_args_from([
+            "sscli",
+            "table-data",
+            "dbo.Orders",
+            "--to",
+            "s3://exports/orders.csv",
+            "--storage-tier",
+            "cool",
+        ]);
         match args.command {
             CommandKind::TableData(cmd) => {
-                assert_eq!(cmd.table.as_deref(), Some("equipment"));
+                assert_eq!(cmd.to.as_deref(), Some("s3://exports/orders.csv"));
+                assert_eq!(cmd.storage_tier.as_deref(), Some("cool"));
             }
             other => panic!("expected table-data command, got: {:?}", other),
         }
+        for argv in [
+            ["sscli", "table-data", "t", "--storage-tier", "hot"].as_slice(),
+            [
+                "sscli", "sql", "select 1", "--csv", "a.csv", "--to", "s3://b/k",
+            ]
+            .as_slice(),
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
     }
 
     #[test]
-    fn table_data_prefers_flag_over_positional_object_name() {
-        let matches = build_cli(false)
-            .try_get_matches_from([
-                "sscli",
-                "table-data",
-                "positional_name",
-                "--table",
-                "flag_name",
-            ])
-            .expect("clap should parse table-data with positional and --table");
-        let args = parse_matches(&matches);
-
+    fn table_data_as_of_parses_dates_and_times() {
+        let args = parse_args_from([
+            "sscli",
+            "table-data",
+            "dbo.Prices",
+            "--as-of",
+            "2024-02-01T06:30:00",
+        ]);
         match args.command {
             CommandKind::TableData(cmd) => {
-                assert_eq!(cmd.table.as_deref(), Some("flag_name"));
+                assert_eq!(
+                    cmd.as_of.map(|t| t.to_string()).as_deref(),
+                    Some("2024-02-01 06:30:00")
+                );
+                assert!(!cmd.history);
             }
             other => panic!("expected table-data command, got: {:?}", other),
         }
+        assert_eq!(
+            parse_as_of("2024-02-01").map(|t| t.to_string()),
+            Ok("2024-02-01 00:00:00".to_string())
+        );
+        assert!(parse_as_of("yesterday").is_err());
+        assert!(
+            build_cli(false)
+                .try_get_matches_from([
+                    "sscli",
+                    "table-data",
+                    "t",
+                    "--as-of",
+                    "2024-02-01",
+                    "--history"
+                ])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sql_out_collects_every_file() {
+        let args = parse_args_from([
+            "sscli",
+            "sql",
+            "select 1",
+            "--out",
+            "results.json",
+            "--out",
+            "results.csv",
+        ]);
+        match args.command {
+            CommandKind::Sql(cmd) => assert_eq!(
+                cmd.out,
+                vec![PathBuf::from("results.json"), PathBuf::from("results.csv")]
+            ),
+            other => panic!("expected sql command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn verify_parses_table_patterns_and_chunking() {
+        let args = parse_args_from([
+            "sscli",
+            "verify",
+            "--tables",
+            "dbo.*,sales.Orders",
+            "--target-profile",
+            "prod",
+            "--chunk-size",
+            "10000",
+        ]);
+        match args.command {
+            CommandKind::Verify(cmd) => {
+                assert_eq!(cmd.target, "prod");
+                assert_eq!(cmd.tables, vec!["dbo.*", "sales.Orders"]);
+                assert!(cmd.checksum);
+                assert_eq!(cmd.chunk_size, Some(10000));
+            }
+            other => panic!("expected verify command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn assert_takes_exactly_one_expectation() {
+        let sql = "SELECT COUNT(*) FROM dbo.Orders WHERE Status IS NULL";
+        match parse_args_from(["sscli", "assert", "--sql", sql, "--expect", "0"]).command {
+            CommandKind::Assert(cmd) => {
+                assert_eq!(cmd.sql.as_deref(), Some(sql));
+                assert_eq!(cmd.expect, Expectation::Equals("0".to_string()));
+            }
+            other => panic!("expected assert command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "assert", "--sql", sql, "--expect-lt", "-1.5"]).command {
+            CommandKind::Assert(cmd) => {
+                assert_eq!(cmd.expect, Expectation::LessThan("-1.5".to_string()))
+            }
+            other => panic!("expected assert command, got: {:?}", other),
+        }
+
+        let cli = build_cli(true);
+        for argv in [
+            vec!["sscli", "assert", "--sql", sql],
+            vec![
+                "sscli",
+                "assert",
+                "--sql",
+                sql,
+                "--expect",
+                "0",
+                "--expect-empty",
+            ],
+            vec!["sscli", "assert", "--sql", sql, "--expect-gt", "many"],
+            vec!["sscli", "assert", "--expect-empty"],
+        ] {
+            assert!(cli.clone().try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn migrate_parses_subcommands_and_directory() {
+        match parse_args_from(["sscli", "migrate", "new", "add_orders_index", "--dir", "db"])
+            .command
+        {
+            CommandKind::Migrate(cmd) => {
+                assert_eq!(cmd.dir, Some(PathBuf::from("db")));
+                assert_eq!(
+                    cmd.command,
+                    MigrateCommand::New {
+                        name: "add_orders_index".to_string()
+                    }
+                );
+            }
+            other => panic!("expected migrate command, got: {:?}", other),
+        }
+        match parse_args_from([
+            "sscli",
+            "--allow-write",
+            "migrate",
+            "up",
+            "--to",
+            "20260301",
+        ])
+        .command
+        {
+            CommandKind::Migrate(cmd) => {
+                assert_eq!(cmd.dir, None);
+                assert_eq!(
+                    cmd.command,
+                    MigrateCommand::Up(MigrateUpArgs {
+                        dry_run: false,
+                        to: Some("20260301".to_string()),
+                    })
+                );
+            }
+            other => panic!("expected migrate command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn seed_takes_a_subcommand_and_files() {
+        match parse_args_from([
+            "sscli",
+            "seed",
+            "diff",
+            "seeds/status.yaml",
+            "seeds/types.yml",
+        ])
+        .command
+        {
+            CommandKind::Seed(cmd) => {
+                assert_eq!(cmd.command, SeedCommand::Diff);
+                assert_eq!(
+                    cmd.files,
+                    vec![
+                        PathBuf::from("seeds/status.yaml"),
+                        PathBuf::from("seeds/types.yml")
+                    ]
+                );
+            }
+            other => panic!("expected seed command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(true)
+                .try_get_matches_from(["sscli", "seed", "apply"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn serve_reads_port_and_bind() {
+        match parse_args_from(["sscli", "serve", "--port", "9090", "--bind", "0.0.0.0"]).command {
+            CommandKind::Serve(cmd) => {
+                assert_eq!(cmd.port, Some(9090));
+                assert_eq!(cmd.bind.as_deref(), Some("0.0.0.0"));
+            }
+            other => panic!("expected serve command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "serve"]).command {
+            CommandKind::Serve(cmd) => assert_eq!(cmd.port, None),
+            other => panic!("expected serve command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn permissions_check_accepts_command_filter() {
+        for argv in [
+            vec!["sscli", "permissions", "check", "--command", "query-stats"],
+            vec!["sscli", "perms", "--command", "query-stats"],
+        ] {
+            match parse_args_from(argv).command {
+                CommandKind::Permissions(cmd) => {
+                    assert_eq!(cmd.command.as_deref(), Some("query-stats"));
+                }
+                other => panic!("expected permissions command, got: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn tables_describe_concurrency_defaults_and_bounds() {
+        match parse_args_from(["sscli", "tables", "--describe"]).command {
+            CommandKind::Tables(cmd) => {
+                assert_eq!(cmd.concurrency, crate::db::pool::DEFAULT_CONCURRENCY)
+            }
+            other => panic!("expected tables command, got: {:?}", other),
+        }
+        match parse_args_from(["sscli", "tables", "--describe", "--concurrency", "8"]).command {
+            CommandKind::Tables(cmd) => assert_eq!(cmd.concurrency, 8),
+            other => panic!("expected tables command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "tables", "--describe", "--concurrency", "99"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn compare_query_timeout_parses_duration() {
+        let args = parse_args_from([
+            "sscli",
+            "compare",
+            "--target",
+            "prod",
+            "--query-timeout",
+            "90s",
+        ]);
+        match args.command {
+            CommandKind::Compare(cmd) => assert_eq!(cmd.query_timeout_ms, Some(90_000)),
+            other => panic!("expected compare command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn compare_filters_collect_categories_and_patterns() {
+        let args = parse_args_from([
+            "sscli",
+            "compare",
+            "--target",
+            "prod",
+            "--only",
+            "modules,tables",
+            "--exclude",
+            "*_backup",
+            "--exclude",
+            "/^audit\\./",
+        ]);
+        match args.command {
+            CommandKind::Compare(cmd) => {
+                assert_eq!(cmd.only, vec!["modules", "tables"]);
+                assert_eq!(cmd.exclude, vec!["*_backup", "/^audit\\./"]);
+                assert!(cmd.include.is_empty());
+            }
+            other => panic!("expected compare command, got: {:?}", other),
+        }
+        assert!(
+            build_cli(false)
+                .try_get_matches_from(["sscli", "compare", "--target", "p", "--only", "procs"])
+                .is_err()
+        );
     }
 
     #[test]
@@ -1637,6 +6032,76 @@ mod tests {
         assert!(matches!(args.command, CommandKind::Status(_)));
     }
 
+    #[test]
+    fn config_profiles_maps_to_profiles_command() {
+        let args = parse_args_from([
+            "sscli",
+            "config",
+            "profiles",
+            "add",
+            "prod",
+            "--server",
+            "db01",
+            "--default-schemas",
+            "dbo,sales",
+        ]);
+        match args.command {
+            CommandKind::Profiles(profiles) => match profiles.command {
+                super::ProfileCommand::Add(edit) => {
+                    assert_eq!(edit.name, "prod");
+                    assert_eq!(
+                        edit.default_schemas,
+                        Some(vec!["dbo".to_string(), "sales".to_string()])
+                    );
+                }
+                other => panic!("unexpected profile command: {:?}", other),
+            },
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert_eq!(args.server.as_deref(), Some("db01"));
+    }
+
+    #[test]
+    fn errorlog_parses_lookback_durations() {
+        let args = parse_args_from(["sscli", "errorlog", "--last", "2h", "--archive", "3"]);
+        match args.command {
+            CommandKind::Errorlog(errorlog) => {
+                assert_eq!(errorlog.last_minutes, Some(120));
+                assert_eq!(errorlog.archive, 3);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert_eq!(super::parse_lookback_minutes("45"), Ok(45));
+        assert_eq!(super::parse_lookback_minutes("1d"), Ok(1440));
+        assert!(super::parse_lookback_minutes("soon").is_err());
+    }
+
+    #[test]
+    fn backups_check_takes_age_thresholds() {
+        let args = parse_args_from([
+            "sscli",
+            "backups",
+            "--check",
+            "--max-full-age",
+            "24h",
+            "--max-log-age",
+            "15m",
+        ]);
+        match args.command {
+            CommandKind::Backups(backups) => {
+                assert!(backups.check);
+                assert_eq!(backups.max_full_age, Some(1440));
+                assert_eq!(backups.max_log_age, Some(15));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        let cli = build_cli(true);
+        assert!(
+            cli.try_get_matches_from(["sscli", "backups", "--max-log-age", "15m"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn sql_keyword_detection_is_case_insensitive() {
         assert!(looks_like_sql("select"));
@@ -1710,4 +6175,141 @@ mod tests {
             other => panic!("expected sql command, got: {:?}", other),
         }
     }
+
+    #[test]
+    fn partitions_scripts_need_a_table() {
+        let args = parse_args_from([
+            "sscli",
+            "partitions",
+            "--table",
+            "dbo.Events",
+            "--script-split",
+            "2025-01-01",
+            "--filegroup",
+            "FG2025",
+        ]);
+        match args.command {
+            CommandKind::Partitions(cmd) => {
+                assert_eq!(cmd.table.as_deref(), Some("dbo.Events"));
+                assert_eq!(cmd.script_split.as_deref(), Some("2025-01-01"));
+                assert_eq!(cmd.filegroup.as_deref(), Some("FG2025"));
+            }
+            other => panic!("expected partitions command, got: {:?}", other),
+        }
+
+        for argv in [
+            vec!["sscli", "partitions", "--script-merge", "10"],
+            vec!["sscli", "partitions", "--table", "t", "--filegroup", "FG"],
+            vec![
+                "sscli",
+                "partitions",
+                "--table",
+                "t",
+                "--script-split",
+                "1",
+                "--script-merge",
+                "2",
+            ],
+        ] {
+            assert!(build_cli(false).try_get_matches_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn config_connection_string_redacts_by_default() {
+        let args = parse_args_from(["sscli", "config", "connection-string", "--format", "odbc"]);
+        match args.command {
+            CommandKind::Config(cmd) => {
+                let conn = cmd.connection_string.expect("connection-string");
+                assert_eq!(conn.format.as_deref(), Some("odbc"));
+                assert!(conn.redact);
+            }
+            other => panic!("expected config command, got: {:?}", other),
+        }
+
+        let args = parse_args_from(["sscli", "config", "connection-string", "--no-redact"]);
+        match args.command {
+            CommandKind::Config(cmd) => assert!(!cmd.connection_string.unwrap().redact),
+            other => panic!("expected config command, got: {:?}", other),
+        }
+        assert_eq!(
+            parse_args_from(["sscli", "config"]).command,
+            CommandKind::Config(super::ConfigArgs::default())
+        );
+    }
+
+    #[test]
+    fn sql_dash_reads_stdin() {
+        for argv in [
+            vec!["sscli", "sql", "-"],
+            vec!["sscli", "sql", "--file", "-"],
+            vec!["sscli", "sql", "--stdin"],
+        ] {
+            match parse_args_from(argv).command {
+                CommandKind::Sql(cmd) => {
+                    assert!(cmd.stdin);
+                    assert_eq!(cmd.sql, None);
+                    assert_eq!(cmd.file, None);
+                }
+                other => panic!("expected sql command, got: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn databases_foreach_takes_sql_and_no_paging() {
+        let args = parse_args_from([
+            "sscli",
+            "databases",
+            "--name",
+            "Sales%",
+            "--foreach",
+            "SELECT COUNT(*) AS tables FROM sys.tables",
+        ]);
+        match args.command {
+            CommandKind::Databases(cmd) => {
+                assert_eq!(
+                    cmd.foreach.as_deref(),
+                    Some("SELECT COUNT(*) AS tables FROM sys.tables")
+                );
+                assert_eq!(cmd.name.as_deref(), Some("Sales%"));
+            }
+            other => panic!("expected databases command, got: {:?}", other),
+        }
+        let argv = [
+            "sscli",
+            "databases",
+            "--foreach",
+            "SELECT 1",
+            "--limit",
+            "5",
+        ];
+        assert!(build_cli(false).try_get_matches_from(argv).is_err());
+    }
+
+    #[test]
+    fn databases_create_script_takes_options() {
+        let args = parse_args_from([
+            "sscli",
+            "databases",
+            "--create-script",
+            "Sales",
+            "--include-security",
+            "--include-data",
+            "ref.Country,ref.Currency",
+            "--out",
+            "sales.sql",
+        ]);
+        match args.command {
+            CommandKind::Databases(cmd) => {
+                assert_eq!(cmd.create_script.as_deref(), Some("Sales"));
+                assert!(cmd.include_security && !cmd.include_files);
+                assert_eq!(cmd.include_data, vec!["ref.Country", "ref.Currency"]);
+                assert_eq!(cmd.out, Some(PathBuf::from("sales.sql")));
+            }
+            other => panic!("expected databases command, got: {:?}", other),
+        }
+        let argv = ["sscli", "databases", "--include-files"];
+        assert!(build_cli(false).try_get_matches_from(argv).is_err());
+    }
 }
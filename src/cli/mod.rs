@@ -1,12 +1,29 @@
 mod args;
 
 pub use args::{
-    BackupsArgs, CliArgs, ColumnsArgs, CommandKind, CompareArgs, CompletionsArgs, ConfigArgs,
-    DatabasesArgs, DescribeArgs, ForeignKeysArgs, IndexesArgs, InitArgs, IntegrationCommand,
-    IntegrationInstallArgs, IntegrationsArgs, OutputFlags, QueryStatsArgs, SessionsArgs, SqlArgs,
-    StatusArgs, StoredProcsArgs, TableDataArgs, TablesArgs, UpdateArgs, build_cli,
+    AgArgs, AssembliesArgs, AssertArgs, BackupsArgs, CdcArgs, ChangesArgs, CliArgs, ColumnsArgs,
+    CommandKind, CompareArgs, CompletionsArgs, ConfigArgs, ConnectionStringArgs, DatabasesArgs,
+    DescribeArgs, DocsArgs, DocsCommand, ErrorlogArgs, Expectation, FkCheckArgs, FmtArgs,
+    ForeignKeysArgs, FormatStyleArgs, GrantScriptArgs, IndexesArgs, InitArgs, IntegrationCommand,
+    IntegrationInstallArgs, IntegrationVscodeArgs, IntegrationsArgs, LintArgs, MigrateArgs,
+    MigrateCommand, MigrateUpArgs, ModifiedSince, OutputFlags, Paginate, PartitionsArgs,
+    PermissionsArgs, ProfileArgs, ProfileCommand, ProfileEditArgs, ProfileNameArgs,
+    ProfileTestArgs, ProfilesArgs, QueryStatsArgs, ReplicationArgs, Sample, SchemaApplyArgs,
+    SchemaExportArgs, SeedArgs, SeedCommand, ServeArgs, SessionsArgs, SqlArgs, StatusArgs,
+    StoredProcsArgs, TableDataArgs, TablesArgs, TraceArgs, TraceCommand, TraceStartArgs,
+    TraceStopArgs, TraceTailArgs, UpdateArgs, VerifyArgs, build_cli, cli_tree,
 };
 
 pub fn parse() -> CliArgs {
     args::parse_args()
 }
+
+/// Parse `argv` (program name first) as if it were the command line.
+#[cfg(test)]
+pub(crate) fn parse_from<I, T>(argv: I) -> CliArgs
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString>,
+{
+    args::parse_args_from(argv.into_iter().map(Into::into).collect())
+}
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{AgArgs, CliArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{ResultSet, Value};
+use crate::error::ExitCode;
+use crate::output::{TableOptions, json as json_out, table};
+
+const GROUPS_SQL: &str = r#"
+SELECT
+    ag.name AS availabilityGroup,
+    ags.primary_replica AS primaryReplica,
+    ags.synchronization_health_desc AS synchronizationHealth,
+    ags.primary_recovery_health_desc AS primaryRecoveryHealth,
+    ag.automated_backup_preference_desc AS backupPreference
+FROM sys.availability_groups ag
+LEFT JOIN sys.dm_hadr_availability_group_states ags ON ags.group_id = ag.group_id
+WHERE (@P1 IS NULL OR ag.name = @P1)
+ORDER BY ag.name;
+"#;
+
+const REPLICAS_SQL: &str = r#"
+SELECT
+    ag.name AS availabilityGroup,
+    ar.replica_server_name AS replica,
+    ars.role_desc AS role,
+    ar.availability_mode_desc AS availabilityMode,
+    ar.failover_mode_desc AS failoverMode,
+    ars.connected_state_desc AS connectedState,
+    ars.operational_state_desc AS operationalState,
+    ars.synchronization_health_desc AS synchronizationHealth,
+    ars.last_connect_error_description AS lastConnectError
+FROM sys.availability_replicas ar
+INNER JOIN sys.availability_groups ag ON ag.group_id = ar.group_id
+LEFT JOIN sys.dm_hadr_availability_replica_states ars ON ars.replica_id = ar.replica_id
+WHERE (@P1 IS NULL OR ag.name = @P1)
+ORDER BY ag.name, ar.replica_server_name;
+"#;
+
+const DATABASES_SQL: &str = r#"
+SELECT
+    ag.name AS availabilityGroup,
+    ar.replica_server_name AS replica,
+    adc.database_name AS [database],
+    drs.synchronization_state_desc AS synchronizationState,
+    drs.synchronization_health_desc AS synchronizationHealth,
+    drs.is_suspended AS isSuspended,
+    drs.suspend_reason_desc AS suspendReason,
+    drs.log_send_queue_size AS logSendQueueKb,
+    drs.redo_queue_size AS redoQueueKb,
+    drs.redo_rate AS redoRateKbPerSec,
+    drs.last_commit_time AS lastCommitTime,
+    drs.last_hardened_time AS lastHardenedTime
+FROM sys.dm_hadr_database_replica_states drs
+INNER JOIN sys.availability_replicas ar ON ar.replica_id = drs.replica_id
+INNER JOIN sys.availability_groups ag ON ag.group_id = drs.group_id
+INNER JOIN sys.availability_databases_cluster adc
+    ON adc.group_id = drs.group_id AND adc.group_database_id = drs.group_database_id
+WHERE (@P1 IS NULL OR ag.name = @P1)
+ORDER BY ag.name, ar.replica_server_name, adc.database_name;
+"#;
+
+struct AgReport {
+    hadr_enabled: bool,
+    groups: ResultSet,
+    replicas: ResultSet,
+    databases: ResultSet,
+}
+
+pub fn run(args: &CliArgs, cmd: &AgArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let group = cmd.group.clone();
+
+    let report = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let enabled_sql =
+            "SELECT CAST(ISNULL(SERVERPROPERTY('IsHadrEnabled'), 0) AS int) AS hadrEnabled";
+        let enabled = executor::run_query(Query::new(enabled_sql), &mut client)
+            .await?
+            .first()
+            .and_then(|rs| rs.rows.first())
+            .and_then(|row| row.first())
+            .is_some_and(|value| matches!(value, Value::Int(1)));
+        if !enabled {
+            return Ok::<_, anyhow::Error>(AgReport {
+                hadr_enabled: false,
+                groups: ResultSet::default(),
+                replicas: ResultSet::default(),
+                databases: ResultSet::default(),
+            });
+        }
+
+        let mut sets = Vec::new();
+        for sql in [GROUPS_SQL, REPLICAS_SQL, DATABASES_SQL] {
+            let mut query = Query::new(sql);
+            query.bind(group.as_deref());
+            let rs = executor::run_query(query, &mut client)
+                .await?
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            sets.push(rs);
+        }
+        let databases = sets.pop().unwrap_or_default();
+        let replicas = sets.pop().unwrap_or_default();
+        let groups = sets.pop().unwrap_or_default();
+        Ok(AgReport {
+            hadr_enabled: true,
+            groups,
+            replicas,
+            databases,
+        })
+    })?;
+
+    let replicas = json_out::result_set_rows_to_objects(&report.replicas);
+    let databases = json_out::result_set_rows_to_objects(&report.databases);
+    let problems = collect_problems(&replicas, &databases);
+    let status = if problems.is_empty() {
+        "ok"
+    } else {
+        "unhealthy"
+    };
+
+    if matches!(format, OutputFormat::Json) {
+        let groups: Vec<serde_json::Value> = json_out::result_set_rows_to_objects(&report.groups)
+            .into_iter()
+            .map(|mut group| {
+                let name = group["availabilityGroup"].clone();
+                group["replicas"] = replicas
+                    .iter()
+                    .filter(|r| r["availabilityGroup"] == name)
+                    .map(|replica| {
+                        let mut replica = replica.clone();
+                        replica["databases"] = databases
+                            .iter()
+                            .filter(|d| {
+                                d["availabilityGroup"] == name && d["replica"] == replica["replica"]
+                            })
+                            .cloned()
+                            .collect();
+                        replica
+                    })
+                    .collect();
+                group
+            })
+            .collect();
+        let payload = json!({
+            "hadrEnabled": report.hadr_enabled,
+            "status": status,
+            "groups": groups,
+            "problems": problems,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if !report.hadr_enabled {
+            println!("Always On availability groups are not enabled on this instance.");
+            return Ok(());
+        }
+        if report.groups.rows.is_empty() {
+            println!("No availability groups found.");
+            return Ok(());
+        }
+        for (title, rs) in [
+            ("Availability Groups", &report.groups),
+            ("Replicas", &report.replicas),
+            ("Databases", &report.databases),
+        ] {
+            if rs.rows.is_empty() {
+                continue;
+            }
+            println!("{}", title);
+            let result = table::render_result_set_table(rs, format, &TableOptions::default());
+            println!("{}", result.output);
+        }
+        for problem in &problems {
+            eprintln!("Unhealthy: {}", problem);
+        }
+    }
+
+    if !problems.is_empty() {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+/// Describe every replica or database that needs attention.
+///
+/// Secondaries only see their own replica state, so rows without state are not counted.
+fn collect_problems(
+    replicas: &[serde_json::Value],
+    databases: &[serde_json::Value],
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for replica in replicas {
+        let Some(health) = replica["synchronizationHealth"].as_str() else {
+            continue;
+        };
+        let connected = replica["connectedState"].as_str().unwrap_or("UNKNOWN");
+        if health != "HEALTHY" || connected == "DISCONNECTED" {
+            problems.push(format!(
+                "{} replica {} is {} ({})",
+                replica["availabilityGroup"].as_str().unwrap_or("?"),
+                replica["replica"].as_str().unwrap_or("?"),
+                health,
+                connected
+            ));
+        }
+    }
+    for database in databases {
+        if database["isSuspended"] == true {
+            problems.push(format!(
+                "{} database {} on {} is suspended ({})",
+                database["availabilityGroup"].as_str().unwrap_or("?"),
+                database["database"].as_str().unwrap_or("?"),
+                database["replica"].as_str().unwrap_or("?"),
+                database["suspendReason"]
+                    .as_str()
+                    .unwrap_or("unknown reason")
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_unhealthy_replicas_and_suspended_databases() {
+        let replicas = vec![
+            json!({"availabilityGroup": "ag1", "replica": "sql01",
+                   "synchronizationHealth": "HEALTHY", "connectedState": "CONNECTED"}),
+            json!({"availabilityGroup": "ag1", "replica": "sql02",
+                   "synchronizationHealth": "NOT_HEALTHY", "connectedState": "DISCONNECTED"}),
+        ];
+        let databases = vec![json!({"availabilityGroup": "ag1", "replica": "sql01",
+            "database": "app", "isSuspended": true, "suspendReason": "SUSPEND_FROM_USER"})];
+        let problems = collect_problems(&replicas, &databases);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("sql02"));
+        assert!(problems[1].contains("suspended"));
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{AssembliesArgs, CliArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+pub fn run(args: &CliArgs, cmd: &AssembliesArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let name = cmd.name.clone();
+    let include_system = cmd.include_system;
+
+    let (assemblies, modules) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let assemblies_sql = r#"
+SELECT
+    a.name AS name,
+    a.permission_set_desc AS permissionSet,
+    a.clr_name AS clrName,
+    a.is_user_defined AS isUserDefined,
+    a.is_visible AS isVisible,
+    p.name AS owner,
+    CONVERT(varchar(19), a.create_date, 120) AS createdAt,
+    CONVERT(varchar(19), a.modify_date, 120) AS modifiedAt,
+    (SELECT COUNT(*) FROM sys.assembly_modules am WHERE am.assembly_id = a.assembly_id) AS moduleCount
+FROM sys.assemblies a
+LEFT JOIN sys.database_principals p ON p.principal_id = a.principal_id
+WHERE (@P1 = 1 OR a.is_user_defined = 1)
+  AND (@P2 IS NULL OR a.name LIKE @P2)
+ORDER BY a.name;
+"#;
+        let mut query = Query::new(assemblies_sql);
+        query.bind(if include_system { 1i32 } else { 0i32 });
+        query.bind(name.as_deref());
+        let assemblies = executor::run_query(query, &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        let modules_sql = r#"
+SELECT
+    a.name AS assembly,
+    s.name AS [schema],
+    o.name AS name,
+    o.type_desc AS objectType,
+    am.assembly_class AS assemblyClass,
+    am.assembly_method AS assemblyMethod,
+    am.execute_as_principal_id AS executeAsPrincipalId
+FROM sys.assembly_modules am
+INNER JOIN sys.assemblies a ON a.assembly_id = am.assembly_id
+INNER JOIN sys.objects o ON o.object_id = am.object_id
+INNER JOIN sys.schemas s ON s.schema_id = o.schema_id
+WHERE (@P1 = 1 OR a.is_user_defined = 1)
+  AND (@P2 IS NULL OR a.name LIKE @P2)
+ORDER BY a.name, s.name, o.name;
+"#;
+        let mut query = Query::new(modules_sql);
+        query.bind(if include_system { 1i32 } else { 0i32 });
+        query.bind(name.as_deref());
+        let modules = executor::run_query(query, &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        Ok::<_, anyhow::Error>((assemblies, modules))
+    })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let module_objects = json_out::result_set_rows_to_objects(&modules);
+        let items: Vec<serde_json::Value> = json_out::result_set_rows_to_objects(&assemblies)
+            .into_iter()
+            .map(|mut assembly| {
+                let assembly_name = assembly["name"].clone();
+                assembly["modules"] = serde_json::Value::Array(
+                    module_objects
+                        .iter()
+                        .filter(|m| m["assembly"] == assembly_name)
+                        .cloned()
+                        .collect(),
+                );
+                assembly
+            })
+            .collect();
+        let payload = json!({
+            "count": items.len(),
+            "assemblies": items,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if assemblies.rows.is_empty() {
+        println!("No CLR assemblies found.");
+        return Ok(());
+    }
+
+    println!("Assemblies");
+    let result = table::render_result_set_table(&assemblies, format, &TableOptions::default());
+    println!("{}", result.output);
+
+    if !modules.rows.is_empty() {
+        println!("Modules");
+        let result = table::render_result_set_table(&modules, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+
+    warn_unsafe(&assemblies);
+
+    Ok(())
+}
+
+/// Point out assemblies that run outside the SAFE sandbox; these are the usual audit findings.
+fn warn_unsafe(assemblies: &ResultSet) {
+    let Some(idx) = assemblies
+        .columns
+        .iter()
+        .position(|c| c.name == "permissionSet")
+    else {
+        return;
+    };
+    let flagged = assemblies
+        .rows
+        .iter()
+        .filter(|row| match row.get(idx) {
+            Some(Value::Text(s)) => !s.eq_ignore_ascii_case("SAFE_ACCESS"),
+            _ => false,
+        })
+        .count();
+    if flagged > 0 {
+        eprintln!(
+            "Note: {} assembl{} use EXTERNAL_ACCESS or UNSAFE permission sets.",
+            flagged,
+            if flagged == 1 { "y" } else { "ies" }
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! `assert`: run one query and exit 3 unless its result meets the expectation, for CI checks
+//! that would otherwise wrap `sql --json` in a shell and jq.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{AssertArgs, CliArgs, Expectation};
+use crate::commands::{common, confirm, sql_utils};
+use crate::config::OutputFormat;
+use crate::db::types::{ResultSet, Value};
+use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::{TableOptions, json as json_out, table};
+use crate::safety;
+
+/// Rows of an unexpectedly non-empty result kept in the failure output.
+const SAMPLE_ROWS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Outcome {
+    passed: bool,
+    /// First column of the first row; `None` when there were no rows.
+    actual: Option<Value>,
+    row_count: usize,
+    message: String,
+}
+
+pub fn run(args: &CliArgs, cmd: &AssertArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let sql_text = match (&cmd.sql, &cmd.file) {
+        (Some(text), _) => text.clone(),
+        (None, Some(path)) => fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        (None, None) => {
+            return Err(AppError::new(ErrorKind::Config, "Provide --sql or --file").into());
+        }
+    };
+    if !args.allow_write {
+        safety::validate_read_only(&sql_text)
+            .map_err(|err| AppError::new(ErrorKind::Safety, err.to_string()))?;
+    } else if safety::validate_read_only(&sql_text).is_err() {
+        confirm::confirm_write(args, &resolved, "run an assertion query that may write")?;
+    }
+    let params = sql_utils::load_params(&cmd.params, None)
+        .map_err(|err| AppError::new(ErrorKind::Query, format!("{:#}", err)))?;
+    let sql = sql_utils::with_table_params(
+        &sql_utils::replace_named_params(&sql_text, &params, 1),
+        &params,
+        1,
+    );
+
+    let result = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(sql.clone());
+        sql_utils::bind_params(&mut query, &params);
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+
+    let outcome = evaluate(&cmd.expect, &result);
+    let (operator, expected) = describe(&cmd.expect);
+    let show_rows = !outcome.passed && cmd.expect == Expectation::Empty;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "name": cmd.name,
+            "status": if outcome.passed { "pass" } else { "fail" },
+            "operator": operator,
+            "expected": expected,
+            "actual": outcome.actual,
+            "rowCount": outcome.row_count,
+            "message": outcome.message,
+            "sql": sql,
+            "sample": show_rows.then(|| json_out::result_set_to_json_sampled(
+                &result,
+                SAMPLE_ROWS,
+                resolved.settings.output.max_value_bytes,
+            )),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        let label = cmd
+            .name
+            .as_deref()
+            .map(|name| format!(" {}", name))
+            .unwrap_or_default();
+        let verdict = if outcome.passed { "PASS" } else { "FAIL" };
+        println!("{}{}: {}", verdict, label, outcome.message);
+        if show_rows {
+            let mut sample = result.clone();
+            sample.rows.truncate(SAMPLE_ROWS);
+            let mut options = TableOptions::truncated();
+            options.max_value_bytes = resolved.settings.output.max_value_bytes;
+            let rendered = table::render_result_set_table(&sample, format, &options);
+            println!("{}", rendered.output);
+        }
+    }
+
+    if !outcome.passed {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+/// Operator name and expected value as reported in JSON.
+fn describe(expect: &Expectation) -> (&'static str, Option<&str>) {
+    match expect {
+        Expectation::Equals(value) => ("eq", Some(value)),
+        Expectation::LessThan(bound) => ("lt", Some(bound)),
+        Expectation::GreaterThan(bound) => ("gt", Some(bound)),
+        Expectation::Empty => ("empty", None),
+    }
+}
+
+fn evaluate(expect: &Expectation, result: &ResultSet) -> Outcome {
+    let row_count = result.rows.len();
+    let actual = result.rows.first().and_then(|row| row.first()).cloned();
+    let outcome = |passed: bool, message: String| Outcome {
+        passed,
+        actual: actual.clone(),
+        row_count,
+        message,
+    };
+
+    if *expect == Expectation::Empty {
+        return if row_count == 0 {
+            outcome(true, "no rows".to_string())
+        } else {
+            outcome(
+                false,
+                format!(
+                    "expected no rows, got {} row{}",
+                    row_count,
+                    if row_count == 1 { "" } else { "s" }
+                ),
+            )
+        };
+    }
+    let Some(value) = &actual else {
+        return outcome(
+            false,
+            "expected a value, but the query returned no rows".to_string(),
+        );
+    };
+    let shown = display(value);
+    match expect {
+        Expectation::Equals(expected) => {
+            let passed = match (value, numeric(value), expected.trim().parse::<f64>().ok()) {
+                (Value::Null, _, _) => expected.eq_ignore_ascii_case("null"),
+                (_, Some(actual), Some(expected)) => actual == expected,
+                _ => value.as_csv() == *expected,
+            };
+            if passed {
+                outcome(true, format!("{} = {}", shown, expected))
+            } else {
+                outcome(false, format!("expected {}, got {}", expected, shown))
+            }
+        }
+        Expectation::LessThan(bound) | Expectation::GreaterThan(bound) => {
+            let less = matches!(expect, Expectation::LessThan(_));
+            let symbol = if less { "<" } else { ">" };
+            let limit = bound.parse::<f64>().unwrap_or(f64::NAN);
+            match numeric(value) {
+                Some(number) if (less && number < limit) || (!less && number > limit) => {
+                    outcome(true, format!("{} {} {}", shown, symbol, bound))
+                }
+                Some(_) => outcome(
+                    false,
+                    format!("expected a value {} {}, got {}", symbol, bound, shown),
+                ),
+                None => outcome(
+                    false,
+                    format!("expected a number {} {}, got {}", symbol, bound, shown),
+                ),
+            }
+        }
+        Expectation::Empty => unreachable!("handled above"),
+    }
+}
+
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        other => other.as_f64(),
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Text(text) if numeric(value).is_none() => format!("'{}'", text),
+        other => other.as_csv(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn scalar(values: &[Value]) -> ResultSet {
+        ResultSet {
+            columns: vec![Column {
+                name: "value".to_string(),
+                data_type: None,
+            }],
+            rows: values.iter().map(|value| vec![value.clone()]).collect(),
+        }
+    }
+
+    #[test]
+    fn compares_the_first_value() {
+        let zero = scalar(&[Value::Int(0)]);
+        let decimal = scalar(&[Value::Text("12.50".to_string())]);
+        let eq = |value: &str| Expectation::Equals(value.to_string());
+
+        assert!(evaluate(&eq("0"), &zero).passed);
+        assert!(evaluate(&eq("12.5"), &decimal).passed);
+        let failed = evaluate(&eq("0"), &decimal);
+        assert!(!failed.passed);
+        assert_eq!(failed.message, "expected 0, got 12.50");
+        assert!(evaluate(&eq("NULL"), &scalar(&[Value::Null])).passed);
+        assert!(evaluate(&eq("open"), &scalar(&[Value::Text("open".to_string())])).passed);
+
+        assert!(evaluate(&Expectation::LessThan("13".to_string()), &decimal).passed);
+        assert!(!evaluate(&Expectation::GreaterThan("13".to_string()), &decimal).passed);
+        let text = evaluate(
+            &Expectation::GreaterThan("0".to_string()),
+            &scalar(&[Value::Text("n/a".to_string())]),
+        );
+        assert_eq!(text.message, "expected a number > 0, got 'n/a'");
+
+        let none = evaluate(&eq("0"), &scalar(&[]));
+        assert!(!none.passed && none.actual.is_none());
+    }
+
+    #[test]
+    fn empty_expectation_counts_rows() {
+        assert!(evaluate(&Expectation::Empty, &scalar(&[])).passed);
+        let outcome = evaluate(
+            &Expectation::Empty,
+            &scalar(&[Value::Int(1), Value::Int(2)]),
+        );
+        assert!(!outcome.passed);
+        assert_eq!(outcome.row_count, 2);
+        assert_eq!(outcome.message, "expected no rows, got 2 rows");
+    }
+}
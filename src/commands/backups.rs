@@ -1,38 +1,32 @@
-use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
 use serde_json::json;
 use tiberius::Query;
 
 use crate::cli::{BackupsArgs, CliArgs};
 use crate::commands::common;
-use crate::config::OutputFormat;
+use crate::config::{OutputFormat, ResolvedConfig};
 use crate::db::client;
 use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::remote::ExportTarget;
 use crate::output::{TableOptions, json as json_out, table};
 
 const LIMIT_DEFAULT: u64 = 20;
 const LIMIT_MAX: u64 = 200;
 
-pub fn run(args: &CliArgs, cmd: &BackupsArgs) -> Result<()> {
-    let resolved = common::load_config(args)?;
-    let format = common::output_format(args, &resolved);
-
-    let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
-    let since_days = cmd.since.unwrap_or(7);
-    let backup_type = cmd.backup_type.clone().unwrap_or_else(|| "all".to_string());
-    let backup_type = backup_type.to_lowercase();
+/// Separator between stripe/mirror device names in the aggregated `devices` column.
+const DEVICE_SEPARATOR: char = '\n';
 
-    let (type_d, type_i, type_l) = match backup_type.as_str() {
-        "full" => (Some("D"), None, None),
-        "diff" => (None, Some("I"), None),
-        "log" => (None, None, Some("L")),
-        _ => (Some("D"), Some("I"), Some("L")),
-    };
-
-    let database = cmd.database.clone();
-
-    let result_set = tokio::runtime::Runtime::new()?.block_on(async {
-        let mut client = client::connect(&resolved.connection).await?;
-        let sql = r#"
+/// One row per backup set; stripes and mirrors are folded into `devices`.
+///
+/// Encryption columns need SQL Server 2014 or later.
+const BACKUPS_SQL: &str = r#"
 SELECT TOP (@P1)
     bs.database_name AS databaseName,
     bs.backup_start_date AS backupStart,
@@ -44,9 +38,36 @@ SELECT TOP (@P1)
         ELSE bs.type
     END AS backupType,
     bs.backup_size AS backupSize,
-    bmf.physical_device_name AS device
+    bs.compressed_backup_size AS compressedSize,
+    CAST(bs.backup_size / NULLIF(bs.compressed_backup_size, 0) AS decimal(9, 2)) AS compressionRatio,
+    bs.media_set_id AS mediaSetId,
+    bms.media_family_count AS stripes,
+    bms.mirror_count AS mirrors,
+    (SELECT TOP (1)
+        CASE bmf.device_type
+            WHEN 2 THEN 'disk'
+            WHEN 5 THEN 'tape'
+            WHEN 7 THEN 'virtual'
+            WHEN 9 THEN 'url'
+            WHEN 105 THEN 'backup device'
+            ELSE 'other'
+        END
+     FROM msdb.dbo.backupmediafamily bmf
+     WHERE bmf.media_set_id = bs.media_set_id
+     ORDER BY bmf.family_sequence_number) AS locationType,
+    STUFF((
+        SELECT NCHAR(10) + bmf.physical_device_name
+        FROM msdb.dbo.backupmediafamily bmf
+        WHERE bmf.media_set_id = bs.media_set_id
+        ORDER BY bmf.mirror, bmf.family_sequence_number
+        FOR XML PATH(''), TYPE
+    ).value('.', 'nvarchar(max)'), 1, 1, N'') AS devices,
+    bs.has_backup_checksums AS hasChecksum,
+    bs.is_copy_only AS isCopyOnly,
+    bs.key_algorithm AS keyAlgorithm,
+    bs.encryptor_type AS encryptorType
 FROM msdb.dbo.backupset bs
-LEFT JOIN msdb.dbo.backupmediafamily bmf ON bs.media_set_id = bmf.media_set_id
+INNER JOIN msdb.dbo.backupmediaset bms ON bms.media_set_id = bs.media_set_id
 WHERE (@P2 IS NULL OR bs.database_name = @P2)
   AND bs.backup_start_date >= DATEADD(day, -@P3, SYSUTCDATETIME())
   AND ((@P4 IS NOT NULL AND bs.type = 'D')
@@ -54,7 +75,138 @@ WHERE (@P2 IS NULL OR bs.database_name = @P2)
     OR (@P6 IS NOT NULL AND bs.type = 'L'))
 ORDER BY bs.backup_start_date DESC;
 "#;
-        let mut query = Query::new(sql);
+
+/// Latest backup of each kind per online database (tempdb and snapshots excluded). Ages are in
+/// minutes against server local time, which is what msdb records.
+const LATEST_SQL: &str = r#"
+SELECT
+    d.name AS databaseName,
+    d.recovery_model_desc AS recoveryModel,
+    MAX(CASE WHEN bs.type = 'D' THEN bs.backup_finish_date END) AS lastFullBackup,
+    MAX(CASE WHEN bs.type = 'L' THEN bs.backup_finish_date END) AS lastLogBackup,
+    DATEDIFF(minute, MAX(CASE WHEN bs.type = 'D' THEN bs.backup_finish_date END), GETDATE()) AS fullAgeMinutes,
+    DATEDIFF(minute, MAX(CASE WHEN bs.type = 'L' THEN bs.backup_finish_date END), GETDATE()) AS logAgeMinutes
+FROM sys.databases d
+LEFT JOIN msdb.dbo.backupset bs ON bs.database_name = d.name
+WHERE d.name <> 'tempdb'
+  AND d.state_desc = 'ONLINE'
+  AND d.source_database_id IS NULL
+  AND (@P1 IS NULL OR d.name = @P1)
+GROUP BY d.name, d.recovery_model_desc
+ORDER BY d.name;
+"#;
+
+/// Every backup since the latest full (not copy-only) backup of one database, oldest first.
+const HISTORY_SQL: &str = r#"
+SELECT
+    bs.backup_set_id AS backupSetId,
+    bs.backup_start_date AS backupStart,
+    bs.backup_finish_date AS backupFinish,
+    CASE bs.type
+        WHEN 'D' THEN 'FULL'
+        WHEN 'I' THEN 'DIFF'
+        WHEN 'L' THEN 'LOG'
+        ELSE bs.type
+    END AS backupType,
+    bs.is_copy_only AS isCopyOnly,
+    bs.first_lsn AS firstLsn,
+    bs.last_lsn AS lastLsn,
+    bs.checkpoint_lsn AS checkpointLsn,
+    bs.database_backup_lsn AS databaseBackupLsn,
+    bs.backup_size AS backupSize,
+    bs.compressed_backup_size AS compressedSize,
+    STUFF((
+        SELECT NCHAR(10) + bmf.physical_device_name
+        FROM msdb.dbo.backupmediafamily bmf
+        WHERE bmf.media_set_id = bs.media_set_id
+        ORDER BY bmf.mirror, bmf.family_sequence_number
+        FOR XML PATH(''), TYPE
+    ).value('.', 'nvarchar(max)'), 1, 1, N'') AS devices
+FROM msdb.dbo.backupset bs
+WHERE bs.database_name = @P1
+  AND bs.backup_start_date >= (
+    SELECT MAX(full_bs.backup_start_date)
+    FROM msdb.dbo.backupset full_bs
+    WHERE full_bs.database_name = @P1 AND full_bs.type = 'D' AND full_bs.is_copy_only = 0
+  )
+ORDER BY bs.backup_start_date, bs.backup_set_id;
+"#;
+
+/// Full, differential, and log backups of one database that a restore can use (copy-only ones
+/// are left out), oldest first. Devices are the first mirror's stripes as `kind<TAB>name`.
+const RESTORE_SQL: &str = r#"
+SELECT
+    bs.backup_set_id AS backupSetId,
+    bs.type AS backupType,
+    bs.backup_finish_date AS backupFinish,
+    bs.first_lsn AS firstLsn,
+    bs.last_lsn AS lastLsn,
+    bs.checkpoint_lsn AS checkpointLsn,
+    bs.database_backup_lsn AS databaseBackupLsn,
+    STUFF((
+        SELECT NCHAR(10)
+            + CASE bmf.device_type WHEN 2 THEN N'DISK' WHEN 5 THEN N'TAPE' WHEN 9 THEN N'URL' ELSE N'OTHER' END
+            + NCHAR(9) + bmf.physical_device_name
+        FROM msdb.dbo.backupmediafamily bmf
+        WHERE bmf.media_set_id = bs.media_set_id AND bmf.mirror = 0
+        ORDER BY bmf.family_sequence_number
+        FOR XML PATH(''), TYPE
+    ).value('.', 'nvarchar(max)'), 1, 1, N'') AS devices
+FROM msdb.dbo.backupset bs
+WHERE bs.database_name = @P1
+  AND bs.is_copy_only = 0
+  AND bs.type IN ('D', 'I', 'L')
+ORDER BY bs.backup_start_date, bs.backup_set_id;
+"#;
+
+/// `--max-full-age` when `--check` is given without one.
+const DEFAULT_MAX_FULL_AGE_MINUTES: u64 = 24 * 60;
+
+/// Columns shown in table output; JSON carries every column.
+const TEXT_COLUMNS: &[&str] = &[
+    "databaseName",
+    "backupFinish",
+    "backupType",
+    "backupSize",
+    "compressionRatio",
+    "stripes",
+    "locationType",
+    "hasChecksum",
+    "keyAlgorithm",
+    "devices",
+];
+
+pub fn run(args: &CliArgs, cmd: &BackupsArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    if cmd.check {
+        return run_check(args, cmd, &resolved, format);
+    }
+    if let Some(database) = &cmd.history {
+        return run_history(args, database, &resolved, format);
+    }
+    if let Some(database) = &cmd.restore_script {
+        return run_restore_script(args, cmd, database, &resolved, format);
+    }
+
+    let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
+    let since_days = cmd.since.unwrap_or(7);
+    let backup_type = cmd.backup_type.clone().unwrap_or_else(|| "all".to_string());
+    let backup_type = backup_type.to_lowercase();
+
+    let (type_d, type_i, type_l) = match backup_type.as_str() {
+        "full" => (Some("D"), None, None),
+        "diff" => (None, Some("I"), None),
+        "log" => (None, None, Some("L")),
+        _ => (Some("D"), Some("I"), Some("L")),
+    };
+
+    let database = cmd.database.clone();
+    let export = ExportTarget::from_args(cmd.to.as_deref(), cmd.storage_tier.as_deref())?;
+
+    let result_set = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(BACKUPS_SQL);
         query.bind(limit as i64);
         query.bind(database.as_deref());
         query.bind(since_days as i64);
@@ -64,13 +216,22 @@ ORDER BY bs.backup_start_date DESC;
         let result_sets = executor::run_query(query, &mut client).await?;
         Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
     })?;
+    let uploaded = match &export {
+        Some(export) => Some(export.upload_csv(
+            std::slice::from_ref(&result_set),
+            resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
+        )?),
+        None => None,
+    };
 
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
             "database": database,
             "sinceDays": since_days,
             "type": backup_type,
-            "backups": json_out::result_set_rows_to_objects(&result_set),
+            "backups": backups_to_json(&result_set),
+            "uploaded": uploaded,
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
@@ -83,8 +244,766 @@ ORDER BY bs.backup_start_date DESC;
         return Ok(());
     }
 
-    let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
+    let view = text_view(&result_set);
+    let result = table::render_result_set_table(&view, format, &TableOptions::default());
     println!("{}", result.output);
+    common::print_uploaded(uploaded.as_deref());
+
+    Ok(())
+}
+
+/// One database whose last backup of a kind is missing or older than allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Violation {
+    database: String,
+    backup_type: &'static str,
+    last_backup: Option<String>,
+    age_minutes: Option<u64>,
+    max_age_minutes: u64,
+}
+
+fn run_check(
+    args: &CliArgs,
+    cmd: &BackupsArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+) -> Result<()> {
+    let max_full = cmd.max_full_age.unwrap_or(DEFAULT_MAX_FULL_AGE_MINUTES);
+    let max_log = cmd.max_log_age;
+    let database = cmd.database.clone();
+    let latest = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(LATEST_SQL);
+        query.bind(database.as_deref());
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+    let violations = evaluate(&latest, max_full, max_log);
 
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "status": if violations.is_empty() { "ok" } else { "violations" },
+            "maxFullAgeMinutes": max_full,
+            "maxLogAgeMinutes": max_log,
+            "databases": json_out::result_set_rows_to_objects(&latest),
+            "violations": violations.iter().map(|v| json!({
+                "database": v.database,
+                "backupType": v.backup_type,
+                "lastBackup": v.last_backup,
+                "ageMinutes": v.age_minutes,
+                "maxAgeMinutes": v.max_age_minutes,
+            })).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if violations.is_empty() {
+            println!(
+                "All {} database{} within backup thresholds.",
+                latest.rows.len(),
+                if latest.rows.len() == 1 { "" } else { "s" }
+            );
+        } else {
+            let view = ResultSet {
+                columns: ["database", "backupType", "lastBackup", "age", "maxAge"]
+                    .iter()
+                    .map(|name| Column {
+                        name: name.to_string(),
+                        data_type: None,
+                    })
+                    .collect(),
+                rows: violations
+                    .iter()
+                    .map(|v| {
+                        vec![
+                            Value::Text(v.database.clone()),
+                            Value::Text(v.backup_type.to_string()),
+                            v.last_backup.clone().map_or(Value::Null, Value::Text),
+                            Value::Text(v.age_minutes.map_or("never".to_string(), format_minutes)),
+                            Value::Text(format_minutes(v.max_age_minutes)),
+                        ]
+                    })
+                    .collect(),
+            };
+            let result = table::render_result_set_table(&view, format, &TableOptions::default());
+            println!("{}", result.output);
+        }
+    }
+
+    if !violations.is_empty() {
+        ExitCode::CheckFailed.exit();
+    }
     Ok(())
 }
+
+/// Databases with no full backup, or none within `max_full` minutes; with `max_log`, the same
+/// for log backups of databases not in simple recovery.
+fn evaluate(latest: &ResultSet, max_full: u64, max_log: Option<u64>) -> Vec<Violation> {
+    let column = |name: &str| latest.columns.iter().position(|c| c.name == name);
+    let (Some(name_idx), Some(model_idx)) = (column("databaseName"), column("recoveryModel"))
+    else {
+        return Vec::new();
+    };
+    let checks = [
+        (
+            "FULL",
+            Some(max_full),
+            column("lastFullBackup"),
+            column("fullAgeMinutes"),
+        ),
+        (
+            "LOG",
+            max_log,
+            column("lastLogBackup"),
+            column("logAgeMinutes"),
+        ),
+    ];
+    let mut violations = Vec::new();
+    for row in &latest.rows {
+        let text = |idx: usize| row.get(idx).map(Value::as_csv).unwrap_or_default();
+        for (backup_type, max_age, last_idx, age_idx) in checks {
+            let Some(max_age) = max_age else {
+                continue;
+            };
+            if backup_type == "LOG" && text(model_idx).eq_ignore_ascii_case("SIMPLE") {
+                continue;
+            }
+            let last_backup = last_idx
+                .and_then(|idx| row.get(idx))
+                .filter(|value| !matches!(value, Value::Null))
+                .map(Value::as_csv);
+            let age_minutes = age_idx.and_then(|idx| match row.get(idx) {
+                Some(Value::Int(age)) => Some((*age).max(0) as u64),
+                _ => None,
+            });
+            if age_minutes.is_none_or(|age| age > max_age) {
+                violations.push(Violation {
+                    database: text(name_idx),
+                    backup_type,
+                    last_backup,
+                    age_minutes,
+                    max_age_minutes: max_age,
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// `90` minutes as `1h30m`, `2880` as `2d`.
+fn format_minutes(minutes: u64) -> String {
+    let (days, hours, mins) = (minutes / 1440, minutes % 1440 / 60, minutes % 60);
+    let mut out = String::new();
+    for (value, unit) in [(days, "d"), (hours, "h"), (mins, "m")] {
+        if value > 0 {
+            out.push_str(&format!("{}{}", value, unit));
+        }
+    }
+    if out.is_empty() {
+        out.push_str("0m");
+    }
+    out
+}
+
+fn run_history(
+    args: &CliArgs,
+    database: &str,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+) -> Result<()> {
+    let history = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(HISTORY_SQL);
+        query.bind(database);
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+    if history.rows.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("No full backup of '{}' is recorded in msdb", database),
+        )
+        .into());
+    }
+    let history = annotate_chain(history);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "database": database,
+            "chain": backups_to_json(&history),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    let result = table::render_result_set_table(&history, format, &TableOptions::default());
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// Add a `chain` column to history rows, oldest first from the base full backup: `base` for
+/// it, `ok` for a differential on that base or a log continuing the previous one, `gap` for a
+/// log that does not start where the last one ended, `other-base` for a differential taken
+/// from another full, and `copy-only` for backups outside the chain.
+fn annotate_chain(mut history: ResultSet) -> ResultSet {
+    let column = |name: &str| history.columns.iter().position(|c| c.name == name);
+    let idx = (
+        column("backupType"),
+        column("isCopyOnly"),
+        column("firstLsn"),
+        column("lastLsn"),
+        column("checkpointLsn"),
+        column("databaseBackupLsn"),
+    );
+    let (Some(kind), Some(copy_only), Some(first), Some(last), Some(checkpoint), Some(base)) = idx
+    else {
+        return history;
+    };
+    let lsn =
+        |row: &[Value], idx: usize| row.get(idx).and_then(|v| v.as_csv().parse::<u128>().ok());
+    let mut base_checkpoint = None;
+    let mut log_end = None;
+    for row in &mut history.rows {
+        let status = if matches!(
+            row.get(copy_only),
+            Some(Value::Bool(true)) | Some(Value::Int(1))
+        ) {
+            "copy-only"
+        } else {
+            match row.get(kind).map(Value::as_csv).as_deref() {
+                Some("FULL") if base_checkpoint.is_none() => {
+                    base_checkpoint = lsn(row, checkpoint);
+                    log_end = lsn(row, last);
+                    "base"
+                }
+                Some("DIFF") if lsn(row, base) == base_checkpoint => "ok",
+                Some("DIFF") => "other-base",
+                Some("LOG") => {
+                    // A log may start before the full (it picks up where the previous log
+                    // ended), but one starting after the last covered LSN leaves a gap.
+                    let starts = lsn(row, first);
+                    let ok = match (starts, log_end) {
+                        (Some(starts), Some(end)) => starts <= end,
+                        _ => false,
+                    };
+                    log_end = lsn(row, last).or(log_end);
+                    if ok { "ok" } else { "gap" }
+                }
+                _ => "ok",
+            }
+        };
+        row.push(Value::Text(status.to_string()));
+    }
+    history.columns.push(Column {
+        name: "chain".to_string(),
+        data_type: None,
+    });
+    history
+}
+
+/// One usable backup set, as the restore planner sees it.
+#[derive(Debug, Clone, PartialEq)]
+struct RestoreBackup {
+    backup_set_id: i64,
+    /// `D`, `I`, or `L`, as msdb records it.
+    kind: String,
+    finished: NaiveDateTime,
+    first_lsn: u128,
+    last_lsn: u128,
+    checkpoint_lsn: u128,
+    database_backup_lsn: u128,
+    /// `(DISK|TAPE|URL|OTHER, name)` per stripe.
+    devices: Vec<(String, String)>,
+}
+
+fn run_restore_script(
+    args: &CliArgs,
+    cmd: &BackupsArgs,
+    database: &str,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+) -> Result<()> {
+    let rows = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(RESTORE_SQL);
+        query.bind(database);
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+    let backups = restore_backups(&rows);
+    let plan = plan_restore(database, &backups, cmd.stop_at)?;
+    let script = restore_script(database, &plan, cmd.stop_at, &resolved.connection.server)?;
+
+    let target = cmd.out.as_deref().filter(|path| *path != Path::new("-"));
+    if let Some(path) = target {
+        fs::write(path, &script).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "database": database,
+            "stopAt": cmd.stop_at.map(|at| at.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()),
+            "backups": plan.iter().map(|backup| json!({
+                "backupSetId": backup.backup_set_id,
+                "backupType": backup.kind,
+                "backupFinish": backup.finished.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
+                "devices": backup.devices.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "out": target.map(|path| path.display().to_string()),
+            "script": script,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    match target {
+        Some(path) if !args.quiet => println!("{}", path.display()),
+        Some(_) => {}
+        None => print!("{}", script),
+    }
+    Ok(())
+}
+
+/// Rows of [`RESTORE_SQL`] as backups, skipping any with values the planner cannot use.
+fn restore_backups(rows: &ResultSet) -> Vec<RestoreBackup> {
+    let column = |name: &str| rows.columns.iter().position(|c| c.name == name);
+    let names = [
+        "backupSetId",
+        "backupType",
+        "backupFinish",
+        "firstLsn",
+        "lastLsn",
+        "checkpointLsn",
+        "databaseBackupLsn",
+        "devices",
+    ];
+    let Some(idx) = names
+        .iter()
+        .map(|name| column(name))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+    let lsn = |value: Option<&Value>| value.and_then(|v| v.as_csv().parse::<u128>().ok());
+    rows.rows
+        .iter()
+        .filter_map(|row| {
+            Some(RestoreBackup {
+                backup_set_id: match row.get(idx[0]) {
+                    Some(Value::Int(id)) => *id,
+                    _ => return None,
+                },
+                kind: row.get(idx[1])?.as_csv(),
+                finished: match row.get(idx[2]) {
+                    Some(Value::DateTime(ts)) => ts.datetime,
+                    _ => return None,
+                },
+                first_lsn: lsn(row.get(idx[3]))?,
+                last_lsn: lsn(row.get(idx[4]))?,
+                checkpoint_lsn: lsn(row.get(idx[5])).unwrap_or(0),
+                database_backup_lsn: lsn(row.get(idx[6])).unwrap_or(0),
+                devices: row
+                    .get(idx[7])
+                    .map(Value::as_csv)
+                    .unwrap_or_default()
+                    .split(DEVICE_SEPARATOR)
+                    .filter_map(|device| device.split_once('\t'))
+                    .map(|(kind, name)| (kind.to_string(), name.to_string()))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// The backups to restore, in order: the latest full finished by `stop_at` (or the latest
+/// overall), its latest differential before then, and the unbroken log chain from there up to
+/// the first log that ends at or after `stop_at`.
+fn plan_restore<'a>(
+    database: &str,
+    backups: &'a [RestoreBackup],
+    stop_at: Option<NaiveDateTime>,
+) -> Result<Vec<&'a RestoreBackup>> {
+    let usable = |backup: &&RestoreBackup| stop_at.is_none_or(|at| backup.finished <= at);
+    let Some(full) = backups
+        .iter()
+        .filter(|b| b.kind == "D")
+        .filter(usable)
+        .max_by_key(|b| b.finished)
+    else {
+        let when = stop_at
+            .map(|at| format!(" finished by {}", at))
+            .unwrap_or_default();
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!(
+                "No full backup of '{}'{} is recorded in msdb",
+                database, when
+            ),
+        )
+        .into());
+    };
+    let diff = backups
+        .iter()
+        .filter(|b| b.kind == "I" && b.database_backup_lsn == full.checkpoint_lsn)
+        .filter(|b| b.finished > full.finished)
+        .filter(usable)
+        .max_by_key(|b| b.finished);
+    let mut plan = vec![full];
+    plan.extend(diff);
+
+    let mut covered = diff.unwrap_or(full).last_lsn;
+    let mut reached = stop_at.is_none();
+    for log in backups.iter().filter(|b| b.kind == "L") {
+        if log.last_lsn <= covered {
+            continue;
+        }
+        if log.first_lsn > covered {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Log chain of '{}' is broken: no log backup covers LSN {} (next log starts at {}, backup set {})",
+                    database, covered, log.first_lsn, log.backup_set_id
+                ),
+            )
+            .into());
+        }
+        plan.push(log);
+        covered = log.last_lsn;
+        if stop_at.is_some_and(|at| log.finished >= at) {
+            reached = true;
+            break;
+        }
+    }
+    if !reached {
+        let last = plan.last().map(|b| b.finished).unwrap_or(full.finished);
+        return Err(AppError::new(
+            ErrorKind::Query,
+            format!(
+                "No log backup of '{}' reaches {}; the latest restorable point is {}",
+                database,
+                stop_at.map(|at| at.to_string()).unwrap_or_default(),
+                last
+            ),
+        )
+        .into());
+    }
+    Ok(plan)
+}
+
+/// RESTORE statements for `plan`, every step `WITH NORECOVERY` and a final `WITH RECOVERY`.
+fn restore_script(
+    database: &str,
+    plan: &[&RestoreBackup],
+    stop_at: Option<NaiveDateTime>,
+    server: &str,
+) -> Result<String> {
+    let name = bracket(database);
+    let mut out = vec![
+        format!(
+            "-- Restore {} on {} from msdb backup history (sscli {}).",
+            name,
+            server,
+            env!("CARGO_PKG_VERSION")
+        ),
+        "-- Generated only; review device paths and add MOVE/REPLACE as needed before running."
+            .to_string(),
+    ];
+    if let Some(at) = stop_at {
+        out.push(format!(
+            "-- Point in time: {} (server local time).",
+            at.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    for backup in plan {
+        let mut devices = Vec::new();
+        for (kind, device) in &backup.devices {
+            if !matches!(kind.as_str(), "DISK" | "TAPE" | "URL") {
+                return Err(AppError::new(
+                    ErrorKind::Query,
+                    format!(
+                        "Backup set {} was written to a virtual device ({}); restore it with the tool that took it",
+                        backup.backup_set_id, device
+                    ),
+                )
+                .into());
+            }
+            devices.push(format!("{} = N'{}'", kind, device.replace('\'', "''")));
+        }
+        let (statement, label) = match backup.kind.as_str() {
+            "D" => ("RESTORE DATABASE", "Full"),
+            "I" => ("RESTORE DATABASE", "Differential"),
+            _ => ("RESTORE LOG", "Log"),
+        };
+        let mut options = vec!["NORECOVERY".to_string()];
+        if backup.kind == "L" {
+            if let Some(at) = stop_at {
+                options.push(format!("STOPAT = '{}'", at.format("%Y-%m-%dT%H:%M:%S%.3f")));
+            }
+        } else {
+            options.push("STATS = 10".to_string());
+        }
+        out.push(String::new());
+        out.push(format!(
+            "-- {} backup set {}, finished {}",
+            label,
+            backup.backup_set_id,
+            backup.finished.format("%Y-%m-%d %H:%M:%S")
+        ));
+        out.push(format!(
+            "{} {}\n    FROM {}\n    WITH {};",
+            statement,
+            name,
+            devices.join(",\n         "),
+            options.join(", ")
+        ));
+        out.push("GO".to_string());
+    }
+    out.push(String::new());
+    out.push(format!("RESTORE DATABASE {} WITH RECOVERY;", name));
+    out.push("GO".to_string());
+    out.push(String::new());
+    Ok(out.join("\n"))
+}
+
+/// Backup rows as JSON objects, with `devices` split into one entry per stripe/mirror.
+fn backups_to_json(result_set: &ResultSet) -> Vec<serde_json::Value> {
+    let mut rows = json_out::result_set_rows_to_objects(result_set);
+    for row in rows.iter_mut() {
+        if let Some(devices) = row.get("devices").and_then(|v| v.as_str()) {
+            let devices: Vec<&str> = devices
+                .split(DEVICE_SEPARATOR)
+                .filter(|d| !d.is_empty())
+                .collect();
+            row["devices"] = json!(devices);
+        }
+    }
+    rows
+}
+
+fn text_view(result_set: &ResultSet) -> ResultSet {
+    let indexes: Vec<usize> = TEXT_COLUMNS
+        .iter()
+        .filter_map(|name| result_set.columns.iter().position(|c| c.name == *name))
+        .collect();
+    ResultSet {
+        columns: indexes
+            .iter()
+            .map(|&idx| result_set.columns[idx].clone())
+            .collect(),
+        rows: result_set
+            .rows
+            .iter()
+            .map(|row| {
+                indexes
+                    .iter()
+                    .map(|&idx| row.get(idx).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_striped_devices_and_projects_text_columns() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let rs = ResultSet {
+            columns: vec![column("databaseName"), column("mediaSetId"), column("devices")],
+            rows: vec![vec![
+                Value::Text("sales".to_string()),
+                Value::Int(7),
+                Value::Text("https://acct.blob.core.windows.net/b/1.bak\nhttps://acct.blob.core.windows.net/b/2.bak".to_string()),
+            ]],
+        };
+        let json = backups_to_json(&rs);
+        assert_eq!(json[0]["devices"].as_array().map(Vec::len), Some(2));
+
+        let view = text_view(&rs);
+        let names: Vec<&str> = view.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["databaseName", "devices"]);
+    }
+
+    #[test]
+    fn flags_stale_and_missing_backups() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let text = |value: &str| Value::Text(value.to_string());
+        let latest = ResultSet {
+            columns: [
+                "databaseName",
+                "recoveryModel",
+                "lastFullBackup",
+                "lastLogBackup",
+                "fullAgeMinutes",
+                "logAgeMinutes",
+            ]
+            .iter()
+            .map(|name| column(name))
+            .collect(),
+            rows: vec![
+                vec![
+                    text("fresh"),
+                    text("FULL"),
+                    text("t"),
+                    text("t"),
+                    Value::Int(60),
+                    Value::Int(5),
+                ],
+                vec![
+                    text("stale"),
+                    text("FULL"),
+                    text("t"),
+                    text("t"),
+                    Value::Int(3000),
+                    Value::Int(40),
+                ],
+                vec![
+                    text("simple"),
+                    text("SIMPLE"),
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                ],
+            ],
+        };
+        let found: Vec<(String, &str)> = evaluate(&latest, 1440, Some(15))
+            .into_iter()
+            .map(|v| (v.database, v.backup_type))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("stale".to_string(), "FULL"),
+                ("stale".to_string(), "LOG"),
+                ("simple".to_string(), "FULL"),
+            ]
+        );
+        assert_eq!(evaluate(&latest, 1440, None).len(), 2);
+        assert_eq!(format_minutes(90), "1h30m");
+        assert_eq!(format_minutes(2880), "2d");
+    }
+
+    #[test]
+    fn annotates_the_backup_chain() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let row = |kind: &str, copy: bool, first: u64, last: u64, checkpoint: u64, base: u64| {
+            vec![
+                Value::Text(kind.to_string()),
+                Value::Bool(copy),
+                Value::Text(first.to_string()),
+                Value::Text(last.to_string()),
+                Value::Text(checkpoint.to_string()),
+                Value::Text(base.to_string()),
+            ]
+        };
+        let history = ResultSet {
+            columns: [
+                "backupType",
+                "isCopyOnly",
+                "firstLsn",
+                "lastLsn",
+                "checkpointLsn",
+                "databaseBackupLsn",
+            ]
+            .iter()
+            .map(|name| column(name))
+            .collect(),
+            rows: vec![
+                row("FULL", false, 100, 200, 150, 50),
+                row("LOG", false, 90, 300, 0, 150),
+                row("DIFF", false, 310, 320, 315, 150),
+                row("LOG", true, 300, 330, 0, 150),
+                row("LOG", false, 300, 400, 0, 150),
+                row("LOG", false, 450, 500, 0, 150),
+                row("DIFF", false, 510, 520, 515, 999),
+            ],
+        };
+        let chain: Vec<String> = annotate_chain(history)
+            .rows
+            .iter()
+            .map(|row| row.last().unwrap().as_csv())
+            .collect();
+        assert_eq!(
+            chain,
+            vec!["base", "ok", "ok", "copy-only", "ok", "gap", "other-base"]
+        );
+    }
+
+    #[test]
+    fn plans_a_point_in_time_restore() {
+        let at = |hour: u32| {
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        let backup =
+            |id: i64, kind: &str, hour: u32, first: u128, last: u128, base: u128| RestoreBackup {
+                backup_set_id: id,
+                kind: kind.to_string(),
+                finished: at(hour),
+                first_lsn: first,
+                last_lsn: last,
+                checkpoint_lsn: first + 5,
+                database_backup_lsn: base,
+                devices: vec![("DISK".to_string(), format!("D:\\bak\\{}.bak", id))],
+            };
+        let backups = vec![
+            backup(1, "D", 1, 100, 200, 0),
+            backup(2, "L", 2, 90, 300, 105),
+            backup(3, "I", 3, 310, 320, 105),
+            backup(4, "L", 4, 300, 400, 105),
+            backup(5, "L", 5, 400, 500, 105),
+            backup(6, "L", 6, 500, 600, 105),
+        ];
+        let ids =
+            |plan: Vec<&RestoreBackup>| plan.iter().map(|b| b.backup_set_id).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(plan_restore("sales", &backups, Some(at(5))).unwrap()),
+            vec![1, 3, 4, 5]
+        );
+        assert_eq!(
+            ids(plan_restore("sales", &backups, None).unwrap()),
+            vec![1, 3, 4, 5, 6]
+        );
+        assert!(plan_restore("sales", &backups, Some(at(7))).is_err());
+        assert!(plan_restore("sales", &backups[1..], Some(at(5))).is_err());
+
+        let mut gap = backups.clone();
+        gap.remove(4);
+        let err = plan_restore("sales", &gap, Some(at(6))).unwrap_err();
+        assert!(err.to_string().contains("broken"));
+
+        let plan = plan_restore("sales", &backups, Some(at(5))).unwrap();
+        let script = restore_script("sales", &plan, Some(at(5)), "db01").unwrap();
+        assert!(script.contains("RESTORE DATABASE [sales]\n    FROM DISK = N'D:\\bak\\1.bak'\n    WITH NORECOVERY, STATS = 10;"));
+        assert!(script.contains("WITH NORECOVERY, STOPAT = '2026-03-01T05:00:00.000';"));
+        assert!(
+            script
+                .trim_end()
+                .ends_with("RESTORE DATABASE [sales] WITH RECOVERY;\nGO")
+        );
+        assert_eq!(script.matches("RESTORE LOG").count(), 2);
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CdcArgs, CliArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+/// Latency (seconds) above which the capture job is reported as lagging.
+const LATENCY_WARN_SECONDS: i64 = 300;
+
+const DATABASE_SQL: &str = r#"
+SELECT DB_NAME() AS [database], CAST(is_cdc_enabled AS int) AS cdcEnabled
+FROM sys.databases
+WHERE database_id = DB_ID();
+"#;
+
+// cdc.change_tables only exists once CDC is enabled, so the caller checks first.
+const TABLES_SQL: &str = r#"
+SELECT
+    OBJECT_SCHEMA_NAME(ct.source_object_id) AS [schema],
+    OBJECT_NAME(ct.source_object_id) AS [table],
+    ct.capture_instance AS captureInstance,
+    ct.supports_net_changes AS supportsNetChanges,
+    ct.index_name AS indexName,
+    ct.role_name AS roleName,
+    (SELECT COUNT(*) FROM cdc.captured_columns cc WHERE cc.object_id = ct.object_id) AS capturedColumns,
+    ct.create_date AS createdAt
+FROM cdc.change_tables ct
+WHERE (@P1 IS NULL OR OBJECT_NAME(ct.source_object_id) = @P1)
+  AND (@P2 IS NULL OR OBJECT_SCHEMA_NAME(ct.source_object_id) = @P2)
+ORDER BY [schema], [table], captureInstance;
+"#;
+
+const JOBS_SQL: &str = r#"
+SELECT
+    cj.job_type AS jobType,
+    j.name AS jobName,
+    j.enabled AS enabled,
+    CASE WHEN ja.start_execution_date IS NOT NULL AND ja.stop_execution_date IS NULL
+         THEN 1 ELSE 0 END AS isRunning,
+    cj.continuous AS continuous,
+    cj.pollinginterval AS pollingIntervalSec,
+    cj.maxtrans AS maxTrans,
+    cj.retention AS retentionMinutes,
+    CASE lh.run_status
+        WHEN 0 THEN 'Failed' WHEN 1 THEN 'Succeeded' WHEN 2 THEN 'Retry'
+        WHEN 3 THEN 'Canceled' WHEN 4 THEN 'In Progress' END AS lastOutcome
+FROM msdb.dbo.cdc_jobs cj
+LEFT JOIN msdb.dbo.sysjobs j ON j.job_id = cj.job_id
+OUTER APPLY (
+    SELECT TOP 1 a.start_execution_date, a.stop_execution_date
+    FROM msdb.dbo.sysjobactivity a
+    WHERE a.job_id = cj.job_id
+    ORDER BY a.session_id DESC
+) ja
+OUTER APPLY (
+    SELECT TOP 1 h.run_status
+    FROM msdb.dbo.sysjobhistory h
+    WHERE h.job_id = cj.job_id AND h.step_id = 0
+    ORDER BY h.instance_id DESC
+) lh
+WHERE cj.database_id = DB_ID()
+ORDER BY cj.job_type;
+"#;
+
+const SCANS_SQL: &str = r#"
+SELECT TOP 5
+    session_id AS sessionId,
+    start_time AS startTime,
+    end_time AS endTime,
+    duration AS durationSec,
+    scan_phase AS scanPhase,
+    tran_count AS tranCount,
+    command_count AS commandCount,
+    latency AS latencySec,
+    error_count AS errorCount
+FROM sys.dm_cdc_log_scan_sessions
+WHERE session_id > 0
+ORDER BY session_id DESC;
+"#;
+
+const ERRORS_SQL: &str = r#"
+SELECT TOP 10
+    session_id AS sessionId,
+    entry_time AS entryTime,
+    error_number AS errorNumber,
+    error_severity AS severity,
+    error_message AS message
+FROM sys.dm_cdc_errors
+ORDER BY entry_time DESC;
+"#;
+
+struct CdcReport {
+    database: String,
+    enabled: bool,
+    tables: ResultSet,
+    jobs: ResultSet,
+    scans: ResultSet,
+    errors: ResultSet,
+    warnings: Vec<String>,
+}
+
+pub fn run(args: &CliArgs, cmd: &CdcArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let (table_name, schema) = match cmd.table.as_deref() {
+        Some(t) => {
+            let (name, schema) = common::normalize_object_input(t);
+            (Some(name), schema)
+        }
+        None => (None, None),
+    };
+
+    let report = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let db = executor::run_query(Query::new(DATABASE_SQL), &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let row = db.rows.first();
+        let database = row
+            .and_then(|r| r.first())
+            .map(Value::as_display)
+            .unwrap_or_default();
+        let enabled = row
+            .and_then(|r| r.get(1))
+            .is_some_and(|v| matches!(v, Value::Int(1)));
+
+        let mut report = CdcReport {
+            database,
+            enabled,
+            tables: ResultSet::default(),
+            jobs: ResultSet::default(),
+            scans: ResultSet::default(),
+            errors: ResultSet::default(),
+            warnings: Vec::new(),
+        };
+        if !enabled {
+            return Ok::<_, anyhow::Error>(report);
+        }
+
+        let mut query = Query::new(TABLES_SQL);
+        query.bind(table_name.as_deref());
+        query.bind(schema.as_deref());
+        report.tables = executor::run_query(query, &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        // msdb and the CDC DMVs need extra permissions; report what we can see.
+        for (name, sql, slot) in [
+            ("jobs", JOBS_SQL, &mut report.jobs),
+            ("logScanSessions", SCANS_SQL, &mut report.scans),
+            ("errors", ERRORS_SQL, &mut report.errors),
+        ] {
+            match executor::run_query(Query::new(sql), &mut client).await {
+                Ok(sets) => *slot = sets.into_iter().next().unwrap_or_default(),
+                Err(err) => report
+                    .warnings
+                    .push(format!("{} unavailable: {}", name, err)),
+            }
+        }
+        Ok(report)
+    })?;
+
+    let jobs = json_out::result_set_rows_to_objects(&report.jobs);
+    let scans = json_out::result_set_rows_to_objects(&report.scans);
+    let errors = json_out::result_set_rows_to_objects(&report.errors);
+    let mut problems = Vec::new();
+    if report.enabled {
+        problems = collect_problems(&jobs, scans.first());
+    }
+    let status = if !report.enabled {
+        "disabled"
+    } else if problems.is_empty() && errors.is_empty() {
+        "ok"
+    } else {
+        "degraded"
+    };
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "database": report.database,
+            "cdcEnabled": report.enabled,
+            "status": status,
+            "tables": json_out::result_set_rows_to_objects(&report.tables),
+            "jobs": jobs,
+            "logScanSessions": scans,
+            "errors": errors,
+            "problems": problems,
+            "warnings": report.warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if !report.enabled {
+        println!("CDC is not enabled on database '{}'.", report.database);
+        return Ok(());
+    }
+
+    for (title, rs) in [
+        ("CDC Tables", &report.tables),
+        ("Jobs", &report.jobs),
+        ("Log Scan Sessions", &report.scans),
+        ("Errors", &report.errors),
+    ] {
+        if rs.rows.is_empty() {
+            continue;
+        }
+        println!("{}", title);
+        let result = table::render_result_set_table(rs, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+    for problem in &problems {
+        eprintln!("Warning: {}", problem);
+    }
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+fn collect_problems(
+    jobs: &[serde_json::Value],
+    latest_scan: Option<&serde_json::Value>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for job_type in ["capture", "cleanup"] {
+        if !jobs.iter().any(|j| j["jobType"] == job_type) {
+            problems.push(format!("No {} job is registered", job_type));
+        }
+    }
+    for job in jobs {
+        let job_type = job["jobType"].as_str().unwrap_or("?");
+        if job["enabled"] == 0 || job["enabled"] == false {
+            problems.push(format!("{} job is disabled", job_type));
+        }
+        if job_type == "capture" && job["continuous"] == true && job["isRunning"] == 0 {
+            problems.push("capture job is not running".to_string());
+        }
+        if job["lastOutcome"] == "Failed" {
+            problems.push(format!("{} job last run failed", job_type));
+        }
+    }
+    if let Some(scan) = latest_scan {
+        if scan["latencySec"]
+            .as_i64()
+            .is_some_and(|latency| latency > LATENCY_WARN_SECONDS)
+        {
+            problems.push(format!(
+                "log scan latency is {}s (>{}s)",
+                scan["latencySec"], LATENCY_WARN_SECONDS
+            ));
+        }
+        if scan["errorCount"].as_i64().is_some_and(|n| n > 0) {
+            problems.push(format!(
+                "latest log scan reported {} error(s)",
+                scan["errorCount"]
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_stopped_capture_job_and_latency() {
+        let jobs = vec![
+            json!({"jobType": "capture", "enabled": true, "continuous": true,
+                   "isRunning": 0, "lastOutcome": "Succeeded"}),
+            json!({"jobType": "cleanup", "enabled": true, "continuous": false,
+                   "isRunning": 0, "lastOutcome": "Succeeded"}),
+        ];
+        let scan = json!({"latencySec": 900, "errorCount": 0});
+        let problems = collect_problems(&jobs, Some(&scan));
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("not running"));
+        assert!(problems[1].contains("latency"));
+    }
+
+    #[test]
+    fn missing_jobs_are_problems() {
+        let problems = collect_problems(&[], None);
+        assert_eq!(problems.len(), 2);
+    }
+}
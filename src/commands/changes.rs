@@ -0,0 +1,200 @@
+//! `changes --since`: objects created or modified in a window, grouped by type.
+//!
+//! Everything comes from `sys.objects` in the current database, so a dropped object leaves no
+//! trace and an `ALTER` shows only its latest `modify_date`.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{ChangesArgs, CliArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+/// Type groups in report order: `--type` value and text heading.
+const GROUPS: [(&str, &str); 7] = [
+    ("table", "Tables"),
+    ("view", "Views"),
+    ("proc", "Procedures"),
+    ("function", "Functions"),
+    ("trigger", "Triggers"),
+    ("constraint", "Constraints"),
+    ("other", "Other"),
+];
+
+fn changes_sql(cmd: &ChangesArgs) -> String {
+    format!(
+        r#"
+DECLARE @cutoff datetime2 = {};
+SELECT CONVERT(varchar(19), @cutoff, 120) AS cutoff;
+WITH changed AS (
+    SELECT
+        CASE
+            WHEN o.type = 'U' THEN 'table'
+            WHEN o.type = 'V' THEN 'view'
+            WHEN o.type IN ('P', 'PC', 'X', 'RF') THEN 'proc'
+            WHEN o.type IN ('FN', 'IF', 'TF', 'FS', 'FT', 'AF') THEN 'function'
+            WHEN o.type IN ('TR', 'TA') THEN 'trigger'
+            WHEN o.type IN ('PK', 'UQ', 'F', 'C', 'D', 'EC') THEN 'constraint'
+            ELSE 'other'
+        END AS kind,
+        s.name AS schema_name,
+        o.name,
+        o.type_desc,
+        parent.name AS parent_name,
+        o.create_date,
+        o.modify_date
+    FROM sys.objects o
+    INNER JOIN sys.schemas s ON s.schema_id = o.schema_id
+    LEFT JOIN sys.objects parent ON parent.object_id = o.parent_object_id
+    WHERE o.modify_date >= @cutoff
+      AND o.type NOT IN ('S', 'IT')
+      AND (@P2 = 1 OR o.is_ms_shipped = 0)
+      AND (@P3 IS NULL OR s.name = @P3)
+)
+SELECT kind AS [type],
+       schema_name AS [schema],
+       name,
+       type_desc AS objectType,
+       parent_name AS parent,
+       CASE WHEN create_date >= @cutoff THEN 'created' ELSE 'modified' END AS change,
+       CONVERT(varchar(19), create_date, 120) AS createdAt,
+       CONVERT(varchar(19), modify_date, 120) AS modifiedAt
+FROM changed
+WHERE @P4 IS NULL OR kind = @P4
+ORDER BY modify_date DESC, schema_name, name;
+"#,
+        common::modified_since_cutoff(&cmd.since, "@P1")
+    )
+}
+
+pub fn run(args: &CliArgs, cmd: &ChangesArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let mut result_sets = tokio::runtime::Runtime::new()?
+        .block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let mut query = Query::new(changes_sql(cmd));
+            common::bind_modified_since(&mut query, Some(&cmd.since));
+            query.bind(if cmd.include_system { 1i32 } else { 0i32 });
+            query.bind(cmd.schema.as_deref());
+            query.bind(cmd.object_type.as_deref());
+            executor::run_query(query, &mut client).await
+        })?
+        .into_iter();
+    let cutoff = result_sets
+        .next()
+        .and_then(|rs| rs.rows.into_iter().next())
+        .and_then(|row| row.into_iter().next())
+        .map(|value| value.as_display())
+        .unwrap_or_default();
+    let changes = result_sets.next().unwrap_or_default();
+    let count = changes.rows.len();
+    let groups = group_by_type(changes);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "since": cutoff,
+            "count": count,
+            "groups": groups
+                .iter()
+                .map(|(kind, rs)| json!({
+                    "type": kind,
+                    "count": rs.rows.len(),
+                    "objects": json_out::result_set_rows_to_objects(rs),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    if groups.is_empty() {
+        println!("No objects created or modified since {}.", cutoff);
+        return Ok(());
+    }
+    println!("{} objects changed since {}", count, cutoff);
+    let options = TableOptions::default();
+    for (kind, rs) in &groups {
+        let heading = GROUPS
+            .iter()
+            .find(|(key, _)| key == kind)
+            .map_or(kind.as_str(), |(_, heading)| heading);
+        println!("\n{} ({})", heading, rs.rows.len());
+        println!(
+            "{}",
+            table::render_result_set_table(rs, format, &options).output
+        );
+    }
+    Ok(())
+}
+
+/// Split rows on their leading `type` column into one result set per group, in `GROUPS` order,
+/// dropping that column. Empty groups are left out.
+fn group_by_type(changes: ResultSet) -> Vec<(String, ResultSet)> {
+    let columns = changes.columns.into_iter().skip(1).collect::<Vec<_>>();
+    let mut groups: Vec<(String, ResultSet)> = GROUPS
+        .iter()
+        .map(|(kind, _)| {
+            (
+                kind.to_string(),
+                ResultSet {
+                    columns: columns.clone(),
+                    rows: Vec::new(),
+                },
+            )
+        })
+        .collect();
+    for row in changes.rows {
+        let kind = row.first().map(Value::as_display).unwrap_or_default();
+        let index = groups
+            .iter()
+            .position(|(key, _)| *key == kind)
+            .unwrap_or(GROUPS.len() - 1);
+        groups[index].1.rows.push(row.into_iter().skip(1).collect());
+    }
+    groups.retain(|(_, rs)| !rs.rows.is_empty());
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn changes_group_in_report_order() {
+        let text = |s: &str| Value::Text(s.to_string());
+        let changes = ResultSet {
+            columns: ["type", "schema", "name"]
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: vec![
+                vec![text("proc"), text("dbo"), text("PlaceOrder")],
+                vec![text("table"), text("dbo"), text("Orders")],
+                vec![text("proc"), text("sales"), text("Refund")],
+            ],
+        };
+        let groups = group_by_type(changes);
+        let kinds: Vec<_> = groups.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(kinds, vec!["table", "proc"]);
+        assert_eq!(groups[1].1.rows.len(), 2);
+        assert_eq!(groups[1].1.columns[0].name, "schema");
+        assert_eq!(groups[1].1.rows[1], vec![text("sales"), text("Refund")]);
+    }
+}
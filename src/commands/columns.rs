@@ -3,12 +3,13 @@ use serde_json::json;
 use tiberius::Query;
 
 use crate::cli::{CliArgs, ColumnsArgs};
-use crate::commands::{common, paging};
+use crate::commands::{common, describe, paging};
 use crate::config::OutputFormat;
 use crate::db::client;
-use crate::db::executor;
+use crate::db::metadata_cache;
+use crate::db::queries::bracket;
 use crate::db::types::{ResultSet, Value};
-use crate::output::{TableOptions, json as json_out, table};
+use crate::output::{TableOptions, json as json_out, shape, table};
 
 const LIMIT_DEFAULT: u64 = 50;
 const LIMIT_MAX: u64 = 500;
@@ -56,7 +57,8 @@ pub fn run(args: &CliArgs, cmd: &ColumnsArgs) -> Result<()> {
             return Ok::<_, anyhow::Error>((list_set, total));
         }
 
-        let list_sql = r#"
+        let list_sql = format!(
+            r#"
 WITH filtered AS (
     SELECT
         c.TABLE_SCHEMA AS schemaName,
@@ -64,10 +66,16 @@ WITH filtered AS (
         c.COLUMN_NAME AS columnName,
         c.DATA_TYPE AS dataType,
         c.IS_NULLABLE AS isNullable,
-        ROW_NUMBER() OVER (ORDER BY c.TABLE_SCHEMA, c.TABLE_NAME, c.ORDINAL_POSITION) AS rownum
+        CAST(ep.value AS nvarchar(max)) AS description,
+        ROW_NUMBER() OVER (ORDER BY {}) AS rownum
     FROM INFORMATION_SCHEMA.COLUMNS c
     INNER JOIN INFORMATION_SCHEMA.TABLES t
         ON c.TABLE_SCHEMA = t.TABLE_SCHEMA AND c.TABLE_NAME = t.TABLE_NAME
+    LEFT JOIN sys.extended_properties ep
+        ON ep.class = 1 AND ep.name = N'MS_Description'
+       AND ep.major_id = OBJECT_ID(QUOTENAME(c.TABLE_SCHEMA) + '.' + QUOTENAME(c.TABLE_NAME))
+       AND ep.minor_id = COLUMNPROPERTY(
+           OBJECT_ID(QUOTENAME(c.TABLE_SCHEMA) + '.' + QUOTENAME(c.TABLE_NAME)), c.COLUMN_NAME, 'ColumnId')
     WHERE (@P1 = 1 OR t.TABLE_TYPE = 'BASE TABLE')
       AND (@P2 IS NULL OR c.COLUMN_NAME LIKE @P2)
       AND (@P3 IS NULL OR c.TABLE_NAME LIKE @P3)
@@ -77,11 +85,19 @@ SELECT schemaName AS [schema],
        tableName AS tableName,
        columnName AS columnName,
        dataType AS dataType,
-       isNullable AS isNullable
+       isNullable AS isNullable,
+       description AS description
 FROM filtered
 WHERE rownum BETWEEN (@P5 + 1) AND (@P5 + @P6)
-ORDER BY schemaName, tableName, columnName;
-"#;
+ORDER BY {};
+"#,
+            common::order_keys(
+                &resolved,
+                "c.TABLE_SCHEMA, c.TABLE_NAME, c.ORDINAL_POSITION",
+                "OBJECT_ID(QUOTENAME(c.TABLE_SCHEMA) + '.' + QUOTENAME(c.TABLE_NAME))",
+            ),
+            common::order_keys(&resolved, "schemaName, tableName, columnName", "rownum"),
+        );
 
         let mut list_query = Query::new(list_sql);
         list_query.bind(if include_views { 1i32 } else { 0i32 });
@@ -91,7 +107,7 @@ ORDER BY schemaName, tableName, columnName;
         list_query.bind(offset as i64);
         list_query.bind(limit as i64);
 
-        let list_sets = executor::run_query(list_query, &mut client).await?;
+        let list_sets = metadata_cache::run_query(list_query, &mut client).await?;
         let list_set = list_sets.into_iter().next().unwrap_or_default();
 
         let count_sql = r#"
@@ -109,7 +125,7 @@ WHERE (@P1 = 1 OR t.TABLE_TYPE = 'BASE TABLE')
         count_query.bind(like.as_deref());
         count_query.bind(table_filter.as_deref());
         count_query.bind(schema.as_deref());
-        let count_sets = executor::run_query(count_query, &mut client).await?;
+        let count_sets = metadata_cache::run_query(count_query, &mut client).await?;
         let total = count_sets
             .first()
             .and_then(|rs| rs.rows.first())
@@ -120,6 +136,7 @@ WHERE (@P1 = 1 OR t.TABLE_TYPE = 'BASE TABLE')
         Ok::<_, anyhow::Error>((list_set, total))
     })?;
 
+    let rows = shape::apply(rows, &args.output)?;
     let count = rows.rows.len() as u64;
     let paging = paging::build_paging(total, count, offset, limit);
 
@@ -153,6 +170,7 @@ WHERE (@P1 = 1 OR t.TABLE_TYPE = 'BASE TABLE')
             limit: page_limit,
         });
     }
+    let rows = describe::without_empty_column(&rows, "description");
     let result = table::render_result_set_table(&rows, format, &options);
     println!("{}", result.output);
 
@@ -205,7 +223,7 @@ ORDER BY CASE o.type WHEN 'U' THEN 1 WHEN 'V' THEN 2 ELSE 3 END;
     query.bind(name);
     query.bind(schema);
 
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let Some(row) = result_set.rows.first() else {
@@ -282,7 +300,7 @@ ORDER BY rownum;
     query.bind(offset as i64);
     query.bind(limit as i64);
 
-    let sets = executor::run_query(query, client).await?;
+    let sets = metadata_cache::run_query(query, client).await?;
     let mut result_set = sets.into_iter().next().unwrap_or_default();
 
     let (result_set, total) = if result_set.rows.is_empty() {
@@ -322,9 +340,7 @@ async fn fetch_routine_columns_via_sp(
     offset: u64,
     limit: u64,
 ) -> Result<(ResultSet, u64)> {
-    let escaped_schema = meta.schema.replace(']', "]]");
-    let escaped_name = meta.name.replace(']', "]]");
-    let exec_stmt = format!("EXEC [{}].[{}]", escaped_schema, escaped_name);
+    let exec_stmt = format!("EXEC {}.{}", bracket(&meta.schema), bracket(&meta.name));
 
     let sql = r#"
 DECLARE @dfr TABLE (
@@ -369,7 +385,7 @@ ORDER BY rownum;
     query.bind(offset as i64);
     query.bind(limit as i64);
 
-    let sets = match executor::run_query(query, client).await {
+    let sets = match metadata_cache::run_query(query, client).await {
         Ok(s) => s,
         Err(err) => {
             let mut message = format!(
@@ -419,7 +435,7 @@ WHERE error_state IS NOT NULL;
 "#;
     let mut query = Query::new(sql);
     query.bind(meta.object_id);
-    let sets = executor::run_query(query, client).await?;
+    let sets = metadata_cache::run_query(query, client).await?;
     let message = sets
         .first()
         .and_then(|rs| rs.rows.first())
@@ -1,8 +1,12 @@
 use anyhow::Result;
 
-use crate::cli::CliArgs;
+use crate::cli::{self, CliArgs, ModifiedSince};
+use crate::commands::object_lookup;
 use crate::config::OutputFormat;
-use crate::config::{self, CliOverrides, ResolvedConfig};
+use crate::config::{self, CliOverrides, ProfilePolicy, ResolvedConfig};
+use crate::db::audit::{self, AuditContext};
+use crate::db::metadata_cache::{self, MetadataCache};
+use crate::db::types;
 use crate::error::{AppError, ErrorKind};
 use crate::output;
 
@@ -17,15 +21,145 @@ pub fn overrides_from_args(args: &CliArgs) -> CliOverrides {
         user: args.user.clone(),
         password: args.password.clone(),
         timeout_ms: args.timeout_ms,
+        isolation_level: args.isolation_level,
+        lock_timeout_ms: args.lock_timeout_ms,
+        run_id: args.run_id.clone(),
+        command: Some(args.command_path.clone()).filter(|path| !path.is_empty()),
         encrypt: args.encrypt,
         trust_cert: args.trust_cert,
+        time_zone: args.time_zone,
+        source_time_zone: args.source_time_zone,
+        null_display: args.null_display.clone(),
+        booleans: args.booleans,
+        max_cell_width: args.max_cell_width,
+        max_output_chars: args.max_output_chars,
+        binary: args.binary,
+        max_value_bytes: args.max_value_bytes,
+        contract_version: args.contract,
     }
 }
 
 pub fn load_config(args: &CliArgs) -> Result<ResolvedConfig> {
     let overrides = overrides_from_args(args);
-    config::load_from_system(&overrides)
-        .map_err(|err| AppError::new(ErrorKind::Config, err.to_string()).into())
+    let resolved = config::load_from_system(&overrides)
+        .map_err(|err| AppError::new(ErrorKind::Config, err.to_string()))?;
+    apply_config(args, &resolved)?;
+    Ok(resolved)
+}
+
+/// The rest of `load_config` once config is resolved: enforce the profile's policy and install
+/// the process-wide output, audit, and metadata cache settings.
+pub fn apply_config(args: &CliArgs, resolved: &ResolvedConfig) -> Result<()> {
+    enforce_profile_policy(args, resolved)?;
+    types::set_display_time_zone(resolved.settings.output.time_zone);
+    types::set_source_time_zone(resolved.settings.output.source_time_zone);
+    types::set_json_large_integers(resolved.settings.output.json.large_integers);
+    types::set_binary_format(resolved.settings.output.binary);
+    output::classify::set_render_settings(resolved.settings.output.render.clone());
+    output::table::set_table_settings(resolved.settings.output.table.clone());
+    output::theme::set_theme(resolved.settings.output.theme.clone(), args.output.no_color);
+    output::json::set_run_config(
+        resolved.settings.output.json.contract_version,
+        resolved.profile_name.clone(),
+    );
+    audit::configure(
+        &resolved.settings.audit,
+        AuditContext {
+            command: args.command_path.clone(),
+            profile: resolved.profile_name.clone(),
+            allow_write: args.allow_write,
+        },
+    )?;
+    metadata_cache::configure(object_lookup::profile_cache_dir(resolved).and_then(|dir| {
+        MetadataCache::new(
+            &resolved.settings.metadata_cache,
+            dir,
+            &resolved.connection.server,
+            &resolved.connection.database,
+            args.refresh,
+        )
+    }));
+    Ok(())
+}
+
+/// Refuse a command that changes the server unless `--allow-write` was given; `what` says what it
+/// would change (`"schema apply builds ... on the target server"`).
+pub fn require_allow_write(args: &CliArgs, what: &str) -> Result<()> {
+    if args.allow_write {
+        return Ok(());
+    }
+    Err(AppError::new(
+        ErrorKind::Safety,
+        format!("{}; re-run with --allow-write", what),
+    )
+    .into())
+}
+
+/// Refuse the command when the profile `resolved` came from forbids it in its `permissions` block.
+///
+/// `load_config` runs this for every command that reads config; commands that resolve profiles
+/// themselves (`compare`, `verify`) call it once per profile.
+pub fn enforce_profile_policy(args: &CliArgs, resolved: &ResolvedConfig) -> Result<()> {
+    if resolved.policy.is_unrestricted() {
+        return Ok(());
+    }
+    let policy = ProfilePolicy {
+        deny: resolved
+            .policy
+            .deny
+            .iter()
+            .map(|entry| canonical_command_path(entry))
+            .collect(),
+        read_only: resolved.policy.read_only,
+        ..ProfilePolicy::default()
+    };
+    match policy_violation(
+        &policy,
+        &resolved.profile_name,
+        &args.command_path,
+        args.allow_write,
+    ) {
+        Some(message) => Err(AppError::new(ErrorKind::Safety, message).into()),
+        None => Ok(()),
+    }
+}
+
+fn policy_violation(
+    policy: &ProfilePolicy,
+    profile: &str,
+    command_path: &str,
+    allow_write: bool,
+) -> Option<String> {
+    if let Some(entry) = policy.denied_by(command_path) {
+        return Some(format!(
+            "Profile '{}' denies `{}` (permissions.deny: {})",
+            profile, command_path, entry
+        ));
+    }
+    if policy.read_only && allow_write {
+        return Some(format!(
+            "Profile '{}' is read-only; --allow-write is not permitted",
+            profile
+        ));
+    }
+    None
+}
+
+/// Resolve aliases in a deny entry (`procs exec` -> `stored-procs exec`); unknown words stay as written.
+fn canonical_command_path(entry: &str) -> String {
+    let root = cli::build_cli(true);
+    let mut current = &root;
+    let mut words = Vec::new();
+    for word in entry.split_whitespace() {
+        match current.find_subcommand(word) {
+            Some(sub) => {
+                words.push(sub.get_name().to_string());
+                current = sub;
+            }
+            None => words.push(word.to_string()),
+        }
+    }
+    words.join(" ")
 }
 
 pub fn output_format(args: &CliArgs, resolved: &ResolvedConfig) -> OutputFormat {
@@ -36,6 +170,72 @@ pub fn json_pretty(resolved: &ResolvedConfig) -> bool {
     resolved.settings.output.json.pretty
 }
 
+/// `ORDER BY` keys for a metadata listing. With `settings.output.stableOrdering` the
+/// tie-breaker is appended so rows that tie on `keys` (or sort differently under another
+/// collation) still come back in one fixed order.
+pub fn order_keys(resolved: &ResolvedConfig, keys: &str, tie_breaker: &str) -> String {
+    if resolved.settings.output.stable_ordering {
+        format!("{}, {}", keys, tie_breaker)
+    } else {
+        keys.to_string()
+    }
+}
+
+/// List `--out` files after text output.
+pub fn print_written(paths: Option<&[std::path::PathBuf]>) {
+    if let Some(paths) = paths {
+        println!("\nWritten:");
+        for path in paths {
+            println!("- {}", path.display());
+        }
+    }
+}
+
+/// Apply `--save-blobs` before rows are printed or exported. Cells over
+/// `settings.output.maxValueBytes` (else 8000 bytes) move to files in `dir`.
+pub fn save_blobs(
+    dir: Option<&std::path::Path>,
+    sets: &mut [types::ResultSet],
+    resolved: &ResolvedConfig,
+) -> Result<usize> {
+    let Some(dir) = dir else {
+        return Ok(0);
+    };
+    let threshold = resolved
+        .settings
+        .output
+        .max_value_bytes
+        .unwrap_or(output::blobs::BLOB_BYTES_DEFAULT);
+    Ok(output::blobs::save_blobs(dir, sets, threshold)?.len())
+}
+
+/// Note `--save-blobs` files after text output.
+pub fn print_saved_blobs(count: usize, dir: Option<&std::path::Path>) {
+    if let Some(dir) = dir.filter(|_| count > 0) {
+        println!(
+            "
+Saved {} value(s) to {}",
+            count,
+            dir.display()
+        );
+    }
+}
+
+/// List `--to` uploads after text output.
+pub fn print_uploaded(uploaded: Option<&[String]>) {
+    if let Some(urls) = uploaded {
+        println!("\nUploaded:");
+        for url in urls {
+            println!("- {}", url);
+        }
+    }
+}
+
+/// A catalog value as text; NULL and missing values are empty.
+pub fn value_text(value: Option<&types::Value>) -> String {
+    value.map(types::Value::as_csv).unwrap_or_default()
+}
+
 pub fn parse_limit(value: Option<u64>, default: u64, max: u64) -> u64 {
     match value {
         Some(v) if v < 1 => default,
@@ -61,9 +261,110 @@ pub fn normalize_object_input(input: &str) -> (String, Option<String>) {
     }
 }
 
+/// Whether an object argument is a `*`/`?` wildcard pattern rather than a name.
+pub fn is_wildcard(input: &str) -> bool {
+    input.contains(['*', '?'])
+}
+
+/// A `*`/`?` pattern as a `LIKE ... ESCAPE '\'` pattern; other LIKE metacharacters match
+/// literally. A name without wildcards matches only itself.
+pub fn wildcard_to_like(pattern: &str) -> String {
+    let mut like = String::with_capacity(pattern.len());
+    for ch in pattern.chars() {
+        match ch {
+            '*' => like.push('%'),
+            '?' => like.push('_'),
+            '%' | '_' | '[' | '\\' => {
+                like.push('\\');
+                like.push(ch);
+            }
+            _ => like.push(ch),
+        }
+    }
+    like
+}
+
+/// Footer for a pattern that matched more objects than `--max-objects` let through.
+pub fn max_objects_note(shown: usize, matched: usize) -> Option<String> {
+    (matched > shown).then(|| {
+        format!(
+            "Showing {} of {} matching objects; raise --max-objects to see more.",
+            shown, matched
+        )
+    })
+}
+
+/// The start of a `--modified-since` window as a SQL expression over the value bound at
+/// `placeholder`. Durations count back from the server's clock.
+pub fn modified_since_cutoff(since: &ModifiedSince, placeholder: &str) -> String {
+    match since {
+        ModifiedSince::Minutes(_) => format!("DATEADD(MINUTE, -{}, GETDATE())", placeholder),
+        ModifiedSince::At(_) => placeholder.to_string(),
+    }
+}
+
+/// `AND <column> >= <cutoff>` for `--modified-since`; empty when no window was given.
+pub fn modified_since_clause(
+    column: &str,
+    since: Option<&ModifiedSince>,
+    placeholder: &str,
+) -> String {
+    since.map_or_else(String::new, |since| {
+        format!(
+            "AND {} >= {}",
+            column,
+            modified_since_cutoff(since, placeholder)
+        )
+    })
+}
+
+/// Bind the value for `modified_since_cutoff`; nothing when no window was given.
+pub fn bind_modified_since(query: &mut tiberius::Query<'_>, since: Option<&ModifiedSince>) {
+    match since {
+        None => {}
+        // DATEADD rejects a bigint count.
+        Some(ModifiedSince::Minutes(minutes)) => {
+            query.bind(i32::try_from(*minutes).unwrap_or(i32::MAX))
+        }
+        Some(ModifiedSince::At(at)) => query.bind(*at),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::normalize_object_input;
+    use super::{
+        ModifiedSince, ProfilePolicy, canonical_command_path, modified_since_clause,
+        normalize_object_input, policy_violation, wildcard_to_like,
+    };
+
+    #[test]
+    fn profile_policy_denies_commands_and_writes() {
+        let policy = ProfilePolicy {
+            deny: ["procs", "migrate up"]
+                .iter()
+                .map(|entry| canonical_command_path(entry))
+                .collect(),
+            read_only: true,
+            ..ProfilePolicy::default()
+        };
+        assert_eq!(policy.deny, vec!["stored-procs", "migrate up"]);
+
+        let denied = policy_violation(&policy, "prod", "stored-procs", false).expect("denied");
+        assert!(denied.contains("Profile 'prod' denies `stored-procs`"));
+        assert!(policy_violation(&policy, "prod", "migrate up", false).is_some());
+        assert!(policy_violation(&policy, "prod", "migrate status", false).is_none());
+        assert!(policy_violation(&policy, "prod", "tables", false).is_none());
+
+        let write = policy_violation(&policy, "prod", "tables", true).expect("read-only");
+        assert!(write.contains("read-only"));
+    }
+
+    #[test]
+    fn wildcards_become_like_patterns() {
+        assert_eq!(wildcard_to_like("Order*"), "Order%");
+        assert_eq!(wildcard_to_like("tmp_?"), "tmp\\__");
+        assert_eq!(wildcard_to_like("100%"), "100\\%");
+    }
 
     #[test]
     fn strips_brackets_and_extracts_schema() {
@@ -85,4 +386,20 @@ mod tests {
         assert_eq!(name, "table");
         assert!(schema.is_none());
     }
+
+    #[test]
+    fn modified_since_counts_back_on_the_server() {
+        assert_eq!(modified_since_clause("o.modify_date", None, "@P4"), "");
+        assert_eq!(
+            modified_since_clause("o.modify_date", Some(&ModifiedSince::Minutes(60)), "@P4"),
+            "AND o.modify_date >= DATEADD(MINUTE, -@P4, GETDATE())"
+        );
+        let at = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .expect("valid date");
+        assert_eq!(
+            modified_since_clause("o.modify_date", Some(&ModifiedSince::At(at)), "@P4"),
+            "AND o.modify_date >= @P4"
+        );
+    }
 }
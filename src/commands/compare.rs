@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::OnceLock;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use chrono::Local;
@@ -16,15 +16,27 @@ use tiberius::Query;
 use tokio::runtime::Runtime;
 
 use crate::cli::{CliArgs, CompareArgs};
-use crate::commands::common;
-use crate::config::{CliOverrides, ConnectionSettings, OutputFormat, ResolvedConfig, parse_bool};
+use crate::commands::compare_ignore::{Category, Change, IgnoreRules};
+use crate::commands::schema_apply::{self, ModelBatch};
+use crate::commands::{common, dacpac, dependency_order, sql_format, verify};
+use crate::config::{
+    CliOverrides, ConnectionSettings, FormattingSettingsResolved, OutputFormat, ResolvedConfig,
+    parse_bool,
+};
+use crate::db::executor;
+use crate::db::pool::Pool;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
 use crate::db::types::{Column, ResultSet, Value};
-use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind, ExitCode};
 use crate::output::json as json_out;
+use crate::output::patch;
 
 const DEFAULT_SCHEMAS: &[&str] = &["dbo", "web", "rbac", "notification"];
+/// Snapshot queries per side, each given its own connection.
+const SNAPSHOT_SECTIONS: usize = 5;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Snapshot {
     name: String,
@@ -42,6 +54,9 @@ struct ModuleRow {
     name: String,
     r#type: String,
     definition: String,
+    /// `schema.name` of the objects and types the module references; orders apply scripts.
+    #[serde(skip)]
+    depends_on: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -49,12 +64,15 @@ struct ModuleRow {
 struct IndexRow {
     schema_name: String,
     table_name: String,
+    /// Not part of the drift signature, so renamed indexes are not reported; used for scripts.
+    name: String,
     r#type: String,
     is_unique: bool,
     is_primary_key: bool,
     is_unique_constraint: bool,
     key_columns: String,
     include_columns: String,
+    filter_definition: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -104,11 +122,21 @@ struct DiffSet {
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct CompareSummary {
+pub(crate) struct CompareSummary {
     modules: DiffSet,
     indexes: DiffSet,
     constraints: DiffSet,
     tables: DiffSet,
+    /// Differences dropped by the ignore file.
+    #[serde(skip_serializing_if = "is_zero")]
+    ignored: usize,
+    /// Snapshot sections not compared because the fetch was interrupted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    incomplete: Vec<String>,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
 }
 
 /// Execute the `compare` command: fetch snapshots, diff, and emit summary or apply script.
@@ -118,45 +146,149 @@ pub fn run(args: &CliArgs, cmd: &CompareArgs) -> Result<()> {
     let target_profile = cmd.target.clone();
 
     let source_cfg = apply_connection_override(
-        resolve_profile(&base_overrides, source_profile.as_deref())?,
+        resolve_profile(args, &base_overrides, source_profile.as_deref())?,
         &cmd.source_connection,
     )?;
     let target_cfg = apply_connection_override(
-        resolve_profile(&base_overrides, Some(&target_profile))?,
+        resolve_profile(args, &base_overrides, Some(&target_profile))?,
         &cmd.target_connection,
     )?;
 
-    let schemas = resolve_schemas(cmd, &source_cfg, &target_cfg);
+    let object_filter = ObjectFilter::from_args(cmd)?;
+    let ignore_rules = IgnoreRules::load(cmd.ignore_file.as_deref(), &std::env::current_dir()?)?;
     let rt = Runtime::new()?;
 
     let output_format = common::output_format(args, &source_cfg);
     let json_pretty = common::json_pretty(&source_cfg);
+    let fetch_options = FetchOptions {
+        query_timeout: cmd.query_timeout_ms.map(Duration::from_millis),
+        progress: !args.quiet,
+    };
 
-    let (source_snap, target_snap) = rt.block_on(async {
-        tokio::try_join!(
-            fetch_snapshot(&source_cfg.profile_name, &source_cfg.connection, &schemas),
-            fetch_snapshot(&target_cfg.profile_name, &target_cfg.connection, &schemas),
-        )
-    })?;
+    let mut incomplete = Vec::new();
+    let (mut source_snap, mut target_snap) = match cmd.source_dacpac.as_deref() {
+        Some(path) => {
+            let (name, batches) = dacpac_model(path, fetch_options.progress)?;
+            let shadow = cmd
+                .shadow_database
+                .clone()
+                .unwrap_or_else(schema_apply::shadow_name);
+            schema_apply::allow_shadow(
+                args,
+                &target_cfg,
+                "compare --source-dacpac",
+                &shadow,
+                batches.len(),
+            )?;
+            rt.block_on(dacpac_snapshots(
+                &name,
+                &batches,
+                &shadow,
+                &target_cfg,
+                explicit_schemas(cmd),
+                fetch_options,
+            ))?
+        }
+        None => {
+            let schemas = resolve_schemas(cmd, &source_cfg, &target_cfg);
+            let source_fetched = FetchedSections::default();
+            let target_fetched = FetchedSections::default();
+            let started = Instant::now();
+            let interrupted = rt.block_on(async {
+                let fetch = async {
+                    tokio::try_join!(
+                        fetch_sections(
+                            &source_cfg.profile_name,
+                            &source_cfg.connection,
+                            &schemas,
+                            fetch_options,
+                            &source_fetched,
+                        ),
+                        fetch_sections(
+                            &target_cfg.profile_name,
+                            &target_cfg.connection,
+                            &schemas,
+                            fetch_options,
+                            &target_fetched,
+                        ),
+                    )
+                };
+                // Dropping the fetch on Ctrl+C closes every snapshot connection on both sides.
+                tokio::select! {
+                    fetched = fetch => fetched.map(|_| false),
+                    _ = tokio::signal::ctrl_c() => Ok(true),
+                }
+            })?;
+            let mut source_snap = build_snapshot(&source_cfg.profile_name, &source_fetched);
+            let mut target_snap = build_snapshot(&target_cfg.profile_name, &target_fetched);
+            if interrupted {
+                incomplete = missing_sections(&source_fetched, &target_fetched);
+                clear_sections(&mut source_snap, &incomplete);
+                clear_sections(&mut target_snap, &incomplete);
+                eprintln!(
+                    "Interrupted after {:.1}s; incomplete compare, not fetched on both sides: {}",
+                    started.elapsed().as_secs_f64(),
+                    incomplete.join(", ")
+                );
+            }
+            (source_snap, target_snap)
+        }
+    };
+    if cmd.format_sql {
+        let style = source_cfg.settings.formatting;
+        format_modules(&mut source_snap, &style);
+        format_modules(&mut target_snap, &style);
+    }
+    if !incomplete.is_empty() && (cmd.object.is_some() || cmd.apply_script) {
+        eprintln!("Compare was interrupted; no object diff or apply script was produced.");
+        ExitCode::Interrupted.exit();
+    }
 
     if let Some(object) = &cmd.object {
         handle_object_diff(args, cmd, &source_snap, &target_snap, object)?;
         return Ok(());
     }
 
-    let summary = summarize(
+    let mut summary = summarize(
         &source_snap,
         &target_snap,
         cmd.ignore_whitespace,
         cmd.strip_comments,
+        &ignore_rules,
     );
+    object_filter.apply(&mut summary);
+    summary.ignored = apply_ignore_rules(&mut summary, &ignore_rules);
+    if summary.ignored > 0 && !args.quiet {
+        eprintln!(
+            "{} difference{} ignored by {}",
+            summary.ignored,
+            if summary.ignored == 1 { "" } else { "s" },
+            ignore_rules
+                .path
+                .as_deref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        );
+    }
 
     if cmd.apply_script {
-        let script = render_apply_script(&summary, &source_snap, &target_snap, cmd.include_drops);
+        let keys = KeyOptions {
+            ignore_whitespace: cmd.ignore_whitespace,
+            strip_comments: cmd.strip_comments,
+            rules: &ignore_rules,
+        };
+        let script = render_apply_script(
+            &summary,
+            &source_snap,
+            &target_snap,
+            cmd.include_drops,
+            &keys,
+        );
         write_apply_script(cmd.apply_path.as_deref(), &script)?;
         return Ok(());
     }
 
+    summary.incomplete = incomplete;
     if cmd.summary {
         output_summary(
             args,
@@ -167,19 +299,25 @@ pub fn run(args: &CliArgs, cmd: &CompareArgs) -> Result<()> {
             output_format,
             json_pretty,
         )?;
+        if !summary.incomplete.is_empty() {
+            ExitCode::Interrupted.exit();
+        }
         let drifted = has_drift(&summary);
         if drifted {
-            std::process::exit(3);
+            ExitCode::CheckFailed.exit();
         }
         return Ok(());
     }
 
     // Full snapshot output
     if matches!(output_format, OutputFormat::Json) || args.output.json {
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "source": source_snap,
             "target": target_snap,
         });
+        if !summary.incomplete.is_empty() {
+            payload["incomplete"] = serde_json::json!(summary.incomplete);
+        }
         let body = json_out::emit_json_value(&payload, json_pretty)?;
         if !args.quiet {
             println!("{body}");
@@ -189,16 +327,72 @@ pub fn run(args: &CliArgs, cmd: &CompareArgs) -> Result<()> {
         println!("Use --json or --summary for readable output.");
     }
 
+    if !summary.incomplete.is_empty() {
+        ExitCode::Interrupted.exit();
+    }
     Ok(())
 }
 
-fn resolve_profile(base: &CliOverrides, profile: Option<&str>) -> Result<ResolvedConfig> {
+/// Drift from a current database to a desired one, and the script that applies it.
+pub(crate) struct ApplyPlan {
+    pub(crate) summary: CompareSummary,
+    pub(crate) script: String,
+}
+
+impl ApplyPlan {
+    pub(crate) fn has_drift(&self) -> bool {
+        has_drift(&self.summary)
+    }
+
+    pub(crate) fn counts_table(&self, format: OutputFormat) -> String {
+        render_counts_table(&self.summary, format)
+    }
+}
+
+/// Snapshot `desired` and `current` in parallel and plan the changes that align `current`.
+///
+/// This is the compare engine behind `schema apply`; definitions are compared with whitespace
+/// and comments normalized, and `rules` suppress accepted drift as in `compare`.
+pub(crate) async fn plan_apply(
+    desired: (&str, &ConnectionSettings),
+    current: (&str, &ConnectionSettings),
+    schemas: &[String],
+    include_drops: bool,
+    rules: &IgnoreRules,
+    progress: bool,
+) -> Result<ApplyPlan> {
+    let options = FetchOptions {
+        query_timeout: None,
+        progress,
+    };
+    let (desired_snap, current_snap) = tokio::try_join!(
+        fetch_snapshot(desired.0, desired.1, schemas, options),
+        fetch_snapshot(current.0, current.1, schemas, options),
+    )?;
+    let mut summary = summarize(&desired_snap, &current_snap, true, true, rules);
+    summary.ignored = apply_ignore_rules(&mut summary, rules);
+    let keys = KeyOptions {
+        ignore_whitespace: true,
+        strip_comments: true,
+        rules,
+    };
+    let script = render_apply_script(&summary, &desired_snap, &current_snap, include_drops, &keys);
+    Ok(ApplyPlan { summary, script })
+}
+
+pub(crate) fn resolve_profile(
+    args: &CliArgs,
+    base: &CliOverrides,
+    profile: Option<&str>,
+) -> Result<ResolvedConfig> {
     let mut overrides = base.clone();
     overrides.profile = profile.map(str::to_string);
-    crate::config::load_from_system(&overrides)
+    let resolved = crate::config::load_from_system(&overrides)?;
+    common::enforce_profile_policy(args, &resolved)?;
+    Ok(resolved)
 }
 
-fn apply_connection_override(
+pub(crate) fn apply_connection_override(
     resolved: ResolvedConfig,
     connection_override: &Option<String>,
 ) -> Result<ResolvedConfig> {
@@ -212,17 +406,270 @@ fn apply_connection_override(
     Ok(resolved)
 }
 
+/// A dacpac's `model.xml` scripted into batches, named after the package file.
+fn dacpac_model(path: &Path, progress: bool) -> Result<(String, Vec<ModelBatch>)> {
+    let model = dacpac::read_model(path)?;
+    if progress && !model.skipped.is_empty() {
+        let skipped = model
+            .skipped
+            .iter()
+            .map(|(kind, count)| format!("{} ({})", kind, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        eprintln!("Warning: dacpac elements not compared: {}", skipped);
+    }
+    let batches: Vec<ModelBatch> = model
+        .scripts
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, sql))| ModelBatch {
+            file: path.to_path_buf(),
+            index: index + 1,
+            sql,
+        })
+        .collect();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "dacpac".to_string());
+    Ok((name, batches))
+}
+
+/// Snapshot of a dacpac's model, built in the `shadow` database on the target's server, and of
+/// the target. Without `--schema`, both sides cover the schemas the model uses.
+async fn dacpac_snapshots(
+    name: &str,
+    batches: &[ModelBatch],
+    shadow: &str,
+    target: &ResolvedConfig,
+    schemas: Option<Vec<String>>,
+    options: FetchOptions,
+) -> Result<(Snapshot, Snapshot)> {
+    schema_apply::with_shadow(
+        &target.connection,
+        shadow,
+        batches,
+        schemas,
+        options.progress,
+        async |_: &mut SqlClient, shadow_settings: &ConnectionSettings, schemas| {
+            tokio::try_join!(
+                fetch_snapshot(name, shadow_settings, &schemas, options),
+                fetch_snapshot(&target.profile_name, &target.connection, &schemas, options),
+            )
+        },
+    )
+    .await
+}
+
+/// Tables, keys, indexes and constraints of `schemas` for a dacpac package.
+pub(crate) async fn export_snapshot(
+    name: &str,
+    settings: &ConnectionSettings,
+    schemas: &[String],
+    progress: bool,
+) -> Result<dacpac::Export> {
+    let options = FetchOptions {
+        query_timeout: None,
+        progress,
+    };
+    let snapshot = fetch_snapshot(name, settings, schemas, options).await?;
+    Ok(export_from_snapshot(&snapshot))
+}
+
+fn export_from_snapshot(snapshot: &Snapshot) -> dacpac::Export {
+    let mut export = dacpac::Export::default();
+
+    let mut columns: Vec<&TableColumnRow> = snapshot.table_columns.iter().collect();
+    columns.sort_by(|a, b| {
+        (&a.schema_name, &a.table_name, a.column_id).cmp(&(
+            &b.schema_name,
+            &b.table_name,
+            b.column_id,
+        ))
+    });
+    for col in columns {
+        let column = dacpac::ExportColumn {
+            name: col.column_name.clone(),
+            data_type: col.data_type.clone(),
+            max_length: col.max_length,
+            precision: col.precision,
+            scale: col.scale,
+            nullable: col.is_nullable,
+            identity: col.is_identity,
+            default: non_empty(&col.default_definition),
+            computed: non_empty(&col.computed_definition),
+        };
+        match export.tables.last_mut() {
+            Some(table) if table.schema == col.schema_name && table.name == col.table_name => {
+                table.columns.push(column)
+            }
+            _ => export.tables.push(dacpac::ExportTable {
+                schema: col.schema_name.clone(),
+                name: col.table_name.clone(),
+                columns: vec![column],
+            }),
+        }
+    }
+
+    for idx in &snapshot.indexes {
+        let clustered = match idx.r#type.as_str() {
+            "CLUSTERED" => true,
+            "NONCLUSTERED" => false,
+            other => {
+                *export
+                    .skipped
+                    .entry(format!("{} INDEX", other))
+                    .or_default() += 1;
+                continue;
+            }
+        };
+        let kind = if idx.is_primary_key {
+            dacpac::IndexKind::PrimaryKey
+        } else if idx.is_unique_constraint {
+            dacpac::IndexKind::Unique
+        } else {
+            dacpac::IndexKind::Index
+        };
+        let keys = idx
+            .key_columns
+            .split(',')
+            .filter(|k| !k.is_empty())
+            .map(|k| match k.rsplit_once(' ') {
+                Some((column, direction)) => (column.to_string(), direction == "DESC"),
+                None => (k.to_string(), false),
+            })
+            .collect();
+        export.indexes.push(dacpac::ExportIndex {
+            schema: idx.schema_name.clone(),
+            table: idx.table_name.clone(),
+            name: idx.name.clone(),
+            kind,
+            unique: idx.is_unique,
+            clustered,
+            keys,
+            includes: idx
+                .include_columns
+                .split(',')
+                .filter(|c| !c.is_empty())
+                .map(str::to_string)
+                .collect(),
+            filter: non_empty(&idx.filter_definition),
+        });
+    }
+
+    for con in &snapshot.constraints {
+        match con.r#type.as_str() {
+            "FK" => match parse_foreign_key(con) {
+                Some(fk) => export.foreign_keys.push(fk),
+                None => *export.skipped.entry("FOREIGN KEY".to_string()).or_default() += 1,
+            },
+            "CHECK" => export.checks.push(dacpac::ExportCheck {
+                schema: con.schema_name.clone(),
+                table: con.table_name.clone(),
+                name: con.name.clone(),
+                expression: con.definition.clone(),
+            }),
+            // Keys come from their indexes and defaults from their columns.
+            _ => {}
+        }
+    }
+
+    // Referenced tables first, so the model lists tables in an order they can be created in.
+    let positions: HashMap<(String, String), usize> = dependency_order::sort(
+        &export.tables,
+        |table| format!("{}.{}", table.schema, table.name),
+        |table| {
+            export
+                .foreign_keys
+                .iter()
+                .filter(|fk| fk.schema == table.schema && fk.table == table.name)
+                .map(|fk| format!("{}.{}", fk.referenced_schema, fk.referenced_table))
+                .collect()
+        },
+    )
+    .ordered
+    .iter()
+    .enumerate()
+    .map(|(position, table)| ((table.schema.clone(), table.name.clone()), position))
+    .collect();
+    export
+        .tables
+        .sort_by_key(|table| positions[&(table.schema.clone(), table.name.clone())]);
+
+    for module in &snapshot.modules {
+        *export
+            .skipped
+            .entry(type_keyword(&module.r#type).to_string())
+            .or_default() += 1;
+    }
+    export
+}
+
+/// Split the `FOREIGN KEY (...) REFERENCES [s].[t] (...) ON ...` text the snapshot renders.
+fn parse_foreign_key(row: &ConstraintRow) -> Option<dacpac::ExportForeignKey> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"^FOREIGN KEY \((.*?)\) REFERENCES (\[(?:[^\]]|\]\])*\]\.\[(?:[^\]]|\]\])*\]) \((.*?)\)(.*)$")
+            .expect("valid regex")
+    });
+    let caps = re.captures(&row.definition)?;
+    let referenced = bracketed_names(&caps[2]);
+    let [referenced_schema, referenced_table] = <[String; 2]>::try_from(referenced).ok()?;
+    let action = |clause: &str| {
+        let rest = &caps[4];
+        let idx = rest.find(clause)? + clause.len();
+        let action = rest[idx..].trim_start();
+        Some(if action.starts_with("CASCADE") {
+            1
+        } else if action.starts_with("SET NULL") {
+            2
+        } else {
+            3
+        })
+    };
+    Some(dacpac::ExportForeignKey {
+        schema: row.schema_name.clone(),
+        table: row.table_name.clone(),
+        name: row.name.clone(),
+        columns: bracketed_names(&caps[1]),
+        referenced_schema,
+        referenced_table,
+        referenced_columns: bracketed_names(&caps[3]),
+        on_delete: action("ON DELETE").unwrap_or(0),
+        on_update: action("ON UPDATE").unwrap_or(0),
+    })
+}
+
+fn bracketed_names(text: &str) -> Vec<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"\[((?:[^\]]|\]\])*)\]").expect("valid regex"));
+    re.captures_iter(text)
+        .map(|c| c[1].replace("]]", "]"))
+        .collect()
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Schemas named with `--schema`, if any.
+fn explicit_schemas(cmd: &CompareArgs) -> Option<Vec<String>> {
+    let list = cmd.schemas.as_ref()?;
+    let schemas: Vec<String> = list
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!schemas.is_empty()).then_some(schemas)
+}
+
 fn resolve_schemas(
     cmd: &CompareArgs,
     source: &ResolvedConfig,
     target: &ResolvedConfig,
 ) -> Vec<String> {
-    if let Some(list) = &cmd.schemas {
-        let mut schemas: Vec<String> = list.iter().map(|s| s.trim().to_string()).collect();
-        schemas.retain(|s| !s.is_empty());
-        if !schemas.is_empty() {
-            return schemas;
-        }
+    if let Some(schemas) = explicit_schemas(cmd) {
+        return schemas;
     }
 
     if !source.connection.default_schemas.is_empty() {
@@ -235,34 +682,142 @@ fn resolve_schemas(
     DEFAULT_SCHEMAS.iter().map(|s| s.to_string()).collect()
 }
 
+/// How snapshot queries are run: per-query limit and whether to report progress on stderr.
+#[derive(Debug, Clone, Copy)]
+struct FetchOptions {
+    query_timeout: Option<Duration>,
+    progress: bool,
+}
+
+/// Result sets of one side's snapshot queries, keyed by section, as each one completes.
+///
+/// Kept outside the fetch so an interrupted compare can still report what arrived.
+type FetchedSections = std::sync::Mutex<HashMap<&'static str, Vec<ResultSet>>>;
+
+/// Section labels in the order they are queried.
+const SECTION_LABELS: [&str; SNAPSHOT_SECTIONS] =
+    ["modules", "indexes", "constraints", "tables", "columns"];
+
+/// Fetch one side's metadata, running the five section queries on separate connections.
 async fn fetch_snapshot(
     name: &str,
     settings: &ConnectionSettings,
     schemas: &[String],
+    options: FetchOptions,
 ) -> Result<Snapshot> {
-    let mut client = client::connect(settings).await?;
-    let sql = build_sql(schemas);
+    let fetched = FetchedSections::default();
+    fetch_sections(name, settings, schemas, options, &fetched).await?;
+    Ok(build_snapshot(name, &fetched))
+}
 
-    let modules_rs = executor::run_query(Query::new(sql.modules), &mut client).await?;
-    let indexes_rs = executor::run_query(Query::new(sql.indexes), &mut client).await?;
-    let constraints_rs = executor::run_query(Query::new(sql.constraints), &mut client).await?;
-    let tables_rs = executor::run_query(Query::new(sql.tables), &mut client).await?;
-    let cols_rs = executor::run_query(Query::new(sql.table_columns), &mut client).await?;
+async fn fetch_sections(
+    name: &str,
+    settings: &ConnectionSettings,
+    schemas: &[String],
+    options: FetchOptions,
+    fetched: &FetchedSections,
+) -> Result<()> {
+    let started = Instant::now();
+    let pool = &Pool::new(settings, SNAPSHOT_SECTIONS);
+    let sql = build_sql(schemas);
+    let section = |label: &'static str, sql: String| async move {
+        let result_sets = fetch_section(pool, name, label, sql, options).await?;
+        fetched
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(label, result_sets);
+        Ok::<_, anyhow::Error>(())
+    };
 
-    let modules = map_modules(modules_rs.first());
-    let indexes = map_indexes(indexes_rs.first());
-    let constraints = map_constraints(constraints_rs.first());
-    let tables = map_tables(tables_rs.first());
-    let table_columns = map_table_columns(cols_rs.first());
+    let [modules, indexes, constraints, tables, columns] = SECTION_LABELS;
+    tokio::try_join!(
+        section(modules, sql.modules),
+        section(indexes, sql.indexes),
+        section(constraints, sql.constraints),
+        section(tables, sql.tables),
+        section(columns, sql.table_columns),
+    )?;
+    if options.progress {
+        eprintln!(
+            "{}: snapshot fetched ({:.1}s)",
+            name,
+            started.elapsed().as_secs_f64()
+        );
+    }
+    Ok(())
+}
 
-    Ok(Snapshot {
+/// Map whatever sections have arrived; a missing section leaves its rows empty.
+fn build_snapshot(name: &str, fetched: &FetchedSections) -> Snapshot {
+    let fetched = fetched.lock().unwrap_or_else(|e| e.into_inner());
+    let first = |label: &str| fetched.get(label).and_then(|sets| sets.first());
+    Snapshot {
         name: name.to_string(),
-        modules,
-        indexes,
-        constraints,
-        tables,
-        table_columns,
-    })
+        modules: map_modules(first("modules")),
+        indexes: map_indexes(first("indexes")),
+        constraints: map_constraints(first("constraints")),
+        tables: map_tables(first("tables")),
+        table_columns: map_table_columns(first("columns")),
+    }
+}
+
+/// Sections missing from either side, in query order.
+fn missing_sections(left: &FetchedSections, right: &FetchedSections) -> Vec<String> {
+    let left = left.lock().unwrap_or_else(|e| e.into_inner());
+    let right = right.lock().unwrap_or_else(|e| e.into_inner());
+    SECTION_LABELS
+        .iter()
+        .filter(|label| !left.contains_key(*label) || !right.contains_key(*label))
+        .map(|label| label.to_string())
+        .collect()
+}
+
+/// Empty the sections that did not arrive on both sides so they compare as equal.
+fn clear_sections(snapshot: &mut Snapshot, missing: &[String]) {
+    for label in missing {
+        match label.as_str() {
+            "modules" => snapshot.modules.clear(),
+            "indexes" => snapshot.indexes.clear(),
+            "constraints" => snapshot.constraints.clear(),
+            "tables" => snapshot.tables.clear(),
+            _ => snapshot.table_columns.clear(),
+        }
+    }
+}
+
+async fn fetch_section(
+    pool: &Arc<Pool>,
+    side: &str,
+    label: &str,
+    sql: String,
+    options: FetchOptions,
+) -> Result<Vec<ResultSet>> {
+    let started = Instant::now();
+    let mut client = pool.get().await?;
+    let query = client.run(async move |client| executor::run_query(Query::new(sql), client).await);
+    let result_sets = match options.query_timeout {
+        Some(limit) => tokio::time::timeout(limit, query).await.map_err(|_| {
+            AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "{}: {} query did not finish within {:.0?}; raise --query-timeout or narrow --schema",
+                    side, label, limit
+                ),
+            )
+        })??,
+        None => query.await?,
+    };
+    if options.progress {
+        let rows: usize = result_sets.iter().map(|rs| rs.rows.len()).sum();
+        eprintln!(
+            "{}: {}: {} fetched ({:.1}s)",
+            side,
+            label,
+            rows,
+            started.elapsed().as_secs_f64()
+        );
+    }
+    Ok(result_sets)
 }
 
 struct SnapshotSql {
@@ -282,10 +837,25 @@ fn build_sql(schemas: &[String]) -> SnapshotSql {
 
     let modules = format!(
         "
-        SELECT s.name AS schema_name, o.name, o.type, ISNULL(sm.definition, N'') AS definition
+        SELECT s.name AS schema_name, o.name, o.type, ISNULL(sm.definition, N'') AS definition,
+               deps.depends_on
         FROM sys.objects o
         JOIN sys.schemas s ON s.schema_id = o.schema_id
         LEFT JOIN sys.sql_modules sm ON sm.object_id = o.object_id
+        OUTER APPLY (
+          SELECT STRING_AGG(r.ref, '||') AS depends_on
+          FROM (
+            SELECT DISTINCT CASE
+                     WHEN d.referenced_class = 1 AND d.referenced_id IS NOT NULL
+                       THEN CONCAT(OBJECT_SCHEMA_NAME(d.referenced_id), '.', OBJECT_NAME(d.referenced_id))
+                     ELSE CONCAT(ISNULL(d.referenced_schema_name, s.name), '.', d.referenced_entity_name)
+                   END AS ref
+            FROM sys.sql_expression_dependencies d
+            WHERE d.referencing_id = o.object_id
+              AND d.referenced_database_name IS NULL
+              AND d.referenced_class IN (1, 6)
+          ) r
+        ) deps
         WHERE s.name IN ({schema_list})
           AND o.type IN ('P','V','FN','IF','TF','TR')
         ORDER BY s.name, o.name, o.type;
@@ -390,7 +960,8 @@ fn build_sql(schemas: &[String]) -> SnapshotSql {
                i.is_primary_key,
                i.is_unique_constraint,
                key_cols.keys AS key_columns,
-               include_cols.includes AS include_columns
+               include_cols.includes AS include_columns,
+               i.filter_definition
         FROM sys.indexes i
           JOIN sys.tables t ON t.object_id = i.object_id
           JOIN sys.schemas s ON s.schema_id = t.schema_id
@@ -424,10 +995,29 @@ fn build_sql(schemas: &[String]) -> SnapshotSql {
                o.name AS table_name,
                fk.name AS name,
                'FK' AS type,
-               OBJECT_DEFINITION(fk.object_id) AS definition
+               CONCAT(
+                 'FOREIGN KEY (', fk_cols.parent_cols, ') REFERENCES ',
+                 QUOTENAME(rs.name), '.', QUOTENAME(rt.name), ' (', fk_cols.ref_cols, ')',
+                 CASE fk.delete_referential_action
+                   WHEN 1 THEN ' ON DELETE CASCADE' WHEN 2 THEN ' ON DELETE SET NULL'
+                   WHEN 3 THEN ' ON DELETE SET DEFAULT' ELSE '' END,
+                 CASE fk.update_referential_action
+                   WHEN 1 THEN ' ON UPDATE CASCADE' WHEN 2 THEN ' ON UPDATE SET NULL'
+                   WHEN 3 THEN ' ON UPDATE SET DEFAULT' ELSE '' END
+               ) AS definition
         FROM sys.foreign_keys fk
           JOIN sys.objects o ON o.object_id = fk.parent_object_id
           JOIN sys.schemas s ON s.schema_id = o.schema_id
+          JOIN sys.objects rt ON rt.object_id = fk.referenced_object_id
+          JOIN sys.schemas rs ON rs.schema_id = rt.schema_id
+          CROSS APPLY (
+            SELECT STRING_AGG(QUOTENAME(pc.name), ', ') WITHIN GROUP (ORDER BY fkc.constraint_column_id) AS parent_cols,
+                   STRING_AGG(QUOTENAME(rc.name), ', ') WITHIN GROUP (ORDER BY fkc.constraint_column_id) AS ref_cols
+            FROM sys.foreign_key_columns fkc
+              JOIN sys.columns pc ON pc.object_id = fkc.parent_object_id AND pc.column_id = fkc.parent_column_id
+              JOIN sys.columns rc ON rc.object_id = fkc.referenced_object_id AND rc.column_id = fkc.referenced_column_id
+            WHERE fkc.constraint_object_id = fk.object_id
+          ) fk_cols
         WHERE s.name IN ({schema_list})
         UNION ALL
         SELECT s.name AS schema_name,
@@ -481,6 +1071,7 @@ fn map_modules(rs: Option<&ResultSet>) -> Vec<ModuleRow> {
     let idx_name = col_idx(&rs.columns, "name");
     let idx_type = col_idx(&rs.columns, "type");
     let idx_def = col_idx(&rs.columns, "definition");
+    let idx_deps = col_idx(&rs.columns, "depends_on");
 
     rs.rows
         .iter()
@@ -489,6 +1080,11 @@ fn map_modules(rs: Option<&ResultSet>) -> Vec<ModuleRow> {
             name: get_text(row, idx_name),
             r#type: get_text(row, idx_type).trim().to_uppercase(),
             definition: get_text(row, idx_def),
+            depends_on: get_text(row, idx_deps)
+                .split("||")
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect(),
         })
         .collect()
 }
@@ -504,20 +1100,24 @@ fn map_indexes(rs: Option<&ResultSet>) -> Vec<IndexRow> {
     let idx_unique = col_idx(&rs.columns, "is_unique");
     let idx_pk = col_idx(&rs.columns, "is_primary_key");
     let idx_unique_const = col_idx(&rs.columns, "is_unique_constraint");
+    let idx_name = col_idx(&rs.columns, "index");
     let idx_keys = col_idx(&rs.columns, "key_columns");
     let idx_inc = col_idx(&rs.columns, "include_columns");
+    let idx_filter = col_idx(&rs.columns, "filter_definition");
 
     rs.rows
         .iter()
         .map(|row| IndexRow {
             schema_name: get_text(row, idx_schema),
             table_name: get_text(row, idx_table),
+            name: get_text(row, idx_name),
             r#type: get_text(row, idx_type_desc),
             is_unique: get_bool(row, idx_unique),
             is_primary_key: get_bool(row, idx_pk),
             is_unique_constraint: get_bool(row, idx_unique_const),
             key_columns: get_text(row, idx_keys),
             include_columns: get_text(row, idx_inc),
+            filter_definition: get_text(row, idx_filter),
         })
         .collect()
 }
@@ -616,6 +1216,12 @@ fn get_text(row: &[Value], idx: Option<usize>) -> String {
             Value::Int(i) => i.to_string(),
             Value::Float(f) => f.to_string(),
             Value::Bool(b) => b.to_string(),
+            Value::DateTime(ts) => ts.to_iso8601(),
+            Value::Decimal(_)
+            | Value::Date(_)
+            | Value::Time(_)
+            | Value::Uuid(_)
+            | Value::Bytes(_) => v.as_csv(),
             Value::Null => "".to_string(),
         })
         .unwrap_or_default()
@@ -634,7 +1240,13 @@ fn get_int(row: &[Value], idx: Option<usize>) -> i64 {
                 }
             }
             Value::Text(t) => t.parse::<i64>().unwrap_or(0),
-            Value::Null => 0,
+            Value::Decimal(d) => d.to_f64() as i64,
+            Value::DateTime(_)
+            | Value::Date(_)
+            | Value::Time(_)
+            | Value::Uuid(_)
+            | Value::Bytes(_)
+            | Value::Null => 0,
         })
         .unwrap_or(0)
 }
@@ -646,11 +1258,24 @@ fn get_bool(row: &[Value], idx: Option<usize>) -> bool {
             Value::Int(i) => *i != 0,
             Value::Float(f) => *f != 0.0,
             Value::Text(t) => matches!(t.as_str(), "1" | "true" | "True" | "TRUE"),
-            Value::Null => false,
+            Value::Decimal(d) => d.value != 0,
+            Value::DateTime(_)
+            | Value::Date(_)
+            | Value::Time(_)
+            | Value::Uuid(_)
+            | Value::Bytes(_)
+            | Value::Null => false,
         })
         .unwrap_or(false)
 }
 
+/// Reformat module definitions so layout-only edits do not show as drift (`--format-sql`).
+fn format_modules(snapshot: &mut Snapshot, style: &FormattingSettingsResolved) {
+    for module in &mut snapshot.modules {
+        module.definition = sql_format::format_sql(&module.definition, style);
+    }
+}
+
 fn normalize_definition(definition: &str, ignore_whitespace: bool, strip_comments: bool) -> String {
     let mut d = definition.replace("\r\n", "\n");
     if strip_comments {
@@ -689,29 +1314,48 @@ fn build_module_map(
     rows: &[ModuleRow],
     ignore_whitespace: bool,
     strip_comments: bool,
+    rules: &IgnoreRules,
 ) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for row in rows {
         let key = format!("{}.{}.{}", row.schema_name, row.r#type, row.name);
-        let value = normalize_definition(&row.definition, ignore_whitespace, strip_comments);
+        let definition = rules.strip_definition(
+            Category::Modules,
+            &row.schema_name,
+            &row.name,
+            &row.definition,
+        );
+        let value = normalize_definition(&definition, ignore_whitespace, strip_comments);
         map.insert(key, value);
     }
     map
 }
 
+fn index_signature(row: &IndexRow) -> serde_json::Value {
+    serde_json::json!({
+        "type": row.r#type,
+        "unique": row.is_unique,
+        "primaryKey": row.is_primary_key,
+        "uniqueConstraint": row.is_unique_constraint,
+        "keyColumns": row.key_columns,
+        "includeColumns": row.include_columns,
+        "filter": row.filter_definition,
+    })
+}
+
+fn index_key(row: &IndexRow) -> String {
+    format!(
+        "{}.{}::{}",
+        row.schema_name,
+        row.table_name,
+        index_signature(row)
+    )
+}
+
 fn build_index_map(rows: &[IndexRow]) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for row in rows {
-        let signature = serde_json::json!({
-            "type": row.r#type,
-            "unique": row.is_unique,
-            "primaryKey": row.is_primary_key,
-            "uniqueConstraint": row.is_unique_constraint,
-            "keyColumns": row.key_columns,
-            "includeColumns": row.include_columns,
-        });
-        let key = format!("{}.{}::{}", row.schema_name, row.table_name, signature);
-        map.insert(key, signature.to_string());
+        map.insert(index_key(row), index_signature(row).to_string());
     }
     map
 }
@@ -720,19 +1364,35 @@ fn build_constraint_map(
     rows: &[ConstraintRow],
     ignore_whitespace: bool,
     strip_comments: bool,
+    rules: &IgnoreRules,
 ) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for row in rows {
-        let def = normalize_definition(&row.definition, ignore_whitespace, strip_comments);
-        let key = format!(
-            "{}.{}.{}::{}",
-            row.schema_name, row.table_name, row.r#type, def
-        );
+        let key = constraint_key(row, ignore_whitespace, strip_comments, rules);
         map.insert(key.clone(), key);
     }
     map
 }
 
+fn constraint_key(
+    row: &ConstraintRow,
+    ignore_whitespace: bool,
+    strip_comments: bool,
+    rules: &IgnoreRules,
+) -> String {
+    let definition = rules.strip_definition(
+        Category::Constraints,
+        &row.schema_name,
+        &row.table_name,
+        &row.definition,
+    );
+    let def = normalize_definition(&definition, ignore_whitespace, strip_comments);
+    format!(
+        "{}.{}.{}::{}",
+        row.schema_name, row.table_name, row.r#type, def
+    )
+}
+
 fn build_table_map(rows: &[TableRow]) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for row in rows {
@@ -783,13 +1443,16 @@ fn summarize(
     right: &Snapshot,
     ignore_whitespace: bool,
     strip_comments: bool,
+    rules: &IgnoreRules,
 ) -> CompareSummary {
-    let mod_left = build_module_map(&left.modules, ignore_whitespace, strip_comments);
-    let mod_right = build_module_map(&right.modules, ignore_whitespace, strip_comments);
+    let mod_left = build_module_map(&left.modules, ignore_whitespace, strip_comments, rules);
+    let mod_right = build_module_map(&right.modules, ignore_whitespace, strip_comments, rules);
     let idx_left = build_index_map(&left.indexes);
     let idx_right = build_index_map(&right.indexes);
-    let con_left = build_constraint_map(&left.constraints, ignore_whitespace, strip_comments);
-    let con_right = build_constraint_map(&right.constraints, ignore_whitespace, strip_comments);
+    let con_left =
+        build_constraint_map(&left.constraints, ignore_whitespace, strip_comments, rules);
+    let con_right =
+        build_constraint_map(&right.constraints, ignore_whitespace, strip_comments, rules);
     let tbl_left = build_table_map(&left.tables);
     let tbl_right = build_table_map(&right.tables);
 
@@ -798,7 +1461,149 @@ fn summarize(
         indexes: diff_maps(&idx_left, &idx_right),
         constraints: diff_maps(&con_left, &con_right),
         tables: diff_maps(&tbl_left, &tbl_right),
+        ignored: 0,
+        incomplete: Vec::new(),
+    }
+}
+
+/// Extracts `(schema, name)` from a diff key for filter and ignore matching.
+type ObjectOf = fn(&str) -> Option<(String, String)>;
+
+/// Keep only the differences `keep` accepts; returns how many were dropped.
+///
+/// Keys that cannot be split into schema and name are always kept.
+fn retain_differences(
+    summary: &mut CompareSummary,
+    keep: impl Fn(Category, &str, &str, Change) -> bool,
+) -> usize {
+    let mut dropped = 0;
+    let sections: [(&mut DiffSet, Category, ObjectOf); 4] = [
+        (&mut summary.modules, Category::Modules, |key| {
+            parse_module_key(key).map(|(schema, _, name)| (schema.to_string(), name))
+        }),
+        (&mut summary.indexes, Category::Indexes, |key| {
+            parse_index_key(key).and_then(|obj| split_object(&obj))
+        }),
+        (&mut summary.constraints, Category::Constraints, |key| {
+            parse_constraint_key(key).and_then(|(obj, _)| split_object(&obj))
+        }),
+        (&mut summary.tables, Category::Tables, split_object),
+    ];
+    for (diff, category, object_of) in sections {
+        for (keys, change) in [
+            (&mut diff.changed, Change::Changed),
+            (&mut diff.missing_in_right, Change::OnlyInSource),
+            (&mut diff.missing_in_left, Change::OnlyInTarget),
+        ] {
+            let before = keys.len();
+            keys.retain(|key| {
+                object_of(key).is_none_or(|(schema, name)| keep(category, &schema, &name, change))
+            });
+            dropped += before - keys.len();
+        }
+    }
+    dropped
+}
+
+/// Drop differences the ignore file accepts; returns how many were dropped.
+fn apply_ignore_rules(summary: &mut CompareSummary, rules: &IgnoreRules) -> usize {
+    if rules.is_empty() {
+        return 0;
+    }
+    retain_differences(summary, |category, schema, name, change| {
+        !rules.ignores(category, schema, name, change)
+    })
+}
+
+/// `--include`/`--exclude` pattern: a glob like `dbo.*_backup`, or a regex written `/.../`.
+#[derive(Debug)]
+enum NamePattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    fn parse(raw: &str) -> Result<Self> {
+        match raw
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(inner) if !inner.is_empty() => Regex::new(&format!("(?i){}", inner))
+                .map(NamePattern::Regex)
+                .with_context(|| format!("Invalid regex in pattern '{}'", raw)),
+            _ => Ok(NamePattern::Glob(raw.to_string())),
+        }
+    }
+
+    /// Globs follow `verify --tables` (a bare name matches any schema); regexes see `schema.name`.
+    fn matches(&self, schema: &str, name: &str) -> bool {
+        match self {
+            NamePattern::Glob(pattern) => {
+                verify::table_matches(std::slice::from_ref(pattern), schema, name)
+            }
+            NamePattern::Regex(re) => re.is_match(&format!("{}.{}", schema, name)),
+        }
+    }
+}
+
+/// Category and name filters from `--only`, `--include`, and `--exclude`.
+#[derive(Debug, Default)]
+struct ObjectFilter {
+    only: Vec<Category>,
+    include: Vec<NamePattern>,
+    exclude: Vec<NamePattern>,
+}
+
+impl ObjectFilter {
+    fn from_args(cmd: &CompareArgs) -> Result<Self> {
+        let only = cmd
+            .only
+            .iter()
+            .map(|value| match value.as_str() {
+                "modules" => Ok(Category::Modules),
+                "indexes" => Ok(Category::Indexes),
+                "constraints" => Ok(Category::Constraints),
+                "tables" => Ok(Category::Tables),
+                other => Err(anyhow::anyhow!("Unknown --only category '{}'", other)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let parse_all = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| NamePattern::parse(p))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            only,
+            include: parse_all(&cmd.include)?,
+            exclude: parse_all(&cmd.exclude)?,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.include.is_empty() && self.exclude.is_empty()
     }
+
+    fn keeps(&self, category: Category, schema: &str, name: &str) -> bool {
+        (self.only.is_empty() || self.only.contains(&category))
+            && (self.include.is_empty() || self.include.iter().any(|p| p.matches(schema, name)))
+            && !self.exclude.iter().any(|p| p.matches(schema, name))
+    }
+
+    /// Drop differences outside the filter; returns how many were dropped.
+    fn apply(&self, summary: &mut CompareSummary) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        retain_differences(summary, |category, schema, name, _| {
+            self.keeps(category, schema, name)
+        })
+    }
+}
+
+fn split_object(key: &str) -> Option<(String, String)> {
+    key.split_once('.')
+        .map(|(schema, name)| (schema.to_string(), name.to_string()))
 }
 
 fn pretty_summary(left_name: &str, right_name: &str, summary: &CompareSummary) -> String {
@@ -865,6 +1670,13 @@ fn output_summary(
         return Ok(());
     }
 
+    if !summary.incomplete.is_empty() && !args.quiet {
+        println!(
+            "INCOMPLETE: interrupted before {} arrived from both sides; those sections were not compared.\n",
+            summary.incomplete.join(", ")
+        );
+    }
+
     if cmd.compact {
         let pretty = cmd.pretty || args.output.pretty;
         let rendered = match format {
@@ -1173,6 +1985,9 @@ fn handle_object_diff(
     right: &Snapshot,
     object: &str,
 ) -> Result<()> {
+    if object.contains(['*', '?']) || cmd.out.is_some() {
+        return handle_module_set_diff(args, cmd, left, right, object);
+    }
     let left_obj = pick_first_module(left, object);
     let right_obj = pick_first_module(right, object);
 
@@ -1205,9 +2020,23 @@ fn handle_object_diff(
         return Ok(());
     }
 
+    if let Some(target) = &cmd.patch {
+        let module = left_obj
+            .as_ref()
+            .or(right_obj.as_ref())
+            .expect("one side has the module");
+        let body = patch::file_diff(
+            &script_file_name(&module.schema_name, &module.name),
+            left_obj.as_ref().map(|_| raw_left.as_str()),
+            right_obj.as_ref().map(|_| raw_right.as_str()),
+        );
+        write_patch(args, target, &body)?;
+        ExitCode::CheckFailed.exit();
+    }
+
     if let (Some(l), Some(r)) = (left_obj.as_ref(), right_obj.as_ref()) {
         if cmd.gui_diff && try_launch_code_diff(&raw_left, &raw_right, object)? {
-            std::process::exit(3);
+            ExitCode::CheckFailed.exit();
         }
 
         if cmd.side_by_side {
@@ -1216,10 +2045,10 @@ fn handle_object_diff(
                 &diff,
                 &format!("{}:{}.{}", left.name, l.schema_name, l.name),
                 &format!("{}:{}.{}", right.name, r.schema_name, r.name),
-                should_color_stdout(),
+                should_color_stdout(args),
             );
             println!("{rendered}");
-            std::process::exit(3);
+            ExitCode::CheckFailed.exit();
         } else {
             let header_left = format!("{}:{}.{}.{}", left.name, l.schema_name, l.name, l.r#type);
             let header_right = format!("{}:{}.{}.{}", right.name, r.schema_name, r.name, r.r#type);
@@ -1229,7 +2058,7 @@ fn handle_object_diff(
                 .header(&header_left, &header_right)
                 .to_string();
             println!("{diff}");
-            std::process::exit(3);
+            ExitCode::CheckFailed.exit();
         }
     } else {
         println!(
@@ -1249,8 +2078,206 @@ fn handle_object_diff(
                 .unwrap_or_else(|| "missing".to_string())
         );
         println!("{}", raw_right);
-        std::process::exit(3);
+        ExitCode::CheckFailed.exit();
+    }
+}
+
+/// One module's unified diff for a wildcard `--object`.
+#[derive(Debug, Clone, PartialEq)]
+struct ModulePatch {
+    schema: String,
+    name: String,
+    diff: String,
+    /// Raw definitions, `None` where the module is missing; used for `--patch`.
+    left: Option<String>,
+    right: Option<String>,
+}
+
+impl ModulePatch {
+    fn file_name(&self) -> String {
+        format!(
+            "{}.{}.patch",
+            file_safe(&self.schema),
+            file_safe(&self.name)
+        )
+    }
+
+    /// This module's section of a `--patch` file.
+    fn git_diff(&self) -> String {
+        patch::file_diff(
+            &script_file_name(&self.schema, &self.name),
+            self.left.as_deref(),
+            self.right.as_deref(),
+        )
+    }
+}
+
+fn file_safe(part: &str) -> String {
+    part.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Path an object's script goes by inside a `--patch` file: `<schema>.<name>.sql`.
+fn script_file_name(schema: &str, name: &str) -> String {
+    format!("{}.{}.sql", file_safe(schema), file_safe(name))
+}
+
+/// Write a `--patch` body to `target`, or to stdout for `-`.
+fn write_patch(args: &CliArgs, target: &Path, body: &str) -> Result<()> {
+    if target == Path::new("-") {
+        print!("{body}");
+        return Ok(());
+    }
+    fs::write(target, body).with_context(|| format!("Failed to write {}", target.display()))?;
+    if !args.quiet {
+        println!("{}", target.display());
+    }
+    Ok(())
+}
+
+/// The source and target versions of one module, either of which may be missing.
+type ModulePair<'a> = (Option<&'a ModuleRow>, Option<&'a ModuleRow>);
+
+/// Unified diffs for every module matching `pattern` (a `verify --tables` style glob) on
+/// either side, ordered by schema and name. Returns the patches that differ and how many
+/// modules matched.
+fn module_patches(
+    left: &Snapshot,
+    right: &Snapshot,
+    pattern: &str,
+    ignore_whitespace: bool,
+    strip_comments: bool,
+) -> (Vec<ModulePatch>, usize) {
+    let patterns = [pattern.to_string()];
+    let mut matched: BTreeMap<(String, String), ModulePair<'_>> = BTreeMap::new();
+    for (side, snapshot) in [(0, left), (1, right)] {
+        for module in snapshot
+            .modules
+            .iter()
+            .filter(|m| verify::table_matches(&patterns, &m.schema_name, &m.name))
+        {
+            let key = (
+                module.schema_name.to_lowercase(),
+                module.name.to_lowercase(),
+            );
+            let entry = matched.entry(key).or_default();
+            if side == 0 {
+                entry.0 = Some(module);
+            } else {
+                entry.1 = Some(module);
+            }
+        }
+    }
+
+    let total = matched.len();
+    let patches = matched
+        .into_values()
+        .filter_map(|(l, r)| {
+            let normalize = |m: Option<&ModuleRow>| {
+                m.map(|m| normalize_definition(&m.definition, ignore_whitespace, strip_comments))
+            };
+            if l.is_some() && r.is_some() && normalize(l) == normalize(r) {
+                return None;
+            }
+            let raw = |m: Option<&ModuleRow>| {
+                m.map(|m| m.definition.replace("\r\n", "\n"))
+                    .unwrap_or_default()
+            };
+            let header = |snapshot: &Snapshot, m: Option<&ModuleRow>| match m {
+                Some(m) => format!(
+                    "{}:{}.{}.{}",
+                    snapshot.name, m.schema_name, m.name, m.r#type
+                ),
+                None => format!("{}:missing", snapshot.name),
+            };
+            let (raw_left, raw_right) = (raw(l), raw(r));
+            let diff = TextDiff::from_lines(&raw_left, &raw_right)
+                .unified_diff()
+                .context_radius(5)
+                .header(&header(left, l), &header(right, r))
+                .to_string();
+            let module = l.or(r).expect("matched on at least one side");
+            Some(ModulePatch {
+                schema: module.schema_name.clone(),
+                name: module.name.clone(),
+                diff,
+                left: l.map(|_| raw_left),
+                right: r.map(|_| raw_right),
+            })
+        })
+        .collect();
+    (patches, total)
+}
+
+/// `--object` with `*`/`?` (or `--out`): diff every matching module. Prints one combined
+/// unified diff, or writes `<schema>.<name>.patch` files under `--out`. Exits 3 on drift and
+/// 4 when nothing matches, like a single-object diff.
+fn handle_module_set_diff(
+    args: &CliArgs,
+    cmd: &CompareArgs,
+    left: &Snapshot,
+    right: &Snapshot,
+    pattern: &str,
+) -> Result<()> {
+    if cmd.gui_diff || cmd.side_by_side {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "--gui-diff and --side-by-side need a single --object without wildcards or --out",
+        )
+        .into());
+    }
+    let (patches, total) = module_patches(
+        left,
+        right,
+        pattern,
+        cmd.ignore_whitespace,
+        cmd.strip_comments,
+    );
+    if total == 0 {
+        println!("No modules match '{pattern}' on either side.");
+        ExitCode::NotFound.exit();
+    }
+
+    match (&cmd.out, &cmd.patch) {
+        (_, Some(target)) => {
+            let body = patches
+                .iter()
+                .map(ModulePatch::git_diff)
+                .collect::<String>();
+            write_patch(args, target, &body)?;
+        }
+        (Some(dir), None) => {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            for patch in &patches {
+                let path = dir.join(patch.file_name());
+                fs::write(&path, &patch.diff)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                if !args.quiet {
+                    println!("{}", path.display());
+                }
+            }
+        }
+        (None, None) => {
+            for patch in &patches {
+                print!("{}", patch.diff);
+            }
+        }
+    }
+    if !args.quiet {
+        eprintln!("{} of {} matching modules differ.", patches.len(), total);
+    }
+    if !patches.is_empty() {
+        ExitCode::CheckFailed.exit();
     }
+    Ok(())
 }
 
 fn handle_table_object_diff(
@@ -1265,7 +2292,7 @@ fn handle_table_object_diff(
 
     if left_tbl.is_none() && right_tbl.is_none() {
         println!("Object '{object}' not found in either side.");
-        std::process::exit(4);
+        ExitCode::NotFound.exit();
     }
 
     let left_def = left_tbl
@@ -1289,8 +2316,22 @@ fn handle_table_object_diff(
         return Ok(());
     }
 
+    if let Some(target) = &cmd.patch {
+        let table = left_tbl
+            .as_ref()
+            .or(right_tbl.as_ref())
+            .expect("one side has the table");
+        let body = patch::file_diff(
+            &script_file_name(&table.schema_name, &table.table_name),
+            left_tbl.as_ref().map(|_| left_def.as_str()),
+            right_tbl.as_ref().map(|_| right_def.as_str()),
+        );
+        write_patch(args, target, &body)?;
+        ExitCode::CheckFailed.exit();
+    }
+
     if cmd.gui_diff && try_launch_code_diff(&left_def, &right_def, object)? {
-        std::process::exit(3);
+        ExitCode::CheckFailed.exit();
     }
 
     if cmd.side_by_side {
@@ -1315,10 +2356,10 @@ fn handle_table_object_diff(
                     .unwrap_or(""),
                 object_name_only(object)
             ),
-            should_color_stdout(),
+            should_color_stdout(args),
         );
         println!("{rendered}");
-        std::process::exit(3);
+        ExitCode::CheckFailed.exit();
     }
 
     let header_left = left_tbl
@@ -1336,7 +2377,7 @@ fn handle_table_object_diff(
         .header(&header_left, &header_right)
         .to_string();
     println!("{diff}");
-    std::process::exit(3);
+    ExitCode::CheckFailed.exit();
 }
 
 fn type_keyword(code: &str) -> &'static str {
@@ -1644,8 +2685,10 @@ fn clean_line(line: &str) -> String {
         .to_string()
 }
 
-fn should_color_stdout() -> bool {
-    std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+fn should_color_stdout(args: &CliArgs) -> bool {
+    !args.output.no_color
+        && std::env::var_os("NO_COLOR").is_none()
+        && std::io::stdout().is_terminal()
 }
 
 fn column_width() -> usize {
@@ -1821,11 +2864,25 @@ fn render_add_columns(
     lines
 }
 
+/// How diff keys were built, so the script can map keys back to snapshot rows.
+struct KeyOptions<'a> {
+    ignore_whitespace: bool,
+    strip_comments: bool,
+    rules: &'a IgnoreRules,
+}
+
+/// Build a script that aligns target to source.
+///
+/// Statements are ordered so each step's dependencies exist: constraints and indexes being
+/// replaced are dropped first, then tables and columns change, then indexes and constraints are
+/// created (foreign keys last, once referenced keys exist), then modules, each after the modules
+/// it references.
 fn render_apply_script(
     summary: &CompareSummary,
     source: &Snapshot,
     target: &Snapshot,
     include_drops: bool,
+    keys: &KeyOptions<'_>,
 ) -> String {
     let mut source_map = HashMap::new();
     for row in &source.modules {
@@ -1878,31 +2935,68 @@ fn render_apply_script(
         out.push(String::new());
     };
 
-    for key in &summary.modules.changed {
-        if let Some(row) = source_map.get(key) {
-            emit_module(row, "ALTER", &mut module_lines);
-        }
+    // Creates and alters run together so a changed view can use a new one, and drops run in
+    // reverse so nothing is dropped while something still references it.
+    let mut upserts: Vec<(&ModuleRow, &str)> = Vec::new();
+    for (keys, reason) in [
+        (&summary.modules.changed, "ALTER"),
+        (&summary.modules.missing_in_left, "CREATE"),
+    ] {
+        upserts.extend(
+            keys.iter()
+                .filter_map(|key| source_map.get(key).map(|row| (*row, reason))),
+        );
     }
-    for key in &summary.modules.missing_in_left {
-        if let Some(row) = source_map.get(key) {
-            emit_module(row, "CREATE", &mut module_lines);
-        }
+    let sorted = dependency_order::sort(
+        &upserts,
+        |(row, _)| format!("{}.{}", row.schema_name, row.name),
+        |(row, _)| row.depends_on.clone(),
+    );
+    if !sorted.cyclic.is_empty() {
+        module_lines.push(format!(
+            "-- Circular references; run order may need adjusting: {}",
+            sorted
+                .cyclic
+                .iter()
+                .map(|(row, _)| format!("{}.{}", row.schema_name, row.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    for (row, reason) in sorted.ordered {
+        emit_module(row, reason, &mut module_lines);
     }
 
     if include_drops && !summary.modules.missing_in_right.is_empty() {
         drop_lines.push("-- Dropping objects that exist only in target".to_string());
-        for key in &summary.modules.missing_in_right {
-            let parts: Vec<&str> = key.split('.').collect();
-            if parts.len() >= 3 {
-                let schema = parts[0];
-                let type_code = parts[1];
-                let name = parts[2..].join(".");
-                let type_key = type_keyword(type_code);
-                if matches!(type_key, "PROCEDURE" | "FUNCTION" | "VIEW") {
-                    drop_lines.push(format!("DROP {type_key} IF EXISTS [{schema}].[{name}];"));
-                } else {
-                    drop_lines.push(format!("-- TODO: drop {type_key} {schema}.{name} manually"));
-                }
+        let target_map: HashMap<String, &ModuleRow> = target
+            .modules
+            .iter()
+            .map(|row| {
+                (
+                    format!("{}.{}.{}", row.schema_name, row.r#type, row.name),
+                    row,
+                )
+            })
+            .collect();
+        let drops: Vec<&ModuleRow> = summary
+            .modules
+            .missing_in_right
+            .iter()
+            .filter_map(|key| target_map.get(key).copied())
+            .collect();
+        let sorted = dependency_order::sort(
+            &drops,
+            |row| format!("{}.{}", row.schema_name, row.name),
+            |row| row.depends_on.clone(),
+        );
+        for row in sorted.ordered.into_iter().rev() {
+            let type_key = type_keyword(&row.r#type);
+            let (schema, name) = (&row.schema_name, &row.name);
+            if matches!(type_key, "PROCEDURE" | "FUNCTION" | "VIEW") {
+                drop_lines.push(format!("DROP {type_key} IF EXISTS [{schema}].[{name}];"));
+            } else {
+                drop_lines.push(format!("-- TODO: drop {type_key} {schema}.{name} manually"));
             }
         }
         drop_lines.push("GO".to_string());
@@ -1913,17 +3007,21 @@ fn render_apply_script(
         || !summary.tables.missing_in_right.is_empty()
     {
         table_lines.push(
-            "-- Table drift detected; non-destructive additions are applied automatically; other changes remain commented."
+            "-- Table drift detected; additions and column changes are generated, anything else is left as a TODO."
                 .to_string(),
         );
         for key in &summary.tables.changed {
             let right_sig = source_table_sig.get(key).cloned();
             let left_sig = target_table_sig.get(key).cloned();
             table_lines.push(render_table_alter(key, left_sig, right_sig));
-            table_lines.extend(render_add_columns(
+            let source_cols = src_cols.get(key).cloned().unwrap_or_default();
+            let target_cols = tgt_cols.get(key).cloned().unwrap_or_default();
+            table_lines.extend(render_add_columns(key, &source_cols, &target_cols));
+            table_lines.extend(render_alter_columns(
                 key,
-                src_cols.get(key).cloned().unwrap_or_default().as_slice(),
-                tgt_cols.get(key).cloned().unwrap_or_default().as_slice(),
+                &source_cols,
+                &target_cols,
+                &target.indexes,
             ));
         }
         for key in &summary.tables.missing_in_left {
@@ -1949,15 +3047,22 @@ fn render_apply_script(
         }
     }
 
+    let (drop_index_lines, create_index_lines) =
+        render_index_changes(&summary.indexes, source, target, include_drops);
+    let (drop_constraint_lines, add_constraint_lines) =
+        render_constraint_changes(&summary.constraints, source, target, include_drops, keys);
+
     let mut lines = Vec::new();
-    if !table_lines.is_empty() {
-        lines.extend(table_lines);
-    }
-    if !drop_lines.is_empty() {
-        lines.extend(drop_lines);
-    }
-    if !module_lines.is_empty() {
-        lines.extend(module_lines);
+    for section in [
+        drop_constraint_lines,
+        drop_index_lines,
+        table_lines,
+        create_index_lines,
+        add_constraint_lines,
+        drop_lines,
+        module_lines,
+    ] {
+        lines.extend(section);
     }
     if lines.is_empty() {
         lines.push("-- No drift detected; nothing to apply".to_string());
@@ -1965,26 +3070,419 @@ fn render_apply_script(
     lines.join("\n")
 }
 
+/// `ALTER COLUMN` for columns whose type or nullability differ, with data-loss warnings.
+///
+/// Identity, computed, and default differences cannot be altered in place and stay TODOs.
+fn render_alter_columns(
+    table_key: &str,
+    source_cols: &[TableColumnRow],
+    target_cols: &[TableColumnRow],
+    target_indexes: &[IndexRow],
+) -> Vec<String> {
+    let (schema, table) = table_key.split_once('.').unwrap_or(("", table_key));
+    let target_by_name: HashMap<String, &TableColumnRow> = target_cols
+        .iter()
+        .map(|c| (c.column_name.to_lowercase(), c))
+        .collect();
+    let mut lines = Vec::new();
+    for new in source_cols {
+        let Some(old) = target_by_name.get(&new.column_name.to_lowercase()) else {
+            continue;
+        };
+        let column = &new.column_name;
+        if !new.computed_definition.is_empty() || !old.computed_definition.is_empty() {
+            if new.computed_definition != old.computed_definition {
+                lines.push(format!(
+                    "-- TODO: computed column [{column}] differs; drop and re-add it as: {}",
+                    column_definition(new)
+                ));
+            }
+            continue;
+        }
+        if new.is_identity != old.is_identity {
+            lines.push(format!(
+                "-- TODO: IDENTITY on [{column}] differs; this needs a table rebuild."
+            ));
+        }
+        if new.default_definition != old.default_definition {
+            lines.push(format!(
+                "-- TODO: default on [{column}] differs (target: {}, source: {}); defaults are named constraints, align manually.",
+                non_empty_or(&old.default_definition, "none"),
+                non_empty_or(&new.default_definition, "none")
+            ));
+        }
+        if format_type(new) == format_type(old) && new.is_nullable == old.is_nullable {
+            continue;
+        }
+        for warning in alter_column_warnings(schema, table, new, old) {
+            lines.push(format!("-- WARNING: {warning}"));
+        }
+        for index in target_indexes.iter().filter(|i| {
+            i.schema_name == schema && i.table_name == table && index_uses_column(i, column)
+        }) {
+            lines.push(format!(
+                "-- WARNING: index [{}] uses [{column}]; drop it before this ALTER and recreate it after.",
+                index.name
+            ));
+        }
+        lines.push(format!(
+            "ALTER TABLE {}.{} ALTER COLUMN {} {} {};",
+            bracket(schema),
+            bracket(table),
+            bracket(column),
+            format_type(new),
+            if new.is_nullable { "NULL" } else { "NOT NULL" }
+        ));
+        lines.push("GO".to_string());
+    }
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn non_empty_or<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.is_empty() { fallback } else { value }
+}
+
+/// Why altering `old` into `new` may fail or lose data.
+fn alter_column_warnings(
+    schema: &str,
+    table: &str,
+    new: &TableColumnRow,
+    old: &TableColumnRow,
+) -> Vec<String> {
+    let column = &new.column_name;
+    let mut warnings = Vec::new();
+    let new_type = new.data_type.to_lowercase();
+    let old_type = old.data_type.to_lowercase();
+    if new_type != old_type {
+        warnings.push(format!(
+            "[{column}] changes type from {} to {}; values that do not convert make the ALTER fail or lose precision.",
+            format_type(old),
+            format_type(new)
+        ));
+    } else if matches!(
+        new_type.as_str(),
+        "varchar" | "char" | "nvarchar" | "nchar" | "varbinary" | "binary"
+    ) {
+        let shrinks =
+            new.max_length != -1 && (old.max_length == -1 || new.max_length < old.max_length);
+        if shrinks {
+            warnings.push(format!(
+                "[{column}] shrinks from {} to {}; longer values make the ALTER fail.",
+                format_type(old),
+                format_type(new)
+            ));
+        }
+    } else if matches!(new_type.as_str(), "decimal" | "numeric") {
+        if new.scale < old.scale || new.precision - new.scale < old.precision - old.scale {
+            warnings.push(format!(
+                "[{column}] narrows from {} to {}; values are rounded or overflow.",
+                format_type(old),
+                format_type(new)
+            ));
+        }
+    } else if matches!(new_type.as_str(), "datetime2" | "time" | "datetimeoffset")
+        && new.scale < old.scale
+    {
+        warnings.push(format!(
+            "[{column}] drops fractional-second precision ({} to {}); values are rounded.",
+            old.scale, new.scale
+        ));
+    }
+    if old.is_nullable && !new.is_nullable {
+        warnings.push(format!(
+            "[{column}] becomes NOT NULL; check first: SELECT COUNT(*) FROM {}.{} WHERE {} IS NULL;",
+            bracket(schema),
+            bracket(table),
+            bracket(column)
+        ));
+    }
+    warnings
+}
+
+fn index_uses_column(index: &IndexRow, column: &str) -> bool {
+    index
+        .key_columns
+        .split(',')
+        .map(|key| key.rsplit_once(' ').map_or(key, |(name, _)| name))
+        .chain(index.include_columns.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case(column))
+}
+
+/// Pair source-only and target-only entries that share a name: those are redefinitions.
+fn split_redefined<'a, T>(
+    only_in_source: Vec<&'a T>,
+    only_in_target: Vec<&'a T>,
+    identity: impl Fn(&T) -> String,
+) -> (Vec<&'a T>, Vec<&'a T>, Vec<&'a T>) {
+    let source_names: Vec<String> = only_in_source.iter().map(|row| identity(row)).collect();
+    let (redefined, removed): (Vec<&T>, Vec<&T>) = only_in_target
+        .into_iter()
+        .partition(|row| source_names.contains(&identity(row)));
+    (only_in_source, redefined, removed)
+}
+
+/// `DROP INDEX` statements (run early) and `CREATE INDEX` statements (run after table changes).
+fn render_index_changes(
+    diff: &DiffSet,
+    source: &Snapshot,
+    target: &Snapshot,
+    include_drops: bool,
+) -> (Vec<String>, Vec<String>) {
+    let source_by_key: HashMap<String, &IndexRow> = source
+        .indexes
+        .iter()
+        .map(|row| (index_key(row), row))
+        .collect();
+    let target_by_key: HashMap<String, &IndexRow> = target
+        .indexes
+        .iter()
+        .map(|row| (index_key(row), row))
+        .collect();
+    let only_in_source: Vec<&IndexRow> = diff
+        .missing_in_left
+        .iter()
+        .filter_map(|key| source_by_key.get(key).copied())
+        .collect();
+    let only_in_target: Vec<&IndexRow> = diff
+        .missing_in_right
+        .iter()
+        .filter_map(|key| target_by_key.get(key).copied())
+        .collect();
+    let (creates, redefined, removed) =
+        split_redefined(only_in_source, only_in_target, |row: &IndexRow| {
+            format!("{}.{}.{}", row.schema_name, row.table_name, row.name).to_lowercase()
+        });
+
+    let mut drops = Vec::new();
+    let to_drop: Vec<&IndexRow> = if include_drops {
+        redefined.into_iter().chain(removed).collect()
+    } else {
+        for row in &removed {
+            drops.push(format!(
+                "-- Index {} on {}.{} exists only in target (use --include-drops to drop it).",
+                row.name, row.schema_name, row.table_name
+            ));
+        }
+        redefined
+    };
+    for row in to_drop {
+        if row.is_primary_key || row.is_unique_constraint {
+            drops.push(format!(
+                "-- TODO: index {} on {}.{} backs a PRIMARY KEY/UNIQUE constraint; drop the constraint manually.",
+                row.name, row.schema_name, row.table_name
+            ));
+        } else {
+            drops.push(format!(
+                "DROP INDEX IF EXISTS {} ON {}.{};",
+                bracket(&row.name),
+                bracket(&row.schema_name),
+                bracket(&row.table_name)
+            ));
+        }
+    }
+    if !drops.is_empty() {
+        drops.insert(
+            0,
+            "-- Dropping indexes that are redefined or removed".to_string(),
+        );
+        drops.push("GO".to_string());
+        drops.push(String::new());
+    }
+
+    let mut created: Vec<String> = creates.into_iter().map(create_index_statement).collect();
+    if !created.is_empty() {
+        created.insert(
+            0,
+            "-- Creating indexes that exist only in source".to_string(),
+        );
+        created.push("GO".to_string());
+        created.push(String::new());
+    }
+    (drops, created)
+}
+
+fn create_index_statement(row: &IndexRow) -> String {
+    if row.is_primary_key || row.is_unique_constraint {
+        return format!(
+            "-- TODO: index {} on {}.{} backs a PRIMARY KEY/UNIQUE constraint; add the constraint manually.",
+            row.name, row.schema_name, row.table_name
+        );
+    }
+    let kind = match row.r#type.as_str() {
+        "CLUSTERED" => "CLUSTERED",
+        "NONCLUSTERED" => "NONCLUSTERED",
+        other => {
+            return format!(
+                "-- TODO: create {} index {} on {}.{} manually.",
+                other.to_lowercase(),
+                row.name,
+                row.schema_name,
+                row.table_name
+            );
+        }
+    };
+    let keys = row
+        .key_columns
+        .split(',')
+        .filter(|key| !key.is_empty())
+        .map(|key| match key.rsplit_once(' ') {
+            Some((name, direction)) => format!("{} {}", bracket(name), direction),
+            None => bracket(key),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut statement = format!(
+        "CREATE {}{} INDEX {} ON {}.{} ({})",
+        if row.is_unique { "UNIQUE " } else { "" },
+        kind,
+        bracket(&row.name),
+        bracket(&row.schema_name),
+        bracket(&row.table_name),
+        keys
+    );
+    if !row.include_columns.is_empty() {
+        let includes = row
+            .include_columns
+            .split(',')
+            .map(bracket)
+            .collect::<Vec<_>>()
+            .join(", ");
+        statement.push_str(&format!(" INCLUDE ({})", includes));
+    }
+    if !row.filter_definition.is_empty() {
+        statement.push_str(&format!(" WHERE {}", row.filter_definition));
+    }
+    statement.push(';');
+    statement
+}
+
+/// Drops (run first) and `ADD CONSTRAINT` statements (run last) for CHECK and FOREIGN KEY drift.
+///
+/// Foreign keys are added after checks so the referenced keys and indexes already exist.
+fn render_constraint_changes(
+    diff: &DiffSet,
+    source: &Snapshot,
+    target: &Snapshot,
+    include_drops: bool,
+    keys: &KeyOptions<'_>,
+) -> (Vec<String>, Vec<String>) {
+    let scriptable = |row: &&ConstraintRow| matches!(row.r#type.as_str(), "FK" | "CHECK");
+    let source_by_key = constraints_by_key(&source.constraints, keys);
+    let target_by_key = constraints_by_key(&target.constraints, keys);
+    let only_in_source: Vec<&ConstraintRow> = diff
+        .missing_in_left
+        .iter()
+        .filter_map(|key| source_by_key.get(key))
+        .flatten()
+        .copied()
+        .filter(scriptable)
+        .collect();
+    let only_in_target: Vec<&ConstraintRow> = diff
+        .missing_in_right
+        .iter()
+        .filter_map(|key| target_by_key.get(key))
+        .flatten()
+        .copied()
+        .filter(scriptable)
+        .collect();
+    let (adds, redefined, removed) =
+        split_redefined(only_in_source, only_in_target, |row: &ConstraintRow| {
+            format!("{}.{}.{}", row.schema_name, row.table_name, row.name).to_lowercase()
+        });
+
+    let drop_statement = |row: &ConstraintRow| {
+        format!(
+            "ALTER TABLE {}.{} DROP CONSTRAINT {};",
+            bracket(&row.schema_name),
+            bracket(&row.table_name),
+            bracket(&row.name)
+        )
+    };
+    let mut drops = Vec::new();
+    // Foreign keys go first: they may depend on indexes or keys dropped later.
+    let mut to_drop: Vec<&ConstraintRow> = redefined;
+    if include_drops {
+        to_drop.extend(removed);
+    } else {
+        for row in &removed {
+            drops.push(format!(
+                "-- Constraint {} on {}.{} exists only in target (use --include-drops to drop it).",
+                row.name, row.schema_name, row.table_name
+            ));
+        }
+    }
+    to_drop.sort_by_key(|row| row.r#type != "FK");
+    drops.extend(to_drop.into_iter().map(drop_statement));
+    if !drops.is_empty() {
+        drops.insert(
+            0,
+            "-- Dropping constraints that are redefined or removed".to_string(),
+        );
+        drops.push("GO".to_string());
+        drops.push(String::new());
+    }
+
+    let mut adds = adds;
+    adds.sort_by_key(|row| row.r#type == "FK");
+    let mut added: Vec<String> = adds
+        .into_iter()
+        .map(|row| {
+            let body = if row.r#type == "CHECK" {
+                format!("CHECK {}", row.definition)
+            } else {
+                row.definition.clone()
+            };
+            format!(
+                "ALTER TABLE {}.{} WITH CHECK ADD CONSTRAINT {} {};",
+                bracket(&row.schema_name),
+                bracket(&row.table_name),
+                bracket(&row.name),
+                body
+            )
+        })
+        .collect();
+    if !added.is_empty() {
+        added.insert(
+            0,
+            "-- Adding constraints that exist only in source".to_string(),
+        );
+        added.push("GO".to_string());
+        added.push(String::new());
+    }
+    (drops, added)
+}
+
+fn constraints_by_key<'a>(
+    rows: &'a [ConstraintRow],
+    keys: &KeyOptions<'_>,
+) -> HashMap<String, Vec<&'a ConstraintRow>> {
+    let mut map: HashMap<String, Vec<&ConstraintRow>> = HashMap::new();
+    for row in rows {
+        let key = constraint_key(row, keys.ignore_whitespace, keys.strip_comments, keys.rules);
+        map.entry(key).or_default().push(row);
+    }
+    map
+}
+
 fn render_table_alter(key: &str, left_sig: Option<String>, right_sig: Option<String>) -> String {
     let changes = diff_table_details(left_sig.as_deref(), right_sig.as_deref());
     let mut stmts = Vec::new();
     let (schema, table) = key.split_once('.').unwrap_or(("", key));
-    stmts.push(format!(
-        "-- TODO: Table drift detected for {schema}.{table}"
-    ));
+    stmts.push(format!("-- Table drift detected for {schema}.{table}"));
     if changes.columns_changed {
-        stmts.push(format!(
-            "--   Columns differ (type/nullability/default/identity/computed). Review and craft ALTER TABLE for {schema}.{table}."
-        ));
-    }
-    if changes.indexes_changed {
         stmts.push(
-            "--   Non-PK/unique indexes differ. Consider recreating indexes to match source."
+            "--   Columns differ; generated ADD/ALTER COLUMN statements follow, other column changes are TODOs."
                 .to_string(),
         );
     }
+    if changes.indexes_changed {
+        stmts.push("--   Indexes differ; see the index sections.".to_string());
+    }
     if changes.checks_changed {
-        stmts.push("--   CHECK constraints differ. Align definitions as needed.".to_string());
+        stmts.push("--   CHECK constraints differ; see the constraint sections.".to_string());
     }
     stmts.push(String::new());
     stmts.join("\n")
@@ -2208,4 +3706,231 @@ mod tests {
         assert!(lines.contains("ALTER TABLE [dbo].[Users]"));
         assert!(lines.contains("[Id] int"));
     }
+
+    #[test]
+    fn render_alter_columns_warns_about_data_loss() {
+        let column = |data_type: &str, max_length: i64, is_nullable: bool| TableColumnRow {
+            schema_name: "dbo".into(),
+            table_name: "Users".into(),
+            column_id: 2,
+            column_name: "Email".into(),
+            data_type: data_type.into(),
+            max_length,
+            precision: 0,
+            scale: 0,
+            is_nullable,
+            is_identity: false,
+            default_definition: "".into(),
+            computed_definition: "".into(),
+        };
+        let index = IndexRow {
+            schema_name: "dbo".into(),
+            table_name: "Users".into(),
+            name: "IX_Users_Email".into(),
+            r#type: "NONCLUSTERED".into(),
+            is_unique: true,
+            is_primary_key: false,
+            is_unique_constraint: false,
+            key_columns: "Email ASC".into(),
+            include_columns: "".into(),
+            filter_definition: "".into(),
+        };
+        let lines = render_alter_columns(
+            "dbo.Users",
+            &[column("varchar", 100, false)],
+            &[column("varchar", 255, true)],
+            std::slice::from_ref(&index),
+        )
+        .join("\n");
+        assert!(lines.contains("shrinks from varchar(255) to varchar(100)"));
+        assert!(lines.contains("becomes NOT NULL"));
+        assert!(lines.contains("index [IX_Users_Email] uses [Email]"));
+        assert!(
+            lines.contains("ALTER TABLE [dbo].[Users] ALTER COLUMN [Email] varchar(100) NOT NULL;")
+        );
+
+        assert!(
+            render_alter_columns(
+                "dbo.Users",
+                &[column("varchar", 255, true)],
+                &[column("varchar", 255, true)],
+                &[]
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            create_index_statement(&IndexRow {
+                filter_definition: "([Email] IS NOT NULL)".into(),
+                include_columns: "Name".into(),
+                ..index
+            }),
+            "CREATE UNIQUE NONCLUSTERED INDEX [IX_Users_Email] ON [dbo].[Users] ([Email] ASC) INCLUDE ([Name]) WHERE ([Email] IS NOT NULL);"
+        );
+    }
+
+    #[test]
+    fn apply_script_orders_modules_by_dependencies() {
+        let module = |kind: &str, name: &str, depends_on: &[&str]| ModuleRow {
+            schema_name: "dbo".to_string(),
+            name: name.to_string(),
+            r#type: kind.to_string(),
+            definition: format!("CREATE VIEW dbo.{} AS SELECT 1 AS x", name),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        };
+        let source = Snapshot {
+            modules: vec![
+                module("P", "usp_Report", &["dbo.vOrders"]),
+                module("V", "vOrders", &["dbo.vCustomers", "dbo.Orders"]),
+                module("V", "vCustomers", &[]),
+            ],
+            ..Snapshot::default()
+        };
+        let target = Snapshot {
+            modules: vec![
+                module("V", "vOld", &[]),
+                module("V", "vOlder", &["dbo.vOld"]),
+            ],
+            ..Snapshot::default()
+        };
+        let summary = CompareSummary {
+            modules: DiffSet {
+                changed: vec!["dbo.P.usp_Report".into()],
+                missing_in_left: vec!["dbo.V.vOrders".into(), "dbo.V.vCustomers".into()],
+                missing_in_right: vec!["dbo.V.vOld".into(), "dbo.V.vOlder".into()],
+            },
+            indexes: DiffSet::default(),
+            constraints: DiffSet::default(),
+            tables: DiffSet::default(),
+            ignored: 0,
+            incomplete: Vec::new(),
+        };
+        let rules = IgnoreRules::default();
+        let keys = KeyOptions {
+            ignore_whitespace: false,
+            strip_comments: false,
+            rules: &rules,
+        };
+        let script = render_apply_script(&summary, &source, &target, true, &keys);
+        let position = |needle: &str| script.find(needle).expect(needle);
+        assert!(position("CREATE: dbo.vCustomers") < position("CREATE: dbo.vOrders"));
+        assert!(position("CREATE: dbo.vOrders") < position("ALTER: dbo.usp_Report"));
+        assert!(position("[dbo].[vOlder]") < position("[dbo].[vOld];"));
+    }
+
+    #[test]
+    fn object_filter_applies_category_and_patterns() {
+        let mut summary = CompareSummary {
+            modules: DiffSet {
+                changed: vec!["dbo.P.GetOrders".into(), "audit.P.Purge".into()],
+                ..DiffSet::default()
+            },
+            indexes: DiffSet::default(),
+            constraints: DiffSet::default(),
+            tables: DiffSet {
+                missing_in_left: vec!["dbo.Orders_backup".into()],
+                ..DiffSet::default()
+            },
+            ignored: 0,
+            incomplete: Vec::new(),
+        };
+        let filter = ObjectFilter {
+            only: vec![Category::Modules, Category::Tables],
+            include: Vec::new(),
+            exclude: vec![
+                NamePattern::parse("*_backup").expect("glob"),
+                NamePattern::parse("/^audit\\./").expect("regex"),
+            ],
+        };
+        assert_eq!(filter.apply(&mut summary), 2);
+        assert_eq!(summary.modules.changed, vec!["dbo.P.GetOrders".to_string()]);
+        assert!(summary.tables.missing_in_left.is_empty());
+    }
+
+    #[test]
+    fn interrupted_fetch_reports_sections_missing_on_either_side() {
+        let module_rs = ResultSet {
+            columns: ["schema_name", "name", "type", "definition"]
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: vec![vec![
+                Value::Text("dbo".into()),
+                Value::Text("GetOrders".into()),
+                Value::Text("P".into()),
+                Value::Text("SELECT 1".into()),
+            ]],
+        };
+        let left = FetchedSections::default();
+        let right = FetchedSections::default();
+        left.lock()
+            .unwrap()
+            .insert("modules", vec![module_rs.clone()]);
+        left.lock().unwrap().insert("tables", Vec::new());
+        right.lock().unwrap().insert("tables", Vec::new());
+
+        let missing = missing_sections(&left, &right);
+        assert_eq!(
+            missing,
+            vec!["modules", "indexes", "constraints", "columns"]
+        );
+
+        let mut snapshot = build_snapshot("source", &left);
+        assert_eq!(snapshot.modules.len(), 1);
+        clear_sections(&mut snapshot, &missing);
+        assert!(snapshot.modules.is_empty());
+    }
+
+    #[test]
+    fn wildcard_object_diffs_every_matching_module() {
+        let module = |schema: &str, name: &str, body: &str| ModuleRow {
+            schema_name: schema.to_string(),
+            name: name.to_string(),
+            r#type: "P".to_string(),
+            definition: format!("CREATE PROCEDURE p AS\n{}\n", body),
+            depends_on: Vec::new(),
+        };
+        let left = Snapshot {
+            name: "dev".to_string(),
+            modules: vec![
+                module("dbo", "usp_B", "SELECT 2"),
+                module("dbo", "usp_A", "SELECT 1"),
+                module("dbo", "usp_Same", "SELECT 3"),
+                module("dbo", "GetOrders", "SELECT 4"),
+            ],
+            ..Snapshot::default()
+        };
+        let right = Snapshot {
+            name: "prod".to_string(),
+            modules: vec![
+                module("dbo", "usp_A", "SELECT 10"),
+                module("dbo", "usp_same", "SELECT  3"),
+                module("dbo", "usp_C", "SELECT 5"),
+            ],
+            ..Snapshot::default()
+        };
+
+        let (patches, total) = module_patches(&left, &right, "dbo.usp_*", true, false);
+        assert_eq!(total, 4);
+        let names = patches.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["usp_A", "usp_B", "usp_C"]);
+        assert!(
+            patches[0]
+                .diff
+                .starts_with("--- dev:dbo.usp_A.P\n+++ prod:dbo.usp_A.P\n")
+        );
+        assert!(patches[0].diff.contains("-SELECT 1\n+SELECT 10\n"));
+        assert!(patches[1].diff.contains("+++ prod:missing"));
+        assert_eq!(patches[2].file_name(), "dbo.usp_C.patch");
+        assert!(
+            patches[2]
+                .git_diff()
+                .starts_with("diff --git a/dbo.usp_C.sql b/dbo.usp_C.sql\nnew file mode 100644\n")
+        );
+
+        let (patches, total) = module_patches(&left, &right, "usp_?", true, false);
+        assert_eq!((patches.len(), total), (3, 3));
+    }
 }
@@ -0,0 +1,264 @@
+//! Accepted drift for `compare`, read from `.sscli-compare-ignore`.
+//!
+//! The file is YAML with one `ignore` list. A rule matches by object pattern, category, and
+//! change kind (all optional, all must agree), or strips a `definition` regex from module and
+//! constraint text before diffing so environment-specific clauses do not register as changes:
+//!
+//! ```yaml
+//! ignore:
+//!   - object: "dbo.MSmerge_*"            # replication artifacts, any category
+//!   - object: "sp_MS*"
+//!     category: modules
+//!     change: only-in-target
+//!   - definition: "NOT FOR REPLICATION"  # removed from definitions before comparing
+//!     reason: publisher-only clause
+//! ```
+
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::commands::verify;
+
+pub const IGNORE_FILE_NAME: &str = ".sscli-compare-ignore";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Modules,
+    Indexes,
+    Constraints,
+    Tables,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Change {
+    Changed,
+    OnlyInSource,
+    OnlyInTarget,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IgnoreFile {
+    #[serde(default)]
+    ignore: Vec<RuleSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    object: Option<String>,
+    category: Option<Category>,
+    change: Option<Change>,
+    definition: Option<String>,
+    /// Free text for reviewers; not used for matching.
+    #[allow(dead_code)]
+    reason: Option<String>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    object: Option<String>,
+    category: Option<Category>,
+    change: Option<Change>,
+    definition: Option<Regex>,
+}
+
+impl Rule {
+    fn selects(&self, category: Category, schema: &str, name: &str) -> bool {
+        self.category.is_none_or(|c| c == category)
+            && self.object.as_ref().is_none_or(|pattern| {
+                verify::table_matches(std::slice::from_ref(pattern), schema, name)
+            })
+    }
+}
+
+/// Rules loaded from an ignore file; empty when none was found.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+    pub path: Option<PathBuf>,
+}
+
+impl IgnoreRules {
+    /// Load `explicit`, or the nearest `.sscli-compare-ignore` in `cwd` or its ancestors.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `explicit` does not exist, or when the file is not valid YAML, has unknown
+    /// keys, or contains an invalid regex.
+    pub fn load(explicit: Option<&Path>, cwd: &Path) -> Result<Self> {
+        let path = match explicit {
+            Some(path) if !path.is_file() => {
+                return Err(anyhow!("Ignore file not found: {}", path.display()));
+            }
+            Some(path) => path.to_path_buf(),
+            None => match cwd
+                .ancestors()
+                .map(|dir| dir.join(IGNORE_FILE_NAME))
+                .find(|path| path.is_file())
+            {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ignore file {}", path.display()))?;
+        let mut rules = Self::parse(&content)
+            .with_context(|| format!("Invalid ignore file {}", path.display()))?;
+        rules.path = Some(path);
+        Ok(rules)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let file: IgnoreFile = serde_yaml::from_str(content)?;
+        let rules = file
+            .ignore
+            .into_iter()
+            .enumerate()
+            .map(|(idx, spec)| {
+                let definition = spec
+                    .definition
+                    .as_deref()
+                    .map(|pattern| {
+                        Regex::new(pattern).with_context(|| {
+                            format!("rule {}: invalid definition regex '{}'", idx + 1, pattern)
+                        })
+                    })
+                    .transpose()?;
+                if definition.is_some() && spec.change.is_some() {
+                    return Err(anyhow!(
+                        "rule {}: a definition rule cannot also set change",
+                        idx + 1
+                    ));
+                }
+                if spec.object.is_none()
+                    && spec.category.is_none()
+                    && spec.change.is_none()
+                    && definition.is_none()
+                {
+                    return Err(anyhow!(
+                        "rule {}: set at least one of object, category, change, definition",
+                        idx + 1
+                    ));
+                }
+                Ok(Rule {
+                    object: spec.object,
+                    category: spec.category,
+                    change: spec.change,
+                    definition,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules, path: None })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Remove every matching `definition` pattern from an object's definition.
+    pub fn strip_definition<'a>(
+        &self,
+        category: Category,
+        schema: &str,
+        name: &str,
+        definition: &'a str,
+    ) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(definition);
+        for rule in &self.rules {
+            if let Some(re) = &rule.definition {
+                if rule.selects(category, schema, name) {
+                    if let Cow::Owned(replaced) = re.replace_all(&text, "") {
+                        text = Cow::Owned(replaced);
+                    }
+                }
+            }
+        }
+        text
+    }
+
+    /// Whether a difference on `schema.name` should be dropped from the drift report.
+    ///
+    /// Rules that only carry a `definition` pattern never suppress a difference outright.
+    pub fn ignores(&self, category: Category, schema: &str, name: &str, change: Change) -> bool {
+        self.rules.iter().any(|rule| {
+            rule.definition.is_none()
+                && rule.change.is_none_or(|c| c == change)
+                && rule.selects(category, schema, name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_by_object_category_and_change() {
+        let rules = IgnoreRules::parse(
+            r#"
+ignore:
+  - object: "dbo.MSmerge_*"
+  - object: "sp_MS*"
+    category: modules
+    change: only-in-target
+"#,
+        )
+        .expect("valid rules");
+        assert!(rules.ignores(
+            Category::Tables,
+            "dbo",
+            "MSmerge_tombstone",
+            Change::Changed
+        ));
+        assert!(!rules.ignores(Category::Tables, "sales", "MSmerge_x", Change::Changed));
+        assert!(rules.ignores(
+            Category::Modules,
+            "dbo",
+            "sp_MSins_Orders",
+            Change::OnlyInTarget
+        ));
+        assert!(!rules.ignores(Category::Modules, "dbo", "sp_MSins_Orders", Change::Changed));
+        assert!(!rules.ignores(
+            Category::Indexes,
+            "dbo",
+            "sp_MSins_Orders",
+            Change::OnlyInTarget
+        ));
+    }
+
+    #[test]
+    fn strips_definition_patterns() {
+        let rules = IgnoreRules::parse(
+            "ignore:\n  - definition: \"(?i)\\\\s*NOT FOR REPLICATION\"\n    category: constraints\n",
+        )
+        .expect("valid rules");
+        assert_eq!(
+            rules.strip_definition(
+                Category::Constraints,
+                "dbo",
+                "Orders",
+                "CHECK NOT FOR REPLICATION ([Qty]>(0))"
+            ),
+            "CHECK ([Qty]>(0))"
+        );
+        assert!(!rules.ignores(Category::Constraints, "dbo", "Orders", Change::Changed));
+    }
+
+    #[test]
+    fn rejects_empty_rules_and_bad_regex() {
+        assert!(IgnoreRules::parse("ignore:\n  - reason: nothing\n").is_err());
+        assert!(IgnoreRules::parse("ignore:\n  - definition: \"(\"\n").is_err());
+        assert!(IgnoreRules::parse("ignore:\n  - objects: x\n").is_err());
+    }
+}
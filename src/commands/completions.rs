@@ -1,11 +1,64 @@
-use std::io;
+use std::io::{self, Write};
 
 use anyhow::{Result, anyhow};
 use clap_complete::{Shell, generate};
+use tiberius::Query;
 
-use crate::cli::{CliArgs, CompletionsArgs, build_cli};
+use crate::cli::{CliArgs, CompletionsArgs, cli_tree};
+use crate::commands::{common, object_lookup};
+use crate::config::{self, MetadataCacheSettingsResolved};
+use crate::db::client;
+use crate::db::metadata_cache::{self, MetadataCache};
+use crate::db::types::Value;
+use crate::error::{AppError, ErrorKind};
+
+/// Commands whose first positional argument names a table or view.
+const TABLE_COMMANDS: &[&str] = &[
+    "table-data",
+    "data",
+    "head",
+    "columns",
+    "cols",
+    "find-column",
+    "profile",
+];
+/// Commands whose first positional argument names any object.
+const OBJECT_COMMANDS: &[&str] = &["describe", "desc"];
+/// Connection options forwarded to `completions --list`, so names come from the same database.
+const FORWARDED_OPTIONS: &[&str] = &[
+    "--config",
+    "--env-file",
+    "--profile",
+    "--server",
+    "--database",
+];
+
+const NAMES_SQL: &str = r#"
+SELECT s.name AS schema_name, o.name AS object_name, o.type AS object_type
+FROM sys.objects o
+INNER JOIN sys.schemas s ON s.schema_id = o.schema_id
+WHERE o.is_ms_shipped = 0
+  AND o.type IN ('U', 'V', 'P', 'PC', 'FN', 'IF', 'TF', 'FS', 'FT', 'TR', 'SO', 'SN')
+UNION ALL
+SELECT s.name, NULL, 'SCHEMA'
+FROM sys.schemas s
+WHERE s.schema_id < 16384
+  AND s.name NOT IN ('sys', 'INFORMATION_SCHEMA', 'guest')
+ORDER BY 1, 2;
+"#;
+
+pub fn run(args: &CliArgs, cmd: &CompletionsArgs) -> Result<()> {
+    if let Some(kind) = cmd.list.as_deref() {
+        // Completion must stay quiet: a missing profile or unreachable server completes nothing.
+        if let Ok(names) = list_names(args, kind) {
+            let mut stdout = io::stdout().lock();
+            for name in names {
+                writeln!(stdout, "{}", name)?;
+            }
+        }
+        return Ok(());
+    }
 
-pub fn run(_args: &CliArgs, cmd: &CompletionsArgs) -> Result<()> {
     let shell_name = cmd
         .shell
         .as_deref()
@@ -20,7 +73,234 @@ pub fn run(_args: &CliArgs, cmd: &CompletionsArgs) -> Result<()> {
         _ => return Err(anyhow!("Unsupported shell: {}", shell_name)),
     };
 
-    let mut cmd = build_cli(true);
+    let mut cmd = cli_tree(true);
     generate(shell, &mut cmd, "sscli", &mut io::stdout());
+    if let Some(script) = dynamic_script(shell) {
+        print!("{}", script);
+    }
     Ok(())
 }
+
+/// Table, schema, or object names from the catalog, always through the metadata cache so a
+/// repeated Tab answers from disk.
+fn list_names(args: &CliArgs, kind: &str) -> Result<Vec<String>> {
+    let resolved = config::load_from_system(&common::overrides_from_args(args))
+        .map_err(|err| AppError::new(ErrorKind::Config, err.to_string()))?;
+    if let Some(dir) = object_lookup::profile_cache_dir(&resolved) {
+        let settings = MetadataCacheSettingsResolved {
+            enabled: true,
+            ..resolved.settings.metadata_cache.clone()
+        };
+        // Installed before `apply_config`, whose own (possibly disabled) cache is then ignored.
+        metadata_cache::configure(MetadataCache::new(
+            &settings,
+            dir,
+            &resolved.connection.server,
+            &resolved.connection.database,
+            args.refresh,
+        ));
+    }
+    common::apply_config(args, &resolved)?;
+
+    let rows = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let sets = metadata_cache::run_query(Query::new(NAMES_SQL), &mut client).await?;
+        Ok::<_, anyhow::Error>(sets.into_iter().next().unwrap_or_default().rows)
+    })?;
+
+    let text = |value: Option<&Value>| match value {
+        Some(Value::Text(text)) => Some(text.clone()),
+        _ => None,
+    };
+    let entries: Vec<(String, Option<String>, String)> = rows
+        .iter()
+        .filter_map(|row| {
+            Some((
+                text(row.first())?,
+                text(row.get(1)),
+                text(row.get(2))?.trim().to_string(),
+            ))
+        })
+        .collect();
+    Ok(names_for(
+        kind,
+        &entries,
+        &resolved.connection.default_schemas,
+    ))
+}
+
+/// Completion candidates of `kind` from `(schema, name, type)` rows. Objects in a default schema
+/// are offered unqualified as well, matching how the commands resolve them.
+fn names_for(
+    kind: &str,
+    entries: &[(String, Option<String>, String)],
+    default_schemas: &[String],
+) -> Vec<String> {
+    let mut names = Vec::new();
+    for (schema, name, object_type) in entries {
+        let wanted = match kind {
+            "schemas" => object_type == "SCHEMA",
+            "tables" => object_type == "U" || object_type == "V",
+            _ => object_type != "SCHEMA",
+        };
+        if !wanted {
+            continue;
+        }
+        let Some(name) = name else {
+            names.push(schema.clone());
+            continue;
+        };
+        if default_schemas
+            .iter()
+            .any(|default| default.eq_ignore_ascii_case(schema))
+        {
+            names.push(name.clone());
+        }
+        names.push(format!("{}.{}", schema, name));
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Shell code appended to clap's static script: `--table`, `--schema`, and the object argument
+/// of `describe`, `table-data`, `columns`, and `profile` complete from `completions --list`.
+fn dynamic_script(shell: Shell) -> Option<String> {
+    let tables = TABLE_COMMANDS.join("|");
+    let objects = OBJECT_COMMANDS.join("|");
+    let forwarded = FORWARDED_OPTIONS.join("|");
+    let script = match shell {
+        Shell::Bash => format!(
+            r#"
+_sscli_names() {{
+    local i args=()
+    for ((i = 1; i < COMP_CWORD - 1; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            {forwarded}) args+=("${{COMP_WORDS[i]}}" "${{COMP_WORDS[i+1]}}") ;;
+        esac
+    done
+    sscli "${{args[@]}}" completions --list "$1" 2>/dev/null
+}}
+
+_sscli_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}" kind=""
+    if [[ "$cur" != -* ]]; then
+        case "$prev" in
+            -t|--table) kind=tables ;;
+            -s|--schema) kind=schemas ;;
+            {objects}) kind=objects ;;
+            {tables}) kind=tables ;;
+        esac
+    fi
+    if [[ -n "$kind" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(_sscli_names "$kind")" -- "$cur"))
+        return 0
+    fi
+    _sscli "$@"
+}}
+
+complete -F _sscli_dynamic -o bashdefault -o default sscli
+"#
+        ),
+        Shell::Zsh => format!(
+            r#"
+_sscli_names() {{
+    local i
+    local -a forwarded names
+    for (( i = 2; i < CURRENT - 1; i++ )); do
+        case ${{words[i]}} in
+            {forwarded}) forwarded+=(${{words[i]}} ${{words[i+1]}}) ;;
+        esac
+    done
+    names=(${{(f)"$(sscli $forwarded completions --list $1 2>/dev/null)"}})
+    compadd -a names
+}}
+
+_sscli_dynamic() {{
+    if [[ $PREFIX != -* ]]; then
+        case ${{words[CURRENT-1]}} in
+            -t|--table) _sscli_names tables; return ;;
+            -s|--schema) _sscli_names schemas; return ;;
+            {objects}) _sscli_names objects; return ;;
+            {tables}) _sscli_names tables; return ;;
+        esac
+    fi
+    _sscli "$@"
+}}
+
+compdef _sscli_dynamic sscli
+"#
+        ),
+        Shell::Fish => format!(
+            r#"
+function __sscli_names
+    set -l tokens (commandline -opc)
+    set -l forwarded
+    for i in (seq 2 (math (count $tokens) - 1))
+        if contains -- $tokens[$i] {forwarded_words}
+            set -a forwarded $tokens[$i] $tokens[(math $i + 1)]
+        end
+    end
+    sscli $forwarded completions --list $argv[1] 2>/dev/null
+end
+
+complete -c sscli -s t -l table -x -a '(__sscli_names tables)'
+complete -c sscli -s s -l schema -x -a '(__sscli_names schemas)'
+complete -c sscli -n '__fish_seen_subcommand_from {object_words}' -f -a '(__sscli_names objects)'
+complete -c sscli -n '__fish_seen_subcommand_from {table_words}' -f -a '(__sscli_names tables)'
+"#,
+            forwarded_words = FORWARDED_OPTIONS.join(" "),
+            object_words = OBJECT_COMMANDS.join(" "),
+            table_words = TABLE_COMMANDS.join(" "),
+        ),
+        _ => return None,
+    };
+    Some(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_filtered_by_kind_and_qualified() {
+        let entry = |schema: &str, name: Option<&str>, object_type: &str| {
+            (
+                schema.to_string(),
+                name.map(str::to_string),
+                object_type.to_string(),
+            )
+        };
+        let entries = vec![
+            entry("dbo", Some("Orders"), "U"),
+            entry("sales", Some("Customers"), "V"),
+            entry("dbo", Some("usp_Load"), "P"),
+            entry("dbo", None, "SCHEMA"),
+            entry("sales", None, "SCHEMA"),
+        ];
+        let defaults = vec!["dbo".to_string()];
+
+        assert_eq!(
+            names_for("tables", &entries, &defaults),
+            vec!["Orders", "dbo.Orders", "sales.Customers"]
+        );
+        assert_eq!(
+            names_for("schemas", &entries, &defaults),
+            vec!["dbo", "sales"]
+        );
+        assert!(names_for("objects", &entries, &defaults).contains(&"usp_Load".to_string()));
+    }
+
+    #[test]
+    fn only_bash_zsh_and_fish_get_dynamic_names() {
+        let bash = dynamic_script(Shell::Bash).expect("bash");
+        assert!(bash.contains("describe|desc) kind=objects"));
+        assert!(bash.contains("complete -F _sscli_dynamic"));
+        assert!(
+            dynamic_script(Shell::Zsh).is_some_and(|zsh| zsh.contains("compdef _sscli_dynamic"))
+        );
+        assert!(dynamic_script(Shell::Fish).is_some_and(|fish| fish.contains("-l table -x")));
+        assert!(dynamic_script(Shell::PowerShell).is_none());
+    }
+}
@@ -2,18 +2,27 @@ use std::io::{self, Write};
 
 use anyhow::Result;
 
-use crate::cli::CliArgs;
+use serde_json::json;
+
+use crate::cli::{CliArgs, ConfigArgs, ConnectionStringArgs};
 use crate::commands::common;
-use crate::config;
+use crate::config::{self, ConnectionSettings, ResolvedConfig};
+use crate::db::connection;
 use crate::output::{self, TableOptions, json, table};
 
-pub fn run(args: &CliArgs) -> Result<()> {
+/// Stands in for the password unless `--no-redact` is passed.
+const REDACTED: &str = "********";
+
+pub fn run(args: &CliArgs, cmd: &ConfigArgs) -> Result<()> {
     let resolved = common::load_config(args)?;
     let format = output::select_format(&args.output, &resolved.settings);
 
     if args.quiet {
         return Ok(());
     }
+    if let Some(conn_args) = &cmd.connection_string {
+        return connection_strings(&resolved, format, conn_args);
+    }
 
     match format {
         config::OutputFormat::Json => {
@@ -48,6 +57,18 @@ pub fn run(args: &CliArgs) -> Result<()> {
                     "trustCert".to_string(),
                     resolved.connection.trust_cert.to_string(),
                 ),
+            ]);
+            let tls = &resolved.connection.tls;
+            if let Some(path) = &tls.ca_cert {
+                rows.push(("tls.caCert".to_string(), path.display().to_string()));
+            }
+            if tls.strict {
+                rows.push(("tls.strict".to_string(), "true".to_string()));
+            }
+            if let Some(version) = tls.min_version {
+                rows.push(("tls.minVersion".to_string(), version.as_str().to_string()));
+            }
+            rows.extend([
                 (
                     "timeoutMs".to_string(),
                     resolved.connection.timeout_ms.to_string(),
@@ -57,6 +78,13 @@ pub fn run(args: &CliArgs) -> Result<()> {
                     resolved.connection.default_schemas.join(","),
                 ),
             ]);
+            if let Some(name) = &resolved.connection.application_name {
+                rows.push(("applicationName".to_string(), name.clone()));
+            }
+            let session = resolved.connection.session.statements();
+            if !session.is_empty() {
+                rows.push(("session".to_string(), session.join(" ")));
+            }
 
             let result =
                 table::render_key_value_table("Config", &rows, format, &TableOptions::default());
@@ -66,3 +94,55 @@ pub fn run(args: &CliArgs) -> Result<()> {
 
     Ok(())
 }
+
+fn connection_strings(
+    resolved: &ResolvedConfig,
+    format: config::OutputFormat,
+    cmd: &ConnectionStringArgs,
+) -> Result<()> {
+    let mut settings = resolved.connection.clone();
+    if cmd.redact && settings.password.is_some() {
+        settings.password = Some(REDACTED.to_string());
+    }
+    let forms: Vec<(&str, String)> = forms(&settings)
+        .into_iter()
+        .filter(|(name, _)| cmd.format.as_deref().is_none_or(|wanted| wanted == *name))
+        .collect();
+
+    if matches!(format, config::OutputFormat::Json) {
+        let mut payload = json!({
+            "profileName": resolved.profile_name,
+            "redacted": cmd.redact,
+        });
+        for (name, value) in &forms {
+            payload[*name] = json!(value);
+        }
+        let body = json::emit_json_value(&payload, resolved.settings.output.json.pretty)?;
+        println!("{}", body);
+        return Ok(());
+    }
+    // A single form prints bare so it can be piped or copied as is.
+    if cmd.format.is_some() {
+        for (_, value) in &forms {
+            writeln!(io::stdout(), "{}", value)?;
+        }
+        return Ok(());
+    }
+    let rows: Vec<(String, String)> = forms
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    let result =
+        table::render_key_value_table("Connection String", &rows, format, &TableOptions::default());
+    writeln!(io::stdout(), "{}", result.output)?;
+    Ok(())
+}
+
+fn forms(settings: &ConnectionSettings) -> [(&'static str, String); 4] {
+    [
+        ("ado", connection::build_ado_string(settings)),
+        ("jdbc", connection::build_jdbc_string(settings)),
+        ("odbc", connection::build_odbc_string(settings)),
+        ("sqlcmd", connection::build_sqlcmd_command(settings)),
+    ]
+}
@@ -0,0 +1,99 @@
+//! Confirmation before writes against profiles tagged `environment: production`.
+
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::{Context, Result};
+
+use crate::cli::CliArgs;
+use crate::config::{ProfilePolicy, ResolvedConfig};
+use crate::error::{AppError, ErrorKind};
+
+/// Ask before `action` runs when the profile is tagged production; `--yes` skips the prompt.
+/// A `readOnly` profile refuses the write outright, `--yes` or not.
+///
+/// `action` completes "About to ..." (`"apply 3 pending migrations"`). Without a terminal on
+/// stdin and stderr there is no one to ask, so the command fails unless `--yes` was given.
+pub fn confirm_write(args: &CliArgs, resolved: &ResolvedConfig, action: &str) -> Result<()> {
+    if let Some(message) = read_only_refusal(&resolved.policy, &resolved.profile_name, action) {
+        return Err(AppError::new(ErrorKind::Safety, message).into());
+    }
+    if args.yes || !resolved.policy.is_production() {
+        return Ok(());
+    }
+    let target = format!(
+        "{}:{}/{}",
+        resolved.connection.server, resolved.connection.port, resolved.connection.database
+    );
+    if !(io::stdin().is_terminal() && io::stderr().is_terminal()) {
+        return Err(AppError::new(
+            ErrorKind::Safety,
+            format!(
+                "Profile '{}' is tagged environment: production; re-run with --yes to {} on {}",
+                resolved.profile_name, action, target
+            ),
+        )
+        .into());
+    }
+
+    eprintln!(
+        "Profile '{}' is tagged environment: production ({}).",
+        resolved.profile_name, target
+    );
+    eprint!("About to {}. Continue? [y/N]: ", action);
+    io::stderr().flush().context("Failed to flush prompt")?;
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read confirmation")?;
+    if is_yes(&answer) {
+        Ok(())
+    } else {
+        Err(AppError::new(ErrorKind::Safety, "Canceled; nothing was changed").into())
+    }
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// `"1 batch"` / `"3 batches"`-style counts for prompt summaries.
+pub fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+fn read_only_refusal(policy: &ProfilePolicy, profile: &str, action: &str) -> Option<String> {
+    policy.read_only.then(|| {
+        format!(
+            "Profile '{}' is read-only (permissions.readOnly); refusing to {}",
+            profile, action
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_yes, plural, read_only_refusal};
+    use crate::config::ProfilePolicy;
+
+    #[test]
+    fn only_explicit_yes_confirms() {
+        assert!(is_yes("y\n") && is_yes(" YES "));
+        assert!(!is_yes("\n") && !is_yes("n") && !is_yes("yep"));
+        assert_eq!(plural(1, "batch", "batches"), "1 batch");
+        assert_eq!(plural(3, "batch", "batches"), "3 batches");
+    }
+
+    #[test]
+    fn read_only_profiles_refuse_every_write() {
+        let open = ProfilePolicy::default();
+        assert!(read_only_refusal(&open, "dev", "execute procedure dbo.Purge").is_none());
+
+        let locked = ProfilePolicy {
+            read_only: true,
+            ..ProfilePolicy::default()
+        };
+        let message = read_only_refusal(&locked, "prod", "execute procedure dbo.Purge").unwrap();
+        assert!(message.contains("Profile 'prod' is read-only"));
+        assert!(message.ends_with("refusing to execute procedure dbo.Purge"));
+    }
+}
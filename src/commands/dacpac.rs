@@ -0,0 +1,1011 @@
+//! `.dacpac` interop with SSDT.
+//!
+//! Import turns a package's `model.xml` back into CREATE scripts: schemas, tables, keys,
+//! indexes, constraints, and the views, procedures, functions and triggers whose source text
+//! the model keeps. Callers build those scripts in a shadow database and compare that, so
+//! definitions are normalized by SQL Server exactly as for a live source.
+//!
+//! Export writes a minimal package (tables, keys, indexes, defaults, checks and foreign keys)
+//! that SqlPackage and DacFx load. Programmable objects are not exported because DacFx models
+//! their parameters and dependencies as elements the catalog does not describe.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+
+use roxmltree::{Document, Node};
+
+use crate::commands::dacpac_package;
+use crate::db::queries::bracket;
+use crate::error::{AppError, ErrorKind};
+
+const MODEL_NAMESPACE: &str = "http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02";
+/// SQL Server 2019 platform; DacFx deploys such a model to 2019 and later.
+const DSP_NAME: &str = "Microsoft.Data.Tools.Schema.Sql.Sql150DatabaseSchemaProvider";
+const BUILTIN_SCHEMAS: &[&str] = &["dbo", "guest", "sys", "INFORMATION_SCHEMA"];
+
+/// CREATE scripts rebuilt from a package, in dependency-friendly order.
+#[derive(Debug, Default)]
+pub(crate) struct ImportedModel {
+    /// `(element name, script)` pairs.
+    pub(crate) scripts: Vec<(String, String)>,
+    /// Element types that could not be scripted, with how many were seen.
+    pub(crate) skipped: BTreeMap<String, usize>,
+}
+
+/// Read `path` and script every element of its model that sscli understands.
+pub(crate) fn read_model(path: &Path) -> Result<ImportedModel> {
+    let archive =
+        fs::read(path).with_context(|| format!("Failed to read dacpac {}", path.display()))?;
+    let model = dacpac_package::read_entry(&archive, "model.xml")
+        .with_context(|| format!("Invalid dacpac {}", path.display()))?
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Config,
+                format!("{} has no model.xml; is it a dacpac?", path.display()),
+            )
+        })?;
+    let text = String::from_utf8(model)
+        .map_err(|_| anyhow!("model.xml in {} is not UTF-8", path.display()))?;
+    import_model(&text).with_context(|| format!("Invalid model.xml in {}", path.display()))
+}
+
+fn import_model(xml: &str) -> Result<ImportedModel> {
+    let document = Document::parse(xml)?;
+    let root = document.root_element();
+    let model = Some(root)
+        .filter(|doc| doc.tag_name().name() == "DataSchemaModel")
+        .and_then(|doc| doc.child("Model"))
+        .ok_or_else(|| anyhow!("missing DataSchemaModel/Model"))?;
+
+    let mut imported = ImportedModel::default();
+    // Schemas and tables first so the shadow build needs fewer retry passes.
+    let mut deferred = Vec::new();
+    for element in model.children_named("Element") {
+        let kind = element.attribute("Type").unwrap_or_default();
+        let name = element.attribute("Name").unwrap_or_default().to_string();
+        let script = match kind {
+            "SqlDatabaseOptions" => continue,
+            "SqlSchema" => match split_name(&name).first() {
+                Some(schema) if BUILTIN_SCHEMAS.contains(&schema.as_str()) => continue,
+                Some(schema) => Some(format!("CREATE SCHEMA {};", bracket(schema))),
+                None => None,
+            },
+            "SqlTable" => table_script(element),
+            "SqlPrimaryKeyConstraint" | "SqlUniqueConstraint" => key_script(element, kind),
+            "SqlForeignKeyConstraint" => foreign_key_script(element),
+            "SqlCheckConstraint" => check_script(element),
+            "SqlDefaultConstraint" => default_script(element),
+            "SqlIndex" => index_script(element),
+            "SqlView"
+            | "SqlProcedure"
+            | "SqlScalarFunction"
+            | "SqlInlineTableValuedFunction"
+            | "SqlMultiStatementTableValuedFunction"
+            | "SqlTableValuedFunction"
+            | "SqlDmlTrigger" => module_script(element),
+            _ => None,
+        };
+        match script {
+            Some(sql) if matches!(kind, "SqlSchema" | "SqlTable") => {
+                imported.scripts.push((name, sql))
+            }
+            Some(sql) => deferred.push((name, sql)),
+            None => *imported.skipped.entry(kind.to_string()).or_default() += 1,
+        }
+    }
+    imported.scripts.extend(deferred);
+    Ok(imported)
+}
+
+fn table_script(table: Node<'_, '_>) -> Option<String> {
+    let columns = table
+        .related_elements("Columns")
+        .map(column_definition)
+        .collect::<Option<Vec<_>>>()?;
+    if columns.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "CREATE TABLE {} (\n    {}\n);",
+        quote_model_name(table.attribute("Name")?),
+        columns.join(",\n    ")
+    ))
+}
+
+fn column_definition(column: Node<'_, '_>) -> Option<String> {
+    let name = bracket(split_name(column.attribute("Name")?).last()?);
+    if column.attribute("Type") == Some("SqlComputedColumn") {
+        let persisted = if column.property("IsPersisted").as_deref() == Some("True") {
+            " PERSISTED"
+        } else {
+            ""
+        };
+        return Some(format!(
+            "{} AS ({}){}",
+            name,
+            column.property("ExpressionScript")?,
+            persisted
+        ));
+    }
+    let mut definition = format!("{} {}", name, type_specifier(column)?);
+    if column.property("IsIdentity").as_deref() == Some("True") {
+        let seed = column.deep_property("Seed").unwrap_or_else(|| "1".into());
+        let increment = column
+            .deep_property("Increment")
+            .unwrap_or_else(|| "1".into());
+        let _ = write!(definition, " IDENTITY({}, {})", seed, increment);
+    }
+    // DacFx omits IsNullable for nullable columns.
+    if column.property("IsNullable").as_deref() == Some("False") {
+        definition.push_str(" NOT NULL");
+    } else {
+        definition.push_str(" NULL");
+    }
+    Some(definition)
+}
+
+fn type_specifier(column: Node<'_, '_>) -> Option<String> {
+    let spec = column.related_elements("TypeSpecifier").next()?;
+    let type_ref = spec.references("Type").into_iter().next()?;
+    let parts = split_name(&type_ref);
+    let base = match parts.as_slice() {
+        [builtin] => builtin.to_ascii_lowercase(),
+        _ => quote_model_name(&type_ref),
+    };
+    let suffix = if spec.property("IsMax").as_deref() == Some("True") {
+        "(MAX)".to_string()
+    } else if let Some(length) = spec.property("Length") {
+        format!("({})", length)
+    } else if matches!(base.as_str(), "decimal" | "numeric") {
+        format!(
+            "({}, {})",
+            spec.property("Precision").unwrap_or_else(|| "18".into()),
+            spec.property("Scale").unwrap_or_else(|| "0".into())
+        )
+    } else if base == "float" {
+        spec.property("Precision")
+            .map(|p| format!("({})", p))
+            .unwrap_or_default()
+    } else if matches!(base.as_str(), "datetime2" | "datetimeoffset" | "time") {
+        spec.property("Scale")
+            .map(|s| format!("({})", s))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+    Some(format!("{}{}", base, suffix))
+}
+
+fn constraint_prefix(element: Node<'_, '_>) -> Option<(String, String)> {
+    let table = element.references("DefiningTable").into_iter().next()?;
+    let name = element
+        .attribute("Name")
+        .and_then(|n| split_name(n).last().cloned())
+        .map(|n| format!("CONSTRAINT {} ", bracket(&n)))
+        .unwrap_or_default();
+    Some((quote_model_name(&table), name))
+}
+
+fn key_script(element: Node<'_, '_>, kind: &str) -> Option<String> {
+    let (table, name) = constraint_prefix(element)?;
+    let primary = kind == "SqlPrimaryKeyConstraint";
+    let clustered = match element.property("IsClustered").as_deref() {
+        Some(value) => value == "True",
+        None => primary,
+    };
+    Some(format!(
+        "ALTER TABLE {} ADD {}{} {} ({});",
+        table,
+        name,
+        if primary { "PRIMARY KEY" } else { "UNIQUE" },
+        if clustered {
+            "CLUSTERED"
+        } else {
+            "NONCLUSTERED"
+        },
+        indexed_columns(element)?
+    ))
+}
+
+fn foreign_key_script(element: Node<'_, '_>) -> Option<String> {
+    let (table, name) = constraint_prefix(element)?;
+    let columns = column_list(&element.references("Columns"))?;
+    let foreign_table = element.references("ForeignTable").into_iter().next()?;
+    let foreign_columns = column_list(&element.references("ForeignColumns"))?;
+    let mut script = format!(
+        "ALTER TABLE {} ADD {}FOREIGN KEY ({}) REFERENCES {} ({})",
+        table,
+        name,
+        columns,
+        quote_model_name(&foreign_table),
+        foreign_columns
+    );
+    for (property, clause) in [("DeleteAction", "ON DELETE"), ("UpdateAction", "ON UPDATE")] {
+        let action = match element.property(property).as_deref() {
+            Some("1" | "Cascade") => "CASCADE",
+            Some("2" | "SetNull") => "SET NULL",
+            Some("3" | "SetDefault") => "SET DEFAULT",
+            _ => continue,
+        };
+        let _ = write!(script, " {} {}", clause, action);
+    }
+    script.push(';');
+    Some(script)
+}
+
+fn check_script(element: Node<'_, '_>) -> Option<String> {
+    let (table, name) = constraint_prefix(element)?;
+    Some(format!(
+        "ALTER TABLE {} ADD {}CHECK ({});",
+        table,
+        name,
+        element.property("CheckExpressionScript")?
+    ))
+}
+
+fn default_script(element: Node<'_, '_>) -> Option<String> {
+    let (table, name) = constraint_prefix(element)?;
+    let column = element.references("ForColumn").into_iter().next()?;
+    Some(format!(
+        "ALTER TABLE {} ADD {}DEFAULT ({}) FOR {};",
+        table,
+        name,
+        element.property("DefaultExpressionScript")?,
+        bracket(split_name(&column).last()?)
+    ))
+}
+
+fn index_script(element: Node<'_, '_>) -> Option<String> {
+    let name = split_name(element.attribute("Name")?).last()?.clone();
+    let table = element.references("IndexedObject").into_iter().next()?;
+    let mut script = format!(
+        "CREATE {}{} INDEX {} ON {} ({})",
+        if element.property("IsUnique").as_deref() == Some("True") {
+            "UNIQUE "
+        } else {
+            ""
+        },
+        if element.property("IsClustered").as_deref() == Some("True") {
+            "CLUSTERED"
+        } else {
+            "NONCLUSTERED"
+        },
+        bracket(&name),
+        quote_model_name(&table),
+        indexed_columns(element)?
+    );
+    let included = element.references("IncludedColumns");
+    if !included.is_empty() {
+        let _ = write!(script, " INCLUDE ({})", column_list(&included)?);
+    }
+    if let Some(filter) = element.property("FilterPredicate") {
+        let _ = write!(script, " WHERE {}", filter);
+    }
+    script.push(';');
+    Some(script)
+}
+
+fn indexed_columns(element: Node<'_, '_>) -> Option<String> {
+    let columns = element
+        .related_elements("ColumnSpecifications")
+        .map(|spec| {
+            let column = spec.references("Column").into_iter().next()?;
+            let direction = if spec.property("IsAscending").as_deref() == Some("False") {
+                " DESC"
+            } else {
+                ""
+            };
+            Some(format!(
+                "{}{}",
+                bracket(split_name(&column).last()?),
+                direction
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (!columns.is_empty()).then(|| columns.join(", "))
+}
+
+fn column_list(references: &[String]) -> Option<String> {
+    let columns = references
+        .iter()
+        .map(|r| split_name(r).last().map(|c| bracket(c)))
+        .collect::<Option<Vec<_>>>()?;
+    (!columns.is_empty()).then(|| columns.join(", "))
+}
+
+/// The module's CREATE text: the header kept in its source annotation, then the body.
+fn module_script(element: Node<'_, '_>) -> Option<String> {
+    let body = element
+        .property("BodyScript")
+        .or_else(|| element.property("QueryScript"))?;
+    let annotation = element
+        .children_named("Annotation")
+        .find(|a| a.attribute("Type") == Some("SysCommentsObjectAnnotation"))?;
+    let header = annotation.property("HeaderContents")?;
+    let footer = annotation.property("FooterContents").unwrap_or_default();
+    let separator =
+        if header.ends_with(char::is_whitespace) || body.starts_with(char::is_whitespace) {
+            ""
+        } else {
+            "\n"
+        };
+    Some(format!("{}{}{}{}", header, separator, body, footer))
+}
+
+/// Catalog metadata for `write_package`.
+#[derive(Debug, Default)]
+pub(crate) struct Export {
+    pub(crate) tables: Vec<ExportTable>,
+    pub(crate) indexes: Vec<ExportIndex>,
+    pub(crate) foreign_keys: Vec<ExportForeignKey>,
+    pub(crate) checks: Vec<ExportCheck>,
+    /// Objects left out of the package, by kind.
+    pub(crate) skipped: BTreeMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportTable {
+    pub(crate) schema: String,
+    pub(crate) name: String,
+    pub(crate) columns: Vec<ExportColumn>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportColumn {
+    pub(crate) name: String,
+    pub(crate) data_type: String,
+    /// Bytes, as in `sys.columns`; -1 for MAX.
+    pub(crate) max_length: i64,
+    pub(crate) precision: i64,
+    pub(crate) scale: i64,
+    pub(crate) nullable: bool,
+    pub(crate) identity: bool,
+    pub(crate) default: Option<String>,
+    pub(crate) computed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IndexKind {
+    PrimaryKey,
+    Unique,
+    Index,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportIndex {
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) name: String,
+    pub(crate) kind: IndexKind,
+    pub(crate) unique: bool,
+    pub(crate) clustered: bool,
+    /// `(column, descending)` in key order.
+    pub(crate) keys: Vec<(String, bool)>,
+    pub(crate) includes: Vec<String>,
+    pub(crate) filter: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportForeignKey {
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) name: String,
+    pub(crate) columns: Vec<String>,
+    pub(crate) referenced_schema: String,
+    pub(crate) referenced_table: String,
+    pub(crate) referenced_columns: Vec<String>,
+    /// DacFx action codes: 1 cascade, 2 set null, 3 set default.
+    pub(crate) on_delete: u8,
+    pub(crate) on_update: u8,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportCheck {
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) name: String,
+    pub(crate) expression: String,
+}
+
+/// A `.dacpac` holding `export`, identified as `name` in its metadata.
+pub(crate) fn write_package(export: &Export, name: &str) -> Result<Vec<u8>> {
+    let model = model_xml(export);
+    let checksum = ring::digest::digest(&ring::digest::SHA256, model.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<String>();
+    let metadata = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <DacType xmlns=\"{MODEL_NAMESPACE}\">\n  <Name>{}</Name>\n  <Version>1.0.0.0</Version>\n</DacType>\n",
+        escape(name)
+    );
+    let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ");
+    let origin = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <DacOrigin xmlns=\"{MODEL_NAMESPACE}\">\n\
+         \x20 <PackageProperties>\n\
+         \x20   <Version>3.1.0.0</Version>\n\
+         \x20   <ContainsExportedData>false</ContainsExportedData>\n\
+         \x20   <StreamVersions>\n\
+         \x20     <Version StreamName=\"Data\">2.0.0.0</Version>\n\
+         \x20     <Version StreamName=\"DeploymentContributors\">1.0.0.0</Version>\n\
+         \x20   </StreamVersions>\n\
+         \x20 </PackageProperties>\n\
+         \x20 <Operation>\n\
+         \x20   <Start>{now}</Start>\n\
+         \x20   <End>{now}</End>\n\
+         \x20   <ProductName>sscli</ProductName>\n\
+         \x20   <ProductVersion>{}</ProductVersion>\n\
+         \x20   <ProductSchema>{MODEL_NAMESPACE}</ProductSchema>\n\
+         \x20 </Operation>\n\
+         \x20 <Checksums>\n\
+         \x20   <Checksum Uri=\"/model.xml\">{checksum}</Checksum>\n\
+         \x20 </Checksums>\n\
+         \x20 <ModelSchemaVersion>2.9</ModelSchemaVersion>\n\
+         </DacOrigin>\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    let content_types = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+        <Default Extension=\"xml\" ContentType=\"text/xml\" /></Types>\n";
+    dacpac_package::write_archive(&[
+        ("model.xml", model.as_bytes()),
+        ("DacMetadata.xml", metadata.as_bytes()),
+        ("Origin.xml", origin.as_bytes()),
+        ("[Content_Types].xml", content_types.as_bytes()),
+    ])
+}
+
+fn model_xml(export: &Export) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <DataSchemaModel FileFormatVersion=\"1.2\" SchemaVersion=\"2.9\" DspName=\"{DSP_NAME}\" \
+         CollationLcid=\"1033\" CollationCaseSensitive=\"False\" xmlns=\"{MODEL_NAMESPACE}\">\n\
+         \x20 <Header>\n\
+         \x20   <CustomData Category=\"AnsiNulls\"><Metadata Name=\"AnsiNulls\" Value=\"True\" /></CustomData>\n\
+         \x20   <CustomData Category=\"QuotedIdentifier\"><Metadata Name=\"QuotedIdentifier\" Value=\"True\" /></CustomData>\n\
+         \x20 </Header>\n\
+         \x20 <Model>\n"
+    );
+
+    let mut schemas: Vec<&str> = export
+        .tables
+        .iter()
+        .map(|t| t.schema.as_str())
+        .filter(|s| !BUILTIN_SCHEMAS.contains(s))
+        .collect();
+    schemas.sort_unstable();
+    schemas.dedup();
+    for schema in schemas {
+        let _ = writeln!(
+            xml,
+            "    <Element Type=\"SqlSchema\" Name=\"{}\">{}</Element>",
+            escape(&bracket(schema)),
+            reference("Authorizer", "[dbo]", true)
+        );
+    }
+
+    for table in &export.tables {
+        let table_name = model_name(&[&table.schema, &table.name]);
+        let _ = write!(
+            xml,
+            "    <Element Type=\"SqlTable\" Name=\"{}\">\n      <Relationship Name=\"Columns\">",
+            escape(&table_name)
+        );
+        for column in &table.columns {
+            xml.push_str(&column_element(table, column));
+        }
+        let _ = writeln!(
+            xml,
+            "</Relationship>\n      {}\n    </Element>",
+            schema_reference(&table.schema)
+        );
+        for column in table.columns.iter().filter(|c| c.computed.is_none()) {
+            let Some(default) = &column.default else {
+                continue;
+            };
+            let _ = writeln!(
+                xml,
+                "    <Element Type=\"SqlDefaultConstraint\">{}{}{}</Element>",
+                script_property("DefaultExpressionScript", default),
+                reference("DefiningTable", &table_name, false),
+                reference(
+                    "ForColumn",
+                    &model_name(&[&table.schema, &table.name, &column.name]),
+                    false
+                )
+            );
+        }
+    }
+
+    for index in &export.indexes {
+        let (kind, element_name) = match index.kind {
+            IndexKind::PrimaryKey => (
+                "SqlPrimaryKeyConstraint",
+                model_name(&[&index.schema, &index.name]),
+            ),
+            IndexKind::Unique => (
+                "SqlUniqueConstraint",
+                model_name(&[&index.schema, &index.name]),
+            ),
+            IndexKind::Index => (
+                "SqlIndex",
+                model_name(&[&index.schema, &index.table, &index.name]),
+            ),
+        };
+        let table_name = model_name(&[&index.schema, &index.table]);
+        let mut properties = String::new();
+        match index.kind {
+            IndexKind::PrimaryKey if !index.clustered => {
+                properties.push_str(&property("IsClustered", "False"))
+            }
+            IndexKind::Unique if index.clustered => {
+                properties.push_str(&property("IsClustered", "True"))
+            }
+            IndexKind::Index => {
+                if index.clustered {
+                    properties.push_str(&property("IsClustered", "True"));
+                }
+                if index.unique {
+                    properties.push_str(&property("IsUnique", "True"));
+                }
+                if let Some(filter) = &index.filter {
+                    properties.push_str(&script_property("FilterPredicate", filter));
+                }
+            }
+            _ => {}
+        }
+        let mut specs = String::from("<Relationship Name=\"ColumnSpecifications\">");
+        for (column, descending) in &index.keys {
+            let _ = write!(
+                specs,
+                "<Entry><Element Type=\"SqlIndexedColumnSpecification\">{}{}</Element></Entry>",
+                if *descending {
+                    property("IsAscending", "False")
+                } else {
+                    String::new()
+                },
+                reference(
+                    "Column",
+                    &model_name(&[&index.schema, &index.table, column]),
+                    false
+                )
+            );
+        }
+        specs.push_str("</Relationship>");
+        let includes = references(
+            "IncludedColumns",
+            index
+                .includes
+                .iter()
+                .map(|c| model_name(&[&index.schema, &index.table, c])),
+        );
+        let owner = if index.kind == IndexKind::Index {
+            reference("IndexedObject", &table_name, false)
+        } else {
+            reference("DefiningTable", &table_name, false)
+        };
+        let _ = writeln!(
+            xml,
+            "    <Element Type=\"{}\" Name=\"{}\">{}{}{}{}</Element>",
+            kind,
+            escape(&element_name),
+            properties,
+            specs,
+            includes,
+            owner
+        );
+    }
+
+    for fk in &export.foreign_keys {
+        let mut properties = String::new();
+        if fk.on_delete != 0 {
+            properties.push_str(&property("DeleteAction", &fk.on_delete.to_string()));
+        }
+        if fk.on_update != 0 {
+            properties.push_str(&property("UpdateAction", &fk.on_update.to_string()));
+        }
+        let _ = writeln!(
+            xml,
+            "    <Element Type=\"SqlForeignKeyConstraint\" Name=\"{}\">{}{}{}{}{}</Element>",
+            escape(&model_name(&[&fk.schema, &fk.name])),
+            properties,
+            references(
+                "Columns",
+                fk.columns
+                    .iter()
+                    .map(|c| model_name(&[&fk.schema, &fk.table, c]))
+            ),
+            reference(
+                "DefiningTable",
+                &model_name(&[&fk.schema, &fk.table]),
+                false
+            ),
+            references(
+                "ForeignColumns",
+                fk.referenced_columns.iter().map(|c| model_name(&[
+                    &fk.referenced_schema,
+                    &fk.referenced_table,
+                    c
+                ]))
+            ),
+            reference(
+                "ForeignTable",
+                &model_name(&[&fk.referenced_schema, &fk.referenced_table]),
+                false
+            )
+        );
+    }
+
+    for check in &export.checks {
+        let _ = writeln!(
+            xml,
+            "    <Element Type=\"SqlCheckConstraint\" Name=\"{}\">{}{}</Element>",
+            escape(&model_name(&[&check.schema, &check.name])),
+            script_property("CheckExpressionScript", &check.expression),
+            reference(
+                "DefiningTable",
+                &model_name(&[&check.schema, &check.table]),
+                false
+            )
+        );
+    }
+
+    xml.push_str("  </Model>\n</DataSchemaModel>\n");
+    xml
+}
+
+fn column_element(table: &ExportTable, column: &ExportColumn) -> String {
+    let name = escape(&model_name(&[&table.schema, &table.name, &column.name]));
+    if let Some(expression) = &column.computed {
+        return format!(
+            "<Entry><Element Type=\"SqlComputedColumn\" Name=\"{}\">{}</Element></Entry>",
+            name,
+            script_property("ExpressionScript", expression)
+        );
+    }
+    let mut properties = String::new();
+    if !column.nullable {
+        properties.push_str(&property("IsNullable", "False"));
+    }
+    if column.identity {
+        properties.push_str(&property("IsIdentity", "True"));
+    }
+    let data_type = column.data_type.to_ascii_lowercase();
+    let mut spec = String::new();
+    match data_type.as_str() {
+        "varchar" | "char" | "varbinary" | "binary" | "nvarchar" | "nchar" => {
+            if column.max_length == -1 {
+                spec.push_str(&property("IsMax", "True"));
+            } else {
+                let length = if data_type.starts_with('n') {
+                    column.max_length / 2
+                } else {
+                    column.max_length
+                };
+                spec.push_str(&property("Length", &length.to_string()));
+            }
+        }
+        "decimal" | "numeric" => {
+            spec.push_str(&property("Precision", &column.precision.to_string()));
+            spec.push_str(&property("Scale", &column.scale.to_string()));
+        }
+        "float" if column.precision != 53 => {
+            spec.push_str(&property("Precision", &column.precision.to_string()))
+        }
+        "datetime2" | "datetimeoffset" | "time" if column.scale != 7 => {
+            spec.push_str(&property("Scale", &column.scale.to_string()))
+        }
+        _ => {}
+    }
+    format!(
+        "<Entry><Element Type=\"SqlSimpleColumn\" Name=\"{}\">{}<Relationship Name=\"TypeSpecifier\">\
+         <Entry><Element Type=\"SqlTypeSpecifier\">{}{}</Element></Entry></Relationship></Element></Entry>",
+        name,
+        properties,
+        spec,
+        reference("Type", &bracket(&data_type), true)
+    )
+}
+
+fn property(name: &str, value: &str) -> String {
+    format!("<Property Name=\"{}\" Value=\"{}\" />", name, escape(value))
+}
+
+fn script_property(name: &str, script: &str) -> String {
+    format!(
+        "<Property Name=\"{}\"><Value><![CDATA[{}]]></Value></Property>",
+        name,
+        script.replace("]]>", "]]]]><![CDATA[>")
+    )
+}
+
+fn reference(relationship: &str, name: &str, builtin: bool) -> String {
+    format!(
+        "<Relationship Name=\"{}\"><Entry><References {}Name=\"{}\" /></Entry></Relationship>",
+        relationship,
+        if builtin {
+            "ExternalSource=\"BuiltIns\" "
+        } else {
+            ""
+        },
+        escape(name)
+    )
+}
+
+fn references(relationship: &str, names: impl Iterator<Item = String>) -> String {
+    let entries: String = names
+        .map(|name| format!("<Entry><References Name=\"{}\" /></Entry>", escape(&name)))
+        .collect();
+    if entries.is_empty() {
+        return String::new();
+    }
+    format!(
+        "<Relationship Name=\"{}\">{}</Relationship>",
+        relationship, entries
+    )
+}
+
+fn schema_reference(schema: &str) -> String {
+    reference("Schema", &bracket(schema), schema == "dbo")
+}
+
+fn model_name(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|p| bracket(p))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Re-quote a model name such as `[dbo].[Orders]` part by part.
+fn quote_model_name(name: &str) -> String {
+    split_name(name)
+        .iter()
+        .map(|p| bracket(p))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `[dbo].[Order]]s]` -> `["dbo", "Order]s"]`; unbracketed parts split on dots.
+fn split_name(name: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut chars = name.chars().peekable();
+    while chars.peek().is_some() {
+        let mut part = String::new();
+        if chars.peek() == Some(&'[') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == ']' {
+                    if chars.peek() == Some(&']') {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                part.push(c);
+            }
+            chars.next_if_eq(&'.');
+        } else {
+            for c in chars.by_ref() {
+                if c == '.' {
+                    break;
+                }
+                part.push(c);
+            }
+        }
+        parts.push(part);
+    }
+    parts
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\r', "&#xD;")
+        .replace('\n', "&#xA;")
+}
+
+/// Navigation over `model.xml` elements. DacFx writes the model in a single default namespace,
+/// so elements are matched on their local names.
+trait ModelNode<'a, 'input: 'a> {
+    fn child(self, name: &str) -> Option<Node<'a, 'input>>;
+    fn children_named(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>>;
+    fn property(self, name: &str) -> Option<String>;
+    fn deep_property(self, suffix: &str) -> Option<String>;
+    fn relationship_entries(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>>;
+    fn related_elements(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>>;
+    fn references(self, name: &'static str) -> Vec<String>;
+}
+
+impl<'a, 'input: 'a> ModelNode<'a, 'input> for Node<'a, 'input> {
+    fn child(self, name: &str) -> Option<Node<'a, 'input>> {
+        self.children().find(|c| c.tag_name().name() == name)
+    }
+
+    fn children_named(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+        self.children().filter(move |c| c.tag_name().name() == name)
+    }
+
+    /// A `<Property>` value, given inline or as a `<Value>` child.
+    fn property(self, name: &str) -> Option<String> {
+        let prop = self
+            .children_named("Property")
+            .find(|p| p.attribute("Name") == Some(name))?;
+        property_value(prop)
+    }
+
+    /// A property anywhere below this element whose name ends with `suffix`.
+    fn deep_property(self, suffix: &str) -> Option<String> {
+        self.children().filter(Node::is_element).find_map(|child| {
+            if child.tag_name().name() == "Property"
+                && child.attribute("Name").is_some_and(|n| n.ends_with(suffix))
+            {
+                property_value(child)
+            } else {
+                child.deep_property(suffix)
+            }
+        })
+    }
+
+    fn relationship_entries(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+        self.children_named("Relationship")
+            .filter(move |r| r.attribute("Name") == Some(name))
+            .flat_map(|r| r.children_named("Entry"))
+            .flat_map(|e| e.children().filter(Node::is_element))
+    }
+
+    fn related_elements(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+        self.relationship_entries(name)
+            .filter(|n| n.tag_name().name() == "Element")
+    }
+
+    fn references(self, name: &'static str) -> Vec<String> {
+        self.relationship_entries(name)
+            .filter(|n| n.tag_name().name() == "References")
+            .filter_map(|n| n.attribute("Name").map(str::to_string))
+            .collect()
+    }
+}
+
+fn property_value(prop: Node<'_, '_>) -> Option<String> {
+    prop.attribute("Value").map(str::to_string).or_else(|| {
+        prop.child("Value").map(|value| {
+            value
+                .children()
+                .filter_map(|node| node.text())
+                .collect::<String>()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_tables_keys_and_modules_from_model_xml() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<DataSchemaModel FileFormatVersion="1.2" xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Model>
+    <Element Type="SqlDatabaseOptions" />
+    <Element Type="SqlProcedure" Name="[sales].[GetOrder]">
+      <Property Name="BodyScript"><Value><![CDATA[
+BEGIN SELECT 1 WHERE 2 > 1; END]]></Value></Property>
+      <Annotation Type="SysCommentsObjectAnnotation">
+        <Property Name="HeaderContents" Value="CREATE PROCEDURE [sales].[GetOrder]&#xD;&#xA;    @Id int&#xD;&#xA;AS" />
+      </Annotation>
+    </Element>
+    <Element Type="SqlSchema" Name="[sales]" />
+    <Element Type="SqlTable" Name="[sales].[Orders]">
+      <Relationship Name="Columns">
+        <Entry>
+          <Element Type="SqlSimpleColumn" Name="[sales].[Orders].[Id]">
+            <Property Name="IsNullable" Value="False" />
+            <Property Name="IsIdentity" Value="True" />
+            <Relationship Name="TypeSpecifier"><Entry><Element Type="SqlTypeSpecifier">
+              <Relationship Name="Type"><Entry><References ExternalSource="BuiltIns" Name="[int]" /></Entry></Relationship>
+            </Element></Entry></Relationship>
+          </Element>
+        </Entry>
+        <Entry>
+          <Element Type="SqlSimpleColumn" Name="[sales].[Orders].[Total]">
+            <Relationship Name="TypeSpecifier"><Entry><Element Type="SqlTypeSpecifier">
+              <Property Name="Precision" Value="19" /><Property Name="Scale" Value="4" />
+              <Relationship Name="Type"><Entry><References ExternalSource="BuiltIns" Name="[decimal]" /></Entry></Relationship>
+            </Element></Entry></Relationship>
+          </Element>
+        </Entry>
+      </Relationship>
+    </Element>
+    <Element Type="SqlPrimaryKeyConstraint" Name="[sales].[PK_Orders]">
+      <Relationship Name="ColumnSpecifications"><Entry><Element Type="SqlIndexedColumnSpecification">
+        <Property Name="IsAscending" Value="False" />
+        <Relationship Name="Column"><Entry><References Name="[sales].[Orders].[Id]" /></Entry></Relationship>
+      </Element></Entry></Relationship>
+      <Relationship Name="DefiningTable"><Entry><References Name="[sales].[Orders]" /></Entry></Relationship>
+    </Element>
+    <Element Type="SqlSequence" Name="[sales].[OrderNumbers]" />
+  </Model>
+</DataSchemaModel>"#;
+        let model = import_model(xml).expect("valid model");
+        let scripts: Vec<&str> = model.scripts.iter().map(|(_, sql)| sql.as_str()).collect();
+        assert_eq!(
+            scripts,
+            vec![
+                "CREATE SCHEMA [sales];",
+                "CREATE TABLE [sales].[Orders] (\n    [Id] int IDENTITY(1, 1) NOT NULL,\n    [Total] decimal(19, 4) NULL\n);",
+                "CREATE PROCEDURE [sales].[GetOrder]\r\n    @Id int\r\nAS\nBEGIN SELECT 1 WHERE 2 > 1; END",
+                "ALTER TABLE [sales].[Orders] ADD CONSTRAINT [PK_Orders] PRIMARY KEY CLUSTERED ([Id] DESC);",
+            ]
+        );
+        assert_eq!(model.skipped.get("SqlSequence"), Some(&1));
+    }
+
+    #[test]
+    fn exported_package_imports_back() {
+        let export = Export {
+            tables: vec![ExportTable {
+                schema: "dbo".into(),
+                name: "Order]s".into(),
+                columns: vec![
+                    ExportColumn {
+                        name: "Id".into(),
+                        data_type: "int".into(),
+                        max_length: 4,
+                        precision: 10,
+                        scale: 0,
+                        nullable: false,
+                        identity: true,
+                        default: None,
+                        computed: None,
+                    },
+                    ExportColumn {
+                        name: "Note".into(),
+                        data_type: "nvarchar".into(),
+                        max_length: 100,
+                        precision: 0,
+                        scale: 0,
+                        nullable: true,
+                        identity: false,
+                        default: Some("(N'<none>')".into()),
+                        computed: None,
+                    },
+                ],
+            }],
+            checks: vec![ExportCheck {
+                schema: "dbo".into(),
+                table: "Order]s".into(),
+                name: "CK_Note".into(),
+                expression: "([Note]<>N'')".into(),
+            }],
+            ..Export::default()
+        };
+        let package = write_package(&export, "app").expect("writable");
+        let model = dacpac_package::read_entry(&package, "model.xml")
+            .expect("readable")
+            .expect("model.xml present");
+        let imported = import_model(&String::from_utf8(model).unwrap()).expect("valid model");
+        let scripts: Vec<&str> = imported
+            .scripts
+            .iter()
+            .map(|(_, sql)| sql.as_str())
+            .collect();
+        assert_eq!(
+            scripts,
+            vec![
+                "CREATE TABLE [dbo].[Order]]s] (\n    [Id] int IDENTITY(1, 1) NOT NULL,\n    [Note] nvarchar(50) NULL\n);",
+                "ALTER TABLE [dbo].[Order]]s] ADD DEFAULT ((N'<none>')) FOR [Note];",
+                "ALTER TABLE [dbo].[Order]]s] ADD CONSTRAINT [CK_Note] CHECK (([Note]<>N''));",
+            ]
+        );
+        assert!(imported.skipped.is_empty());
+    }
+}
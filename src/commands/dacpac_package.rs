@@ -0,0 +1,100 @@
+//! The ZIP container around a `.dacpac`.
+//!
+//! Packages written by DacFx deflate their parts; packages written here do the same. Reading
+//! stops at `MAX_ENTRY_BYTES` of extracted data, so a hostile or corrupt package cannot make the
+//! inflater allocate without bound.
+
+use std::io::{Cursor, Read, Write};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Datelike, Timelike};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+/// Largest part `read_entry` will extract; far above any real `model.xml`.
+const MAX_ENTRY_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Contents of the entry called `name` (case-insensitive), or `None` when the archive has none.
+pub(crate) fn read_entry(archive: &[u8], name: &str) -> Result<Option<Vec<u8>>> {
+    let mut zip = ZipArchive::new(Cursor::new(archive)).context("not a ZIP archive")?;
+    let Some(index) = (0..zip.len()).find(|&index| {
+        zip.name_for_index(index)
+            .is_some_and(|entry| entry.trim_start_matches('/').eq_ignore_ascii_case(name))
+    }) else {
+        return Ok(None);
+    };
+    let entry = zip
+        .by_index(index)
+        .with_context(|| format!("corrupt ZIP entry {}", name))?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(too_large(name));
+    }
+    let mut contents = Vec::with_capacity(entry.size() as usize);
+    // The declared size can lie; the cap holds against the bytes actually inflated.
+    entry
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut contents)
+        .with_context(|| format!("{} could not be extracted", name))?;
+    if contents.len() as u64 > MAX_ENTRY_BYTES {
+        return Err(too_large(name));
+    }
+    Ok(Some(contents))
+}
+
+/// A ZIP archive holding `entries`, deflated, in order.
+pub(crate) fn write_archive(entries: &[(&str, &[u8])]) -> Result<Vec<u8>> {
+    let now = chrono::Local::now().naive_local();
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(
+            DateTime::from_date_and_time(
+                now.year().clamp(1980, 2107) as u16,
+                now.month() as u8,
+                now.day() as u8,
+                now.hour() as u8,
+                now.minute() as u8,
+                now.second().min(59) as u8,
+            )
+            .unwrap_or_default(),
+        );
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in entries {
+        zip.start_file(*name, options)?;
+        zip.write_all(data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+fn too_large(name: &str) -> anyhow::Error {
+    anyhow!(
+        "{} extracts to more than {} MiB; refusing to read it",
+        name,
+        MAX_ENTRY_BYTES / (1024 * 1024)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_deflated_archive() {
+        let model = "<DataSchemaModel />".repeat(64);
+        let archive = write_archive(&[
+            ("model.xml", model.as_bytes()),
+            ("Origin.xml", b"<DacOrigin />".as_slice()),
+        ])
+        .expect("writable");
+        assert!(archive.len() < model.len());
+        assert_eq!(
+            read_entry(&archive, "MODEL.xml").expect("readable"),
+            Some(model.into_bytes())
+        );
+        assert_eq!(
+            read_entry(&archive, "origin.xml").expect("readable"),
+            Some(b"<DacOrigin />".to_vec())
+        );
+        assert_eq!(read_entry(&archive, "missing.xml").expect("readable"), None);
+        assert!(read_entry(b"not a zip", "model.xml").is_err());
+    }
+}
@@ -1,51 +1,108 @@
 use anyhow::Result;
-use serde_json::json;
 use tiberius::Query;
 
-use crate::cli::{CliArgs, DatabasesArgs};
-use crate::commands::{common, paging};
+use crate::cli::{CliArgs, DatabasesArgs, OutputFlags};
+use crate::commands::{common, databases_create_script, databases_foreach, paging};
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
-use crate::db::types::Value;
-use crate::output::{TableOptions, json as json_out, table};
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::contract::DatabasesOutput;
+use crate::output::{TableOptions, json as json_out, shape, table};
 
 const LIMIT_DEFAULT: u64 = 10;
 const LIMIT_MAX: u64 = 100;
 
+/// Sizes in MB from `sys.master_files`; without `VIEW ANY DEFINITION` only databases the caller
+/// can see have rows, and the rest show nulls.
+const SIZES_CTE: &str = r#"
+sizes AS (
+    SELECT
+        database_id AS file_database_id,
+        CAST(SUM(CASE WHEN type = 0 THEN CAST(size AS bigint) ELSE 0 END) * 8 / 1024.0 AS decimal(18, 2)) AS data_mb,
+        CAST(SUM(CASE WHEN type = 1 THEN CAST(size AS bigint) ELSE 0 END) * 8 / 1024.0 AS decimal(18, 2)) AS log_mb,
+        CAST(SUM(CAST(size AS bigint)) * 8 / 1024.0 AS decimal(18, 2)) AS total_mb
+    FROM sys.master_files
+    GROUP BY database_id
+)"#;
+
+/// Last full and log backup per database. Kept out of the listing query so a login without
+/// msdb access (or Azure SQL Database, which has no backup history) still gets the list.
+const BACKUPS_SQL: &str = r#"
+SELECT
+    database_name AS name,
+    MAX(CASE WHEN type = 'D' THEN backup_finish_date END) AS lastFullBackup,
+    MAX(CASE WHEN type = 'L' THEN backup_finish_date END) AS lastLogBackup
+FROM msdb.dbo.backupset
+GROUP BY database_name;
+"#;
+
 pub fn run(args: &CliArgs, cmd: &DatabasesArgs) -> Result<()> {
+    if let Some(sql) = &cmd.foreach {
+        return databases_foreach::run(args, cmd, sql);
+    }
+    if let Some(database) = &cmd.create_script {
+        return databases_create_script::run(args, cmd, database);
+    }
     let resolved = common::load_config(args)?;
     let format = common::output_format(args, &resolved);
     let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
     let offset = common::parse_offset(cmd.offset);
 
     let include_system = cmd.include_system;
+    let details = cmd.details;
     let name = cmd.name.clone();
     let owner = cmd.owner.clone();
+    let (size_order, output_flags) = take_size_sort(&args.output)?;
 
-    let (rows, total) = tokio::runtime::Runtime::new()?.block_on(async {
+    let (rows, total, warnings) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
 
-        let list_sql = r#"
-WITH filtered AS (
+        let order = match size_order {
+            Some(true) => "total_mb DESC, name".to_string(),
+            Some(false) => "total_mb, name".to_string(),
+            None => common::order_keys(&resolved, "name", "database_id"),
+        };
+        let (detail_select, detail_columns) = if details {
+            (
+                "compatibility_level AS compatibilityLevel, data_mb AS dataMb, log_mb AS logMb, total_mb AS sizeMb,",
+                "compatibilityLevel, dataMb, logMb, sizeMb,",
+            )
+        } else {
+            ("", "")
+        };
+        let list_sql = format!(
+            r#"
+WITH {sizes},
+filtered AS (
     SELECT
         name AS name,
         state_desc AS state,
         SUSER_SNAME(owner_sid) AS owner,
         recovery_model_desc AS recoveryModel,
+        {detail_select}
         create_date AS createdAt,
         CASE WHEN database_id <= 4 THEN 1 ELSE 0 END AS isSystem,
-        ROW_NUMBER() OVER (ORDER BY name) AS rownum
+        ROW_NUMBER() OVER (ORDER BY {order}) AS rownum
     FROM sys.databases
+    LEFT JOIN sizes ON file_database_id = database_id
     WHERE (@P1 = 1 OR database_id > 4)
       AND (@P2 IS NULL OR name LIKE @P2)
       AND (@P3 IS NULL OR SUSER_SNAME(owner_sid) = @P3)
 )
-SELECT name, state, owner, recoveryModel, createdAt, isSystem
+SELECT name, state, owner, recoveryModel, {detail_columns} createdAt, isSystem
 FROM filtered
 WHERE rownum BETWEEN (@P4 + 1) AND (@P4 + @P5)
-ORDER BY name;
-"#;
+ORDER BY {final_order};
+"#,
+            sizes = SIZES_CTE.trim_start(),
+            final_order = if size_order.is_some() {
+                "rownum".to_string()
+            } else {
+                common::order_keys(&resolved, "name", "rownum")
+            },
+        );
 
         let mut list_query = Query::new(list_sql);
         list_query.bind(if include_system { 1i32 } else { 0i32 });
@@ -55,7 +112,19 @@ ORDER BY name;
         list_query.bind(limit as i64);
 
         let list_sets = executor::run_query(list_query, &mut client).await?;
-        let list_set = list_sets.into_iter().next().unwrap_or_default();
+        let mut list_set = list_sets.into_iter().next().unwrap_or_default();
+
+        let mut warnings = Vec::new();
+        if details {
+            let backups = match executor::run_query(Query::new(BACKUPS_SQL), &mut client).await {
+                Ok(sets) => sets.into_iter().next().unwrap_or_default(),
+                Err(err) => {
+                    warnings.push(format!("Backup history unavailable: {}", err));
+                    ResultSet::default()
+                }
+            };
+            attach_backups(&mut list_set, &backups);
+        }
 
         let count_sql = r#"
 SELECT COUNT(*) AS total
@@ -76,23 +145,30 @@ WHERE (@P1 = 1 OR database_id > 4)
             .and_then(value_as_u64)
             .unwrap_or(0);
 
-        Ok::<_, anyhow::Error>((list_set, total))
+        Ok::<_, anyhow::Error>((list_set, total, warnings))
     })?;
 
+    if !matches!(format, OutputFormat::Json) {
+        for warning in &warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+    let rows = shape::apply(rows, &output_flags)?;
     let count = rows.rows.len() as u64;
     let paging = paging::build_paging(total, count, offset, limit);
 
     if matches!(format, OutputFormat::Json) {
-        let payload = json!({
-            "total": paging.total,
-            "count": paging.count,
-            "offset": paging.offset,
-            "limit": paging.limit,
-            "hasMore": paging.has_more,
-            "nextOffset": paging.next_offset,
-            "databases": json_out::result_set_rows_to_objects(&rows),
-        });
-        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        let payload = DatabasesOutput {
+            total: paging.total,
+            count: paging.count,
+            offset: paging.offset,
+            limit: paging.limit,
+            has_more: paging.has_more,
+            next_offset: paging.next_offset,
+            databases: json_out::result_set_rows_to_maps(&rows),
+            warnings,
+        };
+        let body = json_out::emit_json(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
             println!("{}", body);
         }
@@ -118,6 +194,67 @@ WHERE (@P1 = 1 OR database_id > 4)
     Ok(())
 }
 
+/// Pull a `size` key out of `--sort` so the server orders every page by total size; the other
+/// keys still sort the fetched page. `size` alone sorts largest first.
+fn take_size_sort(flags: &OutputFlags) -> Result<(Option<bool>, OutputFlags)> {
+    let mut flags = flags.clone();
+    let mut order = None;
+    let mut rest = Vec::new();
+    for key in flags.sort.drain(..) {
+        let (name, direction) = key.split_once(':').unwrap_or((&key, "desc"));
+        if !name.trim().eq_ignore_ascii_case("size") {
+            rest.push(key);
+            continue;
+        }
+        order = Some(match direction.trim().to_ascii_lowercase().as_str() {
+            "desc" => true,
+            "asc" => false,
+            other => {
+                return Err(AppError::new(
+                    ErrorKind::Config,
+                    format!("Invalid --sort direction '{}' (use asc or desc)", other),
+                )
+                .into());
+            }
+        });
+    }
+    flags.sort = rest;
+    Ok((order, flags))
+}
+
+/// Add `lastFullBackup` and `lastLogBackup` columns, matched by database name, before `createdAt`.
+fn attach_backups(list: &mut ResultSet, backups: &ResultSet) {
+    let at = list
+        .columns
+        .iter()
+        .position(|column| column.name == "createdAt")
+        .unwrap_or(list.columns.len());
+    for (offset, name) in ["lastFullBackup", "lastLogBackup"].into_iter().enumerate() {
+        list.columns.insert(
+            at + offset,
+            Column {
+                name: name.to_string(),
+                data_type: None,
+            },
+        );
+    }
+    for row in &mut list.rows {
+        let found = row.first().and_then(|name| {
+            backups
+                .rows
+                .iter()
+                .find(|backup| backup.first() == Some(name))
+        });
+        for offset in 0..2 {
+            let value = found
+                .and_then(|backup| backup.get(offset + 1))
+                .cloned()
+                .unwrap_or(Value::Null);
+            row.insert(at + offset, value);
+        }
+    }
+}
+
 fn value_as_u64(value: &Value) -> Option<u64> {
     match value {
         Value::Int(v) => (*v).try_into().ok(),
@@ -126,3 +263,62 @@ fn value_as_u64(value: &Value) -> Option<u64> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_sort_is_taken_server_side_and_backups_attach_by_name() {
+        let flags = OutputFlags {
+            json: false,
+            markdown: false,
+            pretty: false,
+            expanded: false,
+            no_color: false,
+            fields: Vec::new(),
+            sort: vec!["size".to_string(), "name:asc".to_string()],
+            filters: Vec::new(),
+        };
+        let (order, rest) = take_size_sort(&flags).unwrap();
+        assert_eq!(order, Some(true));
+        assert_eq!(rest.sort, vec!["name:asc"]);
+        let flags = OutputFlags {
+            sort: vec!["SIZE:asc".to_string()],
+            ..flags
+        };
+        assert_eq!(take_size_sort(&flags).unwrap().0, Some(false));
+
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let text = |value: &str| Value::Text(value.to_string());
+        let mut list = ResultSet {
+            columns: vec![column("name"), column("createdAt")],
+            rows: vec![
+                vec![text("Sales"), text("2020")],
+                vec![text("HR"), text("2021")],
+            ],
+        };
+        let backups = ResultSet {
+            columns: vec![
+                column("name"),
+                column("lastFullBackup"),
+                column("lastLogBackup"),
+            ],
+            rows: vec![vec![text("Sales"), text("full"), Value::Null]],
+        };
+        attach_backups(&mut list, &backups);
+        let names: Vec<&str> = list.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["name", "lastFullBackup", "lastLogBackup", "createdAt"]
+        );
+        assert_eq!(
+            list.rows[0],
+            vec![text("Sales"), text("full"), Value::Null, text("2020")]
+        );
+        assert_eq!(list.rows[1][1], Value::Null);
+    }
+}
@@ -0,0 +1,1048 @@
+//! `databases --create-script`: one ordered script that creates an empty copy of a database.
+//!
+//! The script runs top to bottom: schemas, alias and table types, sequences, every table with its
+//! defaults, checks, and indexes, then the foreign keys once all tables exist, synonyms, and the
+//! modules (functions, views, procedures, triggers) sorted so each follows what it references.
+//! `--include-files` starts it with `CREATE DATABASE` and the source's files and filegroups;
+//! `--include-security` creates users and roles before the schemas they may own and grants their
+//! permissions at the end; `--include-data` inserts the named tables' rows before the foreign
+//! keys are added. The script is printed, never run.
+//!
+//! Table types are scripted with their columns only. Partition schemes and encrypted modules
+//! cannot be scripted here and are reported as warnings.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, DatabasesArgs};
+use crate::commands::{common, dependency_order, describe, table_script};
+use crate::config::{ConnectionSettings, OutputFormat};
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::json as json_out;
+
+const FILES_SQL: &str = r#"
+SELECT df.type, df.name, df.physical_name, df.size, df.max_size, df.growth, df.is_percent_growth,
+       fg.name, fg.type, fg.is_default, CAST(DATABASEPROPERTYEX(DB_NAME(), 'Collation') AS sysname)
+FROM sys.database_files df
+LEFT JOIN sys.filegroups fg ON fg.data_space_id = df.data_space_id
+WHERE df.type IN (0, 1, 2)
+ORDER BY CASE WHEN fg.name = 'PRIMARY' THEN 0 ELSE 1 END, fg.name, df.file_id;
+"#;
+
+/// User schemas; 1-4 are dbo, guest, INFORMATION_SCHEMA, and sys, 16384 and up the fixed roles.
+const SCHEMAS_SQL: &str = r#"
+SELECT s.name, USER_NAME(s.principal_id)
+FROM sys.schemas s
+WHERE s.schema_id BETWEEN 5 AND 16383
+ORDER BY s.name;
+"#;
+
+const ALIAS_TYPES_SQL: &str = r#"
+SELECT SCHEMA_NAME(t.schema_id), t.name, TYPE_NAME(t.system_type_id), t.max_length, t.precision,
+       t.scale, t.is_nullable
+FROM sys.types t
+WHERE t.is_user_defined = 1 AND t.is_table_type = 0 AND t.is_assembly_type = 0
+ORDER BY 1, 2;
+"#;
+
+const TABLE_TYPES_SQL: &str = r#"
+SELECT SCHEMA_NAME(tt.schema_id), tt.name, c.name, TYPE_NAME(c.user_type_id), ty.is_user_defined,
+       SCHEMA_NAME(ty.schema_id), c.max_length, c.precision, c.scale, c.is_nullable
+FROM sys.table_types tt
+JOIN sys.columns c ON c.object_id = tt.type_table_object_id
+JOIN sys.types ty ON ty.user_type_id = c.user_type_id
+ORDER BY 1, 2, c.column_id;
+"#;
+
+const SEQUENCES_SQL: &str = r#"
+SELECT SCHEMA_NAME(sq.schema_id), sq.name, TYPE_NAME(sq.user_type_id),
+       CAST(sq.start_value AS nvarchar(40)), CAST(sq.increment AS nvarchar(40)),
+       CAST(sq.minimum_value AS nvarchar(40)), CAST(sq.maximum_value AS nvarchar(40)),
+       sq.is_cycling
+FROM sys.sequences sq
+ORDER BY 1, 2;
+"#;
+
+const TABLES_SQL: &str = r#"
+SELECT SCHEMA_NAME(t.schema_id), t.name
+FROM sys.tables t
+WHERE t.is_ms_shipped = 0
+ORDER BY 1, 2;
+"#;
+
+const SYNONYMS_SQL: &str = r#"
+SELECT SCHEMA_NAME(sn.schema_id), sn.name, sn.base_object_name
+FROM sys.synonyms sn
+ORDER BY 1, 2;
+"#;
+
+/// Modules with the objects they reference, triggers last; database DDL triggers have no schema.
+const MODULES_SQL: &str = r#"
+SELECT OBJECT_SCHEMA_NAME(o.object_id), o.name, m.definition, m.uses_ansi_nulls,
+       m.uses_quoted_identifier, deps.depends_on, CASE WHEN o.type = 'TR' THEN 1 ELSE 0 END
+FROM sys.objects o
+JOIN sys.sql_modules m ON m.object_id = o.object_id
+OUTER APPLY (
+  SELECT STRING_AGG(r.ref, '||') AS depends_on
+  FROM (
+    SELECT DISTINCT CASE
+             WHEN d.referenced_class = 1 AND d.referenced_id IS NOT NULL
+               THEN CONCAT(OBJECT_SCHEMA_NAME(d.referenced_id), '.', OBJECT_NAME(d.referenced_id))
+             ELSE CONCAT(ISNULL(d.referenced_schema_name, OBJECT_SCHEMA_NAME(o.object_id)), '.',
+                         d.referenced_entity_name)
+           END AS ref
+    FROM sys.sql_expression_dependencies d
+    WHERE d.referencing_id = o.object_id
+      AND d.referenced_database_name IS NULL
+      AND d.referenced_class IN (1, 6)
+  ) r
+) deps
+WHERE o.is_ms_shipped = 0 AND o.type IN ('FN', 'IF', 'TF', 'V', 'P', 'TR')
+UNION ALL
+SELECT NULL, t.name, m.definition, m.uses_ansi_nulls, m.uses_quoted_identifier, NULL, 2
+FROM sys.triggers t
+JOIN sys.sql_modules m ON m.object_id = t.object_id
+WHERE t.parent_class = 0 AND t.is_ms_shipped = 0
+ORDER BY 7, 1, 2;
+"#;
+
+const PARTITION_SCHEMES_SQL: &str = "SELECT name FROM sys.partition_schemes ORDER BY name;";
+
+/// Users and roles other than the built-in ones (principal ids 0-4 and the fixed roles).
+const PRINCIPALS_SQL: &str = r#"
+SELECT dp.name, dp.type, SUSER_SNAME(dp.sid), dp.authentication_type, dp.default_schema_name
+FROM sys.database_principals dp
+WHERE dp.principal_id > 4 AND dp.is_fixed_role = 0 AND dp.type IN ('S', 'U', 'G', 'E', 'X', 'R')
+ORDER BY CASE WHEN dp.type = 'R' THEN 1 ELSE 0 END, dp.name;
+"#;
+
+const MEMBERS_SQL: &str = r#"
+SELECT r.name, m.name
+FROM sys.database_role_members rm
+JOIN sys.database_principals r ON r.principal_id = rm.role_principal_id
+JOIN sys.database_principals m ON m.principal_id = rm.member_principal_id
+WHERE m.principal_id > 4
+ORDER BY r.name, m.name;
+"#;
+
+/// Database, object (and column), and schema permissions; `CONNECT` comes with `CREATE USER`.
+const PERMISSIONS_SQL: &str = r#"
+SELECT p.state_desc, p.permission_name,
+       CASE p.class
+         WHEN 1 THEN CONCAT('OBJECT::', QUOTENAME(OBJECT_SCHEMA_NAME(p.major_id)), '.',
+                            QUOTENAME(OBJECT_NAME(p.major_id)))
+         WHEN 3 THEN CONCAT('SCHEMA::', QUOTENAME(SCHEMA_NAME(p.major_id)))
+       END,
+       CASE WHEN p.class = 1 AND p.minor_id > 0 THEN COL_NAME(p.major_id, p.minor_id) END,
+       USER_NAME(p.grantee_principal_id)
+FROM sys.database_permissions p
+WHERE p.class IN (0, 1, 3)
+  AND p.grantee_principal_id NOT BETWEEN 1 AND 4
+  AND (p.class <> 1 OR OBJECTPROPERTY(p.major_id, 'IsMSShipped') = 0)
+  AND NOT (p.class = 0 AND p.permission_name = 'CONNECT' AND p.state = 'G')
+ORDER BY 5, 3, 2;
+"#;
+
+const DATA_TABLE_SQL: &str = r#"
+SELECT OBJECT_SCHEMA_NAME(OBJECT_ID(@P1, 'U')), OBJECT_NAME(OBJECT_ID(@P1, 'U'));
+"#;
+
+const DATA_COLUMNS_SQL: &str = r#"
+SELECT c.name, TYPE_NAME(c.system_type_id), c.max_length, c.precision, c.scale, c.is_identity,
+       c.is_computed, c.system_type_id
+FROM sys.columns c
+WHERE c.object_id = OBJECT_ID(@P1, 'U')
+ORDER BY c.column_id;
+"#;
+
+/// One data or log file from `sys.database_files`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DatabaseFile {
+    /// 0 rows, 1 log, 2 FILESTREAM or memory-optimized container.
+    kind: i64,
+    name: String,
+    physical_name: String,
+    /// Sizes and growth in 8 KB pages; `max_size` is -1 for unlimited.
+    size: i64,
+    max_size: i64,
+    growth: i64,
+    percent_growth: bool,
+    filegroup: Option<String>,
+    /// `FG`, `FD` (FILESTREAM), or `FX` (memory-optimized).
+    filegroup_type: Option<String>,
+    default_filegroup: bool,
+}
+
+/// A column copied by `--include-data`, with the type OPENJSON reads it as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DataColumn {
+    name: String,
+    type_spec: String,
+    identity: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Counts {
+    schemas: usize,
+    types: usize,
+    sequences: usize,
+    tables: usize,
+    foreign_keys: usize,
+    synonyms: usize,
+    modules: usize,
+    principals: usize,
+    permissions: usize,
+    data_rows: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CreateScript {
+    /// Sections of `GO`-terminated statements, each under a `--` heading.
+    sections: Vec<(String, Vec<String>)>,
+    counts: Counts,
+    warnings: Vec<String>,
+}
+
+impl CreateScript {
+    fn section(&mut self, heading: &str, statements: Vec<String>) {
+        if !statements.is_empty() {
+            self.sections.push((heading.to_string(), statements));
+        }
+    }
+}
+
+pub fn run(args: &CliArgs, cmd: &DatabasesArgs, database: &str) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let settings = ConnectionSettings {
+        database: database.to_string(),
+        ..resolved.connection.clone()
+    };
+    let script = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&settings).await?;
+        build(&mut client, cmd, database).await
+    })?;
+    let body = render(
+        &script,
+        database,
+        &resolved.connection.server,
+        cmd.include_files,
+    );
+
+    let target = cmd.out.as_deref().filter(|path| *path != Path::new("-"));
+    if let Some(path) = target {
+        fs::write(path, &body).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    if matches!(format, OutputFormat::Json) {
+        let counts = &script.counts;
+        let payload = json!({
+            "database": database,
+            "out": target.map(|path| path.display().to_string()),
+            "counts": {
+                "schemas": counts.schemas,
+                "types": counts.types,
+                "sequences": counts.sequences,
+                "tables": counts.tables,
+                "foreignKeys": counts.foreign_keys,
+                "synonyms": counts.synonyms,
+                "modules": counts.modules,
+                "principals": counts.principals,
+                "permissions": counts.permissions,
+                "dataRows": counts.data_rows,
+            },
+            "warnings": script.warnings,
+            "script": body,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    match target {
+        Some(path) if !args.quiet => println!("{}", path.display()),
+        Some(_) => {}
+        None => print!("{}", body),
+    }
+    for warning in &script.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+async fn build(
+    client: &mut SqlClient,
+    cmd: &DatabasesArgs,
+    database: &str,
+) -> Result<CreateScript> {
+    let mut script = CreateScript::default();
+
+    let file_rows = fetch(client, FILES_SQL).await?;
+    let files: Vec<DatabaseFile> = file_rows
+        .rows
+        .iter()
+        .map(|row| DatabaseFile {
+            kind: int(row.first()),
+            name: text(row.get(1)).unwrap_or_default(),
+            physical_name: text(row.get(2)).unwrap_or_default(),
+            size: int(row.get(3)),
+            max_size: int(row.get(4)),
+            growth: int(row.get(5)),
+            percent_growth: flag(row.get(6)),
+            filegroup: text(row.get(7)),
+            filegroup_type: text(row.get(8)),
+            default_filegroup: flag(row.get(9)),
+        })
+        .collect();
+    if cmd.include_files {
+        let collation = file_rows.rows.first().and_then(|row| text(row.get(10)));
+        script.section(
+            "Database, files, and filegroups",
+            create_database(database, &files, collation.as_deref()),
+        );
+    } else {
+        let mut filegroups: Vec<&str> = files
+            .iter()
+            .filter_map(|file| file.filegroup.as_deref())
+            .filter(|name| *name != "PRIMARY")
+            .collect();
+        filegroups.dedup();
+        if !filegroups.is_empty() {
+            script.warnings.push(format!(
+                "Filegroups {} are only created with --include-files; tables and indexes stored on them need them to exist",
+                filegroups.join(", ")
+            ));
+        }
+    }
+    let schemes = column_texts(&fetch(client, PARTITION_SCHEMES_SQL).await?, 0);
+    if !schemes.is_empty() {
+        script.warnings.push(format!(
+            "Partition schemes are not scripted; create {} and their functions before running",
+            schemes.join(", ")
+        ));
+    }
+
+    if cmd.include_security {
+        let principals = fetch(client, PRINCIPALS_SQL).await?;
+        let mut statements = Vec::new();
+        for row in &principals.rows {
+            let (statement, warning) = principal_statement(
+                &text(row.first()).unwrap_or_default(),
+                &text(row.get(1)).unwrap_or_default(),
+                text(row.get(2)).as_deref(),
+                int(row.get(3)),
+                text(row.get(4)).as_deref(),
+            );
+            statements.push(statement);
+            script.warnings.extend(warning);
+        }
+        script.counts.principals = statements.len();
+        let members = fetch(client, MEMBERS_SQL).await?;
+        statements.extend(members.rows.iter().map(|row| {
+            format!(
+                "ALTER ROLE {} ADD MEMBER {};",
+                bracket(&text(row.first()).unwrap_or_default()),
+                bracket(&text(row.get(1)).unwrap_or_default())
+            )
+        }));
+        script.section("Users, roles, and role membership", statements);
+    }
+
+    let schemas = fetch(client, SCHEMAS_SQL).await?;
+    script.counts.schemas = schemas.rows.len();
+    let statements = schemas
+        .rows
+        .iter()
+        .map(|row| {
+            let name = bracket(&text(row.first()).unwrap_or_default());
+            match text(row.get(1)) {
+                Some(owner) if cmd.include_security && owner != "dbo" => {
+                    format!("CREATE SCHEMA {} AUTHORIZATION {};", name, bracket(&owner))
+                }
+                _ => format!("CREATE SCHEMA {};", name),
+            }
+        })
+        .collect();
+    script.section("Schemas", statements);
+
+    let mut statements: Vec<String> = fetch(client, ALIAS_TYPES_SQL)
+        .await?
+        .rows
+        .iter()
+        .map(|row| {
+            format!(
+                "CREATE TYPE {}.{} FROM {}{};",
+                bracket(&text(row.first()).unwrap_or_default()),
+                bracket(&text(row.get(1)).unwrap_or_default()),
+                type_spec(&text(row.get(2)).unwrap_or_default(), row, 3),
+                if flag(row.get(6)) { "" } else { " NOT NULL" }
+            )
+        })
+        .collect();
+    statements.extend(table_types(&fetch(client, TABLE_TYPES_SQL).await?));
+    script.counts.types = statements.len();
+    script.section("Types", statements);
+
+    let statements: Vec<String> = fetch(client, SEQUENCES_SQL)
+        .await?
+        .rows
+        .iter()
+        .map(|row| {
+            format!(
+                "CREATE SEQUENCE {}.{} AS {} START WITH {} INCREMENT BY {} MINVALUE {} MAXVALUE {} {};",
+                bracket(&text(row.first()).unwrap_or_default()),
+                bracket(&text(row.get(1)).unwrap_or_default()),
+                text(row.get(2)).unwrap_or_default(),
+                text(row.get(3)).unwrap_or_default(),
+                text(row.get(4)).unwrap_or_default(),
+                text(row.get(5)).unwrap_or_default(),
+                text(row.get(6)).unwrap_or_default(),
+                if flag(row.get(7)) { "CYCLE" } else { "NO CYCLE" }
+            )
+        })
+        .collect();
+    script.counts.sequences = statements.len();
+    script.section("Sequences", statements);
+
+    let tables = fetch(client, TABLES_SQL).await?;
+    let mut statements = Vec::new();
+    let mut foreign_keys = Vec::new();
+    for row in &tables.rows {
+        let schema = text(row.first()).unwrap_or_default();
+        let name = text(row.get(1)).unwrap_or_default();
+        if let Some(table) = table_script::table_statements(client, &name, &schema).await? {
+            statements.extend(table.table);
+            foreign_keys.extend(table.foreign_keys);
+            script.counts.tables += 1;
+        }
+    }
+    script.section("Tables, defaults, checks, and indexes", statements);
+
+    let mut statements = Vec::new();
+    for table in &cmd.include_data {
+        let (statement, rows) = table_data(client, table, database).await?;
+        statements.push(statement);
+        script.counts.data_rows += rows;
+    }
+    script.section("Data", statements);
+
+    script.counts.foreign_keys = foreign_keys
+        .iter()
+        .filter(|statement| statement.contains(" FOREIGN KEY "))
+        .count();
+    script.section("Foreign keys", foreign_keys);
+
+    let statements: Vec<String> = fetch(client, SYNONYMS_SQL)
+        .await?
+        .rows
+        .iter()
+        .map(|row| {
+            format!(
+                "CREATE SYNONYM {}.{} FOR {};",
+                bracket(&text(row.first()).unwrap_or_default()),
+                bracket(&text(row.get(1)).unwrap_or_default()),
+                text(row.get(2)).unwrap_or_default()
+            )
+        })
+        .collect();
+    script.counts.synonyms = statements.len();
+    script.section("Synonyms", statements);
+
+    let modules = fetch(client, MODULES_SQL).await?;
+    let name = |row: &Vec<Value>| match text(row.first()) {
+        Some(schema) => format!("{}.{}", schema, text(row.get(1)).unwrap_or_default()),
+        None => text(row.get(1)).unwrap_or_default(),
+    };
+    let sorted = dependency_order::sort(&modules.rows, name, |row| {
+        text(row.get(5))
+            .map(|deps| deps.split("||").map(str::to_string).collect())
+            .unwrap_or_default()
+    });
+    if !sorted.cyclic.is_empty() {
+        script.warnings.push(format!(
+            "Circular references; run order may need adjusting: {}",
+            sorted
+                .cyclic
+                .iter()
+                .map(|row| name(row))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let mut statements = Vec::new();
+    for row in sorted.ordered {
+        let Some(definition) = text(row.get(2)) else {
+            script.warnings.push(format!(
+                "{} is encrypted; its definition cannot be scripted",
+                name(row)
+            ));
+            statements.push(format!(
+                "-- {} is encrypted and was not scripted",
+                name(row)
+            ));
+            continue;
+        };
+        let options: Vec<&str> = [
+            (flag(row.get(3)), "ANSI_NULLS"),
+            (flag(row.get(4)), "QUOTED_IDENTIFIER"),
+        ]
+        .into_iter()
+        .filter(|(on, _)| !on)
+        .map(|(_, option)| option)
+        .collect();
+        for option in &options {
+            statements.push(format!("SET {} OFF;", option));
+        }
+        statements.push(definition.trim().to_string());
+        for option in &options {
+            statements.push(format!("SET {} ON;", option));
+        }
+        script.counts.modules += 1;
+    }
+    script.section("Functions, views, procedures, and triggers", statements);
+
+    if cmd.include_security {
+        let permissions = fetch(client, PERMISSIONS_SQL).await?;
+        let statements: Vec<String> = permissions
+            .rows
+            .iter()
+            .map(|row| {
+                permission_statement(
+                    &text(row.first()).unwrap_or_default(),
+                    &text(row.get(1)).unwrap_or_default(),
+                    text(row.get(2)).as_deref(),
+                    text(row.get(3)).as_deref(),
+                    &text(row.get(4)).unwrap_or_default(),
+                )
+            })
+            .collect();
+        script.counts.permissions = statements.len();
+        script.section("Permissions", statements);
+    }
+    Ok(script)
+}
+
+fn render(script: &CreateScript, database: &str, server: &str, include_files: bool) -> String {
+    let mut out = vec![
+        format!(
+            "-- Create script for {} on {} (sscli {}).",
+            bracket(database),
+            server,
+            env!("CARGO_PKG_VERSION")
+        ),
+        if include_files {
+            "-- Generated only; rename the database and its FILENAME paths before running it on the same server."
+        } else {
+            "-- Generated only; run it in an empty database."
+        }
+        .to_string(),
+        "SET ANSI_NULLS ON;".to_string(),
+        "SET QUOTED_IDENTIFIER ON;".to_string(),
+        "GO".to_string(),
+    ];
+    for (heading, statements) in &script.sections {
+        out.push(String::new());
+        out.push(format!("-- {}", heading));
+        for statement in statements {
+            out.push(statement.clone());
+            if !statement.starts_with("--") {
+                out.push("GO".to_string());
+            }
+        }
+    }
+    out.join("\n") + "\n"
+}
+
+/// `CREATE DATABASE` with the source's files, filegroups, and collation, then `USE` it.
+fn create_database(database: &str, files: &[DatabaseFile], collation: Option<&str>) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = "PRIMARY";
+    for file in files.iter().filter(|file| file.kind != 1) {
+        let filegroup = file.filegroup.as_deref().unwrap_or("PRIMARY");
+        let header = if filegroup != current {
+            current = filegroup;
+            format!(
+                "FILEGROUP {}{}\n",
+                bracket(filegroup),
+                match file.filegroup_type.as_deref() {
+                    Some("FD") => " CONTAINS FILESTREAM",
+                    Some("FX") => " CONTAINS MEMORY_OPTIMIZED_DATA",
+                    _ => "",
+                }
+            )
+        } else {
+            String::new()
+        };
+        entries.push(format!("{}    {}", header, file_spec(file)));
+    }
+    let logs: Vec<String> = files
+        .iter()
+        .filter(|file| file.kind == 1)
+        .map(|file| format!("    {}", file_spec(file)))
+        .collect();
+
+    let mut statement = format!(
+        "CREATE DATABASE {}\nON PRIMARY\n{}",
+        bracket(database),
+        entries.join(",\n")
+    );
+    if !logs.is_empty() {
+        statement.push_str(&format!("\nLOG ON\n{}", logs.join(",\n")));
+    }
+    if let Some(collation) = collation {
+        statement.push_str(&format!("\nCOLLATE {}", collation));
+    }
+    statement.push(';');
+    let mut statements = vec![statement];
+    if let Some(filegroup) = files
+        .iter()
+        .filter(|file| file.default_filegroup)
+        .filter_map(|file| file.filegroup.as_deref())
+        .find(|name| *name != "PRIMARY")
+    {
+        statements.push(format!(
+            "ALTER DATABASE {} MODIFY FILEGROUP {} DEFAULT;",
+            bracket(database),
+            bracket(filegroup)
+        ));
+    }
+    statements.push(format!("USE {};", bracket(database)));
+    statements
+}
+
+fn file_spec(file: &DatabaseFile) -> String {
+    let name = format!(
+        "NAME = N'{}', FILENAME = N'{}'",
+        file.name.replace('\'', "''"),
+        file.physical_name.replace('\'', "''")
+    );
+    if file.kind == 2 {
+        return format!("({})", name);
+    }
+    format!(
+        "({}, SIZE = {}KB, MAXSIZE = {}, FILEGROWTH = {})",
+        name,
+        file.size * 8,
+        if file.max_size < 0 {
+            "UNLIMITED".to_string()
+        } else {
+            format!("{}KB", file.max_size * 8)
+        },
+        if file.percent_growth {
+            format!("{}%", file.growth)
+        } else {
+            format!("{}KB", file.growth * 8)
+        }
+    )
+}
+
+/// `CREATE USER` or `CREATE ROLE`, with a warning when the principal cannot be recreated as is.
+fn principal_statement(
+    name: &str,
+    kind: &str,
+    login: Option<&str>,
+    authentication: i64,
+    default_schema: Option<&str>,
+) -> (String, Option<String>) {
+    if kind == "R" {
+        return (format!("CREATE ROLE {};", bracket(name)), None);
+    }
+    let schema = default_schema
+        .filter(|schema| *schema != "dbo")
+        .map(|schema| format!(" WITH DEFAULT_SCHEMA = {}", bracket(schema)))
+        .unwrap_or_default();
+    let user = bracket(name);
+    match (kind, authentication, login) {
+        ("E" | "X", _, _) => (
+            format!("CREATE USER {} FROM EXTERNAL PROVIDER{};", user, schema),
+            None,
+        ),
+        (_, 2, _) => (
+            format!(
+                "-- {} is a contained user with a password: CREATE USER {} WITH PASSWORD = '...'{};",
+                user, user, schema
+            ),
+            Some(format!(
+                "Contained user {} has a password and was scripted as a comment",
+                name
+            )),
+        ),
+        (_, 0, _) => (
+            format!("CREATE USER {} WITHOUT LOGIN{};", user, schema),
+            None,
+        ),
+        (_, 3, None) => (format!("CREATE USER {}{};", user, schema), None),
+        (_, _, Some(login)) => (
+            format!(
+                "CREATE USER {} FOR LOGIN {}{};",
+                user,
+                bracket(login),
+                schema
+            ),
+            None,
+        ),
+        (_, _, None) => (
+            format!("CREATE USER {} WITHOUT LOGIN{};", user, schema),
+            Some(format!(
+                "User {} has no matching login on the source server; scripted WITHOUT LOGIN",
+                name
+            )),
+        ),
+    }
+}
+
+fn permission_statement(
+    state: &str,
+    permission: &str,
+    securable: Option<&str>,
+    column: Option<&str>,
+    grantee: &str,
+) -> String {
+    let on = securable
+        .map(|securable| {
+            let column = column
+                .map(|column| format!(" ({})", bracket(column)))
+                .unwrap_or_default();
+            format!(" ON {}{}", securable, column)
+        })
+        .unwrap_or_default();
+    let (verb, suffix) = match state {
+        "DENY" => ("DENY", ""),
+        "GRANT_WITH_GRANT_OPTION" => ("GRANT", " WITH GRANT OPTION"),
+        _ => ("GRANT", ""),
+    };
+    format!(
+        "{} {}{} TO {}{};",
+        verb,
+        permission,
+        on,
+        bracket(grantee),
+        suffix
+    )
+}
+
+/// `CREATE TYPE ... AS TABLE` per table type, from rows of [`TABLE_TYPES_SQL`].
+fn table_types(rows: &ResultSet) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    for row in &rows.rows {
+        let target = format!(
+            "{}.{}",
+            bracket(&text(row.first()).unwrap_or_default()),
+            bracket(&text(row.get(1)).unwrap_or_default())
+        );
+        let column_type = if flag(row.get(4)) {
+            format!(
+                "{}.{}",
+                bracket(&text(row.get(5)).unwrap_or_default()),
+                bracket(&text(row.get(3)).unwrap_or_default())
+            )
+        } else {
+            type_spec(&text(row.get(3)).unwrap_or_default(), row, 6)
+        };
+        let column = format!(
+            "{} {} {}",
+            bracket(&text(row.get(2)).unwrap_or_default()),
+            column_type,
+            if flag(row.get(9)) { "NULL" } else { "NOT NULL" }
+        );
+        match &mut current {
+            Some((name, columns)) if *name == target => columns.push(column),
+            _ => {
+                statements.extend(current.take().map(table_type));
+                current = Some((target, vec![column]));
+            }
+        }
+    }
+    statements.extend(current.map(table_type));
+    statements
+}
+
+fn table_type((target, columns): (String, Vec<String>)) -> String {
+    format!(
+        "CREATE TYPE {} AS TABLE (\n    {}\n);",
+        target,
+        columns.join(",\n    ")
+    )
+}
+
+/// An `INSERT` of every row in `table`, carried as one JSON document, and the row count.
+///
+/// The rows are read with `FOR JSON` so SQL Server does the escaping and `OPENJSON` reads them
+/// back with each column's own type; binary values travel as base64.
+async fn table_data(
+    client: &mut SqlClient,
+    table: &str,
+    database: &str,
+) -> Result<(String, usize)> {
+    let mut query = Query::new(DATA_TABLE_SQL);
+    query.bind(table);
+    let found = first_set(executor::run_query(query, client).await?);
+    let (Some(schema), Some(name)) = found
+        .rows
+        .first()
+        .map(|row| (text(row.first()), text(row.get(1))))
+        .unwrap_or_default()
+    else {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("--include-data: no table '{}' in {}", table, database),
+        )
+        .into());
+    };
+    let target = format!("{}.{}", bracket(&schema), bracket(&name));
+
+    let mut query = Query::new(DATA_COLUMNS_SQL);
+    query.bind(target.as_str());
+    let columns: Vec<DataColumn> = first_set(executor::run_query(query, client).await?)
+        .rows
+        .iter()
+        // Computed and rowversion columns fill themselves; CLR types and sql_variant have no
+        // JSON form.
+        .filter(|row| !flag(row.get(6)) && !matches!(int(row.get(7)), 189 | 240 | 98))
+        .map(|row| {
+            let type_name = text(row.get(1)).unwrap_or_default();
+            DataColumn {
+                name: text(row.first()).unwrap_or_default(),
+                type_spec: match type_name.as_str() {
+                    "xml" | "ntext" => "nvarchar(max)".to_string(),
+                    "text" => "varchar(max)".to_string(),
+                    "image" => "varbinary(max)".to_string(),
+                    _ => type_spec(&type_name, row, 2),
+                },
+                identity: flag(row.get(5)),
+            }
+        })
+        .collect();
+
+    let select = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} AS [c{}]", bracket(&column.name), i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT {} FROM {} FOR JSON PATH, INCLUDE_NULL_VALUES;",
+        select, target
+    );
+    let document: String = fetch(client, &sql)
+        .await?
+        .rows
+        .iter()
+        .filter_map(|row| text(row.first()))
+        .collect();
+    let rows = serde_json::from_str::<Vec<serde_json::Value>>(&document)
+        .map(|rows| rows.len())
+        .unwrap_or_default();
+    if rows == 0 {
+        return Ok((format!("-- {} has no rows", target), 0));
+    }
+    Ok((insert_statement(&target, &columns, &document), rows))
+}
+
+fn insert_statement(target: &str, columns: &[DataColumn], document: &str) -> String {
+    let names = columns
+        .iter()
+        .map(|column| bracket(&column.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let aliases = (1..=columns.len())
+        .map(|i| format!("[c{}]", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let with = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("[c{}] {} '$.c{}'", i + 1, column.type_spec, i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let insert = format!(
+        "INSERT INTO {} ({})\nSELECT {}\nFROM OPENJSON(N'{}')\nWITH ({});",
+        target,
+        names,
+        aliases,
+        document.replace('\'', "''"),
+        with
+    );
+    if columns.iter().any(|column| column.identity) {
+        format!(
+            "SET IDENTITY_INSERT {} ON;\n{}\nSET IDENTITY_INSERT {} OFF;",
+            target, insert, target
+        )
+    } else {
+        insert
+    }
+}
+
+/// The type spec for the `(type, max_length, precision, scale)` columns starting at `at`.
+fn type_spec(type_name: &str, row: &[Value], at: usize) -> String {
+    describe::format_type_spec(
+        type_name,
+        Some(int(row.get(at))),
+        u8::try_from(int(row.get(at + 1))).ok(),
+        u8::try_from(int(row.get(at + 2))).ok(),
+    )
+}
+
+async fn fetch(client: &mut SqlClient, sql: &str) -> Result<ResultSet> {
+    Ok(first_set(
+        executor::run_query(Query::new(sql), client).await?,
+    ))
+}
+
+fn first_set(result_sets: Vec<ResultSet>) -> ResultSet {
+    result_sets.into_iter().next().unwrap_or_default()
+}
+
+fn column_texts(rows: &ResultSet, index: usize) -> Vec<String> {
+    rows.rows
+        .iter()
+        .filter_map(|row| text(row.get(index)))
+        .collect()
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    match value {
+        Some(Value::Null) | None => None,
+        Some(Value::Text(text)) => Some(text.clone()),
+        Some(other) => Some(other.as_csv()),
+    }
+}
+
+fn int(value: Option<&Value>) -> i64 {
+    match value {
+        Some(Value::Int(number)) => *number,
+        Some(Value::Bool(flag)) => i64::from(*flag),
+        Some(other) => other.as_csv().parse().unwrap_or_default(),
+        None => 0,
+    }
+}
+
+fn flag(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::Int(number)) => *number != 0,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(kind: i64, name: &str, filegroup: Option<&str>) -> DatabaseFile {
+        DatabaseFile {
+            kind,
+            name: name.to_string(),
+            physical_name: format!("D:\\data\\{}.mdf", name),
+            size: 1024,
+            max_size: -1,
+            growth: 8192,
+            filegroup: filegroup.map(str::to_string),
+            filegroup_type: filegroup.map(|_| "FG".to_string()),
+            ..DatabaseFile::default()
+        }
+    }
+
+    #[test]
+    fn create_database_groups_files_by_filegroup() {
+        let mut archive = file(0, "Sales_archive", Some("Archive"));
+        archive.default_filegroup = true;
+        let mut log = file(1, "Sales_log", None);
+        log.percent_growth = true;
+        log.growth = 10;
+        log.max_size = 262144;
+        let files = vec![file(0, "Sales", Some("PRIMARY")), archive, log];
+        let statements = create_database("Sales", &files, Some("Latin1_General_CI_AS"));
+        assert_eq!(
+            statements[0],
+            "CREATE DATABASE [Sales]\nON PRIMARY\n\
+             \x20   (NAME = N'Sales', FILENAME = N'D:\\data\\Sales.mdf', SIZE = 8192KB, MAXSIZE = UNLIMITED, FILEGROWTH = 65536KB),\n\
+             FILEGROUP [Archive]\n\
+             \x20   (NAME = N'Sales_archive', FILENAME = N'D:\\data\\Sales_archive.mdf', SIZE = 8192KB, MAXSIZE = UNLIMITED, FILEGROWTH = 65536KB)\n\
+             LOG ON\n\
+             \x20   (NAME = N'Sales_log', FILENAME = N'D:\\data\\Sales_log.mdf', SIZE = 8192KB, MAXSIZE = 2097152KB, FILEGROWTH = 10%)\n\
+             COLLATE Latin1_General_CI_AS;"
+        );
+        assert_eq!(
+            statements[1..],
+            [
+                "ALTER DATABASE [Sales] MODIFY FILEGROUP [Archive] DEFAULT;",
+                "USE [Sales];"
+            ]
+        );
+    }
+
+    #[test]
+    fn principals_and_permissions_script_as_they_were_created() {
+        assert_eq!(
+            principal_statement("app", "S", Some("app_login"), 1, Some("web")),
+            (
+                "CREATE USER [app] FOR LOGIN [app_login] WITH DEFAULT_SCHEMA = [web];".to_string(),
+                None
+            )
+        );
+        assert_eq!(
+            principal_statement("etl", "S", None, 0, Some("dbo")).0,
+            "CREATE USER [etl] WITHOUT LOGIN;"
+        );
+        assert!(principal_statement("ghost", "S", None, 1, None).1.is_some());
+        assert_eq!(
+            principal_statement("reader", "R", None, 0, None).0,
+            "CREATE ROLE [reader];"
+        );
+        assert_eq!(
+            permission_statement(
+                "GRANT_WITH_GRANT_OPTION",
+                "SELECT",
+                Some("OBJECT::[dbo].[Orders]"),
+                Some("Total"),
+                "reader"
+            ),
+            "GRANT SELECT ON OBJECT::[dbo].[Orders] ([Total]) TO [reader] WITH GRANT OPTION;"
+        );
+        assert_eq!(
+            permission_statement("DENY", "EXECUTE", None, None, "public"),
+            "DENY EXECUTE TO [public];"
+        );
+    }
+
+    #[test]
+    fn data_is_inserted_through_openjson_with_identity_insert() {
+        let columns = vec![
+            DataColumn {
+                name: "Id".to_string(),
+                type_spec: "int".to_string(),
+                identity: true,
+            },
+            DataColumn {
+                name: "Name".to_string(),
+                type_spec: "nvarchar(50)".to_string(),
+                identity: false,
+            },
+        ];
+        let statement = insert_statement(
+            "[ref].[Country]",
+            &columns,
+            r#"[{"c1":1,"c2":"Côte d'Ivoire"}]"#,
+        );
+        assert_eq!(
+            statement,
+            "SET IDENTITY_INSERT [ref].[Country] ON;\n\
+             INSERT INTO [ref].[Country] ([Id], [Name])\n\
+             SELECT [c1], [c2]\n\
+             FROM OPENJSON(N'[{\"c1\":1,\"c2\":\"Côte d''Ivoire\"}]')\n\
+             WITH ([c1] int '$.c1', [c2] nvarchar(50) '$.c2');\n\
+             SET IDENTITY_INSERT [ref].[Country] OFF;"
+        );
+    }
+}
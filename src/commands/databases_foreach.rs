@@ -0,0 +1,197 @@
+//! `databases --foreach`: run one read-only query in every matching database.
+//!
+//! One session hops between databases with `USE`, which only this command issues; the query
+//! itself still goes through the read-only check, so it cannot switch databases or write unless
+//! `--allow-write` is given. A script that may write runs once per database and is never retried
+//! after a dropped connection. A database that fails (offline, no access, a query error) is
+//! reported and skipped. The profile's `mask` rules apply to every database's rows.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, DatabasesArgs};
+use crate::commands::{common, confirm};
+use crate::config::OutputFormat;
+use crate::db::session::Session;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, mask, table};
+use crate::safety;
+
+/// Online databases the login can open, filtered like the listing.
+const TARGETS_SQL: &str = r#"
+SELECT name
+FROM sys.databases
+WHERE (@P1 = 1 OR database_id > 4)
+  AND (@P2 IS NULL OR name LIKE @P2)
+  AND (@P3 IS NULL OR SUSER_SNAME(owner_sid) = @P3)
+  AND state = 0
+  AND HAS_DBACCESS(name) = 1
+ORDER BY name;
+"#;
+
+pub fn run(args: &CliArgs, cmd: &DatabasesArgs, sql: &str) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let writes = safety::validate_read_only(sql).is_err();
+    if !args.allow_write {
+        safety::validate_read_only(sql)
+            .map_err(|err| AppError::new(ErrorKind::Safety, err.to_string()))?;
+    } else if writes {
+        confirm::confirm_write(
+            args,
+            &resolved,
+            "run a script that may write in each matching database",
+        )?;
+    }
+
+    let (databases, outcomes, warnings) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut session = Session::connect(&resolved.connection).await?;
+        let targets = session
+            .query_read(|| {
+                let mut query = Query::new(TARGETS_SQL);
+                query.bind(if cmd.include_system { 1i32 } else { 0i32 });
+                query.bind(cmd.name.as_deref());
+                query.bind(cmd.owner.as_deref());
+                query
+            })
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let databases: Vec<String> = targets
+            .rows
+            .iter()
+            .filter_map(|row| row.first().map(Value::as_display))
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(databases.len());
+        for database in &databases {
+            let outcome = async {
+                session.use_database(database).await?;
+                let sets = if writes {
+                    session.query_once(Query::new(sql)).await?
+                } else {
+                    session.query_read(|| Query::new(sql)).await?
+                };
+                let mut rs = sets.into_iter().next().unwrap_or_default();
+                mask::apply(&mut rs, &resolved.policy.mask, None);
+                Ok::<_, anyhow::Error>(rs)
+            }
+            .await;
+            outcomes.push(outcome.map_err(|err| err.to_string()));
+        }
+        Ok::<_, anyhow::Error>((databases, outcomes, session.take_warnings()))
+    })?;
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for (database, outcome) in databases.iter().zip(outcomes) {
+        match outcome {
+            Ok(rs) => results.push((database.clone(), rs)),
+            Err(err) => errors.push((database.clone(), err)),
+        }
+    }
+
+    if !matches!(format, OutputFormat::Json) {
+        for warning in &warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "sql": sql,
+            "count": databases.len(),
+            "results": results
+                .iter()
+                .map(|(database, rs)| json!({
+                    "database": database,
+                    "rows": json_out::result_set_rows_to_objects(rs),
+                }))
+                .collect::<Vec<_>>(),
+            "errors": errors
+                .iter()
+                .map(|(database, err)| json!({"database": database, "error": err}))
+                .collect::<Vec<_>>(),
+            "warnings": warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    if databases.is_empty() {
+        println!("No databases matched.");
+        return Ok(());
+    }
+    let merged = merge_results(&results);
+    let result = table::render_result_set_table(&merged, format, &TableOptions::default());
+    println!("{}", result.output);
+    if !errors.is_empty() {
+        println!("\nErrors ({}):", errors.len());
+        for (database, err) in &errors {
+            println!("  - {}: {}", database, err);
+        }
+    }
+    Ok(())
+}
+
+/// One result set with a leading `database` column. Columns come from the first database that
+/// returned any; a database whose columns differ keeps its values in position.
+fn merge_results(results: &[(String, ResultSet)]) -> ResultSet {
+    let mut columns = vec![Column {
+        name: "database".to_string(),
+        data_type: None,
+    }];
+    if let Some((_, first)) = results.iter().find(|(_, rs)| !rs.columns.is_empty()) {
+        columns.extend(first.columns.iter().cloned());
+    }
+    let rows = results
+        .iter()
+        .flat_map(|(database, rs)| {
+            rs.rows.iter().map(move |row| {
+                let mut merged = Vec::with_capacity(row.len() + 1);
+                merged.push(Value::Text(database.clone()));
+                merged.extend(row.iter().cloned());
+                merged
+            })
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_rows_lead_with_the_database() {
+        let rs = |count: i64| ResultSet {
+            columns: vec![Column {
+                name: "tables".to_string(),
+                data_type: None,
+            }],
+            rows: vec![vec![Value::Int(count)]],
+        };
+        let merged = merge_results(&[
+            ("Sales".to_string(), rs(12)),
+            ("Empty".to_string(), ResultSet::default()),
+            ("Hr".to_string(), rs(3)),
+        ]);
+        let names: Vec<_> = merged.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["database", "tables"]);
+        assert_eq!(
+            merged.rows,
+            vec![
+                vec![Value::Text("Sales".to_string()), Value::Int(12)],
+                vec![Value::Text("Hr".to_string()), Value::Int(3)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Dependency ordering for generated scripts.
+//!
+//! Objects are sorted so each comes after everything it references (a topological sort), and
+//! otherwise keep the order they were given in, so scripts stay stable between runs. Names are
+//! `schema.name` and match case-insensitively, as on a default-collation server; references
+//! to objects outside the set being scripted are ignored. Objects in a reference cycle, or
+//! depending on one, cannot be ordered and are appended in their original order.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+#[derive(Debug)]
+pub(crate) struct Sorted<'a, T> {
+    /// Every item, dependencies first.
+    pub(crate) ordered: Vec<&'a T>,
+    /// Items in or behind a reference cycle; they are also the tail of `ordered`.
+    pub(crate) cyclic: Vec<&'a T>,
+}
+
+/// Sort `items` so that each follows the items named by its `depends_on`.
+pub(crate) fn sort<'a, T>(
+    items: &'a [T],
+    name: impl Fn(&T) -> String,
+    depends_on: impl Fn(&T) -> Vec<String>,
+) -> Sorted<'a, T> {
+    let index: HashMap<String, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (name(item).to_lowercase(), i))
+        .collect();
+
+    let mut waiting_on = vec![0usize; items.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); items.len()];
+    for (i, item) in items.iter().enumerate() {
+        let mut seen = Vec::new();
+        for dependency in depends_on(item) {
+            match index.get(&dependency.to_lowercase()) {
+                Some(&d) if d != i && !seen.contains(&d) => {
+                    seen.push(d);
+                    waiting_on[i] += 1;
+                    dependents[d].push(i);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Always release the earliest ready item, so independent objects keep their order.
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..items.len())
+        .filter(|&i| waiting_on[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut placed = vec![false; items.len()];
+    while let Some(Reverse(i)) = ready.pop() {
+        placed[i] = true;
+        ordered.push(&items[i]);
+        for &dependent in &dependents[i] {
+            waiting_on[dependent] -= 1;
+            if waiting_on[dependent] == 0 {
+                ready.push(Reverse(dependent));
+            }
+        }
+    }
+
+    let cyclic: Vec<&T> = (0..items.len())
+        .filter(|&i| !placed[i])
+        .map(|i| &items[i])
+        .collect();
+    ordered.extend(cyclic.iter().copied());
+    Sorted { ordered, cyclic }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(items: &[&'a (&'a str, Vec<&'a str>)]) -> Vec<&'a str> {
+        items.iter().map(|(name, _)| *name).collect()
+    }
+
+    fn run<'a>(items: &'a [(&'a str, Vec<&'a str>)]) -> Sorted<'a, (&'a str, Vec<&'a str>)> {
+        sort(
+            items,
+            |(name, _)| name.to_string(),
+            |(_, deps)| deps.iter().map(|d| d.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn dependencies_come_first_and_the_rest_keep_their_order() {
+        let items = vec![
+            ("dbo.vOrders", vec!["dbo.vCustomers", "dbo.Orders"]),
+            ("dbo.Audit", vec![]),
+            ("dbo.usp_Report", vec!["DBO.VORDERS", "dbo.Phone"]),
+            ("dbo.vCustomers", vec!["dbo.vCustomers"]),
+        ];
+        let sorted = run(&items);
+        assert_eq!(
+            names(&sorted.ordered),
+            vec![
+                "dbo.Audit",
+                "dbo.vCustomers",
+                "dbo.vOrders",
+                "dbo.usp_Report"
+            ]
+        );
+        assert!(sorted.cyclic.is_empty());
+    }
+
+    #[test]
+    fn cycles_are_appended_in_original_order() {
+        let items = vec![
+            ("dbo.a", vec!["dbo.b"]),
+            ("dbo.b", vec!["dbo.a"]),
+            ("dbo.c", vec!["dbo.a"]),
+            ("dbo.d", vec![]),
+        ];
+        let sorted = run(&items);
+        assert_eq!(
+            names(&sorted.ordered),
+            vec!["dbo.d", "dbo.a", "dbo.b", "dbo.c"]
+        );
+        assert_eq!(names(&sorted.cyclic), vec!["dbo.a", "dbo.b", "dbo.c"]);
+    }
+}
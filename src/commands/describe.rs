@@ -1,14 +1,16 @@
 use anyhow::{Result, anyhow};
 use serde_json::json;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tiberius::Query;
 
 use crate::cli::{CliArgs, DescribeArgs};
-use crate::commands::common;
+use crate::commands::{common, describe_database, partitions, table_script};
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
+use crate::db::metadata_cache;
 use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
 use crate::output::{TableOptions, json as json_out, table};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,6 +20,11 @@ enum ObjectType {
     Trigger,
     Procedure,
     Function,
+    Sequence,
+    Synonym,
+    /// User-defined alias or table type (`sys.types`).
+    Type,
+    Schema,
 }
 
 impl ObjectType {
@@ -26,8 +33,13 @@ impl ObjectType {
             "U" => Some(ObjectType::Table),
             "V" => Some(ObjectType::View),
             "TR" => Some(ObjectType::Trigger),
-            "P" => Some(ObjectType::Procedure),
-            "FN" | "IF" | "TF" | "AF" => Some(ObjectType::Function),
+            "P" | "PC" => Some(ObjectType::Procedure),
+            "FN" | "IF" | "TF" | "AF" | "FS" | "FT" => Some(ObjectType::Function),
+            "SO" => Some(ObjectType::Sequence),
+            "SN" => Some(ObjectType::Synonym),
+            // Not sys.objects codes: detection tags sys.types and sys.schemas rows with these.
+            "TY" => Some(ObjectType::Type),
+            "SCHEMA" => Some(ObjectType::Schema),
             _ => None,
         }
     }
@@ -39,6 +51,10 @@ impl ObjectType {
             "trigger" => Some(ObjectType::Trigger),
             "proc" | "procedure" => Some(ObjectType::Procedure),
             "function" | "fn" => Some(ObjectType::Function),
+            "sequence" => Some(ObjectType::Sequence),
+            "synonym" => Some(ObjectType::Synonym),
+            "type" => Some(ObjectType::Type),
+            "schema" => Some(ObjectType::Schema),
             _ => None,
         }
     }
@@ -50,6 +66,10 @@ impl ObjectType {
             ObjectType::Trigger => "trigger",
             ObjectType::Procedure => "procedure",
             ObjectType::Function => "function",
+            ObjectType::Sequence => "sequence",
+            ObjectType::Synonym => "synonym",
+            ObjectType::Type => "type",
+            ObjectType::Schema => "schema",
         }
     }
 
@@ -60,16 +80,10 @@ impl ObjectType {
             ObjectType::Trigger => "Trigger",
             ObjectType::Procedure => "Procedure",
             ObjectType::Function => "Function",
-        }
-    }
-
-    fn sql_type_filter(&self) -> &'static str {
-        match self {
-            ObjectType::Table => "'U'",
-            ObjectType::View => "'V'",
-            ObjectType::Trigger => "'TR'",
-            ObjectType::Procedure => "'P'",
-            ObjectType::Function => "'FN', 'IF', 'TF', 'AF'",
+            ObjectType::Sequence => "Sequence",
+            ObjectType::Synonym => "Synonym",
+            ObjectType::Type => "Type",
+            ObjectType::Schema => "Schema",
         }
     }
 }
@@ -138,7 +152,9 @@ struct ParameterInfo {
     is_table_type: bool,
 }
 
-/// Async function for describing a table with an existing client connection
+/// Async function for describing a table with an existing client connection.
+///
+/// `batch` holds catalog facts fetched up front for many tables; see [`prefetch_tables`].
 pub async fn describe_table_async(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
     table_name: &str,
@@ -146,11 +162,164 @@ pub async fn describe_table_async(
     cmd: &DescribeArgs,
     format: crate::config::OutputFormat,
     json_pretty: bool,
+    batch: Option<&TableBatch>,
 ) -> Result<String> {
-    describe_table(client, table_name, schema, cmd, format, json_pretty).await
+    describe_table(client, table_name, schema, cmd, format, json_pretty, batch).await
+}
+
+/// Tables per prefetch query: two parameters each, under SQL Server's 2,100-parameter cap.
+const PREFETCH_CHUNK: usize = 500;
+
+/// Catalog facts fetched once for a list of tables, so describing each one skips the column query
+/// and the versioning and partitioning lookups that come back empty for most tables.
+#[derive(Debug, Default)]
+pub struct TableBatch {
+    columns: HashMap<(String, String), ResultSet>,
+    versioned: HashSet<(String, String)>,
+    partitioned: HashSet<(String, String)>,
+    descriptions: HashMap<(String, String), String>,
+}
+
+impl TableBatch {
+    fn key(schema: &str, name: &str) -> (String, String) {
+        (schema.to_lowercase(), name.to_lowercase())
+    }
+
+    fn columns(&self, schema: &str, name: &str) -> Option<&ResultSet> {
+        self.columns.get(&Self::key(schema, name))
+    }
+
+    fn contains(&self, schema: &str, name: &str) -> bool {
+        self.columns.contains_key(&Self::key(schema, name))
+    }
+}
+
+/// Fetch columns, and which tables are temporal, change-tracked, or partitioned, for every
+/// `(schema, name)` in `tables` in a couple of queries per 500 tables.
+pub async fn prefetch_tables(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    tables: &[(String, String)],
+) -> Result<TableBatch> {
+    let mut batch = TableBatch::default();
+    for chunk in tables.chunks(PREFETCH_CHUNK) {
+        // A table with no visible columns still counts as fetched.
+        for (schema, name) in chunk {
+            let columns = ResultSet {
+                columns: column_headers(),
+                rows: Vec::new(),
+            };
+            batch.columns.insert(TableBatch::key(schema, name), columns);
+        }
+        let wanted = prefetch_values(chunk.len());
+
+        let sql = format!(
+            r#"
+SELECT
+    c.TABLE_SCHEMA,
+    c.TABLE_NAME,
+    {COLUMN_LIST}
+FROM INFORMATION_SCHEMA.COLUMNS c
+INNER JOIN {wanted} ON w.schema_name = c.TABLE_SCHEMA AND w.table_name = c.TABLE_NAME
+ORDER BY c.TABLE_SCHEMA, c.TABLE_NAME, c.ORDINAL_POSITION;
+"#
+        );
+        let mut query = Query::new(sql);
+        bind_tables(&mut query, chunk);
+        let result_set = metadata_cache::run_query(query, client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        for row in result_set.rows {
+            let key = TableBatch::key(&value_to_string(row.first()), &value_to_string(row.get(1)));
+            if let Some(columns) = batch.columns.get_mut(&key) {
+                columns.rows.push(row.into_iter().skip(2).collect());
+            }
+        }
+
+        let sql = format!(
+            r#"
+SELECT
+    s.name,
+    t.name,
+    CASE WHEN t.temporal_type <> 0 OR ct.object_id IS NOT NULL THEN 1 ELSE 0 END AS versioned,
+    CASE WHEN EXISTS (
+        SELECT 1 FROM sys.indexes i
+        INNER JOIN sys.partition_schemes ps ON ps.data_space_id = i.data_space_id
+        WHERE i.object_id = t.object_id AND i.index_id IN (0, 1)
+    ) THEN 1 ELSE 0 END AS partitioned,
+    CAST(ep.value AS nvarchar(max)) AS description
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN {wanted} ON w.schema_name = s.name AND w.table_name = t.name
+LEFT JOIN sys.change_tracking_tables ct ON ct.object_id = t.object_id
+LEFT JOIN sys.extended_properties ep
+    ON ep.class = 1 AND ep.major_id = t.object_id AND ep.minor_id = 0 AND ep.name = N'MS_Description';
+"#
+        );
+        let mut query = Query::new(sql);
+        bind_tables(&mut query, chunk);
+        let result_set = metadata_cache::run_query(query, client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        for row in result_set.rows {
+            let key = TableBatch::key(&value_to_string(row.first()), &value_to_string(row.get(1)));
+            if value_to_bool(row.get(2)) {
+                batch.versioned.insert(key.clone());
+            }
+            if value_to_bool(row.get(3)) {
+                batch.partitioned.insert(key.clone());
+            }
+            if let Some(Value::Text(description)) = row.get(4) {
+                batch.descriptions.insert(key, description.clone());
+            }
+        }
+    }
+    Ok(batch)
+}
+
+/// `(VALUES (@P1, @P2), ...) AS w(schema_name, table_name)` for `count` tables.
+fn prefetch_values(count: usize) -> String {
+    let rows: Vec<String> = (0..count)
+        .map(|idx| format!("(@P{}, @P{})", idx * 2 + 1, idx * 2 + 2))
+        .collect();
+    format!("(VALUES {}) AS w(schema_name, table_name)", rows.join(", "))
+}
+
+fn bind_tables<'a>(query: &mut Query<'a>, tables: &'a [(String, String)]) {
+    for (schema, name) in tables {
+        query.bind(schema.as_str());
+        query.bind(name.as_str());
+    }
+}
+
+/// Column names `fetch_columns` returns, for batches whose tables have no visible columns.
+fn column_headers() -> Vec<Column> {
+    [
+        "name",
+        "dataType",
+        "isNullable",
+        "defaultValue",
+        "maxLength",
+        "numericPrecision",
+        "numericScale",
+        "description",
+    ]
+    .into_iter()
+    .map(|name| Column {
+        name: name.to_string(),
+        data_type: None,
+    })
+    .collect()
 }
 
 pub fn run(args: &CliArgs, cmd: &DescribeArgs) -> Result<()> {
+    if cmd.object_type.as_deref() == Some("database") {
+        return describe_database::run(args, cmd.object.as_deref());
+    }
+
     let raw_object = cmd
         .object
         .as_deref()
@@ -199,28 +368,67 @@ async fn describe_object(
     format: OutputFormat,
     json_pretty: bool,
 ) -> Result<String> {
-    // Detect all matching objects
-    let matches = detect_all_matches(client, object_name, schema, forced_type.as_ref()).await?;
+    // Detect all matching objects; a wildcard name describes at most --max-objects of them.
+    let pattern = common::is_wildcard(object_name) || schema.is_some_and(common::is_wildcard);
+    let mut matches =
+        detect_all_matches(client, object_name, schema, pattern, forced_type.as_ref()).await?;
+    let matched = matches.len();
+    if pattern {
+        matches.truncate(cmd.max_objects);
+    }
+    let listing = MatchListing {
+        input: match (pattern, schema) {
+            (true, Some(schema)) => format!("{}.{}", schema, object_name),
+            _ => object_name.to_string(),
+        },
+        pattern,
+        matched,
+    };
 
     if matches!(format, OutputFormat::Json) {
         // JSON mode: wrap multiple matches in a "matches" array
-        describe_all_json(client, object_name, &matches, cmd, json_pretty).await
+        describe_all_json(client, &listing, &matches, cmd, json_pretty).await
     } else {
         // Text mode: describe each match with headers
-        describe_all_text(client, object_name, &matches, cmd, format).await
+        describe_all_text(client, &listing, &matches, cmd, format).await
     }
 }
 
+/// Prefetch catalog facts when a pattern matched several tables; one table is cheaper alone.
+async fn prefetch_matches(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    matches: &[ObjectMatch],
+) -> Result<Option<TableBatch>> {
+    let tables: Vec<(String, String)> = matches
+        .iter()
+        .filter(|m| m.object_type == ObjectType::Table)
+        .map(|m| (m.schema.clone(), m.name.clone()))
+        .collect();
+    if tables.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(prefetch_tables(client, &tables).await?))
+}
+
+/// What the user asked for, to word the note under several matches.
+struct MatchListing {
+    input: String,
+    pattern: bool,
+    matched: usize,
+}
+
 async fn describe_all_json(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
-    object_name: &str,
+    listing: &MatchListing,
     matches: &[ObjectMatch],
     cmd: &DescribeArgs,
     json_pretty: bool,
 ) -> Result<String> {
+    let batch = prefetch_matches(client, matches).await?;
     let mut results: Vec<serde_json::Value> = Vec::new();
 
     for m in matches {
+        let object_name = m.name.as_str();
         let json_str = match m.object_type {
             ObjectType::Table => {
                 describe_table(
@@ -230,6 +438,7 @@ async fn describe_all_json(
                     cmd,
                     OutputFormat::Json,
                     json_pretty,
+                    batch.as_ref(),
                 )
                 .await?
             }
@@ -277,21 +486,63 @@ async fn describe_all_json(
                 )
                 .await?
             }
+            ObjectType::Sequence => {
+                describe_sequence(
+                    client,
+                    object_name,
+                    &m.schema,
+                    OutputFormat::Json,
+                    json_pretty,
+                )
+                .await?
+            }
+            ObjectType::Synonym => {
+                describe_synonym(
+                    client,
+                    object_name,
+                    &m.schema,
+                    OutputFormat::Json,
+                    json_pretty,
+                )
+                .await?
+            }
+            ObjectType::Type => {
+                describe_type(
+                    client,
+                    object_name,
+                    &m.schema,
+                    OutputFormat::Json,
+                    json_pretty,
+                )
+                .await?
+            }
+            ObjectType::Schema => {
+                describe_schema(client, object_name, OutputFormat::Json, json_pretty).await?
+            }
         };
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(&json_str) {
             results.push(v);
         }
     }
 
-    // Single match: return flat object, multiple matches: wrap in array with guidance
-    if results.len() == 1 {
+    // Single match: return flat object, multiple matches: wrap in array with guidance.
+    // A wildcard always gets the array, however many objects it matched.
+    if results.len() == 1 && !listing.pattern {
         json_out::emit_json_value(&results[0], json_pretty)
+    } else if listing.pattern {
+        let payload = json!({
+            "pattern": listing.input,
+            "matched": listing.matched,
+            "truncated": listing.matched > results.len(),
+            "matches": results,
+        });
+        json_out::emit_json_value(&payload, json_pretty)
     } else {
         let payload = json!({
             "matches": results,
             "guidance": format!(
                 "Multiple objects match '{}'. Filter with --schema <name> or --type <type>.",
-                object_name
+                listing.input
             )
         });
         json_out::emit_json_value(&payload, json_pretty)
@@ -300,28 +551,43 @@ async fn describe_all_json(
 
 async fn describe_all_text(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
-    object_name: &str,
+    listing: &MatchListing,
     matches: &[ObjectMatch],
     cmd: &DescribeArgs,
     format: OutputFormat,
 ) -> Result<String> {
+    let batch = prefetch_matches(client, matches).await?;
     let mut output = String::new();
     let multiple = matches.len() > 1;
 
     for (i, m) in matches.iter().enumerate() {
+        let object_name = m.name.as_str();
         // Always show header with schema.name (type)
         let type_label = m.object_type.display_name();
         if i > 0 {
             output.push_str("\n---\n\n");
         }
-        output.push_str(&format!(
-            "## {}.{} ({})\n\n",
-            m.schema, object_name, type_label
-        ));
+        if m.object_type == ObjectType::Schema {
+            output.push_str(&format!("## {} ({})\n\n", object_name, type_label));
+        } else {
+            output.push_str(&format!(
+                "## {}.{} ({})\n\n",
+                m.schema, object_name, type_label
+            ));
+        }
 
         let section = match m.object_type {
             ObjectType::Table => {
-                describe_table(client, object_name, Some(&m.schema), cmd, format, false).await?
+                describe_table(
+                    client,
+                    object_name,
+                    Some(&m.schema),
+                    cmd,
+                    format,
+                    false,
+                    batch.as_ref(),
+                )
+                .await?
             }
             ObjectType::View => {
                 describe_view(client, object_name, Some(&m.schema), cmd, format, false).await?
@@ -335,16 +601,32 @@ async fn describe_all_text(
             ObjectType::Function => {
                 describe_function(client, object_name, Some(&m.schema), cmd, format, false).await?
             }
+            ObjectType::Sequence => {
+                describe_sequence(client, object_name, &m.schema, format, false).await?
+            }
+            ObjectType::Synonym => {
+                describe_synonym(client, object_name, &m.schema, format, false).await?
+            }
+            ObjectType::Type => {
+                describe_type(client, object_name, &m.schema, format, false).await?
+            }
+            ObjectType::Schema => describe_schema(client, object_name, format, false).await?,
         };
         output.push_str(&section);
     }
 
-    // Add disambiguation guidance if multiple matches
-    if multiple {
+    // Add disambiguation guidance if multiple matches; a wildcard only notes what was cut off
+    if listing.pattern {
+        if let Some(note) = common::max_objects_note(matches.len(), listing.matched) {
+            output.push_str("\n---\n");
+            output.push_str(&note);
+            output.push('\n');
+        }
+    } else if multiple {
         output.push_str("\n---\n");
         output.push_str(&format!(
             "Multiple objects match '{}'. Filter with: --schema <name>, --type <type>\n",
-            object_name
+            listing.input
         ));
     }
 
@@ -356,77 +638,72 @@ async fn describe_all_text(
 struct ObjectMatch {
     object_type: ObjectType,
     schema: String,
+    name: String,
 }
 
-/// Detect all matching objects for the given name
+/// Detect all matching objects for the given name, or for a `*`/`?` pattern when `pattern`
 async fn detect_all_matches(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
     object_name: &str,
     schema: Option<&str>,
+    pattern: bool,
     forced_type: Option<&ObjectType>,
 ) -> Result<Vec<ObjectMatch>> {
-    // If user forced a type, only return that type
-    if let Some(forced) = forced_type {
-        let sql = format!(
-            r#"
-SELECT s.name AS schema_name
-FROM sys.objects o
-INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
-WHERE o.name = @P1
-  AND (@P2 IS NULL OR s.name = @P2)
-  AND o.type IN ({})
-ORDER BY s.name
-"#,
-            forced.sql_type_filter()
-        );
-        let mut query = Query::new(sql);
-        query.bind(object_name);
-        query.bind(schema);
-        let result_sets = executor::run_query(query, client).await?;
-        let result_set = result_sets.into_iter().next().unwrap_or_default();
-
-        let matches: Vec<ObjectMatch> = result_set
-            .rows
-            .iter()
-            .filter_map(|row| {
-                row.first().and_then(|v| match v {
-                    Value::Text(s) => Some(ObjectMatch {
-                        object_type: forced.clone(),
-                        schema: s.clone(),
-                    }),
-                    _ => None,
-                })
-            })
-            .collect();
-
-        if matches.is_empty() {
-            return Err(anyhow!("{} '{}' not found", forced.as_str(), object_name));
-        }
-        return Ok(matches);
-    }
-
-    // Auto-detect: search all object types, return ALL matches
-    let sql = r#"
-SELECT o.type, s.name AS schema_name
-FROM sys.objects o
-INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
-WHERE o.name = @P1
-  AND (@P2 IS NULL OR s.name = @P2)
-  AND o.type IN ('U', 'V', 'TR', 'P', 'FN', 'IF', 'TF', 'AF')
+    // Search every object kind; a forced type filters the same result set. Schemas only match
+    // a bare name, since `schema.name` always addresses an object inside the schema.
+    let compare = if pattern { "LIKE" } else { "=" };
+    let escape = if pattern { " ESCAPE '\\'" } else { "" };
+    let sql = format!(
+        r#"
+SELECT m.type, m.schema_name, m.name
+FROM (
+    SELECT RTRIM(o.type) AS type, s.name AS schema_name, o.name
+    FROM sys.objects o
+    INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
+    WHERE o.name {compare} @P1{escape}
+      AND (@P2 IS NULL OR s.name {compare} @P2{escape})
+      AND o.type IN ('U', 'V', 'TR', 'P', 'PC', 'FN', 'IF', 'TF', 'AF', 'FS', 'FT', 'SO', 'SN')
+    UNION ALL
+    SELECT 'TY', s.name, t.name
+    FROM sys.types t
+    INNER JOIN sys.schemas s ON t.schema_id = s.schema_id
+    WHERE t.is_user_defined = 1
+      AND t.name {compare} @P1{escape}
+      AND (@P2 IS NULL OR s.name {compare} @P2{escape})
+    UNION ALL
+    SELECT 'SCHEMA', s.name, s.name
+    FROM sys.schemas s
+    WHERE s.name {compare} @P1{escape}
+      AND @P2 IS NULL
+) m
 ORDER BY
-    CASE o.type
+    CASE m.type
         WHEN 'U' THEN 1  -- Tables first
         WHEN 'V' THEN 2  -- Then views
         WHEN 'P' THEN 3  -- Then procs
+        WHEN 'PC' THEN 3
         WHEN 'TR' THEN 4 -- Then triggers
-        ELSE 5           -- Then functions
+        WHEN 'SO' THEN 6 -- Then sequences, synonyms, types
+        WHEN 'SN' THEN 6
+        WHEN 'TY' THEN 7
+        WHEN 'SCHEMA' THEN 8
+        ELSE 5           -- Functions before those
     END,
-    s.name
-"#;
+    m.schema_name,
+    m.name
+"#
+    );
+    let like = |text: &str| {
+        if pattern {
+            common::wildcard_to_like(text)
+        } else {
+            text.to_string()
+        }
+    };
     let mut query = Query::new(sql);
-    query.bind(object_name);
-    query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    query.bind(like(object_name));
+    query.bind(schema.map(like));
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let matches: Vec<ObjectMatch> = result_set
@@ -441,15 +718,26 @@ ORDER BY
                 Some(Value::Text(s)) => s.clone(),
                 _ => return None,
             };
+            let name = match row.get(2) {
+                Some(Value::Text(s)) => s.clone(),
+                _ => return None,
+            };
             ObjectType::from_sql_type(type_str).map(|obj_type| ObjectMatch {
                 object_type: obj_type,
                 schema: schema_name,
+                name,
             })
         })
+        .filter(|m| forced_type.is_none_or(|forced| m.object_type == *forced))
         .collect();
 
     if matches.is_empty() {
-        return Err(anyhow!("Object '{}' not found", object_name));
+        let message = match forced_type {
+            _ if pattern => format!("No objects match '{}'", object_name),
+            Some(forced) => format!("{} '{}' not found", forced.as_str(), object_name),
+            None => format!("Object '{}' not found", object_name),
+        };
+        return Err(AppError::new(ErrorKind::NotFound, message).into());
     }
 
     Ok(matches)
@@ -462,6 +750,7 @@ async fn describe_table(
     cmd: &DescribeArgs,
     format: OutputFormat,
     json_pretty: bool,
+    batch: Option<&TableBatch>,
 ) -> Result<String> {
     let include_indexes = !cmd.no_indexes;
     let include_triggers = !cmd.no_triggers;
@@ -470,7 +759,12 @@ async fn describe_table(
     let include_constraints = cmd.include_all || cmd.include_constraints;
     let include_usage = cmd.usage;
 
-    let columns_rs = fetch_columns(client, table_name, schema).await?;
+    let schema_name = schema.unwrap_or("dbo");
+    let batch = batch.filter(|batch| batch.contains(schema_name, table_name));
+    let columns_rs = match batch.and_then(|batch| batch.columns(schema_name, table_name)) {
+        Some(columns) => columns.clone(),
+        None => fetch_columns(client, table_name, schema).await?,
+    };
     let indexes = if include_indexes {
         fetch_indexes(client, table_name, schema).await?
     } else {
@@ -498,21 +792,55 @@ async fn describe_table(
     } else {
         None
     };
-    let ddl = if include_ddl {
+    let ddl = if cmd.ddl_full {
+        table_script::script_table(client, table_name, schema.unwrap_or("dbo")).await?
+    } else if include_ddl {
         fetch_table_ddl(client, table_name, schema).await?
     } else {
         None
     };
+    let permissions_rs = if cmd.permissions {
+        Some(fetch_permissions(client, table_name, schema).await?)
+    } else {
+        None
+    };
+    let key = TableBatch::key(schema_name, table_name);
+    let description = match batch {
+        Some(batch) => batch.descriptions.get(&key).cloned(),
+        None => fetch_description(client, table_name, schema).await?,
+    };
+    let versioning = if batch.is_some_and(|batch| !batch.versioned.contains(&key)) {
+        None
+    } else {
+        fetch_versioning(client, table_name, schema).await?
+    };
+    let layout = if batch.is_some_and(|batch| !batch.partitioned.contains(&key)) {
+        None
+    } else {
+        partitions::fetch_layout(client, schema_name, table_name).await?
+    };
+    let partitioning = match layout {
+        Some(layout) => {
+            let partitions_rs =
+                partitions::fetch_partitions(client, schema_name, table_name).await?;
+            Some((layout, partitions_rs))
+        }
+        None => None,
+    };
 
     format_table_output(
         table_name,
         schema.unwrap_or("dbo"),
+        description.as_deref(),
         &columns_rs,
         &indexes,
         &fks,
         &constraints,
+        versioning.as_deref(),
+        partitioning.as_ref().map(|(layout, rs)| (layout, rs)),
         triggers_rs.as_ref(),
         usage_rs.as_ref(),
+        permissions_rs.as_ref(),
         ddl.as_deref(),
         format,
         json_pretty,
@@ -535,6 +863,7 @@ async fn describe_view(
     let include_usage = cmd.usage;
 
     let columns_rs = fetch_columns(client, view_name, schema).await?;
+    let description = fetch_description(client, view_name, schema).await?;
     let usage_rs = if include_usage {
         let u = fetch_usage(client, view_name, schema).await?;
         if u.rows.is_empty() { None } else { Some(u) }
@@ -546,12 +875,19 @@ async fn describe_view(
     } else {
         None
     };
+    let permissions_rs = if cmd.permissions {
+        Some(fetch_permissions(client, view_name, schema).await?)
+    } else {
+        None
+    };
 
     format_view_output(
         view_name,
         schema.unwrap_or("dbo"),
+        description.as_deref(),
         &columns_rs,
         usage_rs.as_ref(),
+        permissions_rs.as_ref(),
         ddl.as_deref(),
         format,
         json_pretty,
@@ -592,11 +928,15 @@ WHERE tr.name = @P1
     let mut query = Query::new(sql);
     query.bind(trigger_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     if result_set.rows.is_empty() {
-        return Err(anyhow!("Trigger '{}' not found", trigger_name));
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Trigger '{}' not found", trigger_name),
+        )
+        .into());
     }
 
     let row = result_set.rows.first().unwrap();
@@ -638,13 +978,10 @@ WHERE tr.name = @P1
 
         output.push_str(&format!("Parent Table: {}\n", parent_table));
         output.push_str(&format!("Events: {}\n", events));
-        output.push_str(&format!(
-            "Disabled: {}\n",
-            if is_disabled { "yes" } else { "no" }
-        ));
+        output.push_str(&format!("Disabled: {}\n", table::format_bool(is_disabled)));
         output.push_str(&format!(
             "Instead Of: {}\n",
-            if is_instead_of { "yes" } else { "no" }
+            table::format_bool(is_instead_of)
         ));
     }
 
@@ -668,6 +1005,13 @@ async fn describe_procedure(
     } else {
         None
     };
+    let permissions_rs = if cmd.permissions {
+        Some(fetch_permissions(client, proc_name, schema).await?)
+    } else {
+        None
+    };
+    let signatures_rs = fetch_signatures(client, proc_name, schema).await?;
+    let clr_rs = fetch_clr_module(client, proc_name, schema).await?;
 
     let mut output = String::new();
 
@@ -681,7 +1025,16 @@ async fn describe_procedure(
                 "type": "procedure"
             },
             "parameters": params,
+            "isSigned": !signatures_rs.rows.is_empty(),
+            "signatures": json_out::result_set_rows_to_objects(&signatures_rs),
         });
+        if let Some(clr) = json_out::result_set_rows_to_objects(&clr_rs)
+            .into_iter()
+            .next()
+        {
+            payload["clr"] = clr;
+        }
+        append_permissions_json(&mut payload, permissions_rs.as_ref());
         if let Some(ddl_text) = ddl {
             payload["ddl"] = json!(ddl_text);
         }
@@ -703,6 +1056,25 @@ async fn describe_procedure(
         } else {
             output.push_str("(no parameters)\n");
         }
+
+        if !clr_rs.rows.is_empty() {
+            output.push_str("\nCLR Module\n");
+            output.push_str(
+                &table::render_result_set_table(&clr_rs, format, &TableOptions::default()).output,
+            );
+        }
+
+        if signatures_rs.rows.is_empty() {
+            output.push_str("\nSigned: no\n");
+        } else {
+            output.push_str("\nSignatures\n");
+            output.push_str(
+                &table::render_result_set_table(&signatures_rs, format, &TableOptions::default())
+                    .output,
+            );
+        }
+
+        append_permissions_text(&mut output, permissions_rs.as_ref(), format);
     }
 
     Ok(output)
@@ -728,18 +1100,22 @@ INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
 LEFT JOIN sys.parameters r ON o.object_id = r.object_id AND r.parameter_id = 0
 WHERE o.name = @P1
   AND (@P2 IS NULL OR s.name = @P2)
-  AND o.type IN ('FN', 'IF', 'TF', 'AF')
+  AND o.type IN ('FN', 'IF', 'TF', 'AF', 'FS', 'FT')
 "#;
     let mut query = Query::new(sql);
     query.bind(fn_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let meta_rs = result_sets.into_iter().next().unwrap_or_default();
 
     let (fn_type, return_type) = if let Some(row) = meta_rs.rows.first() {
         (value_to_string(row.first()), value_to_string(row.get(1)))
     } else {
-        return Err(anyhow!("Function '{}' not found", fn_name));
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Function '{}' not found", fn_name),
+        )
+        .into());
     };
 
     let parameters = fetch_parameters(client, fn_name, schema, true)
@@ -753,6 +1129,11 @@ WHERE o.name = @P1
     } else {
         None
     };
+    let permissions_rs = if cmd.permissions {
+        Some(fetch_permissions(client, fn_name, schema).await?)
+    } else {
+        None
+    };
 
     let mut output = String::new();
 
@@ -769,6 +1150,7 @@ WHERE o.name = @P1
             "returnType": return_type,
             "parameters": params,
         });
+        append_permissions_json(&mut payload, permissions_rs.as_ref());
         if let Some(ddl_text) = ddl {
             payload["ddl"] = json!(ddl_text);
         }
@@ -793,6 +1175,8 @@ WHERE o.name = @P1
         } else {
             output.push_str("(no parameters)\n");
         }
+
+        append_permissions_text(&mut output, permissions_rs.as_ref(), format);
     }
 
     Ok(output)
@@ -800,29 +1184,371 @@ WHERE o.name = @P1
 
 // Helper functions
 
-async fn fetch_columns(
+async fn describe_sequence(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
-    table_name: &str,
-    schema: Option<&str>,
-) -> Result<ResultSet> {
+    sequence_name: &str,
+    schema: &str,
+    format: OutputFormat,
+    json_pretty: bool,
+) -> Result<String> {
+    // Sequence bounds are sql_variant; decimal(38, 0) holds every integer type a sequence allows.
+    let sql = r#"
+SELECT
+    TYPE_NAME(sq.user_type_id) AS dataType,
+    CAST(sq.current_value AS decimal(38, 0)) AS currentValue,
+    CAST(sq.increment AS decimal(38, 0)) AS increment,
+    CAST(sq.start_value AS decimal(38, 0)) AS startValue,
+    CAST(sq.minimum_value AS decimal(38, 0)) AS minimumValue,
+    CAST(sq.maximum_value AS decimal(38, 0)) AS maximumValue,
+    sq.is_cycling AS isCycling,
+    sq.is_cached AS isCached,
+    sq.cache_size AS cacheSize,
+    sq.is_exhausted AS isExhausted
+FROM sys.sequences sq
+INNER JOIN sys.schemas s ON sq.schema_id = s.schema_id
+WHERE sq.name = @P1
+  AND s.name = @P2
+"#;
+    let mut query = Query::new(sql);
+    query.bind(sequence_name);
+    query.bind(schema);
+    let result_sets = executor::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+
+    let Some(row) = result_set.rows.first() else {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Sequence '{}' not found", sequence_name),
+        )
+        .into());
+    };
+    let data_type = value_to_string(row.first());
+    let current_value = value_to_string(row.get(1));
+    let increment = value_to_string(row.get(2));
+    let start_value = value_to_string(row.get(3));
+    let minimum_value = value_to_string(row.get(4));
+    let maximum_value = value_to_string(row.get(5));
+    let is_cycling = value_to_bool(row.get(6));
+    let is_cached = value_to_bool(row.get(7));
+    let cache_size = value_to_optional_i64(row.get(8));
+    let is_exhausted = value_to_bool(row.get(9));
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "object": {
+                "name": sequence_name,
+                "schema": schema,
+                "type": "sequence"
+            },
+            "dataType": data_type,
+            "currentValue": current_value,
+            "increment": increment,
+            "startValue": start_value,
+            "minimumValue": minimum_value,
+            "maximumValue": maximum_value,
+            "isCycling": is_cycling,
+            "cacheSize": if is_cached { json!(cache_size) } else { json!(0) },
+            "isExhausted": is_exhausted,
+        });
+        return json_out::emit_json_value(&payload, json_pretty);
+    }
+
+    let cache = match (is_cached, cache_size) {
+        (false, _) => "no cache".to_string(),
+        (true, Some(size)) => size.to_string(),
+        (true, None) => "default".to_string(),
+    };
+    let mut output = String::new();
+    output.push_str(&format!("Data Type: {}\n", data_type));
+    output.push_str(&format!("Current Value: {}\n", current_value));
+    output.push_str(&format!("Increment: {}\n", increment));
+    output.push_str(&format!("Start: {}\n", start_value));
+    output.push_str(&format!("Range: {} .. {}\n", minimum_value, maximum_value));
+    output.push_str(&format!("Cycle: {}\n", table::format_bool(is_cycling)));
+    output.push_str(&format!("Cache: {}\n", cache));
+    if is_exhausted {
+        output.push_str("Exhausted: yes (next value will fail)\n");
+    }
+    Ok(output)
+}
+
+async fn describe_synonym(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    synonym_name: &str,
+    schema: &str,
+    format: OutputFormat,
+    json_pretty: bool,
+) -> Result<String> {
+    // OBJECT_ID resolves local and cross-database targets; linked-server targets stay NULL.
+    let sql = r#"
+SELECT
+    sn.base_object_name AS baseObject,
+    base.type_desc AS baseObjectType
+FROM sys.synonyms sn
+INNER JOIN sys.schemas s ON sn.schema_id = s.schema_id
+LEFT JOIN sys.objects base ON base.object_id = OBJECT_ID(sn.base_object_name)
+WHERE sn.name = @P1
+  AND s.name = @P2
+"#;
+    let mut query = Query::new(sql);
+    query.bind(synonym_name);
+    query.bind(schema);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+
+    let Some(row) = result_set.rows.first() else {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Synonym '{}' not found", synonym_name),
+        )
+        .into());
+    };
+    let base_object = value_to_string(row.first());
+    let base_type = match row.get(1) {
+        Some(Value::Text(v)) => Some(v.clone()),
+        _ => None,
+    };
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "object": {
+                "name": synonym_name,
+                "schema": schema,
+                "type": "synonym"
+            },
+            "baseObject": base_object,
+            "baseObjectType": base_type,
+            "baseObjectResolved": base_type.is_some(),
+        });
+        return json_out::emit_json_value(&payload, json_pretty);
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!("Base Object: {}\n", base_object));
+    output.push_str(&format!(
+        "Base Type: {}\n",
+        base_type
+            .as_deref()
+            .unwrap_or("(unresolved: missing, remote, or in another database)")
+    ));
+    Ok(output)
+}
+
+async fn describe_type(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    type_name: &str,
+    schema: &str,
+    format: OutputFormat,
+    json_pretty: bool,
+) -> Result<String> {
+    let sql = r#"
+SELECT
+    t.is_table_type,
+    TYPE_NAME(t.system_type_id) AS base_type,
+    t.max_length,
+    t.precision,
+    t.scale,
+    t.is_nullable,
+    tt.type_table_object_id
+FROM sys.types t
+INNER JOIN sys.schemas s ON t.schema_id = s.schema_id
+LEFT JOIN sys.table_types tt ON tt.user_type_id = t.user_type_id
+WHERE t.is_user_defined = 1
+  AND t.name = @P1
+  AND s.name = @P2
+"#;
+    let mut query = Query::new(sql);
+    query.bind(type_name);
+    query.bind(schema);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+
+    let Some(row) = result_set.rows.first() else {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Type '{}' not found", type_name),
+        )
+        .into());
+    };
+    let is_table_type = value_to_bool(row.first());
+
+    if !is_table_type {
+        let base = format_type_spec(
+            &value_to_string(row.get(1)),
+            value_to_optional_i64(row.get(2)),
+            value_to_optional_u8(row.get(3)),
+            value_to_optional_u8(row.get(4)),
+        );
+        let nullable = value_to_bool(row.get(5));
+        if matches!(format, OutputFormat::Json) {
+            let payload = json!({
+                "object": {
+                    "name": type_name,
+                    "schema": schema,
+                    "type": "type"
+                },
+                "kind": "alias",
+                "baseType": base,
+                "isNullable": nullable,
+            });
+            return json_out::emit_json_value(&payload, json_pretty);
+        }
+        let mut output = String::new();
+        output.push_str("Kind: alias\n");
+        output.push_str(&format!("Base Type: {}\n", base));
+        output.push_str(&format!("Nullable: {}\n", table::format_bool(nullable)));
+        return Ok(output);
+    }
+
+    let columns_sql = r#"
+SELECT
+    c.name,
+    TYPE_NAME(c.user_type_id) AS dataType,
+    CASE WHEN c.is_nullable = 1 THEN 'YES' ELSE 'NO' END AS isNullable,
+    OBJECT_DEFINITION(c.default_object_id) AS defaultValue,
+    CASE
+        WHEN TYPE_NAME(c.user_type_id) IN ('nvarchar', 'nchar') AND c.max_length > 0
+            THEN c.max_length / 2
+        WHEN TYPE_NAME(c.user_type_id) IN ('varchar', 'char', 'varbinary', 'binary', 'nvarchar', 'nchar')
+            THEN c.max_length
+    END AS maxLength,
+    CASE WHEN TYPE_NAME(c.user_type_id) IN ('decimal', 'numeric') THEN c.precision END AS numericPrecision,
+    CASE WHEN TYPE_NAME(c.user_type_id) IN ('decimal', 'numeric') THEN c.scale END AS numericScale,
+    c.is_identity AS isIdentity
+FROM sys.columns c
+WHERE c.object_id = @P1
+ORDER BY c.column_id
+"#;
+    let mut query = Query::new(columns_sql);
+    query.bind(value_to_optional_i64(row.get(6)).unwrap_or_default() as i32);
+    let columns_rs = metadata_cache::run_query(query, client)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "object": {
+                "name": type_name,
+                "schema": schema,
+                "type": "type"
+            },
+            "kind": "table",
+            "columns": json_out::result_set_rows_to_objects(&columns_rs),
+        });
+        return json_out::emit_json_value(&payload, json_pretty);
+    }
+
+    let mut output = String::from("Kind: table type\n\nColumns\n");
+    output.push_str(
+        &table::render_result_set_table(&columns_rs, format, &TableOptions::default()).output,
+    );
+    Ok(output)
+}
+
+async fn describe_schema(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    schema_name: &str,
+    format: OutputFormat,
+    json_pretty: bool,
+) -> Result<String> {
+    // Constraints and internal tables belong to their parent table, so they are not counted.
     let sql = r#"
+SELECT USER_NAME(s.principal_id) AS owner
+FROM sys.schemas s
+WHERE s.name = @P1;
+
+SELECT o.type_desc AS type, COUNT(*) AS count
+FROM sys.objects o
+WHERE o.schema_id = SCHEMA_ID(@P1)
+  AND o.is_ms_shipped = 0
+  AND o.type NOT IN ('PK', 'UQ', 'F', 'C', 'D', 'IT', 'TT', 'S')
+GROUP BY o.type_desc
+UNION ALL
 SELECT
-    COLUMN_NAME AS name,
+    CASE WHEN t.is_table_type = 1 THEN 'TABLE_TYPE' ELSE 'ALIAS_TYPE' END,
+    COUNT(*)
+FROM sys.types t
+WHERE t.schema_id = SCHEMA_ID(@P1)
+  AND t.is_user_defined = 1
+GROUP BY t.is_table_type
+ORDER BY count DESC, type;
+"#;
+    let mut query = Query::new(sql);
+    query.bind(schema_name);
+    let mut result_sets = metadata_cache::run_query(query, client).await?.into_iter();
+    let owner_rs = result_sets.next().unwrap_or_default();
+    let counts_rs = result_sets.next().unwrap_or_default();
+
+    let Some(row) = owner_rs.rows.first() else {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Schema '{}' not found", schema_name),
+        )
+        .into());
+    };
+    let owner = value_to_string(row.first());
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "object": {
+                "name": schema_name,
+                "type": "schema"
+            },
+            "owner": owner,
+            "objectCounts": json_out::result_set_rows_to_objects(&counts_rs),
+        });
+        return json_out::emit_json_value(&payload, json_pretty);
+    }
+
+    let mut output = format!("Owner: {}\n\nObjects\n", owner);
+    if counts_rs.rows.is_empty() {
+        output.push_str("(schema is empty)\n");
+    } else {
+        output.push_str(
+            &table::render_result_set_table(&counts_rs, format, &TableOptions::default()).output,
+        );
+    }
+    Ok(output)
+}
+
+/// Select list shared by `fetch_columns` and `prefetch_tables`; see `column_headers`.
+const COLUMN_LIST: &str = "COLUMN_NAME AS name,
     DATA_TYPE AS dataType,
     IS_NULLABLE AS isNullable,
     COLUMN_DEFAULT AS defaultValue,
     CHARACTER_MAXIMUM_LENGTH AS maxLength,
     NUMERIC_PRECISION AS numericPrecision,
-    NUMERIC_SCALE AS numericScale
+    NUMERIC_SCALE AS numericScale,
+    CAST((
+        SELECT ep.value
+        FROM sys.extended_properties ep
+        WHERE ep.class = 1 AND ep.name = N'MS_Description'
+          AND ep.major_id = OBJECT_ID(QUOTENAME(TABLE_SCHEMA) + '.' + QUOTENAME(TABLE_NAME))
+          AND ep.minor_id = COLUMNPROPERTY(
+              OBJECT_ID(QUOTENAME(TABLE_SCHEMA) + '.' + QUOTENAME(TABLE_NAME)), COLUMN_NAME, 'ColumnId')
+    ) AS nvarchar(max)) AS description";
+
+async fn fetch_columns(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    table_name: &str,
+    schema: Option<&str>,
+) -> Result<ResultSet> {
+    let sql = format!(
+        r#"
+SELECT
+    {COLUMN_LIST}
 FROM INFORMATION_SCHEMA.COLUMNS
 WHERE TABLE_NAME = @P1
   AND (@P2 IS NULL OR TABLE_SCHEMA = @P2)
 ORDER BY ORDINAL_POSITION;
-"#;
+"#
+    );
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     Ok(result_sets.into_iter().next().unwrap_or_default())
 }
 
@@ -869,7 +1595,7 @@ ORDER BY p.parameter_id
     let mut query = Query::new(sql);
     query.bind(object_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let mut params = Vec::with_capacity(result_set.rows.len());
@@ -944,7 +1670,7 @@ ORDER BY i.name, ic.key_ordinal, ic.index_column_id;
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let mut grouped: BTreeMap<String, IndexInfo> = BTreeMap::new();
@@ -1006,7 +1732,7 @@ ORDER BY fk.name, fkc.constraint_column_id;
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let mut grouped: BTreeMap<String, ForeignKeyInfo> = BTreeMap::new();
@@ -1090,7 +1816,7 @@ ORDER BY tc.CONSTRAINT_NAME, kcu.ORDINAL_POSITION;
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     let mut grouped: BTreeMap<String, ConstraintInfo> = BTreeMap::new();
@@ -1113,6 +1839,60 @@ ORDER BY tc.CONSTRAINT_NAME, kcu.ORDINAL_POSITION;
     Ok(grouped.into_values().collect())
 }
 
+/// Temporal (system-versioning) and change tracking settings, or `None` when the table uses neither.
+async fn fetch_versioning(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    table_name: &str,
+    schema: Option<&str>,
+) -> Result<Option<Vec<(String, String)>>> {
+    let sql = r#"
+SELECT
+    t.temporal_type_desc AS temporal_type,
+    CASE WHEN h.object_id IS NOT NULL
+         THEN CONCAT(QUOTENAME(SCHEMA_NAME(h.schema_id)), '.', QUOTENAME(h.name)) END AS history_table,
+    CASE WHEN cur.object_id IS NOT NULL
+         THEN CONCAT(QUOTENAME(SCHEMA_NAME(cur.schema_id)), '.', QUOTENAME(cur.name)) END AS current_table,
+    ps.name AS period_start,
+    pe.name AS period_end,
+    CASE WHEN t.temporal_type = 2 THEN
+        CASE WHEN t.history_retention_period = -1 THEN 'INFINITE'
+             ELSE CONCAT(t.history_retention_period, ' ', t.history_retention_period_unit_desc) END
+    END AS history_retention,
+    CASE WHEN ct.object_id IS NULL THEN 'OFF' ELSE 'ON' END AS change_tracking,
+    CASE WHEN ct.object_id IS NOT NULL
+         THEN CASE WHEN ct.is_track_columns_updated_on = 1 THEN 'ON' ELSE 'OFF' END END AS track_columns_updated,
+    CASE WHEN ct.object_id IS NOT NULL
+         THEN CONCAT(ctd.retention_period, ' ', ctd.retention_period_units_desc) END AS change_retention,
+    CAST(CHANGE_TRACKING_MIN_VALID_VERSION(ct.object_id) AS nvarchar(20)) AS min_valid_version
+FROM sys.tables t
+INNER JOIN sys.schemas s ON t.schema_id = s.schema_id
+LEFT JOIN sys.tables h ON h.object_id = t.history_table_id
+LEFT JOIN sys.tables cur ON cur.history_table_id = t.object_id
+LEFT JOIN sys.periods p ON p.object_id = t.object_id
+LEFT JOIN sys.columns ps ON ps.object_id = p.object_id AND ps.column_id = p.start_column_id
+LEFT JOIN sys.columns pe ON pe.object_id = p.object_id AND pe.column_id = p.end_column_id
+LEFT JOIN sys.change_tracking_tables ct ON ct.object_id = t.object_id
+LEFT JOIN sys.change_tracking_databases ctd ON ctd.database_id = DB_ID()
+WHERE t.name = @P1
+  AND (@P2 IS NULL OR s.name = @P2)
+  AND (t.temporal_type <> 0 OR ct.object_id IS NOT NULL)
+"#;
+    let mut query = Query::new(sql);
+    query.bind(table_name);
+    query.bind(schema);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+    Ok(result_set.rows.first().map(|row| {
+        result_set
+            .columns
+            .iter()
+            .zip(row)
+            .filter(|(_, value)| !matches!(value, Value::Null))
+            .map(|(col, value)| (col.name.clone(), value.as_display()))
+            .collect()
+    }))
+}
+
 async fn fetch_triggers(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
     table_name: &str,
@@ -1134,7 +1914,7 @@ ORDER BY tr.name;
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     Ok(result_sets.into_iter().next().unwrap_or_default())
 }
 
@@ -1163,7 +1943,126 @@ ORDER BY o.type_desc, s.name, o.name;
 "#;
     let mut query = Query::new(sql);
     query.bind(&full_name);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    Ok(result_sets.into_iter().next().unwrap_or_default())
+}
+
+async fn fetch_permissions(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    object_name: &str,
+    schema: Option<&str>,
+) -> Result<ResultSet> {
+    let schema_name = schema.unwrap_or("dbo");
+    let full_name = format!("[{}].[{}]", schema_name, object_name);
+
+    // Object-level and schema-level grants, plus every member that inherits a
+    // grant through (possibly nested) database role membership.
+    let sql = r#"
+DECLARE @target_id int = OBJECT_ID(@P1);
+DECLARE @schema_id int = OBJECTPROPERTY(@target_id, 'SchemaId');
+
+WITH grants AS (
+    SELECT dp.grantee_principal_id, dp.permission_name, dp.state_desc, 'object' AS scope
+    FROM sys.database_permissions dp
+    WHERE dp.class = 1 AND dp.major_id = @target_id
+    UNION ALL
+    SELECT dp.grantee_principal_id, dp.permission_name, dp.state_desc, 'schema' AS scope
+    FROM sys.database_permissions dp
+    WHERE dp.class = 3 AND dp.major_id = @schema_id
+),
+members AS (
+    SELECT rm.member_principal_id AS member_id, rm.role_principal_id AS root_role_id
+    FROM sys.database_role_members rm
+    UNION ALL
+    SELECT rm.member_principal_id, m.root_role_id
+    FROM sys.database_role_members rm
+    INNER JOIN members m ON rm.role_principal_id = m.member_id
+)
+SELECT DISTINCT *
+FROM (
+    SELECT
+        pr.name AS principal,
+        pr.type_desc AS principalType,
+        g.permission_name AS permission,
+        g.state_desc AS state,
+        g.scope AS scope,
+        CAST(NULL AS sysname) AS viaRole
+    FROM grants g
+    INNER JOIN sys.database_principals pr ON pr.principal_id = g.grantee_principal_id
+    UNION ALL
+    SELECT
+        mp.name,
+        mp.type_desc,
+        g.permission_name,
+        g.state_desc,
+        g.scope,
+        rp.name
+    FROM grants g
+    INNER JOIN members m ON m.root_role_id = g.grantee_principal_id
+    INNER JOIN sys.database_principals rp ON rp.principal_id = g.grantee_principal_id
+    INNER JOIN sys.database_principals mp ON mp.principal_id = m.member_id
+) p
+ORDER BY principal, permission, viaRole
+OPTION (MAXRECURSION 32);
+"#;
+    let mut query = Query::new(sql);
+    query.bind(&full_name);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    Ok(result_sets.into_iter().next().unwrap_or_default())
+}
+
+async fn fetch_signatures(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    object_name: &str,
+    schema: Option<&str>,
+) -> Result<ResultSet> {
+    let schema_name = schema.unwrap_or("dbo");
+    let full_name = format!("[{}].[{}]", schema_name, object_name);
+
+    let sql = r#"
+SELECT
+    cp.crypt_type_desc AS signatureType,
+    COALESCE(c.name, ak.name) AS signedBy,
+    CASE
+        WHEN c.name IS NOT NULL THEN 'CERTIFICATE'
+        WHEN ak.name IS NOT NULL THEN 'ASYMMETRIC_KEY'
+        ELSE 'UNKNOWN'
+    END AS keyType,
+    CONVERT(varchar(64), cp.thumbprint, 2) AS thumbprint
+FROM sys.crypt_properties cp
+LEFT JOIN sys.certificates c ON c.thumbprint = cp.thumbprint
+LEFT JOIN sys.asymmetric_keys ak ON ak.thumbprint = cp.thumbprint
+WHERE cp.class = 1
+  AND cp.major_id = OBJECT_ID(@P1)
+ORDER BY signedBy;
+"#;
+    let mut query = Query::new(sql);
+    query.bind(&full_name);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    Ok(result_sets.into_iter().next().unwrap_or_default())
+}
+
+async fn fetch_clr_module(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    object_name: &str,
+    schema: Option<&str>,
+) -> Result<ResultSet> {
+    let schema_name = schema.unwrap_or("dbo");
+    let full_name = format!("[{}].[{}]", schema_name, object_name);
+
+    let sql = r#"
+SELECT
+    a.name AS assembly,
+    am.assembly_class AS assemblyClass,
+    am.assembly_method AS assemblyMethod,
+    a.permission_set_desc AS permissionSet
+FROM sys.assembly_modules am
+INNER JOIN sys.assemblies a ON a.assembly_id = am.assembly_id
+WHERE am.object_id = OBJECT_ID(@P1);
+"#;
+    let mut query = Query::new(sql);
+    query.bind(&full_name);
+    let result_sets = metadata_cache::run_query(query, client).await?;
     Ok(result_sets.into_iter().next().unwrap_or_default())
 }
 
@@ -1203,7 +2102,7 @@ ORDER BY c.column_id
     let mut query = Query::new(sql);
     query.bind(table_name);
     query.bind(schema);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     if result_set.rows.is_empty() {
@@ -1275,6 +2174,30 @@ ORDER BY c.column_id
     Ok(Some(ddl))
 }
 
+/// The `MS_Description` extended property of a table or view, if set.
+async fn fetch_description(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+    object_name: &str,
+    schema: Option<&str>,
+) -> Result<Option<String>> {
+    let full_name = format!("[{}].[{}]", schema.unwrap_or("dbo"), object_name);
+
+    let sql = r#"
+SELECT CAST(value AS nvarchar(max))
+FROM sys.extended_properties
+WHERE class = 1 AND major_id = OBJECT_ID(@P1) AND minor_id = 0 AND name = N'MS_Description';
+"#;
+    let mut query = Query::new(sql);
+    query.bind(&full_name);
+    let result_sets = metadata_cache::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+
+    Ok(result_set.rows.first().and_then(|row| match row.first() {
+        Some(Value::Text(s)) => Some(s.clone()),
+        _ => None,
+    }))
+}
+
 async fn fetch_object_definition(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
     object_name: &str,
@@ -1286,7 +2209,7 @@ async fn fetch_object_definition(
     let sql = "SELECT OBJECT_DEFINITION(OBJECT_ID(@P1))";
     let mut query = Query::new(sql);
     query.bind(&full_name);
-    let result_sets = executor::run_query(query, client).await?;
+    let result_sets = metadata_cache::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     Ok(result_set.rows.first().and_then(|row| {
@@ -1297,7 +2220,30 @@ async fn fetch_object_definition(
     }))
 }
 
-fn format_type_spec(
+/// `rs` without column `name` when no row has a value in it, so text tables skip an all-NULL
+/// description column on databases that do not document their schema.
+pub(super) fn without_empty_column(rs: &ResultSet, name: &str) -> ResultSet {
+    let Some(idx) = rs.columns.iter().position(|column| column.name == name) else {
+        return rs.clone();
+    };
+    if rs
+        .rows
+        .iter()
+        .any(|row| !matches!(row.get(idx), None | Some(Value::Null)))
+    {
+        return rs.clone();
+    }
+    let mut rs = rs.clone();
+    rs.columns.remove(idx);
+    for row in &mut rs.rows {
+        if idx < row.len() {
+            row.remove(idx);
+        }
+    }
+    rs
+}
+
+pub(super) fn format_type_spec(
     data_type: &str,
     max_length: Option<i64>,
     precision: Option<u8>,
@@ -1349,12 +2295,16 @@ fn format_type_spec(
 fn format_table_output(
     table_name: &str,
     schema: &str,
+    description: Option<&str>,
     columns_rs: &ResultSet,
     indexes: &[IndexInfo],
     fks: &[ForeignKeyInfo],
     constraints: &[ConstraintInfo],
+    versioning: Option<&[(String, String)]>,
+    partitioning: Option<(&partitions::Layout, &ResultSet)>,
     triggers_rs: Option<&ResultSet>,
     usage_rs: Option<&ResultSet>,
+    permissions_rs: Option<&ResultSet>,
     ddl: Option<&str>,
     format: OutputFormat,
     json_pretty: bool,
@@ -1370,7 +2320,8 @@ fn format_table_output(
             "object": {
                 "schema": schema,
                 "name": table_name,
-                "type": "table"
+                "type": "table",
+                "description": description,
             },
             "columns": json_out::result_set_rows_to_objects(columns_rs),
         });
@@ -1387,6 +2338,12 @@ fn format_table_output(
                 constraints.iter().map(|c| json!({"name": c.name, "type": c.constraint_type, "columns": c.columns})).collect()
             );
         }
+        if let Some(versioning) = versioning {
+            payload["versioning"] = versioning_to_json(versioning);
+        }
+        if let Some((layout, partitions_rs)) = partitioning {
+            payload["partitioning"] = partitions::layout_json(layout, partitions_rs);
+        }
         if include_usage {
             if let Some(usage) = usage_rs {
                 payload["usage"] =
@@ -1399,6 +2356,7 @@ fn format_table_output(
             payload["triggers"] =
                 serde_json::Value::Array(json_out::result_set_rows_to_objects(triggers));
         }
+        append_permissions_json(&mut payload, permissions_rs);
         if let Some(ddl_text) = ddl {
             payload["ddl"] = json!(ddl_text);
         }
@@ -1411,9 +2369,17 @@ fn format_table_output(
             output.push_str("\n```\n\n");
         }
 
+        if let Some(description) = description {
+            output.push_str(&format!("Description: {}\n\n", description));
+        }
         output.push_str("Columns\n");
         output.push_str(
-            &table::render_result_set_table(columns_rs, format, &TableOptions::default()).output,
+            &table::render_result_set_table(
+                &without_empty_column(columns_rs, "description"),
+                format,
+                &TableOptions::default(),
+            )
+            .output,
         );
 
         if include_indexes && !indexes.is_empty() {
@@ -1440,6 +2406,37 @@ fn format_table_output(
             );
         }
 
+        if let Some(versioning) = versioning {
+            output.push_str("\nVersioning\n");
+            output.push_str(
+                &table::render_key_value_table(
+                    "Setting",
+                    versioning,
+                    format,
+                    &TableOptions::default(),
+                )
+                .output,
+            );
+        }
+
+        if let Some((layout, partitions_rs)) = partitioning {
+            output.push_str("\nPartitioning\n");
+            output.push_str(
+                &table::render_key_value_table(
+                    "Setting",
+                    &partitions::layout_rows(layout),
+                    format,
+                    &TableOptions::default(),
+                )
+                .output,
+            );
+            output.push('\n');
+            output.push_str(
+                &table::render_result_set_table(partitions_rs, format, &TableOptions::default())
+                    .output,
+            );
+        }
+
         if include_usage {
             output.push_str("\nUsage\n");
             if let Some(usage) = usage_rs {
@@ -1457,6 +2454,8 @@ fn format_table_output(
                 &table::render_result_set_table(triggers, format, &TableOptions::default()).output,
             );
         }
+
+        append_permissions_text(&mut output, permissions_rs, format);
     }
 
     Ok(output)
@@ -1466,8 +2465,10 @@ fn format_table_output(
 fn format_view_output(
     view_name: &str,
     schema: &str,
+    description: Option<&str>,
     columns_rs: &ResultSet,
     usage_rs: Option<&ResultSet>,
+    permissions_rs: Option<&ResultSet>,
     ddl: Option<&str>,
     format: OutputFormat,
     json_pretty: bool,
@@ -1480,7 +2481,8 @@ fn format_view_output(
             "object": {
                 "schema": schema,
                 "name": view_name,
-                "type": "view"
+                "type": "view",
+                "description": description,
             },
             "columns": json_out::result_set_rows_to_objects(columns_rs),
         });
@@ -1492,6 +2494,7 @@ fn format_view_output(
                 payload["usage"] = serde_json::Value::Array(Vec::new());
             }
         }
+        append_permissions_json(&mut payload, permissions_rs);
         if let Some(ddl_text) = ddl {
             payload["ddl"] = json!(ddl_text);
         }
@@ -1503,9 +2506,17 @@ fn format_view_output(
             output.push_str("\n```\n\n");
         }
 
+        if let Some(description) = description {
+            output.push_str(&format!("Description: {}\n\n", description));
+        }
         output.push_str("Columns\n");
         output.push_str(
-            &table::render_result_set_table(columns_rs, format, &TableOptions::default()).output,
+            &table::render_result_set_table(
+                &without_empty_column(columns_rs, "description"),
+                format,
+                &TableOptions::default(),
+            )
+            .output,
         );
 
         if include_usage {
@@ -1518,11 +2529,56 @@ fn format_view_output(
                 output.push_str("(no dependent objects found)\n");
             }
         }
+
+        append_permissions_text(&mut output, permissions_rs, format);
     }
 
     Ok(output)
 }
 
+/// `versioning` as a JSON object with camelCase keys (`temporalType`, `historyTable`, ...).
+fn versioning_to_json(versioning: &[(String, String)]) -> serde_json::Value {
+    let mut object = serde_json::Map::new();
+    for (key, value) in versioning {
+        let mut parts = key.split('_');
+        let mut name = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                name.push(first.to_ascii_uppercase());
+                name.push_str(chars.as_str());
+            }
+        }
+        object.insert(name, json!(value));
+    }
+    serde_json::Value::Object(object)
+}
+
+fn append_permissions_json(payload: &mut serde_json::Value, permissions_rs: Option<&ResultSet>) {
+    if let Some(permissions) = permissions_rs {
+        payload["permissions"] =
+            serde_json::Value::Array(json_out::result_set_rows_to_objects(permissions));
+    }
+}
+
+fn append_permissions_text(
+    output: &mut String,
+    permissions_rs: Option<&ResultSet>,
+    format: OutputFormat,
+) {
+    let Some(permissions) = permissions_rs else {
+        return;
+    };
+    output.push_str("\nPermissions\n");
+    if permissions.rows.is_empty() {
+        output.push_str("(no explicit permissions; only owners and db-level roles apply)\n");
+    } else {
+        output.push_str(
+            &table::render_result_set_table(permissions, format, &TableOptions::default()).output,
+        );
+    }
+}
+
 fn indexes_to_result_set(indexes: &[IndexInfo]) -> ResultSet {
     let columns = vec![
         Column {
@@ -1557,8 +2613,8 @@ fn indexes_to_result_set(indexes: &[IndexInfo]) -> ResultSet {
             vec![
                 Value::Text(idx.name.clone()),
                 Value::Text(idx.index_type.clone()),
-                Value::Text(if idx.is_unique { "yes" } else { "no" }.to_string()),
-                Value::Text(if idx.is_primary { "yes" } else { "no" }.to_string()),
+                Value::Bool(idx.is_unique),
+                Value::Bool(idx.is_primary),
                 Value::Text(idx.key_columns.join(", ")),
                 Value::Text(idx.included_columns.join(", ")),
             ]
@@ -1762,18 +2818,11 @@ fn parameters_to_result_set(params: &[ParameterInfo]) -> ResultSet {
                     .map(|v| Value::Int(v as i64))
                     .unwrap_or(Value::Null),
                 p.scale.map(|v| Value::Int(v as i64)).unwrap_or(Value::Null),
-                Value::Text(
-                    match p.is_nullable {
-                        Some(true) => "yes",
-                        Some(false) => "no",
-                        None => "unknown",
-                    }
-                    .to_string(),
-                ),
-                Value::Text(if p.has_default { "yes" } else { "no" }.to_string()),
+                p.is_nullable.map(Value::Bool).unwrap_or(Value::Null),
+                Value::Bool(p.has_default),
                 p.default_value.clone().unwrap_or(Value::Null),
-                Value::Text(if p.is_user_defined { "yes" } else { "no" }.to_string()),
-                Value::Text(if p.is_table_type { "yes" } else { "no" }.to_string()),
+                Value::Bool(p.is_user_defined),
+                Value::Bool(p.is_table_type),
             ]
         })
         .collect();
@@ -1799,6 +2848,7 @@ fn value_to_string(value: Option<&Value>) -> String {
         Some(Value::Int(v)) => v.to_string(),
         Some(Value::Bool(v)) => v.to_string(),
         Some(Value::Float(v)) => v.to_string(),
+        Some(Value::Decimal(v)) => v.to_string(),
         _ => "".to_string(),
     }
 }
@@ -1825,6 +2875,7 @@ fn value_to_optional_bool(value: Option<&Value>) -> Option<bool> {
 fn value_to_optional_i64(value: Option<&Value>) -> Option<i64> {
     match value {
         Some(Value::Int(v)) => Some(*v),
+        Some(Value::Decimal(v)) => v.to_string().parse().ok(),
         Some(Value::Text(v)) => v.parse::<i64>().ok(),
         _ => None,
     }
@@ -1842,6 +2893,79 @@ fn value_to_optional_u8(value: Option<&Value>) -> Option<u8> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn prefetch_lists_each_table_as_a_value_row() {
+        assert_eq!(
+            prefetch_values(2),
+            "(VALUES (@P1, @P2), (@P3, @P4)) AS w(schema_name, table_name)"
+        );
+        let mut batch = TableBatch::default();
+        batch.columns.insert(
+            TableBatch::key("Sales", "Orders"),
+            ResultSet {
+                columns: column_headers(),
+                rows: Vec::new(),
+            },
+        );
+        assert!(batch.contains("sales", "ORDERS"));
+        assert!(!batch.contains("dbo", "Orders"));
+        assert_eq!(
+            batch.columns("Sales", "Orders").map(|rs| rs.columns.len()),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn empty_description_column_is_dropped() {
+        let mut rs = ResultSet {
+            columns: column_headers(),
+            rows: vec![vec![Value::Null; 8]],
+        };
+        assert_eq!(without_empty_column(&rs, "description").columns.len(), 7);
+        rs.rows[0][7] = Value::Text("Order state".to_string());
+        assert_eq!(without_empty_column(&rs, "description").columns.len(), 8);
+    }
+
+    #[test]
+    fn maps_detection_codes_and_cli_types() {
+        assert_eq!(ObjectType::from_sql_type("SO"), Some(ObjectType::Sequence));
+        assert_eq!(ObjectType::from_sql_type("SN"), Some(ObjectType::Synonym));
+        assert_eq!(ObjectType::from_sql_type("TY"), Some(ObjectType::Type));
+        assert_eq!(
+            ObjectType::from_sql_type("SCHEMA"),
+            Some(ObjectType::Schema)
+        );
+        assert_eq!(ObjectType::from_sql_type("U "), Some(ObjectType::Table));
+        assert_eq!(ObjectType::from_sql_type("PK"), None);
+        for cli in ["sequence", "synonym", "type", "schema"] {
+            let parsed = ObjectType::from_cli_type(cli).expect("known type");
+            assert_eq!(parsed.as_str(), cli);
+        }
+    }
+
+    #[test]
+    fn versioning_json_uses_camel_case_keys() {
+        let versioning = vec![
+            (
+                "temporal_type".to_string(),
+                "SYSTEM_VERSIONED_TEMPORAL_TABLE".to_string(),
+            ),
+            (
+                "history_table".to_string(),
+                "[dbo].[PricesHistory]".to_string(),
+            ),
+            ("change_tracking".to_string(), "OFF".to_string()),
+        ];
+        assert_eq!(
+            versioning_to_json(&versioning),
+            json!({
+                "temporalType": "SYSTEM_VERSIONED_TEMPORAL_TABLE",
+                "historyTable": "[dbo].[PricesHistory]",
+                "changeTracking": "OFF",
+            })
+        );
+    }
+
     #[test]
     fn renders_parameter_type_with_length() {
         let param = ParameterInfo {
@@ -1883,4 +3007,19 @@ mod tests {
         assert_eq!(rs.rows[0][1], Value::Text("INOUT".to_string()));
         assert_eq!(rs.rows[0][9], Value::Int(1));
     }
+
+    #[test]
+    fn permissions_section_only_rendered_when_requested() {
+        let mut output = String::new();
+        append_permissions_text(&mut output, None, OutputFormat::Markdown);
+        assert!(output.is_empty());
+
+        let rs = ResultSet::default();
+        append_permissions_text(&mut output, Some(&rs), OutputFormat::Markdown);
+        assert!(output.starts_with("\nPermissions\n(no explicit permissions"));
+
+        let mut payload = json!({});
+        append_permissions_json(&mut payload, Some(&rs));
+        assert_eq!(payload["permissions"], json!([]));
+    }
 }
@@ -0,0 +1,201 @@
+//! `describe --type database`: database-level metadata.
+//!
+//! Settings and sizes come from `sys.databases`/`sys.master_files`, so they are available for
+//! any database on the server. File layout and per-schema counts read the database's own
+//! catalog through three-part names and are skipped when it is not ONLINE.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::CliArgs;
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+const DATABASE_SQL: &str = r#"
+SELECT
+    d.name,
+    d.database_id AS databaseId,
+    SUSER_SNAME(d.owner_sid) AS owner,
+    d.create_date AS createDate,
+    d.state_desc AS state,
+    d.user_access_desc AS userAccess,
+    d.is_read_only AS isReadOnly,
+    d.compatibility_level AS compatibilityLevel,
+    d.collation_name AS collation,
+    d.recovery_model_desc AS recoveryModel,
+    d.log_reuse_wait_desc AS logReuseWait,
+    d.is_read_committed_snapshot_on AS readCommittedSnapshot,
+    d.snapshot_isolation_state_desc AS snapshotIsolation,
+    d.is_auto_close_on AS autoClose,
+    d.is_auto_shrink_on AS autoShrink,
+    d.is_auto_create_stats_on AS autoCreateStats,
+    d.is_auto_update_stats_on AS autoUpdateStats,
+    d.is_auto_update_stats_async_on AS autoUpdateStatsAsync,
+    d.page_verify_option_desc AS pageVerify,
+    d.is_encrypted AS isEncrypted,
+    d.is_query_store_on AS queryStore,
+    d.containment_desc AS containment,
+    d.delayed_durability_desc AS delayedDurability,
+    d.target_recovery_time_in_seconds AS targetRecoverySeconds,
+    CAST(ISNULL(sizes.dataPages, 0) * 8 / 1024.0 AS decimal(18, 2)) AS dataSizeMb,
+    CAST(ISNULL(sizes.logPages, 0) * 8 / 1024.0 AS decimal(18, 2)) AS logSizeMb
+FROM sys.databases d
+OUTER APPLY (
+    SELECT
+        SUM(CASE WHEN mf.type_desc = 'LOG' THEN 0 ELSE CAST(mf.size AS bigint) END) AS dataPages,
+        SUM(CASE WHEN mf.type_desc = 'LOG' THEN CAST(mf.size AS bigint) ELSE 0 END) AS logPages
+    FROM sys.master_files mf
+    WHERE mf.database_id = d.database_id
+) sizes
+WHERE d.name = ISNULL(@P1, DB_NAME());
+"#;
+
+/// Files and per-schema object counts, run in the described database via `{db}` three-part names.
+const FILES_SQL: &str = r#"
+SELECT
+    df.name,
+    df.type_desc AS type,
+    fg.name AS filegroup,
+    df.physical_name AS physicalName,
+    CAST(CAST(df.size AS bigint) * 8 / 1024.0 AS decimal(18, 2)) AS sizeMb,
+    CASE
+        WHEN df.max_size = -1 THEN 'unlimited'
+        WHEN df.max_size = 0 THEN 'none'
+        ELSE CONCAT(CAST(CAST(df.max_size AS bigint) * 8 / 1024 AS bigint), ' MB')
+    END AS maxSize,
+    CASE
+        WHEN df.growth = 0 THEN 'none'
+        WHEN df.is_percent_growth = 1 THEN CONCAT(df.growth, '%')
+        ELSE CONCAT(CAST(df.growth AS bigint) * 8 / 1024, ' MB')
+    END AS growth,
+    df.state_desc AS state
+FROM {db}.sys.database_files df
+LEFT JOIN {db}.sys.filegroups fg ON fg.data_space_id = df.data_space_id
+ORDER BY df.type, df.file_id;
+"#;
+
+const SCHEMAS_SQL: &str = r#"
+SELECT
+    s.name AS [schema],
+    SUM(CASE WHEN o.type = 'U' THEN 1 ELSE 0 END) AS tables,
+    SUM(CASE WHEN o.type = 'V' THEN 1 ELSE 0 END) AS views,
+    SUM(CASE WHEN o.type IN ('P', 'PC') THEN 1 ELSE 0 END) AS procedures,
+    SUM(CASE WHEN o.type IN ('FN', 'IF', 'TF', 'AF', 'FS', 'FT') THEN 1 ELSE 0 END) AS functions,
+    SUM(CASE WHEN o.type = 'TR' THEN 1 ELSE 0 END) AS triggers,
+    SUM(CASE WHEN o.type = 'SO' THEN 1 ELSE 0 END) AS sequences,
+    SUM(CASE WHEN o.type = 'SN' THEN 1 ELSE 0 END) AS synonyms
+FROM {db}.sys.schemas s
+JOIN {db}.sys.objects o ON o.schema_id = s.schema_id AND o.is_ms_shipped = 0
+GROUP BY s.name
+ORDER BY s.name;
+"#;
+
+/// Describe `name`, or the connection's database when `name` is `None`.
+pub fn run(args: &CliArgs, name: Option<&str>) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let (overview, files, schemas) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(DATABASE_SQL);
+        query.bind(name);
+        let overview = executor::run_query(query, &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let Some(row) = overview.rows.first() else {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!("Database '{}' not found", name.unwrap_or_default()),
+            )
+            .into());
+        };
+        let db_name = row.first().map(Value::as_display).unwrap_or_default();
+        let online = overview
+            .columns
+            .iter()
+            .position(|c| c.name == "state")
+            .and_then(|idx| row.get(idx))
+            .is_some_and(|state| state.as_display() == "ONLINE");
+        if !online {
+            return Ok::<_, anyhow::Error>((overview, None, None));
+        }
+
+        let db = bracket(&db_name);
+        let files = executor::run_query(Query::new(FILES_SQL.replace("{db}", &db)), &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let schemas =
+            executor::run_query(Query::new(SCHEMAS_SQL.replace("{db}", &db)), &mut client)
+                .await?
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+        Ok((overview, Some(files), Some(schemas)))
+    })?;
+
+    let warning = files
+        .is_none()
+        .then_some("Database is not ONLINE; file layout and object counts are unavailable");
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "database": json_out::result_set_rows_to_objects(&overview)
+                .into_iter()
+                .next()
+                .unwrap_or(serde_json::Value::Null),
+            "files": files.as_ref().map(json_out::result_set_rows_to_objects),
+            "schemas": schemas.as_ref().map(json_out::result_set_rows_to_objects),
+            "warnings": warning.into_iter().collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    let result = table::render_key_value_table(
+        "Database",
+        &key_value_rows(&overview),
+        format,
+        &TableOptions::default(),
+    );
+    println!("{}", result.output);
+    if let Some(warning) = warning {
+        eprintln!("Warning: {}", warning);
+    }
+    for (title, rs) in [("Files", files), ("Objects by schema", schemas)] {
+        let Some(rs) = rs else { continue };
+        println!("\n{}", title);
+        let result = table::render_result_set_table(&rs, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+
+    Ok(())
+}
+
+fn key_value_rows(rs: &ResultSet) -> Vec<(String, String)> {
+    let Some(row) = rs.rows.first() else {
+        return Vec::new();
+    };
+    rs.columns
+        .iter()
+        .zip(row.iter())
+        .map(|(col, value)| (col.name.clone(), value.as_display()))
+        .collect()
+}
@@ -0,0 +1,957 @@
+//! `docs`: schema documentation pages and `MS_Description` editing.
+//!
+//! `docs generate` reads tables, columns, foreign keys, and indexes for the chosen schemas in
+//! four catalog queries and writes one page per table plus an `index` page with the ER diagram of
+//! the whole set. Pages are built as a small block model first, so Markdown and HTML come from the
+//! same content. `docs set-description` writes the extended property the pages read.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, DocsArgs, DocsCommand};
+use crate::commands::{common, confirm, describe};
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::json as json_out;
+
+const TABLES_SQL: &str = r#"
+SELECT s.name, t.name, CAST(ep.value AS nvarchar(max)) AS description
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+LEFT JOIN sys.extended_properties ep
+    ON ep.class = 1 AND ep.major_id = t.object_id AND ep.minor_id = 0 AND ep.name = N'MS_Description'
+WHERE t.is_ms_shipped = 0 AND s.name IN ({schemas})
+ORDER BY s.name, t.name;
+"#;
+
+const COLUMNS_SQL: &str = r#"
+SELECT s.name, t.name, c.name, ty.name, c.max_length, c.precision, c.scale, c.is_nullable,
+       c.is_identity, dc.definition,
+       CASE WHEN pk.column_id IS NULL THEN 0 ELSE 1 END AS is_primary_key,
+       CAST(ep.value AS nvarchar(max)) AS description
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN sys.columns c ON c.object_id = t.object_id
+INNER JOIN sys.types ty ON ty.user_type_id = c.user_type_id
+LEFT JOIN sys.default_constraints dc ON dc.object_id = c.default_object_id
+LEFT JOIN (
+    SELECT ic.object_id, ic.column_id
+    FROM sys.indexes i
+    INNER JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+    WHERE i.is_primary_key = 1
+) pk ON pk.object_id = c.object_id AND pk.column_id = c.column_id
+LEFT JOIN sys.extended_properties ep
+    ON ep.class = 1 AND ep.major_id = c.object_id AND ep.minor_id = c.column_id AND ep.name = N'MS_Description'
+WHERE t.is_ms_shipped = 0 AND s.name IN ({schemas})
+ORDER BY s.name, t.name, c.column_id;
+"#;
+
+const FOREIGN_KEYS_SQL: &str = r#"
+SELECT fk.name, sp.name, tp.name, sr.name, tr.name,
+       STRING_AGG(cp.name, ', ') WITHIN GROUP (ORDER BY fkc.constraint_column_id) AS columns,
+       STRING_AGG(cr.name, ', ') WITHIN GROUP (ORDER BY fkc.constraint_column_id) AS referenced_columns,
+       MAX(CAST(cp.is_nullable AS int)) AS nullable
+FROM sys.foreign_keys fk
+INNER JOIN sys.tables tp ON tp.object_id = fk.parent_object_id
+INNER JOIN sys.schemas sp ON sp.schema_id = tp.schema_id
+INNER JOIN sys.tables tr ON tr.object_id = fk.referenced_object_id
+INNER JOIN sys.schemas sr ON sr.schema_id = tr.schema_id
+INNER JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id
+INNER JOIN sys.columns cp ON cp.object_id = fkc.parent_object_id AND cp.column_id = fkc.parent_column_id
+INNER JOIN sys.columns cr ON cr.object_id = fkc.referenced_object_id AND cr.column_id = fkc.referenced_column_id
+WHERE sp.name IN ({schemas}) OR sr.name IN ({schemas})
+GROUP BY fk.name, sp.name, tp.name, sr.name, tr.name
+ORDER BY sp.name, tp.name, fk.name;
+"#;
+
+const INDEXES_SQL: &str = r#"
+SELECT s.name, t.name, i.name, i.type_desc, i.is_unique, i.is_primary_key,
+       STRING_AGG(CASE WHEN ic.is_included_column = 0 THEN c.name END, ', ')
+           WITHIN GROUP (ORDER BY ic.key_ordinal, ic.index_column_id) AS key_columns,
+       STRING_AGG(CASE WHEN ic.is_included_column = 1 THEN c.name END, ', ')
+           WITHIN GROUP (ORDER BY ic.key_ordinal, ic.index_column_id) AS included_columns
+FROM sys.indexes i
+INNER JOIN sys.tables t ON t.object_id = i.object_id
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+INNER JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+WHERE t.is_ms_shipped = 0 AND i.name IS NOT NULL AND i.is_hypothetical = 0
+  AND s.name IN ({schemas})
+GROUP BY s.name, t.name, i.name, i.type_desc, i.is_unique, i.is_primary_key
+ORDER BY s.name, t.name, i.name;
+"#;
+
+/// The object and column a description is attached to, with its current value.
+const DESCRIPTION_TARGET_SQL: &str = r#"
+SELECT o.type, c.column_id, CAST(ep.value AS nvarchar(max)) AS description
+FROM sys.objects o
+INNER JOIN sys.schemas s ON s.schema_id = o.schema_id
+LEFT JOIN sys.columns c ON c.object_id = o.object_id AND c.name = @P3
+LEFT JOIN sys.extended_properties ep
+    ON ep.class = 1 AND ep.major_id = o.object_id AND ep.name = N'MS_Description'
+   AND ep.minor_id = CASE WHEN @P3 IS NULL THEN 0 ELSE c.column_id END
+WHERE s.name = @P1 AND o.name = @P2 AND o.type IN ('U', 'V');
+"#;
+
+type TableKey = (String, String);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DocTable {
+    schema: String,
+    name: String,
+    description: Option<String>,
+    columns: Vec<DocColumn>,
+    indexes: Vec<DocIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DocColumn {
+    name: String,
+    data_type: String,
+    type_spec: String,
+    nullable: bool,
+    identity: bool,
+    default: Option<String>,
+    primary_key: bool,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DocForeignKey {
+    name: String,
+    from: TableKey,
+    to: TableKey,
+    columns: String,
+    referenced_columns: String,
+    nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DocIndex {
+    name: String,
+    index_type: String,
+    unique: bool,
+    primary: bool,
+    key_columns: String,
+    included_columns: String,
+}
+
+/// Page content shared by the Markdown and HTML renderers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    Table {
+        headers: Vec<&'static str>,
+        rows: Vec<Vec<Cell>>,
+    },
+    Mermaid(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Cell {
+    Text(String),
+    /// Text linking to another page, by file stem.
+    Link(String, String),
+}
+
+pub fn run(args: &CliArgs, cmd: &DocsArgs) -> Result<()> {
+    match &cmd.command {
+        DocsCommand::Generate { schemas, out, html } => generate(args, schemas, out, *html),
+        DocsCommand::SetDescription {
+            target,
+            description,
+        } => set_description(args, target, description),
+    }
+}
+
+fn generate(args: &CliArgs, schemas: &[String], out: &Path, html: bool) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let schemas = if schemas.is_empty() {
+        resolved.connection.default_schemas.clone()
+    } else {
+        schemas.to_vec()
+    };
+    if schemas.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "No schemas to document; pass --schema or set the profile's defaultSchemas",
+        )
+        .into());
+    }
+
+    let (tables, foreign_keys) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        fetch_model(&mut client, &schemas).await
+    })?;
+
+    let extension = if html { "html" } else { "md" };
+    fs::create_dir_all(out)?;
+    let mut written = Vec::new();
+    let mut write_page = |stem: &str, title: &str, blocks: &[Block]| -> Result<()> {
+        let path = out.join(format!("{}.{}", stem, extension));
+        let content = if html {
+            render_html(title, blocks)
+        } else {
+            render_markdown(blocks)
+        };
+        fs::write(&path, content)?;
+        written.push(path);
+        Ok(())
+    };
+    let title = format!("{} schema", resolved.connection.database);
+    write_page("index", &title, &index_page(&title, &tables, &foreign_keys))?;
+    for table in &tables {
+        let title = format!("{}.{}", table.schema, table.name);
+        write_page(
+            &page_stem(&table.schema, &table.name),
+            &title,
+            &table_page(table, &tables, &foreign_keys),
+        )?;
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "out": out.display().to_string(),
+            "format": if html { "html" } else { "markdown" },
+            "schemas": schemas,
+            "tables": tables.len(),
+            "files": written.iter().map(|path| path.display().to_string()).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        println!(
+            "Wrote {} table pages and {} to {}.",
+            tables.len(),
+            written[0]
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            out.display()
+        );
+    }
+    Ok(())
+}
+
+async fn fetch_model(
+    client: &mut SqlClient,
+    schemas: &[String],
+) -> Result<(Vec<DocTable>, Vec<DocForeignKey>)> {
+    let placeholders = (1..=schemas.len())
+        .map(|i| format!("@P{}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sets = Vec::new();
+    for sql in [TABLES_SQL, COLUMNS_SQL, FOREIGN_KEYS_SQL, INDEXES_SQL] {
+        let mut query = Query::new(sql.replace("{schemas}", &placeholders));
+        for schema in schemas {
+            query.bind(schema.as_str());
+        }
+        let result_sets = executor::run_query(query, client).await?;
+        sets.push(result_sets.into_iter().next().unwrap_or_default());
+    }
+    Ok(build_model(&sets))
+}
+
+/// Tables with their columns and indexes, and every foreign key touching them, from the
+/// tables, columns, foreign keys, and indexes result sets in that order.
+fn build_model(sets: &[ResultSet]) -> (Vec<DocTable>, Vec<DocForeignKey>) {
+    let rows = |i: usize| sets.get(i).map(|rs| rs.rows.as_slice()).unwrap_or_default();
+    let key = |row: &[Value]| (text(row.first()), text(row.get(1)));
+
+    let mut tables: BTreeMap<TableKey, DocTable> = BTreeMap::new();
+    for row in rows(0) {
+        let (schema, name) = key(row);
+        tables.insert(
+            (schema.clone(), name.clone()),
+            DocTable {
+                schema,
+                name,
+                description: optional_text(row.get(2)),
+                ..DocTable::default()
+            },
+        );
+    }
+    for row in rows(1) {
+        let Some(table) = tables.get_mut(&key(row)) else {
+            continue;
+        };
+        let data_type = text(row.get(3));
+        let small = |i: usize| int(row.get(i)).and_then(|v| u8::try_from(v).ok());
+        table.columns.push(DocColumn {
+            name: text(row.get(2)),
+            type_spec: describe::format_type_spec(&data_type, int(row.get(4)), small(5), small(6)),
+            data_type,
+            nullable: flag(row.get(7)),
+            identity: flag(row.get(8)),
+            default: optional_text(row.get(9)),
+            primary_key: flag(row.get(10)),
+            description: optional_text(row.get(11)),
+        });
+    }
+    let foreign_keys = rows(2)
+        .iter()
+        .map(|row| DocForeignKey {
+            name: text(row.first()),
+            from: (text(row.get(1)), text(row.get(2))),
+            to: (text(row.get(3)), text(row.get(4))),
+            columns: text(row.get(5)),
+            referenced_columns: text(row.get(6)),
+            nullable: flag(row.get(7)),
+        })
+        .collect();
+    for row in rows(3) {
+        if let Some(table) = tables.get_mut(&key(row)) {
+            table.indexes.push(DocIndex {
+                name: text(row.get(2)),
+                index_type: text(row.get(3)),
+                unique: flag(row.get(4)),
+                primary: flag(row.get(5)),
+                key_columns: text(row.get(6)),
+                included_columns: text(row.get(7)),
+            });
+        }
+    }
+    (tables.into_values().collect(), foreign_keys)
+}
+
+fn index_page(title: &str, tables: &[DocTable], foreign_keys: &[DocForeignKey]) -> Vec<Block> {
+    let rows = tables
+        .iter()
+        .map(|table| {
+            vec![
+                Cell::Link(
+                    format!("{}.{}", table.schema, table.name),
+                    page_stem(&table.schema, &table.name),
+                ),
+                Cell::Text(table.columns.len().to_string()),
+                Cell::Text(table.description.clone().unwrap_or_default()),
+            ]
+        })
+        .collect();
+    let keys: Vec<TableKey> = tables
+        .iter()
+        .map(|table| (table.schema.clone(), table.name.clone()))
+        .collect();
+    let mut blocks = vec![
+        Block::Heading(1, title.to_string()),
+        Block::Table {
+            headers: vec!["Table", "Columns", "Description"],
+            rows,
+        },
+    ];
+    if !foreign_keys.is_empty() {
+        blocks.push(Block::Heading(2, "Relationships".to_string()));
+        blocks.push(Block::Mermaid(render_mermaid(&keys, None, foreign_keys)));
+    }
+    blocks
+}
+
+fn table_page(table: &DocTable, tables: &[DocTable], foreign_keys: &[DocForeignKey]) -> Vec<Block> {
+    let key = (table.schema.clone(), table.name.clone());
+    let documented: BTreeSet<&TableKey> = foreign_keys
+        .iter()
+        .flat_map(|fk| [&fk.from, &fk.to])
+        .filter(|other| {
+            tables
+                .iter()
+                .any(|t| t.schema == other.0 && t.name == other.1)
+        })
+        .collect();
+    let link = |other: &TableKey, columns: &str| {
+        let label = format!("{}.{} ({})", other.0, other.1, columns);
+        if documented.contains(other) {
+            Cell::Link(label, page_stem(&other.0, &other.1))
+        } else {
+            Cell::Text(label)
+        }
+    };
+    let outbound: Vec<&DocForeignKey> = foreign_keys.iter().filter(|fk| fk.from == key).collect();
+    let inbound: Vec<&DocForeignKey> = foreign_keys.iter().filter(|fk| fk.to == key).collect();
+    let fk_columns: BTreeSet<&str> = outbound
+        .iter()
+        .flat_map(|fk| fk.columns.split(", "))
+        .collect();
+
+    let mut blocks = vec![Block::Heading(
+        1,
+        format!("{}.{}", table.schema, table.name),
+    )];
+    if let Some(description) = &table.description {
+        blocks.push(Block::Paragraph(description.clone()));
+    }
+    blocks.push(Block::Heading(2, "Columns".to_string()));
+    blocks.push(Block::Table {
+        headers: vec![
+            "Column",
+            "Type",
+            "Nullable",
+            "Default",
+            "Key",
+            "Description",
+        ],
+        rows: table
+            .columns
+            .iter()
+            .map(|column| {
+                let mut keys = Vec::new();
+                if column.primary_key {
+                    keys.push("PK");
+                }
+                if fk_columns.contains(column.name.as_str()) {
+                    keys.push("FK");
+                }
+                if column.identity {
+                    keys.push("identity");
+                }
+                vec![
+                    Cell::Text(column.name.clone()),
+                    Cell::Text(column.type_spec.clone()),
+                    Cell::Text(if column.nullable { "yes" } else { "no" }.to_string()),
+                    Cell::Text(column.default.clone().unwrap_or_default()),
+                    Cell::Text(keys.join(", ")),
+                    Cell::Text(column.description.clone().unwrap_or_default()),
+                ]
+            })
+            .collect(),
+    });
+    if !outbound.is_empty() {
+        blocks.push(Block::Heading(2, "Foreign keys".to_string()));
+        blocks.push(Block::Table {
+            headers: vec!["Name", "Columns", "References"],
+            rows: outbound
+                .iter()
+                .map(|fk| {
+                    vec![
+                        Cell::Text(fk.name.clone()),
+                        Cell::Text(fk.columns.clone()),
+                        link(&fk.to, &fk.referenced_columns),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if !inbound.is_empty() {
+        blocks.push(Block::Heading(2, "Referenced by".to_string()));
+        blocks.push(Block::Table {
+            headers: vec!["Name", "Table", "Columns"],
+            rows: inbound
+                .iter()
+                .map(|fk| {
+                    vec![
+                        Cell::Text(fk.name.clone()),
+                        link(&fk.from, &fk.columns),
+                        Cell::Text(fk.referenced_columns.clone()),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if !table.indexes.is_empty() {
+        blocks.push(Block::Heading(2, "Indexes".to_string()));
+        blocks.push(Block::Table {
+            headers: vec!["Name", "Type", "Unique", "Key columns", "Included columns"],
+            rows: table
+                .indexes
+                .iter()
+                .map(|index| {
+                    let kind = if index.primary {
+                        format!("{} (primary key)", index.index_type)
+                    } else {
+                        index.index_type.clone()
+                    };
+                    vec![
+                        Cell::Text(index.name.clone()),
+                        Cell::Text(kind),
+                        Cell::Text(if index.unique { "yes" } else { "no" }.to_string()),
+                        Cell::Text(index.key_columns.clone()),
+                        Cell::Text(index.included_columns.clone()),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if !outbound.is_empty() || !inbound.is_empty() {
+        let mut neighbours: BTreeSet<TableKey> = BTreeSet::from([key.clone()]);
+        for fk in outbound.iter().chain(&inbound) {
+            neighbours.insert(fk.from.clone());
+            neighbours.insert(fk.to.clone());
+        }
+        let neighbours: Vec<TableKey> = neighbours.into_iter().collect();
+        let edges: Vec<DocForeignKey> = outbound.into_iter().chain(inbound).cloned().collect();
+        blocks.push(Block::Heading(2, "Relationships".to_string()));
+        blocks.push(Block::Mermaid(render_mermaid(
+            &neighbours,
+            Some(table),
+            &edges,
+        )));
+    }
+    blocks
+}
+
+/// Mermaid `erDiagram` of `tables` and the foreign keys between them; `detail` also lists that
+/// table's columns. Tables only referenced by a key are added as plain entities.
+fn render_mermaid(
+    tables: &[TableKey],
+    detail: Option<&DocTable>,
+    foreign_keys: &[DocForeignKey],
+) -> String {
+    let mut entities: BTreeSet<&TableKey> = tables.iter().collect();
+    let edges: Vec<&DocForeignKey> = foreign_keys
+        .iter()
+        .filter(|fk| entities.contains(&fk.from) || entities.contains(&fk.to))
+        .collect();
+    for fk in &edges {
+        entities.insert(&fk.from);
+        entities.insert(&fk.to);
+    }
+    // Mermaid entity ids are limited to word characters; the real name goes in the alias.
+    let mut ids: BTreeMap<&TableKey, String> = BTreeMap::new();
+    let mut taken = BTreeSet::new();
+    for entity in &entities {
+        let base = word(&format!("{}_{}", entity.0, entity.1));
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(id.clone()) {
+            id = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        ids.insert(entity, id);
+    }
+
+    let mut out = String::from("erDiagram\n");
+    for entity in &entities {
+        let alias = format!("{}.{}", entity.0, entity.1).replace('"', "'");
+        match detail.filter(|table| table.schema == entity.0 && table.name == entity.1) {
+            Some(table) => {
+                out.push_str(&format!("    {}[\"{}\"] {{\n", ids[entity], alias));
+                for column in &table.columns {
+                    out.push_str(&format!(
+                        "        {} {}{}\n",
+                        word(&column.data_type),
+                        word(&column.name),
+                        if column.primary_key { " PK" } else { "" }
+                    ));
+                }
+                out.push_str("    }\n");
+            }
+            None => out.push_str(&format!("    {}[\"{}\"]\n", ids[entity], alias)),
+        }
+    }
+    for fk in edges {
+        out.push_str(&format!(
+            "    {} }}o--{} {} : \"{}\"\n",
+            ids[&fk.from],
+            if fk.nullable { "o|" } else { "||" },
+            ids[&fk.to],
+            fk.name.replace('"', "'")
+        ));
+    }
+    out
+}
+
+fn render_markdown(blocks: &[Block]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("{} {}\n\n", "#".repeat(usize::from(*level)), text))
+            }
+            Block::Paragraph(text) => out.push_str(&format!("{}\n\n", text)),
+            Block::Table { headers, rows } => {
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| match cell {
+                            Cell::Text(text) => escape(text),
+                            Cell::Link(text, stem) => format!("[{}]({}.md)", escape(text), stem),
+                        })
+                        .collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+                out.push('\n');
+            }
+            Block::Mermaid(diagram) => out.push_str(&format!("```mermaid\n{}```\n\n", diagram)),
+        }
+    }
+    out
+}
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut body = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                body.push_str(&format!("<h{0}>{1}</h{0}>\n", level, html_escape(text)))
+            }
+            Block::Paragraph(text) => body.push_str(&format!("<p>{}</p>\n", html_escape(text))),
+            Block::Table { headers, rows } => {
+                body.push_str("<table>\n<tr>");
+                for header in headers {
+                    body.push_str(&format!("<th>{}</th>", html_escape(header)));
+                }
+                body.push_str("</tr>\n");
+                for row in rows {
+                    body.push_str("<tr>");
+                    for cell in row {
+                        match cell {
+                            Cell::Text(text) => {
+                                body.push_str(&format!("<td>{}</td>", html_escape(text)))
+                            }
+                            Cell::Link(text, stem) => body.push_str(&format!(
+                                "<td><a href=\"{}.html\">{}</a></td>",
+                                html_escape(stem),
+                                html_escape(text)
+                            )),
+                        }
+                    }
+                    body.push_str("</tr>\n");
+                }
+                body.push_str("</table>\n");
+            }
+            Block::Mermaid(diagram) => body.push_str(&format!(
+                "<pre class=\"mermaid\">\n{}</pre>\n",
+                html_escape(diagram)
+            )),
+        }
+    }
+    let mermaid = if blocks
+        .iter()
+        .any(|block| matches!(block, Block::Mermaid(_)))
+    {
+        "<script type=\"module\">import mermaid from \"https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs\"; mermaid.initialize({ startOnLoad: true });</script>\n"
+    } else {
+        ""
+    };
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\
+         body {{ font-family: sans-serif; margin: 2em; }} \
+         table {{ border-collapse: collapse; margin-bottom: 1.5em; }} \
+         th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}\
+         </style>\n{}</head>\n<body>\n{}</body>\n</html>\n",
+        html_escape(title),
+        mermaid,
+        body
+    )
+}
+
+fn set_description(args: &CliArgs, target: &str, description: &str) -> Result<()> {
+    if !args.allow_write {
+        return Err(AppError::new(
+            ErrorKind::Safety,
+            "docs set-description writes an extended property; re-run with --allow-write",
+        )
+        .into());
+    }
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let (schema, table, column) = parse_target(target, &resolved.connection.default_schemas)?;
+    confirm::confirm_write(
+        args,
+        &resolved,
+        &format!("set the MS_Description of {}", target),
+    )?;
+
+    let action = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(DESCRIPTION_TARGET_SQL);
+        query.bind(schema.as_str());
+        query.bind(table.as_str());
+        query.bind(column.as_deref());
+        let sets = executor::run_query(query, &mut client).await?;
+        let row = sets
+            .first()
+            .and_then(|rs| rs.rows.first())
+            .cloned()
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorKind::NotFound,
+                    format!("Table or view '{}.{}' not found", schema, table),
+                )
+            })?;
+        if column.is_some() && matches!(row.get(1), None | Some(Value::Null)) {
+            return Err(AppError::new(
+                ErrorKind::NotFound,
+                format!("Column '{}' not found", target),
+            )
+            .into());
+        }
+        let level1_type = if text(row.first()).trim() == "V" {
+            "VIEW"
+        } else {
+            "TABLE"
+        };
+        let exists = !matches!(row.get(2), None | Some(Value::Null));
+        let Some((action, procedure)) = description_action(exists, description) else {
+            return Ok("unchanged");
+        };
+        let mut sql = format!("EXEC sys.{} @name = N'MS_Description', ", procedure);
+        if procedure != "sp_dropextendedproperty" {
+            sql.push_str("@value = @P5, ");
+        }
+        sql.push_str(
+            "@level0type = N'SCHEMA', @level0name = @P1, @level1type = @P4, @level1name = @P2, \
+             @level2type = @P6, @level2name = @P3;",
+        );
+        let mut query = Query::new(sql);
+        query.bind(schema.as_str());
+        query.bind(table.as_str());
+        query.bind(column.as_deref());
+        query.bind(level1_type);
+        query.bind(description);
+        query.bind(column.as_ref().map(|_| "COLUMN"));
+        executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(action)
+    })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "target": { "schema": schema, "table": table, "column": column },
+            "description": (!description.is_empty()).then_some(description),
+            "action": action,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        let name = match &column {
+            Some(column) => format!("{}.{}.{}", schema, table, column),
+            None => format!("{}.{}", schema, table),
+        };
+        println!("Description of {}: {}.", name, action);
+    }
+    Ok(())
+}
+
+/// `(action, procedure)` for the current state; `None` when clearing a missing description.
+fn description_action(exists: bool, description: &str) -> Option<(&'static str, &'static str)> {
+    match (exists, description.is_empty()) {
+        (true, true) => Some(("dropped", "sp_dropextendedproperty")),
+        (true, false) => Some(("updated", "sp_updateextendedproperty")),
+        (false, false) => Some(("added", "sp_addextendedproperty")),
+        (false, true) => None,
+    }
+}
+
+/// `table`, `schema.table`, or `schema.table.column`; a bare table takes the first default schema.
+fn parse_target(
+    target: &str,
+    default_schemas: &[String],
+) -> Result<(String, String, Option<String>)> {
+    let cleaned = target.replace(['[', ']'], "");
+    let parts: Vec<&str> = cleaned.split('.').collect();
+    match parts.as_slice() {
+        [table] if !table.is_empty() => Ok((
+            default_schemas
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "dbo".to_string()),
+            table.to_string(),
+            None,
+        )),
+        [schema, table] => Ok((schema.to_string(), table.to_string(), None)),
+        [schema, table, column] => Ok((
+            schema.to_string(),
+            table.to_string(),
+            Some(column.to_string()),
+        )),
+        _ => Err(AppError::new(
+            ErrorKind::Config,
+            format!(
+                "Expected table, schema.table, or schema.table.column, got '{}'",
+                target
+            ),
+        )
+        .into()),
+    }
+}
+
+/// File stem of a table's page; characters file systems reject become `_`.
+fn page_stem(schema: &str, table: &str) -> String {
+    format!("{}.{}", schema, table)
+        .chars()
+        .map(|ch| {
+            if matches!(ch, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+fn word(text: &str) -> String {
+    text.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::Text(v)) => v.clone(),
+        Some(Value::Int(v)) => v.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn optional_text(value: Option<&Value>) -> Option<String> {
+    Some(text(value)).filter(|text| !text.is_empty())
+}
+
+fn int(value: Option<&Value>) -> Option<i64> {
+    match value {
+        Some(Value::Int(v)) => Some(*v),
+        _ => None,
+    }
+}
+
+fn flag(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(v)) => *v,
+        Some(Value::Int(v)) => *v != 0,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn set(rows: Vec<Vec<Value>>) -> ResultSet {
+        ResultSet {
+            columns: vec![Column {
+                name: "c".to_string(),
+                data_type: None,
+            }],
+            rows,
+        }
+    }
+
+    fn t(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    fn model() -> (Vec<DocTable>, Vec<DocForeignKey>) {
+        build_model(&[
+            set(vec![
+                vec![t("dbo"), t("Customers"), Value::Null],
+                vec![t("dbo"), t("Orders"), t("One row per | order")],
+            ]),
+            set(vec![
+                vec![
+                    t("dbo"),
+                    t("Orders"),
+                    t("Id"),
+                    t("int"),
+                    Value::Int(4),
+                    Value::Int(10),
+                    Value::Int(0),
+                    Value::Bool(false),
+                    Value::Bool(true),
+                    Value::Null,
+                    Value::Int(1),
+                    Value::Null,
+                ],
+                vec![
+                    t("dbo"),
+                    t("Orders"),
+                    t("CustomerId"),
+                    t("nvarchar"),
+                    Value::Int(40),
+                    Value::Int(0),
+                    Value::Int(0),
+                    Value::Bool(true),
+                    Value::Bool(false),
+                    Value::Null,
+                    Value::Int(0),
+                    t("Buyer"),
+                ],
+            ]),
+            set(vec![vec![
+                t("FK_Orders_Customers"),
+                t("dbo"),
+                t("Orders"),
+                t("dbo"),
+                t("Customers"),
+                t("CustomerId"),
+                t("Id"),
+                Value::Int(1),
+            ]]),
+            set(vec![vec![
+                t("dbo"),
+                t("Orders"),
+                t("PK_Orders"),
+                t("CLUSTERED"),
+                Value::Bool(true),
+                Value::Bool(true),
+                t("Id"),
+                Value::Null,
+            ]]),
+        ])
+    }
+
+    #[test]
+    fn table_page_renders_columns_keys_and_diagram() {
+        let (tables, foreign_keys) = model();
+        let orders = &tables[1];
+        let page = render_markdown(&table_page(orders, &tables, &foreign_keys));
+        assert!(page.starts_with("# dbo.Orders\n\nOne row per | order\n"));
+        assert!(page.contains("| Id | int | no |  | PK, identity |  |"));
+        assert!(page.contains("| CustomerId | nvarchar(20) | yes |  | FK | Buyer |"));
+        assert!(page.contains("[dbo.Customers (Id)](dbo.Customers.md)"));
+        assert!(page.contains("| PK_Orders | CLUSTERED (primary key) | yes | Id |  |"));
+        assert!(page.contains("    dbo_Orders[\"dbo.Orders\"] {\n        int Id PK\n"));
+        assert!(page.contains("    dbo_Orders }o--o| dbo_Customers : \"FK_Orders_Customers\""));
+
+        let html = render_html("dbo.Orders", &table_page(orders, &tables, &foreign_keys));
+        assert!(html.contains("<a href=\"dbo.Customers.html\">dbo.Customers (Id)</a>"));
+        assert!(html.contains("mermaid.initialize"));
+    }
+
+    #[test]
+    fn description_targets_and_actions() {
+        assert_eq!(
+            parse_target("[dbo].[Orders].[Status]", &[]).expect("target"),
+            (
+                "dbo".to_string(),
+                "Orders".to_string(),
+                Some("Status".to_string())
+            )
+        );
+        assert!(parse_target("a.b.c.d", &[]).is_err());
+        assert_eq!(
+            description_action(true, ""),
+            Some(("dropped", "sp_dropextendedproperty"))
+        );
+        assert_eq!(description_action(false, ""), None);
+        assert_eq!(
+            description_action(false, "Order state").map(|(action, _)| action),
+            Some("added")
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, ErrorlogArgs};
+use crate::commands::{common, paging};
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+const LIMIT_DEFAULT: u64 = 50;
+const LIMIT_MAX: u64 = 1000;
+
+const ERROR_LOG_SQL: &str = r#"
+DECLARE @log TABLE (
+    Id int IDENTITY(1, 1) PRIMARY KEY,
+    LogDate datetime,
+    ProcessInfo nvarchar(64),
+    Text nvarchar(max)
+);
+INSERT INTO @log (LogDate, ProcessInfo, Text) EXEC sys.xp_readerrorlog @P1, 1;
+SELECT LogDate AS logDate, ProcessInfo AS source, Text AS message
+FROM @log
+WHERE (@P2 IS NULL OR LogDate >= DATEADD(MINUTE, -@P2, GETDATE()))
+ORDER BY Id;
+"#;
+
+/// One logical error log entry. SQL Server writes `Error: n, Severity: s, State: t.` and the
+/// message text as two consecutive lines; they are folded together here.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    log_date: Value,
+    source: String,
+    error_number: Option<i64>,
+    severity: Option<i64>,
+    state: Option<i64>,
+    message: String,
+}
+
+pub fn run(args: &CliArgs, cmd: &ErrorlogArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
+    let offset = common::parse_offset(cmd.offset);
+    let pattern = cmd
+        .grep
+        .as_deref()
+        .map(|p| RegexBuilder::new(p).case_insensitive(true).build())
+        .transpose()
+        .map_err(|err| AppError::new(ErrorKind::Query, format!("Invalid --grep: {}", err)))?;
+
+    let archive = cmd.archive;
+    let last_minutes = cmd.last_minutes;
+    let raw = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(ERROR_LOG_SQL);
+        query.bind(archive as i32);
+        query.bind(last_minutes.map(|m| m as i64));
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+
+    let mut entries = fold_entries(&raw);
+    entries.reverse();
+    entries.retain(|entry| matches_filters(entry, pattern.as_ref(), cmd.min_severity));
+
+    let total = entries.len() as u64;
+    let page: Vec<Entry> = entries
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+    let paging = paging::build_paging(total, page.len() as u64, offset, limit);
+
+    if matches!(format, OutputFormat::Json) {
+        let items: Vec<serde_json::Value> = page
+            .iter()
+            .map(|entry| {
+                json!({
+                    "logDate": entry.log_date,
+                    "source": entry.source,
+                    "errorNumber": entry.error_number,
+                    "severity": entry.severity,
+                    "state": entry.state,
+                    "message": entry.message,
+                })
+            })
+            .collect();
+        let payload = json!({
+            "archive": archive,
+            "total": paging.total,
+            "count": paging.count,
+            "offset": paging.offset,
+            "limit": paging.limit,
+            "hasMore": paging.has_more,
+            "nextOffset": paging.next_offset,
+            "entries": items,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if page.is_empty() {
+        println!("No matching error log entries.");
+        return Ok(());
+    }
+
+    let result_set = ResultSet {
+        columns: ["logDate", "source", "severity", "error", "message"]
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: None,
+            })
+            .collect(),
+        rows: page
+            .iter()
+            .map(|entry| {
+                vec![
+                    entry.log_date.clone(),
+                    Value::Text(entry.source.clone()),
+                    entry.severity.map(Value::Int).unwrap_or(Value::Null),
+                    entry.error_number.map(Value::Int).unwrap_or(Value::Null),
+                    Value::Text(entry.message.clone()),
+                ]
+            })
+            .collect(),
+    };
+    let options = TableOptions {
+        pagination: Some(table::Pagination {
+            total: Some(paging.total),
+            offset: paging.offset,
+            limit: paging.count.max(1),
+        }),
+        ..TableOptions::default()
+    };
+    let result = table::render_result_set_table(&result_set, format, &options);
+    println!("{}", result.output);
+
+    Ok(())
+}
+
+fn fold_entries(raw: &ResultSet) -> Vec<Entry> {
+    let header = Regex::new(r"^Error: (\d+), Severity: (\d+), State: (\d+)").expect("regex");
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut pending: Option<Entry> = None;
+
+    for row in &raw.rows {
+        let log_date = row.first().cloned().unwrap_or(Value::Null);
+        let source = row.get(1).map(Value::as_display).unwrap_or_default();
+        let text = row.get(2).map(Value::as_display).unwrap_or_default();
+
+        if let Some(mut header_entry) = pending.take() {
+            if header_entry.log_date == log_date && header_entry.source == source {
+                header_entry.message = text;
+                entries.push(header_entry);
+                continue;
+            }
+            entries.push(header_entry);
+        }
+
+        match header.captures(&text) {
+            Some(caps) => {
+                let number = |idx: usize| caps.get(idx).and_then(|m| m.as_str().parse().ok());
+                pending = Some(Entry {
+                    log_date,
+                    source,
+                    error_number: number(1),
+                    severity: number(2),
+                    state: number(3),
+                    message: text.clone(),
+                });
+            }
+            None => entries.push(Entry {
+                log_date,
+                source,
+                error_number: None,
+                severity: None,
+                state: None,
+                message: text,
+            }),
+        }
+    }
+    entries.extend(pending);
+    entries
+}
+
+fn matches_filters(entry: &Entry, pattern: Option<&Regex>, min_severity: Option<u32>) -> bool {
+    if let Some(min) = min_severity {
+        if entry.severity.is_none_or(|s| s < i64::from(min)) {
+            return false;
+        }
+    }
+    pattern.is_none_or(|re| re.is_match(&entry.message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(rows: &[(&str, &str, &str)]) -> ResultSet {
+        ResultSet {
+            columns: Vec::new(),
+            rows: rows
+                .iter()
+                .map(|(date, source, text)| {
+                    vec![
+                        Value::Text(date.to_string()),
+                        Value::Text(source.to_string()),
+                        Value::Text(text.to_string()),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn folds_error_header_into_following_message() {
+        let entries = fold_entries(&raw(&[
+            (
+                "2024-01-01 10:00:00",
+                "Logon",
+                "Error: 18456, Severity: 14, State: 8.",
+            ),
+            (
+                "2024-01-01 10:00:00",
+                "Logon",
+                "Login failed for user 'app'.",
+            ),
+            ("2024-01-01 10:05:00", "spid12", "Starting up database 'x'."),
+        ]));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].error_number, Some(18456));
+        assert_eq!(entries[0].severity, Some(14));
+        assert_eq!(entries[0].message, "Login failed for user 'app'.");
+        assert_eq!(entries[1].severity, None);
+    }
+
+    #[test]
+    fn filters_by_severity_and_pattern() {
+        let entries = fold_entries(&raw(&[
+            (
+                "2024-01-01 10:00:00",
+                "spid5",
+                "Error: 1205, Severity: 13, State: 51.",
+            ),
+            ("2024-01-01 10:00:00", "spid5", "Transaction was deadlocked"),
+            ("2024-01-01 10:01:00", "spid6", "Informational message"),
+        ]));
+        let re = RegexBuilder::new("deadlock|login failed")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert!(matches_filters(&entries[0], Some(&re), Some(13)));
+        assert!(!matches_filters(&entries[0], None, Some(16)));
+        assert!(!matches_filters(&entries[1], Some(&re), None));
+    }
+}
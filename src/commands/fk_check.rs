@@ -0,0 +1,442 @@
+//! `fk-check`: orphaned child rows per foreign key, and fix scripts for untrusted constraints.
+//!
+//! A constraint re-enabled `WITH NOCHECK` (or left disabled after a bulk load) is not trusted:
+//! the optimizer ignores it and rows that break it may already exist. Each such foreign key gets
+//! an anti-join counting child rows whose parent is missing; the ones that come back clean get
+//! the `ALTER TABLE ... WITH CHECK CHECK CONSTRAINT` statement that makes them trusted again.
+//! Nothing is changed on the server.
+
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, FkCheckArgs};
+use crate::commands::{common, object_lookup};
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::ExitCode;
+use crate::output::{TableOptions, json as json_out, table};
+
+const FOREIGN_KEYS_SQL: &str = r#"
+SELECT
+    fk.name AS fk_name,
+    sp.name AS parent_schema,
+    tp.name AS parent_table,
+    sr.name AS referenced_schema,
+    tr.name AS referenced_table,
+    cp.name AS parent_column,
+    cr.name AS referenced_column,
+    fk.is_disabled,
+    fk.is_not_trusted
+FROM sys.foreign_keys fk
+INNER JOIN sys.tables tp ON tp.object_id = fk.parent_object_id
+INNER JOIN sys.schemas sp ON sp.schema_id = tp.schema_id
+INNER JOIN sys.tables tr ON tr.object_id = fk.referenced_object_id
+INNER JOIN sys.schemas sr ON sr.schema_id = tr.schema_id
+INNER JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id
+INNER JOIN sys.columns cp ON cp.object_id = fkc.parent_object_id AND cp.column_id = fkc.parent_column_id
+INNER JOIN sys.columns cr ON cr.object_id = fkc.referenced_object_id AND cr.column_id = fkc.referenced_column_id
+WHERE tp.is_ms_shipped = 0
+ORDER BY sp.name, tp.name, fk.name, fkc.constraint_column_id;
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ForeignKey {
+    name: String,
+    schema: String,
+    table: String,
+    referenced_schema: String,
+    referenced_table: String,
+    columns: Vec<String>,
+    referenced_columns: Vec<String>,
+    disabled: bool,
+    trusted: bool,
+}
+
+impl ForeignKey {
+    fn state(&self) -> &'static str {
+        if self.disabled {
+            "disabled"
+        } else if self.trusted {
+            "trusted"
+        } else {
+            "untrusted"
+        }
+    }
+
+    /// `FROM ... WHERE ...` selecting child rows with no parent. Rows with a NULL in any key
+    /// column satisfy the constraint, as SQL Server only enforces fully populated keys.
+    fn orphan_predicate(&self) -> String {
+        let mut conditions: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| format!("c.{} IS NOT NULL", bracket(column)))
+            .collect();
+        let join = self
+            .columns
+            .iter()
+            .zip(&self.referenced_columns)
+            .map(|(column, referenced)| {
+                format!("p.{} = c.{}", bracket(referenced), bracket(column))
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        conditions.push(format!(
+            "NOT EXISTS (SELECT 1 FROM {} p WHERE {})",
+            qualified(&self.referenced_schema, &self.referenced_table),
+            join
+        ));
+        format!(
+            "FROM {} c WHERE {}",
+            qualified(&self.schema, &self.table),
+            conditions.join(" AND ")
+        )
+    }
+
+    /// Query listing the orphaned rows, for the user to inspect or turn into a DELETE.
+    fn orphan_query(&self) -> String {
+        format!("SELECT c.* {};", self.orphan_predicate())
+    }
+
+    fn fix_statement(&self) -> String {
+        format!(
+            "ALTER TABLE {} WITH CHECK CHECK CONSTRAINT {};",
+            qualified(&self.schema, &self.table),
+            bracket(&self.name)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CheckResult {
+    foreign_key: ForeignKey,
+    /// `None` when the constraint was not scanned (trusted without `--all`) or the scan failed.
+    orphans: Option<i64>,
+    error: Option<String>,
+}
+
+impl CheckResult {
+    fn status(&self) -> &'static str {
+        match (self.orphans, &self.error) {
+            (_, Some(_)) => "error",
+            (Some(count), _) if count > 0 => "orphans",
+            (None, _) => "skipped",
+            _ if self.foreign_key.trusted && !self.foreign_key.disabled => "ok",
+            _ => "fixable",
+        }
+    }
+}
+
+pub fn run(args: &CliArgs, cmd: &FkCheckArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let allow_prompt = !matches!(format, OutputFormat::Json)
+        && std::io::stdin().is_terminal()
+        && std::io::stderr().is_terminal();
+
+    let results = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let targets = match cmd.table.as_deref() {
+            Some(table_raw) => {
+                let (table_name, schema_from_name) = common::normalize_object_input(table_raw);
+                let schema_hint = cmd.schema.as_deref().or(schema_from_name.as_deref());
+                if common::is_wildcard(table_raw) {
+                    object_lookup::expand_table_pattern(&mut client, &table_name, schema_hint)
+                        .await?
+                } else {
+                    vec![
+                        object_lookup::resolve_schema_for_object(
+                            &mut client,
+                            &resolved,
+                            &table_name,
+                            schema_hint,
+                            object_lookup::LookupScope::TablesOnly,
+                            "table",
+                            allow_prompt,
+                        )
+                        .await?,
+                    ]
+                }
+            }
+            None => {
+                object_lookup::expand_table_pattern(&mut client, "*", cmd.schema.as_deref()).await?
+            }
+        };
+
+        let result_sets = executor::run_query(Query::new(FOREIGN_KEYS_SQL), &mut client).await?;
+        let foreign_keys = group_rows(&result_sets.into_iter().next().unwrap_or_default().rows)
+            .into_iter()
+            .filter(|fk| {
+                targets.iter().any(|(schema, table)| {
+                    schema.eq_ignore_ascii_case(&fk.schema) && table.eq_ignore_ascii_case(&fk.table)
+                })
+            });
+
+        let mut results = Vec::new();
+        for foreign_key in foreign_keys {
+            // A trusted, enabled constraint was checked against every row; scanning it again
+            // only costs time unless the user asks.
+            if foreign_key.trusted && !foreign_key.disabled && !cmd.all {
+                results.push(CheckResult {
+                    foreign_key,
+                    orphans: None,
+                    error: None,
+                });
+                continue;
+            }
+            let sql = format!(
+                "SELECT COUNT_BIG(*) AS orphans {};",
+                foreign_key.orphan_predicate()
+            );
+            let (orphans, error) = match executor::run_query(Query::new(sql), &mut client).await {
+                Ok(sets) => (
+                    sets.first()
+                        .and_then(|rs| rs.rows.first())
+                        .and_then(|row| value_to_i64(row.first())),
+                    None,
+                ),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            results.push(CheckResult {
+                foreign_key,
+                orphans,
+                error,
+            });
+        }
+        Ok::<_, anyhow::Error>(results)
+    })?;
+
+    let count = |status: &str| results.iter().filter(|r| r.status() == status).count();
+    let with_orphans = count("orphans");
+    let untrusted = results.iter().filter(|r| !r.foreign_key.trusted).count();
+    let script = fix_script(&results);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "summary": {
+                "foreignKeys": results.len(),
+                "checked": results.len() - count("skipped"),
+                "untrusted": untrusted,
+                "withOrphans": with_orphans,
+                "fixable": count("fixable"),
+            },
+            "foreignKeys": results.iter().map(result_to_json).collect::<Vec<_>>(),
+            "script": script,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if results.is_empty() {
+            println!("No foreign keys found.");
+        } else {
+            let result_set = results_to_result_set(&results);
+            let rendered =
+                table::render_result_set_table(&result_set, format, &TableOptions::default());
+            println!("{}", rendered.output);
+            for result in &results {
+                if let Some(error) = &result.error {
+                    eprintln!("Warning: {}: {}", result.foreign_key.name, error);
+                }
+            }
+            println!(
+                "{} foreign keys, {} untrusted or disabled, {} with orphaned rows.",
+                results.len(),
+                untrusted,
+                with_orphans
+            );
+            if let Some(script) = &script {
+                println!("\nFix script (not run):\n{}", script);
+            }
+        }
+    }
+
+    if with_orphans > 0 {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+/// Fold one row per key column into foreign keys, ordered by table and constraint name.
+fn group_rows(rows: &[Vec<Value>]) -> Vec<ForeignKey> {
+    let mut grouped: BTreeMap<(String, String, String), ForeignKey> = BTreeMap::new();
+    for row in rows {
+        let schema = common::value_text(row.get(1));
+        let table = common::value_text(row.get(2));
+        let name = common::value_text(row.first());
+        let entry = grouped
+            .entry((schema.clone(), table.clone(), name.clone()))
+            .or_insert_with(|| ForeignKey {
+                name,
+                schema,
+                table,
+                referenced_schema: common::value_text(row.get(3)),
+                referenced_table: common::value_text(row.get(4)),
+                columns: Vec::new(),
+                referenced_columns: Vec::new(),
+                disabled: value_to_bool(row.get(7)),
+                trusted: !value_to_bool(row.get(8)),
+            });
+        entry.columns.push(common::value_text(row.get(5)));
+        entry
+            .referenced_columns
+            .push(common::value_text(row.get(6)));
+    }
+    grouped.into_values().collect()
+}
+
+/// `WITH CHECK CHECK CONSTRAINT` for every disabled or untrusted key found clean; keys with
+/// orphans are listed as comments, since the statement would fail until the rows are fixed.
+fn fix_script(results: &[CheckResult]) -> Option<String> {
+    let mut lines = Vec::new();
+    for result in results {
+        let fk = &result.foreign_key;
+        match result.status() {
+            "fixable" => lines.push(fk.fix_statement()),
+            "orphans" if !fk.trusted || fk.disabled => lines.push(format!(
+                "-- {}: {} orphaned rows; fix them first, then: {}",
+                fk.name,
+                result.orphans.unwrap_or_default(),
+                fk.fix_statement()
+            )),
+            _ => {}
+        }
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn result_to_json(result: &CheckResult) -> serde_json::Value {
+    let fk = &result.foreign_key;
+    json!({
+        "name": fk.name,
+        "table": { "schema": fk.schema, "name": fk.table },
+        "references": { "schema": fk.referenced_schema, "name": fk.referenced_table },
+        "columns": fk.columns,
+        "referencedColumns": fk.referenced_columns,
+        "state": fk.state(),
+        "status": result.status(),
+        "orphans": result.orphans,
+        "query": fk.orphan_query(),
+        "error": result.error,
+    })
+}
+
+fn results_to_result_set(results: &[CheckResult]) -> ResultSet {
+    let columns = [
+        "foreignKey",
+        "table",
+        "references",
+        "state",
+        "orphans",
+        "status",
+    ]
+    .into_iter()
+    .map(|name| Column {
+        name: name.to_string(),
+        data_type: None,
+    })
+    .collect();
+    let rows = results
+        .iter()
+        .map(|result| {
+            let fk = &result.foreign_key;
+            vec![
+                Value::Text(fk.name.clone()),
+                Value::Text(format!("{}.{}", fk.schema, fk.table)),
+                Value::Text(format!("{}.{}", fk.referenced_schema, fk.referenced_table)),
+                Value::Text(fk.state().to_string()),
+                result.orphans.map(Value::Int).unwrap_or(Value::Null),
+                Value::Text(result.status().to_string()),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+fn qualified(schema: &str, name: &str) -> String {
+    format!("{}.{}", bracket(schema), bracket(name))
+}
+
+fn value_to_bool(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(v)) => *v,
+        Some(Value::Int(v)) => *v != 0,
+        _ => false,
+    }
+}
+
+fn value_to_i64(value: Option<&Value>) -> Option<i64> {
+    match value {
+        Some(Value::Int(v)) => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_customer_fk(trusted: bool) -> ForeignKey {
+        ForeignKey {
+            name: "FK_Orders_Customers".to_string(),
+            schema: "dbo".to_string(),
+            table: "Orders".to_string(),
+            referenced_schema: "sales".to_string(),
+            referenced_table: "Customers".to_string(),
+            columns: vec!["CustomerId".to_string(), "Region".to_string()],
+            referenced_columns: vec!["Id".to_string(), "Region".to_string()],
+            disabled: false,
+            trusted,
+        }
+    }
+
+    #[test]
+    fn anti_join_skips_partially_null_keys() {
+        assert_eq!(
+            order_customer_fk(false).orphan_query(),
+            "SELECT c.* FROM [dbo].[Orders] c WHERE c.[CustomerId] IS NOT NULL \
+             AND c.[Region] IS NOT NULL AND NOT EXISTS (SELECT 1 FROM [sales].[Customers] p \
+             WHERE p.[Id] = c.[CustomerId] AND p.[Region] = c.[Region]);"
+        );
+    }
+
+    #[test]
+    fn script_fixes_clean_keys_and_comments_orphaned_ones() {
+        let clean = CheckResult {
+            foreign_key: order_customer_fk(false),
+            orphans: Some(0),
+            error: None,
+        };
+        let dirty = CheckResult {
+            foreign_key: ForeignKey {
+                name: "FK_Lines_Orders".to_string(),
+                ..order_customer_fk(false)
+            },
+            orphans: Some(4),
+            error: None,
+        };
+        let trusted = CheckResult {
+            foreign_key: order_customer_fk(true),
+            orphans: None,
+            error: None,
+        };
+        assert_eq!(clean.status(), "fixable");
+        assert_eq!(dirty.status(), "orphans");
+        assert_eq!(trusted.status(), "skipped");
+
+        let script = fix_script(&[clean, dirty, trusted]).expect("script");
+        assert_eq!(
+            script,
+            "ALTER TABLE [dbo].[Orders] WITH CHECK CHECK CONSTRAINT [FK_Orders_Customers];\n\
+             -- FK_Lines_Orders: 4 orphaned rows; fix them first, then: \
+             ALTER TABLE [dbo].[Orders] WITH CHECK CHECK CONSTRAINT [FK_Lines_Orders];"
+        );
+    }
+}
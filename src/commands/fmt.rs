@@ -0,0 +1,64 @@
+//! `fmt`: reformat T-SQL from an argument, a file, or stdin without connecting.
+
+use std::fs;
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::cli::{CliArgs, FmtArgs};
+use crate::commands::{common, sql, sql_format};
+use crate::config::OutputFormat;
+use crate::error::ExitCode;
+use crate::output::json as json_out;
+
+pub fn run(args: &CliArgs, cmd: &FmtArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let style = sql_format::style_from(&resolved.settings.formatting, &cmd.style);
+
+    let input = sql::read_sql_input(cmd.sql.as_deref(), cmd.file.as_deref(), cmd.stdin)?;
+    let formatted = sql_format::format_sql(&input, &style);
+    let changed = input.trim_end() != formatted;
+    let source = cmd
+        .file
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "input".to_string());
+
+    let written = cmd.write && changed;
+    if written && let Some(path) = &cmd.file {
+        fs::write(path, format!("{}\n", formatted))?;
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "file": cmd.file.as_ref().map(|path| path.display().to_string()),
+            "changed": changed,
+            "written": written,
+            "sql": formatted,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if cmd.check {
+            if changed {
+                eprintln!("{} is not formatted", source);
+            }
+        } else if cmd.write {
+            if written {
+                println!("Formatted {}", source);
+            } else {
+                println!("{} is already formatted", source);
+            }
+        } else {
+            println!("{}", formatted);
+        }
+    }
+
+    if cmd.check && changed {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
@@ -1,14 +1,15 @@
 use anyhow::{Result, anyhow};
 use serde_json::json;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::IsTerminal;
 use tiberius::Query;
 
 use crate::cli::{CliArgs, ForeignKeysArgs};
 use crate::commands::{common, object_lookup};
-use crate::config::OutputFormat;
+use crate::config::{OutputFormat, ResolvedConfig};
 use crate::db::client;
 use crate::db::executor;
+use crate::db::session::SqlClient;
 use crate::db::types::{Column, ResultSet, Value};
 use crate::output::{TableOptions, json as json_out, table};
 
@@ -24,18 +25,47 @@ struct ForeignKeyInfo {
     referenced_columns: Vec<String>,
     update_rule: String,
     delete_rule: String,
+    /// True when any referencing column allows NULL, so the relationship is optional.
+    nullable: bool,
 }
 
+const FOREIGN_KEY_SELECT: &str = r#"
+SELECT
+    fk.name AS fk_name,
+    schParent.name AS parent_schema,
+    parent.name AS parent_table,
+    cparent.name AS parent_column,
+    schRef.name AS referenced_schema,
+    referenced.name AS referenced_table,
+    cref.name AS referenced_column,
+    fk.update_referential_action_desc AS update_rule,
+    fk.delete_referential_action_desc AS delete_rule,
+    cparent.is_nullable AS parent_nullable
+FROM sys.foreign_keys fk
+INNER JOIN sys.tables parent ON fk.parent_object_id = parent.object_id
+INNER JOIN sys.schemas schParent ON parent.schema_id = schParent.schema_id
+INNER JOIN sys.tables referenced ON fk.referenced_object_id = referenced.object_id
+INNER JOIN sys.schemas schRef ON referenced.schema_id = schRef.schema_id
+INNER JOIN sys.foreign_key_columns fkc ON fk.object_id = fkc.constraint_object_id
+INNER JOIN sys.columns cparent ON fkc.parent_object_id = cparent.object_id AND fkc.parent_column_id = cparent.column_id
+INNER JOIN sys.columns cref ON fkc.referenced_object_id = cref.object_id AND fkc.referenced_column_id = cref.column_id
+"#;
+
 pub fn run(args: &CliArgs, cmd: &ForeignKeysArgs) -> Result<()> {
     let table_raw = cmd
         .table
         .as_deref()
         .ok_or_else(|| anyhow!("Missing required --table"))?;
     let (table_name, schema_from_name) = common::normalize_object_input(table_raw);
-    let direction = cmd
-        .direction
-        .clone()
-        .unwrap_or_else(|| "outbound".to_string());
+    // A graph is most useful with both parents and children, so it defaults to both.
+    let direction = cmd.direction.clone().unwrap_or_else(|| {
+        if cmd.graph.is_some() {
+            "both"
+        } else {
+            "outbound"
+        }
+        .to_string()
+    });
     let direction = direction.to_lowercase();
     if !["outbound", "inbound", "both"].contains(&direction.as_str()) {
         return Err(anyhow!("--direction must be outbound, inbound, or both"));
@@ -48,93 +78,103 @@ pub fn run(args: &CliArgs, cmd: &ForeignKeysArgs) -> Result<()> {
         && std::io::stdin().is_terminal()
         && std::io::stderr().is_terminal();
 
+    let wildcard = common::is_wildcard(table_raw);
+    if wildcard && cmd.graph.is_some() {
+        return Err(anyhow!(
+            "--graph starts from a single table; --table cannot be a wildcard pattern"
+        ));
+    }
+
     let requested_table_name = table_name.clone();
-    let fks = tokio::runtime::Runtime::new()?.block_on(async {
+    let (tables, matched) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
-        let (schema, table_name) = object_lookup::resolve_schema_for_object(
-            &mut client,
+        let targets = if wildcard {
+            object_lookup::expand_table_pattern(&mut client, &requested_table_name, schema_hint)
+                .await?
+        } else {
+            vec![
+                object_lookup::resolve_schema_for_object(
+                    &mut client,
+                    &resolved,
+                    &requested_table_name,
+                    schema_hint,
+                    object_lookup::LookupScope::TablesOnly,
+                    "table",
+                    allow_prompt,
+                )
+                .await?,
+            ]
+        };
+        let matched = targets.len();
+        let order = common::order_keys(
             &resolved,
-            &requested_table_name,
-            schema_hint,
-            object_lookup::LookupScope::TablesOnly,
-            "table",
-            allow_prompt,
-        )
-        .await?;
-        let sql = r#"
-SELECT
-    fk.name AS fk_name,
-    schParent.name AS parent_schema,
-    parent.name AS parent_table,
-    cparent.name AS parent_column,
-    schRef.name AS referenced_schema,
-    referenced.name AS referenced_table,
-    cref.name AS referenced_column,
-    fk.update_referential_action_desc AS update_rule,
-    fk.delete_referential_action_desc AS delete_rule,
-    fkc.constraint_column_id
-FROM sys.foreign_keys fk
-INNER JOIN sys.tables parent ON fk.parent_object_id = parent.object_id
-INNER JOIN sys.schemas schParent ON parent.schema_id = schParent.schema_id
-INNER JOIN sys.tables referenced ON fk.referenced_object_id = referenced.object_id
-INNER JOIN sys.schemas schRef ON referenced.schema_id = schRef.schema_id
-INNER JOIN sys.foreign_key_columns fkc ON fk.object_id = fkc.constraint_object_id
-INNER JOIN sys.columns cparent ON fkc.parent_object_id = cparent.object_id AND fkc.parent_column_id = cparent.column_id
-INNER JOIN sys.columns cref ON fkc.referenced_object_id = cref.object_id AND fkc.referenced_column_id = cref.column_id
-WHERE (
-    @P3 = 1 AND parent.name = @P1 AND (@P2 IS NULL OR schParent.name = @P2)
-) OR (
-    @P4 = 1 AND referenced.name = @P1 AND (@P2 IS NULL OR schRef.name = @P2)
-)
-ORDER BY fk.name, fkc.constraint_column_id;
-"#;
+            "schParent.name, fk.name, fkc.constraint_column_id",
+            "fk.object_id",
+        );
+        if cmd.graph.is_some() {
+            let (schema, table_name) = targets.into_iter().next().expect("resolved table");
+            let sql = format!("{}ORDER BY {};", FOREIGN_KEY_SELECT, order);
+            let result_sets = executor::run_query(Query::new(sql), &mut client).await?;
+            let result_set = result_sets.into_iter().next().unwrap_or_default();
+            let fks = group_rows(result_set.rows, |_| true);
+            return Ok::<_, anyhow::Error>((vec![(schema, table_name, fks)], matched));
+        }
 
-        let mut query = Query::new(sql);
-        query.bind(table_name.as_str());
-        query.bind(Some(schema.as_str()));
-        query.bind(if direction == "outbound" || direction == "both" { 1i32 } else { 0i32 });
-        query.bind(if direction == "inbound" || direction == "both" { 1i32 } else { 0i32 });
-        let result_sets = executor::run_query(query, &mut client).await?;
-        let result_set = result_sets.into_iter().next().unwrap_or_default();
-
-        let mut grouped: BTreeMap<String, ForeignKeyInfo> = BTreeMap::new();
-        for row in result_set.rows {
-            let fk_name = value_to_string(row.first());
-            let parent_schema = value_to_string(row.get(1));
-            let parent_table = value_to_string(row.get(2));
-            let parent_column = value_to_string(row.get(3));
-            let ref_schema = value_to_string(row.get(4));
-            let ref_table = value_to_string(row.get(5));
-            let ref_column = value_to_string(row.get(6));
-            let update_rule = value_to_string(row.get(7));
-            let delete_rule = value_to_string(row.get(8));
-
-            let is_outbound = parent_table.eq_ignore_ascii_case(table_name.as_str());
-            let entry = grouped.entry(fk_name.clone()).or_insert_with(|| ForeignKeyInfo {
-                name: fk_name.clone(),
-                direction: if is_outbound { "outbound".to_string() } else { "inbound".to_string() },
-                from_schema: if is_outbound { parent_schema.clone() } else { ref_schema.clone() },
-                from_table: if is_outbound { parent_table.clone() } else { ref_table.clone() },
-                to_schema: if is_outbound { ref_schema.clone() } else { parent_schema.clone() },
-                to_table: if is_outbound { ref_table.clone() } else { parent_table.clone() },
-                columns: Vec::new(),
-                referenced_columns: Vec::new(),
-                update_rule: update_rule.clone(),
-                delete_rule: delete_rule.clone(),
-            });
+        let mut tables = Vec::new();
+        for (schema, table_name) in targets.into_iter().take(cmd.max_objects) {
+            let fks =
+                fetch_foreign_keys(&mut client, &order, &schema, &table_name, &direction).await?;
+            tables.push((schema, table_name, fks));
+        }
+        Ok::<_, anyhow::Error>((tables, matched))
+    })?;
 
-            if entry.direction == "outbound" {
-                entry.columns.push(parent_column);
-                entry.referenced_columns.push(ref_column);
-            } else {
-                entry.columns.push(ref_column);
-                entry.referenced_columns.push(parent_column);
+    if wildcard {
+        return print_matches(args, &resolved, format, &direction, &tables, matched);
+    }
+    let (resolved_schema, resolved_table_name, fks) =
+        tables.into_iter().next().expect("resolved table");
+
+    if let Some(graph_format) = cmd.graph.as_deref() {
+        let start = (resolved_schema.clone(), resolved_table_name.clone());
+        let (tables, edges) = walk_graph(&fks, &start, &direction, cmd.depth);
+        let graph = if graph_format == "mermaid" {
+            render_mermaid(&tables, &edges)
+        } else {
+            render_dot(&start, &tables, &edges)
+        };
+        if matches!(format, OutputFormat::Json) {
+            let payload = json!({
+                "table": { "schema": resolved_schema, "name": resolved_table_name },
+                "direction": direction,
+                "depth": cmd.depth,
+                "format": graph_format,
+                "tables": tables
+                    .iter()
+                    .map(|(schema, name)| json!({ "schema": schema, "name": name }))
+                    .collect::<Vec<_>>(),
+                "foreignKeys": edges
+                    .iter()
+                    .map(|fk| json!({
+                        "name": fk.name,
+                        "from": { "schema": fk.from_schema, "table": fk.from_table },
+                        "to": { "schema": fk.to_schema, "table": fk.to_table },
+                        "columns": fk.columns,
+                        "referencedColumns": fk.referenced_columns,
+                        "nullable": fk.nullable,
+                    }))
+                    .collect::<Vec<_>>(),
+                "graph": graph,
+            });
+            let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+            if !args.quiet {
+                println!("{}", body);
             }
+        } else if !args.quiet {
+            print!("{}", graph);
         }
-
-        Ok::<_, anyhow::Error>((schema, table_name, grouped.into_values().collect::<Vec<_>>()))
-    })?;
-    let (resolved_schema, resolved_table_name, fks) = fks;
+        return Ok(());
+    }
 
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
@@ -160,6 +200,272 @@ ORDER BY fk.name, fkc.constraint_column_id;
     Ok(())
 }
 
+/// Output for a `--table` pattern: one section per matching table, as `describe` lays out
+/// several matches.
+fn print_matches(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    direction: &str,
+    tables: &[(String, String, Vec<ForeignKeyInfo>)],
+    matched: usize,
+) -> Result<()> {
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "direction": direction,
+            "matched": matched,
+            "truncated": matched > tables.len(),
+            "matches": tables
+                .iter()
+                .map(|(schema, name, fks)| json!({
+                    "table": { "schema": schema, "name": name },
+                    "foreignKeys": fks.iter().map(fk_to_json).collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    for (i, (schema, name, fks)) in tables.iter().enumerate() {
+        if i > 0 {
+            println!("\n---\n");
+        }
+        println!("## {}.{}\n", schema, name);
+        if fks.is_empty() {
+            println!("(no foreign keys)");
+            continue;
+        }
+        let result_set = fks_to_result_set(fks);
+        let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+    if let Some(note) = common::max_objects_note(tables.len(), matched) {
+        println!("\n---\n{}", note);
+    }
+    Ok(())
+}
+
+async fn fetch_foreign_keys(
+    client: &mut SqlClient,
+    order: &str,
+    schema: &str,
+    table_name: &str,
+    direction: &str,
+) -> Result<Vec<ForeignKeyInfo>> {
+    let sql = format!(
+        r#"{}WHERE (
+    @P3 = 1 AND parent.name = @P1 AND (@P2 IS NULL OR schParent.name = @P2)
+) OR (
+    @P4 = 1 AND referenced.name = @P1 AND (@P2 IS NULL OR schRef.name = @P2)
+)
+ORDER BY {};
+"#,
+        FOREIGN_KEY_SELECT, order
+    );
+
+    let mut query = Query::new(sql);
+    query.bind(table_name);
+    query.bind(Some(schema));
+    query.bind(if direction == "outbound" || direction == "both" {
+        1i32
+    } else {
+        0i32
+    });
+    query.bind(if direction == "inbound" || direction == "both" {
+        1i32
+    } else {
+        0i32
+    });
+    let result_sets = executor::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+    Ok(group_rows(result_set.rows, |parent_table| {
+        parent_table.eq_ignore_ascii_case(table_name)
+    }))
+}
+
+fn group_rows(rows: Vec<Vec<Value>>, is_outbound: impl Fn(&str) -> bool) -> Vec<ForeignKeyInfo> {
+    let mut grouped: BTreeMap<(String, String), ForeignKeyInfo> = BTreeMap::new();
+    for row in rows {
+        let fk_name = value_to_string(row.first());
+        let parent_schema = value_to_string(row.get(1));
+        let parent_table = value_to_string(row.get(2));
+        let parent_column = value_to_string(row.get(3));
+        let ref_schema = value_to_string(row.get(4));
+        let ref_table = value_to_string(row.get(5));
+        let ref_column = value_to_string(row.get(6));
+        let update_rule = value_to_string(row.get(7));
+        let delete_rule = value_to_string(row.get(8));
+        let parent_nullable = matches!(row.get(9), Some(Value::Bool(true)));
+
+        let is_outbound = is_outbound(&parent_table);
+        let key = (parent_schema.clone(), fk_name.clone());
+        let entry = grouped.entry(key).or_insert_with(|| ForeignKeyInfo {
+            name: fk_name.clone(),
+            direction: if is_outbound {
+                "outbound".to_string()
+            } else {
+                "inbound".to_string()
+            },
+            from_schema: if is_outbound {
+                parent_schema.clone()
+            } else {
+                ref_schema.clone()
+            },
+            from_table: if is_outbound {
+                parent_table.clone()
+            } else {
+                ref_table.clone()
+            },
+            to_schema: if is_outbound {
+                ref_schema.clone()
+            } else {
+                parent_schema.clone()
+            },
+            to_table: if is_outbound {
+                ref_table.clone()
+            } else {
+                parent_table.clone()
+            },
+            columns: Vec::new(),
+            referenced_columns: Vec::new(),
+            update_rule: update_rule.clone(),
+            delete_rule: delete_rule.clone(),
+            nullable: false,
+        });
+
+        entry.nullable |= parent_nullable;
+        if entry.direction == "outbound" {
+            entry.columns.push(parent_column);
+            entry.referenced_columns.push(ref_column);
+        } else {
+            entry.columns.push(ref_column);
+            entry.referenced_columns.push(parent_column);
+        }
+    }
+    grouped.into_values().collect()
+}
+
+type TableKey = (String, String);
+
+/// Breadth-first walk of the foreign keys around `start`; each key runs child to parent.
+///
+/// `direction` picks which way each hop may follow a key and `depth` caps the hops. Every
+/// foreign key between two reached tables is returned, so sibling links show up too.
+fn walk_graph<'a>(
+    fks: &'a [ForeignKeyInfo],
+    start: &TableKey,
+    direction: &str,
+    depth: u32,
+) -> (Vec<TableKey>, Vec<&'a ForeignKeyInfo>) {
+    let follow_outbound = direction == "outbound" || direction == "both";
+    let follow_inbound = direction == "inbound" || direction == "both";
+    let mut reached: BTreeSet<TableKey> = BTreeSet::from([start.clone()]);
+    let mut frontier = vec![start.clone()];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for table in &frontier {
+            for fk in fks {
+                let from = (fk.from_schema.clone(), fk.from_table.clone());
+                let to = (fk.to_schema.clone(), fk.to_table.clone());
+                let neighbour = if follow_outbound && &from == table {
+                    to
+                } else if follow_inbound && &to == table {
+                    from
+                } else {
+                    continue;
+                };
+                if reached.insert(neighbour.clone()) {
+                    next.push(neighbour);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let edges = fks
+        .iter()
+        .filter(|fk| {
+            reached.contains(&(fk.from_schema.clone(), fk.from_table.clone()))
+                && reached.contains(&(fk.to_schema.clone(), fk.to_table.clone()))
+        })
+        .collect();
+    (reached.into_iter().collect(), edges)
+}
+
+fn render_dot(start: &TableKey, tables: &[TableKey], edges: &[&ForeignKeyInfo]) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut out = String::from("digraph foreign_keys {\n    rankdir=LR;\n    node [shape=box];\n");
+    for table in tables {
+        let label = format!("{}.{}", table.0, table.1);
+        if table == start {
+            out.push_str(&format!("    {} [style=bold];\n", quote(&label)));
+        } else {
+            out.push_str(&format!("    {};\n", quote(&label)));
+        }
+    }
+    for fk in edges {
+        out.push_str(&format!(
+            "    {} -> {} [label={}{}];\n",
+            quote(&format!("{}.{}", fk.from_schema, fk.from_table)),
+            quote(&format!("{}.{}", fk.to_schema, fk.to_table)),
+            quote(&format!("{} ({})", fk.name, fk.columns.join(", "))),
+            if fk.nullable { ", style=dashed" } else { "" }
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_mermaid(tables: &[TableKey], edges: &[&ForeignKeyInfo]) -> String {
+    // Mermaid entity ids are limited to word characters; the real name goes in the alias.
+    let mut ids: BTreeMap<&TableKey, String> = BTreeMap::new();
+    let mut taken = BTreeSet::new();
+    for table in tables {
+        let base: String = format!("{}_{}", table.0, table.1)
+            .chars()
+            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+            .collect();
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(id.clone()) {
+            id = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        ids.insert(table, id);
+    }
+
+    let mut out = String::from("erDiagram\n");
+    for table in tables {
+        out.push_str(&format!(
+            "    {}[\"{}.{}\"]\n",
+            ids[table],
+            table.0.replace('"', "'"),
+            table.1.replace('"', "'")
+        ));
+    }
+    for fk in edges {
+        let from = (fk.from_schema.clone(), fk.from_table.clone());
+        let to = (fk.to_schema.clone(), fk.to_table.clone());
+        out.push_str(&format!(
+            "    {} }}o--{} {} : \"{}\"\n",
+            ids[&from],
+            if fk.nullable { "o|" } else { "||" },
+            ids[&to],
+            fk.name.replace('"', "'")
+        ));
+    }
+    out
+}
+
 fn fks_to_result_set(fks: &[ForeignKeyInfo]) -> ResultSet {
     let columns = vec![
         Column {
@@ -234,6 +540,61 @@ fn value_to_string(value: Option<&Value>) -> String {
         Some(Value::Int(v)) => v.to_string(),
         Some(Value::Bool(v)) => v.to_string(),
         Some(Value::Float(v)) => v.to_string(),
+        Some(Value::Decimal(v)) => v.to_string(),
         _ => "".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fk(name: &str, from: &str, to: &str, nullable: bool) -> ForeignKeyInfo {
+        ForeignKeyInfo {
+            name: name.to_string(),
+            direction: "outbound".to_string(),
+            from_schema: "dbo".to_string(),
+            from_table: from.to_string(),
+            to_schema: "dbo".to_string(),
+            to_table: to.to_string(),
+            columns: vec![format!("{}Id", to)],
+            referenced_columns: vec!["Id".to_string()],
+            update_rule: "NO_ACTION".to_string(),
+            delete_rule: "NO_ACTION".to_string(),
+            nullable,
+        }
+    }
+
+    #[test]
+    fn graph_walk_respects_depth_and_renders() {
+        let fks = vec![
+            fk("FK_Orders_Customers", "Orders", "Customers", false),
+            fk("FK_Lines_Orders", "Lines", "Orders", false),
+            fk("FK_Customers_Regions", "Customers", "Regions", true),
+            fk("FK_Lines_Customers", "Lines", "Customers", false),
+        ];
+        let start = ("dbo".to_string(), "Orders".to_string());
+
+        let (tables, edges) = walk_graph(&fks, &start, "both", 1);
+        let names: Vec<_> = tables.iter().map(|t| t.1.as_str()).collect();
+        assert_eq!(names, vec!["Customers", "Lines", "Orders"]);
+        assert_eq!(edges.len(), 3);
+
+        let (tables, _) = walk_graph(&fks, &start, "outbound", 5);
+        assert_eq!(tables.len(), 3);
+        assert!(!tables.iter().any(|t| t.1 == "Lines"));
+
+        let (tables, edges) = walk_graph(&fks, &start, "outbound", 2);
+        let mermaid = render_mermaid(&tables, &edges);
+        assert!(mermaid.starts_with("erDiagram\n"));
+        assert!(mermaid.contains("    dbo_Orders[\"dbo.Orders\"]\n"));
+        assert!(mermaid.contains("dbo_Orders }o--|| dbo_Customers : \"FK_Orders_Customers\""));
+        assert!(mermaid.contains("dbo_Customers }o--o| dbo_Regions"));
+
+        let dot = render_dot(&start, &tables, &edges);
+        assert!(dot.contains("    \"dbo.Orders\" [style=bold];\n"));
+        assert!(dot.contains(
+            "\"dbo.Customers\" -> \"dbo.Regions\" [label=\"FK_Customers_Regions (RegionsId)\", style=dashed];"
+        ));
+    }
+}
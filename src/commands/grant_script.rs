@@ -0,0 +1,367 @@
+//! `security grant-script`: CREATE ROLE, GRANT, and ALTER ROLE statements for an app account.
+//!
+//! Grants are schema-scoped (`GRANT SELECT ON SCHEMA::[web] TO [app_reader]`), so objects added
+//! later are covered without another script. With `--missing-only` the principal's current
+//! permissions, including those it inherits through role membership, are read first and only
+//! the difference is emitted. The script is printed, never run.
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, GrantScriptArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::Value;
+use crate::error::{AppError, ErrorKind};
+use crate::output::json as json_out;
+
+const PRINCIPAL_SQL: &str = r#"
+SELECT dp.type_desc
+FROM sys.database_principals dp
+WHERE dp.name = @P1;
+"#;
+
+/// Schema- and database-level permissions of the principal and every role it belongs to,
+/// followed by the names of those roles (`state = 'R'`).
+const PERMISSIONS_SQL: &str = r#"
+WITH principals AS (
+    SELECT dp.principal_id
+    FROM sys.database_principals dp
+    WHERE dp.name = @P1
+    UNION ALL
+    SELECT rm.role_principal_id
+    FROM sys.database_role_members rm
+    INNER JOIN principals p ON p.principal_id = rm.member_principal_id
+)
+SELECT perm.state, perm.permission_name,
+       CASE WHEN perm.class = 3 THEN SCHEMA_NAME(perm.major_id) END AS schema_name
+FROM sys.database_permissions perm
+INNER JOIN principals p ON p.principal_id = perm.grantee_principal_id
+WHERE perm.class IN (0, 3)
+UNION ALL
+SELECT 'R', r.name, NULL
+FROM principals p
+INNER JOIN sys.database_principals r ON r.principal_id = p.principal_id
+WHERE r.type = 'R';
+"#;
+
+const MEMBERS_SQL: &str = r#"
+SELECT m.name
+FROM sys.database_role_members rm
+INNER JOIN sys.database_principals r ON r.principal_id = rm.role_principal_id
+INNER JOIN sys.database_principals m ON m.principal_id = rm.member_principal_id
+WHERE r.name = @P1;
+"#;
+
+/// What the principal already holds; every name is lowercased for comparison.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Held {
+    /// `type_desc` of the principal, `None` when it does not exist yet.
+    principal_type: Option<String>,
+    /// `(schema, permission)`; a `None` schema is a database-wide grant.
+    grants: BTreeSet<(Option<String>, String)>,
+    denies: BTreeSet<(Option<String>, String)>,
+    /// Roles the principal is a member of, directly or through other roles.
+    roles: BTreeSet<String>,
+    /// Current members of the principal, when it is a role.
+    members: BTreeSet<String>,
+}
+
+impl Held {
+    fn has(set: &BTreeSet<(Option<String>, String)>, schema: &str, permission: &str) -> bool {
+        let schema = schema.to_lowercase();
+        [None, Some(schema)].into_iter().any(|scope| {
+            set.contains(&(scope.clone(), permission.to_lowercase()))
+                || set.contains(&(scope, "control".to_string()))
+        })
+    }
+
+    /// Whether `permission` on `schema` is already granted, explicitly or by a fixed role.
+    fn covers(&self, schema: &str, permission: &str) -> bool {
+        let role = |name: &str| self.roles.contains(name);
+        role("db_owner")
+            || (permission == "SELECT" && role("db_datareader"))
+            || (matches!(permission, "INSERT" | "UPDATE" | "DELETE") && role("db_datawriter"))
+            || Self::has(&self.grants, schema, permission)
+    }
+
+    fn denied(&self, schema: &str, permission: &str) -> bool {
+        (permission == "SELECT" && self.roles.contains("db_denydatareader"))
+            || (matches!(permission, "INSERT" | "UPDATE" | "DELETE")
+                && self.roles.contains("db_denydatawriter"))
+            || self
+                .denies
+                .contains(&(Some(schema.to_lowercase()), permission.to_lowercase()))
+            || self.denies.contains(&(None, permission.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct GrantScript {
+    statements: Vec<String>,
+    /// Statements left out because the principal already holds them (`--missing-only`).
+    already_held: Vec<String>,
+    warnings: Vec<String>,
+}
+
+pub fn run(args: &CliArgs, cmd: &GrantScriptArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let schemas = if cmd.schemas.is_empty() {
+        resolved.connection.default_schemas.clone()
+    } else {
+        cmd.schemas.clone()
+    };
+    if schemas.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "No schemas to grant on; pass --schemas or set the profile's defaultSchemas",
+        )
+        .into());
+    }
+    let permissions: Vec<String> = cmd.allow.iter().map(|p| permission_name(p)).collect();
+
+    let held = if cmd.missing_only {
+        Some(tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            fetch_held(&mut client, &cmd.principal).await
+        })?)
+    } else {
+        None
+    };
+    let script = build_script(
+        &cmd.principal,
+        &permissions,
+        &schemas,
+        &cmd.members,
+        held.as_ref(),
+    );
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "principal": cmd.principal,
+            "database": resolved.connection.database,
+            "schemas": schemas,
+            "allow": permissions,
+            "members": cmd.members,
+            "missingOnly": cmd.missing_only,
+            "statements": script.statements,
+            "alreadyHeld": script.already_held,
+            "warnings": script.warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    println!(
+        "-- Least-privilege grants for {} in {} (review, then run)",
+        bracket(&cmd.principal),
+        bracket(&resolved.connection.database)
+    );
+    if script.statements.is_empty() {
+        println!("-- Nothing to grant: the principal already holds every requested permission.");
+    }
+    for statement in &script.statements {
+        println!("{}", statement);
+    }
+    for warning in &script.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+async fn fetch_held(client: &mut SqlClient, principal: &str) -> Result<Held> {
+    let mut held = Held::default();
+    let mut query = Query::new(PRINCIPAL_SQL);
+    query.bind(principal);
+    let sets = executor::run_query(query, client).await?;
+    held.principal_type = sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .map(|row| common::value_text(row.first()));
+    if held.principal_type.is_none() {
+        return Ok(held);
+    }
+
+    let mut query = Query::new(PERMISSIONS_SQL);
+    query.bind(principal);
+    let sets = executor::run_query(query, client).await?;
+    for row in sets.into_iter().next().unwrap_or_default().rows {
+        let state = common::value_text(row.first());
+        let name = common::value_text(row.get(1)).to_lowercase();
+        let schema = match row.get(2) {
+            Some(Value::Text(schema)) => Some(schema.to_lowercase()),
+            _ => None,
+        };
+        match state.as_str() {
+            "R" => {
+                held.roles.insert(name);
+            }
+            "D" => {
+                held.denies.insert((schema, name));
+            }
+            _ => {
+                held.grants.insert((schema, name));
+            }
+        }
+    }
+
+    let mut query = Query::new(MEMBERS_SQL);
+    query.bind(principal);
+    let sets = executor::run_query(query, client).await?;
+    held.members = sets
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+        .rows
+        .iter()
+        .map(|row| common::value_text(row.first()).to_lowercase())
+        .collect();
+    Ok(held)
+}
+
+/// The statements granting `permissions` on `schemas` to `principal`. Without `held` the
+/// script is complete and idempotent; with it, only what is missing is kept.
+fn build_script(
+    principal: &str,
+    permissions: &[String],
+    schemas: &[String],
+    members: &[String],
+    held: Option<&Held>,
+) -> GrantScript {
+    let mut script = GrantScript::default();
+    let quoted = bracket(principal);
+    let mut emit = |statement: String, already: bool| {
+        if already {
+            script.already_held.push(statement);
+        } else {
+            script.statements.push(statement);
+        }
+    };
+
+    match held.map(|held| held.principal_type.as_deref()) {
+        None => emit(
+            format!(
+                "IF DATABASE_PRINCIPAL_ID(N'{}') IS NULL CREATE ROLE {};",
+                principal.replace('\'', "''"),
+                quoted
+            ),
+            false,
+        ),
+        Some(None) => emit(format!("CREATE ROLE {};", quoted), false),
+        Some(Some(_)) => {}
+    }
+
+    for schema in schemas {
+        for permission in permissions {
+            let already = held.is_some_and(|held| held.covers(schema, permission));
+            emit(
+                format!(
+                    "GRANT {} ON SCHEMA::{} TO {};",
+                    permission,
+                    bracket(schema),
+                    quoted
+                ),
+                already,
+            );
+            if held.is_some_and(|held| held.denied(schema, permission)) {
+                script.warnings.push(format!(
+                    "{} is denied {} on {} (directly or through a role); DENY overrides the grant until it is revoked",
+                    principal, permission, schema
+                ));
+            }
+        }
+    }
+
+    for member in members {
+        let already = held.is_some_and(|held| held.members.contains(&member.to_lowercase()));
+        emit(
+            format!("ALTER ROLE {} ADD MEMBER {};", quoted, bracket(member)),
+            already,
+        );
+    }
+    if !members.is_empty()
+        && let Some(Some(principal_type)) = held.map(|held| held.principal_type.as_deref())
+        && principal_type != "DATABASE_ROLE"
+    {
+        script.warnings.push(format!(
+            "{} is a {}, not a role; --member needs a role to add users to",
+            principal,
+            principal_type.to_lowercase().replace('_', " ")
+        ));
+    }
+    script
+}
+
+fn permission_name(value: &str) -> String {
+    value.replace('-', " ").to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn full_script_creates_role_and_grants_per_schema() {
+        let script = build_script(
+            "app_reader",
+            &strings(&["SELECT", "VIEW DEFINITION"]),
+            &strings(&["dbo", "web"]),
+            &strings(&["svc_web"]),
+            None,
+        );
+        assert_eq!(
+            script.statements,
+            strings(&[
+                "IF DATABASE_PRINCIPAL_ID(N'app_reader') IS NULL CREATE ROLE [app_reader];",
+                "GRANT SELECT ON SCHEMA::[dbo] TO [app_reader];",
+                "GRANT VIEW DEFINITION ON SCHEMA::[dbo] TO [app_reader];",
+                "GRANT SELECT ON SCHEMA::[web] TO [app_reader];",
+                "GRANT VIEW DEFINITION ON SCHEMA::[web] TO [app_reader];",
+                "ALTER ROLE [app_reader] ADD MEMBER [svc_web];",
+            ])
+        );
+        assert_eq!(permission_name("view-definition"), "VIEW DEFINITION");
+    }
+
+    #[test]
+    fn missing_only_skips_held_grants_and_warns_on_denies() {
+        let held = Held {
+            principal_type: Some("DATABASE_ROLE".to_string()),
+            grants: BTreeSet::from([(Some("web".to_string()), "execute".to_string())]),
+            denies: BTreeSet::from([(Some("dbo".to_string()), "execute".to_string())]),
+            roles: BTreeSet::from(["db_datareader".to_string()]),
+            members: BTreeSet::from(["svc_web".to_string()]),
+        };
+        let script = build_script(
+            "app_reader",
+            &strings(&["SELECT", "EXECUTE"]),
+            &strings(&["dbo", "Web"]),
+            &strings(&["svc_web"]),
+            Some(&held),
+        );
+        assert_eq!(
+            script.statements,
+            strings(&["GRANT EXECUTE ON SCHEMA::[dbo] TO [app_reader];"])
+        );
+        assert_eq!(script.already_held.len(), 4);
+        assert_eq!(script.warnings.len(), 1);
+        assert!(script.warnings[0].contains("DENY overrides"));
+    }
+}
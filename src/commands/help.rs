@@ -1,9 +1,14 @@
 use std::io::{self, Write};
 
-use crate::cli::build_cli;
+use serde_json::json;
+
+use crate::cli::{CliArgs, cli_tree};
+use crate::config::JsonContractVersion;
+use crate::error::{AppError, ErrorKind};
+use crate::output::contract;
 
 pub fn run(show_all: bool, command: Option<&str>) -> anyhow::Result<()> {
-    let mut cmd = build_cli(show_all);
+    let mut cmd = cli_tree(show_all);
 
     if let Some(name) = command {
         if let Some(sub) = cmd.find_subcommand_mut(name) {
@@ -17,3 +22,41 @@ pub fn run(show_all: bool, command: Option<&str>) -> anyhow::Result<()> {
     io::stdout().flush()?;
     Ok(())
 }
+
+/// `help --schema [COMMAND]`: one command's output schema (`error` for the error document), or
+/// every published schema keyed by command. Config is not read, so `--contract` picks the version.
+pub fn run_schema(args: &CliArgs, command: Option<&str>) -> anyhow::Result<()> {
+    let version = args.contract.unwrap_or(JsonContractVersion::V1);
+    let schema = match command {
+        Some("error") => contract::error_schema(version),
+        Some(name) => {
+            let cli = cli_tree(true);
+            let canonical = cli
+                .find_subcommand(name)
+                .map(|sub| sub.get_name().to_string())
+                .unwrap_or_else(|| name.to_string());
+            contract::command_schema(&canonical, version).ok_or_else(|| {
+                AppError::new(
+                    ErrorKind::Config,
+                    format!(
+                        "No JSON Schema is published for '{}'. Available: {}, error",
+                        name,
+                        contract::COMMANDS.join(", ")
+                    ),
+                )
+            })?
+        }
+        None => {
+            let mut all = serde_json::Map::new();
+            for name in contract::COMMANDS {
+                if let Some(schema) = contract::command_schema(name, version) {
+                    all.insert(name.to_string(), schema);
+                }
+            }
+            all.insert("error".to_string(), contract::error_schema(version));
+            json!(all)
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
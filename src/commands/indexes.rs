@@ -6,12 +6,50 @@ use tiberius::Query;
 
 use crate::cli::{CliArgs, IndexesArgs};
 use crate::commands::{common, object_lookup};
-use crate::config::OutputFormat;
+use crate::config::{OutputFormat, ResolvedConfig};
 use crate::db::client;
 use crate::db::executor;
+use crate::db::permissions;
+use crate::db::session::SqlClient;
 use crate::db::types::{Column, ResultSet, Value};
 use crate::output::{TableOptions, json as json_out, table};
 
+const USAGE_JOIN: &str = "\
+LEFT JOIN sys.dm_db_index_usage_stats usage_stats
+    ON usage_stats.database_id = DB_ID()
+   AND usage_stats.object_id = i.object_id
+   AND usage_stats.index_id = i.index_id";
+
+/// Row group states per columnstore index; reading the DMV needs VIEW DATABASE STATE.
+const ROW_GROUPS_SQL: &str = r#"
+SELECT
+    rg.index_id,
+    SUM(CASE WHEN rg.state_desc = 'OPEN' THEN 1 ELSE 0 END) AS open_groups,
+    SUM(CASE WHEN rg.state_desc = 'CLOSED' THEN 1 ELSE 0 END) AS closed_groups,
+    SUM(CASE WHEN rg.state_desc = 'COMPRESSED' THEN 1 ELSE 0 END) AS compressed_groups,
+    SUM(CASE WHEN rg.state_desc = 'TOMBSTONE' THEN 1 ELSE 0 END) AS tombstone_groups,
+    CAST(SUM(rg.total_rows) AS bigint) AS total_rows,
+    CAST(SUM(ISNULL(rg.deleted_rows, 0)) AS bigint) AS deleted_rows
+FROM sys.dm_db_column_store_row_group_physical_stats rg
+INNER JOIN sys.objects o ON o.object_id = rg.object_id
+INNER JOIN sys.schemas s ON s.schema_id = o.schema_id
+WHERE o.name = @P1
+  AND s.name = @P2
+GROUP BY rg.index_id;
+"#;
+
+/// Sizes come back per partition: column 4 is the current size, column 5 the requested one (KB).
+const ESTIMATE_SQL: &str = "\
+EXEC sys.sp_estimate_data_compression_savings
+    @schema_name = @P1,
+    @object_name = @P2,
+    @index_id = @P3,
+    @partition_number = NULL,
+    @data_compression = @P4;";
+
+/// Smallest saving, in percent of the current size, worth a rebuild.
+const MIN_SAVINGS_PERCENT: f64 = 20.0;
+
 #[derive(Debug, Clone)]
 struct IndexInfo {
     schema: String,
@@ -23,6 +61,62 @@ struct IndexInfo {
     included_columns: Vec<String>,
     user_seeks: Option<i64>,
     user_updates: Option<i64>,
+    index_id: i64,
+    compression: String,
+    row_groups: Option<RowGroups>,
+    estimate: Option<CompressionEstimate>,
+}
+
+/// Columnstore row group health, summed over partitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct RowGroups {
+    open: i64,
+    closed: i64,
+    compressed: i64,
+    tombstone: i64,
+    total_rows: i64,
+    deleted_rows: i64,
+}
+
+impl RowGroups {
+    fn deleted_percent(&self) -> f64 {
+        if self.total_rows == 0 {
+            0.0
+        } else {
+            self.deleted_rows as f64 * 100.0 / self.total_rows as f64
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} compressed, {} open, {} closed; {:.1}% deleted",
+            self.compressed,
+            self.open,
+            self.closed,
+            self.deleted_percent()
+        )
+    }
+}
+
+/// `sp_estimate_data_compression_savings` sizes for a rowstore index, in KB.
+#[derive(Debug, Clone, PartialEq)]
+struct CompressionEstimate {
+    current_kb: i64,
+    row_kb: i64,
+    page_kb: i64,
+    recommendation: String,
+    savings_percent: f64,
+}
+
+impl IndexInfo {
+    fn is_columnstore(&self) -> bool {
+        self.index_type.contains("COLUMNSTORE")
+    }
+
+    /// B-tree indexes, the only kind `sp_estimate_data_compression_savings` sizes as ROW/PAGE.
+    fn is_rowstore(&self) -> bool {
+        matches!(self.index_type.as_str(), "CLUSTERED" | "NONCLUSTERED")
+    }
 }
 
 pub fn run(args: &CliArgs, cmd: &IndexesArgs) -> Result<()> {
@@ -38,85 +132,89 @@ pub fn run(args: &CliArgs, cmd: &IndexesArgs) -> Result<()> {
     let allow_prompt = !matches!(format, OutputFormat::Json)
         && std::io::stdin().is_terminal()
         && std::io::stderr().is_terminal();
+    let wildcard = common::is_wildcard(table_raw);
 
     let requested_table_name = table_name.clone();
-    let indexes = tokio::runtime::Runtime::new()?.block_on(async {
-        let mut client = client::connect(&resolved.connection).await?;
-        let (schema, table_name) = object_lookup::resolve_schema_for_object(
-            &mut client,
-            &resolved,
-            &requested_table_name,
-            schema_hint,
-            object_lookup::LookupScope::TablesOnly,
-            "table",
-            allow_prompt,
-        )
-        .await?;
-        let sql = r#"
-SELECT
-    s.name AS schema_name,
-    i.name AS index_name,
-    i.type_desc AS index_type,
-    i.is_unique,
-    i.is_primary_key,
-    ic.is_included_column,
-    ic.key_ordinal,
-    c.name AS column_name,
-    usage_stats.user_seeks,
-    usage_stats.user_updates
-FROM sys.indexes i
-INNER JOIN sys.objects o ON i.object_id = o.object_id
-INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
-INNER JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
-INNER JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
-LEFT JOIN sys.dm_db_index_usage_stats usage_stats
-    ON usage_stats.database_id = DB_ID()
-   AND usage_stats.object_id = i.object_id
-   AND usage_stats.index_id = i.index_id
-WHERE o.type = 'U'
-  AND o.name = @P1
-  AND (@P2 IS NULL OR s.name = @P2)
-  AND i.name IS NOT NULL
-  AND i.is_hypothetical = 0
-ORDER BY i.name, ic.key_ordinal, ic.index_column_id;
-"#;
-
-        let mut query = Query::new(sql);
-        query.bind(table_name.as_str());
-        query.bind(Some(schema.as_str()));
-        let result_sets = executor::run_query(query, &mut client).await?;
-        let result_set = result_sets.into_iter().next().unwrap_or_default();
-
-        let mut grouped: BTreeMap<String, IndexInfo> = BTreeMap::new();
-        for row in result_set.rows {
-            let index_name = value_to_string(row.get(1));
-            let entry = grouped
-                .entry(index_name.clone())
-                .or_insert_with(|| IndexInfo {
-                    schema: value_to_string(row.first()),
-                    name: index_name.clone(),
-                    index_type: value_to_string(row.get(2)),
-                    is_unique: value_to_bool(row.get(3)),
-                    is_primary: value_to_bool(row.get(4)),
-                    key_columns: Vec::new(),
-                    included_columns: Vec::new(),
-                    user_seeks: value_to_i64(row.get(8)),
-                    user_updates: value_to_i64(row.get(9)),
-                });
-            let column_name = value_to_string(row.get(7));
-            let is_included = value_to_bool(row.get(5));
-            if is_included {
-                if !entry.included_columns.contains(&column_name) {
-                    entry.included_columns.push(column_name);
+    let (tables, matched, permission, warnings) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let targets = if wildcard {
+                object_lookup::expand_table_pattern(&mut client, &requested_table_name, schema_hint)
+                    .await?
+            } else {
+                vec![
+                    object_lookup::resolve_schema_for_object(
+                        &mut client,
+                        &resolved,
+                        &requested_table_name,
+                        schema_hint,
+                        object_lookup::LookupScope::TablesOnly,
+                        "table",
+                        allow_prompt,
+                    )
+                    .await?,
+                ]
+            };
+            let matched = targets.len();
+            let permission =
+                permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                    .await?;
+            let mut warnings = Vec::new();
+            if !permission.granted {
+                warnings.push(permission.warning("index usage (seeks/updates) is omitted"));
+            }
+            let mut tables = Vec::new();
+            for (schema, table_name) in targets.into_iter().take(cmd.max_objects) {
+                let mut indexes = fetch_indexes(
+                    &mut client,
+                    &resolved,
+                    &schema,
+                    &table_name,
+                    permission.granted,
+                )
+                .await?;
+                if indexes.iter().any(IndexInfo::is_columnstore) {
+                    match fetch_row_groups(&mut client, &schema, &table_name).await {
+                        Ok(mut row_groups) => {
+                            for index in &mut indexes {
+                                index.row_groups = row_groups.remove(&index.index_id);
+                            }
+                        }
+                        Err(err) => push_unique(
+                            &mut warnings,
+                            format!("Columnstore row group health could not be read: {}", err),
+                        ),
+                    }
+                }
+                if cmd.recommend_compression {
+                    for index in indexes.iter_mut().filter(|index| index.is_rowstore()) {
+                        match estimate_compression(&mut client, &schema, &table_name, index).await {
+                            Ok(estimate) => index.estimate = Some(estimate),
+                            Err(err) => push_unique(
+                                &mut warnings,
+                                format!("Compression savings could not be estimated: {}", err),
+                            ),
+                        }
+                    }
                 }
-            } else if !entry.key_columns.contains(&column_name) {
-                entry.key_columns.push(column_name);
+                tables.push((schema, table_name, indexes));
             }
-        }
-
-        Ok::<_, anyhow::Error>(grouped.into_values().collect::<Vec<_>>())
-    })?;
+            Ok::<_, anyhow::Error>((tables, matched, permission, warnings))
+        })?;
 
+    if wildcard {
+        return print_matches(
+            args,
+            cmd,
+            &resolved,
+            format,
+            &tables,
+            matched,
+            permission.granted,
+            &warnings,
+        );
+    }
+    let indexes = &tables[0].2;
     if indexes.is_empty() {
         return Err(anyhow!("No indexes found for table '{}'.", table_name));
     }
@@ -124,7 +222,9 @@ ORDER BY i.name, ic.key_ordinal, ic.index_column_id;
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
             "table": { "schema": indexes[0].schema, "name": table_name },
+            "usageAvailable": permission.granted,
             "indexes": indexes.iter().map(index_to_json).collect::<Vec<_>>(),
+            "warnings": warnings,
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
@@ -137,15 +237,311 @@ ORDER BY i.name, ic.key_ordinal, ic.index_column_id;
         return Ok(());
     }
 
-    let result_set = indexes_to_result_set(&indexes, cmd.show_usage);
+    print_indexes(indexes, cmd.show_usage && permission.granted, format);
+    print_warnings(cmd, permission.granted, &warnings);
+
+    Ok(())
+}
+
+/// Output for a `--table` pattern: one section per matching table, as `describe` lays out
+/// several matches.
+#[allow(clippy::too_many_arguments)]
+fn print_matches(
+    args: &CliArgs,
+    cmd: &IndexesArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    tables: &[(String, String, Vec<IndexInfo>)],
+    matched: usize,
+    usage_available: bool,
+    warnings: &[String],
+) -> Result<()> {
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "matched": matched,
+            "truncated": matched > tables.len(),
+            "usageAvailable": usage_available,
+            "matches": tables
+                .iter()
+                .map(|(schema, name, indexes)| json!({
+                    "table": { "schema": schema, "name": name },
+                    "indexes": indexes.iter().map(index_to_json).collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+            "warnings": warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    for (i, (schema, name, indexes)) in tables.iter().enumerate() {
+        if i > 0 {
+            println!("\n---\n");
+        }
+        println!("## {}.{}\n", schema, name);
+        if indexes.is_empty() {
+            println!("(no indexes)");
+            continue;
+        }
+        print_indexes(indexes, cmd.show_usage && usage_available, format);
+    }
+    if let Some(note) = common::max_objects_note(tables.len(), matched) {
+        println!("\n---\n{}", note);
+    }
+    print_warnings(cmd, usage_available, warnings);
+    Ok(())
+}
+
+fn print_indexes(indexes: &[IndexInfo], show_usage: bool, format: OutputFormat) {
+    let result_set = indexes_to_result_set(indexes, show_usage);
     let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
     println!("{}", result.output);
+    if indexes.iter().any(|index| index.estimate.is_some()) {
+        let result_set = estimates_to_result_set(indexes);
+        let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
+        println!("\nCompression estimates:\n{}", result.output);
+    }
+}
 
-    Ok(())
+/// The usage permission warning only matters to `--show-usage`; the rest always print.
+fn print_warnings(cmd: &IndexesArgs, usage_available: bool, warnings: &[String]) {
+    let skip_usage = !usage_available && !cmd.show_usage;
+    for warning in warnings.iter().skip(usize::from(skip_usage)) {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+fn push_unique(warnings: &mut Vec<String>, warning: String) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+async fn fetch_indexes(
+    client: &mut SqlClient,
+    resolved: &ResolvedConfig,
+    schema: &str,
+    table_name: &str,
+    usage_granted: bool,
+) -> Result<Vec<IndexInfo>> {
+    // Referencing dm_db_index_usage_stats without the grant fails the whole query.
+    let (usage_columns, usage_join) = if usage_granted {
+        (
+            "usage_stats.user_seeks, usage_stats.user_updates",
+            USAGE_JOIN,
+        )
+    } else {
+        (
+            "CAST(NULL AS bigint) AS user_seeks, CAST(NULL AS bigint) AS user_updates",
+            "",
+        )
+    };
+    let sql = format!(
+        "\
+SELECT
+s.name AS schema_name,
+i.name AS index_name,
+i.type_desc AS index_type,
+i.is_unique,
+i.is_primary_key,
+ic.is_included_column,
+ic.key_ordinal,
+c.name AS column_name,
+i.index_id,
+(SELECT STRING_AGG(d.data_compression_desc, ', ') WITHIN GROUP (ORDER BY d.data_compression_desc)
+   FROM (SELECT DISTINCT p.data_compression_desc
+           FROM sys.partitions p
+          WHERE p.object_id = i.object_id AND p.index_id = i.index_id) d) AS compression,
+{}
+FROM sys.indexes i
+INNER JOIN sys.objects o ON i.object_id = o.object_id
+INNER JOIN sys.schemas s ON o.schema_id = s.schema_id
+INNER JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+INNER JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+{}
+WHERE o.type = 'U'
+  AND o.name = @P1
+  AND (@P2 IS NULL OR s.name = @P2)
+  AND i.name IS NOT NULL
+  AND i.is_hypothetical = 0
+ORDER BY {};\
+",
+        usage_columns,
+        usage_join,
+        common::order_keys(
+            resolved,
+            "i.name, ic.key_ordinal, ic.index_column_id",
+            "i.index_id"
+        )
+    );
+
+    let mut query = Query::new(sql);
+    query.bind(table_name);
+    query.bind(Some(schema));
+    let result_sets = executor::run_query(query, client).await?;
+    let result_set = result_sets.into_iter().next().unwrap_or_default();
+
+    let mut grouped: BTreeMap<String, IndexInfo> = BTreeMap::new();
+    for row in result_set.rows {
+        let index_name = value_to_string(row.get(1));
+        let entry = grouped
+            .entry(index_name.clone())
+            .or_insert_with(|| IndexInfo {
+                schema: value_to_string(row.first()),
+                name: index_name.clone(),
+                index_type: value_to_string(row.get(2)),
+                is_unique: value_to_bool(row.get(3)),
+                is_primary: value_to_bool(row.get(4)),
+                key_columns: Vec::new(),
+                included_columns: Vec::new(),
+                user_seeks: value_to_i64(row.get(10)),
+                user_updates: value_to_i64(row.get(11)),
+                index_id: value_to_i64(row.get(8)).unwrap_or_default(),
+                compression: value_to_string(row.get(9)),
+                row_groups: None,
+                estimate: None,
+            });
+        let column_name = value_to_string(row.get(7));
+        let is_included = value_to_bool(row.get(5));
+        if is_included {
+            if !entry.included_columns.contains(&column_name) {
+                entry.included_columns.push(column_name);
+            }
+        } else if !entry.key_columns.contains(&column_name) {
+            entry.key_columns.push(column_name);
+        }
+    }
+
+    Ok(grouped.into_values().collect())
+}
+
+async fn fetch_row_groups(
+    client: &mut SqlClient,
+    schema: &str,
+    table_name: &str,
+) -> Result<BTreeMap<i64, RowGroups>> {
+    let mut query = Query::new(ROW_GROUPS_SQL);
+    query.bind(table_name);
+    query.bind(schema);
+    let result_sets = executor::run_query(query, client).await?;
+    let rows = result_sets.into_iter().next().unwrap_or_default().rows;
+    let count = |row: &[Value], i: usize| value_to_i64(row.get(i)).unwrap_or_default();
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                count(row, 0),
+                RowGroups {
+                    open: count(row, 1),
+                    closed: count(row, 2),
+                    compressed: count(row, 3),
+                    tombstone: count(row, 4),
+                    total_rows: count(row, 5),
+                    deleted_rows: count(row, 6),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Estimate ROW and PAGE sizes for one index; the procedure samples the table into tempdb.
+async fn estimate_compression(
+    client: &mut SqlClient,
+    schema: &str,
+    table_name: &str,
+    index: &IndexInfo,
+) -> Result<CompressionEstimate> {
+    let mut sizes = Vec::new();
+    for option in ["ROW", "PAGE"] {
+        let mut query = Query::new(ESTIMATE_SQL);
+        query.bind(schema);
+        query.bind(table_name);
+        query.bind(index.index_id as i32);
+        query.bind(option);
+        let result_sets = executor::run_query(query, client).await?;
+        let rows = result_sets.into_iter().next().unwrap_or_default().rows;
+        let sum =
+            |i: usize| -> i64 { rows.iter().filter_map(|row| value_to_i64(row.get(i))).sum() };
+        sizes.push((sum(4), sum(5)));
+    }
+    let current_kb = sizes[0].0;
+    let (row_kb, page_kb) = (sizes[0].1, sizes[1].1);
+    let (recommendation, savings_percent) =
+        recommend(&index.compression, current_kb, row_kb, page_kb);
+    Ok(CompressionEstimate {
+        current_kb,
+        row_kb,
+        page_kb,
+        recommendation,
+        savings_percent,
+    })
+}
+
+/// The smaller of ROW and PAGE when it saves at least [`MIN_SAVINGS_PERCENT`] over the current
+/// size and differs from the current setting; otherwise `keep`. Returns the saving in percent.
+fn recommend(current: &str, current_kb: i64, row_kb: i64, page_kb: i64) -> (String, f64) {
+    let (option, size_kb) = if page_kb < row_kb {
+        ("PAGE", page_kb)
+    } else {
+        ("ROW", row_kb)
+    };
+    if current_kb <= 0 {
+        return ("keep".to_string(), 0.0);
+    }
+    let savings = (current_kb - size_kb) as f64 * 100.0 / current_kb as f64;
+    if savings >= MIN_SAVINGS_PERCENT && !current.eq_ignore_ascii_case(option) {
+        (option.to_string(), savings)
+    } else {
+        ("keep".to_string(), savings.max(0.0))
+    }
+}
+
+fn text_column(name: &str) -> Column {
+    Column {
+        name: name.to_string(),
+        data_type: None,
+    }
+}
+
+fn estimates_to_result_set(indexes: &[IndexInfo]) -> ResultSet {
+    let columns = [
+        "name",
+        "compression",
+        "currentKB",
+        "rowKB",
+        "pageKB",
+        "recommendation",
+        "savings",
+    ]
+    .into_iter()
+    .map(text_column)
+    .collect();
+    let rows = indexes
+        .iter()
+        .filter_map(|idx| {
+            let estimate = idx.estimate.as_ref()?;
+            Some(vec![
+                Value::Text(idx.name.clone()),
+                Value::Text(idx.compression.clone()),
+                Value::Int(estimate.current_kb),
+                Value::Int(estimate.row_kb),
+                Value::Int(estimate.page_kb),
+                Value::Text(estimate.recommendation.clone()),
+                Value::Text(format!("{:.1}%", estimate.savings_percent)),
+            ])
+        })
+        .collect();
+    ResultSet { columns, rows }
 }
 
 fn indexes_to_result_set(indexes: &[IndexInfo], show_usage: bool) -> ResultSet {
-    let columns = vec![
+    let show_row_groups = indexes.iter().any(|idx| idx.row_groups.is_some());
+    let mut columns = vec![
         Column {
             name: "name".to_string(),
             data_type: None,
@@ -154,6 +550,10 @@ fn indexes_to_result_set(indexes: &[IndexInfo], show_usage: bool) -> ResultSet {
             name: "type".to_string(),
             data_type: None,
         },
+        Column {
+            name: "compression".to_string(),
+            data_type: None,
+        },
         Column {
             name: "unique".to_string(),
             data_type: None,
@@ -179,15 +579,19 @@ fn indexes_to_result_set(indexes: &[IndexInfo], show_usage: bool) -> ResultSet {
             data_type: None,
         },
     ];
+    if show_row_groups {
+        columns.push(text_column("rowGroups"));
+    }
 
     let rows = indexes
         .iter()
         .map(|idx| {
-            vec![
+            let mut row = vec![
                 Value::Text(idx.name.clone()),
                 Value::Text(idx.index_type.clone()),
-                Value::Text(if idx.is_unique { "yes" } else { "no" }.to_string()),
-                Value::Text(if idx.is_primary { "yes" } else { "no" }.to_string()),
+                Value::Text(idx.compression.clone()),
+                Value::Bool(idx.is_unique),
+                Value::Bool(idx.is_primary),
                 Value::Text(if idx.key_columns.is_empty() {
                     "-".to_string()
                 } else {
@@ -208,7 +612,16 @@ fn indexes_to_result_set(indexes: &[IndexInfo], show_usage: bool) -> ResultSet {
                 } else {
                     Value::Null
                 },
-            ]
+            ];
+            if show_row_groups {
+                row.push(
+                    idx.row_groups
+                        .as_ref()
+                        .map(|groups| Value::Text(groups.summary()))
+                        .unwrap_or(Value::Null),
+                );
+            }
+            row
         })
         .collect();
 
@@ -226,6 +639,23 @@ fn index_to_json(index: &IndexInfo) -> serde_json::Value {
         "includedColumns": index.included_columns,
         "userSeeks": index.user_seeks,
         "userUpdates": index.user_updates,
+        "compression": index.compression,
+        "rowGroups": index.row_groups.as_ref().map(|groups| json!({
+            "open": groups.open,
+            "closed": groups.closed,
+            "compressed": groups.compressed,
+            "tombstone": groups.tombstone,
+            "totalRows": groups.total_rows,
+            "deletedRows": groups.deleted_rows,
+            "deletedPercent": groups.deleted_percent(),
+        })),
+        "compressionEstimate": index.estimate.as_ref().map(|estimate| json!({
+            "currentKB": estimate.current_kb,
+            "rowKB": estimate.row_kb,
+            "pageKB": estimate.page_kb,
+            "recommendation": estimate.recommendation,
+            "savingsPercent": estimate.savings_percent,
+        })),
     })
 }
 
@@ -235,6 +665,7 @@ fn value_to_string(value: Option<&Value>) -> String {
         Some(Value::Int(v)) => v.to_string(),
         Some(Value::Bool(v)) => v.to_string(),
         Some(Value::Float(v)) => v.to_string(),
+        Some(Value::Decimal(v)) => v.to_string(),
         _ => "".to_string(),
     }
 }
@@ -256,3 +687,34 @@ fn value_to_i64(value: Option<&Value>) -> Option<i64> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommends_the_smaller_option_only_past_the_threshold() {
+        let (option, savings) = recommend("NONE", 1000, 700, 400);
+        assert_eq!(option, "PAGE");
+        assert!((savings - 60.0).abs() < f64::EPSILON);
+        assert_eq!(recommend("NONE", 1000, 900, 850).0, "keep");
+        assert_eq!(recommend("PAGE", 1000, 900, 500).0, "keep");
+        assert_eq!(recommend("NONE", 0, 0, 0), ("keep".to_string(), 0.0));
+    }
+
+    #[test]
+    fn row_group_summary_reports_deleted_share() {
+        let groups = RowGroups {
+            open: 1,
+            closed: 0,
+            compressed: 12,
+            tombstone: 0,
+            total_rows: 1000,
+            deleted_rows: 32,
+        };
+        assert_eq!(
+            groups.summary(),
+            "12 compressed, 1 open, 0 closed; 3.2% deleted"
+        );
+    }
+}
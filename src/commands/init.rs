@@ -73,11 +73,11 @@ defaultProfile: {profile}
 settings:
   output:
     # defaultFormat controls output when no explicit flag is used.
-    # Values: pretty | markdown | json
+    # Values: pretty | markdown | json | expanded
     defaultFormat: pretty
     json:
       # contractVersion allows JSON shape upgrades while keeping defaults stable.
-      # Values: v1
+      # Values: v1 | v2 (v2 wraps output in {{ meta, data, warnings, errors }})
       contractVersion: v1
       # pretty controls indentation when emitting JSON.
       pretty: true
@@ -1,15 +1,23 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Map, Value, json};
 
-use crate::cli::{CliArgs, IntegrationCommand, IntegrationInstallArgs, IntegrationsArgs};
+use crate::cli::{
+    CliArgs, IntegrationCommand, IntegrationInstallArgs, IntegrationVscodeArgs, IntegrationsArgs,
+};
+use crate::commands::{common, manifest};
+use crate::config::{self, ConnectionSettings, Profile};
 
 const DEFAULT_SKILL_NAME: &str = "sscli";
 const DEFAULT_GEMINI_NAME: &str = "sscli";
+/// Prefix on generated task labels and connection names; entries with it are replaced on rerun.
+const VSCODE_PREFIX: &str = "sscli: ";
+const SQLTOOLS_CONNECTIONS_KEY: &str = "sqltools.connections";
 
 const SKILL_TEMPLATE: &str = include_str!("../../assets/SKILL.md.template");
-const GEMINI_MD: &str = include_str!("../../assets/GEMINI.md");
+const GEMINI_TEMPLATE: &str = include_str!("../../assets/GEMINI.md");
 
 pub fn run(args: &CliArgs, cmd: &IntegrationsArgs) -> Result<()> {
     match &cmd.command {
@@ -21,6 +29,7 @@ pub fn run(args: &CliArgs, cmd: &IntegrationsArgs) -> Result<()> {
         }
         IntegrationCommand::Skills(opts) => install_skills(opts, args.quiet),
         IntegrationCommand::Gemini(opts) => install_gemini(opts, args.quiet),
+        IntegrationCommand::Vscode(opts) => install_vscode(args, opts),
     }
 }
 
@@ -29,6 +38,7 @@ fn print_help() {
     println!("Usage:");
     println!("  sscli integrations skills add [--global] [--name <skillName>]");
     println!("  sscli integrations gemini add [--global] [--name <extensionName>]");
+    println!("  sscli integrations vscode add [--force]");
 }
 
 fn install_skills(opts: &IntegrationInstallArgs, quiet: bool) -> Result<()> {
@@ -90,7 +100,7 @@ fn install_gemini(opts: &IntegrationInstallArgs, quiet: bool) -> Result<()> {
     let dest = base.join("extensions").join(name);
     fs::create_dir_all(&dest)?;
 
-    fs::write(dest.join("GEMINI.md"), GEMINI_MD)?;
+    fs::write(dest.join("GEMINI.md"), render_gemini_md())?;
     fs::write(dest.join("gemini-extension.json"), render_gemini_json(name))?;
 
     if !quiet {
@@ -109,8 +119,15 @@ fn write_skill(dest: &Path, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// The `{reference}` placeholder is filled last, so braces in JSON shapes are left alone.
 fn render_skill_template(name: &str) -> String {
-    SKILL_TEMPLATE.replace("{name}", name)
+    SKILL_TEMPLATE
+        .replace("{name}", name)
+        .replace("{reference}", &manifest::command_reference())
+}
+
+fn render_gemini_md() -> String {
+    GEMINI_TEMPLATE.replace("{reference}", &manifest::command_reference())
 }
 
 fn render_gemini_json(name: &str) -> String {
@@ -119,3 +136,204 @@ fn render_gemini_json(name: &str) -> String {
         "{{\n  \"name\": \"{name}\",\n  \"version\": \"{version}\",\n  \"contextFileName\": \"GEMINI.md\"\n}}\n"
     )
 }
+
+/// Write `.vscode/tasks.json` tasks and SQLTools connections for every profile in the config
+/// file. Earlier `sscli: ` entries are replaced; everything else in both files is kept.
+fn install_vscode(args: &CliArgs, opts: &IntegrationVscodeArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let config_path = resolved
+        .config_path
+        .as_ref()
+        .ok_or_else(|| anyhow!("No config file found; create one with `sscli init`"))?;
+    let mut profiles: Vec<(String, Profile)> = config::load_config_file(config_path)?
+        .profiles
+        .into_iter()
+        .collect();
+    if profiles.is_empty() {
+        return Err(anyhow!(
+            "No profiles configured. Add one with `sscli profiles add <name> --server <host>`."
+        ));
+    }
+    profiles.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let dir = std::env::current_dir()?.join(".vscode");
+    fs::create_dir_all(&dir)?;
+    let tasks_path = dir.join("tasks.json");
+    update_json_file(&tasks_path, opts.force, |root| {
+        root.entry("version").or_insert_with(|| json!("2.0.0"));
+        replace_prefixed(root, "tasks", "label", vscode_tasks(&profiles));
+    })?;
+    let settings_path = dir.join("settings.json");
+    update_json_file(&settings_path, opts.force, |root| {
+        let connections = profiles
+            .iter()
+            .map(|(name, profile)| sqltools_connection(name, profile))
+            .collect();
+        replace_prefixed(root, SQLTOOLS_CONNECTIONS_KEY, "name", connections);
+    })?;
+
+    if !args.quiet {
+        println!(
+            "Added VS Code integration for {} profile(s)",
+            profiles.len()
+        );
+        println!("- Tasks:    {}", tasks_path.display());
+        println!("- SQLTools: {}", settings_path.display());
+    }
+    Ok(())
+}
+
+fn vscode_tasks(profiles: &[(String, Profile)]) -> Vec<Value> {
+    let task = |label: String, args: Vec<&str>| {
+        json!({
+            "label": label,
+            "type": "shell",
+            "command": "sscli",
+            "args": args,
+            "problemMatcher": [],
+        })
+    };
+    profiles
+        .iter()
+        .flat_map(|(name, _)| {
+            [
+                task(
+                    format!("{}status ({})", VSCODE_PREFIX, name),
+                    vec!["--profile", name, "status"],
+                ),
+                task(
+                    format!("{}tables ({})", VSCODE_PREFIX, name),
+                    vec!["--profile", name, "tables"],
+                ),
+                task(
+                    format!("{}run current file ({})", VSCODE_PREFIX, name),
+                    vec!["--profile", name, "sql", "--file", "${file}"],
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// A SQLTools MSSQL connection. The password is never copied: `passwordEnv` becomes an
+/// `${env:VAR}` reference, and anything else makes SQLTools prompt for it.
+fn sqltools_connection(name: &str, profile: &Profile) -> Value {
+    let defaults = ConnectionSettings::default();
+    let mut connection = json!({
+        "name": format!("{}{}", VSCODE_PREFIX, name),
+        "driver": "MSSQL",
+        "server": profile.server.as_deref().unwrap_or(&defaults.server),
+        "port": profile.port.unwrap_or(defaults.port),
+        "database": profile.database.as_deref().unwrap_or(&defaults.database),
+        "mssqlOptions": {
+            "encrypt": profile.encrypt.unwrap_or(defaults.encrypt),
+            "trustServerCertificate": profile.trust_cert.unwrap_or(defaults.trust_cert),
+        },
+    });
+    if let Some(user) = &profile.user {
+        connection["username"] = json!(user);
+    }
+    match &profile.password_env {
+        Some(var) => connection["password"] = json!(format!("${{env:{}}}", var)),
+        None => connection["askForPassword"] = json!(true),
+    }
+    connection
+}
+
+/// Replace the entries of array `key` in `root` whose `name_field` starts with
+/// [`VSCODE_PREFIX`] with `entries`, keeping the others in place.
+fn replace_prefixed(
+    root: &mut Map<String, Value>,
+    key: &str,
+    name_field: &str,
+    entries: Vec<Value>,
+) {
+    let mut kept = match root.remove(key) {
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .filter(|item| {
+                !item
+                    .get(name_field)
+                    .and_then(Value::as_str)
+                    .is_some_and(|name| name.starts_with(VSCODE_PREFIX))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    kept.extend(entries);
+    root.insert(key.to_string(), Value::Array(kept));
+}
+
+/// Read `path` as a JSON object (or start empty), apply `update`, and write it back. VS Code
+/// allows comments in these files; those cannot be kept, so they need `--force`.
+fn update_json_file(
+    path: &Path,
+    force: bool,
+    update: impl FnOnce(&mut Map<String, Value>),
+) -> Result<()> {
+    let mut root = if path.exists() {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(Value::Object(map)) => map,
+            _ if force => Map::new(),
+            _ => {
+                return Err(anyhow!(
+                    "{} is not plain JSON (comments?); edit it by hand or rerun with --force to replace it",
+                    path.display()
+                ));
+            }
+        }
+    } else {
+        Map::new()
+    };
+    update(&mut root);
+    let body = serde_json::to_string_pretty(&Value::Object(root))?;
+    fs::write(path, format!("{}\n", body))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqltools_connections_reference_passwords_and_replace_earlier_entries() {
+        let profile = Profile {
+            server: Some("db.example.com".to_string()),
+            user: Some("app".to_string()),
+            password_env: Some("APP_DB_PASSWORD".to_string()),
+            password: Some("hunter2".to_string()),
+            ..Profile::default()
+        };
+        let connection = sqltools_connection("prod", &profile);
+        assert_eq!(connection["name"], "sscli: prod");
+        assert_eq!(connection["port"], 1433);
+        assert_eq!(connection["database"], "master");
+        assert_eq!(connection["password"], "${env:APP_DB_PASSWORD}");
+        assert!(!connection.to_string().contains("hunter2"));
+
+        let local = sqltools_connection("local", &Profile::default());
+        assert_eq!(local["askForPassword"], true);
+        assert!(local.get("password").is_none());
+
+        let mut root = Map::new();
+        root.insert(
+            SQLTOOLS_CONNECTIONS_KEY.to_string(),
+            json!([{ "name": "mine" }, { "name": "sscli: old" }]),
+        );
+        replace_prefixed(
+            &mut root,
+            SQLTOOLS_CONNECTIONS_KEY,
+            "name",
+            vec![connection],
+        );
+        let names: Vec<_> = root[SQLTOOLS_CONNECTIONS_KEY]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["mine", "sscli: prod"]);
+    }
+}
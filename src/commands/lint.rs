@@ -0,0 +1,1119 @@
+//! `lint`: flag T-SQL anti-patterns in scripts or in module definitions from the database.
+//!
+//! Rules run over the formatter's token stream, so string literals, comments, and bracketed
+//! names never trigger them. The implicit-conversion rule needs column types and only runs
+//! when connected (`--object`, or `--check-types` for scripts).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tiberius::Query;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::cli::{CliArgs, LintArgs};
+use crate::commands::sql_format::{self, Token};
+use crate::commands::{common, sql};
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::{TableOptions, json as json_out, table};
+
+/// Functions that hide a column from index seeks when they wrap it in a predicate.
+const NON_SARGABLE_FUNCTIONS: &[&str] = &[
+    "ABS",
+    "CAST",
+    "CEILING",
+    "COALESCE",
+    "CONVERT",
+    "DATEADD",
+    "DATEDIFF",
+    "DATENAME",
+    "DATEPART",
+    "DATETRUNC",
+    "DAY",
+    "EOMONTH",
+    "FLOOR",
+    "FORMAT",
+    "ISNULL",
+    "LEFT",
+    "LEN",
+    "LOWER",
+    "LTRIM",
+    "MONTH",
+    "REPLACE",
+    "RIGHT",
+    "ROUND",
+    "RTRIM",
+    "SUBSTRING",
+    "TRIM",
+    "TRY_CAST",
+    "TRY_CONVERT",
+    "UPPER",
+    "YEAR",
+];
+
+/// Functions whose first argument is a date part or type name rather than a value.
+const LEADING_NON_VALUE: &[&str] = &[
+    "CONVERT",
+    "DATEADD",
+    "DATEDIFF",
+    "DATENAME",
+    "DATEPART",
+    "DATETRUNC",
+    "TRY_CONVERT",
+];
+
+/// Cursor options that may follow `CURSOR` in a declaration.
+const CURSOR_OPTIONS: &[&str] = &[
+    "DYNAMIC",
+    "FAST_FORWARD",
+    "FOR",
+    "FORWARD_ONLY",
+    "GLOBAL",
+    "KEYSET",
+    "LOCAL",
+    "OPTIMISTIC",
+    "READ_ONLY",
+    "SCROLL",
+    "SCROLL_LOCKS",
+    "STATIC",
+    "TYPE_WARNING",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    fn parse(value: &str) -> Severity {
+        match value {
+            "high" => Severity::High,
+            "medium" => Severity::Medium,
+            _ => Severity::Low,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    source: String,
+    line: usize,
+    severity: Severity,
+    rule: &'static str,
+    message: String,
+    /// The offending source line, trimmed.
+    text: String,
+}
+
+struct Source {
+    name: String,
+    sql: String,
+}
+
+/// Column types by normalized table name (`dbo.orders`), then lowercase column name.
+type ColumnTypes = HashMap<String, HashMap<String, String>>;
+
+pub fn run(args: &CliArgs, cmd: &LintArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let fail_on = Severity::parse(&cmd.fail_on);
+
+    let connect = cmd.object.is_some() || cmd.check_types;
+    let local_sources = match &cmd.object {
+        Some(_) => Vec::new(),
+        None => read_sources(cmd)?,
+    };
+    let (sources, types) = if connect {
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let sources = match &cmd.object {
+                Some(pattern) => fetch_modules(&mut client, pattern).await?,
+                None => local_sources,
+            };
+            let mut tables = Vec::new();
+            for source in &sources {
+                for table in TableScope::from_tokens(&significant_tokens(&source.sql)).names {
+                    if !tables.contains(&table) {
+                        tables.push(table);
+                    }
+                }
+            }
+            let types = fetch_column_types(&mut client, &tables).await?;
+            Ok::<_, anyhow::Error>((sources, types))
+        })?
+    } else {
+        (local_sources, ColumnTypes::new())
+    };
+
+    let mut findings: Vec<Finding> = sources
+        .iter()
+        .flat_map(|source| lint_sql(&source.name, &source.sql, &types))
+        .filter(|f| !cmd.disable.iter().any(|rule| rule == f.rule))
+        .collect();
+    findings.sort_by_key(|f| {
+        (
+            sources.iter().position(|s| s.name == f.source),
+            f.line,
+            f.severity,
+        )
+    });
+    let count = |severity: Severity| findings.iter().filter(|f| f.severity == severity).count();
+    let failed = findings.iter().any(|f| f.severity <= fail_on);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "sources": sources.iter().map(|s| &s.name).collect::<Vec<_>>(),
+            "failOn": fail_on.as_str(),
+            "typesChecked": connect,
+            "counts": {
+                "high": count(Severity::High),
+                "medium": count(Severity::Medium),
+                "low": count(Severity::Low),
+            },
+            "findings": findings
+                .iter()
+                .map(|f| json!({
+                    "source": f.source,
+                    "line": f.line,
+                    "severity": f.severity.as_str(),
+                    "rule": f.rule,
+                    "message": f.message,
+                    "text": f.text,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if findings.is_empty() {
+            println!("No lint findings.");
+        } else {
+            let result = table::render_result_set_table(
+                &findings_to_result_set(&findings),
+                format,
+                &TableOptions::default(),
+            );
+            println!("{}", result.output);
+            println!(
+                "\n{} finding{}: {} high, {} medium, {} low",
+                findings.len(),
+                if findings.len() == 1 { "" } else { "s" },
+                count(Severity::High),
+                count(Severity::Medium),
+                count(Severity::Low)
+            );
+        }
+    }
+
+    if failed {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+fn read_sources(cmd: &LintArgs) -> Result<Vec<Source>> {
+    if cmd.files.is_empty() {
+        let sql = sql::read_sql_input(cmd.sql.as_deref(), None, cmd.stdin)?;
+        let name = if cmd.sql.is_some() { "input" } else { "stdin" };
+        return Ok(vec![Source {
+            name: name.to_string(),
+            sql,
+        }]);
+    }
+    cmd.files
+        .iter()
+        .map(|path| {
+            let sql = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok(Source {
+                name: path.display().to_string(),
+                sql,
+            })
+        })
+        .collect()
+}
+
+async fn fetch_modules(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    pattern: &str,
+) -> Result<Vec<Source>> {
+    let (name, schema) = common::normalize_object_input(pattern);
+    let sql = r#"
+SELECT OBJECT_SCHEMA_NAME(m.object_id) AS [schema], o.name, m.definition
+FROM sys.sql_modules m
+INNER JOIN sys.objects o ON o.object_id = m.object_id
+WHERE o.is_ms_shipped = 0
+  AND o.type IN ('P', 'V', 'FN', 'IF', 'TF', 'TR')
+  AND OBJECT_SCHEMA_NAME(m.object_id) LIKE @P1 ESCAPE '\'
+  AND o.name LIKE @P2 ESCAPE '\'
+ORDER BY 1, 2;
+"#;
+    let mut query = Query::new(sql);
+    query.bind(
+        schema
+            .as_deref()
+            .map(common::wildcard_to_like)
+            .unwrap_or_else(|| "%".to_string()),
+    );
+    query.bind(common::wildcard_to_like(&name));
+    let sets = executor::run_query(query, client).await?;
+    let rows = sets.into_iter().next().unwrap_or_default().rows;
+    if rows.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!(
+                "No procedure, view, function, or trigger matches '{}'",
+                pattern
+            ),
+        )
+        .into());
+    }
+    Ok(rows
+        .iter()
+        .map(|row| Source {
+            name: format!(
+                "{}.{}",
+                common::value_text(row.first()),
+                common::value_text(row.get(1))
+            ),
+            sql: common::value_text(row.get(2)),
+        })
+        .collect())
+}
+
+/// Column types of the tables a script names, keyed as `referenced_tables` normalizes them.
+/// Names that do not resolve (CTEs, aliases, other databases) simply have no entry.
+async fn fetch_column_types(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    tables: &[String],
+) -> Result<ColumnTypes> {
+    let mut types = ColumnTypes::new();
+    // Stay well under the 2100-parameter limit of a single request.
+    for chunk in tables.chunks(1000) {
+        let values = (1..=chunk.len())
+            .map(|i| format!("(@P{})", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            r#"
+SELECT v.name, c.name, TYPE_NAME(c.system_type_id)
+FROM (VALUES {values}) AS v(name)
+INNER JOIN sys.columns c ON c.object_id = OBJECT_ID(v.name);
+"#
+        );
+        let mut query = Query::new(sql);
+        for table in chunk {
+            query.bind(table.clone());
+        }
+        let sets = executor::run_query(query, client).await?;
+        for row in sets.into_iter().next().unwrap_or_default().rows {
+            types
+                .entry(normalize_name(&common::value_text(row.first())))
+                .or_default()
+                .insert(
+                    common::value_text(row.get(1)).to_ascii_lowercase(),
+                    common::value_text(row.get(2)).to_ascii_lowercase(),
+                );
+        }
+    }
+    Ok(types)
+}
+
+#[derive(Debug, Clone)]
+struct Tok {
+    line: usize,
+    token: Token,
+}
+
+impl Tok {
+    fn is(&self, keyword: &str) -> bool {
+        matches!(&self.token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn punct(&self, punct: &str) -> bool {
+        matches!(&self.token, Token::Punct(p) if p == punct)
+    }
+
+    fn upper(&self) -> Option<String> {
+        match &self.token {
+            Token::Word(word) => Some(word.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+
+    /// The name this token spells when it is an identifier: a non-keyword word or a quoted
+    /// name, without brackets. Variables and temp tables are not identifiers here.
+    fn identifier(&self) -> Option<String> {
+        match &self.token {
+            Token::Word(word) if !word.starts_with(['@', '#']) && !sql_format::is_keyword(word) => {
+                Some(word.clone())
+            }
+            Token::Quoted(quoted) => Some(unquote(quoted)),
+            _ => None,
+        }
+    }
+
+    fn is_comparison(&self) -> bool {
+        matches!(&self.token, Token::Punct(p) if matches!(p.as_str(), "=" | "<" | ">" | "<=" | ">=" | "<>" | "!=" | "!<" | "!>"))
+    }
+}
+
+/// Non-comment tokens with their 1-based line numbers.
+fn significant_tokens(sql: &str) -> Vec<Tok> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(
+            sql.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == '\n')
+                .map(|(idx, _)| idx + 1),
+        )
+        .collect();
+    sql_format::tokenize_spans(sql)
+        .into_iter()
+        .filter(|(_, token)| !matches!(token, Token::Comment { .. }))
+        .map(|(start, token)| Tok {
+            line: line_starts.partition_point(|line_start| *line_start <= start),
+            token,
+        })
+        .collect()
+}
+
+fn lint_sql(source: &str, sql: &str, types: &ColumnTypes) -> Vec<Finding> {
+    let toks = significant_tokens(sql);
+    let lines: Vec<&str> = sql.lines().collect();
+    let mut issues = Vec::new();
+    select_star(&toks, &mut issues);
+    nolock(&toks, &mut issues);
+    missing_nocount(&toks, &mut issues);
+    cursors(&toks, &mut issues);
+    predicates(&toks, types, &mut issues);
+    issues.sort_by_key(|(line, ..)| *line);
+    issues
+        .into_iter()
+        .map(|(line, severity, rule, message)| Finding {
+            source: source.to_string(),
+            line,
+            severity,
+            rule,
+            message,
+            text: lines
+                .get(line.saturating_sub(1))
+                .map(|text| text.trim().to_string())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+type Issue = (usize, Severity, &'static str, String);
+
+fn select_star(toks: &[Tok], issues: &mut Vec<Issue>) {
+    for (idx, tok) in toks.iter().enumerate() {
+        if !tok.punct("*") {
+            continue;
+        }
+        // `alias.*` starts where the qualifier does.
+        let mut prev = idx.checked_sub(1);
+        if let Some(dot) = prev
+            && toks[dot].punct(".")
+        {
+            prev = dot.checked_sub(2);
+        }
+        let Some(select) = owning_select(toks, idx) else {
+            continue;
+        };
+        let starts_item = match prev {
+            Some(p) => {
+                let p_tok = &toks[p];
+                p_tok.is("SELECT")
+                    || p_tok.is("DISTINCT")
+                    || p_tok.is("ALL")
+                    || p_tok.punct(",")
+                    // `SELECT TOP (10) *`, `TOP 5 PERCENT *`, `TOP 1 WITH TIES *`
+                    || ((matches!(p_tok.token, Token::Number(_))
+                        || p_tok.punct(")")
+                        || p_tok.is("PERCENT")
+                        || p_tok.is("TIES"))
+                        && toks[select..idx].iter().any(|t| t.is("TOP")))
+            }
+            None => false,
+        };
+        // `EXISTS (SELECT * ...)` never materializes the columns.
+        let in_exists = select >= 2 && toks[select - 1].punct("(") && toks[select - 2].is("EXISTS");
+        if starts_item && !in_exists {
+            issues.push((
+                tok.line,
+                Severity::Medium,
+                "select-star",
+                "SELECT * returns every column, including ones added later; list the columns needed"
+                    .to_string(),
+            ));
+        }
+    }
+}
+
+/// The `SELECT` whose list contains the token at `idx`, if any, at the same paren depth.
+fn owning_select(toks: &[Tok], idx: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for j in (0..idx).rev() {
+        let tok = &toks[j];
+        if tok.punct(")") {
+            depth += 1;
+        } else if tok.punct("(") {
+            if depth == 0 {
+                return None;
+            }
+            depth -= 1;
+        } else if depth == 0 && tok.is("SELECT") {
+            return Some(j);
+        } else if depth == 0 && (tok.is("FROM") || tok.punct(";")) {
+            return None;
+        }
+    }
+    None
+}
+
+fn nolock(toks: &[Tok], issues: &mut Vec<Issue>) {
+    for (idx, tok) in toks.iter().enumerate() {
+        if tok.is("NOLOCK") || tok.is("READUNCOMMITTED") {
+            issues.push((
+                tok.line,
+                Severity::High,
+                "nolock",
+                format!(
+                    "{} reads uncommitted data and can return missing or duplicated rows",
+                    tok.upper().unwrap_or_default()
+                ),
+            ));
+        } else if tok.is("READ") && toks.get(idx + 1).is_some_and(|next| next.is("UNCOMMITTED")) {
+            issues.push((
+                tok.line,
+                Severity::High,
+                "nolock",
+                "READ UNCOMMITTED isolation can return missing or duplicated rows".to_string(),
+            ));
+        }
+    }
+}
+
+fn missing_nocount(toks: &[Tok], issues: &mut Vec<Issue>) {
+    let headers: Vec<(usize, usize)> = (0..toks.len())
+        .filter_map(|idx| procedure_header(toks, idx).map(|name_at| (idx, name_at)))
+        .collect();
+    for (pos, (start, name_at)) in headers.iter().enumerate() {
+        let next_header = headers.get(pos + 1).map_or(toks.len(), |(next, _)| *next);
+        let end = toks[*name_at..next_header]
+            .iter()
+            .position(|tok| tok.is("GO"))
+            .map_or(next_header, |offset| name_at + offset);
+        let body = &toks[*name_at..end];
+        let sets_nocount = body
+            .windows(3)
+            .any(|w| w[0].is("SET") && w[1].is("NOCOUNT") && w[2].is("ON"));
+        if !sets_nocount {
+            issues.push((
+                toks[*start].line,
+                Severity::Low,
+                "missing-nocount",
+                format!(
+                    "procedure {} does not SET NOCOUNT ON; row-count messages add chatter for every statement",
+                    dotted_name(toks, *name_at)
+                ),
+            ));
+        }
+    }
+}
+
+/// For `CREATE [OR ALTER] PROC[EDURE]` or `ALTER PROC[EDURE]` at `idx`, the index of the
+/// procedure name.
+fn procedure_header(toks: &[Tok], idx: usize) -> Option<usize> {
+    let mut at = idx;
+    if toks[at].is("CREATE") {
+        if toks.get(at + 1).is_some_and(|t| t.is("OR")) && toks.get(at + 2)?.is("ALTER") {
+            at += 2;
+        }
+    } else if !toks[at].is("ALTER") || at > 0 && toks[at - 1].is("OR") {
+        return None;
+    }
+    let kind = toks.get(at + 1)?;
+    (kind.is("PROC") || kind.is("PROCEDURE")).then_some(at + 2)
+}
+
+fn dotted_name(toks: &[Tok], start: usize) -> String {
+    let mut parts = Vec::new();
+    let mut idx = start;
+    while let Some(tok) = toks.get(idx) {
+        match &tok.token {
+            Token::Word(word) => parts.push(word.clone()),
+            Token::Quoted(quoted) => parts.push(unquote(quoted)),
+            _ => break,
+        }
+        if !toks.get(idx + 1).is_some_and(|next| next.punct(".")) {
+            break;
+        }
+        idx += 2;
+    }
+    parts.join(".")
+}
+
+fn cursors(toks: &[Tok], issues: &mut Vec<Issue>) {
+    for (idx, tok) in toks.iter().enumerate() {
+        if !tok.is("CURSOR") {
+            continue;
+        }
+        let declared = idx >= 2 && toks[idx - 2].is("DECLARE")
+            || idx >= 1 && matches!(&toks[idx - 1].token, Token::Word(w) if w.starts_with('@'))
+            || toks
+                .get(idx + 1)
+                .and_then(Tok::upper)
+                .is_some_and(|next| CURSOR_OPTIONS.contains(&next.as_str()));
+        if declared {
+            issues.push((
+                tok.line,
+                Severity::Medium,
+                "cursor",
+                "cursor processes rows one at a time; prefer a set-based statement".to_string(),
+            ));
+        }
+    }
+}
+
+/// Non-SARGable predicates and implicit conversions: both only matter in `WHERE`, `ON`, and
+/// `HAVING`, so a stack of "inside a predicate" flags follows the paren depth.
+fn predicates(toks: &[Tok], types: &ColumnTypes, issues: &mut Vec<Issue>) {
+    let scope = TableScope::from_tokens(toks);
+    let variables = variable_types(toks);
+    let mut stack = vec![false];
+    for (idx, tok) in toks.iter().enumerate() {
+        if tok.punct("(") {
+            let inherited = *stack.last().unwrap_or(&false);
+            stack.push(inherited);
+            continue;
+        }
+        if tok.punct(")") {
+            if stack.len() > 1 {
+                stack.pop();
+            }
+            continue;
+        }
+        if let Some(upper) = tok.upper() {
+            match upper.as_str() {
+                "WHERE" | "ON" | "HAVING" => {
+                    if let Some(top) = stack.last_mut() {
+                        *top = true;
+                    }
+                }
+                "SELECT" | "FROM" | "JOIN" | "GROUP" | "ORDER" | "SET" | "INSERT" | "UPDATE"
+                | "DELETE" | "VALUES" | "INTO" | "DECLARE" | "RETURN" | "OUTPUT" | "UNION"
+                | "EXCEPT" | "INTERSECT" => {
+                    if let Some(top) = stack.last_mut() {
+                        *top = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !stack.last().copied().unwrap_or(false) {
+            continue;
+        }
+
+        if let Some(function) = tok.upper()
+            && NON_SARGABLE_FUNCTIONS.contains(&function.as_str())
+            && toks.get(idx + 1).is_some_and(|next| next.punct("("))
+        {
+            let close = matching_paren(toks, idx + 1);
+            let compared = toks.get(close + 1).is_some_and(is_predicate_operator)
+                || idx
+                    .checked_sub(1)
+                    .is_some_and(|prev| is_predicate_operator(&toks[prev]));
+            if compared && wraps_column(toks, &function, idx + 1, close) {
+                issues.push((
+                    tok.line,
+                    Severity::Medium,
+                    "non-sargable",
+                    format!(
+                        "{}() around a column in a predicate prevents index seeks; compare the bare column instead",
+                        function
+                    ),
+                ));
+            }
+        }
+
+        if tok.is("LIKE")
+            && let Some(Token::Str(pattern)) = toks.get(idx + 1).map(|next| &next.token)
+            && pattern.trim_start_matches(['N', 'n']).starts_with("'%")
+        {
+            issues.push((
+                tok.line,
+                Severity::Low,
+                "non-sargable",
+                "LIKE with a leading wildcard scans every row instead of seeking".to_string(),
+            ));
+        }
+
+        if !types.is_empty()
+            && (tok.is_comparison() || tok.is("LIKE") || tok.is("IN"))
+            && let Some(issue) = implicit_conversion(toks, idx, &scope, types, &variables)
+        {
+            issues.push(issue);
+        }
+    }
+}
+
+fn is_predicate_operator(tok: &Tok) -> bool {
+    tok.is_comparison()
+        || ["LIKE", "IN", "BETWEEN", "NOT", "IS"]
+            .iter()
+            .any(|keyword| tok.is(keyword))
+}
+
+/// Index of the `)` closing the `(` at `open`, or the last token when unbalanced.
+fn matching_paren(toks: &[Tok], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (idx, tok) in toks.iter().enumerate().skip(open) {
+        if tok.punct("(") {
+            depth += 1;
+        } else if tok.punct(")") {
+            depth -= 1;
+            if depth == 0 {
+                return idx;
+            }
+        }
+    }
+    toks.len().saturating_sub(1)
+}
+
+/// Whether the value arguments of `function` between `open` and `close` reference a column.
+fn wraps_column(toks: &[Tok], function: &str, open: usize, close: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_value = !LEADING_NON_VALUE.contains(&function);
+    for idx in open + 1..close {
+        let tok = &toks[idx];
+        if tok.punct("(") {
+            depth += 1;
+            continue;
+        }
+        if tok.punct(")") {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        if depth == 0 && tok.punct(",") {
+            in_value = true;
+            continue;
+        }
+        // `CAST(col AS date)`: the type after AS is not a value.
+        if depth == 0 && tok.is("AS") {
+            in_value = false;
+            continue;
+        }
+        let is_call = toks.get(idx + 1).is_some_and(|next| next.punct("("));
+        let is_qualifier = toks.get(idx + 1).is_some_and(|next| next.punct("."));
+        if in_value && !is_call && !is_qualifier && tok.identifier().is_some() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Flags `column <op> value` (or `value <op> column`) where the value's type forces SQL Server
+/// to convert the column: varchar columns against nvarchar values, and character columns
+/// against numbers.
+fn implicit_conversion(
+    toks: &[Tok],
+    idx: usize,
+    scope: &TableScope,
+    types: &ColumnTypes,
+    variables: &HashMap<String, String>,
+) -> Option<Issue> {
+    let op = &toks[idx];
+    let right = if op.is("IN") && toks.get(idx + 1).is_some_and(|t| t.punct("(")) {
+        idx + 2
+    } else {
+        idx + 1
+    };
+    let (column, column_type, value) = match column_before(toks, idx) {
+        Some((qualifier, column)) => {
+            let column_type = scope.column_type(types, qualifier.as_deref(), &column)?;
+            (column, column_type, toks.get(right)?)
+        }
+        None => {
+            let (qualifier, column) = column_after(toks, idx)?;
+            let column_type = scope.column_type(types, qualifier.as_deref(), &column)?;
+            (column, column_type, toks.get(idx.checked_sub(1)?)?)
+        }
+    };
+    let value_family = match &value.token {
+        Token::Str(text) if text.starts_with(['N', 'n']) => Family::Unicode,
+        Token::Str(_) => Family::Ansi,
+        Token::Number(_) => Family::Numeric,
+        Token::Word(word) if word.starts_with('@') => {
+            family(variables.get(&word.to_ascii_lowercase())?)
+        }
+        _ => return None,
+    };
+    let describe = match &value.token {
+        Token::Word(word) => format!("{} ({})", word, variables[&word.to_ascii_lowercase()]),
+        Token::Number(_) => "a number".to_string(),
+        _ => "an N'...' literal".to_string(),
+    };
+    match (family(&column_type), value_family) {
+        (Family::Ansi, Family::Unicode) => Some((
+            op.line,
+            Severity::Medium,
+            "implicit-conversion",
+            format!(
+                "{} is {} but is compared to {}; the column is converted to nvarchar, which prevents index seeks",
+                column, column_type, describe
+            ),
+        )),
+        (Family::Ansi | Family::Unicode, Family::Numeric) => Some((
+            op.line,
+            Severity::High,
+            "implicit-conversion",
+            format!(
+                "{} is {} but is compared to {}; every row is converted to a number and non-numeric values fail",
+                column, column_type, describe
+            ),
+        )),
+        _ => None,
+    }
+}
+
+/// `[qualifier.]column` ending just before `idx`.
+fn column_before(toks: &[Tok], idx: usize) -> Option<(Option<String>, String)> {
+    let column = toks.get(idx.checked_sub(1)?)?.identifier()?;
+    let qualifier = match idx.checked_sub(3) {
+        Some(q) if toks[q + 1].punct(".") => toks[q].identifier(),
+        _ => None,
+    };
+    Some((qualifier, column))
+}
+
+/// `[qualifier.]column` starting just after `idx`.
+fn column_after(toks: &[Tok], idx: usize) -> Option<(Option<String>, String)> {
+    let first = toks.get(idx + 1)?.identifier()?;
+    if toks.get(idx + 2).is_some_and(|t| t.punct(".")) {
+        let column = toks.get(idx + 3)?.identifier()?;
+        return Some((Some(first), column));
+    }
+    Some((None, first))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    Ansi,
+    Unicode,
+    Numeric,
+    Other,
+}
+
+fn family(type_name: &str) -> Family {
+    match type_name {
+        "char" | "varchar" | "text" => Family::Ansi,
+        "nchar" | "nvarchar" | "ntext" | "sysname" => Family::Unicode,
+        "bigint" | "int" | "smallint" | "tinyint" | "bit" | "decimal" | "numeric" | "float"
+        | "real" | "money" | "smallmoney" => Family::Numeric,
+        _ => Family::Other,
+    }
+}
+
+/// Declared types of `@variables` and parameters: `@name [AS] type` anywhere in the script.
+fn variable_types(toks: &[Tok]) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    for (idx, tok) in toks.iter().enumerate() {
+        let Token::Word(name) = &tok.token else {
+            continue;
+        };
+        if !name.starts_with('@') || name.starts_with("@@") {
+            continue;
+        }
+        let mut at = idx + 1;
+        if toks.get(at).is_some_and(|t| t.is("AS")) {
+            at += 1;
+        }
+        if let Some(Token::Word(type_name)) = toks.get(at).map(|t| &t.token) {
+            let type_name = type_name.to_ascii_lowercase();
+            if family(&type_name) != Family::Other {
+                variables.insert(name.to_ascii_lowercase(), type_name);
+            }
+        }
+    }
+    variables
+}
+
+/// Tables a script reads or writes, and the aliases that point at them.
+#[derive(Debug, Default)]
+struct TableScope {
+    /// Normalized table names, in order of first mention.
+    tables: Vec<String>,
+    /// The same tables as written in the script, for `OBJECT_ID`.
+    names: Vec<String>,
+    /// Lowercase alias or bare table name -> normalized table names.
+    aliases: HashMap<String, HashSet<String>>,
+}
+
+impl TableScope {
+    fn from_tokens(toks: &[Tok]) -> TableScope {
+        let mut scope = TableScope::default();
+        for (idx, tok) in toks.iter().enumerate() {
+            let lists = tok.is("FROM");
+            if !(lists
+                || tok.is("JOIN")
+                || tok.is("UPDATE")
+                || tok.is("INTO")
+                || tok.is("MERGE")
+                || tok.is("USING"))
+            {
+                continue;
+            }
+            let mut at = idx + 1;
+            while let Some((name, after)) = table_name(toks, at) {
+                let key = normalize_name(&name);
+                let bare = key.rsplit('.').next().unwrap_or(&key).to_string();
+                if !scope.tables.contains(&key) {
+                    scope.tables.push(key.clone());
+                    scope.names.push(name);
+                }
+                scope.aliases.entry(bare).or_default().insert(key.clone());
+                let mut next = after;
+                if toks.get(next).is_some_and(|t| t.is("AS")) {
+                    next += 1;
+                }
+                if let Some(alias) = toks.get(next).and_then(Tok::identifier)
+                    && !toks.get(next + 1).is_some_and(|t| t.punct("("))
+                {
+                    scope
+                        .aliases
+                        .entry(alias.to_ascii_lowercase())
+                        .or_default()
+                        .insert(key);
+                    next += 1;
+                }
+                // `FROM a, b` lists more tables.
+                if lists && toks.get(next).is_some_and(|t| t.punct(",")) {
+                    at = next + 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        scope
+    }
+
+    /// The column's type when every table the qualifier (or, unqualified, the whole script)
+    /// could mean agrees on it.
+    fn column_type(
+        &self,
+        types: &ColumnTypes,
+        qualifier: Option<&str>,
+        column: &str,
+    ) -> Option<String> {
+        let candidates: Vec<&String> = match qualifier {
+            Some(q) => self.aliases.get(&q.to_ascii_lowercase())?.iter().collect(),
+            None => self.tables.iter().collect(),
+        };
+        let found: HashSet<&String> = candidates
+            .into_iter()
+            .filter_map(|table| types.get(table)?.get(&column.to_ascii_lowercase()))
+            .collect();
+        if found.len() == 1 {
+            found.into_iter().next().cloned()
+        } else {
+            None
+        }
+    }
+}
+
+/// A dotted table name starting at `idx`, as written, and the index after it. Derived tables,
+/// table variables, and temp tables are skipped.
+fn table_name(toks: &[Tok], idx: usize) -> Option<(String, usize)> {
+    let mut parts = Vec::new();
+    let mut at = idx;
+    loop {
+        let tok = toks.get(at)?;
+        match &tok.token {
+            Token::Quoted(quoted) => parts.push(quoted.clone()),
+            Token::Word(word) if !word.starts_with(['@', '#']) && !sql_format::is_keyword(word) => {
+                parts.push(word.clone())
+            }
+            _ => return None,
+        }
+        at += 1;
+        if !toks.get(at).is_some_and(|t| t.punct(".")) {
+            break;
+        }
+        at += 1;
+    }
+    // `FROM dbo.f(...)` calls a table-valued function; `INTO t (cols)` lists columns.
+    if toks.get(at).is_some_and(|t| t.punct("(")) && !toks[idx.saturating_sub(1)].is("INTO") {
+        return None;
+    }
+    Some((parts.join("."), at))
+}
+
+/// `[dbo].[Orders]` -> `dbo.orders`.
+fn normalize_name(name: &str) -> String {
+    name.split('.')
+        .map(unquote)
+        .collect::<Vec<_>>()
+        .join(".")
+        .to_ascii_lowercase()
+}
+
+fn unquote(name: &str) -> String {
+    let trimmed = name.trim();
+    if (trimmed.starts_with('[') && trimmed.ends_with(']'))
+        || (trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() > 1)
+    {
+        trimmed[1..trimmed.len() - 1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn findings_to_result_set(findings: &[Finding]) -> ResultSet {
+    let columns = ["severity", "rule", "location", "message"]
+        .iter()
+        .map(|name| Column {
+            name: name.to_string(),
+            data_type: None,
+        })
+        .collect();
+    let rows = findings
+        .iter()
+        .map(|f| {
+            vec![
+                Value::Text(f.severity.as_str().to_string()),
+                Value::Text(f.rule.to_string()),
+                Value::Text(format!("{}:{}", f.source, f.line)),
+                Value::Text(f.message.clone()),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(sql: &str, types: &ColumnTypes) -> Vec<(usize, &'static str)> {
+        lint_sql("test.sql", sql, types)
+            .into_iter()
+            .map(|f| (f.line, f.rule))
+            .collect()
+    }
+
+    #[test]
+    fn flags_select_star_nolock_and_cursors() {
+        let sql = "SELECT * FROM dbo.Orders WITH (NOLOCK);\n\
+                   SELECT o.*, COUNT(*) FROM Orders o GROUP BY o.Id;\n\
+                   IF EXISTS (SELECT * FROM Orders) PRINT 'SELECT * FROM x'; -- SELECT *\n\
+                   SELECT TOP (5) * FROM Orders;\n\
+                   DECLARE c CURSOR FAST_FORWARD FOR SELECT Id FROM Orders;\n\
+                   SET TRANSACTION ISOLATION LEVEL READ UNCOMMITTED;";
+        assert_eq!(
+            rules(sql, &ColumnTypes::new()),
+            vec![
+                (1, "select-star"),
+                (1, "nolock"),
+                (2, "select-star"),
+                (4, "select-star"),
+                (5, "cursor"),
+                (6, "nolock"),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_procedures_without_nocount() {
+        let sql = "CREATE OR ALTER PROCEDURE dbo.Quiet AS\nBEGIN\n  SET NOCOUNT ON;\n  SELECT 1;\nEND\nGO\n\
+                   CREATE PROC [dbo].[Chatty] AS\nSELECT 1;\nGO";
+        let findings = lint_sql("procs.sql", sql, &ColumnTypes::new());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "missing-nocount");
+        assert_eq!(findings[0].line, 7);
+        assert!(findings[0].message.contains("dbo.Chatty"));
+        assert_eq!(findings[0].text, "CREATE PROC [dbo].[Chatty] AS");
+    }
+
+    #[test]
+    fn flags_functions_around_columns_only_in_predicates() {
+        let sql = "SELECT YEAR(o.CreatedAt) FROM Orders o\n\
+                   WHERE YEAR(o.CreatedAt) = 2024\n\
+                   AND o.CreatedAt >= DATEADD(day, -7, @today)\n\
+                   AND CONVERT(date, o.ShippedAt) = @d\n\
+                   AND o.Name LIKE '%son';";
+        assert_eq!(
+            rules(sql, &ColumnTypes::new()),
+            vec![
+                (2, "non-sargable"),
+                (4, "non-sargable"),
+                (5, "non-sargable")
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_implicit_conversions_from_column_types() {
+        let mut types = ColumnTypes::new();
+        types.insert(
+            "dbo.customers".to_string(),
+            HashMap::from([
+                ("code".to_string(), "varchar".to_string()),
+                ("phone".to_string(), "nvarchar".to_string()),
+                ("id".to_string(), "int".to_string()),
+            ]),
+        );
+        let sql = "DECLARE @code NVARCHAR(20) = N'A1';\n\
+                   SELECT c.Id FROM [dbo].[Customers] AS c\n\
+                   WHERE c.Code = @code\n\
+                   OR Phone = 5551234\n\
+                   OR c.Id = '42'\n\
+                   OR c.Code IN (N'A', N'B');";
+        let findings = lint_sql("q.sql", sql, &types);
+        let found: Vec<_> = findings
+            .iter()
+            .map(|f| (f.line, f.rule, f.severity))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, "implicit-conversion", Severity::Medium),
+                (4, "implicit-conversion", Severity::High),
+                (6, "implicit-conversion", Severity::Medium),
+            ]
+        );
+        assert!(findings[0].message.contains("@code (nvarchar)"));
+        assert!(rules(sql, &ColumnTypes::new()).is_empty());
+    }
+
+    #[test]
+    fn table_scope_collects_names_and_aliases() {
+        let toks = significant_tokens(
+            "UPDATE o SET Total = 0 FROM dbo.Orders o JOIN [sales].[Lines] AS l ON l.Id = o.Id, \
+             #tmp t, Customers c WHERE EXISTS (SELECT 1 FROM dbo.fn_Open(1))",
+        );
+        let scope = TableScope::from_tokens(&toks);
+        assert_eq!(scope.names, vec!["o", "dbo.Orders", "[sales].[Lines]"]);
+        assert!(scope.aliases["l"].contains("sales.lines"));
+        assert!(scope.aliases["orders"].contains("dbo.orders"));
+    }
+}
@@ -0,0 +1,295 @@
+//! Command reference for agent skill and extension manifests, rendered from the clap tree at
+//! install time so installed instructions always match the binary that wrote them.
+
+use clap::{Arg, Command};
+
+use crate::cli::build_cli;
+
+/// JSON shapes per command, keyed by command path, with the flag that selects a variant.
+pub(crate) const JSON_CONTRACTS: &[(&str, &str, &str)] = &[
+    (
+        "status",
+        "",
+        "{ status, latencyMs, serverName, serverVersion, currentDatabase, timestamp, warnings }",
+    ),
+    (
+        "status",
+        "--diagnose",
+        "{ status, failedStep, target: {server, port, database, user, encrypt, trustCert}, steps: [{step, status, elapsedMs, detail, hint}] }",
+    ),
+    (
+        "databases",
+        "",
+        "{ total, count, offset, limit, hasMore, nextOffset, databases: [...], warnings }",
+    ),
+    (
+        "databases",
+        "--foreach",
+        "{ sql, count, results: [{database, rows}], errors: [{database, error}], warnings }",
+    ),
+    (
+        "databases",
+        "--create-script",
+        "{ database, out, counts: {schemas, types, sequences, tables, foreignKeys, synonyms, modules, principals, permissions, dataRows}, warnings, script }",
+    ),
+    (
+        "tables",
+        "",
+        "{ total, count, offset, limit, hasMore, nextOffset, tables: [...] }",
+    ),
+    (
+        "tables",
+        "--summary",
+        "{ schemas, like, totals: {schemas, tables, views, heaps, withoutPrimaryKey, rows, sizeMb}, bySchema, largest, widest, heaps, withoutPrimaryKey }",
+    ),
+    (
+        "tables",
+        "--orphaned",
+        "{ schemas, like, counts: {high, medium, low}, findings: [{severity, check, schema, table, object, detail}] }",
+    ),
+    (
+        "describe",
+        "",
+        "{ object: {schema, name, type, description}, columns, ddl?, indexes?, triggers?, foreignKeys?, constraints?, versioning?, partitioning? }",
+    ),
+    (
+        "describe",
+        "--type database",
+        "{ database: {name, owner, compatibilityLevel, recoveryModel, collation, ...}, files, schemas, warnings }",
+    ),
+    (
+        "describe",
+        "<pattern>",
+        "{ pattern, matched, truncated, matches: [...] }",
+    ),
+    (
+        "indexes",
+        "<pattern>",
+        "{ matched, truncated, usageAvailable, matches: [{table: {schema, name}, indexes: [{compression, rowGroups, compressionEstimate, ...}]}], warnings }",
+    ),
+    (
+        "foreign-keys",
+        "<pattern>",
+        "{ direction, matched, truncated, matches: [{table: {schema, name}, foreignKeys}] }",
+    ),
+    (
+        "changes",
+        "",
+        "{ since, count, groups: [{type, count, objects: [{schema, name, objectType, parent, change, createdAt, modifiedAt}]}] }",
+    ),
+    (
+        "table-data",
+        "",
+        "{ table, columns, rows, total, offset, limit, hasMore, nextOffset, nextKey, sample }",
+    ),
+    ("table-data", "--follow", "NDJSON: one row object per line"),
+    (
+        "stored-procs",
+        "--history",
+        "{ procedure: {schema, name}, planCache, queryStore: {state, days, daily: [...]}, warnings }",
+    ),
+    (
+        "stored-procs",
+        "--exec",
+        "{ procedure, returnValue, outputParameters: {name: value}, resultSets }",
+    ),
+    (
+        "stored-procs",
+        "--test",
+        "{ file, passed, failed, tests: [{name, procedure, status, elapsedMs, returnValue, resultSets: [{columns, rows}], error, failures}] }",
+    ),
+    (
+        "profile",
+        "",
+        "{ table: {schema, name}, rowCount, distinctMethod, topValues, columns: [{name, dataType, nullable, nullCount, distinctCount, min, max, topValues}] }",
+    ),
+    (
+        "sql",
+        "",
+        "{ success, truncated, batches, resultSets: [{ columns, rows, truncated, totalRowsAvailable?, omittedRows? }], csvPaths?, outPaths? }",
+    ),
+    ("sql", "--format-sql", "{ sql }"),
+    (
+        "config connection-string",
+        "",
+        "{ profileName, redacted, ado, jdbc, odbc, sqlcmd }",
+    ),
+    (
+        "fk-check",
+        "",
+        "{ summary: {foreignKeys, checked, untrusted, withOrphans, fixable}, foreignKeys: [{name, table, references, columns, referencedColumns, state, status, orphans, query, error}], script }",
+    ),
+    (
+        "partitions",
+        "--table",
+        "{ table: {schema, name}, partitioning: {scheme, function, parameterType, column, rangeType, nextUsed, sharedWith, partitions: [...]}, script? }",
+    ),
+    (
+        "compare",
+        "--summary",
+        "{ modules, indexes, constraints, tables }",
+    ),
+    ("compare", "", "{ source, target } snapshots"),
+    (
+        "assert",
+        "",
+        "{ name, status, operator, expected, actual, rowCount, message, sql, sample }",
+    ),
+    (
+        "security grant-script",
+        "",
+        "{ principal, database, schemas, allow, members, missingOnly, statements, alreadyHeld, warnings }",
+    ),
+    (
+        "docs generate",
+        "",
+        "{ out, format, schemas, tables, files }",
+    ),
+    (
+        "docs set-description",
+        "",
+        "{ target: {schema, table, column}, description, action }",
+    ),
+    ("fmt", "", "{ file, changed, written, sql }"),
+    (
+        "lint",
+        "",
+        "{ sources, failOn, typesChecked, counts: {high, medium, low}, findings: [{source, line, severity, rule, message, text}] }",
+    ),
+    (
+        "migrate status",
+        "",
+        "{ directory, counts: {applied, pending, changed, missing}, migrations: [{version, name, status, file, appliedAt, appliedBy}] }",
+    ),
+    (
+        "migrate up",
+        "",
+        "{ directory, dryRun, migrations: [{version, name, file, transaction, batches, elapsedMs, sql}] }",
+    ),
+    (
+        "seed",
+        "",
+        "{ applied, inSync, seeds: [{file, table, key, rows, inserts, updates: [{key, changes}], deletes}] }",
+    ),
+    (
+        "trace start",
+        "",
+        "{ session, scope, events, predicate, started }",
+    ),
+    (
+        "trace tail",
+        "",
+        "NDJSON: one object per event with event, timestamp, fields, and actions",
+    ),
+];
+
+/// Markdown reference of every command (advanced ones included): aliases, description,
+/// options, and JSON shape where the contract documents one.
+pub fn command_reference() -> String {
+    let cli = build_cli(true);
+    let mut out = vec![
+        "## Command reference".to_string(),
+        String::new(),
+        format!(
+            "Generated from sscli {}. Run `sscli <command> --help` for option details.",
+            env!("CARGO_PKG_VERSION")
+        ),
+        String::new(),
+        "Global options (any command):".to_string(),
+        String::new(),
+    ];
+    for arg in cli.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+        out.push(format!("- `{}`: {}", arg_usage(arg), help));
+    }
+    for sub in cli.get_subcommands().filter(|sub| sub.get_name() != "help") {
+        push_command(&mut out, sub, sub.get_name());
+    }
+    out.push(String::new());
+    out.join("\n")
+}
+
+fn push_command(out: &mut Vec<String>, cmd: &Command, path: &str) {
+    out.push(String::new());
+    let aliases = cmd.get_visible_aliases().collect::<Vec<_>>();
+    if aliases.is_empty() {
+        out.push(format!("### `{}`", path));
+    } else {
+        out.push(format!("### `{}` (alias: {})", path, aliases.join(", ")));
+    }
+    if let Some(about) = cmd.get_about() {
+        out.push(String::new());
+        out.push(about.to_string());
+        out.push(String::new());
+    }
+    let options = cmd
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set() && !arg.is_global_set())
+        .map(|arg| format!("`{}`", arg_usage(arg)))
+        .collect::<Vec<_>>();
+    if !options.is_empty() {
+        out.push(format!("- Options: {}", options.join(", ")));
+    }
+    for (_, variant, shape) in JSON_CONTRACTS.iter().filter(|(cmd, _, _)| *cmd == path) {
+        if variant.is_empty() {
+            out.push(format!("- JSON: `{}`", shape));
+        } else {
+            out.push(format!("- JSON (`{}`): `{}`", variant, shape));
+        }
+    }
+    for sub in cmd.get_subcommands() {
+        push_command(out, sub, &format!("{} {}", path, sub.get_name()));
+    }
+}
+
+/// `--long <VALUE>`, `-s`, or `<NAME>` for a positional.
+fn arg_usage(arg: &Arg) -> String {
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| arg.get_id().to_string().to_uppercase());
+    if arg.is_positional() {
+        return if arg.is_required_set() {
+            format!("<{}>", value_name)
+        } else {
+            format!("[{}]", value_name)
+        };
+    }
+    let name = match (arg.get_long(), arg.get_short()) {
+        (Some(long), _) => format!("--{}", long),
+        (None, Some(short)) => format!("-{}", short),
+        (None, None) => arg.get_id().to_string(),
+    };
+    if arg.get_action().takes_values() {
+        format!("{} <{}>", name, value_name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_covers_the_command_tree() {
+        let reference = command_reference();
+        assert!(reference.contains(env!("CARGO_PKG_VERSION")));
+        assert!(reference.contains("- `--json`: Output as JSON"));
+        assert!(reference.contains("### `trace start`"));
+        assert!(reference.contains("### `sql` (alias: query)"));
+        assert!(reference.contains("- JSON (`--orphaned`): `{ schemas"));
+        assert!(!reference.contains("### `help`"));
+
+        let cli = build_cli(true);
+        for (path, _, _) in JSON_CONTRACTS {
+            let mut cmd = &cli;
+            for part in path.split(' ') {
+                cmd = cmd
+                    .find_subcommand(part)
+                    .unwrap_or_else(|| panic!("contract for unknown command '{}'", path));
+            }
+        }
+    }
+}
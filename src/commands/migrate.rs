@@ -0,0 +1,722 @@
+//! `migrate`: versioned migrations from a directory of `<version>_<name>.sql` files.
+//!
+//! Each pending file runs once, in version order, inside its own transaction, and is recorded
+//! with a checksum in `dbo.__sscli_migrations` in the same transaction. Files are split into
+//! batches on `GO` exactly as `sql` splits them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, MigrateArgs, MigrateCommand, MigrateUpArgs};
+use crate::commands::{common, confirm, sql_utils};
+use crate::config::{OutputFormat, ResolvedConfig};
+use crate::db::session::SqlClient;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+/// First-line marker for migrations that cannot run in a transaction (e.g. `ALTER DATABASE`).
+const NO_TRANSACTION_MARKER: &str = "-- sscli:no-transaction";
+
+const HISTORY_TABLE: &str = "dbo.__sscli_migrations";
+
+const CREATE_HISTORY_SQL: &str = r#"
+IF OBJECT_ID(N'dbo.__sscli_migrations', N'U') IS NULL
+CREATE TABLE dbo.__sscli_migrations (
+    version nvarchar(50) NOT NULL CONSTRAINT PK___sscli_migrations PRIMARY KEY,
+    name nvarchar(200) NOT NULL,
+    checksum char(64) NOT NULL,
+    applied_at datetime2(0) NOT NULL
+        CONSTRAINT DF___sscli_migrations_applied_at DEFAULT SYSUTCDATETIME(),
+    applied_by sysname NOT NULL
+        CONSTRAINT DF___sscli_migrations_applied_by DEFAULT SUSER_SNAME(),
+    duration_ms int NOT NULL
+);
+"#;
+
+/// Applied versions; returns no result set before the first `migrate up`.
+const APPLIED_SQL: &str = r#"
+IF OBJECT_ID(N'dbo.__sscli_migrations', N'U') IS NOT NULL
+    SELECT version, name, checksum,
+           CONVERT(varchar(19), applied_at, 126) AS appliedAt,
+           applied_by AS appliedBy
+    FROM dbo.__sscli_migrations
+    ORDER BY version;
+"#;
+
+const RECORD_SQL: &str = "INSERT INTO dbo.__sscli_migrations (version, name, checksum, duration_ms) \
+                          VALUES (@P1, @P2, @P3, @P4);";
+
+/// A migration file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Migration {
+    version: String,
+    name: String,
+    file: PathBuf,
+    checksum: String,
+    transactional: bool,
+    batches: Vec<String>,
+}
+
+/// A row of the history table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Applied {
+    version: String,
+    name: String,
+    checksum: String,
+    applied_at: String,
+    applied_by: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Applied,
+    Pending,
+    /// Applied, but the file's checksum no longer matches what ran.
+    Changed,
+    /// Recorded as applied, with no file left in the directory.
+    Missing,
+}
+
+impl State {
+    fn as_str(&self) -> &'static str {
+        match self {
+            State::Applied => "applied",
+            State::Pending => "pending",
+            State::Changed => "changed",
+            State::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry<'a> {
+    version: String,
+    name: String,
+    state: State,
+    migration: Option<&'a Migration>,
+    applied: Option<&'a Applied>,
+}
+
+pub fn run(args: &CliArgs, cmd: &MigrateArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let dir = cmd
+        .dir
+        .clone()
+        .unwrap_or_else(|| resolved.settings.migrations.directory.clone());
+
+    match &cmd.command {
+        MigrateCommand::New { name } => run_new(args, &resolved, format, &dir, name),
+        MigrateCommand::Status => run_status(args, &resolved, format, &dir),
+        MigrateCommand::Up(up) => run_up(args, &resolved, format, &dir, up),
+    }
+}
+
+fn run_new(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    dir: &Path,
+    name: &str,
+) -> Result<()> {
+    let slug = slugify(name);
+    if slug.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!("Migration name '{}' has no letters or digits", name),
+        )
+        .into());
+    }
+    let now = chrono::Utc::now();
+    let version = now.format("%Y%m%d%H%M%S").to_string();
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{}_{}.sql", version, slug));
+    let template = format!(
+        "-- Migration {}: {}\n\
+         -- Created {} UTC. Runs once, in a transaction, via `sscli migrate up`; separate batches with GO.\n\
+         -- Put `{}` on the first line for statements that cannot run in a transaction.\n\n",
+        version,
+        slug,
+        now.format("%Y-%m-%d %H:%M:%S"),
+        NO_TRANSACTION_MARKER
+    );
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, template.as_bytes()))
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "version": version,
+            "name": slug,
+            "file": path.display().to_string(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn run_status(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    dir: &Path,
+) -> Result<()> {
+    let migrations = load_migrations(dir)?;
+    let applied = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        load_applied(&mut client).await
+    })?;
+    let entries = reconcile(&migrations, &applied);
+    let count = |state: State| entries.iter().filter(|e| e.state == state).count();
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "directory": dir.display().to_string(),
+            "counts": {
+                "applied": count(State::Applied),
+                "pending": count(State::Pending),
+                "changed": count(State::Changed),
+                "missing": count(State::Missing),
+            },
+            "migrations": entries.iter().map(|e| json!({
+                "version": e.version,
+                "name": e.name,
+                "status": e.state.as_str(),
+                "file": e.migration.map(|m| m.file.display().to_string()),
+                "appliedAt": e.applied.map(|a| a.applied_at.as_str()),
+                "appliedBy": e.applied.map(|a| a.applied_by.as_str()),
+            })).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No migrations in {}.", dir.display());
+        return Ok(());
+    }
+    let view = ResultSet {
+        columns: ["version", "name", "status", "appliedAt"]
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: None,
+            })
+            .collect(),
+        rows: entries
+            .iter()
+            .map(|e| {
+                vec![
+                    Value::Text(e.version.clone()),
+                    Value::Text(e.name.clone()),
+                    Value::Text(e.state.as_str().to_string()),
+                    e.applied
+                        .map_or(Value::Null, |a| Value::Text(a.applied_at.clone())),
+                ]
+            })
+            .collect(),
+    };
+    let result = table::render_result_set_table(&view, format, &TableOptions::default());
+    println!("{}", result.output);
+    if count(State::Changed) > 0 {
+        eprintln!(
+            "Warning: changed migrations were edited after they ran; add a new migration instead"
+        );
+    }
+    Ok(())
+}
+
+fn run_up(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    dir: &Path,
+    up: &MigrateUpArgs,
+) -> Result<()> {
+    if !up.dry_run && !args.allow_write {
+        return Err(AppError::new(
+            ErrorKind::Safety,
+            "migrate up changes the database; re-run with --allow-write (or --dry-run to preview)",
+        )
+        .into());
+    }
+    let migrations = load_migrations(dir)?;
+    if let Some(to) = &up.to {
+        if !migrations.iter().any(|m| &m.version == to) {
+            return Err(AppError::new(
+                ErrorKind::NotFound,
+                format!("No migration with version {} in {}", to, dir.display()),
+            )
+            .into());
+        }
+    }
+    if !args.quiet && !args.quiet_target {
+        eprintln!(
+            "Target: {}:{}/{}",
+            resolved.connection.server, resolved.connection.port, resolved.connection.database
+        );
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let mut client = rt.block_on(client::connect(&resolved.connection))?;
+    let applied = rt.block_on(load_applied(&mut client))?;
+    let entries = reconcile(&migrations, &applied);
+    let changed: Vec<&str> = entries
+        .iter()
+        .filter(|e| e.state == State::Changed)
+        .map(|e| e.version.as_str())
+        .collect();
+    if !changed.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Safety,
+            format!(
+                "Applied migrations were edited after they ran ({}); revert the edits and add a new migration instead",
+                changed.join(", "),
+            ),
+        )
+        .into());
+    }
+    let pending = pending_migrations(&entries, up.to.as_deref());
+    if !up.dry_run && !pending.is_empty() {
+        let action = format!(
+            "apply {}",
+            confirm::plural(pending.len(), "pending migration", "pending migrations")
+        );
+        confirm::confirm_write(args, resolved, &action)?;
+    }
+
+    let mut results = Vec::new();
+    if !up.dry_run && !pending.is_empty() {
+        rt.block_on(executor::run_query(
+            Query::new(CREATE_HISTORY_SQL),
+            &mut client,
+        ))
+        .with_context(|| format!("Failed to create {}", HISTORY_TABLE))?;
+    }
+    for migration in &pending {
+        if up.dry_run {
+            results.push((*migration, None));
+            continue;
+        }
+        let elapsed_ms = rt.block_on(apply(&mut client, migration))?;
+        if !args.quiet && !matches!(format, OutputFormat::Json) {
+            println!(
+                "Applied {}_{} ({} batch{}, {} ms)",
+                migration.version,
+                migration.name,
+                migration.batches.len(),
+                if migration.batches.len() == 1 {
+                    ""
+                } else {
+                    "es"
+                },
+                elapsed_ms
+            );
+        }
+        results.push((*migration, Some(elapsed_ms)));
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "directory": dir.display().to_string(),
+            "dryRun": up.dry_run,
+            "migrations": results.iter().map(|(m, elapsed_ms)| json!({
+                "version": m.version,
+                "name": m.name,
+                "file": m.file.display().to_string(),
+                "transaction": m.transactional,
+                "batches": m.batches.len(),
+                "elapsedMs": elapsed_ms,
+                "sql": if up.dry_run { Some(&m.batches) } else { None },
+            })).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if pending.is_empty() {
+            println!("Database is up to date.");
+        } else if up.dry_run {
+            for migration in &pending {
+                println!(
+                    "-- {}_{} ({}{})",
+                    migration.version,
+                    migration.name,
+                    migration.file.display(),
+                    if migration.transactional {
+                        ""
+                    } else {
+                        ", no transaction"
+                    }
+                );
+                for batch in &migration.batches {
+                    println!("{}\nGO", batch);
+                }
+                println!();
+            }
+            println!(
+                "Dry run: {} migration{} pending; re-run without --dry-run to apply.",
+                pending.len(),
+                if pending.len() == 1 { "" } else { "s" }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Run one migration and record it; in a transaction unless the file opts out.
+async fn apply(client: &mut SqlClient, migration: &Migration) -> Result<u128> {
+    let started = Instant::now();
+    if migration.transactional {
+        executor::run_query(Query::new("SET XACT_ABORT ON; BEGIN TRANSACTION;"), client).await?;
+    }
+    let total = migration.batches.len();
+    for (idx, batch) in migration.batches.iter().enumerate() {
+        if let Err(err) = executor::run_query(Query::new(batch.clone()), client).await {
+            let outcome = if migration.transactional {
+                let _ =
+                    executor::run_query(Query::new("IF @@TRANCOUNT > 0 ROLLBACK;"), client).await;
+                "it was rolled back"
+            } else {
+                "earlier batches were NOT rolled back (no-transaction migration)"
+            };
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Migration {}_{} failed at batch {} of {}; {}: {}",
+                    migration.version,
+                    migration.name,
+                    idx + 1,
+                    total,
+                    outcome,
+                    err
+                ),
+            )
+            .into());
+        }
+    }
+    let elapsed_ms = started.elapsed().as_millis();
+    let mut record = Query::new(RECORD_SQL);
+    record.bind(migration.version.as_str());
+    record.bind(migration.name.as_str());
+    record.bind(migration.checksum.as_str());
+    record.bind(i32::try_from(elapsed_ms).unwrap_or(i32::MAX));
+    if let Err(err) = executor::run_query(record, client).await {
+        if migration.transactional {
+            let _ = executor::run_query(Query::new("IF @@TRANCOUNT > 0 ROLLBACK;"), client).await;
+        }
+        return Err(err.context(format!(
+            "Failed to record migration {} in {}",
+            migration.version, HISTORY_TABLE
+        )));
+    }
+    if migration.transactional {
+        executor::run_query(Query::new("COMMIT TRANSACTION;"), client).await?;
+    }
+    Ok(elapsed_ms)
+}
+
+async fn load_applied(client: &mut SqlClient) -> Result<Vec<Applied>> {
+    let rows = executor::run_query(Query::new(APPLIED_SQL), client)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let text = |row: &[Value], idx: usize| row.get(idx).map(Value::as_csv).unwrap_or_default();
+    Ok(rows
+        .rows
+        .iter()
+        .map(|row| Applied {
+            version: text(row, 0),
+            name: text(row, 1),
+            checksum: text(row, 2).trim().to_string(),
+            applied_at: text(row, 3),
+            applied_by: text(row, 4),
+        })
+        .collect())
+}
+
+/// Every `<version>_<name>.sql` file directly under `dir`, in version order.
+fn load_migrations(dir: &Path) -> Result<Vec<Migration>> {
+    if !dir.is_dir() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!(
+                "Migrations directory not found: {} (create one with `sscli migrate new <name>`)",
+                dir.display()
+            ),
+        )
+        .into());
+    }
+    let mut migrations = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if !path.is_file()
+            || !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"))
+        {
+            continue;
+        }
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let Some((version, name)) = parse_file_stem(stem) else {
+            return Err(AppError::new(
+                ErrorKind::Config,
+                format!(
+                    "Migration file names must look like <version>_<name>.sql: {}",
+                    path.display()
+                ),
+            )
+            .into());
+        };
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        migrations.push(parse_migration(version, name, path.clone(), &text));
+    }
+    migrations.sort_by(|a, b| version_key(&a.version).cmp(&version_key(&b.version)));
+    if let Some(pair) = migrations
+        .windows(2)
+        .find(|pair| version_key(&pair[0].version) == version_key(&pair[1].version))
+    {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!(
+                "Duplicate migration version {}: {} and {}",
+                pair[0].version,
+                pair[0].file.display(),
+                pair[1].file.display()
+            ),
+        )
+        .into());
+    }
+    Ok(migrations)
+}
+
+/// `20260301120000_add_orders_index` into its version and name.
+fn parse_file_stem(stem: &str) -> Option<(&str, &str)> {
+    let (version, name) = stem.split_once('_')?;
+    (!version.is_empty() && version.chars().all(|ch| ch.is_ascii_digit()) && !name.is_empty())
+        .then_some((version, name))
+}
+
+fn parse_migration(version: &str, name: &str, file: PathBuf, text: &str) -> Migration {
+    // Line endings do not change what runs, so they do not change the checksum either.
+    let normalized = text.replace("\r\n", "\n");
+    let transactional = !normalized
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .next()
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(NO_TRANSACTION_MARKER));
+    let batches = sql_utils::split_batches(&normalized)
+        .into_iter()
+        .filter(|batch| {
+            batch
+                .lines()
+                .any(|line| !line.trim().is_empty() && !line.trim_start().starts_with("--"))
+        })
+        .collect();
+    Migration {
+        version: version.to_string(),
+        name: name.to_string(),
+        file,
+        checksum: sha256_hex(normalized.as_bytes()),
+        transactional,
+        batches,
+    }
+}
+
+/// Numeric order, so `9_x` sorts before `10_x`; leading zeros do not make a new version.
+fn version_key(version: &str) -> (usize, &str) {
+    let digits = version.trim_start_matches('0');
+    (digits.len(), digits)
+}
+
+/// Files and history rows merged in version order.
+fn reconcile<'a>(migrations: &'a [Migration], applied: &'a [Applied]) -> Vec<Entry<'a>> {
+    let mut entries: BTreeMap<(usize, &str), Entry<'a>> = BTreeMap::new();
+    for migration in migrations {
+        entries.insert(
+            version_key(&migration.version),
+            Entry {
+                version: migration.version.clone(),
+                name: migration.name.clone(),
+                state: State::Pending,
+                migration: Some(migration),
+                applied: None,
+            },
+        );
+    }
+    for row in applied {
+        let entry = entries
+            .entry(version_key(&row.version))
+            .or_insert_with(|| Entry {
+                version: row.version.clone(),
+                name: row.name.clone(),
+                state: State::Missing,
+                migration: None,
+                applied: None,
+            });
+        entry.applied = Some(row);
+        if let Some(migration) = entry.migration {
+            entry.state = if migration.checksum.eq_ignore_ascii_case(&row.checksum) {
+                State::Applied
+            } else {
+                State::Changed
+            };
+        }
+    }
+    entries.into_values().collect()
+}
+
+/// Pending migrations to run, stopping after `to` when given.
+fn pending_migrations<'a>(entries: &[Entry<'a>], to: Option<&str>) -> Vec<&'a Migration> {
+    let limit = to.map(version_key);
+    entries
+        .iter()
+        .filter(|e| e.state == State::Pending)
+        .filter(|e| limit.is_none_or(|limit| version_key(&e.version) <= limit))
+        .filter_map(|e| e.migration)
+        .collect()
+}
+
+/// `Add Orders index!` to `add_orders_index`.
+fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for ch in name.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    slug.trim_matches('_').to_string()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(version: &str, text: &str) -> Migration {
+        parse_migration(
+            version,
+            "step",
+            PathBuf::from(format!("{}_step.sql", version)),
+            text,
+        )
+    }
+
+    #[test]
+    fn parses_files_into_batches_and_checksums() {
+        assert_eq!(
+            parse_file_stem("20260301120000_add_orders_index"),
+            Some(("20260301120000", "add_orders_index"))
+        );
+        assert_eq!(parse_file_stem("v1_init"), None);
+        assert_eq!(parse_file_stem("0001"), None);
+        assert_eq!(slugify("  Add Orders index! "), "add_orders_index");
+
+        let unix = migration(
+            "1",
+            "-- header\n\nCREATE TABLE t (id int);\nGO\nINSERT t VALUES (1);\n",
+        );
+        let windows = migration(
+            "1",
+            "-- header\r\n\r\nCREATE TABLE t (id int);\r\nGO\r\nINSERT t VALUES (1);\r\n",
+        );
+        assert_eq!(unix.batches.len(), 2);
+        assert!(unix.transactional);
+        assert_eq!(unix.checksum, windows.checksum);
+        assert_eq!(unix.checksum.len(), 64);
+
+        let loose = migration(
+            "2",
+            "-- sscli:no-transaction\nALTER DATABASE CURRENT SET RECOVERY FULL;\n",
+        );
+        assert!(!loose.transactional);
+        assert_eq!(loose.batches.len(), 1);
+    }
+
+    #[test]
+    fn reconciles_files_with_history() {
+        let migrations = vec![
+            migration("9", "SELECT 9;"),
+            migration("10", "SELECT 10;"),
+            migration("11", "SELECT 11;"),
+            migration("12", "SELECT 12;"),
+        ];
+        let applied_row = |version: &str, checksum: &str| Applied {
+            version: version.to_string(),
+            name: "step".to_string(),
+            checksum: checksum.to_string(),
+            applied_at: "2026-03-01T12:00:00".to_string(),
+            applied_by: "deploy".to_string(),
+        };
+        let applied = vec![
+            applied_row("9", &migrations[0].checksum),
+            applied_row("10", "0000"),
+            applied_row("8", "ffff"),
+        ];
+        let entries = reconcile(&migrations, &applied);
+        let states: Vec<(&str, State)> = entries
+            .iter()
+            .map(|e| (e.version.as_str(), e.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("8", State::Missing),
+                ("9", State::Applied),
+                ("10", State::Changed),
+                ("11", State::Pending),
+                ("12", State::Pending),
+            ]
+        );
+        let versions = |pending: Vec<&Migration>| {
+            pending
+                .iter()
+                .map(|m| m.version.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            versions(pending_migrations(&entries, None)),
+            vec!["11", "12"]
+        );
+        assert_eq!(
+            versions(pending_migrations(&entries, Some("11"))),
+            vec!["11"]
+        );
+    }
+}
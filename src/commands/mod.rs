@@ -1,28 +1,67 @@
+mod ag;
+mod assemblies;
+mod assert;
 mod backups;
+mod cdc;
+mod changes;
 mod columns;
 mod common;
 mod compare;
+mod compare_ignore;
 mod completions;
 mod config;
+mod confirm;
+mod dacpac;
+mod dacpac_package;
 mod databases;
+mod databases_create_script;
+mod databases_foreach;
+mod dependency_order;
 mod describe;
+mod describe_database;
+mod docs;
+mod errorlog;
+mod fk_check;
+mod fmt;
 mod foreign_keys;
+mod grant_script;
 mod help;
 mod indexes;
 mod init;
 mod integrations;
+mod lint;
+mod manifest;
+mod migrate;
 mod object_lookup;
 mod paging;
+mod partitions;
+mod permissions;
+mod profile;
+mod profiles;
 mod query_stats;
+mod replication;
+mod schema_apply;
+mod schema_export;
+mod seed;
+mod serve;
 mod sessions;
 mod sql;
+mod sql_format;
 mod sql_utils;
 mod status;
+mod status_diagnose;
 mod stored_procs;
+mod stored_procs_history;
+mod stored_procs_test;
 mod table_data;
+mod table_script;
 mod tables;
+mod tables_audit;
+mod tables_summary;
+mod trace;
 mod update;
 mod update_notice;
+mod verify;
 
 use anyhow::Result;
 
@@ -30,24 +69,52 @@ use crate::cli::{CliArgs, CommandKind};
 
 pub fn dispatch(args: &CliArgs) -> Result<()> {
     let result = match &args.command {
-        CommandKind::Help { all, command } => help::run(*all, command.as_deref()),
+        CommandKind::Help {
+            schema: true,
+            command,
+            ..
+        } => help::run_schema(args, command.as_deref()),
+        CommandKind::Help { all, command, .. } => help::run(*all, command.as_deref()),
         CommandKind::Status(cmd) => status::run(args, cmd),
         CommandKind::Databases(cmd) => databases::run(args, cmd),
         CommandKind::Tables(cmd) => tables::run(args, cmd),
         CommandKind::Describe(cmd) => describe::run(args, cmd),
         CommandKind::Sql(cmd) => sql::run(args, cmd),
         CommandKind::TableData(cmd) => table_data::run(args, cmd),
+        CommandKind::Profile(cmd) => profile::run(args, cmd),
         CommandKind::Columns(cmd) => columns::run(args, cmd),
         CommandKind::Update(cmd) => update::run(args, cmd),
         CommandKind::Indexes(cmd) => indexes::run(args, cmd),
         CommandKind::ForeignKeys(cmd) => foreign_keys::run(args, cmd),
+        CommandKind::FkCheck(cmd) => fk_check::run(args, cmd),
         CommandKind::StoredProcs(cmd) => stored_procs::run(args, cmd),
         CommandKind::Sessions(cmd) => sessions::run(args, cmd),
         CommandKind::QueryStats(cmd) => query_stats::run(args, cmd),
+        CommandKind::Trace(cmd) => trace::run(args, cmd),
         CommandKind::Backups(cmd) => backups::run(args, cmd),
+        CommandKind::Assemblies(cmd) => assemblies::run(args, cmd),
+        CommandKind::Changes(cmd) => changes::run(args, cmd),
+        CommandKind::Partitions(cmd) => partitions::run(args, cmd),
+        CommandKind::Errorlog(cmd) => errorlog::run(args, cmd),
+        CommandKind::Ag(cmd) => ag::run(args, cmd),
+        CommandKind::Cdc(cmd) => cdc::run(args, cmd),
+        CommandKind::Replication(cmd) => replication::run(args, cmd),
         CommandKind::Compare(cmd) => compare::run(args, cmd),
+        CommandKind::Verify(cmd) => verify::run(args, cmd),
+        CommandKind::Assert(cmd) => assert::run(args, cmd),
+        CommandKind::Permissions(cmd) => permissions::run(args, cmd),
+        CommandKind::SchemaApply(cmd) => schema_apply::run(args, cmd),
+        CommandKind::Migrate(cmd) => migrate::run(args, cmd),
+        CommandKind::Seed(cmd) => seed::run(args, cmd),
+        CommandKind::Serve(cmd) => serve::run(args, cmd),
+        CommandKind::SchemaExport(cmd) => schema_export::run(args, cmd),
+        CommandKind::GrantScript(cmd) => grant_script::run(args, cmd),
+        CommandKind::Docs(cmd) => docs::run(args, cmd),
+        CommandKind::Fmt(cmd) => fmt::run(args, cmd),
+        CommandKind::Lint(cmd) => lint::run(args, cmd),
         CommandKind::Init(cmd) => init::run(args, cmd),
-        CommandKind::Config(_) => config::run(args),
+        CommandKind::Config(cmd) => config::run(args, cmd),
+        CommandKind::Profiles(cmd) => profiles::run(args, cmd),
         CommandKind::Completions(cmd) => completions::run(args, cmd),
         CommandKind::Integrations(cmd) => integrations::run(args, cmd),
     };
@@ -9,9 +9,11 @@ use tiberius::Query;
 use tokio::net::TcpStream;
 use tokio_util::compat::Compat;
 
+use crate::commands::common;
 use crate::config::ResolvedConfig;
 use crate::db::executor;
 use crate::db::types::Value;
+use crate::error::{AppError, ErrorKind};
 
 const CACHE_FILE_NAME: &str = "object-index.json";
 const CACHE_TTL_SECS: u64 = 300;
@@ -82,11 +84,11 @@ pub async fn resolve_schema_for_object(
 
     let matches = find_object_matches(client, resolved, object_name, scope).await?;
     if matches.is_empty() {
-        return Err(anyhow!(
-            "{} '{}' not found",
-            title_case(display_kind),
-            object_name
-        ));
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("{} '{}' not found", title_case(display_kind), object_name),
+        )
+        .into());
     }
 
     if matches.len() == 1 {
@@ -105,6 +107,57 @@ pub async fn resolve_schema_for_object(
     ))
 }
 
+/// Expand a `*`/`?` table pattern, optionally with a schema pattern, into `(schema, table)`
+/// pairs ordered by schema and name.
+///
+/// # Errors
+///
+/// Returns a not-found error when nothing matches, or the catalog query's error.
+pub async fn expand_table_pattern(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    name_pattern: &str,
+    schema_pattern: Option<&str>,
+) -> Result<Vec<(String, String)>> {
+    let sql = r#"
+SELECT s.name AS schema_name, t.name AS table_name
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+WHERE t.is_ms_shipped = 0
+  AND t.name LIKE @P1 ESCAPE '\'
+  AND (@P2 IS NULL OR s.name LIKE @P2 ESCAPE '\')
+ORDER BY s.name, t.name;
+"#;
+    let mut query = Query::new(sql);
+    query.bind(common::wildcard_to_like(name_pattern));
+    query.bind(schema_pattern.map(common::wildcard_to_like));
+    let result_sets = executor::run_query(query, client).await?;
+    let tables: Vec<(String, String)> = result_sets
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+        .rows
+        .iter()
+        .filter_map(|row| match (row.first(), row.get(1)) {
+            (Some(Value::Text(schema)), Some(Value::Text(name))) => {
+                Some((schema.clone(), name.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    if tables.is_empty() {
+        let pattern = match schema_pattern {
+            Some(schema) => format!("{}.{}", schema, name_pattern),
+            None => name_pattern.to_string(),
+        };
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("No tables match '{}'", pattern),
+        )
+        .into());
+    }
+    Ok(tables)
+}
+
 async fn find_object_matches(
     client: &mut tiberius::Client<Compat<TcpStream>>,
     resolved: &ResolvedConfig,
@@ -248,9 +301,14 @@ fn cache_is_fresh(cache: &ObjectIndexCache) -> bool {
 }
 
 fn cache_path(resolved: &ResolvedConfig) -> Option<PathBuf> {
+    Some(profile_cache_dir(resolved)?.join(CACHE_FILE_NAME))
+}
+
+/// Per-profile directory for cached catalog data, such as `.sql-server/profiles/dev/`.
+pub(super) fn profile_cache_dir(resolved: &ResolvedConfig) -> Option<PathBuf> {
     let base = cache_base_dir(resolved)?;
     let profile = sanitize_profile_name(&resolved.profile_name);
-    Some(base.join("profiles").join(profile).join(CACHE_FILE_NAME))
+    Some(base.join("profiles").join(profile))
 }
 
 fn cache_base_dir(resolved: &ResolvedConfig) -> Option<PathBuf> {
@@ -376,7 +434,10 @@ fn ambiguous_object_error(
 mod tests {
     use std::path::PathBuf;
 
-    use crate::config::{ConnectionSettings, ResolvedConfig, SettingsResolved};
+    use crate::config::{
+        ConnectionSettings, ProfilePolicy, ResolvedConfig, SessionSettings, SettingsResolved,
+        TlsSettings,
+    };
 
     use super::{LookupScope, ObjectMatch};
     use super::{
@@ -395,10 +456,14 @@ mod tests {
                 password: None,
                 encrypt: true,
                 trust_cert: true,
+                tls: TlsSettings::default(),
                 timeout_ms: 30_000,
                 default_schemas: vec!["dbo".to_string()],
+                session: SessionSettings::default(),
+                application_name: None,
             },
             settings: SettingsResolved::default(),
+            policy: ProfilePolicy::default(),
         }
     }
 
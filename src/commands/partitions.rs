@@ -0,0 +1,564 @@
+//! `partitions`: partitioned tables, and per-partition boundaries, rows, and compression.
+//!
+//! `--script-split`/`--script-merge` generate (never run) the `ALTER PARTITION` statements for
+//! a new or removed boundary, with warnings when the change moves rows or reaches other tables
+//! on the same function.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, PartitionsArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+const TABLES_SQL: &str = r#"
+SELECT
+    s.name AS [schema],
+    t.name AS [table],
+    ps.name AS scheme,
+    pf.name AS [function],
+    c.name AS [column],
+    CASE WHEN pf.boundary_value_on_right = 1 THEN 'RIGHT' ELSE 'LEFT' END AS rangeType,
+    pf.fanout AS partitions,
+    SUM(p.rows) AS [rows]
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN sys.indexes i ON i.object_id = t.object_id AND i.index_id IN (0, 1)
+INNER JOIN sys.partition_schemes ps ON ps.data_space_id = i.data_space_id
+INNER JOIN sys.partition_functions pf ON pf.function_id = ps.function_id
+INNER JOIN sys.partitions p ON p.object_id = i.object_id AND p.index_id = i.index_id
+LEFT JOIN sys.index_columns ic
+    ON ic.object_id = i.object_id AND ic.index_id = i.index_id AND ic.partition_ordinal = 1
+LEFT JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+GROUP BY s.name, t.name, ps.name, pf.name, c.name, pf.boundary_value_on_right, pf.fanout
+ORDER BY s.name, t.name;
+"#;
+
+/// Boundaries are `sql_variant`, which the driver cannot read, so they come back as text:
+/// ISO 8601 for dates and times, the plain value otherwise.
+const PARTITIONS_SQL: &str = r#"
+WITH boundaries AS (
+    SELECT prv.function_id, prv.boundary_id,
+           CASE WHEN CAST(SQL_VARIANT_PROPERTY(prv.value, 'BaseType') AS sysname)
+                     IN ('date', 'datetime', 'datetime2', 'smalldatetime', 'datetimeoffset', 'time')
+                THEN CONVERT(nvarchar(4000), prv.value, 126)
+                ELSE CAST(prv.value AS nvarchar(4000)) END AS value
+    FROM sys.partition_range_values prv
+)
+SELECT
+    p.partition_number AS [partition],
+    lower.value AS lowerBoundary,
+    upper.value AS upperBoundary,
+    p.rows AS [rows],
+    p.data_compression_desc AS compression,
+    fg.name AS filegroup
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN sys.indexes i ON i.object_id = t.object_id AND i.index_id IN (0, 1)
+INNER JOIN sys.partition_schemes ps ON ps.data_space_id = i.data_space_id
+INNER JOIN sys.partitions p ON p.object_id = i.object_id AND p.index_id = i.index_id
+LEFT JOIN boundaries lower
+    ON lower.function_id = ps.function_id AND lower.boundary_id = p.partition_number - 1
+LEFT JOIN boundaries upper
+    ON upper.function_id = ps.function_id AND upper.boundary_id = p.partition_number
+LEFT JOIN sys.destination_data_spaces dds
+    ON dds.partition_scheme_id = ps.data_space_id AND dds.destination_id = p.partition_number
+LEFT JOIN sys.filegroups fg ON fg.data_space_id = dds.data_space_id
+WHERE s.name = @P1 AND t.name = @P2
+ORDER BY p.partition_number;
+"#;
+
+const LAYOUT_SQL: &str = r#"
+SELECT
+    ps.name AS scheme,
+    pf.name AS [function],
+    c.name AS [column],
+    pf.boundary_value_on_right AS range_right,
+    TYPE_NAME(pp.system_type_id) AS parameter_type,
+    next_fg.name AS next_used,
+    (SELECT STRING_AGG(CONCAT(QUOTENAME(os.name), '.', QUOTENAME(o.name)), ', ')
+     FROM sys.indexes oi
+     INNER JOIN sys.partition_schemes ops ON ops.data_space_id = oi.data_space_id
+     INNER JOIN sys.objects o ON o.object_id = oi.object_id
+     INNER JOIN sys.schemas os ON os.schema_id = o.schema_id
+     WHERE ops.function_id = pf.function_id AND oi.index_id IN (0, 1)
+       AND oi.object_id <> t.object_id) AS shared_with
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+INNER JOIN sys.indexes i ON i.object_id = t.object_id AND i.index_id IN (0, 1)
+INNER JOIN sys.partition_schemes ps ON ps.data_space_id = i.data_space_id
+INNER JOIN sys.partition_functions pf ON pf.function_id = ps.function_id
+LEFT JOIN sys.partition_parameters pp ON pp.function_id = pf.function_id AND pp.parameter_id = 1
+LEFT JOIN sys.index_columns ic
+    ON ic.object_id = i.object_id AND ic.index_id = i.index_id AND ic.partition_ordinal = 1
+LEFT JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+LEFT JOIN sys.destination_data_spaces next_dds
+    ON next_dds.partition_scheme_id = ps.data_space_id AND next_dds.destination_id = pf.fanout + 1
+LEFT JOIN sys.filegroups next_fg ON next_fg.data_space_id = next_dds.data_space_id
+WHERE s.name = @P1 AND t.name = @P2;
+"#;
+
+/// How a table is partitioned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Layout {
+    pub(super) scheme: String,
+    pub(super) function: String,
+    pub(super) column: String,
+    pub(super) range_right: bool,
+    /// Base type of the partition function's parameter, e.g. `datetime2`.
+    pub(super) parameter_type: String,
+    /// Filegroup marked NEXT USED on the scheme, if any.
+    pub(super) next_used: Option<String>,
+    /// Other tables on the same partition function, as `[schema].[table]`.
+    pub(super) shared_with: Vec<String>,
+}
+
+pub fn run(args: &CliArgs, cmd: &PartitionsArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let pretty = common::json_pretty(&resolved);
+
+    let Some(table_raw) = cmd.table.as_deref() else {
+        let tables = tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let result_sets = executor::run_query(Query::new(TABLES_SQL), &mut client).await?;
+            Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+        })?;
+        if matches!(format, OutputFormat::Json) {
+            let payload = json!({
+                "count": tables.rows.len(),
+                "tables": json_out::result_set_rows_to_objects(&tables),
+            });
+            let body = json_out::emit_json_value(&payload, pretty)?;
+            if !args.quiet {
+                println!("{}", body);
+            }
+        } else if !args.quiet {
+            if tables.rows.is_empty() {
+                println!("No partitioned tables found.");
+            } else {
+                let result =
+                    table::render_result_set_table(&tables, format, &TableOptions::default());
+                println!("{}", result.output);
+            }
+        }
+        return Ok(());
+    };
+
+    let (name, schema) = common::normalize_object_input(table_raw);
+    let schema = schema.unwrap_or_else(|| "dbo".to_string());
+    let (layout, partitions) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let layout = fetch_layout(&mut client, &schema, &name).await?;
+        let partitions = fetch_partitions(&mut client, &schema, &name).await?;
+        Ok::<_, anyhow::Error>((layout, partitions))
+    })?;
+    let layout = layout.ok_or_else(|| {
+        AppError::new(
+            ErrorKind::NotFound,
+            format!("{}.{} is not a partitioned table", schema, name),
+        )
+    })?;
+
+    let script = match (&cmd.script_split, &cmd.script_merge) {
+        (Some(value), _) => Some(split_script(
+            &layout,
+            &partitions,
+            value,
+            cmd.filegroup.as_deref(),
+        )?),
+        (_, Some(value)) => Some(merge_script(&layout, &partitions, value)?),
+        _ => None,
+    };
+
+    if matches!(format, OutputFormat::Json) {
+        let mut payload = json!({
+            "table": { "schema": schema, "name": name },
+            "partitioning": layout_json(&layout, &partitions),
+        });
+        if let Some(script) = &script {
+            payload["script"] = json!(script);
+        }
+        let body = json_out::emit_json_value(&payload, pretty)?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if args.quiet {
+        return Ok(());
+    }
+    if let Some(script) = script {
+        print!("{}", script);
+        return Ok(());
+    }
+    let options = TableOptions::default();
+    println!(
+        "{}",
+        table::render_key_value_table("Partitioning", &layout_rows(&layout), format, &options)
+            .output
+    );
+    println!(
+        "{}",
+        table::render_result_set_table(&partitions, format, &options).output
+    );
+    Ok(())
+}
+
+pub(super) async fn fetch_layout(
+    client: &mut SqlClient,
+    schema: &str,
+    table_name: &str,
+) -> Result<Option<Layout>> {
+    let mut query = Query::new(LAYOUT_SQL);
+    query.bind(schema);
+    query.bind(table_name);
+    let result_sets = executor::run_query(query, client).await?;
+    let Some(row) = result_sets.first().and_then(|rs| rs.rows.first()) else {
+        return Ok(None);
+    };
+    let text = |idx: usize| match row.get(idx) {
+        Some(Value::Null) | None => None,
+        Some(value) => Some(value.as_display()),
+    };
+    Ok(Some(Layout {
+        scheme: text(0).unwrap_or_default(),
+        function: text(1).unwrap_or_default(),
+        column: text(2).unwrap_or_default(),
+        range_right: matches!(row.get(3), Some(Value::Bool(true)) | Some(Value::Int(1))),
+        parameter_type: text(4).unwrap_or_default(),
+        next_used: text(5),
+        shared_with: text(6)
+            .map(|list| list.split(", ").map(str::to_string).collect())
+            .unwrap_or_default(),
+    }))
+}
+
+/// One row per partition: `partition, lowerBoundary, upperBoundary, rows, compression, filegroup`.
+pub(super) async fn fetch_partitions(
+    client: &mut SqlClient,
+    schema: &str,
+    table_name: &str,
+) -> Result<ResultSet> {
+    let mut query = Query::new(PARTITIONS_SQL);
+    query.bind(schema);
+    query.bind(table_name);
+    Ok(executor::run_query(query, client)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default())
+}
+
+pub(super) fn layout_rows(layout: &Layout) -> Vec<(String, String)> {
+    let mut rows = vec![
+        ("Scheme".to_string(), layout.scheme.clone()),
+        (
+            "Function".to_string(),
+            format!("{} ({})", layout.function, layout.parameter_type),
+        ),
+        ("Column".to_string(), layout.column.clone()),
+        ("Range".to_string(), range_type(layout).to_string()),
+    ];
+    if let Some(next_used) = &layout.next_used {
+        rows.push(("Next used".to_string(), next_used.clone()));
+    }
+    if !layout.shared_with.is_empty() {
+        rows.push(("Shared with".to_string(), layout.shared_with.join(", ")));
+    }
+    rows
+}
+
+pub(super) fn layout_json(layout: &Layout, partitions: &ResultSet) -> serde_json::Value {
+    json!({
+        "scheme": layout.scheme,
+        "function": layout.function,
+        "parameterType": layout.parameter_type,
+        "column": layout.column,
+        "rangeType": range_type(layout),
+        "nextUsed": layout.next_used,
+        "sharedWith": layout.shared_with,
+        "partitions": json_out::result_set_rows_to_objects(partitions),
+    })
+}
+
+fn range_type(layout: &Layout) -> &'static str {
+    if layout.range_right { "RIGHT" } else { "LEFT" }
+}
+
+/// `ALTER PARTITION` statements adding `value` as a boundary.
+fn split_script(
+    layout: &Layout,
+    partitions: &ResultSet,
+    value: &str,
+    filegroup: Option<&str>,
+) -> Result<String> {
+    let literal = boundary_literal(&layout.parameter_type, value)?;
+    let info = PartitionInfo::from_result_set(partitions);
+    if info.iter().any(|p| p.boundaries().contains(&Some(value))) {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!("{} is already a boundary of {}", value, layout.function),
+        )
+        .into());
+    }
+    // Without --filegroup, the new partition goes where the last one lives.
+    let filegroup = filegroup
+        .map(str::to_string)
+        .or_else(|| info.last().map(|p| p.filegroup.clone()))
+        .unwrap_or_else(|| "PRIMARY".to_string());
+
+    let mut script = header(layout);
+    if info
+        .iter()
+        .find(|p| p.contains(value))
+        .is_some_and(|p| p.rows > 0)
+    {
+        script.push_str(
+            "-- WARNING: splitting a partition that holds rows moves them, fully logged and under\n\
+             -- a schema lock; split an empty partition (e.g. ahead of the data) when possible.\n",
+        );
+    }
+    script.push_str(&format!(
+        "ALTER PARTITION SCHEME {} NEXT USED {};\nGO\nALTER PARTITION FUNCTION {}() SPLIT RANGE ({});\nGO\n",
+        bracket(&layout.scheme),
+        bracket(&filegroup),
+        bracket(&layout.function),
+        literal
+    ));
+    Ok(script)
+}
+
+/// `ALTER PARTITION FUNCTION ... MERGE RANGE` removing the boundary `value`.
+fn merge_script(layout: &Layout, partitions: &ResultSet, value: &str) -> Result<String> {
+    let literal = boundary_literal(&layout.parameter_type, value)?;
+    let info = PartitionInfo::from_result_set(partitions);
+    let Some(index) = info.iter().position(|p| p.upper.as_deref() == Some(value)) else {
+        let existing = info
+            .iter()
+            .filter_map(|p| p.upper.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!(
+                "{} is not a boundary of {}; boundaries: {}",
+                value, layout.function, existing
+            ),
+        )
+        .into());
+    };
+    // The partitions on either side of the boundary become one.
+    let both_hold_rows = info[index..].iter().take(2).all(|p| p.rows > 0);
+
+    let mut script = header(layout);
+    if both_hold_rows {
+        script.push_str(
+            "-- WARNING: both partitions around this boundary hold rows; the merge moves one of\n\
+             -- them, fully logged and under a schema lock.\n",
+        );
+    }
+    script.push_str(&format!(
+        "ALTER PARTITION FUNCTION {}() MERGE RANGE ({});\nGO\n",
+        bracket(&layout.function),
+        literal
+    ));
+    Ok(script)
+}
+
+fn header(layout: &Layout) -> String {
+    let mut header = format!(
+        "-- Partition function {} (RANGE {}) on {}, scheme {}\n",
+        layout.function,
+        range_type(layout),
+        layout.column,
+        layout.scheme
+    );
+    if !layout.shared_with.is_empty() {
+        header.push_str(&format!(
+            "-- Also repartitions: {}\n",
+            layout.shared_with.join(", ")
+        ));
+    }
+    header
+}
+
+/// `value` as a SQL literal of the partition function's parameter type.
+fn boundary_literal(parameter_type: &str, value: &str) -> Result<String> {
+    let numeric = matches!(
+        parameter_type,
+        "tinyint" | "smallint" | "int" | "bigint" | "decimal" | "numeric" | "money" | "smallmoney"
+    );
+    if numeric {
+        if value.parse::<f64>().is_err() {
+            return Err(AppError::new(
+                ErrorKind::Config,
+                format!("'{}' is not a valid {} boundary", value, parameter_type),
+            )
+            .into());
+        }
+        return Ok(value.to_string());
+    }
+    let prefix = if matches!(parameter_type, "nchar" | "nvarchar") {
+        "N"
+    } else {
+        ""
+    };
+    Ok(format!("{}'{}'", prefix, value.replace('\'', "''")))
+}
+
+struct PartitionInfo {
+    lower: Option<String>,
+    upper: Option<String>,
+    rows: i64,
+    filegroup: String,
+}
+
+impl PartitionInfo {
+    fn from_result_set(partitions: &ResultSet) -> Vec<Self> {
+        let text = |value: Option<&Value>| match value {
+            Some(Value::Null) | None => None,
+            Some(value) => Some(value.as_csv()),
+        };
+        partitions
+            .rows
+            .iter()
+            .map(|row| PartitionInfo {
+                lower: text(row.get(1)),
+                upper: text(row.get(2)),
+                rows: match row.get(3) {
+                    Some(Value::Int(rows)) => *rows,
+                    _ => 0,
+                },
+                filegroup: text(row.get(5)).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn boundaries(&self) -> [Option<&str>; 2] {
+        [self.lower.as_deref(), self.upper.as_deref()]
+    }
+
+    /// Whether `value` falls between this partition's boundaries. Numbers compare numerically,
+    /// everything else as ISO text, which orders dates correctly.
+    fn contains(&self, value: &str) -> bool {
+        let after = |bound: &str| compare(bound, value).is_lt();
+        let before = |bound: &str| compare(value, bound).is_lt();
+        self.lower.as_deref().is_none_or(after) && self.upper.as_deref().is_none_or(before)
+    }
+}
+
+fn compare(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn layout() -> Layout {
+        Layout {
+            scheme: "psMonthly".to_string(),
+            function: "pfMonthly".to_string(),
+            column: "CreatedAt".to_string(),
+            range_right: true,
+            parameter_type: "date".to_string(),
+            next_used: None,
+            shared_with: vec!["[dbo].[EventsArchive]".to_string()],
+        }
+    }
+
+    fn partitions(rows: &[(Option<&str>, Option<&str>, i64)]) -> ResultSet {
+        let columns = [
+            "partition",
+            "lowerBoundary",
+            "upperBoundary",
+            "rows",
+            "compression",
+            "filegroup",
+        ];
+        let text = |value: Option<&str>| match value {
+            Some(v) => Value::Text(v.to_string()),
+            None => Value::Null,
+        };
+        ResultSet {
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: rows
+                .iter()
+                .enumerate()
+                .map(|(i, (lower, upper, count))| {
+                    vec![
+                        Value::Int(i as i64 + 1),
+                        text(*lower),
+                        text(*upper),
+                        Value::Int(*count),
+                        Value::Text("PAGE".to_string()),
+                        Value::Text("FG2024".to_string()),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn split_script_uses_next_filegroup_and_warns_about_rows() {
+        let rs = partitions(&[
+            (None, Some("2024-01-01"), 0),
+            (Some("2024-01-01"), Some("2024-02-01"), 10),
+            (Some("2024-02-01"), None, 5),
+        ]);
+        let script = split_script(&layout(), &rs, "2024-03-01", None).unwrap();
+        assert!(script.contains("-- Also repartitions: [dbo].[EventsArchive]\n"));
+        assert!(script.contains("-- WARNING: splitting"));
+        assert!(script.ends_with(
+            "ALTER PARTITION SCHEME [psMonthly] NEXT USED [FG2024];\nGO\n\
+             ALTER PARTITION FUNCTION [pfMonthly]() SPLIT RANGE ('2024-03-01');\nGO\n"
+        ));
+        assert!(split_script(&layout(), &rs, "2024-02-01", None).is_err());
+    }
+
+    #[test]
+    fn merge_script_requires_an_existing_boundary() {
+        let rs = partitions(&[(None, Some("2024-01-01"), 0), (Some("2024-01-01"), None, 0)]);
+        let script = merge_script(&layout(), &rs, "2024-01-01").unwrap();
+        assert!(!script.contains("WARNING"));
+        assert!(
+            script.ends_with(
+                "ALTER PARTITION FUNCTION [pfMonthly]() MERGE RANGE ('2024-01-01');\nGO\n"
+            )
+        );
+        assert!(merge_script(&layout(), &rs, "2023-12-01").is_err());
+    }
+
+    #[test]
+    fn boundary_literals_follow_the_parameter_type() {
+        assert_eq!(boundary_literal("int", "100").unwrap(), "100");
+        assert!(boundary_literal("bigint", "1e").is_err());
+        assert_eq!(
+            boundary_literal("nvarchar", "O'Neil").unwrap(),
+            "N'O''Neil'"
+        );
+        assert_eq!(
+            boundary_literal("datetime2", "2024-02-01T00:00:00").unwrap(),
+            "'2024-02-01T00:00:00'"
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use anyhow::Result;
+use serde_json::json;
+
+use crate::cli::{CliArgs, PermissionsArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::permissions::{self, Principal, Right, VIEW_SERVER_STATE};
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::{TableOptions, json as json_out, table};
+
+const VIEW_DEFINITION: Right = Right::DatabasePermission("VIEW DEFINITION");
+const VIEW_DATABASE_STATE: Right = Right::DatabasePermission("VIEW DATABASE STATE");
+const DATA_READER: Right = Right::DatabaseRole {
+    role: "db_datareader",
+    permission: "SELECT",
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Impact {
+    /// The command fails or returns nothing useful.
+    Blocks,
+    /// The command runs with partial output.
+    Degrades,
+}
+
+struct Requirement {
+    right: Right,
+    impact: Impact,
+    reason: &'static str,
+}
+
+const fn req(right: Right, impact: Impact, reason: &'static str) -> Requirement {
+    Requirement {
+        right,
+        impact,
+        reason,
+    }
+}
+
+/// What each command needs beyond CONNECT. Commands not listed need nothing extra.
+const REQUIREMENTS: &[(&str, &[Requirement])] = &[
+    (
+        "status",
+        &[req(
+            Right::ServerPermission(VIEW_SERVER_STATE),
+            Impact::Degrades,
+            "--deep host and memory sections",
+        )],
+    ),
+    (
+        "tables",
+        &[
+            req(
+                VIEW_DEFINITION,
+                Impact::Degrades,
+                "tables you have no other permission on are hidden",
+            ),
+            req(VIEW_DATABASE_STATE, Impact::Degrades, "row counts"),
+        ],
+    ),
+    (
+        "describe",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "module definitions and metadata of objects you do not own",
+        )],
+    ),
+    (
+        "databases",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "--create-script definitions of objects you do not own",
+        )],
+    ),
+    (
+        "columns",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "columns of objects you have no other permission on",
+        )],
+    ),
+    (
+        "table-data",
+        &[req(
+            DATA_READER,
+            Impact::Blocks,
+            "SELECT on tables and views",
+        )],
+    ),
+    (
+        "indexes",
+        &[
+            req(
+                Right::ServerPermission(VIEW_SERVER_STATE),
+                Impact::Degrades,
+                "index usage (seeks/updates)",
+            ),
+            req(
+                VIEW_DATABASE_STATE,
+                Impact::Degrades,
+                "columnstore row group health",
+            ),
+        ],
+    ),
+    (
+        "foreign-keys",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "relationships of objects you do not own",
+        )],
+    ),
+    (
+        "docs",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "tables, columns, and descriptions of objects you do not own",
+        )],
+    ),
+    (
+        "lint",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "--object definitions and column types of objects you do not own",
+        )],
+    ),
+    (
+        "fk-check",
+        &[req(DATA_READER, Impact::Blocks, "orphaned row counts")],
+    ),
+    (
+        "stored-procs",
+        &[
+            req(
+                VIEW_DEFINITION,
+                Impact::Degrades,
+                "procedures you have no EXECUTE permission on",
+            ),
+            req(
+                Right::ServerPermission(VIEW_SERVER_STATE),
+                Impact::Degrades,
+                "--history cached execution statistics",
+            ),
+            req(
+                VIEW_DATABASE_STATE,
+                Impact::Degrades,
+                "--history Query Store trend",
+            ),
+        ],
+    ),
+    (
+        "sessions",
+        &[req(
+            Right::ServerPermission(VIEW_SERVER_STATE),
+            Impact::Degrades,
+            "sessions other than your own",
+        )],
+    ),
+    (
+        "query-stats",
+        &[req(
+            Right::ServerPermission(VIEW_SERVER_STATE),
+            Impact::Blocks,
+            "plan cache statistics",
+        )],
+    ),
+    (
+        "trace",
+        &[req(
+            Right::ServerPermission("ALTER ANY EVENT SESSION"),
+            Impact::Blocks,
+            "creating and reading the event session",
+        )],
+    ),
+    (
+        "backups",
+        &[req(
+            Right::MsdbRole {
+                role: "db_datareader",
+                object: "backupset",
+            },
+            Impact::Blocks,
+            "backup history in msdb",
+        )],
+    ),
+    (
+        "errorlog",
+        &[req(
+            Right::ServerRole("securityadmin"),
+            Impact::Blocks,
+            "xp_readerrorlog",
+        )],
+    ),
+    (
+        "ag",
+        &[req(
+            Right::ServerPermission(VIEW_SERVER_STATE),
+            Impact::Blocks,
+            "availability group state DMVs",
+        )],
+    ),
+    (
+        "cdc",
+        &[
+            req(
+                Right::MsdbRole {
+                    role: "SQLAgentReaderRole",
+                    object: "sysjobs",
+                },
+                Impact::Degrades,
+                "capture/cleanup job state",
+            ),
+            req(
+                VIEW_DATABASE_STATE,
+                Impact::Degrades,
+                "log scan sessions and errors",
+            ),
+        ],
+    ),
+    (
+        "compare",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Blocks,
+            "module definitions on both connections",
+        )],
+    ),
+    (
+        "security",
+        &[req(
+            VIEW_DEFINITION,
+            Impact::Degrades,
+            "--missing-only permissions of other principals",
+        )],
+    ),
+    (
+        "verify",
+        &[req(DATA_READER, Impact::Blocks, "row counts and checksums")],
+    ),
+];
+
+pub fn run(args: &CliArgs, cmd: &PermissionsArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let selected: Vec<&(&str, &[Requirement])> = match cmd.command.as_deref() {
+        Some(name) => {
+            let entry = REQUIREMENTS
+                .iter()
+                .find(|(command, _)| command.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    AppError::new(
+                        ErrorKind::Config,
+                        format!(
+                            "No permission requirements known for '{}'. Known commands: {}",
+                            name,
+                            REQUIREMENTS
+                                .iter()
+                                .map(|(command, _)| *command)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    )
+                })?;
+            vec![entry]
+        }
+        None => REQUIREMENTS.iter().collect(),
+    };
+
+    let mut rights: Vec<Right> = Vec::new();
+    for (_, requirements) in &selected {
+        for requirement in requirements.iter() {
+            if !rights.contains(&requirement.right) {
+                rights.push(requirement.right);
+            }
+        }
+    }
+
+    let (principal, granted) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        permissions::check_rights(&mut client, &rights).await
+    })?;
+    let has = |right: &Right| {
+        rights
+            .iter()
+            .position(|r| r == right)
+            .is_some_and(|idx| granted[idx])
+    };
+
+    let mut grants: Vec<String> = Vec::new();
+    let mut commands = Vec::new();
+    let mut rows = Vec::new();
+    let mut blocked = false;
+    for (command, requirements) in &selected {
+        let mut status = "ok";
+        let mut items = Vec::new();
+        for requirement in requirements.iter() {
+            let ok = has(&requirement.right);
+            let grant = (!ok).then(|| requirement.right.grant_statement(&principal));
+            if let Some(statement) = &grant {
+                if !grants.contains(statement) {
+                    grants.push(statement.clone());
+                }
+                match requirement.impact {
+                    Impact::Blocks => status = "blocked",
+                    Impact::Degrades if status == "ok" => status = "degraded",
+                    Impact::Degrades => {}
+                }
+            }
+            rows.push(vec![
+                Value::Text(command.to_string()),
+                Value::Text(requirement.right.describe()),
+                Value::Bool(ok),
+                Value::Text(impact_label(requirement.impact).to_string()),
+                Value::Text(requirement.reason.to_string()),
+            ]);
+            items.push(json!({
+                "right": requirement.right.describe(),
+                "granted": ok,
+                "impact": impact_label(requirement.impact),
+                "reason": requirement.reason,
+                "grant": grant,
+            }));
+        }
+        blocked |= status == "blocked";
+        commands.push(json!({
+            "command": command,
+            "status": status,
+            "requirements": items,
+        }));
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "login": principal.login,
+            "user": principal.user,
+            "database": principal.database,
+            "sysadmin": principal.sysadmin,
+            "status": if blocked { "missing" } else { "ok" },
+            "commands": commands,
+            "grants": grants,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        print_text(&principal, rows, &grants, format);
+    }
+
+    if blocked {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+fn print_text(
+    principal: &Principal,
+    rows: Vec<Vec<Value>>,
+    grants: &[String],
+    format: OutputFormat,
+) {
+    println!(
+        "Login '{}' (user '{}' in {}){}",
+        principal.login,
+        principal.user,
+        principal.database,
+        if principal.sysadmin {
+            " is sysadmin"
+        } else {
+            ""
+        }
+    );
+    let rs = ResultSet {
+        columns: ["command", "right", "granted", "impact", "needed for"]
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: None,
+            })
+            .collect(),
+        rows,
+    };
+    let result = table::render_result_set_table(&rs, format, &TableOptions::default());
+    println!("{}", result.output);
+    if grants.is_empty() {
+        println!("All checked rights are granted.");
+        return;
+    }
+    println!("Ask an administrator to run:");
+    for grant in grants {
+        println!("  {}", grant);
+    }
+}
+
+fn impact_label(impact: Impact) -> &'static str {
+    match impact {
+        Impact::Blocks => "blocks",
+        Impact::Degrades => "degrades",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_are_unique_per_command() {
+        for (command, requirements) in REQUIREMENTS {
+            for (idx, requirement) in requirements.iter().enumerate() {
+                assert!(
+                    !requirements[..idx]
+                        .iter()
+                        .any(|r| r.right == requirement.right),
+                    "{} lists {} twice",
+                    command,
+                    requirement.right.describe()
+                );
+            }
+        }
+    }
+}
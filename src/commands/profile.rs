@@ -0,0 +1,616 @@
+//! `profile`: per-column data profile of one table.
+//!
+//! One scan computes null counts, distinct counts, min/max and average length for every
+//! column; a second query collects the most frequent values. Above `--exact-threshold` rows
+//! distinct counts use `APPROX_COUNT_DISTINCT` (HyperLogLog, SQL Server 2019+) and top values
+//! come from a `TABLESAMPLE` of roughly that many rows. Values are compared and reported as
+//! text so every column type fits one result shape.
+
+use std::io::IsTerminal;
+
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, ProfileArgs};
+use crate::commands::{common, object_lookup};
+use crate::config::{MaskStrategy, OutputFormat};
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, mask, table};
+
+/// Alias types resolve to their base type; CLR types keep their own name.
+const COLUMNS_SQL: &str = r#"
+SELECT
+    c.name,
+    CASE WHEN t.is_user_defined = 1 AND t.is_assembly_type = 0
+         THEN TYPE_NAME(c.system_type_id) ELSE t.name END AS type_name,
+    c.is_nullable
+FROM sys.columns c
+JOIN sys.types t ON t.user_type_id = c.user_type_id
+WHERE c.object_id = OBJECT_ID(@P1)
+ORDER BY c.column_id;
+"#;
+
+const TABLE_SQL: &str = r#"
+SELECT
+    (SELECT SUM(p.rows) FROM sys.partitions p
+     WHERE p.object_id = OBJECT_ID(@P1) AND p.index_id IN (0, 1)) AS estimated_rows,
+    CAST(SERVERPROPERTY('ProductMajorVersion') AS int) AS major_version,
+    CAST(SERVERPROPERTY('EngineEdition') AS int) AS engine_edition;
+"#;
+
+/// Value characters kept for min/max and top values.
+const VALUE_WIDTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Text,
+    Binary,
+    Temporal,
+    Float,
+    Bit,
+    Guid,
+    Other,
+    /// Types SQL Server cannot compare or group (LOBs, xml, spatial, CLR).
+    Uncomparable,
+}
+
+impl ColumnKind {
+    fn of(type_name: &str) -> Self {
+        match type_name.to_ascii_lowercase().as_str() {
+            "char" | "varchar" | "nchar" | "nvarchar" | "sysname" => ColumnKind::Text,
+            "binary" | "varbinary" | "timestamp" | "rowversion" => ColumnKind::Binary,
+            "date" | "time" | "datetime" | "datetime2" | "smalldatetime" | "datetimeoffset" => {
+                ColumnKind::Temporal
+            }
+            "float" | "real" => ColumnKind::Float,
+            "bit" => ColumnKind::Bit,
+            "uniqueidentifier" => ColumnKind::Guid,
+            "tinyint" | "smallint" | "int" | "bigint" | "decimal" | "numeric" | "money"
+            | "smallmoney" => ColumnKind::Other,
+            _ => ColumnKind::Uncomparable,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ProfiledColumn {
+    name: String,
+    data_type: String,
+    kind: ColumnKind,
+    nullable: bool,
+}
+
+/// What the server supports, which shapes the generated SQL.
+#[derive(Debug, Clone, Copy)]
+struct ServerCaps {
+    approx_distinct: bool,
+    /// `CONVERT` style 3 (17 significant digits) for floats needs SQL Server 2016.
+    float_style: u8,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Plan {
+    approximate: bool,
+    /// `TABLESAMPLE` percentage for top values, when the table is over the threshold.
+    sample_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ColumnStats {
+    null_count: i64,
+    distinct: Option<i64>,
+    min: Option<String>,
+    max: Option<String>,
+    avg_length: Option<f64>,
+    top: Vec<(String, i64)>,
+}
+
+pub fn run(args: &CliArgs, cmd: &ProfileArgs) -> Result<()> {
+    let table_raw = cmd.table.as_deref().ok_or_else(|| {
+        anyhow!("Missing table name. Use --table <name> or pass <TABLE> positionally.")
+    })?;
+    let (table_name, schema_from_name) = common::normalize_object_input(table_raw);
+
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let schema_hint = cmd.schema.as_deref().or(schema_from_name.as_deref());
+    let allow_prompt = !matches!(format, OutputFormat::Json)
+        && std::io::stdin().is_terminal()
+        && std::io::stderr().is_terminal();
+
+    let (schema, name, row_count, plan, profiled) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let (schema, name) = object_lookup::resolve_schema_for_object(
+                &mut client,
+                &resolved,
+                &table_name,
+                schema_hint,
+                object_lookup::LookupScope::TablesOnly,
+                "table",
+                allow_prompt,
+            )
+            .await?;
+            let qualified = format!("{}.{}", bracket(&schema), bracket(&name));
+
+            let mut query = Query::new(COLUMNS_SQL);
+            query.bind(qualified.as_str());
+            let columns = executor::run_query(query, &mut client).await?;
+            let columns = select_columns(
+                columns
+                    .first()
+                    .map(|rs| rs.rows.as_slice())
+                    .unwrap_or_default(),
+                &cmd.columns,
+            )?;
+
+            let mut query = Query::new(TABLE_SQL);
+            query.bind(qualified.as_str());
+            let info = executor::run_query(query, &mut client).await?;
+            let info_row = info.first().and_then(|rs| rs.rows.first());
+            let estimated_rows = info_row.and_then(|r| value_i64(r.first())).unwrap_or(0);
+            let major = info_row.and_then(|r| value_i64(r.get(1))).unwrap_or(0);
+            let edition = info_row.and_then(|r| value_i64(r.get(2))).unwrap_or(0);
+            // Azure SQL Database and Managed Instance report an old major version.
+            let cloud = matches!(edition, 5 | 8);
+            let caps = ServerCaps {
+                approx_distinct: major >= 15 || cloud,
+                float_style: if major >= 13 || cloud { 3 } else { 2 },
+            };
+            let over_threshold = estimated_rows.max(0) as u64 > cmd.exact_threshold;
+            let plan = Plan {
+                approximate: over_threshold && caps.approx_distinct,
+                sample_percent: over_threshold.then(|| {
+                    (cmd.exact_threshold as f64 / estimated_rows as f64 * 100.0).clamp(0.001, 100.0)
+                }),
+            };
+
+            let sets = executor::run_query(
+                Query::new(stats_sql(&qualified, &columns, plan, caps)),
+                &mut client,
+            )
+            .await?;
+            let stats_row = sets
+                .first()
+                .and_then(|rs| rs.rows.first())
+                .cloned()
+                .unwrap_or_default();
+            let row_count = value_i64(stats_row.first()).unwrap_or(0);
+            let mut stats: Vec<ColumnStats> = columns
+                .iter()
+                .enumerate()
+                .map(|(idx, _)| column_stats(&stats_row, idx))
+                .collect();
+
+            if let Some(sql) = top_values_sql(&qualified, &columns, cmd.top, plan, caps) {
+                let sets = executor::run_query(Query::new(sql), &mut client).await?;
+                for row in sets
+                    .first()
+                    .map(|rs| rs.rows.as_slice())
+                    .unwrap_or_default()
+                {
+                    let idx = value_i64(row.first()).unwrap_or(-1);
+                    if let Some(entry) = usize::try_from(idx).ok().and_then(|i| stats.get_mut(i)) {
+                        entry.top.push((
+                            value_text(row.get(1)).unwrap_or_default(),
+                            value_i64(row.get(2)).unwrap_or(0),
+                        ));
+                    }
+                }
+            }
+
+            let mut profiled: Vec<(ProfiledColumn, ColumnStats)> =
+                columns.into_iter().zip(stats).collect();
+            mask_stats(&mut profiled, &resolved.policy.mask, (&schema, &name));
+            Ok::<_, anyhow::Error>((schema, name, row_count, plan, profiled))
+        })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "table": { "schema": schema, "name": name },
+            "rowCount": row_count,
+            "distinctMethod": if plan.approximate { "approximate" } else { "exact" },
+            "topValues": {
+                "limit": cmd.top,
+                "sampled": plan.sample_percent.is_some(),
+                "samplePercent": plan.sample_percent,
+            },
+            "columns": profiled
+                .iter()
+                .map(|(col, stats)| json!({
+                    "name": col.name,
+                    "dataType": col.data_type,
+                    "nullable": col.nullable,
+                    "nullCount": stats.null_count,
+                    "nullPercent": null_percent(stats.null_count, row_count),
+                    "distinctCount": stats.distinct,
+                    "min": stats.min,
+                    "max": stats.max,
+                    "avgLength": stats.avg_length,
+                    "topValues": stats
+                        .top
+                        .iter()
+                        .map(|(value, count)| json!({ "value": value, "count": count }))
+                        .collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    let mut notes = Vec::new();
+    if plan.approximate {
+        notes.push("distinct counts approximate".to_string());
+    }
+    if let Some(percent) = plan.sample_percent.filter(|_| cmd.top > 0) {
+        notes.push(format!("top values from a {:.2}% sample", percent));
+    }
+    println!(
+        "{}.{}: {} rows{}\n",
+        schema,
+        name,
+        Value::Int(row_count).as_display(),
+        if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", notes.join("; "))
+        }
+    );
+    let result = table::render_result_set_table(
+        &profile_to_result_set(&profiled, row_count),
+        format,
+        &TableOptions::default(),
+    );
+    println!("{}", result.output);
+    Ok(())
+}
+
+fn select_columns(rows: &[Vec<Value>], wanted: &[String]) -> Result<Vec<ProfiledColumn>> {
+    let all: Vec<ProfiledColumn> = rows
+        .iter()
+        .map(|row| {
+            let data_type = value_text(row.get(1)).unwrap_or_default();
+            ProfiledColumn {
+                name: value_text(row.first()).unwrap_or_default(),
+                kind: ColumnKind::of(&data_type),
+                data_type,
+                nullable: matches!(row.get(2), Some(Value::Bool(true))),
+            }
+        })
+        .collect();
+    if wanted.is_empty() {
+        return Ok(all);
+    }
+    let unknown: Vec<&str> = wanted
+        .iter()
+        .filter(|w| !all.iter().any(|c| c.name.eq_ignore_ascii_case(w)))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Query,
+            format!("Unknown column(s): {}", unknown.join(", ")),
+        )
+        .into());
+    }
+    Ok(all
+        .into_iter()
+        .filter(|c| wanted.iter().any(|w| c.name.eq_ignore_ascii_case(w)))
+        .collect())
+}
+
+/// The expression grouped and compared for a column; bits and GUIDs have no MIN/MAX.
+fn comparable(col: &ProfiledColumn) -> String {
+    let name = bracket(&col.name);
+    match col.kind {
+        ColumnKind::Bit => format!("CAST({} AS int)", name),
+        ColumnKind::Guid => format!("CAST({} AS char(36))", name),
+        _ => name,
+    }
+}
+
+/// Render a comparable expression as bounded text.
+fn as_text(expr: &str, kind: ColumnKind, caps: ServerCaps) -> String {
+    match kind {
+        ColumnKind::Temporal => format!("CONVERT(nvarchar(40), {}, 126)", expr),
+        ColumnKind::Binary => format!("LEFT(CONVERT(varchar(max), {}, 1), {})", expr, VALUE_WIDTH),
+        ColumnKind::Float => format!("CONVERT(nvarchar(40), {}, {})", expr, caps.float_style),
+        ColumnKind::Text => format!("LEFT({}, {})", expr, VALUE_WIDTH),
+        _ => format!("CONVERT(nvarchar({}), {})", VALUE_WIDTH, expr),
+    }
+}
+
+/// One row: the row count, then nulls, distinct, min, max and average length per column.
+fn stats_sql(table: &str, columns: &[ProfiledColumn], plan: Plan, caps: ServerCaps) -> String {
+    let mut select = vec!["COUNT_BIG(*)".to_string()];
+    for col in columns {
+        let name = bracket(&col.name);
+        select.push(format!(
+            "SUM(CASE WHEN {} IS NULL THEN CAST(1 AS bigint) ELSE 0 END)",
+            name
+        ));
+        if col.kind == ColumnKind::Uncomparable {
+            select.push("CAST(NULL AS bigint)".to_string());
+            select.push("CAST(NULL AS nvarchar(1))".to_string());
+            select.push("CAST(NULL AS nvarchar(1))".to_string());
+        } else {
+            let input = comparable(col);
+            select.push(if plan.approximate {
+                format!("APPROX_COUNT_DISTINCT({})", input)
+            } else {
+                format!("COUNT_BIG(DISTINCT {})", input)
+            });
+            select.push(as_text(&format!("MIN({})", input), col.kind, caps));
+            select.push(as_text(&format!("MAX({})", input), col.kind, caps));
+        }
+        select.push(match col.kind {
+            ColumnKind::Text => format!("AVG(CAST(LEN({}) AS float))", name),
+            ColumnKind::Binary | ColumnKind::Uncomparable => {
+                format!("AVG(CAST(DATALENGTH({}) AS float))", name)
+            }
+            _ => "CAST(NULL AS float)".to_string(),
+        });
+    }
+    format!("SELECT\n    {}\nFROM {};", select.join(",\n    "), table)
+}
+
+/// Top values for every comparable column as `(column index, value, count)` rows.
+fn top_values_sql(
+    table: &str,
+    columns: &[ProfiledColumn],
+    top: u32,
+    plan: Plan,
+    caps: ServerCaps,
+) -> Option<String> {
+    if top == 0 {
+        return None;
+    }
+    let source = match plan.sample_percent {
+        Some(percent) => format!("{} TABLESAMPLE SYSTEM ({:.4} PERCENT)", table, percent),
+        None => table.to_string(),
+    };
+    let parts: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, col)| col.kind != ColumnKind::Uncomparable)
+        .map(|(idx, col)| {
+            let input = comparable(col);
+            format!(
+                "SELECT * FROM (\n    SELECT TOP ({top}) {idx} AS col_idx, {value} AS value, COUNT_BIG(*) AS cnt\n    FROM {source}\n    WHERE {input} IS NOT NULL\n    GROUP BY {input}\n    ORDER BY COUNT_BIG(*) DESC, {input}\n) t{idx}",
+                value = as_text(&input, col.kind, caps),
+            )
+        })
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}\nORDER BY col_idx, cnt DESC;",
+        parts.join("\nUNION ALL\n")
+    ))
+}
+
+fn column_stats(row: &[Value], idx: usize) -> ColumnStats {
+    let base = 1 + idx * 5;
+    ColumnStats {
+        null_count: value_i64(row.get(base)).unwrap_or(0),
+        distinct: value_i64(row.get(base + 1)),
+        min: value_text(row.get(base + 2)),
+        max: value_text(row.get(base + 3)),
+        avg_length: value_f64(row.get(base + 4)).map(|v| (v * 10.0).round() / 10.0),
+        top: Vec::new(),
+    }
+}
+
+/// Mask min, max, and top values of the columns a profile `mask` rule covers.
+fn mask_stats(
+    profiled: &mut [(ProfiledColumn, ColumnStats)],
+    rules: &[(String, MaskStrategy)],
+    source: (&str, &str),
+) {
+    for (col, stats) in profiled.iter_mut() {
+        let values = [stats.min.take(), stats.max.take()]
+            .into_iter()
+            .chain(stats.top.iter().map(|(value, _)| Some(value.clone())));
+        let mut rs = ResultSet {
+            columns: vec![Column {
+                name: col.name.clone(),
+                data_type: None,
+            }],
+            rows: values
+                .map(|value| vec![value.map_or(Value::Null, Value::Text)])
+                .collect(),
+        };
+        mask::apply(&mut rs, rules, Some(source));
+        let mut masked = rs.rows.into_iter().map(|row| value_text(row.first()));
+        stats.min = masked.next().flatten();
+        stats.max = masked.next().flatten();
+        for ((value, _), masked) in stats.top.iter_mut().zip(masked) {
+            *value = masked.unwrap_or_default();
+        }
+    }
+}
+
+fn null_percent(nulls: i64, rows: i64) -> f64 {
+    if rows <= 0 {
+        return 0.0;
+    }
+    (nulls as f64 / rows as f64 * 1000.0).round() / 10.0
+}
+
+fn profile_to_result_set(profiled: &[(ProfiledColumn, ColumnStats)], row_count: i64) -> ResultSet {
+    let columns = [
+        "column",
+        "type",
+        "nulls",
+        "distinct",
+        "min",
+        "max",
+        "avgLength",
+        "topValues",
+    ]
+    .iter()
+    .map(|name| Column {
+        name: name.to_string(),
+        data_type: None,
+    })
+    .collect();
+    let text = |v: &Option<String>| v.clone().map(Value::Text).unwrap_or(Value::Null);
+    let rows = profiled
+        .iter()
+        .map(|(col, stats)| {
+            vec![
+                Value::Text(col.name.clone()),
+                Value::Text(col.data_type.clone()),
+                Value::Text(format!("{}%", null_percent(stats.null_count, row_count))),
+                stats.distinct.map(Value::Int).unwrap_or(Value::Null),
+                text(&stats.min),
+                text(&stats.max),
+                stats.avg_length.map(Value::Float).unwrap_or(Value::Null),
+                Value::Text(
+                    stats
+                        .top
+                        .iter()
+                        .map(|(value, count)| format!("{} ({})", value, count))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+fn value_i64(value: Option<&Value>) -> Option<i64> {
+    match value {
+        Some(Value::Int(v)) => Some(*v),
+        Some(Value::Float(v)) => Some(*v as i64),
+        Some(Value::Decimal(v)) => v.to_string().parse().ok(),
+        Some(Value::Text(v)) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_f64(value: Option<&Value>) -> Option<f64> {
+    match value {
+        Some(Value::Float(v)) => Some(*v),
+        Some(Value::Int(v)) => Some(*v as f64),
+        Some(Value::Decimal(v)) => Some(v.to_f64()),
+        Some(Value::Text(v)) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_text(value: Option<&Value>) -> Option<String> {
+    match value {
+        None | Some(Value::Null) => None,
+        Some(other) => Some(other.as_csv()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn col(name: &str, data_type: &str) -> ProfiledColumn {
+        ProfiledColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            kind: ColumnKind::of(data_type),
+            nullable: true,
+        }
+    }
+
+    const CAPS: ServerCaps = ServerCaps {
+        approx_distinct: true,
+        float_style: 3,
+    };
+
+    #[test]
+    fn stats_sql_switches_to_approximate_distinct_and_skips_lobs() {
+        let columns = vec![
+            col("Email", "nvarchar"),
+            col("Active", "bit"),
+            col("Notes", "ntext"),
+        ];
+        let exact = Plan {
+            approximate: false,
+            sample_percent: None,
+        };
+        let sql = stats_sql("[dbo].[Customers]", &columns, exact, CAPS);
+        assert!(sql.contains("COUNT_BIG(DISTINCT [Email])"));
+        assert!(sql.contains("CONVERT(nvarchar(200), MIN(CAST([Active] AS int)))"));
+        assert!(sql.contains("AVG(CAST(DATALENGTH([Notes]) AS float))"));
+        assert!(!sql.contains("MIN([Notes])"));
+
+        let approx = Plan {
+            approximate: true,
+            sample_percent: Some(2.5),
+        };
+        let sql = stats_sql("[dbo].[Customers]", &columns, approx, CAPS);
+        assert!(sql.contains("APPROX_COUNT_DISTINCT([Email])"));
+
+        let top = top_values_sql("[dbo].[Customers]", &columns, 3, approx, CAPS).unwrap();
+        assert!(top.contains("TABLESAMPLE SYSTEM (2.5000 PERCENT)"));
+        assert_eq!(top.matches("UNION ALL").count(), 1);
+        assert!(top_values_sql("[dbo].[Customers]", &columns, 0, approx, CAPS).is_none());
+    }
+
+    #[test]
+    fn column_stats_reads_five_values_per_column() {
+        let row = vec![
+            Value::Int(10),
+            Value::Int(1),
+            Value::Int(4),
+            Value::Text("a".into()),
+            Value::Text("z".into()),
+            Value::Float(3.25),
+            Value::Int(10),
+            Value::Null,
+            Value::Null,
+            Value::Null,
+            Value::Null,
+        ];
+        let first = column_stats(&row, 0);
+        assert_eq!(first.distinct, Some(4));
+        assert_eq!(first.max.as_deref(), Some("z"));
+        assert_eq!(first.avg_length, Some(3.3));
+        let second = column_stats(&row, 1);
+        assert_eq!(second.null_count, 10);
+        assert_eq!(second.distinct, None);
+        assert_eq!(null_percent(1, 3), 33.3);
+        assert_eq!(null_percent(0, 0), 0.0);
+    }
+
+    #[test]
+    fn masked_columns_hide_min_max_and_top_values() {
+        let stats = ColumnStats {
+            min: Some("111-11-1111".into()),
+            max: Some("999-99-9999".into()),
+            top: vec![("555-55-5555".into(), 3)],
+            ..ColumnStats::default()
+        };
+        let mut profiled = vec![
+            (col("SSN", "varchar"), stats.clone()),
+            (col("City", "varchar"), stats),
+        ];
+        let rules = vec![("dbo.People.SSN".to_string(), MaskStrategy::Partial)];
+        mask_stats(&mut profiled, &rules, ("dbo", "People"));
+        assert_eq!(profiled[0].1.min.as_deref(), Some("*******1111"));
+        assert_eq!(profiled[0].1.max.as_deref(), Some("*******9999"));
+        assert_eq!(profiled[0].1.top, vec![("*******5555".to_string(), 3)]);
+        assert_eq!(profiled[1].1.min.as_deref(), Some("111-11-1111"));
+    }
+}
@@ -0,0 +1,338 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, ProfileCommand, ProfileEditArgs, ProfilesArgs};
+use crate::commands::common;
+use crate::config::{self, OutputFormat, ProfileField, ResolvedConfig};
+use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+pub fn run(args: &CliArgs, cmd: &ProfilesArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    match &cmd.command {
+        ProfileCommand::List => list_profiles(args, &resolved),
+        ProfileCommand::Add(edit) => add_profile(args, &resolved, edit),
+        ProfileCommand::Edit(edit) => edit_profile(args, &resolved, edit),
+        ProfileCommand::Remove(remove) => {
+            let path = editable_config_path(&resolved)?;
+            let content = read_config(&path)?;
+            let updated = config::remove_profile(&content, &remove.name).map_err(config_error)?;
+            write_config(&path, &updated)?;
+            emit_change(args, &resolved, "removed", &remove.name, &path)
+        }
+        ProfileCommand::Test(test) => test_profile(args, &resolved, test.name.as_deref()),
+    }
+}
+
+fn list_profiles(args: &CliArgs, resolved: &ResolvedConfig) -> Result<()> {
+    let format = common::output_format(args, resolved);
+    let (default_profile, mut profiles) = match &resolved.config_path {
+        Some(path) => {
+            let file = config::load_config_file(path).map_err(config_error)?;
+            (file.default_profile, file.profiles.into_iter().collect())
+        }
+        None => (None, Vec::new()),
+    };
+    profiles.sort_by(|a: &(String, config::Profile), b| a.0.cmp(&b.0));
+
+    if matches!(format, OutputFormat::Json) {
+        let items: Vec<_> = profiles
+            .iter()
+            .map(|(name, profile)| {
+                json!({
+                    "name": name,
+                    "isDefault": default_profile.as_deref() == Some(name.as_str()),
+                    "server": profile.server,
+                    "port": profile.port,
+                    "database": profile.database,
+                    "user": profile.user,
+                })
+            })
+            .collect();
+        let payload = json!({
+            "configPath": resolved.config_path.as_ref().map(|p| p.display().to_string()),
+            "defaultProfile": default_profile,
+            "profiles": items,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if profiles.is_empty() {
+        println!(
+            "No profiles configured. Add one with `sscli profiles add <name> --server <host>`."
+        );
+        return Ok(());
+    }
+
+    let rows = profiles
+        .iter()
+        .map(|(name, profile)| {
+            let marker = if default_profile.as_deref() == Some(name.as_str()) {
+                " (default)"
+            } else {
+                ""
+            };
+            let target = format!(
+                "{}:{}/{}",
+                profile.server.as_deref().unwrap_or("localhost"),
+                profile.port.unwrap_or(1433),
+                profile.database.as_deref().unwrap_or("master")
+            );
+            (format!("{}{}", name, marker), target)
+        })
+        .collect::<Vec<_>>();
+    let result = table::render_key_value_table("Profiles", &rows, format, &TableOptions::default());
+    println!("{}", result.output);
+    Ok(())
+}
+
+fn add_profile(args: &CliArgs, resolved: &ResolvedConfig, edit: &ProfileEditArgs) -> Result<()> {
+    if args.server.is_none() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "`profiles add` requires --server (and usually --database/--user)",
+        )
+        .into());
+    }
+
+    let path = match &resolved.config_path {
+        Some(path) => path.clone(),
+        None => PathBuf::from(".sql-server").join("config.yaml"),
+    };
+    ensure_yaml(&path)?;
+    let content = if path.exists() {
+        read_config(&path)?
+    } else {
+        String::new()
+    };
+
+    let fields = fields_from_args(args, edit);
+    let mut updated = config::add_profile(&content, &edit.name, &fields).map_err(config_error)?;
+    if edit.set_default || content.trim().is_empty() {
+        updated = config::set_default_profile(&updated, &edit.name);
+    }
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    write_config(&path, &updated)?;
+    emit_change(args, resolved, "added", &edit.name, &path)
+}
+
+fn edit_profile(args: &CliArgs, resolved: &ResolvedConfig, edit: &ProfileEditArgs) -> Result<()> {
+    let path = editable_config_path(resolved)?;
+    let content = read_config(&path)?;
+    let fields = fields_from_args(args, edit);
+    if fields.is_empty() && !edit.set_default {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "Nothing to change; pass --server/--database/--user/--password-env/... to update",
+        )
+        .into());
+    }
+
+    let mut updated =
+        config::update_profile(&content, &edit.name, &fields).map_err(config_error)?;
+    if edit.set_default {
+        updated = config::set_default_profile(&updated, &edit.name);
+    }
+    write_config(&path, &updated)?;
+    emit_change(args, resolved, "updated", &edit.name, &path)
+}
+
+fn test_profile(args: &CliArgs, resolved: &ResolvedConfig, name: Option<&str>) -> Result<()> {
+    let target = match name {
+        Some(name) => {
+            let exists = resolved
+                .config_path
+                .as_ref()
+                .map(|path| config::load_config_file(path))
+                .transpose()
+                .map_err(config_error)?
+                .is_some_and(|file| file.profiles.contains_key(name));
+            if !exists {
+                return Err(config_error(anyhow!("Profile '{}' not found", name)));
+            }
+            let mut overrides = common::overrides_from_args(args);
+            overrides.profile = Some(name.to_string());
+            config::load_from_system(&overrides).map_err(config_error)?
+        }
+        None => resolved.clone(),
+    };
+
+    let format = common::output_format(args, &target);
+    let started = Instant::now();
+    let result_sets = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&target.connection).await?;
+        let query = Query::new("SELECT @@SERVERNAME AS serverName, DB_NAME() AS currentDatabase");
+        executor::run_query(query, &mut client).await
+    })?;
+    let latency_ms = started.elapsed().as_millis();
+
+    let server_name = result_sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .and_then(|row| row.first())
+        .map(|v| v.as_display())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "profile": target.profile_name,
+            "status": "ok",
+            "latencyMs": latency_ms,
+            "server": target.connection.server,
+            "port": target.connection.port,
+            "database": target.connection.database,
+            "serverName": server_name,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&target))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    let rows = vec![
+        ("Profile".to_string(), target.profile_name.clone()),
+        ("Status".to_string(), "ok".to_string()),
+        ("LatencyMs".to_string(), latency_ms.to_string()),
+        (
+            "Target".to_string(),
+            format!(
+                "{}:{}/{}",
+                target.connection.server, target.connection.port, target.connection.database
+            ),
+        ),
+        ("ServerName".to_string(), server_name),
+    ];
+    let result =
+        table::render_key_value_table("Profile Test", &rows, format, &TableOptions::default());
+    println!("{}", result.output);
+    Ok(())
+}
+
+fn fields_from_args(args: &CliArgs, edit: &ProfileEditArgs) -> Vec<ProfileField> {
+    let mut fields = Vec::new();
+    if let Some(server) = &args.server {
+        fields.push(ProfileField::new("server", server.as_str()));
+    }
+    if let Some(port) = args.port {
+        fields.push(ProfileField::new("port", u64::from(port)));
+    }
+    if let Some(database) = &args.database {
+        fields.push(ProfileField::new("database", database.as_str()));
+    }
+    if let Some(user) = &args.user {
+        fields.push(ProfileField::new("user", user.as_str()));
+    }
+    if let Some(password_env) = &edit.password_env {
+        fields.push(ProfileField::new("passwordEnv", password_env.as_str()));
+    }
+    if let Some(password) = &args.password {
+        fields.push(ProfileField::new("password", password.as_str()));
+    }
+    if let Some(encrypt) = args.encrypt {
+        fields.push(ProfileField::new("encrypt", encrypt));
+    }
+    if let Some(trust_cert) = args.trust_cert {
+        fields.push(ProfileField::new("trustCert", trust_cert));
+    }
+    if let Some(timeout) = args.timeout_ms {
+        fields.push(ProfileField::new("timeout", timeout));
+    }
+    if let Some(schemas) = &edit.default_schemas {
+        let values = schemas
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| serde_yaml::Value::from(s.as_str()))
+            .collect::<Vec<_>>();
+        fields.push(ProfileField::new("defaultSchemas", values));
+    }
+    fields
+}
+
+fn editable_config_path(resolved: &ResolvedConfig) -> Result<PathBuf> {
+    let path = resolved.config_path.clone().ok_or_else(|| {
+        AppError::new(
+            ErrorKind::Config,
+            "No config file found; run `sscli init` or `sscli profiles add` first",
+        )
+    })?;
+    ensure_yaml(&path)?;
+    Ok(path)
+}
+
+fn ensure_yaml(path: &Path) -> Result<()> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => Ok(()),
+        _ => Err(AppError::new(
+            ErrorKind::Config,
+            format!(
+                "Profile editing supports YAML configs only: {}",
+                path.display()
+            ),
+        )
+        .into()),
+    }
+}
+
+fn read_config(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))
+}
+
+fn write_config(path: &Path, content: &str) -> Result<()> {
+    // Refuse to write something the loader would reject later.
+    serde_yaml::from_str::<config::ConfigFile>(content)
+        .map_err(|err| config_error(anyhow!("Edited config is not valid YAML: {}", err)))?;
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))
+}
+
+fn emit_change(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    action: &str,
+    name: &str,
+    path: &Path,
+) -> Result<()> {
+    if args.quiet {
+        return Ok(());
+    }
+    if matches!(common::output_format(args, resolved), OutputFormat::Json) {
+        let payload = json!({
+            "profile": name,
+            "action": action,
+            "configPath": path.display().to_string(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        println!("{}", body);
+    } else {
+        println!("Profile '{}' {} in {}", name, action, path.display());
+    }
+    Ok(())
+}
+
+fn config_error(err: anyhow::Error) -> anyhow::Error {
+    AppError::new(ErrorKind::Config, err.to_string()).into()
+}
@@ -1,4 +1,7 @@
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
 use serde_json::json;
 use tiberius::Query;
 
@@ -7,10 +10,20 @@ use crate::commands::common;
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
-use crate::output::{TableOptions, json as json_out, table};
+use crate::db::permissions;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, shape, table};
 
 const LIMIT_DEFAULT: u64 = 10;
 const LIMIT_MAX: u64 = 100;
+const EXPORT_LIMIT_DEFAULT: u64 = 100;
+const EXPORT_LIMIT_MAX: u64 = 1000;
+/// Layout version of `--export-workload` files; bump when a field changes meaning.
+const WORKLOAD_VERSION: u32 = 1;
+/// Share of plan cache memory held by single-use adhoc plans at which `--plan-cache`
+/// recommends `optimize for ad hoc workloads`.
+const AD_HOC_RECOMMEND_PERCENT: f64 = 25.0;
 
 pub fn run(args: &CliArgs, cmd: &QueryStatsArgs) -> Result<()> {
     let resolved = common::load_config(args)?;
@@ -28,6 +41,28 @@ pub fn run(args: &CliArgs, cmd: &QueryStatsArgs) -> Result<()> {
         _ => "qs.total_worker_time",
     };
 
+    if let Some(path) = cmd.export_workload.as_deref() {
+        let limit = common::parse_limit(cmd.limit, EXPORT_LIMIT_DEFAULT, EXPORT_LIMIT_MAX);
+        return export_workload(
+            args,
+            &resolved,
+            format,
+            path,
+            database.as_deref(),
+            &order_key,
+            order_column,
+            limit,
+        );
+    }
+
+    if cmd.plan_cache {
+        return plan_cache(args, &resolved, format, limit);
+    }
+
+    if cmd.procs {
+        return proc_stats(args, &resolved, format, cmd, &order_key, limit);
+    }
+
     let sql = format!(
         "\
 SELECT TOP (@P2)
@@ -52,20 +87,38 @@ ORDER BY {} DESC;\
         order_column
     );
 
-    let result_set = tokio::runtime::Runtime::new()?.block_on(async {
+    let (result_set, permission) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
+        let permission =
+            permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                .await?;
+        // dm_exec_query_stats fails outright without the grant; report nothing instead.
+        if !permission.granted {
+            return Ok::<_, anyhow::Error>((ResultSet::default(), permission));
+        }
         let mut query = Query::new(sql);
         query.bind(database.as_deref());
         query.bind(limit as i64);
         let result_sets = executor::run_query(query, &mut client).await?;
-        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+        Ok((
+            result_sets.into_iter().next().unwrap_or_default(),
+            permission,
+        ))
     })?;
 
+    let result_set = shape::apply(result_set, &args.output)?;
+    let mut warnings = Vec::new();
+    if !permission.granted {
+        warnings.push(permission.warning("plan cache statistics cannot be read"));
+    }
+
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
             "order": order_key,
             "database": database,
+            "degraded": !permission.granted,
             "queries": json_out::result_set_rows_to_objects(&result_set),
+            "warnings": warnings,
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
@@ -78,8 +131,690 @@ ORDER BY {} DESC;\
         return Ok(());
     }
 
-    let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
-    println!("{}", result.output);
+    if permission.granted {
+        let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    Ok(())
+}
+
+/// `--proc`: rank cached procedures, one row per procedure across its cached plans.
+fn proc_stats(
+    args: &CliArgs,
+    resolved: &crate::config::ResolvedConfig,
+    format: OutputFormat,
+    cmd: &QueryStatsArgs,
+    order_key: &str,
+    limit: u64,
+) -> Result<()> {
+    let order_column = match order_key {
+        "duration" => "SUM(ps.total_elapsed_time)",
+        "reads" => "SUM(ps.total_logical_reads)",
+        "executions" => "SUM(ps.execution_count)",
+        _ => "SUM(ps.total_worker_time)",
+    };
+    let (name, schema_from_name) = match cmd.name.as_deref() {
+        Some(n) => {
+            let (name, schema_opt) = common::normalize_object_input(n);
+            (Some(name), schema_opt)
+        }
+        None => (None, None),
+    };
+    let schema = cmd.schema.clone().or(schema_from_name);
+    let database = cmd.database.clone();
+
+    let sql = format!(
+        "\
+SELECT TOP (@P4)
+    DB_NAME(ps.database_id) AS databaseName,
+    OBJECT_SCHEMA_NAME(ps.object_id, ps.database_id) AS schemaName,
+    OBJECT_NAME(ps.object_id, ps.database_id) AS procName,
+    SUM(ps.execution_count) AS executionCount,
+    CAST(SUM(ps.total_worker_time) / 1000.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgCpuMs,
+    CAST(SUM(ps.total_elapsed_time) / 1000.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgDurationMs,
+    CAST(SUM(ps.total_logical_reads) * 1.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgLogicalReads,
+    SUM(ps.total_worker_time) AS totalWorkerTime,
+    SUM(ps.total_elapsed_time) AS totalElapsedTime,
+    MIN(ps.cached_time) AS cachedTime,
+    MAX(ps.last_execution_time) AS lastExecutionTime
+FROM sys.dm_exec_procedure_stats ps
+WHERE ps.database_id <> 32767
+  AND (@P1 IS NULL OR DB_NAME(ps.database_id) = @P1)
+GROUP BY ps.database_id, ps.object_id
+HAVING (@P2 IS NULL OR OBJECT_SCHEMA_NAME(ps.object_id, ps.database_id) = @P2)
+   AND (@P3 IS NULL OR OBJECT_NAME(ps.object_id, ps.database_id) LIKE @P3)
+ORDER BY {} DESC;\
+",
+        order_column
+    );
+
+    let (result_set, permission) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let permission =
+            permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                .await?;
+        if !permission.granted {
+            return Ok::<_, anyhow::Error>((ResultSet::default(), permission));
+        }
+        let mut query = Query::new(sql);
+        query.bind(database.as_deref());
+        query.bind(schema.as_deref());
+        query.bind(name.as_deref());
+        query.bind(limit as i64);
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok((
+            result_sets.into_iter().next().unwrap_or_default(),
+            permission,
+        ))
+    })?;
+
+    let result_set = shape::apply(result_set, &args.output)?;
+    let mut warnings = Vec::new();
+    if !permission.granted {
+        warnings.push(permission.warning("procedure statistics cannot be read"));
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "order": order_key,
+            "database": database,
+            "schema": schema,
+            "name": name,
+            "degraded": !permission.granted,
+            "procedures": json_out::result_set_rows_to_objects(&result_set),
+            "warnings": warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if permission.granted {
+        let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    Ok(())
+}
+
+/// Plan cache totals behind `--plan-cache`.
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlanCacheSummary {
+    plans: i64,
+    size_mb: f64,
+    single_use_adhoc_plans: i64,
+    /// Single-use adhoc plans as a percentage of all cached plans.
+    single_use_adhoc_percent: f64,
+    single_use_adhoc_mb: f64,
+    /// Their share of plan cache memory, which `optimize for ad hoc workloads` would free.
+    single_use_adhoc_memory_percent: f64,
+}
+
+impl PlanCacheSummary {
+    /// Totals from the per-object-type rows; only full adhoc plans count as single-use, since
+    /// with `optimize for ad hoc workloads` on a first execution caches a small stub instead.
+    fn from_cache_types(rs: &ResultSet) -> Self {
+        let column = |name: &str| rs.columns.iter().position(|c| c.name == name);
+        let (Some(obj), Some(cache), Some(plans), Some(single), Some(size), Some(single_size)) = (
+            column("objectType"),
+            column("cacheObjectType"),
+            column("plans"),
+            column("singleUsePlans"),
+            column("sizeMb"),
+            column("singleUseMb"),
+        ) else {
+            return Self::default();
+        };
+        let number = |row: &[Value], idx: usize| row[idx].as_f64().unwrap_or(0.0);
+        let mut summary = Self::default();
+        for row in &rs.rows {
+            summary.plans += number(row, plans) as i64;
+            summary.size_mb += number(row, size);
+            let adhoc = matches!(&row[obj], Value::Text(t) if t.eq_ignore_ascii_case("Adhoc"))
+                && matches!(&row[cache], Value::Text(t) if t.eq_ignore_ascii_case("Compiled Plan"));
+            if adhoc {
+                summary.single_use_adhoc_plans += number(row, single) as i64;
+                summary.single_use_adhoc_mb += number(row, single_size);
+            }
+        }
+        let percent = |part: f64, whole: f64| {
+            if whole > 0.0 {
+                (part * 1000.0 / whole).round() / 10.0
+            } else {
+                0.0
+            }
+        };
+        summary.single_use_adhoc_percent =
+            percent(summary.single_use_adhoc_plans as f64, summary.plans as f64);
+        summary.single_use_adhoc_memory_percent =
+            percent(summary.single_use_adhoc_mb, summary.size_mb);
+        summary.size_mb = (summary.size_mb * 10.0).round() / 10.0;
+        summary.single_use_adhoc_mb = (summary.single_use_adhoc_mb * 10.0).round() / 10.0;
+        summary
+    }
+
+    /// Recommend the option only when it is known to be off and would free enough memory.
+    fn recommend_optimize_for_ad_hoc(&self, enabled: Option<bool>) -> bool {
+        enabled == Some(false) && self.single_use_adhoc_memory_percent >= AD_HOC_RECOMMEND_PERCENT
+    }
+}
+
+/// `--plan-cache`: plan cache size by object type, single-use adhoc plans, and the largest
+/// current memory grants.
+fn plan_cache(
+    args: &CliArgs,
+    resolved: &crate::config::ResolvedConfig,
+    format: OutputFormat,
+    limit: u64,
+) -> Result<()> {
+    let sql = "\
+SELECT
+    cp.objtype AS objectType,
+    cp.cacheobjtype AS cacheObjectType,
+    COUNT_BIG(*) AS plans,
+    SUM(CASE WHEN cp.usecounts = 1 THEN CAST(1 AS bigint) ELSE 0 END) AS singleUsePlans,
+    CAST(SUM(CAST(cp.size_in_bytes AS bigint)) / 1048576.0 AS float) AS sizeMb,
+    CAST(SUM(CASE WHEN cp.usecounts = 1 THEN CAST(cp.size_in_bytes AS bigint) ELSE 0 END) / 1048576.0 AS float) AS singleUseMb
+FROM sys.dm_exec_cached_plans cp
+GROUP BY cp.objtype, cp.cacheobjtype
+ORDER BY sizeMb DESC;
+
+SELECT TOP (@P1)
+    mg.session_id AS sessionId,
+    DB_NAME(s.database_id) AS databaseName,
+    s.login_name AS loginName,
+    CASE WHEN mg.grant_time IS NULL THEN 'waiting' ELSE 'granted' END AS grantStatus,
+    mg.requested_memory_kb AS requestedKb,
+    mg.granted_memory_kb AS grantedKb,
+    mg.used_memory_kb AS usedKb,
+    mg.max_used_memory_kb AS maxUsedKb,
+    mg.ideal_memory_kb AS idealKb,
+    mg.dop AS dop,
+    mg.wait_time_ms AS waitTimeMs,
+    CAST(mg.query_cost AS float) AS queryCost,
+    LEFT(st.text, 4000) AS sqlText
+FROM sys.dm_exec_query_memory_grants mg
+LEFT JOIN sys.dm_exec_sessions s ON s.session_id = mg.session_id
+OUTER APPLY sys.dm_exec_sql_text(mg.sql_handle) st
+WHERE mg.session_id <> @@SPID
+ORDER BY COALESCE(mg.granted_memory_kb, mg.requested_memory_kb) DESC;\
+";
+    let config_sql = "\
+SELECT CAST(value_in_use AS int) AS valueInUse
+FROM sys.configurations
+WHERE name = N'optimize for ad hoc workloads';\
+";
+
+    let (cache_types, grants, enabled, permission) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let permission =
+                permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                    .await?;
+            // sys.configurations is readable without the grant; the setting is still reported.
+            let config = executor::run_query(Query::new(config_sql), &mut client).await?;
+            let enabled = config
+                .first()
+                .and_then(|rs| rs.rows.first())
+                .and_then(|row| row.first())
+                .and_then(Value::as_f64)
+                .map(|value| value != 0.0);
+            if !permission.granted {
+                return Ok::<_, anyhow::Error>((
+                    ResultSet::default(),
+                    ResultSet::default(),
+                    enabled,
+                    permission,
+                ));
+            }
+            let mut query = Query::new(sql);
+            query.bind(limit as i64);
+            let mut result_sets = executor::run_query(query, &mut client).await?.into_iter();
+            Ok((
+                result_sets.next().unwrap_or_default(),
+                result_sets.next().unwrap_or_default(),
+                enabled,
+                permission,
+            ))
+        })?;
+
+    let summary = permission
+        .granted
+        .then(|| PlanCacheSummary::from_cache_types(&cache_types));
+    let recommend = summary
+        .as_ref()
+        .is_some_and(|summary| summary.recommend_optimize_for_ad_hoc(enabled));
+    let mut warnings = Vec::new();
+    if !permission.granted {
+        warnings.push(permission.warning("plan cache and memory grants cannot be read"));
+    }
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "optimizeForAdHocWorkloads": enabled,
+            "recommendOptimizeForAdHoc": recommend,
+            "summary": summary,
+            "cacheByType": json_out::result_set_rows_to_objects(&cache_types),
+            "memoryGrants": json_out::result_set_rows_to_objects(&grants),
+            "degraded": !permission.granted,
+            "warnings": warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if let Some(summary) = &summary {
+        println!(
+            "Plan cache: {} plans, {} MB",
+            summary.plans, summary.size_mb
+        );
+        println!(
+            "Single-use adhoc plans: {} ({}% of plans), {} MB ({}% of memory)",
+            summary.single_use_adhoc_plans,
+            summary.single_use_adhoc_percent,
+            summary.single_use_adhoc_mb,
+            summary.single_use_adhoc_memory_percent
+        );
+    }
+    println!(
+        "optimize for ad hoc workloads: {}",
+        match enabled {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "unknown",
+        }
+    );
+    if permission.granted {
+        println!("\nBy object type:");
+        let result = table::render_result_set_table(&cache_types, format, &TableOptions::default());
+        println!("{}", result.output);
+        println!("\nMemory grants:");
+        if grants.rows.is_empty() {
+            println!("(no active memory grants)");
+        } else {
+            let result = table::render_result_set_table(&grants, format, &TableOptions::default());
+            println!("{}", result.output);
+        }
+    }
+    if recommend {
+        println!(
+            "\nRecommendation: single-use adhoc plans hold at least {}% of the plan cache; enable \
+             'optimize for ad hoc workloads' (EXEC sp_configure 'optimize for ad hoc workloads', 1; \
+             RECONFIGURE;).",
+            AD_HOC_RECOMMEND_PERCENT
+        );
+    }
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
     Ok(())
 }
+
+/// One cached statement in a replay workload.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkloadQuery {
+    id: usize,
+    database: Option<String>,
+    query_hash: Option<String>,
+    sql: String,
+    parameters: Vec<WorkloadParameter>,
+    execution_count: i64,
+    /// Executions per minute between first and last execution while the plan was cached.
+    executions_per_minute: Option<f64>,
+    first_seen: Option<String>,
+    last_seen: Option<String>,
+    duration_ms: Distribution,
+    cpu_ms: Distribution,
+    avg_logical_reads: f64,
+    avg_rows: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkloadParameter {
+    name: String,
+    r#type: String,
+    /// Literal the cached plan was compiled for, e.g. `(42)` or `N'abc'`.
+    compiled_value: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Distribution {
+    avg: f64,
+    min: f64,
+    max: f64,
+    last: f64,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn export_workload(
+    args: &CliArgs,
+    resolved: &crate::config::ResolvedConfig,
+    format: OutputFormat,
+    path: &Path,
+    database: Option<&str>,
+    order_key: &str,
+    order_column: &str,
+    limit: u64,
+) -> Result<()> {
+    let sql = format!(
+        "\
+WITH XMLNAMESPACES (DEFAULT 'http://schemas.microsoft.com/sqlserver/2004/07/showplan')
+SELECT TOP (@P2)
+    DB_NAME(st.dbid) AS databaseName,
+    CONVERT(varchar(18), qs.query_hash, 1) AS queryHash,
+    st.text AS batchText,
+    SUBSTRING(
+        st.text,
+        (qs.statement_start_offset/2) + 1,
+        ((CASE qs.statement_end_offset WHEN -1 THEN DATALENGTH(st.text) ELSE qs.statement_end_offset END - qs.statement_start_offset)/2) + 1
+    ) AS sqlText,
+    qs.execution_count AS executionCount,
+    qs.creation_time AS creationTime,
+    qs.last_execution_time AS lastExecutionTime,
+    DATEDIFF(SECOND, qs.creation_time, qs.last_execution_time) AS activeSeconds,
+    qs.total_elapsed_time AS totalElapsedTime,
+    qs.min_elapsed_time AS minElapsedTime,
+    qs.max_elapsed_time AS maxElapsedTime,
+    qs.last_elapsed_time AS lastElapsedTime,
+    qs.total_worker_time AS totalWorkerTime,
+    qs.min_worker_time AS minWorkerTime,
+    qs.max_worker_time AS maxWorkerTime,
+    qs.last_worker_time AS lastWorkerTime,
+    qs.total_logical_reads AS totalLogicalReads,
+    qs.total_rows AS totalRows,
+    compiled.parameters AS compiledParameters
+FROM sys.dm_exec_query_stats qs
+CROSS APPLY sys.dm_exec_sql_text(qs.sql_handle) st
+OUTER APPLY sys.dm_exec_query_plan(qs.plan_handle) qp
+OUTER APPLY (
+    SELECT STRING_AGG(
+        CONCAT(p.n.value('@Column', 'nvarchar(128)'), NCHAR(9), p.n.value('@ParameterCompiledValue', 'nvarchar(4000)')),
+        NCHAR(10)
+    ) AS parameters
+    FROM qp.query_plan.nodes('//ParameterList/ColumnReference') p(n)
+) compiled
+WHERE (@P1 IS NULL OR DB_NAME(st.dbid) = @P1)
+ORDER BY {} DESC;\
+",
+        order_column
+    );
+
+    let result_set = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let permission =
+            permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                .await?;
+        if !permission.granted {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                permission.warning("the workload cannot be captured"),
+            )
+            .into());
+        }
+        let mut query = Query::new(sql);
+        query.bind(database);
+        query.bind(limit as i64);
+        let result_sets = executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+    })?;
+
+    let queries: Vec<WorkloadQuery> = json_out::result_set_rows_to_objects(&result_set)
+        .iter()
+        .enumerate()
+        .map(|(idx, row)| workload_query(idx + 1, row))
+        .collect();
+    let workload = json!({
+        "format": "sscli-workload",
+        "version": WORKLOAD_VERSION,
+        "capturedAt": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "source": "sys.dm_exec_query_stats",
+        "database": database,
+        "order": order_key,
+        "queries": queries,
+    });
+    let body = serde_json::to_string_pretty(&workload)?;
+
+    if path == Path::new("-") {
+        println!("{}", body);
+        return Ok(());
+    }
+    std::fs::write(path, format!("{}\n", body))
+        .with_context(|| format!("Failed to write workload file {}", path.display()))?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "workloadPath": path.display().to_string(),
+            "queries": queries.len(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        println!(
+            "Workload written: {} ({} queries)",
+            path.display(),
+            queries.len()
+        );
+    }
+    Ok(())
+}
+
+fn workload_query(id: usize, row: &serde_json::Value) -> WorkloadQuery {
+    let text = |key: &str| row[key].as_str().map(str::to_string);
+    let number = |key: &str| row[key].as_f64().unwrap_or(0.0);
+    let executions = row["executionCount"].as_i64().unwrap_or(0);
+    let per_execution = |total: f64| {
+        if executions > 0 {
+            total / executions as f64
+        } else {
+            0.0
+        }
+    };
+    // Elapsed and worker times are reported in microseconds.
+    let micros = |prefix: &str| Distribution {
+        avg: per_execution(number(&format!("total{}", prefix))) / 1000.0,
+        min: number(&format!("min{}", prefix)) / 1000.0,
+        max: number(&format!("max{}", prefix)) / 1000.0,
+        last: number(&format!("last{}", prefix)) / 1000.0,
+    };
+    let active_seconds = number("activeSeconds");
+    let compiled = text("compiledParameters").unwrap_or_default();
+    WorkloadQuery {
+        id,
+        database: text("databaseName"),
+        query_hash: text("queryHash"),
+        sql: text("sqlText").unwrap_or_default(),
+        parameters: workload_parameters(&text("batchText").unwrap_or_default(), &compiled),
+        execution_count: executions,
+        executions_per_minute: (active_seconds > 0.0 && executions > 1)
+            .then(|| executions as f64 * 60.0 / active_seconds),
+        first_seen: text("creationTime"),
+        last_seen: text("lastExecutionTime"),
+        duration_ms: micros("ElapsedTime"),
+        cpu_ms: micros("WorkerTime"),
+        avg_logical_reads: per_execution(number("totalLogicalReads")),
+        avg_rows: per_execution(number("totalRows")),
+    }
+}
+
+/// Parameters declared by a parameterized batch, e.g. `(@id int,@name nvarchar(50))SELECT ...`
+/// from `sp_executesql` or forced parameterization, joined with the plan's compiled values
+/// (`name<TAB>value` lines).
+fn workload_parameters(batch_text: &str, compiled: &str) -> Vec<WorkloadParameter> {
+    let compiled_value = |name: &str| {
+        compiled.lines().find_map(|line| {
+            let (column, value) = line.split_once('\t')?;
+            column.eq_ignore_ascii_case(name).then(|| value.to_string())
+        })
+    };
+    let Some(declarations) = parameter_declarations(batch_text) else {
+        return Vec::new();
+    };
+    split_top_level(declarations)
+        .into_iter()
+        .filter_map(|declaration| {
+            let declaration = declaration.trim();
+            let (name, data_type) = declaration.split_once(char::is_whitespace)?;
+            if !name.starts_with('@') {
+                return None;
+            }
+            let data_type = data_type.trim();
+            let data_type = data_type
+                .strip_suffix(" OUTPUT")
+                .or_else(|| data_type.strip_suffix(" output"))
+                .unwrap_or(data_type)
+                .trim();
+            Some(WorkloadParameter {
+                name: name.to_string(),
+                r#type: data_type.to_string(),
+                compiled_value: compiled_value(name),
+            })
+        })
+        .collect()
+}
+
+/// The text inside a leading `( ... )` parameter list, if the batch has one.
+fn parameter_declarations(batch_text: &str) -> Option<&str> {
+    let text = batch_text.trim_start();
+    if !text.starts_with("(@") {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (idx, ch) in text.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[1..idx]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, ch) in list.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&list[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&list[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workload_parameters_join_declarations_with_compiled_values() {
+        let params = workload_parameters(
+            "(@id int,@total decimal(18,2),@name nvarchar(50) OUTPUT)SELECT * FROM t WHERE id = @id",
+            "@id\t(42)\n@name\tN'abc'",
+        );
+        assert_eq!(
+            params,
+            vec![
+                WorkloadParameter {
+                    name: "@id".into(),
+                    r#type: "int".into(),
+                    compiled_value: Some("(42)".into()),
+                },
+                WorkloadParameter {
+                    name: "@total".into(),
+                    r#type: "decimal(18,2)".into(),
+                    compiled_value: None,
+                },
+                WorkloadParameter {
+                    name: "@name".into(),
+                    r#type: "nvarchar(50)".into(),
+                    compiled_value: Some("N'abc'".into()),
+                },
+            ]
+        );
+        assert!(workload_parameters("SELECT (@@VERSION)", "").is_empty());
+    }
+
+    #[test]
+    fn plan_cache_summary_counts_only_full_adhoc_plans_as_single_use() {
+        let columns = [
+            "objectType",
+            "cacheObjectType",
+            "plans",
+            "singleUsePlans",
+            "sizeMb",
+            "singleUseMb",
+        ];
+        let row = |obj: &str, cache: &str, plans: i64, single: i64, size: f64, single_size: f64| {
+            vec![
+                Value::Text(obj.into()),
+                Value::Text(cache.into()),
+                Value::Int(plans),
+                Value::Int(single),
+                Value::Float(size),
+                Value::Float(single_size),
+            ]
+        };
+        let rs = ResultSet {
+            columns: columns
+                .iter()
+                .map(|name| crate::db::types::Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: vec![
+                row("Adhoc", "Compiled Plan", 600, 450, 300.0, 180.0),
+                row("Adhoc", "Compiled Plan Stub", 200, 200, 1.0, 1.0),
+                row("Proc", "Compiled Plan", 200, 20, 99.0, 9.0),
+            ],
+        };
+        let summary = PlanCacheSummary::from_cache_types(&rs);
+        assert_eq!(summary.plans, 1000);
+        assert_eq!(summary.size_mb, 400.0);
+        assert_eq!(summary.single_use_adhoc_plans, 450);
+        assert_eq!(summary.single_use_adhoc_percent, 45.0);
+        assert_eq!(summary.single_use_adhoc_memory_percent, 45.0);
+        assert!(summary.recommend_optimize_for_ad_hoc(Some(false)));
+        assert!(!summary.recommend_optimize_for_ad_hoc(Some(true)));
+        assert!(!summary.recommend_optimize_for_ad_hoc(None));
+        assert!(!PlanCacheSummary::default().recommend_optimize_for_ad_hoc(Some(false)));
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, ReplicationArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::{ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+const DATABASES_SQL: &str = r#"
+SELECT
+    name AS [database],
+    is_published AS isPublished,
+    is_merge_published AS isMergePublished,
+    is_subscribed AS isSubscribed,
+    is_distributor AS isDistributor
+FROM sys.databases
+WHERE is_published = 1 OR is_merge_published = 1 OR is_subscribed = 1 OR is_distributor = 1
+ORDER BY name;
+"#;
+
+// `{dist}` is replaced with the quoted distribution database name.
+const AGENTS_SQL: &str = r#"
+SELECT
+    md.publisher_db AS publisherDb,
+    md.publication AS publication,
+    CASE md.publication_type WHEN 0 THEN 'transactional' WHEN 1 THEN 'snapshot'
+        WHEN 2 THEN 'merge' END AS publicationType,
+    CASE md.agent_type WHEN 1 THEN 'snapshot' WHEN 2 THEN 'logreader'
+        WHEN 3 THEN 'distribution' WHEN 4 THEN 'merge' WHEN 9 THEN 'queuereader' END AS agentType,
+    md.agent_name AS agentName,
+    CASE md.status WHEN 1 THEN 'Started' WHEN 2 THEN 'Succeeded' WHEN 3 THEN 'In progress'
+        WHEN 4 THEN 'Idle' WHEN 5 THEN 'Retrying' WHEN 6 THEN 'Failed' END AS status,
+    md.warning AS warning,
+    md.cur_latency AS latencySec,
+    md.last_distsync AS lastSync
+FROM {dist}.dbo.MSreplication_monitordata md
+WHERE (@P1 IS NULL OR md.publication = @P1)
+ORDER BY md.publisher_db, md.publication, md.agent_type;
+"#;
+
+const SUBSCRIPTIONS_SQL: &str = r#"
+SELECT DISTINCT
+    p.publisher_db AS publisherDb,
+    p.publication AS publication,
+    COALESCE(srv.name, CAST(s.subscriber_id AS varchar(12))) AS subscriber,
+    s.subscriber_db AS subscriberDb,
+    CASE s.subscription_type WHEN 0 THEN 'push' WHEN 1 THEN 'pull' ELSE 'anonymous' END AS subscriptionType,
+    CASE s.status WHEN 0 THEN 'inactive' WHEN 1 THEN 'subscribed' WHEN 2 THEN 'active' END AS status
+FROM {dist}.dbo.MSsubscriptions s
+INNER JOIN {dist}.dbo.MSpublications p ON p.publication_id = s.publication_id
+LEFT JOIN sys.servers srv ON srv.server_id = s.subscriber_id
+WHERE s.subscriber_db IS NOT NULL
+  AND (@P1 IS NULL OR p.publication = @P1)
+ORDER BY publisherDb, publication, subscriber, subscriberDb;
+"#;
+
+struct ReplicationReport {
+    distributor: Option<String>,
+    databases: ResultSet,
+    agents: ResultSet,
+    subscriptions: ResultSet,
+}
+
+pub fn run(args: &CliArgs, cmd: &ReplicationArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let publication = cmd.publication.clone();
+
+    let report = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let databases = executor::run_query(Query::new(DATABASES_SQL), &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let distributor = distributor_name(&databases);
+
+        let mut report = ReplicationReport {
+            distributor: distributor.clone(),
+            databases,
+            agents: ResultSet::default(),
+            subscriptions: ResultSet::default(),
+        };
+        let Some(dist) = distributor else {
+            return Ok::<_, anyhow::Error>(report);
+        };
+
+        let dist = bracket(&dist);
+        for (sql, slot) in [
+            (AGENTS_SQL, &mut report.agents),
+            (SUBSCRIPTIONS_SQL, &mut report.subscriptions),
+        ] {
+            let sql = sql.replace("{dist}", &dist);
+            let mut query = Query::new(sql);
+            query.bind(publication.as_deref());
+            *slot = executor::run_query(query, &mut client)
+                .await?
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+        }
+        Ok(report)
+    })?;
+
+    let agents = json_out::result_set_rows_to_objects(&report.agents);
+    let problems = collect_problems(&agents);
+    let status = if problems.is_empty() {
+        "ok"
+    } else {
+        "degraded"
+    };
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "distributionDatabase": report.distributor,
+            "status": status,
+            "databases": json_out::result_set_rows_to_objects(&report.databases),
+            "agents": agents,
+            "subscriptions": json_out::result_set_rows_to_objects(&report.subscriptions),
+            "problems": problems,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+
+    if report.databases.rows.is_empty() {
+        println!("No published, subscribed, or distribution databases on this instance.");
+        return Ok(());
+    }
+
+    for (title, rs) in [
+        ("Replication Databases", &report.databases),
+        ("Agents", &report.agents),
+        ("Subscriptions", &report.subscriptions),
+    ] {
+        if rs.rows.is_empty() {
+            continue;
+        }
+        println!("{}", title);
+        let result = table::render_result_set_table(rs, format, &TableOptions::default());
+        println!("{}", result.output);
+    }
+    if report.distributor.is_none() {
+        println!("Distribution database is on another server; agent state is not available here.");
+    }
+    for problem in &problems {
+        eprintln!("Warning: {}", problem);
+    }
+    Ok(())
+}
+
+fn distributor_name(databases: &ResultSet) -> Option<String> {
+    let name_idx = databases
+        .columns
+        .iter()
+        .position(|c| c.name == "database")?;
+    let flag_idx = databases
+        .columns
+        .iter()
+        .position(|c| c.name == "isDistributor")?;
+    databases
+        .rows
+        .iter()
+        .find_map(|row| match row.get(flag_idx) {
+            Some(Value::Bool(true)) | Some(Value::Int(1)) => {
+                row.get(name_idx).map(Value::as_display)
+            }
+            _ => None,
+        })
+}
+
+fn collect_problems(agents: &[serde_json::Value]) -> Vec<String> {
+    agents
+        .iter()
+        .filter(|agent| {
+            matches!(agent["status"].as_str(), Some("Failed" | "Retrying"))
+                || agent["warning"].as_i64().is_some_and(|w| w != 0)
+        })
+        .map(|agent| {
+            format!(
+                "{} agent {} is {} (warning flags: {})",
+                agent["agentType"].as_str().unwrap_or("?"),
+                agent["agentName"].as_str().unwrap_or("?"),
+                agent["status"].as_str().unwrap_or("unknown"),
+                agent["warning"]
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn finds_local_distribution_database() {
+        let databases = ResultSet {
+            columns: vec![
+                Column {
+                    name: "database".to_string(),
+                    data_type: None,
+                },
+                Column {
+                    name: "isDistributor".to_string(),
+                    data_type: None,
+                },
+            ],
+            rows: vec![
+                vec![Value::Text("sales".to_string()), Value::Bool(false)],
+                vec![Value::Text("distribution".to_string()), Value::Bool(true)],
+            ],
+        };
+        assert_eq!(
+            distributor_name(&databases).as_deref(),
+            Some("distribution")
+        );
+    }
+
+    #[test]
+    fn failed_or_warning_agents_are_problems() {
+        let agents = vec![
+            json!({"agentType": "logreader", "agentName": "a", "status": "Idle", "warning": 0}),
+            json!({"agentType": "distribution", "agentName": "b", "status": "Failed", "warning": 0}),
+            json!({"agentType": "distribution", "agentName": "c", "status": "Idle", "warning": 2}),
+        ];
+        assert_eq!(collect_problems(&agents).len(), 2);
+    }
+}
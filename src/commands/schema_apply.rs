@@ -0,0 +1,549 @@
+//! `schema apply`: state-based deployment from a directory of CREATE scripts.
+//!
+//! The model is built in a scratch ("shadow") database on the target server, so the desired
+//! state is introspected by SQL Server itself rather than by parsing T-SQL. Both databases are
+//! then snapshotted and diffed by the compare engine, which renders the migration plan. Plans
+//! that drop objects or may lose data only execute with `--allow-destructive`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, SchemaApplyArgs};
+use crate::commands::compare_ignore::IgnoreRules;
+use crate::commands::{common, compare, confirm, sql_utils};
+use crate::config::{ConnectionSettings, OutputFormat, ResolvedConfig};
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::Value;
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::json as json_out;
+
+/// Gate building a model on the target server: the shadow database and every model batch are
+/// writes, so they need `--allow-write` and, on production profiles, a confirmation.
+pub(crate) fn allow_shadow(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    command: &str,
+    shadow: &str,
+    batches: usize,
+) -> Result<()> {
+    common::require_allow_write(
+        args,
+        &format!(
+            "{} builds its model in a scratch database on the target server",
+            command
+        ),
+    )?;
+    confirm::confirm_write(
+        args,
+        resolved,
+        &format!(
+            "create database {} and run {} in it",
+            bracket(shadow),
+            confirm::plural(batches, "model batch", "model batches")
+        ),
+    )
+}
+
+/// One GO-separated batch from the model directory, or one scripted dacpac element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModelBatch {
+    pub(crate) file: PathBuf,
+    pub(crate) index: usize,
+    pub(crate) sql: String,
+}
+
+pub fn run(args: &CliArgs, cmd: &SchemaApplyArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let batches = load_model(&cmd.dir)?;
+    let rules = IgnoreRules::load(None, &std::env::current_dir()?)?;
+    let shadow = cmd.shadow_database.clone().unwrap_or_else(shadow_name);
+    let progress = !args.quiet;
+    allow_shadow(args, &resolved, "schema apply", &shadow, batches.len())?;
+
+    let (plan, schemas) = tokio::runtime::Runtime::new()?.block_on(async {
+        let schemas = (!cmd.schemas.is_empty()).then(|| cmd.schemas.clone());
+        let (mut plan, schemas, missing) = with_shadow(
+            &resolved.connection,
+            &shadow,
+            &batches,
+            schemas,
+            progress,
+            async |client: &mut SqlClient, shadow_settings: &ConnectionSettings, schemas| {
+                let plan = compare::plan_apply(
+                    ("model", shadow_settings),
+                    (&resolved.profile_name, &resolved.connection),
+                    &schemas,
+                    cmd.include_drops,
+                    &rules,
+                    progress,
+                )
+                .await?;
+                let missing = missing_schemas(client, &schemas).await?;
+                Ok((plan, schemas, missing))
+            },
+        )
+        .await?;
+        if plan.has_drift() && !missing.is_empty() {
+            plan.script = format!("{}{}", create_schemas_script(&missing), plan.script);
+        }
+        Ok::<_, anyhow::Error>((plan, schemas))
+    })?;
+
+    let drifted = plan.has_drift();
+    let destructive = destructive_statements(&plan.script);
+    let todos = plan
+        .script
+        .lines()
+        .filter(|line| line.trim_start().starts_with("-- TODO"))
+        .count();
+    let refused = cmd.execute && !destructive.is_empty() && !cmd.allow_destructive;
+
+    if cmd.execute && drifted && !refused {
+        let action = format!(
+            "execute the planned script ({})",
+            confirm::plural(executable_batches(&plan.script).len(), "batch", "batches")
+        );
+        confirm::confirm_write(args, &resolved, &action)?;
+    }
+    let executed = if cmd.execute && drifted && !refused {
+        let plan_batches = executable_batches(&plan.script);
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            execute_plan(&mut client, &plan_batches).await
+        })?;
+        true
+    } else {
+        false
+    };
+
+    let script_to_stdout = cmd.script.as_deref() == Some(Path::new("-"));
+    if let Some(path) = cmd.script.as_deref().filter(|_| !script_to_stdout) {
+        fs::write(path, &plan.script)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    if script_to_stdout {
+        println!("{}", plan.script);
+    } else if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "target": resolved.profile_name,
+            "model": cmd.dir.display().to_string(),
+            "batches": batches.len(),
+            "schemas": schemas,
+            "drift": drifted,
+            "summary": plan.summary,
+            "destructive": destructive,
+            "todos": todos,
+            "executed": executed,
+            "refused": refused,
+            "script": if cmd.script.is_some() { None } else { Some(&plan.script) },
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if !drifted {
+            println!("Target already matches the model.");
+        } else {
+            println!("{}", plan.counts_table(format));
+            if cmd.script.is_none() && !executed {
+                println!("{}", plan.script);
+            }
+            if let Some(path) = &cmd.script {
+                println!("Wrote plan to {}", path.display());
+            }
+            if todos > 0 {
+                eprintln!(
+                    "Warning: the plan has {} TODO step{} that must be applied by hand",
+                    todos,
+                    if todos == 1 { "" } else { "s" }
+                );
+            }
+            if !destructive.is_empty() {
+                eprintln!("Destructive changes:");
+                for statement in &destructive {
+                    eprintln!("  {}", statement);
+                }
+            }
+            if executed {
+                println!("Plan applied to {}.", resolved.profile_name);
+            } else if refused {
+                eprintln!("Refusing to execute; re-run with --allow-destructive to apply anyway.");
+            } else if !cmd.execute {
+                println!("Dry run; re-run with --execute to apply.");
+            }
+        }
+    }
+
+    if refused {
+        ExitCode::Safety.exit();
+    }
+    Ok(())
+}
+
+/// A scratch database name unlikely to collide with real or concurrent ones.
+pub(crate) fn shadow_name() -> String {
+    format!(
+        "sscli_shadow_{}_{}",
+        chrono::Utc::now().format("%Y%m%d%H%M%S"),
+        std::process::id()
+    )
+}
+
+/// Read every `.sql` file under `dir` in path order and split it into batches.
+pub(crate) fn load_model(dir: &Path) -> Result<Vec<ModelBatch>> {
+    if !dir.is_dir() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!("Model directory not found: {}", dir.display()),
+        )
+        .into());
+    }
+    let mut files = Vec::new();
+    collect_sql_files(dir, &mut files)?;
+    files.sort();
+    let mut batches = Vec::new();
+    for file in files {
+        let text = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        batches.extend(
+            sql_utils::split_batches(&text)
+                .into_iter()
+                .filter(|sql| !sql.trim().is_empty())
+                .enumerate()
+                .map(|(index, sql)| ModelBatch {
+                    file: file.clone(),
+                    index: index + 1,
+                    sql,
+                }),
+        );
+    }
+    if batches.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!("No SQL batches found under {}", dir.display()),
+        )
+        .into());
+    }
+    Ok(batches)
+}
+
+fn collect_sql_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sql_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Build `batches` in a new `shadow` database on `target`'s server, run `work` against it, then
+/// drop it. `work` gets a connection to the target, the shadow's settings, and `schemas`, or the
+/// schemas the model created objects in when that is `None`.
+///
+/// The shadow database is dropped however the build ends: after `work`, on any error, and on
+/// Ctrl+C, which then exits with the interrupted code.
+pub(crate) async fn with_shadow<T>(
+    target: &ConnectionSettings,
+    shadow: &str,
+    batches: &[ModelBatch],
+    schemas: Option<Vec<String>>,
+    progress: bool,
+    work: impl AsyncFnOnce(&mut SqlClient, &ConnectionSettings, Vec<String>) -> Result<T>,
+) -> Result<T> {
+    let mut client = client::connect(target).await?;
+    create_shadow(&mut client, shadow).await?;
+    let shadow_settings = ConnectionSettings {
+        database: shadow.to_string(),
+        ..target.clone()
+    };
+    let built = async {
+        let mut shadow_client = client::connect(&shadow_settings).await?;
+        deploy_model(&mut shadow_client, batches, progress).await?;
+        let schemas = match schemas {
+            Some(schemas) => schemas,
+            None => model_schemas(&mut shadow_client).await?,
+        };
+        drop(shadow_client);
+        work(&mut client, &shadow_settings, schemas).await
+    };
+    // Dropping `built` on Ctrl+C closes the shadow connection, so the database can be dropped.
+    let outcome = tokio::select! {
+        result = built => Some(result),
+        _ = tokio::signal::ctrl_c() => None,
+    };
+    let Some(result) = outcome else {
+        // The target connection may have been mid-query; drop the shadow over a fresh one.
+        let dropped = match client::connect(target).await {
+            Ok(mut fresh) => drop_shadow(&mut fresh, shadow).await,
+            Err(err) => Err(err),
+        };
+        match dropped {
+            Ok(()) => eprintln!("Interrupted; dropped shadow database '{}'", shadow),
+            Err(err) => eprintln!("Interrupted; {:#}", err),
+        }
+        ExitCode::Interrupted.exit();
+    };
+    let dropped = drop_shadow(&mut client, shadow).await;
+    let value = result?;
+    dropped?;
+    Ok(value)
+}
+
+pub(crate) async fn create_shadow(client: &mut SqlClient, name: &str) -> Result<()> {
+    let mut query = Query::new(
+        "SELECT CAST(CASE WHEN DB_ID(@P1) IS NULL THEN 0 ELSE 1 END AS int) AS existsFlag, \
+         CAST(DATABASEPROPERTYEX(DB_NAME(), 'Collation') AS nvarchar(128)) AS collation;",
+    );
+    query.bind(name);
+    let rows = executor::run_query(query, client).await?;
+    let row = rows
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .cloned()
+        .unwrap_or_default();
+    if matches!(row.first(), Some(Value::Int(1))) {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            format!(
+                "Shadow database '{}' already exists; choose another with --shadow-database",
+                name
+            ),
+        )
+        .into());
+    }
+    // Match the target's collation so case sensitivity does not show up as drift.
+    let collation = row
+        .get(1)
+        .map(Value::as_display)
+        .filter(|c| c.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_'))
+        .filter(|c| !c.is_empty())
+        .map(|c| format!(" COLLATE {}", c))
+        .unwrap_or_default();
+    executor::run_query(
+        Query::new(format!("CREATE DATABASE {}{};", bracket(name), collation)),
+        client,
+    )
+    .await
+    .with_context(|| format!("Failed to create shadow database '{}'", name))?;
+    Ok(())
+}
+
+pub(crate) async fn drop_shadow(client: &mut SqlClient, name: &str) -> Result<()> {
+    let quoted = bracket(name);
+    executor::run_query(
+        Query::new(format!(
+            "IF DB_ID(N'{literal}') IS NOT NULL BEGIN \
+             ALTER DATABASE {quoted} SET SINGLE_USER WITH ROLLBACK IMMEDIATE; \
+             DROP DATABASE {quoted}; END",
+            literal = name.replace('\'', "''"),
+        )),
+        client,
+    )
+    .await
+    .with_context(|| format!("Failed to drop shadow database '{}'", name))?;
+    Ok(())
+}
+
+/// Run the model in the shadow database, retrying failed batches while others succeed so
+/// files do not have to be named in dependency order.
+pub(crate) async fn deploy_model(
+    client: &mut SqlClient,
+    batches: &[ModelBatch],
+    progress: bool,
+) -> Result<()> {
+    let mut pending: Vec<&ModelBatch> = batches.iter().collect();
+    let mut pass = 1;
+    loop {
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        for batch in &pending {
+            if let Err(err) = executor::run_query(Query::new(batch.sql.clone()), client).await {
+                failed.push(*batch);
+                errors.push(err.to_string());
+            }
+        }
+        if failed.is_empty() {
+            return Ok(());
+        }
+        if failed.len() == pending.len() {
+            let details = failed
+                .iter()
+                .zip(&errors)
+                .map(|(batch, error)| {
+                    format!(
+                        "  {} (batch {}): {}",
+                        batch.file.display(),
+                        batch.index,
+                        error
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "{} model batch{} failed to build:\n{}",
+                    failed.len(),
+                    if failed.len() == 1 { "" } else { "es" },
+                    details
+                ),
+            )
+            .into());
+        }
+        if progress {
+            eprintln!(
+                "model: pass {}: {} batch{} waiting on dependencies",
+                pass,
+                failed.len(),
+                if failed.len() == 1 { "" } else { "es" }
+            );
+        }
+        pending = failed;
+        pass += 1;
+    }
+}
+
+/// Schemas that contain user objects in the built model.
+pub(crate) async fn model_schemas(client: &mut SqlClient) -> Result<Vec<String>> {
+    let rows = executor::run_query(
+        Query::new(
+            "SELECT DISTINCT s.name FROM sys.objects o \
+             JOIN sys.schemas s ON s.schema_id = o.schema_id \
+             WHERE o.is_ms_shipped = 0 ORDER BY s.name;",
+        ),
+        client,
+    )
+    .await?;
+    Ok(rows
+        .first()
+        .map(|rs| {
+            rs.rows
+                .iter()
+                .filter_map(|row| row.first().map(Value::as_display))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn missing_schemas(client: &mut SqlClient, schemas: &[String]) -> Result<Vec<String>> {
+    let rows = executor::run_query(Query::new("SELECT name FROM sys.schemas;"), client).await?;
+    let existing: Vec<String> = rows
+        .first()
+        .map(|rs| {
+            rs.rows
+                .iter()
+                .filter_map(|row| row.first().map(Value::as_display))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(schemas
+        .iter()
+        .filter(|schema| !existing.iter().any(|e| e.eq_ignore_ascii_case(schema)))
+        .cloned()
+        .collect())
+}
+
+fn create_schemas_script(schemas: &[String]) -> String {
+    let mut lines = vec!["-- Creating schemas that exist only in the model".to_string()];
+    for schema in schemas {
+        lines.push(format!("CREATE SCHEMA {};", bracket(schema)));
+        lines.push("GO".to_string());
+    }
+    lines.push(String::new());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Statements that drop objects, and the compare engine's data-loss warnings.
+///
+/// Index drops are excluded: the plan only drops an index to recreate it or because
+/// `--include-drops` asked for it, and neither loses data.
+fn destructive_statements(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let upper = line.to_ascii_uppercase();
+            (upper.starts_with("DROP ") && !upper.starts_with("DROP INDEX"))
+                || upper.starts_with("-- WARNING:")
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Plan batches that contain more than comments.
+fn executable_batches(script: &str) -> Vec<String> {
+    sql_utils::split_batches(script)
+        .into_iter()
+        .filter(|batch| {
+            batch
+                .lines()
+                .any(|line| !line.trim().is_empty() && !line.trim_start().starts_with("--"))
+        })
+        .collect()
+}
+
+/// Run the plan in one transaction; any failure rolls every batch back.
+async fn execute_plan(client: &mut SqlClient, batches: &[String]) -> Result<()> {
+    executor::run_query(Query::new("SET XACT_ABORT ON; BEGIN TRANSACTION;"), client).await?;
+    for (idx, batch) in batches.iter().enumerate() {
+        if let Err(err) = executor::run_query(Query::new(batch.clone()), client).await {
+            let _ = executor::run_query(Query::new("IF @@TRANCOUNT > 0 ROLLBACK;"), client).await;
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Plan batch {} of {} failed; all changes were rolled back: {}",
+                    idx + 1,
+                    batches.len(),
+                    err
+                ),
+            )
+            .into());
+        }
+    }
+    executor::run_query(Query::new("COMMIT TRANSACTION;"), client).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_destructive_and_executable_statements() {
+        let script = "-- Dropping indexes that are redefined or removed\n\
+                      DROP INDEX IF EXISTS [IX_a] ON [dbo].[T];\n\
+                      GO\n\
+                      -- WARNING: [Name] shrinks from varchar(50) to varchar(20); longer values make the ALTER fail.\n\
+                      ALTER TABLE [dbo].[T] ALTER COLUMN [Name] varchar(20) NOT NULL;\n\
+                      GO\n\
+                      -- TODO: drop SYNONYM dbo.S manually\n\
+                      GO\n\
+                      DROP PROCEDURE IF EXISTS [dbo].[Old];\n\
+                      GO";
+        assert_eq!(
+            destructive_statements(script),
+            vec![
+                "-- WARNING: [Name] shrinks from varchar(50) to varchar(20); longer values make the ALTER fail.",
+                "DROP PROCEDURE IF EXISTS [dbo].[Old];",
+            ]
+        );
+        assert_eq!(executable_batches(script).len(), 3);
+    }
+}
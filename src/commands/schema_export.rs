@@ -0,0 +1,123 @@
+//! `schema export --dacpac`: package a database, or a model directory, for SSDT tooling.
+//!
+//! The package carries tables, keys, indexes and constraints only; see `dacpac` for why
+//! programmable objects are left out. A model directory is built in a shadow database first,
+//! exactly as `schema apply` does, so script-based projects can hand SqlPackage a dacpac.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::cli::{CliArgs, SchemaExportArgs};
+use crate::commands::{common, compare, dacpac, schema_apply};
+use crate::config::{ConnectionSettings, OutputFormat};
+use crate::db::client;
+use crate::db::session::SqlClient;
+use crate::output::json as json_out;
+
+pub fn run(args: &CliArgs, cmd: &SchemaExportArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let batches = cmd
+        .dir
+        .as_deref()
+        .map(schema_apply::load_model)
+        .transpose()?;
+    let shadow = cmd
+        .shadow_database
+        .clone()
+        .unwrap_or_else(schema_apply::shadow_name);
+    if let Some(batches) = &batches {
+        schema_apply::allow_shadow(args, &resolved, "schema export", &shadow, batches.len())?;
+    }
+    let progress = !args.quiet;
+
+    let (export, schemas) = tokio::runtime::Runtime::new()?.block_on(async {
+        if let Some(batches) = &batches {
+            let schemas = (!cmd.schemas.is_empty()).then(|| cmd.schemas.clone());
+            return schema_apply::with_shadow(
+                &resolved.connection,
+                &shadow,
+                batches,
+                schemas,
+                progress,
+                async |_: &mut SqlClient, shadow_settings: &ConnectionSettings, schemas| {
+                    let export =
+                        compare::export_snapshot("model", shadow_settings, &schemas, progress)
+                            .await?;
+                    Ok((export, schemas))
+                },
+            )
+            .await;
+        }
+        let schemas = if cmd.schemas.is_empty() {
+            let mut client = client::connect(&resolved.connection).await?;
+            schema_apply::model_schemas(&mut client).await?
+        } else {
+            cmd.schemas.clone()
+        };
+        let export = compare::export_snapshot(
+            &resolved.profile_name,
+            &resolved.connection,
+            &schemas,
+            progress,
+        )
+        .await?;
+        Ok::<_, anyhow::Error>((export, schemas))
+    })?;
+
+    let name = cmd
+        .dacpac
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "model".to_string());
+    let package = dacpac::write_package(&export, &name)?;
+    fs::write(&cmd.dacpac, package)
+        .with_context(|| format!("Failed to write {}", cmd.dacpac.display()))?;
+
+    let source = match &cmd.dir {
+        Some(dir) => dir.display().to_string(),
+        None => resolved.profile_name.clone(),
+    };
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "dacpac": cmd.dacpac.display().to_string(),
+            "source": source,
+            "schemas": schemas,
+            "tables": export.tables.len(),
+            "indexes": export.indexes.len(),
+            "foreignKeys": export.foreign_keys.len(),
+            "checks": export.checks.len(),
+            "skipped": export.skipped,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    println!(
+        "Wrote {} from {}: {} tables, {} keys and indexes, {} foreign keys, {} check constraints",
+        cmd.dacpac.display(),
+        source,
+        export.tables.len(),
+        export.indexes.len(),
+        export.foreign_keys.len(),
+        export.checks.len()
+    );
+    if !export.skipped.is_empty() {
+        let skipped = export
+            .skipped
+            .iter()
+            .map(|(kind, count)| format!("{} ({})", kind, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        eprintln!("Warning: not included in the package: {}", skipped);
+    }
+    Ok(())
+}
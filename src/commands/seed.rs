@@ -0,0 +1,733 @@
+//! `seed diff` / `seed apply`: keep lookup tables in sync with files of keyed rows.
+//!
+//! A seed file names a table, its key columns, and the rows it should hold:
+//!
+//! ```yaml
+//! table: dbo.OrderStatus
+//! key: [Code]
+//! rows:
+//!   - { Code: NEW, Name: New, SortOrder: 1 }
+//!   - { Code: SHIPPED, Name: Shipped, SortOrder: 2 }
+//! ```
+//!
+//! Rows are loaded into a temp table shaped like the target, so SQL Server converts the seed
+//! values to the column types and compares them (NULLs included) with `EXCEPT`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue, json};
+use tiberius::Query;
+
+use crate::cli::{CliArgs, SeedArgs, SeedCommand};
+use crate::commands::{common, confirm};
+use crate::config::OutputFormat;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Value};
+use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::json as json_out;
+
+const STAGING_TABLE: &str = "#sscli_seed";
+
+/// Rows per `INSERT ... VALUES` into the staging table (SQL Server's limit is 1000).
+const INSERT_CHUNK: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SeedFile {
+    table: String,
+    #[serde(deserialize_with = "one_or_many")]
+    key: Vec<String>,
+    /// Delete table rows whose key is not in the file (default true).
+    #[serde(default = "default_true")]
+    delete: bool,
+    #[serde(default)]
+    rows: Vec<serde_yaml::Mapping>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn one_or_many<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(key) => vec![key],
+        OneOrMany::Many(keys) => keys,
+    })
+}
+
+/// A validated seed: columns in file order and every row's values as T-SQL literals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Seed {
+    file: PathBuf,
+    schema: String,
+    table: String,
+    key: Vec<String>,
+    columns: Vec<String>,
+    delete: bool,
+    rows: Vec<Vec<String>>,
+}
+
+/// What a seed needs done to its table.
+#[derive(Debug, Clone, Default)]
+struct Delta {
+    inserts: Vec<Map<String, JsonValue>>,
+    /// `(key, {column: {from, to}})` per changed row.
+    updates: Vec<(Map<String, JsonValue>, Map<String, JsonValue>)>,
+    deletes: Vec<Map<String, JsonValue>>,
+}
+
+impl Delta {
+    fn changes(&self) -> usize {
+        self.inserts.len() + self.updates.len() + self.deletes.len()
+    }
+}
+
+pub fn run(args: &CliArgs, cmd: &SeedArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let apply = cmd.command == SeedCommand::Apply;
+    if apply && !args.allow_write {
+        return Err(AppError::new(
+            ErrorKind::Safety,
+            "seed apply changes table data; re-run with --allow-write (or use seed diff to preview)",
+        )
+        .into());
+    }
+    let default_schema = resolved
+        .connection
+        .default_schemas
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "dbo".to_string());
+    let seeds = load_seeds(&cmd.files, &default_schema)?;
+    if apply {
+        let action = format!(
+            "apply {}",
+            confirm::plural(seeds.len(), "seed file", "seed files")
+        );
+        confirm::confirm_write(args, &resolved, &action)?;
+    }
+
+    if apply && !args.quiet && !args.quiet_target {
+        eprintln!(
+            "Target: {}:{}/{}",
+            resolved.connection.server, resolved.connection.port, resolved.connection.database
+        );
+    }
+
+    let deltas = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        if apply {
+            executor::run_query(
+                Query::new("SET XACT_ABORT ON; BEGIN TRANSACTION;"),
+                &mut client,
+            )
+            .await?;
+        }
+        let mut deltas = Vec::new();
+        for seed in &seeds {
+            let outcome = async {
+                stage(&mut client, seed).await?;
+                let delta = compute_delta(&mut client, seed).await?;
+                if apply && delta.changes() > 0 {
+                    write_delta(&mut client, seed, &delta).await?;
+                }
+                Ok::<_, anyhow::Error>(delta)
+            }
+            .await
+            .with_context(|| format!("Seed {} ({})", seed.file.display(), seed.qualified()));
+            match outcome {
+                Ok(delta) => deltas.push(delta),
+                Err(err) => {
+                    if apply {
+                        let _ = executor::run_query(
+                            Query::new("IF @@TRANCOUNT > 0 ROLLBACK;"),
+                            &mut client,
+                        )
+                        .await;
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        if apply {
+            executor::run_query(Query::new("COMMIT TRANSACTION;"), &mut client).await?;
+        }
+        Ok::<_, anyhow::Error>(deltas)
+    })?;
+
+    let out_of_sync = deltas.iter().filter(|d| d.changes() > 0).count();
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "applied": apply,
+            "inSync": out_of_sync == 0,
+            "seeds": seeds.iter().zip(&deltas).map(|(seed, delta)| json!({
+                "file": seed.file.display().to_string(),
+                "table": seed.qualified(),
+                "key": seed.key,
+                "rows": seed.rows.len(),
+                "inserts": delta.inserts,
+                "updates": delta.updates.iter().map(|(key, changes)| json!({
+                    "key": key,
+                    "changes": changes,
+                })).collect::<Vec<_>>(),
+                "deletes": delta.deletes,
+            })).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        for (seed, delta) in seeds.iter().zip(&deltas) {
+            print_delta(seed, delta, apply);
+        }
+    }
+
+    if !apply && out_of_sync > 0 {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+impl Seed {
+    fn qualified(&self) -> String {
+        format!("{}.{}", self.schema, self.table)
+    }
+
+    fn target(&self) -> String {
+        format!("{}.{}", bracket(&self.schema), bracket(&self.table))
+    }
+
+    fn non_key_columns(&self) -> Vec<&String> {
+        self.columns
+            .iter()
+            .filter(|c| !self.key.iter().any(|k| k.eq_ignore_ascii_case(c)))
+            .collect()
+    }
+}
+
+fn print_delta(seed: &Seed, delta: &Delta, applied: bool) {
+    let verb = if applied { "applied" } else { "pending" };
+    if delta.changes() == 0 {
+        println!("{}: in sync ({} rows)", seed.qualified(), seed.rows.len());
+        return;
+    }
+    println!(
+        "{}: {} insert{}, {} update{}, {} delete{} {}",
+        seed.qualified(),
+        delta.inserts.len(),
+        if delta.inserts.len() == 1 { "" } else { "s" },
+        delta.updates.len(),
+        if delta.updates.len() == 1 { "" } else { "s" },
+        delta.deletes.len(),
+        if delta.deletes.len() == 1 { "" } else { "s" },
+        verb
+    );
+    for row in &delta.inserts {
+        println!("  + {}", format_row(row));
+    }
+    for (key, changes) in &delta.updates {
+        let detail = changes
+            .iter()
+            .map(|(column, change)| {
+                format!(
+                    "{}: {} -> {}",
+                    column,
+                    format_value(&change["from"]),
+                    format_value(&change["to"])
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("  ~ {}: {}", format_row(key), detail);
+    }
+    for row in &delta.deletes {
+        println!("  - {}", format_row(row));
+    }
+}
+
+fn format_row(row: &Map<String, JsonValue>) -> String {
+    row.iter()
+        .map(|(column, value)| format!("{}={}", column, format_value(value)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "NULL".to_string(),
+        JsonValue::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Seed files from the arguments, with directories expanded to the seed files they contain.
+fn load_seeds(paths: &[PathBuf], default_schema: &str) -> Result<Vec<Seed>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found = Vec::new();
+            for entry in
+                fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?
+            {
+                let file = entry?.path();
+                let is_seed = file
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        ["yaml", "yml", "json"]
+                            .iter()
+                            .any(|known| ext.eq_ignore_ascii_case(known))
+                    });
+                if file.is_file() && is_seed {
+                    found.push(file);
+                }
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    if files.is_empty() {
+        return Err(AppError::new(ErrorKind::Config, "No seed files found").into());
+    }
+    files
+        .into_iter()
+        .map(|file| {
+            let text = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            parse_seed(&file, &text, default_schema)
+        })
+        .collect()
+}
+
+fn parse_seed(file: &Path, text: &str, default_schema: &str) -> Result<Seed> {
+    let invalid = |message: String| -> anyhow::Error {
+        AppError::new(
+            ErrorKind::Config,
+            format!("Invalid seed file {}: {}", file.display(), message),
+        )
+        .into()
+    };
+    let parsed: SeedFile = serde_yaml::from_str(text).map_err(|err| invalid(err.to_string()))?;
+    let (table, schema) = common::normalize_object_input(parsed.table.trim());
+    if table.is_empty() {
+        return Err(invalid("`table` is empty".to_string()));
+    }
+    if parsed.key.is_empty() {
+        return Err(invalid("`key` needs at least one column".to_string()));
+    }
+
+    let mut columns: Vec<String> = Vec::new();
+    for name in parsed.rows.first().into_iter().flat_map(|row| row.keys()) {
+        match name.as_str() {
+            Some(name) => columns.push(name.to_string()),
+            None => return Err(invalid("column names must be strings".to_string())),
+        }
+    }
+    for key in &parsed.key {
+        if !parsed.rows.is_empty() && !columns.iter().any(|c| c.eq_ignore_ascii_case(key)) {
+            return Err(invalid(format!(
+                "key column '{}' is missing from the rows",
+                key
+            )));
+        }
+    }
+
+    let mut rows = Vec::with_capacity(parsed.rows.len());
+    for (idx, row) in parsed.rows.iter().enumerate() {
+        if row.len() != columns.len() {
+            return Err(invalid(format!(
+                "row {} has {} columns; every row needs the same columns as the first ({})",
+                idx + 1,
+                row.len(),
+                columns.join(", ")
+            )));
+        }
+        let mut literals = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let value = row
+                .get(column.as_str())
+                .ok_or_else(|| invalid(format!("row {} has no value for '{}'", idx + 1, column)))?;
+            let literal = sql_literal(value)
+                .ok_or_else(|| invalid(format!("row {} '{}' is not a scalar", idx + 1, column)))?;
+            if literal == "NULL" && parsed.key.iter().any(|k| k.eq_ignore_ascii_case(column)) {
+                return Err(invalid(format!("row {} key '{}' is null", idx + 1, column)));
+            }
+            literals.push(literal);
+        }
+        rows.push(literals);
+    }
+
+    Ok(Seed {
+        file: file.to_path_buf(),
+        schema: schema.unwrap_or_else(|| default_schema.to_string()),
+        table,
+        key: parsed.key,
+        columns,
+        delete: parsed.delete,
+        rows,
+    })
+}
+
+/// A seed scalar as a T-SQL literal; SQL Server converts it to the column type on staging.
+fn sql_literal(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::Null => Some("NULL".to_string()),
+        serde_yaml::Value::Bool(flag) => Some(if *flag { "1" } else { "0" }.to_string()),
+        serde_yaml::Value::Number(number) => Some(format!("N'{}'", number)),
+        serde_yaml::Value::String(text) => Some(format!("N'{}'", text.replace('\'', "''"))),
+        _ => None,
+    }
+}
+
+/// Create the staging table with the target's column types and load the seed rows into it.
+async fn stage(client: &mut SqlClient, seed: &Seed) -> Result<()> {
+    let mut check =
+        Query::new("SELECT CAST(CASE WHEN OBJECT_ID(@P1, N'U') IS NULL THEN 0 ELSE 1 END AS int);");
+    check.bind(seed.target());
+    let exists = executor::run_query(check, client).await?;
+    if !matches!(
+        exists
+            .first()
+            .and_then(|rs| rs.rows.first())
+            .and_then(|row| row.first()),
+        Some(Value::Int(1))
+    ) {
+        return Err(AppError::new(
+            ErrorKind::NotFound,
+            format!("Table {} not found", seed.qualified()),
+        )
+        .into());
+    }
+    if seed.columns.is_empty() {
+        return Ok(());
+    }
+    let columns = column_list(&seed.columns, "");
+    // The UNION ALL keeps SELECT ... INTO from copying an IDENTITY property to the staging table.
+    let create = format!(
+        "IF OBJECT_ID(N'tempdb..{staging}') IS NOT NULL DROP TABLE {staging};\n\
+         SELECT TOP (0) {columns} INTO {staging} FROM {target}\n\
+         UNION ALL SELECT TOP (0) {columns} FROM {target};",
+        staging = STAGING_TABLE,
+        columns = columns,
+        target = seed.target(),
+    );
+    executor::run_query(Query::new(create), client).await?;
+    for chunk in seed.rows.chunks(INSERT_CHUNK) {
+        let values = chunk
+            .iter()
+            .map(|row| format!("({})", row.join(", ")))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let insert = format!(
+            "INSERT INTO {} ({}) VALUES\n{};",
+            STAGING_TABLE, columns, values
+        );
+        executor::run_query(Query::new(insert), client).await?;
+    }
+    Ok(())
+}
+
+async fn compute_delta(client: &mut SqlClient, seed: &Seed) -> Result<Delta> {
+    let mut delta = Delta::default();
+    if seed.columns.is_empty() {
+        // An empty seed only ever deletes; there is nothing to stage.
+        if seed.delete {
+            let rows = executor::run_query(
+                Query::new(format!("SELECT * FROM {};", seed.target())),
+                client,
+            )
+            .await?;
+            delta.deletes = rows.first().map(row_maps).unwrap_or_default();
+        }
+        return Ok(delta);
+    }
+
+    let target = seed.target();
+    let matches = key_match(&seed.key, "t", "s");
+    let inserts = format!(
+        "SELECT {} FROM {} s WHERE NOT EXISTS (SELECT 1 FROM {} t WHERE {});",
+        column_list(&seed.columns, "s."),
+        STAGING_TABLE,
+        target,
+        matches
+    );
+    delta.inserts = first_rows(client, inserts).await?;
+
+    let changeable = seed.non_key_columns();
+    if !changeable.is_empty() {
+        let seed_values = changeable
+            .iter()
+            .map(|c| format!("s.{}", bracket(c)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let table_values = changeable
+            .iter()
+            .map(|c| format!("t.{}", bracket(c)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let updates = format!(
+            "SELECT {keys}, {seed_values}, {table_values} FROM {staging} s \
+             JOIN {target} t ON {matches} \
+             WHERE EXISTS (SELECT {seed_values} EXCEPT SELECT {table_values});",
+            keys = column_list(&seed.key, "s."),
+            staging = STAGING_TABLE,
+        );
+        let rows = executor::run_query(Query::new(updates), client).await?;
+        if let Some(rows) = rows.first() {
+            delta.updates = update_changes(rows, seed.key.len(), changeable.len());
+        }
+    }
+
+    if seed.delete {
+        let deletes = format!(
+            "SELECT {} FROM {} t WHERE NOT EXISTS (SELECT 1 FROM {} s WHERE {});",
+            column_list(&seed.columns, "t."),
+            target,
+            STAGING_TABLE,
+            matches
+        );
+        delta.deletes = first_rows(client, deletes).await?;
+    }
+    Ok(delta)
+}
+
+/// Delete, update, then insert, so replaced keys never collide with unique indexes mid-way.
+async fn write_delta(client: &mut SqlClient, seed: &Seed, delta: &Delta) -> Result<()> {
+    let target = seed.target();
+    if seed.columns.is_empty() {
+        executor::run_query(Query::new(format!("DELETE FROM {};", target)), client).await?;
+        return Ok(());
+    }
+    let matches = key_match(&seed.key, "t", "s");
+    if !delta.deletes.is_empty() {
+        let delete = format!(
+            "DELETE t FROM {} t WHERE NOT EXISTS (SELECT 1 FROM {} s WHERE {});",
+            target, STAGING_TABLE, matches
+        );
+        executor::run_query(Query::new(delete), client).await?;
+    }
+    let changeable = seed.non_key_columns();
+    if !delta.updates.is_empty() && !changeable.is_empty() {
+        let assignments = changeable
+            .iter()
+            .map(|c| format!("{} = s.{}", bracket(c), bracket(c)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let update = format!(
+            "UPDATE t SET {} FROM {} t JOIN {} s ON {} \
+             WHERE EXISTS (SELECT {} EXCEPT SELECT {});",
+            assignments,
+            target,
+            STAGING_TABLE,
+            matches,
+            changeable
+                .iter()
+                .map(|c| format!("s.{}", bracket(c)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            changeable
+                .iter()
+                .map(|c| format!("t.{}", bracket(c)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        executor::run_query(Query::new(update), client).await?;
+    }
+    if !delta.inserts.is_empty() {
+        let insert = format!(
+            "INSERT INTO {target} ({columns}) SELECT {source} FROM {staging} s \
+             WHERE NOT EXISTS (SELECT 1 FROM {target} t WHERE {matches});",
+            columns = column_list(&seed.columns, ""),
+            source = column_list(&seed.columns, "s."),
+            staging = STAGING_TABLE,
+        );
+        // Seeds that list an identity column supply its values explicitly.
+        let insert = format!(
+            "IF EXISTS (SELECT 1 FROM sys.columns WHERE object_id = OBJECT_ID(N'{literal}') \
+             AND is_identity = 1 AND name IN ({names}))\n\
+             BEGIN SET IDENTITY_INSERT {target} ON; {insert} SET IDENTITY_INSERT {target} OFF; END\n\
+             ELSE {insert}",
+            literal = target.replace('\'', "''"),
+            names = seed
+                .columns
+                .iter()
+                .map(|c| format!("N'{}'", c.replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        executor::run_query(Query::new(insert), client).await?;
+    }
+    Ok(())
+}
+
+async fn first_rows(client: &mut SqlClient, sql: String) -> Result<Vec<Map<String, JsonValue>>> {
+    let rows = executor::run_query(Query::new(sql), client).await?;
+    Ok(rows.first().map(row_maps).unwrap_or_default())
+}
+
+fn row_maps(rows: &ResultSet) -> Vec<Map<String, JsonValue>> {
+    rows.rows
+        .iter()
+        .map(|row| {
+            rows.columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.name.clone(), json_value(value)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Rows of `keys..., seed values..., table values...` as the key and the columns that differ.
+fn update_changes(
+    rows: &ResultSet,
+    key_count: usize,
+    value_count: usize,
+) -> Vec<(Map<String, JsonValue>, Map<String, JsonValue>)> {
+    rows.rows
+        .iter()
+        .map(|row| {
+            let key = rows.columns[..key_count]
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.name.clone(), json_value(value)))
+                .collect();
+            let mut changes = Map::new();
+            for idx in 0..value_count {
+                let to = &row[key_count + idx];
+                let from = &row[key_count + value_count + idx];
+                if to != from {
+                    changes.insert(
+                        rows.columns[key_count + idx].name.clone(),
+                        json!({ "from": json_value(from), "to": json_value(to) }),
+                    );
+                }
+            }
+            (key, changes)
+        })
+        .collect()
+}
+
+fn json_value(value: &Value) -> JsonValue {
+    serde_json::to_value(value).unwrap_or(JsonValue::Null)
+}
+
+fn key_match(key: &[String], left: &str, right: &str) -> String {
+    key.iter()
+        .map(|k| {
+            let column = bracket(k);
+            format!("{}.{} = {}.{}", left, column, right, column)
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn column_list(columns: &[String], prefix: &str) -> String {
+    columns
+        .iter()
+        .map(|c| format!("{}{}", prefix, bracket(c)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn parses_seed_files() {
+        let seed = parse_seed(
+            Path::new("status.yaml"),
+            "table: sales.OrderStatus\nkey: Code\nrows:\n  - { Code: NEW, Name: \"Won't ship\", Sort: 1, Active: true, Note: null }\n  - { Active: false, Note: x, Sort: 2.5, Name: Done, Code: DONE }\n",
+            "dbo",
+        )
+        .unwrap();
+        assert_eq!(seed.qualified(), "sales.OrderStatus");
+        assert_eq!(seed.key, vec!["Code"]);
+        assert!(seed.delete);
+        assert_eq!(seed.columns, vec!["Code", "Name", "Sort", "Active", "Note"]);
+        assert_eq!(
+            seed.rows[0],
+            vec!["N'NEW'", "N'Won''t ship'", "N'1'", "1", "NULL"]
+        );
+        assert_eq!(
+            seed.rows[1],
+            vec!["N'DONE'", "N'Done'", "N'2.5'", "0", "N'x'"]
+        );
+        assert_eq!(
+            seed.non_key_columns(),
+            vec!["Name", "Sort", "Active", "Note"]
+        );
+
+        let bare = parse_seed(
+            Path::new("t.yaml"),
+            "table: Types\nkey: [Id]\ndelete: false\nrows: []\n",
+            "app",
+        )
+        .unwrap();
+        assert_eq!(bare.target(), "[app].[Types]");
+        assert!(!bare.delete && bare.rows.is_empty());
+
+        for bad in [
+            "table: t\nkey: [Id]\nrows:\n  - { Name: a }\n",
+            "table: t\nkey: [Id]\nrows:\n  - { Id: 1 }\n  - { Id: 2, Name: b }\n",
+            "table: t\nkey: [Id]\nrows:\n  - { Id: null }\n",
+            "table: t\nkey: []\nrows: []\n",
+            "table: t\nkeys: [Id]\n",
+        ] {
+            assert!(
+                parse_seed(Path::new("bad.yaml"), bad, "dbo").is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn reports_changed_columns_only() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let rows = ResultSet {
+            columns: ["Code", "Name", "Sort", "Name", "Sort"]
+                .iter()
+                .map(|name| column(name))
+                .collect(),
+            rows: vec![vec![
+                Value::Text("NEW".to_string()),
+                Value::Text("New".to_string()),
+                Value::Int(1),
+                Value::Text("New".to_string()),
+                Value::Null,
+            ]],
+        };
+        let changes = update_changes(&rows, 1, 2);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0["Code"], "NEW");
+        assert_eq!(
+            JsonValue::Object(changes[0].1.clone()),
+            json!({ "Sort": { "from": null, "to": 1 } })
+        );
+        assert_eq!(
+            key_match(&["A".to_string(), "B]".to_string()], "t", "s"),
+            "t.[A] = s.[A] AND t.[B]]] = s.[B]]]"
+        );
+    }
+}
@@ -0,0 +1,651 @@
+//! `serve`: read-only JSON endpoints over HTTP, for dashboards that should not hold a SQL login.
+//!
+//! Every request runs this binary as a child (`sscli --json <command>`), so responses carry the
+//! CLI's JSON contracts and error objects unchanged, and one slow query never blocks the others.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::body::Bytes;
+use axum::error_handling::HandleErrorLayer;
+use axum::extract::rejection::{BytesRejection, PathRejection, QueryRejection};
+use axum::extract::{DefaultBodyLimit, Path, Query, Request, State};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use ring::digest;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::process::Command;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::{BoxError, ServiceBuilder};
+
+use crate::cli::{CliArgs, ServeArgs};
+use crate::commands::common;
+use crate::config::JsonContractVersion;
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::json as json_out;
+use crate::safety;
+
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Requests running at once; each holds a child process and its SQL connection.
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+/// Longest a request may take, child included; a child still running then is killed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+const TABLES_PARAMS: &[&str] = &[
+    "schema",
+    "like",
+    "limit",
+    "offset",
+    "includeViews",
+    "withCounts",
+];
+const DESCRIBE_PARAMS: &[&str] = &["schema", "type"];
+const QUERY_PARAMS: &[&str] = &["maxRows"];
+
+/// What every request needs; shared read-only across connections.
+#[derive(Debug)]
+struct Server {
+    token: String,
+    /// Profiles `?profile=` may name; the default profile is always served.
+    profiles: Vec<String>,
+    exe: PathBuf,
+    config_path: Option<PathBuf>,
+    default_profile: Option<String>,
+    contract: Option<JsonContractVersion>,
+    quiet: bool,
+}
+
+type Params = Vec<(String, String)>;
+
+/// A JSON response: the child's output, or an error object in the CLI's shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reply {
+    status: StatusCode,
+    body: String,
+}
+
+impl Reply {
+    fn error(status: StatusCode, kind: ErrorKind, message: &str) -> Self {
+        let body = json_out::emit_error_json(message, kind.as_str(), false)
+            .unwrap_or_else(|_| json!({ "error": { "message": message } }).to_string());
+        Self { status, body }
+    }
+}
+
+impl IntoResponse for Reply {
+    fn into_response(self) -> Response {
+        let mut response = (
+            self.status,
+            [(header::CONTENT_TYPE, "application/json")],
+            self.body,
+        )
+            .into_response();
+        if self.status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+pub fn run(args: &CliArgs, cmd: &ServeArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let settings = &resolved.settings.serve;
+    let token = std::env::var(&settings.token_env)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Config,
+                format!(
+                    "{} is not set; serve needs a bearer token for its clients",
+                    settings.token_env
+                ),
+            )
+        })?;
+    let profiles = served_profiles(&settings.profiles, &resolved.profile_name);
+    let address = format!(
+        "{}:{}",
+        cmd.bind.as_deref().unwrap_or(&settings.bind),
+        cmd.port.unwrap_or(settings.port)
+    );
+    let server = Arc::new(Server {
+        token,
+        profiles,
+        exe: std::env::current_exe().context("Failed to locate the sscli executable")?,
+        config_path: resolved.config_path.clone(),
+        default_profile: args.profile.clone(),
+        contract: args.contract,
+        quiet: args.quiet,
+    });
+
+    tokio::runtime::Runtime::new()?.block_on(async move {
+        let listener = TcpListener::bind(&address).await.map_err(|err| {
+            AppError::new(
+                ErrorKind::Config,
+                format!("Cannot listen on {}: {}", address, err),
+            )
+        })?;
+        if !server.quiet {
+            eprintln!(
+                "Serving http://{} (profile {}; Ctrl+C to stop)",
+                listener.local_addr()?,
+                resolved.profile_name
+            );
+        }
+        axum::serve(listener, router(server))
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+        Ok::<_, anyhow::Error>(())
+    })?;
+    ExitCode::Interrupted.exit();
+}
+
+/// Endpoints plus the limits every request runs under: body size, requests in flight, and
+/// total time. `/health` is the only route that skips the token check.
+fn router(server: Arc<Server>) -> Router {
+    Router::new()
+        .route("/tables", get(tables))
+        .route("/describe/{object}", get(describe))
+        .route("/query", post(query))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&server),
+            require_token,
+        ))
+        .route("/health", get(health))
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(timed_out))
+                .timeout(REQUEST_TIMEOUT)
+                .layer(GlobalConcurrencyLimitLayer::new(MAX_CONCURRENT_REQUESTS)),
+        )
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&server),
+            log_request,
+        ))
+        .with_state(server)
+}
+
+async fn log_request(State(server): State<Arc<Server>>, request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let label = format!("{} {}", request.method(), request.uri().path());
+    let response = next.run(request).await;
+    if !server.quiet {
+        eprintln!(
+            "{} -> {} ({} ms)",
+            label,
+            response.status().as_u16(),
+            started.elapsed().as_millis()
+        );
+    }
+    response
+}
+
+async fn require_token(
+    State(server): State<Arc<Server>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, Reply> {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token.trim(), &server.token));
+    if !authorized {
+        return Err(Reply::error(
+            StatusCode::UNAUTHORIZED,
+            ErrorKind::Auth,
+            "Missing or invalid bearer token",
+        ));
+    }
+    Ok(next.run(request).await)
+}
+
+async fn timed_out(err: BoxError) -> Reply {
+    if err.is::<tower::timeout::error::Elapsed>() {
+        Reply::error(
+            StatusCode::GATEWAY_TIMEOUT,
+            ErrorKind::Query,
+            &format!(
+                "Request did not finish within {} s",
+                REQUEST_TIMEOUT.as_secs()
+            ),
+        )
+    } else {
+        Reply::error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorKind::Internal,
+            &err.to_string(),
+        )
+    }
+}
+
+async fn not_found(uri: Uri) -> Reply {
+    Reply::error(
+        StatusCode::NOT_FOUND,
+        ErrorKind::NotFound,
+        &format!(
+            "No endpoint {}; use /health, /tables, /describe/{{object}}, or POST /query",
+            uri.path()
+        ),
+    )
+}
+
+async fn method_not_allowed(method: Method, uri: Uri) -> Reply {
+    let expected = if uri.path() == "/query" {
+        "POST"
+    } else {
+        "GET"
+    };
+    Reply::error(
+        StatusCode::METHOD_NOT_ALLOWED,
+        ErrorKind::Config,
+        &format!(
+            "{} {} is not supported; use {}",
+            method,
+            uri.path(),
+            expected
+        ),
+    )
+}
+
+async fn health() -> Reply {
+    Reply {
+        status: StatusCode::OK,
+        body: json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }).to_string(),
+    }
+}
+
+async fn tables(
+    State(server): State<Arc<Server>>,
+    params: Result<Query<Params>, QueryRejection>,
+) -> Result<Reply, Reply> {
+    let params = params.map_err(|err| bad_request(&err.body_text()))?.0;
+    let argv = command_args(TABLES_PARAMS, vec!["tables".to_string()], &params)?;
+    run_command(&server, &params, argv, None).await
+}
+
+async fn describe(
+    State(server): State<Arc<Server>>,
+    object: Result<Path<String>, PathRejection>,
+    params: Result<Query<Params>, QueryRejection>,
+) -> Result<Reply, Reply> {
+    let object = object.map_err(|err| bad_request(&err.body_text()))?.0;
+    let params = params.map_err(|err| bad_request(&err.body_text()))?.0;
+    let argv = command_args(
+        DESCRIBE_PARAMS,
+        vec!["describe".to_string(), "--".to_string(), object],
+        &params,
+    )?;
+    run_command(&server, &params, argv, None).await
+}
+
+async fn query(
+    State(server): State<Arc<Server>>,
+    params: Result<Query<Params>, QueryRejection>,
+    headers: HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+) -> Result<Reply, Reply> {
+    let params = params.map_err(|err| bad_request(&err.body_text()))?.0;
+    let body =
+        body.map_err(|err| Reply::error(err.status(), ErrorKind::Config, &err.body_text()))?;
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let (sql, max_rows) = query_body(&body, is_json)?;
+    if let Err(err) = safety::validate_read_only(&sql) {
+        return Err(Reply::error(
+            StatusCode::FORBIDDEN,
+            ErrorKind::Safety,
+            &err.to_string(),
+        ));
+    }
+    let mut argv = command_args(
+        QUERY_PARAMS,
+        vec!["sql".to_string(), "--stdin".to_string()],
+        &params,
+    )?;
+    if let Some(max_rows) =
+        max_rows.or_else(|| param(&params, "maxRows").and_then(|value| value.parse().ok()))
+    {
+        argv.push(format!("--max-rows={}", max_rows));
+    }
+    run_command(&server, &params, argv, Some(sql)).await
+}
+
+fn bad_request(message: &str) -> Reply {
+    Reply::error(StatusCode::BAD_REQUEST, ErrorKind::Config, message)
+}
+
+fn param<'a>(params: &'a Params, name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// The CLI arguments (after the global ones) for an endpoint: `argv` plus the query
+/// parameters in `allowed`. `profile` is handled by `run_command`; anything else is refused.
+fn command_args(
+    allowed: &[&str],
+    mut argv: Vec<String>,
+    params: &Params,
+) -> Result<Vec<String>, Reply> {
+    for (key, value) in params {
+        if key == "profile" {
+            continue;
+        }
+        if !allowed.contains(&key.as_str()) {
+            return Err(bad_request(&format!(
+                "Unknown parameter '{}'; this endpoint takes {}",
+                key,
+                allowed.join(", ")
+            )));
+        }
+        match key.as_str() {
+            "maxRows" => {}
+            "includeViews" | "withCounts" => {
+                if matches!(value.as_str(), "" | "1" | "true") {
+                    argv.push(if key == "includeViews" {
+                        "--include-views".to_string()
+                    } else {
+                        "--with-counts".to_string()
+                    });
+                }
+            }
+            _ => argv.insert(1, format!("--{}={}", key, value)),
+        }
+    }
+    Ok(argv)
+}
+
+/// SQL from a `POST /query` body: `{"sql": "...", "maxRows": n}` or the raw statement text.
+fn query_body(body: &[u8], is_json: bool) -> Result<(String, Option<u64>), Reply> {
+    let text = std::str::from_utf8(body).map_err(|_| bad_request("Request body is not UTF-8"))?;
+    let (sql, max_rows) = if is_json {
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|err| bad_request(&format!("Invalid JSON body: {}", err)))?;
+        (
+            value["sql"].as_str().unwrap_or_default().to_string(),
+            value["maxRows"].as_u64(),
+        )
+    } else {
+        (text.to_string(), None)
+    };
+    if sql.trim().is_empty() {
+        return Err(bad_request(
+            "POST /query needs SQL in the body (text, or JSON with \"sql\")",
+        ));
+    }
+    Ok((sql, max_rows))
+}
+
+/// Run `sscli --json` with the server's globals, the `?profile=` choice, and `command`.
+async fn run_command(
+    server: &Server,
+    params: &Params,
+    command: Vec<String>,
+    stdin: Option<String>,
+) -> Result<Reply, Reply> {
+    let profile = match param(params, "profile") {
+        Some(name) if !server.profiles.iter().any(|p| p == name) => {
+            return Err(Reply::error(
+                StatusCode::NOT_FOUND,
+                ErrorKind::NotFound,
+                &format!("Profile '{}' is not served", name),
+            ));
+        }
+        Some(name) => Some(name.to_string()),
+        None => server.default_profile.clone(),
+    };
+    let mut argv = vec!["--json".to_string(), "--quiet-target".to_string()];
+    if let Some(path) = &server.config_path {
+        argv.push(format!("--config={}", path.display()));
+    }
+    if let Some(profile) = profile {
+        argv.push(format!("--profile={}", profile));
+    }
+    if let Some(contract) = server.contract {
+        let version = if contract == JsonContractVersion::V2 {
+            "v2"
+        } else {
+            "v1"
+        };
+        argv.push(format!("--contract={}", version));
+    }
+    argv.extend(command);
+    run_child(server, &argv, stdin).await
+}
+
+async fn run_child(
+    server: &Server,
+    argv: &[String],
+    stdin: Option<String>,
+) -> Result<Reply, Reply> {
+    let mut child = Command::new(&server.exe)
+        .args(argv)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| {
+            Reply::error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorKind::Internal,
+                &format!("Failed to start sscli: {}", err),
+            )
+        })?;
+    if let (Some(sql), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        let _ = pipe.write_all(sql.as_bytes()).await;
+    }
+    let output = child.wait_with_output().await.map_err(|err| {
+        Reply::error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorKind::Internal,
+            &err.to_string(),
+        )
+    })?;
+    let code = output.status.code().unwrap_or(1);
+    if code == 0 {
+        return Ok(Reply {
+            status: StatusCode::OK,
+            body: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        });
+    }
+    // The error object is the last thing the child writes to stderr.
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let body = stderr
+        .trim()
+        .rsplit_once("\n{")
+        .map(|(_, rest)| format!("{{{}", rest))
+        .unwrap_or_else(|| stderr.trim().to_string());
+    let status = status_for_exit(code);
+    if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+        Err(Reply { status, body })
+    } else {
+        Err(Reply::error(status, ErrorKind::Internal, &body))
+    }
+}
+
+fn status_for_exit(code: i32) -> StatusCode {
+    match code {
+        2 => StatusCode::BAD_REQUEST,
+        4 => StatusCode::NOT_FOUND,
+        5 | 6 => StatusCode::BAD_GATEWAY,
+        7 => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Profiles `?profile=` may pick: the `settings.serve.profiles` allowlist, or only the profile
+/// `serve` started with when there is none, so a token never reaches profiles nobody listed.
+fn served_profiles(allowlist: &[String], active: &str) -> Vec<String> {
+    if allowlist.is_empty() {
+        vec![active.to_string()]
+    } else {
+        allowlist.to_vec()
+    }
+}
+
+/// Compare SHA-256 digests of both sides byte by byte without stopping early, so neither the
+/// position of the first difference nor the token's length shows in the timing.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    let given = digest::digest(&digest::SHA256, given.as_bytes());
+    let expected = digest::digest(&digest::SHA256, expected.as_bytes());
+    given
+        .as_ref()
+        .iter()
+        .zip(expected.as_ref())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    fn params(pairs: &[(&str, &str)]) -> Params {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn test_server() -> Arc<Server> {
+        Arc::new(Server {
+            token: "secret".to_string(),
+            profiles: vec!["prod".to_string()],
+            exe: PathBuf::from("/nonexistent/sscli"),
+            config_path: None,
+            default_profile: None,
+            contract: None,
+            quiet: true,
+        })
+    }
+
+    async fn send(request: axum::http::Request<Body>) -> StatusCode {
+        router(test_server())
+            .oneshot(request)
+            .await
+            .expect("infallible")
+            .status()
+    }
+
+    fn request(
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Body,
+    ) -> axum::http::Request<Body> {
+        let mut builder = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn routes_check_the_token_method_and_body() {
+        let health = request("GET", "/health", None, Body::empty());
+        assert_eq!(send(health).await, StatusCode::OK);
+        let anonymous = request("GET", "/tables", None, Body::empty());
+        assert_eq!(send(anonymous).await, StatusCode::UNAUTHORIZED);
+        let wrong = request("GET", "/tables", Some("secreT"), Body::empty());
+        assert_eq!(send(wrong).await, StatusCode::UNAUTHORIZED);
+        let missing = request("GET", "/nowhere", Some("secret"), Body::empty());
+        assert_eq!(send(missing).await, StatusCode::NOT_FOUND);
+        let method = request("GET", "/query", Some("secret"), Body::empty());
+        assert_eq!(send(method).await, StatusCode::METHOD_NOT_ALLOWED);
+        let write = request(
+            "POST",
+            "/query",
+            Some("secret"),
+            Body::from("DELETE FROM dbo.Orders"),
+        );
+        assert_eq!(send(write).await, StatusCode::FORBIDDEN);
+        let huge = request(
+            "POST",
+            "/query",
+            Some("secret"),
+            Body::from(vec![b' '; MAX_BODY_BYTES + 1]),
+        );
+        assert_eq!(send(huge).await, StatusCode::PAYLOAD_TOO_LARGE);
+        let unserved = request("GET", "/tables?profile=dev", Some("secret"), Body::empty());
+        assert_eq!(send(unserved).await, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn builds_cli_arguments_per_endpoint() {
+        let tables = params(&[
+            ("schema", "sales"),
+            ("includeViews", "true"),
+            ("profile", "prod"),
+        ]);
+        assert_eq!(
+            command_args(TABLES_PARAMS, vec!["tables".to_string()], &tables).unwrap(),
+            vec![
+                "tables".to_string(),
+                "--schema=sales".to_string(),
+                "--include-views".to_string()
+            ]
+        );
+        let unknown = params(&[("database", "master")]);
+        assert_eq!(
+            command_args(TABLES_PARAMS, vec!["tables".to_string()], &unknown)
+                .unwrap_err()
+                .status,
+            StatusCode::BAD_REQUEST
+        );
+
+        assert_eq!(
+            query_body(br#"{"sql": "SELECT 1", "maxRows": 5}"#, true).unwrap(),
+            ("SELECT 1".to_string(), Some(5))
+        );
+        assert_eq!(
+            query_body(b"SELECT 1", false).unwrap(),
+            ("SELECT 1".to_string(), None)
+        );
+        assert!(query_body(br#"{"maxRows": 5}"#, true).is_err());
+    }
+
+    #[test]
+    fn maps_exit_codes_and_compares_tokens() {
+        assert_eq!(status_for_exit(4), StatusCode::NOT_FOUND);
+        assert_eq!(status_for_exit(7), StatusCode::FORBIDDEN);
+        assert_eq!(status_for_exit(1), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+        assert!(!tokens_match("", "secret"));
+    }
+
+    #[test]
+    fn only_the_active_profile_is_served_without_an_allowlist() {
+        assert_eq!(served_profiles(&[], "dev"), vec!["dev".to_string()]);
+        let allowlist = vec!["prod".to_string(), "stage".to_string()];
+        assert_eq!(served_profiles(&allowlist, "dev"), allowlist);
+    }
+}
@@ -7,24 +7,23 @@ use crate::commands::common;
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
-use crate::output::{TableOptions, json as json_out, table};
+use crate::db::permissions;
+use crate::db::types::ResultSet;
+use crate::output::{TableOptions, json as json_out, shape, table};
 
 const LIMIT_DEFAULT: u64 = 20;
 const LIMIT_MAX: u64 = 200;
+/// `--sort` keys that rank `--resource-usage` rows on the server, heaviest first.
+const RESOURCE_SORTS: &[(&str, &str)] = &[
+    ("cpu", "s.cpu_time"),
+    ("reads", "s.logical_reads"),
+    ("writes", "s.writes"),
+    ("memory", "s.memory_usage"),
+    ("tempdb", "tempdbKb"),
+    ("transaction-age", "transactionAgeSeconds"),
+];
 
-pub fn run(args: &CliArgs, cmd: &SessionsArgs) -> Result<()> {
-    let resolved = common::load_config(args)?;
-    let format = common::output_format(args, &resolved);
-
-    let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
-    let database = cmd.database.clone();
-    let login = cmd.login.clone();
-    let host = cmd.host.clone();
-    let status = cmd.status.clone();
-
-    let result_set = tokio::runtime::Runtime::new()?.block_on(async {
-        let mut client = client::connect(&resolved.connection).await?;
-        let sql = r#"
+const SESSIONS_SQL: &str = r#"
 SELECT TOP (@P5)
     s.session_id AS sessionId,
     s.login_name AS loginName,
@@ -47,16 +46,115 @@ WHERE s.is_user_process = 1
   AND (@P4 IS NULL OR s.status = @P4)
 ORDER BY r.total_elapsed_time DESC, s.session_id;
 "#;
-        let mut query = Query::new(sql);
+
+/// Session totals plus current tempdb allocations (net pages of finished and running tasks)
+/// and the age of the oldest open transaction; `{order}` is a [`RESOURCE_SORTS`] column.
+const RESOURCE_SQL: &str = r#"
+SELECT TOP (@P5)
+    s.session_id AS sessionId,
+    s.login_name AS loginName,
+    s.host_name AS hostName,
+    s.program_name AS programName,
+    s.status AS sessionStatus,
+    DB_NAME(s.database_id) AS databaseName,
+    r.command AS command,
+    s.cpu_time AS cpuMs,
+    s.logical_reads AS logicalReads,
+    s.writes AS writes,
+    CAST(s.memory_usage AS bigint) * 8 AS memoryKb,
+    (COALESCE(su.pages, 0) + COALESCE(tu.pages, 0)) * 8 AS tempdbKb,
+    s.open_transaction_count AS openTransactions,
+    DATEDIFF(SECOND, tx.beganAt, SYSDATETIME()) AS transactionAgeSeconds,
+    r.blocking_session_id AS blockingSessionId
+FROM sys.dm_exec_sessions s
+LEFT JOIN sys.dm_exec_requests r ON s.session_id = r.session_id
+OUTER APPLY (
+    SELECT SUM(CAST(u.user_objects_alloc_page_count - u.user_objects_dealloc_page_count
+        + u.internal_objects_alloc_page_count - u.internal_objects_dealloc_page_count AS bigint)) AS pages
+    FROM sys.dm_db_session_space_usage u
+    WHERE u.session_id = s.session_id
+) su
+OUTER APPLY (
+    SELECT SUM(CAST(t.user_objects_alloc_page_count - t.user_objects_dealloc_page_count
+        + t.internal_objects_alloc_page_count - t.internal_objects_dealloc_page_count AS bigint)) AS pages
+    FROM sys.dm_db_task_space_usage t
+    WHERE t.session_id = s.session_id
+) tu
+OUTER APPLY (
+    SELECT MIN(at.transaction_begin_time) AS beganAt
+    FROM sys.dm_tran_session_transactions st
+    JOIN sys.dm_tran_active_transactions at ON at.transaction_id = st.transaction_id
+    WHERE st.session_id = s.session_id
+) tx
+WHERE s.is_user_process = 1
+  AND (@P1 IS NULL OR DB_NAME(s.database_id) = @P1)
+  AND (@P2 IS NULL OR s.login_name = @P2)
+  AND (@P3 IS NULL OR s.host_name = @P3)
+  AND (@P4 IS NULL OR s.status = @P4)
+ORDER BY {order} DESC, s.session_id;
+"#;
+
+pub fn run(args: &CliArgs, cmd: &SessionsArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let resource_usage = cmd.resource_usage || cmd.top.is_some();
+    let limit = common::parse_limit(cmd.top.or(cmd.limit), LIMIT_DEFAULT, LIMIT_MAX);
+    // A leading metric key ranks rows on the server so TOP keeps the heaviest sessions; any
+    // further keys sort the fetched rows as usual.
+    let mut output = args.output.clone();
+    let sort = if resource_usage {
+        let metric = output.sort.first().and_then(|key| resource_sort(key));
+        if metric.is_some() {
+            output.sort.remove(0);
+        }
+        Some(metric.unwrap_or(RESOURCE_SORTS[0]))
+    } else {
+        None
+    };
+    let sql = match sort {
+        Some((_, column)) => RESOURCE_SQL.replace("{order}", column),
+        None => SESSIONS_SQL.to_string(),
+    };
+    let database = cmd.database.clone();
+    let login = cmd.login.clone();
+    let host = cmd.host.clone();
+    let status = cmd.status.clone();
+
+    let (result_set, permission) = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let permission =
+            permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                .await?;
+        // The tempdb and transaction DMVs fail outright without the grant.
+        if resource_usage && !permission.granted {
+            return Ok::<_, anyhow::Error>((ResultSet::default(), permission));
+        }
+        let mut query = Query::new(sql.as_str());
         query.bind(database.as_deref());
         query.bind(login.as_deref());
         query.bind(host.as_deref());
         query.bind(status.as_deref());
         query.bind(limit as i64);
         let result_sets = executor::run_query(query, &mut client).await?;
-        Ok::<_, anyhow::Error>(result_sets.into_iter().next().unwrap_or_default())
+        Ok::<_, anyhow::Error>((
+            result_sets.into_iter().next().unwrap_or_default(),
+            permission,
+        ))
     })?;
 
+    let result_set = shape::apply(result_set, &output)?;
+
+    // Without VIEW SERVER STATE the DMVs only return the caller's own session.
+    let mut warnings = Vec::new();
+    if !permission.granted {
+        warnings.push(permission.warning(if resource_usage {
+            "session resource usage cannot be read"
+        } else {
+            "only your own session is visible"
+        }));
+    }
+
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
             "filters": {
@@ -66,8 +164,12 @@ ORDER BY r.total_elapsed_time DESC, s.session_id;
                 "status": status,
                 "limit": limit,
             },
+            "resourceUsage": resource_usage,
+            "sort": sort.map(|(key, _)| key),
             "count": result_set.rows.len(),
+            "degraded": !permission.granted,
             "sessions": json_out::result_set_rows_to_objects(&result_set),
+            "warnings": warnings,
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
@@ -82,6 +184,36 @@ ORDER BY r.total_elapsed_time DESC, s.session_id;
 
     let result = table::render_result_set_table(&result_set, format, &TableOptions::default());
     println!("{}", result.output);
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
     Ok(())
 }
+
+/// The [`RESOURCE_SORTS`] entry for a `--sort` key such as `cpu` or `tempdb:desc`.
+fn resource_sort(key: &str) -> Option<(&'static str, &'static str)> {
+    let name = key.trim();
+    let name = name
+        .strip_suffix(":desc")
+        .or_else(|| name.strip_suffix(":DESC"))
+        .unwrap_or(name);
+    RESOURCE_SORTS
+        .iter()
+        .find(|(metric, _)| metric.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_sort_accepts_metric_keys_only() {
+        assert_eq!(resource_sort("cpu"), Some(("cpu", "s.cpu_time")));
+        assert_eq!(resource_sort("TempDB:desc"), Some(("tempdb", "tempdbKb")));
+        assert_eq!(resource_sort("cpu:asc"), None);
+        assert_eq!(resource_sort("loginName"), None);
+        assert!(RESOURCE_SQL.contains("{order}"));
+    }
+}
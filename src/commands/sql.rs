@@ -1,5 +1,6 @@
 use std::fs;
-use std::io::Read;
+use std::io::{IsTerminal, Read};
+use std::path::Path;
 use std::time::Instant;
 
 use anyhow::{Result, anyhow};
@@ -7,13 +8,16 @@ use serde_json::json;
 use tiberius::Query;
 
 use crate::cli::{CliArgs, SqlArgs};
-use crate::commands::{common, sql_utils};
+use crate::commands::{common, confirm, sql_format, sql_utils};
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
+use crate::db::projection;
 use crate::db::types::ResultSet;
-use crate::error::{AppError, ErrorKind};
-use crate::output::{TableOptions, csv, json as json_out, table};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::remote::ExportTarget;
+use crate::output::{TableOptions, csv, json as json_out, mask, sink, table};
+use crate::safety;
 
 const MAX_ROWS_DEFAULT: u64 = 200;
 const MAX_ROWS_MAX: u64 = 2000;
@@ -30,34 +34,30 @@ struct BatchResult {
 pub fn run(args: &CliArgs, cmd: &SqlArgs) -> Result<()> {
     let resolved = common::load_config(args)?;
     let format = common::output_format(args, &resolved);
-    let sql_text = match (&cmd.sql, &cmd.file, cmd.stdin) {
-        (Some(_), Some(_), _) => {
-            return Err(anyhow!(
-                "Provide SQL text, --file, or --stdin, not multiple inputs"
-            ));
-        }
-        (Some(_), None, true) => {
-            return Err(anyhow!(
-                "Provide SQL text, --file, or --stdin, not multiple inputs"
-            ));
-        }
-        (None, Some(_), true) => {
-            return Err(anyhow!(
-                "Provide SQL text, --file, or --stdin, not multiple inputs"
-            ));
+    let sql_text = read_sql_text(cmd)?;
+
+    if cmd.format_sql {
+        let style = sql_format::style_from(&resolved.settings.formatting, &cmd.style);
+        let formatted = sql_format::format_sql(&sql_text, &style);
+        if args.quiet {
+            return Ok(());
         }
-        (None, None, false) => return Err(anyhow!("Provide SQL text, --file, or --stdin")),
-        (Some(text), None, false) => text.clone(),
-        (None, Some(path), false) => fs::read_to_string(path)?,
-        (None, None, true) => {
-            let mut sql = String::new();
-            std::io::stdin().read_to_string(&mut sql)?;
-            sql
+        if matches!(format, OutputFormat::Json) {
+            let body = json_out::emit_json_value(
+                &json!({ "sql": formatted }),
+                common::json_pretty(&resolved),
+            )?;
+            println!("{}", body);
+        } else {
+            println!("{}", formatted);
         }
-    };
+        return Ok(());
+    }
 
-    let params = sql_utils::parse_params(&cmd.params)
-        .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
+    let params = sql_utils::load_params(&cmd.params, cmd.params_file.as_deref())
+        .map_err(|err| AppError::new(ErrorKind::Query, format!("{:#}", err)))?;
+    let export = ExportTarget::from_args(cmd.to.as_deref(), cmd.storage_tier.as_deref())?;
+    let sinks = sink::parse_sinks(&cmd.out)?;
 
     let mut batches = sql_utils::split_batches(&sql_text);
     batches.retain(|batch| !batch.trim().is_empty());
@@ -85,96 +85,155 @@ pub fn run(args: &CliArgs, cmd: &SqlArgs) -> Result<()> {
         emit_dry_run(&format, &resolved, &batches)?;
         return Ok(());
     }
+    if batches
+        .iter()
+        .any(|batch| safety::validate_read_only(batch).is_err())
+    {
+        let action = format!(
+            "run {} that may write",
+            confirm::plural(batches.len(), "SQL batch", "SQL batches")
+        );
+        confirm::confirm_write(args, &resolved, &action)?;
+    }
 
     let max_rows = cmd
         .max_rows
         .unwrap_or(MAX_ROWS_DEFAULT)
         .clamp(1, MAX_ROWS_MAX) as usize;
 
-    let (result_sets, batch_results, errors) = tokio::runtime::Runtime::new()?.block_on(async {
-        let mut client = client::connect(&resolved.connection).await?;
-        let mut all_sets: Vec<ResultSet> = Vec::new();
-        let mut batch_results = Vec::new();
-        let mut errors = Vec::new();
-
-        for (idx, batch) in batches.iter().enumerate() {
-            let started = Instant::now();
-            let mut query = Query::new(batch.clone());
-            for param in &params {
-                query.bind(param.value.as_str());
-            }
+    let (mut result_sets, batch_results, errors) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut client = client::connect(&resolved.connection).await?;
+            let mut all_sets: Vec<ResultSet> = Vec::new();
+            let mut batch_results = Vec::new();
+            let mut errors = Vec::new();
 
-            match executor::run_query(query, &mut client).await {
-                Ok(sets) => {
-                    let rows = sets.iter().map(|rs| rs.rows.len()).sum();
-                    all_sets.extend(sets);
-                    batch_results.push(BatchResult {
-                        index: idx + 1,
-                        success: true,
-                        elapsed_ms: started.elapsed().as_millis(),
-                        rows,
-                        error: None,
-                    });
-                }
-                Err(err) => {
-                    let message = err.to_string();
-                    batch_results.push(BatchResult {
-                        index: idx + 1,
-                        success: false,
-                        elapsed_ms: started.elapsed().as_millis(),
-                        rows: 0,
-                        error: Some(message.clone()),
-                    });
-                    errors.push(message);
-                    if !cmd.continue_on_error {
-                        return Err(err);
+            for (idx, batch) in batches.iter().enumerate() {
+                let started = Instant::now();
+                let mut query = Query::new(sql_utils::with_table_params(batch, &params, 1));
+                sql_utils::bind_params(&mut query, &params);
+
+                // The driver aborts on sql_variant and CLR columns, so refuse those batches up front.
+                let specials = projection::describe_special_columns(&mut client, batch).await?;
+                let outcome = if specials.is_empty() {
+                    executor::run_query(query, &mut client).await
+                } else {
+                    Err(
+                        AppError::new(ErrorKind::Query, projection::unreadable_message(&specials))
+                            .into(),
+                    )
+                };
+                match outcome {
+                    Ok(sets) => {
+                        let rows = sets.iter().map(|rs| rs.rows.len()).sum();
+                        all_sets.extend(sets);
+                        batch_results.push(BatchResult {
+                            index: idx + 1,
+                            success: true,
+                            elapsed_ms: started.elapsed().as_millis(),
+                            rows,
+                            error: None,
+                        });
+                    }
+                    Err(err) => {
+                        let message = err.to_string();
+                        batch_results.push(BatchResult {
+                            index: idx + 1,
+                            success: false,
+                            elapsed_ms: started.elapsed().as_millis(),
+                            rows: 0,
+                            error: Some(message.clone()),
+                        });
+                        errors.push(message);
+                        if !cmd.continue_on_error {
+                            return Err(err);
+                        }
                     }
                 }
             }
-        }
 
-        Ok::<_, anyhow::Error>((all_sets, batch_results, errors))
-    })?;
+            Ok::<_, anyhow::Error>((all_sets, batch_results, errors))
+        })?;
 
     if !errors.is_empty() {
         for err in &errors {
             eprintln!("Batch error: {}", err);
         }
     }
+    for result_set in &mut result_sets {
+        mask::apply(result_set, &resolved.policy.mask, None);
+    }
+    let saved_blobs = common::save_blobs(cmd.save_blobs.as_deref(), &mut result_sets, &resolved)?;
 
     let csv_paths = if let Some(path) = cmd.csv.as_ref() {
         Some(csv::write_result_sets(
             path,
             &result_sets,
             resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
         )?)
     } else {
         None
     };
+    let out_paths = if sinks.is_empty() {
+        None
+    } else {
+        Some(sink::write_sinks(
+            &sinks,
+            &result_sets,
+            resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
+        )?)
+    };
+    let uploaded = match &export {
+        Some(export) => Some(export.upload_csv(
+            &result_sets,
+            resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
+        )?),
+        None => None,
+    };
 
     if matches!(format, OutputFormat::Json) {
+        // JSON keeps every row unless --max-rows is given explicitly.
+        let json_max_rows = cmd.max_rows.map(|_| max_rows).unwrap_or(usize::MAX);
+        let json_sets: Vec<serde_json::Value> = result_sets
+            .iter()
+            .map(|rs| {
+                json_out::result_set_to_json_sampled(
+                    rs,
+                    json_max_rows,
+                    resolved.settings.output.max_value_bytes,
+                )
+            })
+            .collect();
+        let truncated = json_sets.iter().any(|rs| rs["truncated"] == true);
         let payload = json!({
             "success": errors.is_empty(),
+            "truncated": truncated,
             "batches": batch_results.iter().map(batch_to_json).collect::<Vec<_>>(),
-            "resultSets": result_sets.iter().map(json_out::result_set_to_json).collect::<Vec<_>>(),
+            "resultSets": json_sets,
             "csvPaths": csv_paths.as_ref().map(|paths| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>()),
+            "outPaths": out_paths.as_ref().map(|paths| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>()),
+            "uploaded": uploaded,
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
             println!("{}", body);
         }
-        return Ok(());
+        return finish(&errors);
     }
 
     if args.quiet {
-        return Ok(());
+        return finish(&errors);
     }
 
-    let table_options = if cmd.no_truncate {
+    let mut table_options = if cmd.no_truncate {
         TableOptions::unlimited()
     } else {
         TableOptions::truncated()
     };
+    table_options.max_value_bytes = resolved.settings.output.max_value_bytes;
 
     let display_sets = truncate_result_sets(&result_sets, max_rows);
     for (idx, result_set) in display_sets.iter().enumerate() {
@@ -194,7 +253,18 @@ pub fn run(args: &CliArgs, cmd: &SqlArgs) -> Result<()> {
             println!("- {}", path.display());
         }
     }
+    common::print_written(out_paths.as_deref());
+    common::print_saved_blobs(saved_blobs, cmd.save_blobs.as_deref());
+    common::print_uploaded(uploaded.as_deref());
 
+    finish(&errors)
+}
+
+/// With `--continue-on-error`, output is complete but some batches failed: say so in the exit code.
+fn finish(errors: &[String]) -> Result<()> {
+    if !errors.is_empty() {
+        ExitCode::PartialFailure.exit();
+    }
     Ok(())
 }
 
@@ -246,3 +316,38 @@ fn batch_to_json(batch: &BatchResult) -> serde_json::Value {
         "error": batch.error,
     })
 }
+
+fn read_sql_text(cmd: &SqlArgs) -> Result<String> {
+    read_sql_input(cmd.sql.as_deref(), cmd.file.as_deref(), cmd.stdin)
+}
+
+/// The SQL from the argument, `--file`, or stdin. With none of them, piped or redirected stdin
+/// is read anyway, so `sscli sql < query.sql` works without `--stdin`.
+pub(super) fn read_sql_input(
+    sql: Option<&str>,
+    file: Option<&Path>,
+    stdin_flag: bool,
+) -> Result<String> {
+    let inputs = [sql.is_some(), file.is_some(), stdin_flag];
+    if inputs.iter().filter(|given| **given).count() > 1 {
+        return Err(anyhow!(
+            "Provide SQL text, --file, or --stdin, not multiple inputs"
+        ));
+    }
+    if let Some(text) = sql {
+        return Ok(text.to_string());
+    }
+    if let Some(path) = file {
+        return Ok(fs::read_to_string(path)?);
+    }
+    let stdin = std::io::stdin();
+    if !stdin_flag && stdin.is_terminal() {
+        return Err(anyhow!("Provide SQL text, --file, or --stdin"));
+    }
+    let mut sql = String::new();
+    stdin.lock().read_to_string(&mut sql)?;
+    if !stdin_flag && sql.trim().is_empty() {
+        return Err(anyhow!("Provide SQL text, --file, or --stdin"));
+    }
+    Ok(sql)
+}
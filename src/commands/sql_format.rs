@@ -0,0 +1,1013 @@
+//! T-SQL pretty-printer behind `fmt`, `sql --format-sql`, and `compare --format-sql`.
+//!
+//! The script is split into tokens (strings, quoted identifiers, and comments kept verbatim) and
+//! laid out again from the tokens alone, so scripts that differ only in whitespace or keyword case
+//! format identically. It is a layout pass rather than a parser: clause keywords start lines,
+//! commas in select/set/declare lists and AND/OR in conditions break within their clause, and
+//! subqueries, column definitions, and BEGIN...END blocks indent.
+
+use crate::cli::FormatStyleArgs;
+use crate::config::{CommaStyle, FormattingSettingsResolved, KeywordCase};
+
+/// Reserved words plus the few non-reserved ones the layout keys on; only these change case.
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "AFTER",
+    "ALL",
+    "ALTER",
+    "AND",
+    "ANY",
+    "APPLY",
+    "AS",
+    "ASC",
+    "AUTHORIZATION",
+    "BACKUP",
+    "BEGIN",
+    "BETWEEN",
+    "BREAK",
+    "BROWSE",
+    "BULK",
+    "BY",
+    "CASCADE",
+    "CASE",
+    "CAST",
+    "CATCH",
+    "CHECK",
+    "CHECKPOINT",
+    "CLOSE",
+    "CLUSTERED",
+    "COALESCE",
+    "COLLATE",
+    "COLUMN",
+    "COMMIT",
+    "COMPUTE",
+    "CONSTRAINT",
+    "CONTAINS",
+    "CONTAINSTABLE",
+    "CONTINUE",
+    "CONVERT",
+    "CREATE",
+    "CROSS",
+    "CURRENT",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "CURSOR",
+    "DATABASE",
+    "DBCC",
+    "DEALLOCATE",
+    "DECLARE",
+    "DEFAULT",
+    "DELETE",
+    "DENY",
+    "DESC",
+    "DISTINCT",
+    "DISTRIBUTED",
+    "DROP",
+    "ELSE",
+    "END",
+    "ERRLVL",
+    "ESCAPE",
+    "EXCEPT",
+    "EXEC",
+    "EXECUTE",
+    "EXISTS",
+    "EXIT",
+    "EXTERNAL",
+    "FETCH",
+    "FILE",
+    "FILLFACTOR",
+    "FOR",
+    "FOREIGN",
+    "FREETEXT",
+    "FREETEXTTABLE",
+    "FROM",
+    "FULL",
+    "FUNCTION",
+    "GO",
+    "GOTO",
+    "GRANT",
+    "GROUP",
+    "HAVING",
+    "HOLDLOCK",
+    "IDENTITY",
+    "IDENTITY_INSERT",
+    "IDENTITYCOL",
+    "IF",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INSTEAD",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "KILL",
+    "LEFT",
+    "LIKE",
+    "LINENO",
+    "MATCHED",
+    "MERGE",
+    "NATIONAL",
+    "NOCHECK",
+    "NOCOUNT",
+    "NONCLUSTERED",
+    "NOT",
+    "NULL",
+    "NULLIF",
+    "OF",
+    "OFF",
+    "OFFSETS",
+    "ON",
+    "OPEN",
+    "OPENDATASOURCE",
+    "OPENQUERY",
+    "OPENROWSET",
+    "OPENXML",
+    "OPTION",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OUTPUT",
+    "OVER",
+    "PARTITION",
+    "PERCENT",
+    "PIVOT",
+    "PLAN",
+    "PRIMARY",
+    "PRINT",
+    "PROC",
+    "PROCEDURE",
+    "PUBLIC",
+    "RAISERROR",
+    "READ",
+    "READTEXT",
+    "RECONFIGURE",
+    "REFERENCES",
+    "REPLICATION",
+    "RESTORE",
+    "RESTRICT",
+    "RETURN",
+    "RETURNS",
+    "REVERT",
+    "REVOKE",
+    "RIGHT",
+    "ROLLBACK",
+    "ROWCOUNT",
+    "ROWGUIDCOL",
+    "RULE",
+    "SAVE",
+    "SCHEMA",
+    "SELECT",
+    "SESSION_USER",
+    "SET",
+    "SETUSER",
+    "SHUTDOWN",
+    "SOME",
+    "STATISTICS",
+    "SYSTEM_USER",
+    "TABLE",
+    "TABLESAMPLE",
+    "TEXTSIZE",
+    "THEN",
+    "THROW",
+    "TO",
+    "TOP",
+    "TRAN",
+    "TRANSACTION",
+    "TRIGGER",
+    "TRUNCATE",
+    "TRY",
+    "TRY_CONVERT",
+    "TSEQUAL",
+    "UNION",
+    "UNIQUE",
+    "UNPIVOT",
+    "UPDATE",
+    "UPDATETEXT",
+    "USE",
+    "USER",
+    "VALUES",
+    "VARYING",
+    "VIEW",
+    "WAITFOR",
+    "WHEN",
+    "WHERE",
+    "WHILE",
+    "WITH",
+    "WITHIN",
+    "WRITETEXT",
+];
+
+/// Keywords written like function calls, with no space before their `(`.
+const FUNCTION_KEYWORDS: &[&str] = &[
+    "CAST",
+    "COALESCE",
+    "CONTAINS",
+    "CONTAINSTABLE",
+    "CONVERT",
+    "FREETEXT",
+    "FREETEXTTABLE",
+    "IDENTITY",
+    "LEFT",
+    "NULLIF",
+    "OPENDATASOURCE",
+    "OPENQUERY",
+    "OPENROWSET",
+    "OPENXML",
+    "RAISERROR",
+    "RIGHT",
+    "TRY_CONVERT",
+];
+
+/// Keywords after which a clause keyword belongs to the same phrase (`ON DELETE`, `FOR UPDATE`,
+/// `GRANT SELECT`, `WITHIN GROUP`) instead of starting a line.
+const BINDING_KEYWORDS: &[&str] = &[
+    "AFTER", "BULK", "DENY", "FOR", "GRANT", "INSTEAD", "OF", "ON", "REVOKE", "WITH", "WITHIN",
+];
+
+/// Object kinds that may precede `IF EXISTS` in `DROP ... IF EXISTS`.
+const DROP_OBJECTS: &[&str] = &[
+    "COLUMN",
+    "CONSTRAINT",
+    "DATABASE",
+    "FUNCTION",
+    "INDEX",
+    "PROC",
+    "PROCEDURE",
+    "ROLE",
+    "SCHEMA",
+    "SEQUENCE",
+    "SYNONYM",
+    "TABLE",
+    "TRIGGER",
+    "TYPE",
+    "USER",
+    "VIEW",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    /// Keywords, identifiers, `@variables`, `#temp` names.
+    Word(String),
+    /// `[name]` or `"name"`.
+    Quoted(String),
+    /// `'text'` or `N'text'`.
+    Str(String),
+    Number(String),
+    /// `own_line` is set when only whitespace precedes the comment on its line.
+    Comment {
+        text: String,
+        line: bool,
+        own_line: bool,
+    },
+    Punct(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Clause {
+    Other,
+    /// Commas separate items that each get a line.
+    List,
+    /// AND/OR start lines.
+    Condition,
+}
+
+/// One level of parentheses; the root frame stands for the statement itself.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Subqueries and column lists lay out their contents; other parentheses stay on one line.
+    block: bool,
+    indent: usize,
+    clause: Clause,
+    /// Extra indent levels for list items after the first.
+    list_indent: usize,
+    /// Inside `BETWEEN x AND y`, whose AND is not a condition break.
+    between: bool,
+}
+
+impl Frame {
+    fn new(block: bool, indent: usize) -> Self {
+        Self {
+            block,
+            indent,
+            clause: Clause::Other,
+            list_indent: 1,
+            between: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Begin,
+    Case,
+}
+
+/// `settings.formatting` with any `--keyword-case`, `--commas`, or `--indent` applied.
+pub fn style_from(
+    settings: &FormattingSettingsResolved,
+    args: &FormatStyleArgs,
+) -> FormattingSettingsResolved {
+    FormattingSettingsResolved {
+        keyword_case: args.keyword_case.unwrap_or(settings.keyword_case),
+        commas: args.commas.unwrap_or(settings.commas),
+        indent: args.indent.unwrap_or(settings.indent),
+    }
+}
+
+/// Reformat `sql` in `style`. The result has no trailing newline.
+pub fn format_sql(sql: &str, style: &FormattingSettingsResolved) -> String {
+    let tokens = tokenize(sql);
+    let mut writer = Writer::new(style);
+    let mut idx = 0;
+    while idx < tokens.len() {
+        idx = writer.token(&tokens, idx);
+    }
+    writer.finish()
+}
+
+struct Writer<'a> {
+    style: &'a FormattingSettingsResolved,
+    out: String,
+    indent: usize,
+    /// The next token starts a line indented `line_indent` levels.
+    line_start: bool,
+    line_indent: usize,
+    /// Start a line before the next token (after `;`, BEGIN, END).
+    pending_newline: bool,
+    /// Write the next token without a leading space (after `.` or a unary sign).
+    glue_next: bool,
+    frames: Vec<Frame>,
+    blocks: Vec<Block>,
+    /// Between `CREATE PROCEDURE|FUNCTION|VIEW|TRIGGER` and its `AS`.
+    header: bool,
+    /// The next `(` opens a column definition list (`CREATE TABLE`, `DECLARE @t TABLE`).
+    table_def: bool,
+    /// After `INTO`, where the `(` of a column list follows the table name.
+    into: bool,
+    prev: Option<Token>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(style: &'a FormattingSettingsResolved) -> Self {
+        Self {
+            style,
+            out: String::new(),
+            indent: 0,
+            line_start: true,
+            line_indent: 0,
+            pending_newline: false,
+            glue_next: false,
+            frames: vec![Frame::new(true, 0)],
+            blocks: Vec::new(),
+            header: false,
+            table_def: false,
+            into: false,
+            prev: None,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        let trimmed = self.out.trim_end().len();
+        self.out.truncate(trimmed);
+        self.out
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("root frame")
+    }
+
+    fn newline(&mut self, extra: usize) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        self.line_start = true;
+        self.line_indent = self.indent + extra;
+        self.pending_newline = false;
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.pending_newline {
+            self.newline(0);
+        }
+        if self.line_start {
+            self.out
+                .push_str(&" ".repeat(self.line_indent * self.style.indent));
+            self.line_start = false;
+        } else if space && !self.glue_next && !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.glue_next = false;
+        self.out.push_str(text);
+    }
+
+    fn keyword(&self, word: &str) -> String {
+        let upper = word.to_ascii_uppercase();
+        if !KEYWORDS.contains(&upper.as_str()) {
+            return word.to_string();
+        }
+        match self.style.keyword_case {
+            KeywordCase::Upper => upper,
+            KeywordCase::Lower => word.to_ascii_lowercase(),
+            KeywordCase::Preserve => word.to_string(),
+        }
+    }
+
+    fn prev_upper(&self) -> Option<String> {
+        match &self.prev {
+            Some(Token::Word(word)) => Some(word.to_ascii_uppercase()),
+            Some(Token::Punct(punct)) => Some(punct.clone()),
+            _ => None,
+        }
+    }
+
+    fn prev_is_keyword(&self) -> bool {
+        matches!(&self.prev, Some(Token::Word(word)) if KEYWORDS.contains(&word.to_ascii_uppercase().as_str()))
+    }
+
+    /// Lay out `tokens[idx]` and return the index of the next token to process.
+    fn token(&mut self, tokens: &[Token], idx: usize) -> usize {
+        let token = &tokens[idx];
+        match token {
+            Token::Comment {
+                text,
+                line,
+                own_line,
+            } => {
+                self.comment(text, *line, *own_line);
+                return idx + 1;
+            }
+            Token::Word(word) => return self.word(tokens, idx, word),
+            Token::Punct(punct) => self.punct(tokens, idx, punct),
+            Token::Quoted(text) | Token::Str(text) | Token::Number(text) => self.write(text, true),
+        }
+        self.prev = Some(token.clone());
+        idx + 1
+    }
+
+    fn comment(&mut self, text: &str, line: bool, own_line: bool) {
+        if own_line {
+            if !self.line_start {
+                self.newline(0);
+            }
+            let indent = self.line_indent;
+            self.write(text, false);
+            self.newline(0);
+            self.line_indent = indent;
+            return;
+        }
+        if line && self.line_start && self.out.ends_with('\n') {
+            // A comment that trailed a token whose line break was already written.
+            self.out.pop();
+            self.out.push(' ');
+            self.out.push_str(text);
+            self.out.push('\n');
+            return;
+        }
+        let pending = self.pending_newline;
+        self.pending_newline = false;
+        self.write(text, true);
+        if line {
+            self.newline(if pending { 0 } else { 1 });
+        } else {
+            self.pending_newline = pending;
+        }
+    }
+
+    fn word(&mut self, tokens: &[Token], idx: usize, word: &str) -> usize {
+        let upper = word.to_ascii_uppercase();
+        let prev = self.prev_upper();
+        let prev = prev.as_deref();
+        let next = next_significant(tokens, idx);
+        let next_upper = match next {
+            Some(Token::Word(word)) => word.to_ascii_uppercase(),
+            Some(Token::Punct(punct)) => punct.clone(),
+            _ => String::new(),
+        };
+        let block_frame = self.frame().block;
+        let in_case = self.blocks.last() == Some(&Block::Case);
+        let bound = prev.is_some_and(|prev| BINDING_KEYWORDS.contains(&prev) || prev == ",");
+        let text = self.keyword(word);
+        let mut consumed = idx + 1;
+        if KEYWORDS.contains(&upper.as_str()) {
+            self.into = upper == "INTO";
+        }
+
+        match upper.as_str() {
+            "GO" if self.frames.len() == 1 => {
+                self.newline(0);
+                self.indent = 0;
+                self.line_indent = 0;
+                self.blocks.clear();
+                self.header = false;
+                self.table_def = false;
+                self.frames[0] = Frame::new(true, 0);
+                self.write(&text, false);
+                if let Some(Token::Number(count)) = tokens.get(idx + 1) {
+                    self.write(count, true);
+                    consumed += 1;
+                }
+                self.newline(0);
+                self.out.push('\n');
+                self.prev = None;
+                return consumed;
+            }
+            "BEGIN" if block_frame => {
+                let transaction = matches!(
+                    next_upper.as_str(),
+                    "TRAN" | "TRANSACTION" | "DISTRIBUTED" | "DIALOG" | "CONVERSATION"
+                );
+                self.newline(0);
+                self.write(&text, false);
+                if !transaction {
+                    if matches!(next_upper.as_str(), "TRY" | "CATCH") {
+                        let label = self.keyword(&word_text(next));
+                        self.write(&label, true);
+                        consumed += 1;
+                    }
+                    self.blocks.push(Block::Begin);
+                    self.indent += 1;
+                    self.frame().clause = Clause::Other;
+                    self.header = false;
+                    self.pending_newline = true;
+                }
+                self.prev = Some(Token::Word(word.to_string()));
+                return consumed;
+            }
+            "CASE" => {
+                self.blocks.push(Block::Case);
+                self.write(&text, true);
+            }
+            "END" => {
+                match self.blocks.pop() {
+                    Some(Block::Case) => self.write(&text, true),
+                    popped => {
+                        if popped.is_some() {
+                            self.indent = self.indent.saturating_sub(1);
+                        }
+                        self.newline(0);
+                        self.write(&text, false);
+                        if matches!(next_upper.as_str(), "TRY" | "CATCH") {
+                            let label = self.keyword(&next_upper);
+                            self.write(&label, true);
+                            consumed += 1;
+                        }
+                        self.frame().clause = Clause::Other;
+                        self.pending_newline = true;
+                    }
+                }
+                self.prev = Some(Token::Word(word.to_string()));
+                return consumed;
+            }
+            "AS" if self.header
+                && self.frames.len() == 1
+                && !matches!(prev, Some("EXEC" | "EXECUTE")) =>
+            {
+                self.newline(0);
+                self.write(&text, false);
+                self.header = false;
+                self.frame().clause = Clause::Other;
+                self.pending_newline = true;
+                self.prev = Some(Token::Word(word.to_string()));
+                return consumed;
+            }
+            "AND" | "OR" if block_frame && !in_case && self.frame().clause == Clause::Condition => {
+                if upper == "AND" && self.frame().between {
+                    self.frame().between = false;
+                    self.write(&text, true);
+                } else {
+                    self.newline(1);
+                    self.write(&text, false);
+                }
+            }
+            _ => {
+                let starts_clause = block_frame
+                    && match upper.as_str() {
+                        "SELECT" | "WHERE" | "HAVING" | "OPTION" | "UNION" | "EXCEPT"
+                        | "INTERSECT" | "VALUES" | "DECLARE" | "WHILE" | "RETURN" | "PRINT"
+                        | "RAISERROR" | "THROW" | "MERGE" | "USING" | "TRUNCATE" | "DROP"
+                        | "CREATE" | "COMMIT" | "ROLLBACK" | "SAVE" | "USE" | "OPEN" | "CLOSE"
+                        | "DEALLOCATE" | "GROUP" | "ORDER" | "JOIN" | "INNER" | "CROSS"
+                        | "FULL" | "INSERT" | "EXEC" | "EXECUTE" => {
+                            !(bound || upper == "JOIN" && is_join_modifier(prev))
+                        }
+                        "FROM" => prev != Some("DELETE"),
+                        "ALTER" => prev != Some("OR"),
+                        "DELETE" => !bound,
+                        "UPDATE" => !bound && next_upper != "(",
+                        "SET" => !bound && !matches!(prev, Some("DELETE" | "UPDATE")),
+                        "LEFT" | "RIGHT" => matches!(next_upper.as_str(), "JOIN" | "OUTER"),
+                        "OUTER" => {
+                            matches!(next_upper.as_str(), "APPLY" | "JOIN")
+                                && !is_join_modifier(prev)
+                        }
+                        "OUTPUT" => {
+                            matches!(next_upper.as_str(), "INSERTED" | "DELETED" | "$ACTION")
+                        }
+                        "ELSE" | "WHEN" => !in_case,
+                        "IF" => {
+                            !prev.is_some_and(|prev| prev == "ELSE" || DROP_OBJECTS.contains(&prev))
+                        }
+                        "WITH" => !self.header && is_cte(tokens, idx),
+                        _ => false,
+                    };
+                if starts_clause {
+                    self.newline(0);
+                    self.write(&text, false);
+                    self.frame().between = false;
+                    self.frame().clause = match upper.as_str() {
+                        "SELECT" | "SET" | "DECLARE" | "VALUES" => Clause::List,
+                        "WHERE" | "HAVING" | "IF" | "WHILE" | "WHEN" => Clause::Condition,
+                        _ => Clause::Other,
+                    };
+                    self.frame().list_indent = 1;
+                } else {
+                    self.write(&text, true);
+                    match upper.as_str() {
+                        "BY" if block_frame && matches!(prev, Some("GROUP" | "ORDER")) => {
+                            self.frame().clause = Clause::List;
+                        }
+                        "ON" if block_frame && !bound => self.frame().clause = Clause::Condition,
+                        "BETWEEN" => self.frame().between = true,
+                        "PROCEDURE" | "PROC" if matches!(prev, Some("CREATE" | "ALTER")) => {
+                            self.header = true;
+                            self.frame().clause = Clause::List;
+                        }
+                        "FUNCTION" | "VIEW" | "TRIGGER"
+                            if matches!(prev, Some("CREATE" | "ALTER")) =>
+                        {
+                            self.header = true;
+                        }
+                        "TABLE"
+                            if prev == Some("CREATE")
+                                || prev.is_some_and(|prev| prev.starts_with('@')) =>
+                        {
+                            self.table_def = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        self.prev = Some(Token::Word(word.to_string()));
+        consumed
+    }
+
+    fn punct(&mut self, tokens: &[Token], idx: usize, punct: &str) {
+        match punct {
+            "(" => {
+                let glued = match &self.prev {
+                    Some(Token::Word(word)) => {
+                        let upper = word.to_ascii_uppercase();
+                        !KEYWORDS.contains(&upper.as_str())
+                            || FUNCTION_KEYWORDS.contains(&upper.as_str())
+                    }
+                    Some(Token::Quoted(_)) => true,
+                    Some(Token::Punct(prev)) => prev == "(" || prev == ".",
+                    _ => false,
+                };
+                let subquery = matches!(
+                    next_significant(tokens, idx),
+                    Some(Token::Word(word)) if word.eq_ignore_ascii_case("SELECT")
+                );
+                let table_def = std::mem::take(&mut self.table_def);
+                let into = std::mem::take(&mut self.into);
+                self.write("(", !glued || table_def || into);
+                if (subquery || table_def) && self.frame().block {
+                    let mut frame = Frame::new(true, self.indent);
+                    if table_def {
+                        frame.clause = Clause::List;
+                        frame.list_indent = 0;
+                    }
+                    self.frames.push(frame);
+                    self.indent += 1;
+                    self.newline(0);
+                } else {
+                    self.frames.push(Frame::new(false, self.indent));
+                    self.glue_next = true;
+                }
+            }
+            ")" => {
+                if self.frames.len() > 1 {
+                    let frame = self.frames.pop().expect("non-root frame");
+                    if frame.block {
+                        self.indent = frame.indent;
+                        self.newline(0);
+                    }
+                }
+                self.write(")", false);
+            }
+            "," => {
+                let frame = *self.frame();
+                if frame.block && frame.clause == Clause::List {
+                    match self.style.commas {
+                        CommaStyle::Trailing => {
+                            self.write(",", false);
+                            self.newline(frame.list_indent);
+                        }
+                        CommaStyle::Leading => {
+                            self.newline(frame.list_indent);
+                            self.write(",", false);
+                        }
+                    }
+                } else {
+                    self.write(",", false);
+                }
+            }
+            ";" => {
+                self.write(";", false);
+                if self.frames.len() == 1 {
+                    self.frames[0] = Frame::new(true, self.indent);
+                    self.header = false;
+                    self.table_def = false;
+                    self.pending_newline = true;
+                }
+            }
+            ":" => self.write(":", false),
+            "." | "::" => {
+                self.write(punct, false);
+                self.glue_next = true;
+            }
+            "+" | "-" | "~" => {
+                let unary = match &self.prev {
+                    None => true,
+                    Some(Token::Punct(prev)) => prev != ")",
+                    Some(Token::Word(word)) => {
+                        self.prev_is_keyword() && !word.eq_ignore_ascii_case("END")
+                    }
+                    _ => false,
+                };
+                self.write(punct, true);
+                self.glue_next = unary;
+            }
+            _ => self.write(punct, true),
+        }
+    }
+}
+
+fn is_join_modifier(prev: Option<&str>) -> bool {
+    matches!(
+        prev,
+        Some("INNER" | "LEFT" | "RIGHT" | "FULL" | "OUTER" | "CROSS")
+    )
+}
+
+/// `WITH name AS (` or `WITH name (columns) AS (`, as opposed to hints and options.
+fn is_cte(tokens: &[Token], idx: usize) -> bool {
+    let mut rest = tokens[idx + 1..]
+        .iter()
+        .filter(|token| !matches!(token, Token::Comment { .. }));
+    let name = match rest.next() {
+        Some(Token::Word(word)) => !KEYWORDS.contains(&word.to_ascii_uppercase().as_str()),
+        Some(Token::Quoted(_)) => true,
+        _ => false,
+    };
+    name && match rest.next() {
+        Some(Token::Word(word)) => word.eq_ignore_ascii_case("AS"),
+        Some(Token::Punct(punct)) => punct == "(",
+        _ => false,
+    }
+}
+
+fn word_text(token: Option<&Token>) -> String {
+    match token {
+        Some(Token::Word(word)) => word.clone(),
+        _ => String::new(),
+    }
+}
+
+fn next_significant(tokens: &[Token], idx: usize) -> Option<&Token> {
+    tokens[idx + 1..]
+        .iter()
+        .find(|token| !matches!(token, Token::Comment { .. }))
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    tokenize_spans(sql)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect()
+}
+
+/// Whether `word` is one of the keywords the formatter recognizes (case-insensitive).
+pub(super) fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(&word.to_ascii_uppercase().as_str())
+}
+
+/// Tokens paired with the char offset each one starts at.
+pub(super) fn tokenize_spans(sql: &str) -> Vec<(usize, Token)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut starts = Vec::new();
+    let mut idx = 0;
+    let mut line_has_token = false;
+    let text = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+    while idx < chars.len() {
+        let ch = chars[idx];
+        let next = chars.get(idx + 1).copied();
+        let start = idx;
+        if ch == '\n' {
+            line_has_token = false;
+            idx += 1;
+            continue;
+        }
+        if ch.is_whitespace() {
+            idx += 1;
+            continue;
+        }
+        let own_line = !line_has_token;
+        line_has_token = true;
+        if ch == '-' && next == Some('-') {
+            while idx < chars.len() && chars[idx] != '\n' {
+                idx += 1;
+            }
+            tokens.push(Token::Comment {
+                text: text(start, idx).trim_end().to_string(),
+                line: true,
+                own_line,
+            });
+        } else if ch == '/' && next == Some('*') {
+            // Block comments nest in T-SQL.
+            let mut depth = 0;
+            while idx < chars.len() {
+                if chars[idx] == '/' && chars.get(idx + 1) == Some(&'*') {
+                    depth += 1;
+                    idx += 2;
+                } else if chars[idx] == '*' && chars.get(idx + 1) == Some(&'/') {
+                    depth -= 1;
+                    idx += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    idx += 1;
+                }
+            }
+            let comment = text(start, idx);
+            if comment.contains('\n') {
+                line_has_token = false;
+            }
+            tokens.push(Token::Comment {
+                text: comment,
+                line: false,
+                own_line,
+            });
+        } else if ch == '\'' || (matches!(ch, 'N' | 'n') && next == Some('\'')) {
+            idx = closing(&chars, if ch == '\'' { idx } else { idx + 1 }, '\'');
+            tokens.push(Token::Str(text(start, idx)));
+        } else if ch == '[' {
+            idx = closing(&chars, idx, ']');
+            tokens.push(Token::Quoted(text(start, idx)));
+        } else if ch == '"' {
+            idx = closing(&chars, idx, '"');
+            tokens.push(Token::Quoted(text(start, idx)));
+        } else if ch.is_ascii_digit() || (ch == '.' && next.is_some_and(|c| c.is_ascii_digit())) {
+            idx += 1;
+            if ch == '0' && matches!(next, Some('x' | 'X')) {
+                idx += 1;
+                while idx < chars.len() && chars[idx].is_ascii_hexdigit() {
+                    idx += 1;
+                }
+            } else {
+                while idx < chars.len() {
+                    let c = chars[idx];
+                    let exponent_sign = matches!(c, '+' | '-')
+                        && matches!(chars[idx - 1], 'e' | 'E')
+                        && chars[start..idx - 1]
+                            .iter()
+                            .all(|c| c.is_ascii_digit() || *c == '.');
+                    if c.is_ascii_digit() || c == '.' || matches!(c, 'e' | 'E') || exponent_sign {
+                        idx += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            tokens.push(Token::Number(text(start, idx)));
+        } else if is_word_char(ch) {
+            while idx < chars.len() && is_word_char(chars[idx]) {
+                idx += 1;
+            }
+            tokens.push(Token::Word(text(start, idx)));
+        } else {
+            let pair = next.map(|next| format!("{}{}", ch, next));
+            let two = pair.as_deref().is_some_and(|pair| {
+                matches!(
+                    pair,
+                    ">=" | "<="
+                        | "<>"
+                        | "!="
+                        | "!<"
+                        | "!>"
+                        | "+="
+                        | "-="
+                        | "*="
+                        | "/="
+                        | "%="
+                        | "&="
+                        | "|="
+                        | "^="
+                        | "::"
+                )
+            });
+            idx += if two { 2 } else { 1 };
+            tokens.push(Token::Punct(text(start, idx)));
+        }
+        starts.push(start);
+    }
+    starts.into_iter().zip(tokens).collect()
+}
+
+/// Index just past the `close` that ends the quoted run starting at `open`; a doubled `close`
+/// is an escaped one.
+fn closing(chars: &[char], open: usize, close: char) -> usize {
+    let mut idx = open + 1;
+    while idx < chars.len() {
+        if chars[idx] == close {
+            if chars.get(idx + 1) == Some(&close) {
+                idx += 2;
+                continue;
+            }
+            return idx + 1;
+        }
+        idx += 1;
+    }
+    chars.len()
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '@' | '#' | '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style() -> FormattingSettingsResolved {
+        FormattingSettingsResolved::default()
+    }
+
+    #[test]
+    fn lays_out_clauses_lists_and_conditions() {
+        let sql = "select a,b, count(*) as n from dbo.t t inner join u on u.id=t.id and u.x>-1 \
+                   where a=1 and b between 1 and 2 or c like N'x''y' group by a,b order by a desc";
+        assert_eq!(
+            format_sql(sql, &style()),
+            "SELECT a,\n    b,\n    count(*) AS n\nFROM dbo.t t\nINNER JOIN u ON u.id = t.id\n    \
+             AND u.x > -1\nWHERE a = 1\n    AND b BETWEEN 1 AND 2\n    OR c LIKE N'x''y'\n\
+             GROUP BY a,\n    b\nORDER BY a DESC"
+        );
+
+        let leading = FormattingSettingsResolved {
+            keyword_case: KeywordCase::Lower,
+            commas: CommaStyle::Leading,
+            indent: 2,
+        };
+        assert_eq!(
+            format_sql("SELECT a, b FROM [t]", &leading),
+            "select a\n  , b\nfrom [t]"
+        );
+    }
+
+    #[test]
+    fn indents_subqueries_blocks_and_keeps_comments() {
+        let sql = "IF EXISTS (SELECT 1 FROM t WHERE x = 'a') BEGIN UPDATE t SET a = CASE WHEN \
+                   b = 1 THEN 'x' ELSE 'y' END, c = 2 -- note\n END ELSE PRINT 'none';";
+        assert_eq!(
+            format_sql(sql, &style()),
+            "IF EXISTS (\n    SELECT 1\n    FROM t\n    WHERE x = 'a'\n)\nBEGIN\n    UPDATE t\n    \
+             SET a = CASE WHEN b = 1 THEN 'x' ELSE 'y' END,\n        c = 2 -- note\nEND\nELSE\n\
+             PRINT 'none';"
+        );
+    }
+
+    #[test]
+    fn formats_modules_and_table_definitions() {
+        let sql = "create or alter procedure dbo.p @a int, @b int = 1 output as begin set nocount on; \
+                   declare @t table (id int not null, name nvarchar(50)); end\nGO\n\
+                   create table dbo.x (id int identity(1,1) primary key, v decimal(10,2))";
+        assert_eq!(
+            format_sql(sql, &style()),
+            "CREATE OR ALTER PROCEDURE dbo.p @a int,\n    @b int = 1 OUTPUT\nAS\nBEGIN\n    \
+             SET NOCOUNT ON;\n    DECLARE @t TABLE (\n        id int NOT NULL,\n        \
+             name nvarchar(50)\n    );\nEND\nGO\n\nCREATE TABLE dbo.x (\n    \
+             id int IDENTITY(1, 1) PRIMARY KEY,\n    v decimal(10, 2)\n)"
+        );
+    }
+
+    #[test]
+    fn formatting_ignores_whitespace_and_is_stable() {
+        let one = "WITH c AS (SELECT id FROM t) SELECT * FROM c /* keep */ WHERE id IN (1, 2)";
+        let two = "with c as (\n  select id\n  from t\n)\nselect   *\nfrom c /* keep */\nwhere id in (1,2)";
+        let formatted = format_sql(one, &style());
+        assert_eq!(formatted, format_sql(two, &style()));
+        assert_eq!(format_sql(&formatted, &style()), formatted);
+        assert!(formatted.starts_with("WITH c AS (\n    SELECT id\n    FROM t\n)\nSELECT *"));
+    }
+}
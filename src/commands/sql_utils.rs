@@ -1,10 +1,201 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+use tiberius::Query;
+
+use crate::db::queries::bracket;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SqlParam {
     pub name: String,
-    pub value: String,
+    pub value: ParamValue,
+    /// User-defined table type (`ids:dbo.IdList=...`); the list is loaded into a table variable
+    /// of that type instead of expanding in place.
+    pub table_type: Option<String>,
+}
+
+/// Parameters SQL Server accepts in one request.
+const MAX_PLACEHOLDERS: usize = 2100;
+
+/// A parameter value as bound: `--param` gives text, `--params-file` keeps YAML/JSON types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+    /// Expands to one placeholder per item, for `IN (@ids)`; with a table type, the rows.
+    List(Vec<ParamValue>),
+}
+
+impl ParamValue {
+    fn from_yaml(value: &serde_yaml::Value) -> Option<Self> {
+        Some(match value {
+            serde_yaml::Value::Null => ParamValue::Null,
+            serde_yaml::Value::Bool(flag) => ParamValue::Bool(*flag),
+            serde_yaml::Value::Number(number) => match number.as_i64() {
+                Some(int) => ParamValue::Int(int),
+                None => ParamValue::Float(number.as_f64()?),
+            },
+            serde_yaml::Value::String(text) => ParamValue::Text(text.clone()),
+            serde_yaml::Value::Sequence(items) => ParamValue::List(
+                items
+                    .iter()
+                    .map(ParamValue::from_yaml)
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            _ => return None,
+        })
+    }
+
+    /// `--param` text: `[1, 2, 3]` (JSON) and `@file:ids.txt` (one item per line) are lists.
+    fn from_flag(text: &str) -> Result<Self> {
+        if let Some(path) = text.strip_prefix("@file:") {
+            let contents =
+                fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+            return Ok(ParamValue::List(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| ParamValue::Text(line.to_string()))
+                    .collect(),
+            ));
+        }
+        // Anything that is not a JSON array, such as the LIKE pattern `[a-c]%`, stays text.
+        if text.trim_start().starts_with('[')
+            && let Ok(list @ serde_yaml::Value::Sequence(_)) =
+                serde_json::from_str::<serde_yaml::Value>(text)
+            && let Some(value) = ParamValue::from_yaml(&list)
+        {
+            return Ok(value);
+        }
+        Ok(ParamValue::Text(text.to_string()))
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            ParamValue::Text(text) => serde_json::Value::from(text.as_str()),
+            ParamValue::Int(int) => serde_json::Value::from(*int),
+            ParamValue::Float(float) => serde_json::Value::from(*float),
+            ParamValue::Bool(flag) => serde_json::Value::from(*flag),
+            ParamValue::Null => serde_json::Value::Null,
+            ParamValue::List(items) => items.iter().map(ParamValue::to_json).collect(),
+        }
+    }
+
+    fn bind(&self, query: &mut Query<'_>) {
+        match self {
+            ParamValue::Text(text) => query.bind(text.clone()),
+            ParamValue::Int(int) => query.bind(*int),
+            ParamValue::Float(float) => query.bind(*float),
+            ParamValue::Bool(flag) => query.bind(*flag),
+            ParamValue::Null => query.bind(Option::<String>::None),
+            ParamValue::List(items) => items.iter().for_each(|item| item.bind(query)),
+        }
+    }
+}
+
+impl SqlParam {
+    /// Placeholders the parameter takes in the SQL text.
+    fn placeholders(&self) -> usize {
+        match (&self.value, &self.table_type) {
+            (_, Some(_)) => 1,
+            (ParamValue::List(items), None) => items.len(),
+            _ => 1,
+        }
+    }
+
+    fn bind(&self, query: &mut Query<'_>) {
+        match (&self.value, &self.table_type) {
+            // The rows travel as one JSON array; see `table_declarations`.
+            (ParamValue::List(rows), Some(_)) => {
+                let rows: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|row| match row {
+                        ParamValue::List(_) => row.to_json(),
+                        scalar => serde_json::Value::Array(vec![scalar.to_json()]),
+                    })
+                    .collect();
+                query.bind(serde_json::Value::Array(rows).to_string());
+            }
+            (value, _) => value.bind(query),
+        }
+    }
+
+    /// Columns per row of a table-type parameter.
+    fn row_width(&self) -> usize {
+        match &self.value {
+            ParamValue::List(rows) => match rows.first() {
+                Some(ParamValue::List(items)) => items.len(),
+                _ => 1,
+            },
+            _ => 1,
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        let ParamValue::List(items) = &self.value else {
+            return match &self.table_type {
+                Some(table_type) => Err(anyhow!(
+                    "parameter '{}' names table type {} but is not a list",
+                    self.name,
+                    table_type
+                )),
+                None => Ok(()),
+            };
+        };
+        if self.table_type.is_none() {
+            if items.iter().any(|item| matches!(item, ParamValue::List(_))) {
+                return Err(anyhow!(
+                    "parameter '{}' has nested lists; rows need a table type ({}:dbo.MyTableType=...)",
+                    self.name,
+                    self.name
+                ));
+            }
+            return Ok(());
+        }
+        let width = self.row_width();
+        for row in items {
+            let (row_width, nested) = match row {
+                ParamValue::List(values) => (
+                    values.len(),
+                    values
+                        .iter()
+                        .any(|value| matches!(value, ParamValue::List(_))),
+                ),
+                _ => (1, false),
+            };
+            if nested || row_width != width || width == 0 {
+                return Err(anyhow!(
+                    "parameter '{}' rows must all be lists of {} value(s)",
+                    self.name,
+                    width.max(1)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Text(text) => f.write_str(text),
+            ParamValue::Int(int) => write!(f, "{}", int),
+            ParamValue::Float(float) => write!(f, "{}", float),
+            ParamValue::Bool(flag) => write!(f, "{}", flag),
+            ParamValue::Null => Ok(()),
+            ParamValue::List(items) => {
+                let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+                f.write_str(&items.join(","))
+            }
+        }
+    }
 }
 
 pub fn parse_params(raw: &[String]) -> Result<Vec<SqlParam>> {
@@ -19,20 +210,179 @@ pub fn parse_params(raw: &[String]) -> Result<Vec<SqlParam>> {
         let value = value.ok_or_else(|| anyhow!("Invalid --param '{}'. Use name=value.", entry))?;
         params.push(SqlParam {
             name: name.to_string(),
-            value: value.to_string(),
+            value: ParamValue::Text(value.to_string()),
+            table_type: None,
         });
     }
     Ok(params)
 }
 
+/// `--params-file` followed by `--param`; a `--param` replaces a file entry of the same name.
+///
+/// Unlike [`parse_params`], list syntax and `name:schema.Type` table types apply.
+pub fn load_params(raw: &[String], file: Option<&Path>) -> Result<Vec<SqlParam>> {
+    let flags = parse_params(raw)?
+        .into_iter()
+        .map(|param| {
+            let (name, table_type) = split_table_type(&param.name);
+            Ok(SqlParam {
+                name,
+                value: ParamValue::from_flag(&param.value.to_string())?,
+                table_type,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut params = match file {
+        Some(path) => parse_params_file(
+            &fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        )
+        .with_context(|| format!("Invalid --params-file {}", path.display()))?,
+        None => Vec::new(),
+    };
+    params.retain(|param| {
+        !flags
+            .iter()
+            .any(|flag| flag.name.eq_ignore_ascii_case(&param.name))
+    });
+    params.extend(flags);
+    for param in &params {
+        param.check()?;
+    }
+    let count = placeholder_count(&params);
+    if count > MAX_PLACEHOLDERS {
+        return Err(anyhow!(
+            "parameters need {} placeholders but SQL Server accepts {}; pass long lists with a table type (ids:dbo.IdList=...)",
+            count,
+            MAX_PLACEHOLDERS
+        ));
+    }
+    Ok(params)
+}
+
+/// `ids:dbo.IdList` → (`ids`, `dbo.IdList`).
+fn split_table_type(raw: &str) -> (String, Option<String>) {
+    let raw = raw.trim().trim_start_matches('@');
+    match raw.split_once(':') {
+        Some((name, table_type)) if !table_type.trim().is_empty() => {
+            (name.trim().to_string(), Some(table_type.trim().to_string()))
+        }
+        _ => (raw.to_string(), None),
+    }
+}
+
+/// A YAML (or JSON) mapping of names to scalars or lists; `name:schema.Type` keys name a table type.
+fn parse_params_file(text: &str) -> Result<Vec<SqlParam>> {
+    let document: serde_yaml::Value = serde_yaml::from_str(text)?;
+    let serde_yaml::Value::Mapping(entries) = document else {
+        return Err(anyhow!("expected a mapping of parameter names to values"));
+    };
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let (name, table_type) = match key {
+                serde_yaml::Value::String(name) => split_table_type(name),
+                other => return Err(anyhow!("parameter names must be strings, got {:?}", other)),
+            };
+            let value = ParamValue::from_yaml(value).ok_or_else(|| {
+                anyhow!(
+                    "parameter '{}' must be a string, number, boolean, null, or a list of those",
+                    name
+                )
+            })?;
+            Ok(SqlParam {
+                name,
+                value,
+                table_type,
+            })
+        })
+        .collect()
+}
+
+/// Placeholders `params` take, so a caller can number its own after them.
+pub fn placeholder_count(params: &[SqlParam]) -> usize {
+    params.iter().map(SqlParam::placeholders).sum()
+}
+
+/// Bind every parameter in order, list items one by one, matching [`replace_named_params`].
+pub fn bind_params(query: &mut Query<'_>, params: &[SqlParam]) {
+    for param in params {
+        param.bind(query);
+    }
+}
+
+/// `sql` preceded by a table variable for each table-type parameter it mentions.
+///
+/// `@ids` stays as written and is declared as its table type, then filled from the parameter's
+/// JSON rows; SQL Server converts each value to its column's type, so a bad value fails the
+/// batch instead of slipping through as text. Apply after read-only checks: the fill is an INSERT.
+pub fn with_table_params(sql: &str, params: &[SqlParam], start_index: usize) -> String {
+    let mut declarations = String::new();
+    let mut next = start_index;
+    for param in params {
+        if let Some(table_type) = &param.table_type
+            && mentions_variable(sql, &param.name)
+        {
+            let columns = (0..param.row_width())
+                .map(|index| format!("JSON_VALUE([value], 'strict $[{}]')", index))
+                .collect::<Vec<_>>()
+                .join(", ");
+            declarations.push_str(&format!(
+                "DECLARE @{name} {table_type};\nINSERT INTO @{name} SELECT {columns} FROM OPENJSON(@P{next});\n",
+                name = param.name,
+                table_type = quote_type_name(table_type),
+            ));
+        }
+        next += param.placeholders();
+    }
+    declarations + sql
+}
+
+fn quote_type_name(name: &str) -> String {
+    name.split('.')
+        .map(|part| bracket(part.trim().trim_start_matches('[').trim_end_matches(']')))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn mentions_variable(sql: &str, name: &str) -> bool {
+    let mut rest = sql;
+    while let Some(at) = rest.find('@') {
+        rest = &rest[at + 1..];
+        let end = rest
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .unwrap_or(rest.len());
+        if rest[..end].eq_ignore_ascii_case(name) {
+            return true;
+        }
+    }
+    false
+}
+
 pub fn replace_named_params(sql: &str, params: &[SqlParam], start_index: usize) -> String {
     if params.is_empty() {
         return sql.to_string();
     }
 
     let mut map = HashMap::new();
-    for (idx, param) in params.iter().enumerate() {
-        let placeholder = format!("@P{}", start_index + idx);
+    let mut next = start_index;
+    for param in params {
+        let count = param.placeholders();
+        if param.table_type.is_some() {
+            // Left as `@name`, the table variable `with_table_params` declares.
+            next += count;
+            continue;
+        }
+        let placeholder = match &param.value {
+            // `IN (NULL)` matches nothing, as an empty list should.
+            ParamValue::List(_) if count == 0 => "NULL".to_string(),
+            ParamValue::List(_) => (next..next + count)
+                .map(|index| format!("@P{}", index))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => format!("@P{}", next),
+        };
+        next += count;
         map.insert(param.name.to_lowercase(), placeholder);
     }
 
@@ -224,7 +574,7 @@ mod tests {
         let params = parse_params(&["foo=bar".to_string(), "x=1".to_string()]).unwrap();
         assert_eq!(params.len(), 2);
         assert_eq!(params[0].name, "foo");
-        assert_eq!(params[1].value, "1");
+        assert_eq!(params[1].value, ParamValue::Text("1".to_string()));
     }
 
     #[test]
@@ -232,11 +582,13 @@ mod tests {
         let params = vec![
             SqlParam {
                 name: "foo".to_string(),
-                value: "bar".to_string(),
+                value: ParamValue::Text("bar".to_string()),
+                table_type: None,
             },
             SqlParam {
                 name: "baz".to_string(),
-                value: "qux".to_string(),
+                value: ParamValue::Text("qux".to_string()),
+                table_type: None,
             },
         ];
         let sql = "SELECT * FROM t WHERE a=@foo AND b=@baz";
@@ -245,6 +597,102 @@ mod tests {
         assert!(replaced.contains("@P2"));
     }
 
+    #[test]
+    fn params_file_keeps_types_and_expands_lists() {
+        let params = parse_params_file(
+            "region: West\nminTotal: 100\nrate: 0.5\nactive: true\nsince: null\nids: [3, 5, 8]\nnone: []\n",
+        )
+        .unwrap();
+        assert_eq!(params[1].value, ParamValue::Int(100));
+        assert_eq!(params[2].value, ParamValue::Float(0.5));
+        assert_eq!(params[3].value, ParamValue::Bool(true));
+        assert_eq!(params[4].value, ParamValue::Null);
+        assert_eq!(placeholder_count(&params), 8);
+
+        let sql = "SELECT * FROM t WHERE Region = @region AND Id IN (@ids) AND Id NOT IN (@none) AND x > @minTotal";
+        assert_eq!(
+            replace_named_params(sql, &params, 1),
+            "SELECT * FROM t WHERE Region = @P1 AND Id IN (@P6, @P7, @P8) AND Id NOT IN (NULL) AND x > @P2"
+        );
+
+        assert!(parse_params_file("- 1\n- 2").is_err());
+    }
+
+    #[test]
+    fn param_flags_take_lists_from_json_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ids.txt");
+        fs::write(&path, "3\n\n 5 \r\n8\n").unwrap();
+        let params = load_params(
+            &[
+                "ids=[1, 2, 3]".to_string(),
+                format!("codes=@file:{}", path.display()),
+                "pattern=[a-c]%".to_string(),
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            params[0].value,
+            ParamValue::List(vec![
+                ParamValue::Int(1),
+                ParamValue::Int(2),
+                ParamValue::Int(3)
+            ])
+        );
+        assert_eq!(params[1].value.to_string(), "3,5,8");
+        assert_eq!(params[2].value, ParamValue::Text("[a-c]%".to_string()));
+        assert_eq!(placeholder_count(&params), 7);
+
+        // Procedures take `--param` text as given.
+        let raw = parse_params(&["ids=[1, 2]".to_string()]).unwrap();
+        assert_eq!(raw[0].value, ParamValue::Text("[1, 2]".to_string()));
+
+        assert!(load_params(&["ids=[[1, 2]]".to_string()], None).is_err());
+        let too_many = format!("ids=[{}]", vec!["1"; 2101].join(","));
+        assert!(load_params(&[too_many], None).is_err());
+    }
+
+    #[test]
+    fn table_type_params_declare_a_table_variable() {
+        let params = load_params(
+            &[
+                "region=West".to_string(),
+                "lines:sales.OrderLines=[[1, \"A-1\"], [2, null]]".to_string(),
+                "ids:dbo.IdList=[5, 6]".to_string(),
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(params[1].name, "lines");
+        assert_eq!(params[1].table_type.as_deref(), Some("sales.OrderLines"));
+        assert_eq!(placeholder_count(&params), 3);
+
+        let sql = "EXEC dbo.Ship @lines, @region";
+        let replaced = replace_named_params(sql, &params, 1);
+        assert_eq!(replaced, "EXEC dbo.Ship @lines, @P1");
+        assert_eq!(
+            with_table_params(&replaced, &params, 1),
+            "DECLARE @lines [sales].[OrderLines];\n\
+             INSERT INTO @lines SELECT JSON_VALUE([value], 'strict $[0]'), JSON_VALUE([value], 'strict $[1]') FROM OPENJSON(@P2);\n\
+             EXEC dbo.Ship @lines, @P1"
+        );
+
+        assert!(load_params(&["ids:dbo.IdList=5".to_string()], None).is_err());
+        assert!(load_params(&["rows:dbo.Pairs=[[1, 2], [3]]".to_string()], None).is_err());
+    }
+
+    #[test]
+    fn param_flags_override_the_params_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("params.json");
+        fs::write(&path, r#"{"region": "West", "top": 10}"#).unwrap();
+        let params = load_params(&["Region=East".to_string()], Some(&path)).unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].value, ParamValue::Int(10));
+        assert_eq!(params[1].value, ParamValue::Text("East".to_string()));
+    }
+
     #[test]
     fn splits_batches_on_go() {
         let script = "SELECT 1\nGO\nSELECT 2\nGO\nSELECT 3";
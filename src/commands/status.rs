@@ -5,17 +5,29 @@ use serde_json::json;
 use tiberius::Query;
 
 use crate::cli::{CliArgs, StatusArgs};
-use crate::commands::common;
-use crate::config::OutputFormat;
+use crate::commands::{common, status_diagnose};
+use crate::config::{OutputFormat, ResolvedConfig};
 use crate::db::client;
 use crate::db::executor;
-use crate::db::types::Value;
+use crate::db::session::Session;
+use crate::db::types::{ResultSet, Value};
+use crate::error::ExitCode;
+use crate::output::contract::StatusOutput;
 use crate::output::{TableOptions, json as json_out, table};
 
-pub fn run(args: &CliArgs, _cmd: &StatusArgs) -> Result<()> {
+const ERROR_LOG_LIMIT: i64 = 20;
+
+pub fn run(args: &CliArgs, cmd: &StatusArgs) -> Result<()> {
     let resolved = common::load_config(args)?;
     let format = common::output_format(args, &resolved);
 
+    if cmd.deep {
+        return run_deep(args, &resolved, format);
+    }
+    if cmd.diagnose {
+        return status_diagnose::run(args, &resolved, format);
+    }
+
     let started = Instant::now();
     let result_sets = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
@@ -47,16 +59,16 @@ pub fn run(args: &CliArgs, _cmd: &StatusArgs) -> Result<()> {
     }
 
     if matches!(format, OutputFormat::Json) {
-        let payload = json!({
-            "status": "ok",
-            "latencyMs": latency_ms,
-            "serverName": server_name,
-            "serverVersion": server_version,
-            "currentDatabase": current_database,
-            "timestamp": timestamp,
-            "warnings": Vec::<String>::new(),
-        });
-        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        let payload = StatusOutput {
+            status: "ok".to_string(),
+            latency_ms,
+            server_name,
+            server_version,
+            current_database,
+            timestamp,
+            warnings: Vec::new(),
+        };
+        let body = json_out::emit_json(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
             println!("{}", body);
         }
@@ -82,9 +94,379 @@ pub fn run(args: &CliArgs, _cmd: &StatusArgs) -> Result<()> {
     Ok(())
 }
 
+/// Sections of the `--deep` health report, each fetched independently so a
+/// missing permission degrades one section instead of failing the whole report.
+struct DeepReport {
+    server: ResultSet,
+    host: ResultSet,
+    memory: ResultSet,
+    tempdb: ResultSet,
+    databases: ResultSet,
+    availability: ResultSet,
+    error_log: ResultSet,
+    /// Sections that could not be read, such as `memory` without VIEW SERVER STATE.
+    unavailable: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// `degraded` when any database is not online, any availability group is not healthy, or a
+/// section could not be read; otherwise `ok`.
+fn overall_status(
+    databases: &[serde_json::Value],
+    availability: &[serde_json::Value],
+    unavailable: &[String],
+) -> &'static str {
+    let offline = databases.iter().any(|db| db["state"] != "ONLINE");
+    let unhealthy = availability
+        .iter()
+        .any(|ag| ag["synchronizationHealth"] != "HEALTHY");
+    if offline || unhealthy || !unavailable.is_empty() {
+        "degraded"
+    } else {
+        "ok"
+    }
+}
+
+fn run_deep(args: &CliArgs, resolved: &ResolvedConfig, format: OutputFormat) -> Result<()> {
+    let started = Instant::now();
+    let report = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut session = Session::connect(&resolved.connection).await?;
+        let mut unavailable = Vec::new();
+        let mut warnings = Vec::new();
+
+        let server = run_section(
+            &mut session,
+            "server",
+            SERVER_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let host = run_section(
+            &mut session,
+            "host",
+            HOST_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let memory = run_section(
+            &mut session,
+            "memory",
+            MEMORY_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let tempdb = run_section(
+            &mut session,
+            "tempdb",
+            TEMPDB_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let databases = run_section(
+            &mut session,
+            "databases",
+            DATABASES_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let availability = run_section(
+            &mut session,
+            "availabilityGroups",
+            AG_SQL,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        let error_log_sql = ERROR_LOG_SQL.replace("{limit}", &ERROR_LOG_LIMIT.to_string());
+        let error_log = run_section(
+            &mut session,
+            "errorLog",
+            &error_log_sql,
+            &mut unavailable,
+            &mut warnings,
+        )
+        .await;
+        warnings.extend(session.take_warnings());
+
+        Ok::<_, anyhow::Error>(DeepReport {
+            server,
+            host,
+            memory,
+            tempdb,
+            databases,
+            availability,
+            error_log,
+            unavailable,
+            warnings,
+        })
+    })?;
+    let latency_ms = started.elapsed().as_millis();
+
+    let databases = json_out::result_set_rows_to_objects(&report.databases);
+    let not_online: Vec<serde_json::Value> = databases
+        .iter()
+        .filter(|db| db["state"] != "ONLINE")
+        .cloned()
+        .collect();
+    let availability = json_out::result_set_rows_to_objects(&report.availability);
+    let status = overall_status(&databases, &availability, &report.unavailable);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "status": status,
+            "latencyMs": latency_ms,
+            "server": first_object(&report.server),
+            "host": first_object(&report.host),
+            "memory": first_object(&report.memory),
+            "tempdb": {
+                "files": json_out::result_set_rows_to_objects(&report.tempdb),
+            },
+            "databases": {
+                "total": databases.len(),
+                "online": databases.len() - not_online.len(),
+                "notOnline": not_online,
+            },
+            "availabilityGroups": availability,
+            "errorLog": json_out::result_set_rows_to_objects(&report.error_log),
+            "unavailableSections": report.unavailable,
+            "warnings": report.warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        print_deep(&report, &databases, status, latency_ms, format);
+    }
+    if status == "degraded" {
+        ExitCode::Degraded.exit();
+    }
+    Ok(())
+}
+
+fn print_deep(
+    report: &DeepReport,
+    databases: &[serde_json::Value],
+    status: &str,
+    latency_ms: u128,
+    format: OutputFormat,
+) {
+    let not_online = databases
+        .iter()
+        .filter(|db| db["state"] != "ONLINE")
+        .count();
+
+    let mut rows = vec![
+        ("Status".to_string(), status.to_string()),
+        ("LatencyMs".to_string(), latency_ms.to_string()),
+    ];
+    for rs in [&report.server, &report.host, &report.memory] {
+        rows.extend(key_value_rows(rs));
+    }
+    rows.push((
+        "Databases".to_string(),
+        format!("{} total, {} not online", databases.len(), not_online),
+    ));
+    let options = TableOptions::default();
+    let summary = table::render_key_value_table("Health", &rows, format, &options);
+    println!("{}", summary.output);
+
+    let sections = [
+        ("Tempdb Files", &report.tempdb),
+        ("Availability Groups", &report.availability),
+        ("Recent Error Log", &report.error_log),
+    ];
+    for (title, rs) in sections {
+        if rs.rows.is_empty() {
+            continue;
+        }
+        println!("{}", title);
+        println!(
+            "{}",
+            table::render_result_set_table(rs, format, &options).output
+        );
+    }
+
+    let offline = report
+        .databases
+        .rows
+        .iter()
+        .zip(databases.iter())
+        .filter(|(_, db)| db["state"] != "ONLINE")
+        .map(|(row, _)| row.clone())
+        .collect::<Vec<_>>();
+    if !offline.is_empty() {
+        let rs = ResultSet {
+            columns: report.databases.columns.clone(),
+            rows: offline,
+        };
+        println!("Databases Not Online");
+        println!(
+            "{}",
+            table::render_result_set_table(&rs, format, &options).output
+        );
+    }
+
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// Fetch one section; on failure the section is empty and named in `unavailable`.
+async fn run_section(
+    session: &mut Session,
+    name: &str,
+    sql: &str,
+    unavailable: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) -> ResultSet {
+    match session.query_read(|| Query::new(sql)).await {
+        Ok(sets) => sets.into_iter().next().unwrap_or_default(),
+        Err(err) => {
+            unavailable.push(name.to_string());
+            warnings.push(format!("{} unavailable: {}", name, err));
+            ResultSet::default()
+        }
+    }
+}
+
+fn first_object(rs: &ResultSet) -> serde_json::Value {
+    json_out::result_set_rows_to_objects(rs)
+        .into_iter()
+        .next()
+        .unwrap_or(serde_json::Value::Null)
+}
+
+fn key_value_rows(rs: &ResultSet) -> Vec<(String, String)> {
+    let Some(row) = rs.rows.first() else {
+        return Vec::new();
+    };
+    rs.columns
+        .iter()
+        .zip(row.iter())
+        .map(|(col, value)| (col.name.clone(), value.as_display()))
+        .collect()
+}
+
+const SERVER_SQL: &str = r#"
+SELECT
+    @@SERVERNAME AS serverName,
+    CAST(SERVERPROPERTY('ProductVersion') AS nvarchar(128)) AS productVersion,
+    CAST(SERVERPROPERTY('ProductLevel') AS nvarchar(128)) AS productLevel,
+    CAST(SERVERPROPERTY('Edition') AS nvarchar(128)) AS edition,
+    CAST(ISNULL(SERVERPROPERTY('IsHadrEnabled'), 0) AS bit) AS hadrEnabled,
+    CAST(ISNULL(SERVERPROPERTY('IsClustered'), 0) AS bit) AS isClustered;
+"#;
+
+const HOST_SQL: &str = r#"
+SELECT
+    CONVERT(varchar(33), TODATETIMEOFFSET(sqlserver_start_time, DATEPART(TZOFFSET, SYSDATETIMEOFFSET())), 127) AS startTime,
+    CAST(DATEDIFF(SECOND, sqlserver_start_time, SYSDATETIME()) AS bigint) AS uptimeSeconds,
+    cpu_count AS cpuCount,
+    hyperthread_ratio AS hyperthreadRatio,
+    CAST(physical_memory_kb / 1024 AS bigint) AS physicalMemoryMb
+FROM sys.dm_os_sys_info;
+"#;
+
+const MEMORY_SQL: &str = r#"
+SELECT
+    CAST((SELECT value_in_use FROM sys.configurations WHERE name = 'max server memory (MB)') AS bigint) AS maxServerMemoryMb,
+    CAST(m.total_physical_memory_kb / 1024 AS bigint) AS totalMemoryMb,
+    CAST(m.available_physical_memory_kb / 1024 AS bigint) AS availableMemoryMb,
+    m.system_memory_state_desc AS memoryState,
+    CAST(p.physical_memory_in_use_kb / 1024 AS bigint) AS sqlMemoryInUseMb
+FROM sys.dm_os_sys_memory m
+CROSS JOIN sys.dm_os_process_memory p;
+"#;
+
+const TEMPDB_SQL: &str = r#"
+SELECT
+    name AS name,
+    type_desc AS fileType,
+    CAST(size / 128 AS bigint) AS sizeMb,
+    CASE WHEN is_percent_growth = 1 THEN CONCAT(growth, '%')
+         ELSE CONCAT(growth / 128, ' MB') END AS growth,
+    physical_name AS physicalName
+FROM tempdb.sys.database_files
+ORDER BY type_desc, file_id;
+"#;
+
+const DATABASES_SQL: &str = r#"
+SELECT
+    name AS name,
+    state_desc AS state,
+    user_access_desc AS userAccess,
+    recovery_model_desc AS recoveryModel,
+    CAST(is_read_only AS bit) AS isReadOnly
+FROM sys.databases
+ORDER BY name;
+"#;
+
+const AG_SQL: &str = r#"
+IF CAST(ISNULL(SERVERPROPERTY('IsHadrEnabled'), 0) AS int) = 1
+    SELECT
+        ag.name AS availabilityGroup,
+        ars.role_desc AS role,
+        ars.operational_state_desc AS operationalState,
+        ars.synchronization_health_desc AS synchronizationHealth
+    FROM sys.dm_hadr_availability_replica_states ars
+    INNER JOIN sys.availability_groups ag ON ag.group_id = ars.group_id
+    WHERE ars.is_local = 1
+    ORDER BY ag.name;
+ELSE
+    SELECT
+        CAST(NULL AS sysname) AS availabilityGroup,
+        CAST(NULL AS nvarchar(60)) AS role,
+        CAST(NULL AS nvarchar(60)) AS operationalState,
+        CAST(NULL AS nvarchar(60)) AS synchronizationHealth
+    WHERE 1 = 0;
+"#;
+
+const ERROR_LOG_SQL: &str = r#"
+DECLARE @log TABLE (LogDate datetime, ProcessInfo nvarchar(64), Text nvarchar(max));
+INSERT INTO @log EXEC sys.xp_readerrorlog 0, 1;
+SELECT TOP ({limit})
+    CONVERT(varchar(19), LogDate, 120) AS logDate,
+    ProcessInfo AS source,
+    Text AS message
+FROM @log
+WHERE LogDate >= DATEADD(HOUR, -24, GETDATE())
+  AND (Text LIKE '%error%' OR Text LIKE '%fail%' OR Text LIKE '%severity%')
+ORDER BY LogDate DESC;
+"#;
+
 fn value_to_string(value: Option<&Value>) -> String {
     match value {
         Some(Value::Null) | None => "unknown".to_string(),
         Some(v) => v.as_display(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::overall_status;
+    use serde_json::json;
+
+    #[test]
+    fn deep_status_degrades_on_any_failed_section() {
+        let online = vec![json!({ "name": "app", "state": "ONLINE" })];
+        let healthy = vec![json!({ "name": "ag1", "synchronizationHealth": "HEALTHY" })];
+        assert_eq!(overall_status(&online, &healthy, &[]), "ok");
+        assert_eq!(overall_status(&[], &[], &[]), "ok");
+
+        let offline = vec![json!({ "name": "app", "state": "RECOVERY_PENDING" })];
+        assert_eq!(overall_status(&offline, &healthy, &[]), "degraded");
+        let unhealthy =
+            vec![json!({ "name": "ag1", "synchronizationHealth": "PARTIALLY_HEALTHY" })];
+        assert_eq!(overall_status(&online, &unhealthy, &[]), "degraded");
+        assert_eq!(
+            overall_status(&online, &healthy, &["memory".to_string()]),
+            "degraded"
+        );
+    }
+}
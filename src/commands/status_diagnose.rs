@@ -0,0 +1,733 @@
+//! `status --diagnose`: walk a connection one layer at a time (DNS, SQL Server Browser for
+//! named instances, TCP, TDS pre-login, TLS, login, database) and report the first layer that
+//! fails with a hint, instead of the single error a normal connect gives.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, lookup_host};
+use tokio_util::compat::TokioAsyncWriteCompatExt;
+
+use crate::cli::CliArgs;
+use crate::commands::common;
+use crate::config::{ConnectionSettings, OutputFormat, ResolvedConfig};
+use crate::db::client::{self, AUTH_ERRORS};
+use crate::db::connection::{browser_lookup, build_config, split_instance};
+use crate::db::executor;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::error::ErrorKind;
+use crate::output::{TableOptions, json as json_out, table};
+
+/// `Cannot open database "x" requested by the login.`
+const CANNOT_OPEN_DATABASE: u32 = 4060;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepStatus {
+    Ok,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            StepStatus::Ok => "ok",
+            StepStatus::Warning => "warning",
+            StepStatus::Failed => "failed",
+            StepStatus::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    name: &'static str,
+    status: StepStatus,
+    elapsed_ms: Option<u128>,
+    detail: String,
+    hint: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Diagnosis {
+    steps: Vec<Step>,
+    /// How the failed step maps onto the usual exit codes.
+    failure: Option<ErrorKind>,
+}
+
+impl Diagnosis {
+    fn ok(&mut self, name: &'static str, started: Instant, detail: impl Into<String>) {
+        self.push(name, StepStatus::Ok, Some(started), detail.into(), None);
+    }
+
+    fn warn(&mut self, name: &'static str, started: Instant, detail: String, hint: String) {
+        self.push(name, StepStatus::Warning, Some(started), detail, Some(hint));
+    }
+
+    fn skip(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(name, StepStatus::Skipped, None, detail.into(), None);
+    }
+
+    fn fail(
+        &mut self,
+        name: &'static str,
+        started: Instant,
+        kind: ErrorKind,
+        detail: String,
+        hint: String,
+    ) {
+        self.push(name, StepStatus::Failed, Some(started), detail, Some(hint));
+        self.failure = Some(kind);
+    }
+
+    fn push(
+        &mut self,
+        name: &'static str,
+        status: StepStatus,
+        started: Option<Instant>,
+        detail: String,
+        hint: Option<String>,
+    ) {
+        self.steps.push(Step {
+            name,
+            status,
+            elapsed_ms: started.map(|at| at.elapsed().as_millis()),
+            detail,
+            hint,
+        });
+    }
+
+    fn failed_step(&self) -> Option<&'static str> {
+        self.steps
+            .iter()
+            .find(|step| step.status == StepStatus::Failed)
+            .map(|step| step.name)
+    }
+
+    /// Mark every planned step after the failure as not reached.
+    fn finish(mut self, plan: &[&'static str]) -> Self {
+        for name in plan {
+            if !self.steps.iter().any(|step| step.name == *name) {
+                self.skip(name, "not reached");
+            }
+        }
+        self
+    }
+}
+
+pub fn run(args: &CliArgs, resolved: &ResolvedConfig, format: OutputFormat) -> Result<()> {
+    let settings = &resolved.connection;
+    let mut plan = vec!["dns", "tcp", "prelogin", "tls", "login", "database"];
+    if browser_lookup(settings).is_some() {
+        plan.insert(1, "browser");
+    }
+    let diagnosis = tokio::runtime::Runtime::new()?
+        .block_on(diagnose(settings))
+        .finish(&plan);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "status": if diagnosis.failure.is_some() { "failed" } else { "ok" },
+            "failedStep": diagnosis.failed_step(),
+            "target": {
+                "server": settings.server,
+                "port": settings.port,
+                "database": settings.database,
+                "user": settings.user,
+                "encrypt": settings.encrypt,
+                "trustCert": settings.trust_cert,
+            },
+            "steps": diagnosis.steps.iter().map(|step| json!({
+                "step": step.name,
+                "status": step.status.as_str(),
+                "elapsedMs": step.elapsed_ms,
+                "detail": step.detail,
+                "hint": step.hint,
+            })).collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        let result = table::render_result_set_table(
+            &steps_to_result_set(&diagnosis.steps),
+            format,
+            &TableOptions::default(),
+        );
+        println!("{}", result.output);
+        for step in &diagnosis.steps {
+            if let Some(hint) = &step.hint {
+                println!("{}: {}", step.name, hint);
+            }
+        }
+        match diagnosis.failed_step() {
+            Some(step) => println!("\nFailed at {}.", step),
+            None => println!("\nAll checks passed."),
+        }
+    }
+
+    if let Some(kind) = diagnosis.failure {
+        kind.exit_code().exit();
+    }
+    Ok(())
+}
+
+async fn diagnose(settings: &ConnectionSettings) -> Diagnosis {
+    let mut report = Diagnosis::default();
+    let wait = (settings.timeout_ms > 0).then(|| Duration::from_millis(settings.timeout_ms));
+    let (host, _) = split_instance(&settings.server);
+
+    let started = Instant::now();
+    let addrs: Vec<SocketAddr> = match lookup_host((host, settings.port)).await {
+        Ok(addrs) => addrs.collect(),
+        Err(err) => {
+            report.fail(
+                "dns",
+                started,
+                ErrorKind::Connection,
+                err.to_string(),
+                format!(
+                    "'{}' does not resolve; check the spelling, the DNS suffix, or use the IP address",
+                    host
+                ),
+            );
+            return report;
+        }
+    };
+    let ips: Vec<String> = addrs.iter().map(|addr| addr.ip().to_string()).collect();
+    report.ok("dns", started, format!("{} -> {}", host, ips.join(", ")));
+
+    let mut port = settings.port;
+    if let Some((host, instance)) = browser_lookup(settings) {
+        let started = Instant::now();
+        let browse_wait = wait.unwrap_or(Duration::from_secs(5));
+        match client::instance_port(host, instance, browse_wait).await {
+            Ok(found) => {
+                port = found;
+                report.ok(
+                    "browser",
+                    started,
+                    format!("instance {} listens on TCP {}", instance, found),
+                );
+            }
+            Err(err) => {
+                report.fail(
+                    "browser",
+                    started,
+                    ErrorKind::Connection,
+                    err.to_string(),
+                    "Start the SQL Server Browser service and allow UDP 1434, or set `port` to \
+                     the instance's TCP port"
+                        .to_string(),
+                );
+                return report;
+            }
+        }
+    }
+    let targets: Vec<SocketAddr> = addrs
+        .iter()
+        .map(|addr| SocketAddr::new(addr.ip(), port))
+        .collect();
+
+    let started = Instant::now();
+    let mut stream = match bounded(wait, TcpStream::connect(&targets[..])).await {
+        Some(Ok(stream)) => stream,
+        Some(Err(err)) => {
+            let hint = if err.kind() == std::io::ErrorKind::ConnectionRefused {
+                format!(
+                    "Nothing accepts connections on port {}: check the port, that SQL Server is \
+                     running, and that its TCP/IP protocol is enabled",
+                    port
+                )
+            } else {
+                format!(
+                    "Port {} is unreachable: check firewalls and network routes to {}",
+                    port, host
+                )
+            };
+            report.fail("tcp", started, ErrorKind::Connection, err.to_string(), hint);
+            return report;
+        }
+        None => {
+            report.fail(
+                "tcp",
+                started,
+                ErrorKind::Connection,
+                format!("no answer within {} ms", settings.timeout_ms),
+                format!(
+                    "A firewall is probably dropping traffic to {}:{}; allow it or raise --timeout",
+                    host, port
+                ),
+            );
+            return report;
+        }
+    };
+    report.ok("tcp", started, format!("connected to {}:{}", host, port));
+
+    let started = Instant::now();
+    match bounded(wait, prelogin(&mut stream, settings.encrypt)).await {
+        Some(Ok(reply)) => {
+            let detail = format!(
+                "server version {}, encryption {}",
+                reply.version,
+                encryption_name(reply.encryption)
+            );
+            match encryption_mismatch(settings.encrypt, reply.encryption) {
+                Some(hint) => {
+                    report.fail("prelogin", started, ErrorKind::Connection, detail, hint);
+                    return report;
+                }
+                None => report.ok("prelogin", started, detail),
+            }
+        }
+        Some(Err(detail)) => {
+            report.fail(
+                "prelogin",
+                started,
+                ErrorKind::Connection,
+                detail,
+                format!(
+                    "{}:{} accepted TCP but did not answer as SQL Server; check the port",
+                    host, port
+                ),
+            );
+            return report;
+        }
+        None => {
+            report.fail(
+                "prelogin",
+                started,
+                ErrorKind::Connection,
+                format!("no pre-login reply within {} ms", settings.timeout_ms),
+                format!(
+                    "{}:{} accepted TCP but did not answer as SQL Server; check the port",
+                    host, port
+                ),
+            );
+            return report;
+        }
+    }
+    drop(stream);
+
+    let started = Instant::now();
+    let mut config = match build_config(settings) {
+        Ok(config) => config,
+        Err(err) => {
+            report.fail(
+                "tls",
+                started,
+                ErrorKind::Config,
+                err.to_string(),
+                "Fix the profile's encrypt / trustCert / tls settings".to_string(),
+            );
+            return report;
+        }
+    };
+    config.port(port);
+    let login = async {
+        let tcp = TcpStream::connect(&targets[..]).await?;
+        tcp.set_nodelay(true)?;
+        tiberius::Client::connect(config, tcp.compat_write()).await
+    };
+    let mut client = match bounded(wait, login).await {
+        Some(Ok(client)) => client,
+        Some(Err(err)) => {
+            classify_login_failure(&mut report, started, settings, err);
+            return report;
+        }
+        None => {
+            report.fail(
+                "login",
+                started,
+                ErrorKind::Connection,
+                format!("no login reply within {} ms", settings.timeout_ms),
+                "The server accepted the connection but stalled; check its error log".to_string(),
+            );
+            return report;
+        }
+    };
+    if settings.encrypt {
+        let detail = if settings.trust_cert {
+            "handshake complete (certificate not validated: trustCert)"
+        } else {
+            "handshake complete, certificate validated"
+        };
+        report.ok("tls", started, detail);
+    } else {
+        report.skip("tls", "encrypt: false");
+    }
+    report.ok(
+        "login",
+        started,
+        match &settings.user {
+            Some(user) => format!("logged in as {}", user),
+            None => "logged in".to_string(),
+        },
+    );
+
+    let started = Instant::now();
+    let query = Query::new(
+        "SELECT DB_NAME() AS name, SUSER_SNAME() AS login, HAS_PERMS_BY_NAME(DB_NAME(), 'DATABASE', 'CONNECT') AS canConnect",
+    );
+    match executor::run_query(query, &mut client).await {
+        Ok(sets) => {
+            let row = sets
+                .first()
+                .and_then(|set| set.rows.first().cloned())
+                .unwrap_or_default();
+            let name = text(row.first());
+            let login = text(row.get(1));
+            let detail = format!("in {} as {}", name, login);
+            if name.eq_ignore_ascii_case(&settings.database) {
+                report.ok("database", started, detail);
+            } else {
+                report.warn(
+                    "database",
+                    started,
+                    detail,
+                    format!(
+                        "Asked for '{}' but the session is in '{}'",
+                        settings.database, name
+                    ),
+                );
+            }
+        }
+        Err(err) => report.fail(
+            "database",
+            started,
+            ErrorKind::Query,
+            err.to_string(),
+            "Logged in, but a trivial query failed; check the login's permissions".to_string(),
+        ),
+    }
+    report
+}
+
+/// Sort a failed tiberius connect into the TLS, login, or database layer.
+fn classify_login_failure(
+    report: &mut Diagnosis,
+    started: Instant,
+    settings: &ConnectionSettings,
+    err: tiberius::error::Error,
+) {
+    let passed_tls = |report: &mut Diagnosis| {
+        if settings.encrypt {
+            report.ok("tls", started, "handshake complete");
+        } else {
+            report.skip("tls", "encrypt: false");
+        }
+    };
+    match &err {
+        tiberius::error::Error::Tls(message) => {
+            let hint = tls_hint(message, split_instance(&settings.server).0);
+            report.fail("tls", started, ErrorKind::Connection, err.to_string(), hint);
+        }
+        tiberius::error::Error::Server(token) if token.code() == CANNOT_OPEN_DATABASE => {
+            passed_tls(report);
+            report.ok("login", started, "credentials accepted");
+            report.fail(
+                "database",
+                started,
+                ErrorKind::Auth,
+                err.to_string(),
+                format!(
+                    "'{}' does not exist, is offline, or the login has no user in it; try \
+                     --database master",
+                    settings.database
+                ),
+            );
+        }
+        tiberius::error::Error::Server(token) if AUTH_ERRORS.contains(&token.code()) => {
+            passed_tls(report);
+            report.fail(
+                "login",
+                started,
+                ErrorKind::Auth,
+                err.to_string(),
+                login_hint(token.code()),
+            );
+        }
+        _ if settings.encrypt => {
+            report.fail(
+                "tls",
+                started,
+                ErrorKind::Connection,
+                err.to_string(),
+                "The connection dropped during the TLS handshake; check that the server has a \
+                 usable certificate, or try encrypt: false on a trusted network"
+                    .to_string(),
+            );
+        }
+        _ => {
+            report.skip("tls", "encrypt: false");
+            report.fail(
+                "login",
+                started,
+                ErrorKind::Connection,
+                err.to_string(),
+                "The server dropped the login; check its error log".to_string(),
+            );
+        }
+    }
+}
+
+fn tls_hint(message: &str, host: &str) -> String {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("notvalidforname") || lower.contains("hostname") {
+        format!(
+            "The certificate is not issued for '{}'; connect with the name on the certificate",
+            host
+        )
+    } else if lower.contains("unknownissuer")
+        || lower.contains("invalid peer certificate")
+        || lower.contains("certificate")
+    {
+        "The server certificate is not trusted: set tls.caCert to the issuing CA, or \
+         trustCert: true on a dev box"
+            .to_string()
+    } else {
+        "The TLS handshake failed; check the server's certificate and TLS settings".to_string()
+    }
+}
+
+fn login_hint(code: u32) -> String {
+    match code {
+        18470 => "The login is disabled; enable it with ALTER LOGIN ... ENABLE".to_string(),
+        18486 => "The login is locked out after failed attempts; unlock it or wait".to_string(),
+        18487 | 18488 => "The password has expired or must be changed before first use".to_string(),
+        18452 => "The server only accepts Windows logins; enable SQL Server authentication \
+                  (mixed mode) or use a Windows login"
+            .to_string(),
+        _ => "Check the user and password (or passwordEnv), and that SQL Server authentication \
+              (mixed mode) is enabled"
+            .to_string(),
+    }
+}
+
+async fn bounded<T>(wait: Option<Duration>, future: impl Future<Output = T>) -> Option<T> {
+    match wait {
+        Some(wait) => tokio::time::timeout(wait, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// What the server says in its PRELOGIN reply.
+#[derive(Debug, PartialEq, Eq)]
+struct PreloginReply {
+    version: String,
+    encryption: u8,
+}
+
+const ENCRYPT_OFF: u8 = 0x00;
+const ENCRYPT_ON: u8 = 0x01;
+const ENCRYPT_NOT_SUP: u8 = 0x02;
+const ENCRYPT_REQ: u8 = 0x03;
+
+/// A TDS PRELOGIN packet with the VERSION and ENCRYPTION options.
+fn prelogin_packet(encrypt: bool) -> Vec<u8> {
+    // Option table: two 5-byte entries plus the terminator, then the data.
+    let payload: Vec<u8> = vec![
+        0x00,
+        0x00,
+        11,
+        0x00,
+        6,
+        0x01,
+        0x00,
+        17,
+        0x00,
+        1,
+        0xFF,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        if encrypt { ENCRYPT_ON } else { ENCRYPT_NOT_SUP },
+    ];
+    let length = (payload.len() + 8) as u16;
+    let mut packet = vec![0x12, 0x01];
+    packet.extend_from_slice(&length.to_be_bytes());
+    packet.extend_from_slice(&[0x00, 0x00, 0x01, 0x00]);
+    packet.extend(payload);
+    packet
+}
+
+async fn prelogin(stream: &mut TcpStream, encrypt: bool) -> Result<PreloginReply, String> {
+    stream
+        .write_all(&prelogin_packet(encrypt))
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut header = [0u8; 8];
+    stream
+        .read_exact(&mut header)
+        .await
+        .map_err(|err| format!("no pre-login reply: {}", err))?;
+    if header[0] != 0x04 {
+        return Err(format!(
+            "unexpected reply type 0x{:02x} (not a TDS server)",
+            header[0]
+        ));
+    }
+    let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+    let mut payload = vec![0u8; length.saturating_sub(8)];
+    stream
+        .read_exact(&mut payload)
+        .await
+        .map_err(|err| format!("truncated pre-login reply: {}", err))?;
+    parse_prelogin(&payload)
+}
+
+fn parse_prelogin(payload: &[u8]) -> Result<PreloginReply, String> {
+    let mut reply = PreloginReply {
+        version: "unknown".to_string(),
+        encryption: ENCRYPT_NOT_SUP,
+    };
+    let mut pos = 0;
+    while pos < payload.len() && payload[pos] != 0xFF {
+        let entry = payload
+            .get(pos..pos + 5)
+            .ok_or_else(|| "malformed pre-login reply".to_string())?;
+        let offset = u16::from_be_bytes([entry[1], entry[2]]) as usize;
+        let length = u16::from_be_bytes([entry[3], entry[4]]) as usize;
+        let data = payload
+            .get(offset..offset + length)
+            .ok_or_else(|| "malformed pre-login reply".to_string())?;
+        match entry[0] {
+            0x00 if data.len() >= 4 => {
+                let build = u16::from_be_bytes([data[2], data[3]]);
+                reply.version = format!("{}.{}.{}", data[0], data[1], build);
+            }
+            0x01 if !data.is_empty() => reply.encryption = data[0],
+            _ => {}
+        }
+        pos += 5;
+    }
+    Ok(reply)
+}
+
+fn encryption_name(value: u8) -> &'static str {
+    match value {
+        ENCRYPT_OFF => "login only",
+        ENCRYPT_ON => "on",
+        ENCRYPT_NOT_SUP => "not supported",
+        ENCRYPT_REQ => "required",
+        _ => "unknown",
+    }
+}
+
+/// A hint when the client's `encrypt` setting and the server's answer cannot meet.
+fn encryption_mismatch(encrypt: bool, server: u8) -> Option<String> {
+    match (encrypt, server) {
+        (false, ENCRYPT_REQ) => Some(
+            "The server requires encryption; set encrypt: true (or drop --encrypt false)"
+                .to_string(),
+        ),
+        (true, ENCRYPT_NOT_SUP) => Some(
+            "The server cannot encrypt (no certificate configured); set encrypt: false or \
+             install a certificate on the server"
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::Text(text)) => text.clone(),
+        Some(Value::Null) | None => "unknown".to_string(),
+        Some(other) => format!("{:?}", other),
+    }
+}
+
+fn steps_to_result_set(steps: &[Step]) -> ResultSet {
+    let columns = ["step", "status", "ms", "detail"]
+        .iter()
+        .map(|name| Column {
+            name: name.to_string(),
+            data_type: None,
+        })
+        .collect();
+    let rows = steps
+        .iter()
+        .map(|step| {
+            vec![
+                Value::Text(step.name.to_string()),
+                Value::Text(step.status.as_str().to_string()),
+                step.elapsed_ms
+                    .map(|ms| Value::Int(ms as i64))
+                    .unwrap_or(Value::Null),
+                Value::Text(step.detail.clone()),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prelogin_round_trip_reads_version_and_encryption() {
+        let packet = prelogin_packet(true);
+        assert_eq!(packet.len(), 26);
+        assert_eq!(&packet[..4], &[0x12, 0x01, 0x00, 26]);
+        assert_eq!(packet[25], ENCRYPT_ON);
+
+        // VERSION 16.0.4135, ENCRYPTION required.
+        let reply = [
+            0x00,
+            0x00,
+            11,
+            0x00,
+            6,
+            0x01,
+            0x00,
+            17,
+            0x00,
+            1,
+            0xFF,
+            16,
+            0,
+            0x10,
+            0x27,
+            0,
+            0,
+            ENCRYPT_REQ,
+        ];
+        let parsed = parse_prelogin(&reply).expect("parse");
+        assert_eq!(parsed.version, "16.0.4135");
+        assert_eq!(parsed.encryption, ENCRYPT_REQ);
+        assert!(encryption_mismatch(false, parsed.encryption).is_some());
+        assert!(encryption_mismatch(true, parsed.encryption).is_none());
+        assert!(parse_prelogin(&[0x00, 0x00, 40, 0x00, 6, 0xFF]).is_err());
+    }
+
+    #[test]
+    fn unreached_steps_are_marked_skipped() {
+        let mut report = Diagnosis::default();
+        let started = Instant::now();
+        report.ok("dns", started, "db -> 10.0.0.5");
+        report.fail(
+            "tcp",
+            started,
+            ErrorKind::Connection,
+            "refused".to_string(),
+            "hint".to_string(),
+        );
+        let report = report.finish(&["dns", "tcp", "prelogin", "tls"]);
+        assert_eq!(report.failed_step(), Some("tcp"));
+        let statuses: Vec<&str> = report.steps.iter().map(|s| s.status.as_str()).collect();
+        assert_eq!(statuses, vec!["ok", "failed", "skipped", "skipped"]);
+        assert!(tls_hint("invalid peer certificate: UnknownIssuer", "db").contains("caCert"));
+    }
+}
@@ -1,14 +1,22 @@
 use anyhow::Result;
+use regex::{Regex, RegexBuilder};
 use serde_json::json;
 use tiberius::Query;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
 
 use crate::cli::{CliArgs, StoredProcsArgs};
-use crate::commands::{common, paging};
+use crate::commands::{
+    common, confirm, paging, sql_utils, stored_procs_history, stored_procs_test,
+};
 use crate::config::OutputFormat;
 use crate::db::client;
 use crate::db::executor;
+use crate::db::metadata_cache;
+use crate::db::queries::bracket;
 use crate::db::types::{Column, ResultSet, Value};
-use crate::output::{TableOptions, json as json_out, table};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, shape, table};
 use crate::safety;
 
 const LIMIT_DEFAULT: u64 = 10;
@@ -16,7 +24,16 @@ const LIMIT_MAX: u64 = 100;
 
 pub fn run(args: &CliArgs, cmd: &StoredProcsArgs) -> Result<()> {
     if let Some(proc_name) = cmd.exec.as_deref() {
-        return exec_proc(args, proc_name, cmd.args.as_deref(), cmd.no_truncate);
+        return exec_proc(args, cmd, proc_name);
+    }
+    if let Some(path) = cmd.test.as_deref() {
+        return stored_procs_test::run(args, path);
+    }
+    if let Some(proc_name) = cmd.history.as_deref() {
+        return stored_procs_history::run(args, cmd, proc_name);
+    }
+    if let Some(pattern) = cmd.grep.as_deref() {
+        return grep_procs(args, cmd, pattern);
     }
 
     list_procs(args, cmd)
@@ -38,22 +55,25 @@ fn list_procs(args: &CliArgs, cmd: &StoredProcsArgs) -> Result<()> {
         None => (None, None),
     };
     let schema = cmd.schema.clone().or(schema_from_name);
+    let since = cmd.modified_since.as_ref();
 
     let (rows, total) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
-        let list_sql = r#"
+        let list_sql = format!(
+            r#"
 WITH filtered AS (
     SELECT
         s.name AS schemaName,
         p.name AS procName,
         p.is_ms_shipped AS isSystem,
         p.modify_date AS modifiedAt,
-        ROW_NUMBER() OVER (ORDER BY s.name, p.name) AS rownum
+        ROW_NUMBER() OVER (ORDER BY {}) AS rownum
     FROM sys.procedures p
     INNER JOIN sys.schemas s ON p.schema_id = s.schema_id
     WHERE (@P1 IS NULL OR s.name = @P1)
       AND (@P2 IS NULL OR p.name LIKE @P2)
       AND (@P3 = 1 OR p.is_ms_shipped = 0)
+      {}
 )
 SELECT schemaName AS [schema],
        procName AS name,
@@ -61,8 +81,12 @@ SELECT schemaName AS [schema],
        modifiedAt AS modifiedAt
 FROM filtered
 WHERE rownum BETWEEN (@P4 + 1) AND (@P4 + @P5)
-ORDER BY schemaName, procName;
-"#;
+ORDER BY {};
+"#,
+            common::order_keys(&resolved, "s.name, p.name", "p.object_id"),
+            common::modified_since_clause("p.modify_date", since, "@P6"),
+            common::order_keys(&resolved, "schemaName, procName", "rownum"),
+        );
 
         let mut list_query = Query::new(list_sql);
         list_query.bind(schema.as_deref());
@@ -70,22 +94,30 @@ ORDER BY schemaName, procName;
         list_query.bind(if include_system { 1i32 } else { 0i32 });
         list_query.bind(offset as i64);
         list_query.bind(limit as i64);
-        let list_sets = executor::run_query(list_query, &mut client).await?;
+        common::bind_modified_since(&mut list_query, since);
+        let list_sets =
+            metadata_cache::run_query_when(since.is_none(), list_query, &mut client).await?;
         let list_set = list_sets.into_iter().next().unwrap_or_default();
 
-        let count_sql = r#"
+        let count_sql = format!(
+            r#"
 SELECT COUNT(*) AS total
 FROM sys.procedures p
 INNER JOIN sys.schemas s ON p.schema_id = s.schema_id
 WHERE (@P1 IS NULL OR s.name = @P1)
   AND (@P2 IS NULL OR p.name LIKE @P2)
-  AND (@P3 = 1 OR p.is_ms_shipped = 0);
-"#;
+  AND (@P3 = 1 OR p.is_ms_shipped = 0)
+  {};
+"#,
+            common::modified_since_clause("p.modify_date", since, "@P4")
+        );
         let mut count_query = Query::new(count_sql);
         count_query.bind(schema.as_deref());
         count_query.bind(name.as_deref());
         count_query.bind(if include_system { 1i32 } else { 0i32 });
-        let count_sets = executor::run_query(count_query, &mut client).await?;
+        common::bind_modified_since(&mut count_query, since);
+        let count_sets =
+            metadata_cache::run_query_when(since.is_none(), count_query, &mut client).await?;
         let total = count_sets
             .first()
             .and_then(|rs| rs.rows.first())
@@ -115,7 +147,7 @@ WHERE (@P1 IS NULL OR s.name = @P1)
             row.first().cloned().unwrap_or(Value::Null),
             Value::Text(name),
             row.get(2).cloned().unwrap_or(Value::Null),
-            Value::Text(if is_allowed { "yes" } else { "no" }.to_string()),
+            Value::Bool(is_allowed),
             row.get(3).cloned().unwrap_or(Value::Null),
         ]);
     }
@@ -145,6 +177,7 @@ WHERE (@P1 IS NULL OR s.name = @P1)
         ],
         rows: enriched_rows,
     };
+    let result_set = shape::apply(result_set, &args.output)?;
 
     if matches!(format, OutputFormat::Json) {
         let payload = json!({
@@ -182,31 +215,337 @@ WHERE (@P1 IS NULL OR s.name = @P1)
     Ok(())
 }
 
-fn exec_proc(
-    args: &CliArgs,
-    proc_name: &str,
-    raw_args: Option<&str>,
-    no_truncate: bool,
-) -> Result<()> {
+/// Consecutive definition lines shown for one or more nearby matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    start_line: usize,
+    lines: Vec<(String, bool)>,
+}
+
+#[derive(Debug, Clone)]
+struct ProcMatch {
+    schema: String,
+    name: String,
+    match_count: usize,
+    hunks: Vec<Hunk>,
+}
+
+fn grep_procs(args: &CliArgs, cmd: &StoredProcsArgs, pattern: &str) -> Result<()> {
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| AppError::new(ErrorKind::Query, format!("Invalid --grep: {}", err)))?;
+
     let resolved = common::load_config(args)?;
     let format = common::output_format(args, &resolved);
-
-    let statement = if let Some(extra) = raw_args {
-        format!("EXEC {} {}", proc_name, extra)
-    } else {
-        format!("EXEC {}", proc_name)
+    let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
+    let offset = common::parse_offset(cmd.offset);
+    let (name, schema_from_name) = match cmd.name.as_deref() {
+        Some(n) => {
+            let (name, schema_opt) = common::normalize_object_input(n);
+            (Some(name), schema_opt)
+        }
+        None => (None, None),
     };
+    let schema = cmd.schema.clone().or(schema_from_name);
+
+    // Regexes cannot be pushed to the server, so definitions are matched locally.
+    let definitions = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let sql = format!(
+            r#"
+SELECT s.name AS [schema], p.name, m.definition
+FROM sys.procedures p
+INNER JOIN sys.schemas s ON p.schema_id = s.schema_id
+INNER JOIN sys.sql_modules m ON m.object_id = p.object_id
+WHERE (@P1 IS NULL OR s.name = @P1)
+  AND (@P2 IS NULL OR p.name LIKE @P2)
+  AND (@P3 = 1 OR p.is_ms_shipped = 0)
+  {}
+ORDER BY {};
+"#,
+            common::modified_since_clause("p.modify_date", cmd.modified_since.as_ref(), "@P4"),
+            common::order_keys(&resolved, "s.name, p.name", "p.object_id")
+        );
+        let mut query = Query::new(sql);
+        query.bind(schema.as_deref());
+        query.bind(name.as_deref());
+        query.bind(if cmd.include_system { 1i32 } else { 0i32 });
+        common::bind_modified_since(&mut query, cmd.modified_since.as_ref());
+        let cacheable = cmd.modified_since.is_none();
+        let sets = metadata_cache::run_query_when(cacheable, query, &mut client).await?;
+        Ok::<_, anyhow::Error>(sets.into_iter().next().unwrap_or_default())
+    })?;
+
+    let matches: Vec<ProcMatch> = definitions
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let definition = value_to_string(row.get(2));
+            let (match_count, hunks) = grep_definition(&definition, &regex, cmd.context as usize);
+            (match_count > 0).then(|| ProcMatch {
+                schema: value_to_string(row.first()),
+                name: value_to_string(row.get(1)),
+                match_count,
+                hunks,
+            })
+        })
+        .collect();
+
+    let total = matches.len() as u64;
+    let page: Vec<ProcMatch> = matches
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+    let paging = paging::build_paging(total, page.len() as u64, offset, limit);
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "pattern": pattern,
+            "total": paging.total,
+            "count": paging.count,
+            "offset": paging.offset,
+            "limit": paging.limit,
+            "hasMore": paging.has_more,
+            "nextOffset": paging.next_offset,
+            "procedures": page
+                .iter()
+                .map(|m| json!({
+                    "schema": m.schema,
+                    "name": m.name,
+                    "matchCount": m.match_count,
+                    "hunks": m
+                        .hunks
+                        .iter()
+                        .map(|hunk| json!({
+                            "startLine": hunk.start_line,
+                            "lines": hunk
+                                .lines
+                                .iter()
+                                .enumerate()
+                                .map(|(idx, (text, matched))| json!({
+                                    "line": hunk.start_line + idx,
+                                    "text": text,
+                                    "match": matched,
+                                }))
+                                .collect::<Vec<_>>(),
+                        }))
+                        .collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    if page.is_empty() {
+        println!("No procedures match /{}/.", pattern);
+        return Ok(());
+    }
+    for (idx, m) in page.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        println!(
+            "{}.{} ({} {})",
+            m.schema,
+            m.name,
+            m.match_count,
+            if m.match_count == 1 {
+                "match"
+            } else {
+                "matches"
+            }
+        );
+        let width = m
+            .hunks
+            .last()
+            .map(|hunk| (hunk.start_line + hunk.lines.len()).to_string().len())
+            .unwrap_or(1);
+        for (hunk_idx, hunk) in m.hunks.iter().enumerate() {
+            if hunk_idx > 0 {
+                println!("  --");
+            }
+            for (line_idx, (text, matched)) in hunk.lines.iter().enumerate() {
+                println!(
+                    "{} {:>width$}  {}",
+                    if *matched { ">" } else { " " },
+                    hunk.start_line + line_idx,
+                    text,
+                    width = width
+                );
+            }
+        }
+    }
+    if paging.has_more {
+        println!("\n---");
+        println!(
+            "Showing {} of {} matching procedures. Next: --offset {}",
+            paging.count,
+            paging.total,
+            paging.next_offset.unwrap_or(offset + paging.count)
+        );
+    }
+    Ok(())
+}
+
+/// Matching lines with `context` lines either side; overlapping windows merge into one hunk.
+fn grep_definition(definition: &str, regex: &Regex, context: usize) -> (usize, Vec<Hunk>) {
+    let lines: Vec<&str> = definition
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect();
+    let hits: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    let mut windows: Vec<(usize, usize)> = Vec::new();
+    for &hit in &hits {
+        let start = hit.saturating_sub(context);
+        let end = (hit + context).min(lines.len() - 1);
+        match windows.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = end,
+            _ => windows.push((start, end)),
+        }
+    }
+
+    let hunks = windows
+        .into_iter()
+        .map(|(start, end)| Hunk {
+            start_line: start + 1,
+            lines: (start..=end)
+                .map(|idx| (lines[idx].to_string(), hits.binary_search(&idx).is_ok()))
+                .collect(),
+        })
+        .collect();
+    (hits.len(), hunks)
+}
 
-    let result_sets = tokio::runtime::Runtime::new()?.block_on(async {
+pub(crate) const PROC_LOOKUP_SQL: &str = r#"
+SELECT o.object_id, OBJECT_SCHEMA_NAME(o.object_id) AS [schema], o.name
+FROM sys.all_objects o
+WHERE o.object_id = OBJECT_ID(@P1)
+  AND o.type IN ('P', 'PC', 'X');
+"#;
+
+/// Alias types resolve to their base type so locals can be declared without schema lookups.
+const PROC_PARAMS_SQL: &str = r#"
+SELECT
+    p.name,
+    CASE WHEN t.is_user_defined = 1 AND t.is_assembly_type = 0
+         THEN TYPE_NAME(p.system_type_id) ELSE t.name END AS type_name,
+    p.max_length,
+    p.precision,
+    p.scale,
+    p.is_output,
+    t.is_table_type
+FROM sys.all_parameters p
+JOIN sys.types t ON t.user_type_id = p.user_type_id
+WHERE p.object_id = @P1
+  AND p.parameter_id > 0
+ORDER BY p.parameter_id;
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ProcParam {
+    /// Name without the leading `@`.
+    name: String,
+    type_name: String,
+    max_length: i64,
+    precision: i64,
+    scale: i64,
+    is_output: bool,
+    is_table_type: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum BoundValue {
+    Int(i64),
+    Bool(bool),
+    Float(f64),
+    Text(String),
+}
+
+pub(super) struct ExecBatch {
+    pub(super) sql: String,
+    pub(super) values: Vec<BoundValue>,
+    /// Output parameter names in the order they follow the return value in the final SELECT.
+    pub(super) outputs: Vec<String>,
+}
+
+fn exec_proc(args: &CliArgs, cmd: &StoredProcsArgs, proc_name: &str) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let supplied = sql_utils::parse_params(&cmd.params)
+        .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
+    let raw_args = cmd.args.as_deref();
+    if safety::validate_read_only(&format!("EXEC {}", proc_name)).is_err() {
+        confirm::confirm_write(args, &resolved, &format!("execute procedure {}", proc_name))?;
+    }
+
+    let (result_sets, returned) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
-        let query = Query::new(statement);
-        executor::run_query(query, &mut client).await
+        // --args stays a raw escape hatch; everything else is bound against the catalog.
+        let target = match raw_args {
+            Some(_) => None,
+            None => lookup_proc(&mut client, proc_name).await?,
+        };
+        let Some((qualified, params)) = target else {
+            if !supplied.is_empty() {
+                return Err(AppError::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Procedure '{}' not found in the current database; --param needs its metadata.",
+                        proc_name
+                    ),
+                )
+                .into());
+            }
+            let statement = match raw_args {
+                Some(extra) => format!("EXEC {} {}", proc_name, extra),
+                None => format!("EXEC {}", proc_name),
+            };
+            let sets = executor::run_query(Query::new(statement), &mut client).await?;
+            return Ok::<_, anyhow::Error>((sets, None));
+        };
+
+        let batch = build_exec_batch(&qualified, &params, &supplied)?;
+        let mut query = Query::new(batch.sql);
+        bind_values(&mut query, batch.values);
+        let mut sets = executor::run_query(query, &mut client).await?;
+        // The trailing SELECT always returns exactly one row, so it is the last set.
+        let returned = sets.pop().and_then(|rs| rs.rows.into_iter().next());
+        Ok((sets, returned.map(|row| (row, batch.outputs))))
     })?;
 
+    let return_value = returned.as_ref().and_then(|(row, _)| row.first().cloned());
+    let outputs: Vec<(String, Value)> = returned
+        .map(|(row, names)| names.into_iter().zip(row.into_iter().skip(1)).collect())
+        .unwrap_or_default();
+
     if matches!(format, OutputFormat::Json) {
+        let mut output_params = serde_json::Map::new();
+        for (name, value) in &outputs {
+            output_params.insert(name.clone(), serde_json::to_value(value)?);
+        }
         let payload = json!({
             "procedure": proc_name,
-            "resultSets": result_sets.iter().map(json_out::result_set_to_json).collect::<Vec<_>>(),
+            "returnValue": return_value,
+            "outputParameters": output_params,
+            "resultSets": result_sets
+                .iter()
+                .map(|rs| json_out::result_set_to_json(rs, resolved.settings.output.max_value_bytes))
+                .collect::<Vec<_>>(),
         });
         let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
@@ -219,16 +558,17 @@ fn exec_proc(
         return Ok(());
     }
 
-    if result_sets.is_empty() {
+    if result_sets.is_empty() && return_value.is_none() {
         println!("Procedure executed (no result set).");
         return Ok(());
     }
 
-    let table_options = if no_truncate {
+    let mut table_options = if cmd.no_truncate {
         TableOptions::unlimited()
     } else {
         TableOptions::truncated()
     };
+    table_options.max_value_bytes = resolved.settings.output.max_value_bytes;
 
     for (idx, result_set) in result_sets.iter().enumerate() {
         if result_sets.len() > 1 {
@@ -241,15 +581,329 @@ fn exec_proc(
         }
     }
 
+    if let Some(value) = return_value {
+        if !result_sets.is_empty() {
+            println!();
+        }
+        println!("Return value: {}", value.as_display());
+        if !outputs.is_empty() {
+            let output_set = ResultSet {
+                columns: vec![
+                    Column {
+                        name: "parameter".to_string(),
+                        data_type: None,
+                    },
+                    Column {
+                        name: "value".to_string(),
+                        data_type: None,
+                    },
+                ],
+                rows: outputs
+                    .into_iter()
+                    .map(|(name, value)| vec![Value::Text(format!("@{}", name)), value])
+                    .collect(),
+            };
+            let result = table::render_result_set_table(&output_set, format, &table_options);
+            println!("{}", result.output);
+        }
+    }
+
     Ok(())
 }
 
+pub(super) fn bind_values(query: &mut Query<'_>, values: Vec<BoundValue>) {
+    for value in values {
+        match value {
+            BoundValue::Int(v) => query.bind(v),
+            BoundValue::Bool(v) => query.bind(v),
+            BoundValue::Float(v) => query.bind(v),
+            BoundValue::Text(v) => query.bind(v),
+        }
+    }
+}
+
+pub(super) async fn lookup_proc(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    proc_name: &str,
+) -> Result<Option<(String, Vec<ProcParam>)>> {
+    let mut query = Query::new(PROC_LOOKUP_SQL);
+    query.bind(proc_name);
+    let sets = executor::run_query(query, client).await?;
+    let Some(row) = sets.first().and_then(|rs| rs.rows.first()) else {
+        return Ok(None);
+    };
+    let object_id = match row.first() {
+        Some(Value::Int(v)) => *v,
+        _ => return Ok(None),
+    };
+    let qualified = format!(
+        "{}.{}",
+        bracket(&value_to_string(row.get(1))),
+        bracket(&value_to_string(row.get(2)))
+    );
+
+    let mut query = Query::new(PROC_PARAMS_SQL);
+    query.bind(object_id as i32);
+    let sets = executor::run_query(query, client).await?;
+    let int = |value: Option<&Value>| match value {
+        Some(Value::Int(v)) => *v,
+        _ => 0,
+    };
+    let params = sets
+        .first()
+        .map(|rs| rs.rows.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|row| ProcParam {
+            name: value_to_string(row.first())
+                .trim_start_matches('@')
+                .to_string(),
+            type_name: value_to_string(row.get(1)),
+            max_length: int(row.get(2)),
+            precision: int(row.get(3)),
+            scale: int(row.get(4)),
+            is_output: matches!(row.get(5), Some(Value::Bool(true))),
+            is_table_type: matches!(row.get(6), Some(Value::Bool(true))),
+        })
+        .collect();
+    Ok(Some((qualified, params)))
+}
+
+/// Bind supplied values by name and declare a typed local for every OUTPUT parameter, so
+/// the trailing SELECT can report the return value and output values after EXEC.
+pub(super) fn build_exec_batch(
+    qualified: &str,
+    params: &[ProcParam],
+    supplied: &[sql_utils::SqlParam],
+) -> Result<ExecBatch> {
+    for entry in supplied {
+        let name = entry.name.trim_start_matches('@');
+        if !params.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+            let known = params
+                .iter()
+                .map(|p| format!("@{}", p.name))
+                .collect::<Vec<_>>();
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Unknown parameter '@{}' for {}. Parameters: {}",
+                    name,
+                    qualified,
+                    if known.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                ),
+            )
+            .into());
+        }
+    }
+
+    let mut declares = vec!["DECLARE @__return_value int;".to_string()];
+    let mut arguments = Vec::new();
+    let mut values = Vec::new();
+    let mut outputs = Vec::new();
+    for param in params {
+        let value = supplied.iter().rev().find(|entry| {
+            entry
+                .name
+                .trim_start_matches('@')
+                .eq_ignore_ascii_case(&param.name)
+        });
+        if param.is_table_type {
+            if value.is_some() {
+                return Err(AppError::new(
+                    ErrorKind::Query,
+                    format!(
+                        "Parameter '@{}' is table-valued; use --args or sql to pass it.",
+                        param.name
+                    ),
+                )
+                .into());
+            }
+            continue;
+        }
+        let placeholder = match value {
+            Some(entry) => {
+                values.push(bind_value(param, &entry.value.to_string())?);
+                Some(format!("@P{}", values.len()))
+            }
+            None => None,
+        };
+        if param.is_output {
+            let local = format!("@__out_{}", outputs.len() + 1);
+            declares.push(format!(
+                "DECLARE {} {}{};",
+                local,
+                type_declaration(param),
+                placeholder.map(|p| format!(" = {}", p)).unwrap_or_default()
+            ));
+            arguments.push(format!("@{} = {} OUTPUT", param.name, local));
+            outputs.push(param.name.clone());
+        } else if let Some(placeholder) = placeholder {
+            arguments.push(format!("@{} = {}", param.name, placeholder));
+        }
+    }
+
+    let mut select = vec!["@__return_value AS [returnValue]".to_string()];
+    for (idx, name) in outputs.iter().enumerate() {
+        select.push(format!("@__out_{} AS {}", idx + 1, bracket(name)));
+    }
+    let sql = format!(
+        "{}\nEXEC @__return_value = {}{}{};\nSELECT {};",
+        declares.join("\n"),
+        qualified,
+        if arguments.is_empty() { "" } else { " " },
+        arguments.join(", "),
+        select.join(", ")
+    );
+    Ok(ExecBatch {
+        sql,
+        values,
+        outputs,
+    })
+}
+
+/// Parse the value for the parameter's type so bad input fails before reaching the server.
+fn bind_value(param: &ProcParam, raw: &str) -> Result<BoundValue> {
+    let invalid = || {
+        AppError::new(
+            ErrorKind::Query,
+            format!(
+                "Invalid value '{}' for @{} ({}).",
+                raw, param.name, param.type_name
+            ),
+        )
+    };
+    let value = match param.type_name.to_ascii_lowercase().as_str() {
+        "tinyint" | "smallint" | "int" | "bigint" => {
+            BoundValue::Int(raw.trim().parse().map_err(|_| invalid())?)
+        }
+        "bit" => match raw.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" => BoundValue::Bool(true),
+            "0" | "false" | "no" => BoundValue::Bool(false),
+            _ => return Err(invalid().into()),
+        },
+        "float" | "real" => BoundValue::Float(raw.trim().parse().map_err(|_| invalid())?),
+        _ => BoundValue::Text(raw.to_string()),
+    };
+    Ok(value)
+}
+
+fn type_declaration(param: &ProcParam) -> String {
+    let name = param.type_name.to_ascii_lowercase();
+    let length = |chars: i64| {
+        if param.max_length < 0 {
+            "max".to_string()
+        } else {
+            chars.to_string()
+        }
+    };
+    match name.as_str() {
+        "char" | "varchar" | "binary" | "varbinary" => {
+            format!("{}({})", name, length(param.max_length))
+        }
+        "nchar" | "nvarchar" => format!("{}({})", name, length(param.max_length / 2)),
+        "decimal" | "numeric" => format!("{}({}, {})", name, param.precision, param.scale),
+        "datetime2" | "time" | "datetimeoffset" => format!("{}({})", name, param.scale),
+        _ => name,
+    }
+}
+
 fn value_to_string(value: Option<&Value>) -> String {
     match value {
         Some(Value::Text(v)) => v.clone(),
         Some(Value::Int(v)) => v.to_string(),
         Some(Value::Bool(v)) => v.to_string(),
         Some(Value::Float(v)) => v.to_string(),
+        Some(Value::Decimal(v)) => v.to_string(),
         _ => "".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grep_definition_merges_overlapping_context() {
+        let definition = "CREATE PROCEDURE dbo.Ship\r\nAS\r\nBEGIN\r\n  UPDATE Orders SET Shipped = 1\r\n  WHERE OrderStatus = 5\r\n    AND orderstatus  =  5\r\nEND\r\n\r\n-- trailer\r\nSELECT OrderStatus = 5";
+        let regex = RegexBuilder::new(r"OrderStatus\s*=\s*5")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let (count, hunks) = grep_definition(definition, &regex, 1);
+        assert_eq!(count, 3);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].start_line, 4);
+        assert_eq!(
+            hunks[0].lines.iter().map(|(_, m)| *m).collect::<Vec<_>>(),
+            vec![false, true, true, false]
+        );
+        assert_eq!(hunks[1].start_line, 9);
+        assert_eq!(hunks[1].lines.len(), 2);
+        assert_eq!(hunks[1].lines[1].0, "SELECT OrderStatus = 5");
+
+        let (count, hunks) = grep_definition(definition, &regex, 0);
+        assert_eq!(count, 3);
+        assert_eq!(hunks.len(), 2);
+    }
+
+    fn param(name: &str, type_name: &str, max_length: i64, is_output: bool) -> ProcParam {
+        ProcParam {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            max_length,
+            precision: 0,
+            scale: 0,
+            is_output,
+            is_table_type: false,
+        }
+    }
+
+    #[test]
+    fn exec_batch_binds_by_name_and_captures_outputs() {
+        let mut total = param("Total", "decimal", 9, true);
+        total.precision = 18;
+        total.scale = 2;
+        let params = vec![
+            param("CustomerId", "int", 4, false),
+            param("Note", "nvarchar", 200, false),
+            total,
+            param("Message", "nvarchar", -1, true),
+        ];
+        let supplied = sql_utils::parse_params(&[
+            "@customerid=42".to_string(),
+            "Message=it's fine".to_string(),
+        ])
+        .unwrap();
+
+        let batch = build_exec_batch("[dbo].[PlaceOrder]", &params, &supplied).unwrap();
+        assert_eq!(
+            batch.sql,
+            "DECLARE @__return_value int;\n\
+             DECLARE @__out_1 decimal(18, 2);\n\
+             DECLARE @__out_2 nvarchar(max) = @P2;\n\
+             EXEC @__return_value = [dbo].[PlaceOrder] @CustomerId = @P1, @Total = @__out_1 OUTPUT, @Message = @__out_2 OUTPUT;\n\
+             SELECT @__return_value AS [returnValue], @__out_1 AS [Total], @__out_2 AS [Message];"
+        );
+        assert_eq!(
+            batch.values,
+            vec![
+                BoundValue::Int(42),
+                BoundValue::Text("it's fine".to_string())
+            ]
+        );
+        assert_eq!(batch.outputs, vec!["Total", "Message"]);
+
+        let bad = sql_utils::parse_params(&["CustomerId=abc".to_string()]).unwrap();
+        assert!(build_exec_batch("[dbo].[PlaceOrder]", &params, &bad).is_err());
+        let unknown = sql_utils::parse_params(&["Nope=1".to_string()]).unwrap();
+        let err = build_exec_batch("[dbo].[PlaceOrder]", &params, &unknown)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("@CustomerId, @Note, @Total, @Message"));
+    }
+}
@@ -0,0 +1,216 @@
+//! `stored-procs --history`: execution statistics for one procedure.
+//!
+//! The plan cache (`sys.dm_exec_procedure_stats`) covers executions since the plan was cached;
+//! Query Store, when enabled, adds a daily trend that survives recompiles and restarts. Query
+//! Store records statements rather than procedures, so a day's executions are those of its
+//! most-executed statement and per-execution costs sum every statement.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, StoredProcsArgs};
+use crate::commands::{common, stored_procs};
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::permissions;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, json as json_out, table};
+
+const PLAN_CACHE_SQL: &str = r#"
+SELECT
+    SUM(ps.execution_count) AS executionCount,
+    CAST(SUM(ps.total_elapsed_time) / 1000.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgDurationMs,
+    CAST(MAX(ps.max_elapsed_time) / 1000.0 AS float) AS maxDurationMs,
+    CAST(SUM(ps.total_worker_time) / 1000.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgCpuMs,
+    CAST(SUM(ps.total_logical_reads) * 1.0 / NULLIF(SUM(ps.execution_count), 0) AS float) AS avgLogicalReads,
+    MIN(ps.cached_time) AS cachedSince,
+    MAX(ps.last_execution_time) AS lastExecution
+FROM sys.dm_exec_procedure_stats ps
+WHERE ps.database_id = DB_ID()
+  AND ps.object_id = @P1
+HAVING COUNT(*) > 0;
+"#;
+
+const QUERY_STORE_STATE_SQL: &str =
+    "SELECT actual_state_desc FROM sys.database_query_store_options;";
+
+const QUERY_STORE_DAILY_SQL: &str = r#"
+WITH per_statement AS (
+    SELECT
+        CAST(rsi.start_time AS date) AS day,
+        q.query_id,
+        SUM(rs.count_executions) AS executions,
+        SUM(rs.avg_duration * rs.count_executions) AS total_duration,
+        SUM(rs.avg_cpu_time * rs.count_executions) AS total_cpu,
+        SUM(rs.avg_logical_io_reads * rs.count_executions) AS total_reads,
+        MAX(rs.max_duration) AS max_duration
+    FROM sys.query_store_query q
+    JOIN sys.query_store_plan p ON p.query_id = q.query_id
+    JOIN sys.query_store_runtime_stats rs ON rs.plan_id = p.plan_id
+    JOIN sys.query_store_runtime_stats_interval rsi
+        ON rsi.runtime_stats_interval_id = rs.runtime_stats_interval_id
+    WHERE q.object_id = @P1
+      AND rsi.start_time >= DATEADD(day, -@P2, SYSDATETIMEOFFSET())
+    GROUP BY CAST(rsi.start_time AS date), q.query_id
+)
+SELECT
+    CONVERT(char(10), day, 23) AS day,
+    MAX(executions) AS executions,
+    CAST(SUM(total_duration) / 1000.0 / NULLIF(MAX(executions), 0) AS float) AS avgDurationMs,
+    CAST(MAX(max_duration) / 1000.0 AS float) AS maxStatementDurationMs,
+    CAST(SUM(total_cpu) / 1000.0 / NULLIF(MAX(executions), 0) AS float) AS avgCpuMs,
+    CAST(SUM(total_reads) / NULLIF(MAX(executions), 0) AS float) AS avgLogicalReads
+FROM per_statement
+GROUP BY day
+ORDER BY day;
+"#;
+
+struct History {
+    schema: String,
+    name: String,
+    plan_cache: Option<ResultSet>,
+    query_store_state: Option<String>,
+    daily: ResultSet,
+    warnings: Vec<String>,
+}
+
+pub fn run(args: &CliArgs, cmd: &StoredProcsArgs, proc_name: &str) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let days = cmd.days;
+
+    let history = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut query = Query::new(stored_procs::PROC_LOOKUP_SQL);
+        query.bind(proc_name);
+        let sets = executor::run_query(query, &mut client).await?;
+        let row = sets.first().and_then(|rs| rs.rows.first()).ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Procedure '{}' not found in the current database.",
+                    proc_name
+                ),
+            )
+        })?;
+        let object_id = match row.first() {
+            Some(Value::Int(v)) => *v as i32,
+            _ => 0,
+        };
+        let schema = text(row.get(1));
+        let name = text(row.get(2));
+        let mut warnings = Vec::new();
+
+        let permission =
+            permissions::check_server_permission(&mut client, permissions::VIEW_SERVER_STATE)
+                .await?;
+        let plan_cache = if permission.granted {
+            let mut query = Query::new(PLAN_CACHE_SQL);
+            query.bind(object_id);
+            let sets = executor::run_query(query, &mut client).await?;
+            sets.into_iter().next().filter(|rs| !rs.rows.is_empty())
+        } else {
+            warnings.push(permission.warning("plan cache statistics cannot be read"));
+            None
+        };
+
+        // Query Store options do not exist before SQL Server 2016.
+        let state = executor::run_query(Query::new(QUERY_STORE_STATE_SQL), &mut client)
+            .await
+            .ok()
+            .and_then(|sets| sets.into_iter().next())
+            .and_then(|rs| rs.rows.into_iter().next())
+            .map(|row| text(row.first()));
+        let mut daily = ResultSet::default();
+        match state.as_deref() {
+            Some("READ_WRITE") | Some("READ_ONLY") => {
+                let mut query = Query::new(QUERY_STORE_DAILY_SQL);
+                query.bind(object_id);
+                query.bind(days as i32);
+                match executor::run_query(query, &mut client).await {
+                    Ok(sets) => daily = sets.into_iter().next().unwrap_or_default(),
+                    Err(err) => warnings.push(format!("Query Store could not be read: {}", err)),
+                }
+            }
+            Some(other) => warnings.push(format!(
+                "Query Store is {} in this database; only plan cache statistics are shown",
+                other
+            )),
+            None => warnings.push(
+                "Query Store is not available on this server; only plan cache statistics are shown"
+                    .to_string(),
+            ),
+        }
+
+        Ok::<_, anyhow::Error>(History {
+            schema,
+            name,
+            plan_cache,
+            query_store_state: state,
+            daily,
+            warnings,
+        })
+    })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let plan_cache = history
+            .plan_cache
+            .as_ref()
+            .and_then(|rs| json_out::result_set_rows_to_objects(rs).into_iter().next());
+        let payload = json!({
+            "procedure": { "schema": history.schema, "name": history.name },
+            "planCache": plan_cache,
+            "queryStore": {
+                "state": history.query_store_state,
+                "days": days,
+                "daily": json_out::result_set_rows_to_objects(&history.daily),
+            },
+            "warnings": history.warnings,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    println!("{}.{}", history.schema, history.name);
+    println!("\nPlan cache:");
+    match &history.plan_cache {
+        Some(rs) => {
+            let result = table::render_result_set_table(rs, format, &TableOptions::default());
+            println!("{}", result.output);
+        }
+        None => println!("(not in the plan cache)"),
+    }
+    if matches!(
+        history.query_store_state.as_deref(),
+        Some("READ_WRITE") | Some("READ_ONLY")
+    ) {
+        println!("\nQuery Store, last {} days:", days);
+        if history.daily.rows.is_empty() {
+            println!("(no executions recorded)");
+        } else {
+            let result =
+                table::render_result_set_table(&history.daily, format, &TableOptions::default());
+            println!("{}", result.output);
+        }
+    }
+    for warning in &history.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.as_csv(),
+    }
+}
@@ -0,0 +1,551 @@
+//! `stored-procs --test`: smoke-test procedures from a YAML file.
+//!
+//! Each test names a procedure, its parameters, and what a healthy call looks like:
+//!
+//! ```yaml
+//! tests:
+//!   - name: orders for a known customer
+//!     proc: dbo.GetOrders
+//!     params: { CustomerId: 42 }
+//!     expect:
+//!       returnValue: 0
+//!       columns: [OrderId, OrderDate, Total]
+//!       minRows: 1
+//!   - proc: dbo.PlaceOrder
+//!     params: { CustomerId: -1, Sku: ABC-1 }
+//!     expect:
+//!       error: Unknown customer
+//! ```
+//!
+//! Every call runs inside `BEGIN TRANSACTION` and is rolled back, so data changes are undone;
+//! effects outside the transaction (identity and sequence values, mail, linked servers) are
+//! not. Parameters bind against the catalog exactly as `--exec --param` does.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::CliArgs;
+use crate::commands::{common, confirm, sql_utils, stored_procs};
+use crate::config::OutputFormat;
+use crate::db::session::SqlClient;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::db::{client, executor};
+use crate::error::{AppError, ErrorKind, ExitCode};
+use crate::output::{TableOptions, json as json_out, table};
+use crate::safety;
+
+const ROLLBACK_SQL: &str = "IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION;";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TestFile {
+    tests: Vec<ProcTest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ProcTest {
+    /// Defaults to the procedure name.
+    name: Option<String>,
+    proc: String,
+    #[serde(default)]
+    params: serde_yaml::Mapping,
+    #[serde(default)]
+    expect: Expect,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Expect {
+    return_value: Option<i64>,
+    /// The call must fail with an error containing this text (case-insensitive).
+    error: Option<String>,
+    /// Shorthand for the first result set.
+    columns: Option<Vec<String>>,
+    rows: Option<usize>,
+    min_rows: Option<usize>,
+    max_rows: Option<usize>,
+    /// Every result set, in order; the call must return exactly these many.
+    result_sets: Option<Vec<Shape>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Shape {
+    columns: Option<Vec<String>>,
+    rows: Option<usize>,
+    min_rows: Option<usize>,
+    max_rows: Option<usize>,
+}
+
+/// What one call produced, reduced to what expectations can check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Outcome {
+    error: Option<String>,
+    return_value: Option<i64>,
+    /// Column names and row count per result set.
+    sets: Vec<(Vec<String>, usize)>,
+}
+
+#[derive(Debug, Clone)]
+struct TestResult {
+    name: String,
+    procedure: String,
+    elapsed_ms: u128,
+    outcome: Outcome,
+    failures: Vec<String>,
+}
+
+pub fn run(args: &CliArgs, path: &Path) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let tests = parse_tests(path, &text)?;
+    // The rollback does not undo effects outside the transaction, so these count as writes.
+    if tests
+        .iter()
+        .any(|test| safety::validate_read_only(&format!("EXEC {}", test.proc)).is_err())
+    {
+        let action = format!(
+            "run {} from {}",
+            confirm::plural(tests.len(), "procedure test", "procedure tests"),
+            path.display()
+        );
+        confirm::confirm_write(args, &resolved, &action)?;
+    }
+
+    let results = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut results = Vec::new();
+        for test in &tests {
+            let started = Instant::now();
+            let outcome = run_test(&mut client, test).await?;
+            let failures = evaluate(&test.expect, &outcome);
+            results.push(TestResult {
+                name: test.name.clone().unwrap_or_else(|| test.proc.clone()),
+                procedure: test.proc.clone(),
+                elapsed_ms: started.elapsed().as_millis(),
+                outcome,
+                failures,
+            });
+        }
+        Ok::<_, anyhow::Error>(results)
+    })?;
+
+    let failed = results.iter().filter(|r| !r.failures.is_empty()).count();
+    let passed = results.len() - failed;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "file": path.display().to_string(),
+            "passed": passed,
+            "failed": failed,
+            "tests": results
+                .iter()
+                .map(|r| json!({
+                    "name": r.name,
+                    "procedure": r.procedure,
+                    "status": if r.failures.is_empty() { "pass" } else { "fail" },
+                    "elapsedMs": r.elapsed_ms,
+                    "returnValue": r.outcome.return_value,
+                    "resultSets": r
+                        .outcome
+                        .sets
+                        .iter()
+                        .map(|(columns, rows)| json!({ "columns": columns, "rows": rows }))
+                        .collect::<Vec<_>>(),
+                    "error": r.outcome.error,
+                    "failures": r.failures,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        let result = table::render_result_set_table(
+            &results_to_result_set(&results),
+            format,
+            &TableOptions::default(),
+        );
+        println!("{}", result.output);
+        println!("\n{} passed, {} failed", passed, failed);
+    }
+
+    if failed > 0 {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+fn parse_tests(path: &Path, text: &str) -> Result<Vec<ProcTest>> {
+    let invalid = |message: String| -> anyhow::Error {
+        AppError::new(
+            ErrorKind::Config,
+            format!("Invalid test file {}: {}", path.display(), message),
+        )
+        .into()
+    };
+    let parsed: TestFile = serde_yaml::from_str(text).map_err(|err| invalid(err.to_string()))?;
+    if parsed.tests.is_empty() {
+        return Err(invalid("`tests` is empty".to_string()));
+    }
+    for test in &parsed.tests {
+        let expect = &test.expect;
+        let shorthand = expect.columns.is_some()
+            || expect.rows.is_some()
+            || expect.min_rows.is_some()
+            || expect.max_rows.is_some();
+        if shorthand && expect.result_sets.is_some() {
+            return Err(invalid(format!(
+                "{}: use `resultSets` or the first-set shorthand (`columns`, `rows`, `minRows`, `maxRows`), not both",
+                test.proc
+            )));
+        }
+        for (key, value) in &test.params {
+            if key.as_str().is_none() {
+                return Err(invalid(format!(
+                    "{}: parameter names must be strings",
+                    test.proc
+                )));
+            }
+            if param_text(value).is_none() {
+                return Err(invalid(format!(
+                    "{}: parameter {} must be a string, number, or boolean",
+                    test.proc,
+                    key.as_str().unwrap_or_default()
+                )));
+            }
+        }
+    }
+    Ok(parsed.tests)
+}
+
+fn param_text(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(text) => Some(text.clone()),
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        serde_yaml::Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Run one test in a transaction that is always rolled back. Setup problems (unknown
+/// procedure or parameter) and server errors are part of the outcome; only a lost connection
+/// aborts the run.
+async fn run_test(client: &mut SqlClient, test: &ProcTest) -> Result<Outcome> {
+    let failed = |message: String| Outcome {
+        error: Some(message),
+        ..Outcome::default()
+    };
+    let Some((qualified, params)) = stored_procs::lookup_proc(client, &test.proc).await? else {
+        return Ok(failed(format!(
+            "Procedure '{}' not found in the current database",
+            test.proc
+        )));
+    };
+    let supplied: Vec<sql_utils::SqlParam> = test
+        .params
+        .iter()
+        .map(|(key, value)| sql_utils::SqlParam {
+            name: key.as_str().unwrap_or_default().to_string(),
+            value: sql_utils::ParamValue::Text(param_text(value).unwrap_or_default()),
+            table_type: None,
+        })
+        .collect();
+    let batch = match stored_procs::build_exec_batch(&qualified, &params, &supplied) {
+        Ok(batch) => batch,
+        Err(err) => return Ok(failed(err.to_string())),
+    };
+
+    let mut query = Query::new(format!(
+        "BEGIN TRANSACTION;\n{}\n{}",
+        batch.sql, ROLLBACK_SQL
+    ));
+    stored_procs::bind_values(&mut query, batch.values);
+    match executor::run_query(query, client).await {
+        Ok(mut sets) => {
+            // The trailing SELECT of the exec batch carries the return value.
+            let return_value = sets
+                .pop()
+                .and_then(|rs| rs.rows.into_iter().next())
+                .and_then(|row| match row.into_iter().next() {
+                    Some(Value::Int(v)) => Some(v),
+                    _ => None,
+                });
+            Ok(Outcome {
+                error: None,
+                return_value,
+                sets: sets
+                    .iter()
+                    .map(|rs| {
+                        (
+                            rs.columns.iter().map(|c| c.name.clone()).collect(),
+                            rs.rows.len(),
+                        )
+                    })
+                    .collect(),
+            })
+        }
+        Err(err) => {
+            // A failed batch can leave the transaction open.
+            executor::run_query(Query::new(ROLLBACK_SQL), client).await?;
+            Ok(failed(err.to_string()))
+        }
+    }
+}
+
+/// Failure messages for every expectation the outcome misses; empty means the test passed.
+fn evaluate(expect: &Expect, outcome: &Outcome) -> Vec<String> {
+    let mut failures = Vec::new();
+    match (&expect.error, &outcome.error) {
+        (Some(wanted), Some(error)) => {
+            if !error.to_lowercase().contains(&wanted.to_lowercase()) {
+                failures.push(format!("error '{}' does not contain '{}'", error, wanted));
+            }
+            return failures;
+        }
+        (Some(wanted), None) => {
+            failures.push(format!(
+                "expected an error containing '{}', but the call succeeded",
+                wanted
+            ));
+            return failures;
+        }
+        (None, Some(error)) => {
+            failures.push(error.clone());
+            return failures;
+        }
+        (None, None) => {}
+    }
+
+    if let Some(wanted) = expect.return_value
+        && outcome.return_value != Some(wanted)
+    {
+        failures.push(format!(
+            "return value {} (expected {})",
+            outcome
+                .return_value
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "NULL".to_string()),
+            wanted
+        ));
+    }
+
+    let shapes = match &expect.result_sets {
+        Some(shapes) => {
+            if outcome.sets.len() != shapes.len() {
+                failures.push(format!(
+                    "{} result set{} (expected {})",
+                    outcome.sets.len(),
+                    if outcome.sets.len() == 1 { "" } else { "s" },
+                    shapes.len()
+                ));
+            }
+            shapes.clone()
+        }
+        None => vec![Shape {
+            columns: expect.columns.clone(),
+            rows: expect.rows,
+            min_rows: expect.min_rows,
+            max_rows: expect.max_rows,
+        }],
+    };
+    for (idx, shape) in shapes.iter().enumerate() {
+        let checks_set = shape.columns.is_some()
+            || shape.rows.is_some()
+            || shape.min_rows.is_some()
+            || shape.max_rows.is_some();
+        if !checks_set {
+            continue;
+        }
+        let label = format!("result set {}", idx + 1);
+        let Some((columns, rows)) = outcome.sets.get(idx) else {
+            if expect.result_sets.is_none() {
+                failures.push(format!("{} missing", label));
+            }
+            continue;
+        };
+        if let Some(wanted) = &shape.columns {
+            let same = wanted.len() == columns.len()
+                && wanted
+                    .iter()
+                    .zip(columns)
+                    .all(|(a, b)| a.eq_ignore_ascii_case(b));
+            if !same {
+                failures.push(format!(
+                    "{} columns [{}] (expected [{}])",
+                    label,
+                    columns.join(", "),
+                    wanted.join(", ")
+                ));
+            }
+        }
+        if let Some(wanted) = shape.rows
+            && *rows != wanted
+        {
+            failures.push(format!(
+                "{} returned {} rows (expected {})",
+                label, rows, wanted
+            ));
+        }
+        if let Some(min) = shape.min_rows
+            && *rows < min
+        {
+            failures.push(format!(
+                "{} returned {} rows (expected at least {})",
+                label, rows, min
+            ));
+        }
+        if let Some(max) = shape.max_rows
+            && *rows > max
+        {
+            failures.push(format!(
+                "{} returned {} rows (expected at most {})",
+                label, rows, max
+            ));
+        }
+    }
+    failures
+}
+
+fn results_to_result_set(results: &[TestResult]) -> ResultSet {
+    let columns = ["test", "procedure", "status", "elapsedMs", "detail"]
+        .iter()
+        .map(|name| Column {
+            name: name.to_string(),
+            data_type: None,
+        })
+        .collect();
+    let rows = results
+        .iter()
+        .map(|r| {
+            vec![
+                Value::Text(r.name.clone()),
+                Value::Text(r.procedure.clone()),
+                Value::Text(
+                    if r.failures.is_empty() {
+                        "pass"
+                    } else {
+                        "FAIL"
+                    }
+                    .to_string(),
+                ),
+                Value::Int(r.elapsed_ms as i64),
+                Value::Text(r.failures.join("; ")),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(sets: &[(&[&str], usize)]) -> Outcome {
+        Outcome {
+            error: None,
+            return_value: Some(0),
+            sets: sets
+                .iter()
+                .map(|(columns, rows)| (columns.iter().map(|c| c.to_string()).collect(), *rows))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parses_tests_and_rejects_mixed_shapes() {
+        let text = r#"
+tests:
+  - proc: dbo.GetOrders
+    params: { CustomerId: 42, Active: true, Code: ABC }
+    expect: { columns: [OrderId, Total], minRows: 1 }
+  - name: bad customer
+    proc: dbo.PlaceOrder
+    expect:
+      error: Unknown customer
+"#;
+        let tests = parse_tests(Path::new("t.yaml"), text).unwrap();
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[1].name.as_deref(), Some("bad customer"));
+        assert_eq!(
+            tests[0]
+                .params
+                .values()
+                .map(|v| param_text(v).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["42", "true", "ABC"]
+        );
+
+        let mixed = "tests:\n  - proc: p\n    expect: { rows: 1, resultSets: [{ rows: 1 }] }\n";
+        assert!(parse_tests(Path::new("t.yaml"), mixed).is_err());
+        let null_param = "tests:\n  - proc: p\n    params: { Id: ~ }\n";
+        assert!(parse_tests(Path::new("t.yaml"), null_param).is_err());
+        assert!(parse_tests(Path::new("t.yaml"), "tests: []").is_err());
+    }
+
+    #[test]
+    fn evaluates_return_value_columns_and_row_bounds() {
+        let expect = Expect {
+            return_value: Some(0),
+            columns: Some(vec!["orderid".to_string(), "Total".to_string()]),
+            min_rows: Some(1),
+            max_rows: Some(10),
+            ..Expect::default()
+        };
+        assert!(evaluate(&expect, &outcome(&[(&["OrderId", "Total"], 3)])).is_empty());
+        assert_eq!(
+            evaluate(&expect, &outcome(&[(&["OrderId"], 0)])),
+            vec![
+                "result set 1 columns [OrderId] (expected [orderid, Total])",
+                "result set 1 returned 0 rows (expected at least 1)",
+            ]
+        );
+        assert_eq!(
+            evaluate(&expect, &outcome(&[])),
+            vec!["result set 1 missing"]
+        );
+
+        let sets = Expect {
+            result_sets: Some(vec![Shape {
+                rows: Some(1),
+                ..Shape::default()
+            }]),
+            ..Expect::default()
+        };
+        assert_eq!(
+            evaluate(&sets, &outcome(&[(&["a"], 1), (&["b"], 2)])),
+            vec!["2 result sets (expected 1)"]
+        );
+    }
+
+    #[test]
+    fn evaluates_expected_and_unexpected_errors() {
+        let failed = Outcome {
+            error: Some("Unknown customer -1".to_string()),
+            ..Outcome::default()
+        };
+        let expect_error = Expect {
+            error: Some("unknown CUSTOMER".to_string()),
+            ..Expect::default()
+        };
+        assert!(evaluate(&expect_error, &failed).is_empty());
+        assert_eq!(
+            evaluate(&Expect::default(), &failed),
+            vec!["Unknown customer -1"]
+        );
+        assert_eq!(
+            evaluate(&expect_error, &outcome(&[])),
+            vec!["expected an error containing 'unknown CUSTOMER', but the call succeeded"]
+        );
+    }
+}
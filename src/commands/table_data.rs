@@ -1,18 +1,31 @@
 use anyhow::{Result, anyhow};
-use serde_json::json;
-use std::io::IsTerminal;
+use chrono::NaiveDateTime;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
 use tiberius::Query;
 
-use crate::cli::{CliArgs, TableDataArgs};
+use crate::cli::{CliArgs, Paginate, Sample, TableDataArgs};
 use crate::commands::{common, object_lookup, paging, sql_utils};
-use crate::config::OutputFormat;
+use crate::config::{OutputFormat, ResolvedConfig};
 use crate::db::client;
 use crate::db::executor;
+use crate::db::projection::{self, SpecialColumn};
+use crate::db::queries::bracket;
+use crate::db::session::Session;
+use crate::db::types::{ResultSet, Value};
 use crate::error::{AppError, ErrorKind};
-use crate::output::{TableOptions, csv, json as json_out, table};
+use crate::output::contract::{TableDataOutput, TableRef};
+use crate::output::remote::ExportTarget;
+use crate::output::{TableOptions, csv, json as json_out, mask, sink, table};
 
 const LIMIT_DEFAULT: u64 = 25;
 const LIMIT_MAX: u64 = 500;
+/// Rows fetched per poll in `--follow` mode; a full batch polls again without sleeping.
+const FOLLOW_BATCH_MAX: u64 = 500;
+/// Above this many rows `--sample random:N` reads sampled pages instead of shuffling the table.
+const RANDOM_SCAN_ROWS_MAX: i64 = 1_000_000;
+/// Most rows a stratified sample returns, however many values the column has.
+const STRATIFIED_ROWS_MAX: u64 = 10_000;
 
 pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
     let table_raw = cmd.table.as_deref().ok_or_else(|| {
@@ -30,6 +43,21 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
 
     let limit = common::parse_limit(cmd.limit, LIMIT_DEFAULT, LIMIT_MAX);
     let offset = common::parse_offset(cmd.offset);
+    let keyset = cmd.paginate == Paginate::Keyset;
+    if !keyset && (cmd.key.is_some() || cmd.after.is_some()) {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "--key and --after need --paginate keyset",
+        )
+        .into());
+    }
+    if keyset && (cmd.offset.is_some() || cmd.order_by.is_some()) {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "--paginate keyset orders by its key; drop --offset and --order-by",
+        )
+        .into());
+    }
 
     let columns_raw = cmd.columns.clone();
     let where_clause = cmd.where_clause.clone();
@@ -38,12 +66,35 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "(SELECT 0)".to_string());
 
-    let params = sql_utils::parse_params(&cmd.params)
-        .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
+    let params = sql_utils::load_params(&cmd.params, cmd.params_file.as_deref())
+        .map_err(|err| AppError::new(ErrorKind::Query, format!("{:#}", err)))?;
+    let export = ExportTarget::from_args(cmd.to.as_deref(), cmd.storage_tier.as_deref())?;
+    let sinks = sink::parse_sinks(&cmd.out)?;
+    let wkt_max = resolved.settings.output.wkt_max_length;
+
+    if let Some(column) = cmd.follow.as_deref() {
+        let target = FollowTarget {
+            table: &table_name,
+            schema_hint,
+            column,
+            allow_prompt,
+            limit,
+            params: &params,
+        };
+        return run_follow(args, cmd, &resolved, format, target);
+    }
 
     let requested_table_name = table_name.clone();
-    let (result_set, total, output_columns, schema, table_name, csv_paths) =
-        tokio::runtime::Runtime::new()?.block_on(async {
+    let (
+        result_set,
+        total,
+        output_columns,
+        schema,
+        table_name,
+        csv_paths,
+        saved_blobs,
+        keyset_page,
+    ) = tokio::runtime::Runtime::new()?.block_on(async {
             let mut client = client::connect(&resolved.connection).await?;
             let (schema, table_name) = object_lookup::resolve_schema_for_object(
                 &mut client,
@@ -56,18 +107,59 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
             )
             .await?;
 
-            let column_tokens = parse_columns(columns_raw.as_deref());
+            let mut column_tokens = parse_columns(columns_raw.as_deref());
+            let (names, specials) = fetch_columns(&mut client, &schema, &table_name).await?;
+            let keys = if keyset {
+                Some(keyset_columns(&mut client, &schema, &table_name, cmd.key.as_deref()).await?)
+            } else {
+                None
+            };
+            // nextKey carries raw key values, which would undo a mask rule on a key column.
+            if let Some(key) = keys.iter().flatten().find(|key| {
+                mask::covers(&resolved.policy.mask, &key.name, Some((&schema, &table_name)))
+            }) {
+                return Err(AppError::new(
+                    ErrorKind::Safety,
+                    format!(
+                        "Key column '{}' is masked; --paginate keyset would expose it in nextKey",
+                        key.name
+                    ),
+                )
+                .into());
+            }
+            let after = match (&keys, cmd.after.as_deref()) {
+                (Some(keys), Some(token)) => Some(parse_key_token(token, keys)?),
+                _ => None,
+            };
+            if let Some(keys) = &keys
+                && !(column_tokens.len() == 1 && column_tokens[0] == "*")
+            {
+                // The next key is read from the page, so key columns are always selected.
+                for key in keys {
+                    let listed = column_tokens.iter().any(|token| {
+                        token
+                            .trim_matches(|c| c == '[' || c == ']')
+                            .eq_ignore_ascii_case(&key.name)
+                    });
+                    if !listed {
+                        column_tokens.push(bracket(&key.name));
+                    }
+                }
+            }
             let (select_list, output_columns) =
                 if column_tokens.len() == 1 && column_tokens[0] == "*" {
-                    let names = fetch_column_names(&mut client, &schema, &table_name).await?;
                     let list = names
                         .iter()
-                        .map(|name| quote_identifier(name))
+                        .map(|name| select_item(&bracket(name), &specials, wkt_max))
                         .collect::<Vec<_>>()
                         .join(", ");
-                    (list, names)
+                    (list, names.clone())
                 } else {
-                    let list = column_tokens.join(", ");
+                    let list = column_tokens
+                        .iter()
+                        .map(|token| select_item(token, &specials, wkt_max))
+                        .collect::<Vec<_>>()
+                        .join(", ");
                     (list, column_tokens)
                 };
 
@@ -79,51 +171,130 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
                 .map(|clause| format!("WHERE {}", clause))
                 .unwrap_or_default();
 
-            let param_count = params.len();
+            let param_count = sql_utils::placeholder_count(&params);
             let offset_placeholder = format!("@P{}", param_count + 1);
             let limit_placeholder = format!("@P{}", param_count + 2);
 
             let qualified_table = format!(
                 "{}.{}",
-                quote_identifier(&schema),
-                quote_identifier(&table_name)
-            );
-            let sql = format!(
-                "SELECT {select_list} FROM {qualified_table} {where_sql} ORDER BY {order_by} OFFSET {offset_placeholder} ROWS FETCH NEXT {limit_placeholder} ROWS ONLY;",
+                bracket(&schema),
+                bracket(&table_name)
             );
+            let qualified_table = if cmd.as_of.is_some() || cmd.history {
+                temporal_source(&mut client, &schema, &table_name, cmd).await?
+            } else {
+                qualified_table
+            };
+            let sql = match &cmd.sample {
+                Some(sample) => {
+                    let source = SampleSource {
+                        select_list: &select_list,
+                        qualified_table: &qualified_table,
+                        where_sql: &where_sql,
+                        // TABLESAMPLE reads pages of the base table before any filter.
+                        can_sample_pages: replaced_where.is_none()
+                            && cmd.as_of.is_none()
+                            && !cmd.history,
+                    };
+                    sample_sql(&mut client, sample, &source, &schema, &table_name, &names)
+                        .await?
+                }
+                None => match &keys {
+                    Some(keys) => {
+                        let filter = replaced_where
+                            .iter()
+                            .map(|clause| format!("({})", clause))
+                            .chain(after.is_some().then(|| keyset_predicate(keys, param_count + 1)))
+                            .collect::<Vec<_>>();
+                        let filter = if filter.is_empty() {
+                            String::new()
+                        } else {
+                            format!("WHERE {}", filter.join(" AND "))
+                        };
+                        let order = keys
+                            .iter()
+                            .map(|key| bracket(&key.name))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        // One row past the page tells whether there is a next one.
+                        format!(
+                            "SELECT TOP ({}) {select_list} FROM {qualified_table} {filter} ORDER BY {order};",
+                            limit + 1
+                        )
+                    }
+                    None => format!(
+                        "SELECT {select_list} FROM {qualified_table} {where_sql} ORDER BY {order_by} OFFSET {offset_placeholder} ROWS FETCH NEXT {limit_placeholder} ROWS ONLY;",
+                    ),
+                },
+            };
 
-            let mut query = Query::new(sql);
-            for param in &params {
-                query.bind(param.value.as_str());
+            let mut query = Query::new(sql_utils::with_table_params(&sql, &params, 1));
+            sql_utils::bind_params(&mut query, &params);
+            if let Some(after) = &after {
+                after.iter().for_each(|cursor| cursor.bind(&mut query));
+            } else if cmd.sample.is_none() && keys.is_none() {
+                query.bind(offset as i64);
+                query.bind(limit as i64);
             }
-            query.bind(offset as i64);
-            query.bind(limit as i64);
             let result_sets = executor::run_query(query, &mut client).await?;
-            let result_set = result_sets.into_iter().next().unwrap_or_default();
+            let mut result_set = result_sets.into_iter().next().unwrap_or_default();
+            projection::unwrap_columns(&mut result_set, &specials, wkt_max);
+            // Masked key columns were refused above, so the token reveals nothing masked.
+            let keyset_page = match &keys {
+                Some(keys) => {
+                    let has_more = result_set.rows.len() as u64 > limit;
+                    result_set.rows.truncate(limit as usize);
+                    let next_key = if has_more {
+                        Some(key_token(&result_set, keys)?)
+                    } else {
+                        None
+                    };
+                    Some(KeysetPage { has_more, next_key })
+                }
+                None => None,
+            };
+            mask::apply(
+                &mut result_set,
+                &resolved.policy.mask,
+                Some((&schema, &table_name)),
+            );
+            let saved_blobs = common::save_blobs(
+                cmd.save_blobs.as_deref(),
+                std::slice::from_mut(&mut result_set),
+                &resolved,
+            )?;
 
-            let count_sql = format!("SELECT COUNT(*) AS total FROM {qualified_table} {where_sql};");
-            let mut count_query = Query::new(count_sql);
-            for param in &params {
-                count_query.bind(param.value.as_str());
-            }
-            let count_sets = executor::run_query(count_query, &mut client).await?;
-            let total = count_sets
-                .first()
-                .and_then(|rs| rs.rows.first())
-                .and_then(|row| row.first())
-                .and_then(|value| match value {
-                    crate::db::types::Value::Int(v) => (*v).try_into().ok(),
-                    crate::db::types::Value::Float(v) => Some(*v as u64),
-                    crate::db::types::Value::Text(s) => s.parse::<u64>().ok(),
-                    _ => None,
-                })
-                .unwrap_or(result_set.rows.len() as u64);
+            // Counting every matching row on every page would undo what keyset paging saves.
+            let total = if keyset_page.is_some() {
+                None
+            } else {
+                let count_sql =
+                    format!("SELECT COUNT(*) AS total FROM {qualified_table} {where_sql};");
+                let mut count_query =
+                    Query::new(sql_utils::with_table_params(&count_sql, &params, 1));
+                sql_utils::bind_params(&mut count_query, &params);
+                let count_sets = executor::run_query(count_query, &mut client).await?;
+                Some(
+                    count_sets
+                        .first()
+                        .and_then(|rs| rs.rows.first())
+                        .and_then(|row| row.first())
+                        .and_then(|value| match value {
+                            crate::db::types::Value::Int(v) => (*v).try_into().ok(),
+                            crate::db::types::Value::Float(v) => Some(*v as u64),
+                            crate::db::types::Value::Text(s) => s.parse::<u64>().ok(),
+                            _ => None,
+                        })
+                        .unwrap_or(result_set.rows.len() as u64),
+                )
+            };
 
             let csv_paths = if let Some(path) = cmd.csv.as_ref() {
                 Some(csv::write_result_sets(
                     path,
                     std::slice::from_ref(&result_set),
                     resolved.settings.output.csv.multi_result_naming,
+                    resolved.settings.output.max_value_bytes,
                 )?)
             } else {
                 None
@@ -136,25 +307,76 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
                 schema,
                 table_name,
                 csv_paths,
+                saved_blobs,
+                keyset_page,
             ))
         })?;
 
+    let out_paths = if sinks.is_empty() {
+        None
+    } else {
+        Some(sink::write_sinks(
+            &sinks,
+            std::slice::from_ref(&result_set),
+            resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
+        )?)
+    };
+    let uploaded = match &export {
+        Some(export) => Some(export.upload_csv(
+            std::slice::from_ref(&result_set),
+            resolved.settings.output.csv.multi_result_naming,
+            resolved.settings.output.max_value_bytes,
+        )?),
+        None => None,
+    };
+
     let count = result_set.rows.len() as u64;
-    let paging = paging::build_paging(total, count, offset, limit);
+    let mut paging = paging::build_paging(total.unwrap_or(count), count, offset, limit);
+    if cmd.sample.is_some() {
+        // A sample is not a page; there is no next one to fetch.
+        paging = paging::build_paging(count, count, 0, count);
+        paging.total = total.unwrap_or(count);
+    }
+    if let Some(page) = &keyset_page {
+        paging = paging::build_paging(count, count, 0, limit);
+        paging.has_more = page.has_more;
+        paging.next_offset = None;
+    }
+    let next_key = keyset_page.and_then(|page| page.next_key);
 
     if matches!(format, OutputFormat::Json) {
-        let payload = json!({
-            "table": { "schema": schema, "name": table_name },
-            "columns": output_columns,
-            "rows": result_set.rows,
-            "total": paging.total,
-            "offset": paging.offset,
-            "limit": paging.limit,
-            "hasMore": paging.has_more,
-            "nextOffset": paging.next_offset,
-            "csvPaths": csv_paths.as_ref().map(|paths| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>()),
-        });
-        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        let payload = TableDataOutput {
+            table: TableRef {
+                schema,
+                name: table_name,
+            },
+            columns: output_columns,
+            rows: result_set
+                .into_capped(resolved.settings.output.max_value_bytes)
+                .rows,
+            total,
+            offset: paging.offset,
+            limit: paging.limit,
+            has_more: paging.has_more,
+            next_offset: paging.next_offset,
+            next_key: next_key.clone(),
+            csv_paths: csv_paths.as_ref().map(|paths| {
+                paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+            }),
+            out_paths: out_paths.as_ref().map(|paths| {
+                paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+            }),
+            uploaded,
+            sample: cmd.sample.as_ref().map(Sample::to_string),
+        };
+        let body = json_out::emit_json(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
             println!("{}", body);
         }
@@ -170,7 +392,8 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
     } else {
         TableOptions::truncated()
     };
-    if paging.total > 0 {
+    options.max_value_bytes = resolved.settings.output.max_value_bytes;
+    if paging.total > 0 && cmd.sample.is_none() && !keyset {
         let page_limit = if count == 0 { limit } else { count };
         options.pagination = Some(table::Pagination {
             total: Some(paging.total),
@@ -181,6 +404,15 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
 
     let result = table::render_result_set_table(&result_set, format, &options);
     println!("{}", result.output);
+    if let Some(sample) = &cmd.sample {
+        println!("\n{} of {} rows ({})", count, paging.total, sample);
+    }
+    if keyset {
+        match &next_key {
+            Some(token) => println!("\n{} rows; next page: --after '{}'", count, token),
+            None => println!("\n{} rows; last page", count),
+        }
+    }
 
     if let Some(paths) = csv_paths {
         println!("\nCSV written:");
@@ -188,10 +420,688 @@ pub fn run(args: &CliArgs, cmd: &TableDataArgs) -> Result<()> {
             println!("- {}", path.display());
         }
     }
+    common::print_written(out_paths.as_deref());
+    common::print_saved_blobs(saved_blobs, cmd.save_blobs.as_deref());
+    common::print_uploaded(uploaded.as_deref());
+
+    Ok(())
+}
+
+/// What a `--sample` query reads from.
+struct SampleSource<'a> {
+    select_list: &'a str,
+    qualified_table: &'a str,
+    where_sql: &'a str,
+    /// Whether `TABLESAMPLE` may be used: a plain table with no filter.
+    can_sample_pages: bool,
+}
+
+/// SQL for `--sample`. Random samples of large tables read about four times the wanted rows'
+/// worth of pages with `TABLESAMPLE` rather than sorting every row by `NEWID()`.
+async fn sample_sql(
+    client: &mut crate::db::session::SqlClient,
+    sample: &Sample,
+    source: &SampleSource<'_>,
+    schema: &str,
+    table_name: &str,
+    names: &[String],
+) -> Result<String> {
+    let SampleSource {
+        select_list,
+        qualified_table,
+        where_sql,
+        ..
+    } = source;
+    Ok(match sample {
+        Sample::Random(n) => {
+            let rows = if source.can_sample_pages {
+                estimated_rows(client, schema, table_name).await?
+            } else {
+                None
+            };
+            let tablesample = match rows {
+                Some(rows) if rows > RANDOM_SCAN_ROWS_MAX => {
+                    format!(" TABLESAMPLE ({:.4} PERCENT)", sample_percent(*n, rows))
+                }
+                _ => String::new(),
+            };
+            format!(
+                "SELECT TOP ({n}) {select_list} FROM {qualified_table}{tablesample} {where_sql} ORDER BY NEWID();"
+            )
+        }
+        Sample::First(n) | Sample::Last(n) => {
+            let keys = key_columns(client, schema, table_name).await?;
+            if keys.is_empty() {
+                return Err(AppError::new(
+                    ErrorKind::Query,
+                    format!(
+                        "{}.{} has no primary key or clustered index for --sample {}; use --order-by with --limit instead",
+                        schema, table_name, sample
+                    ),
+                )
+                .into());
+            }
+            let direction = if matches!(sample, Sample::Last(_)) {
+                " DESC"
+            } else {
+                ""
+            };
+            let order = keys
+                .iter()
+                .map(|key| format!("{}{}", bracket(key), direction))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "SELECT TOP ({n}) {select_list} FROM {qualified_table} {where_sql} ORDER BY {order};"
+            )
+        }
+        Sample::Stratified { column, per_value } => {
+            let wanted = column.trim_matches(|c| c == '[' || c == ']');
+            let name = names
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(wanted))
+                .ok_or_else(|| {
+                    AppError::new(
+                        ErrorKind::Query,
+                        format!("Column '{}' not found on {}.{}", wanted, schema, table_name),
+                    )
+                })?;
+            let column = bracket(name);
+            format!(
+                "SELECT TOP ({STRATIFIED_ROWS_MAX}) {select_list} FROM (\
+                 SELECT *, ROW_NUMBER() OVER (PARTITION BY {column} ORDER BY NEWID()) AS [__sscli_stratum_row] \
+                 FROM {qualified_table} {where_sql}) AS strata \
+                 WHERE [__sscli_stratum_row] <= {per_value} ORDER BY {column};"
+            )
+        }
+    })
+}
+
+/// Percent of pages to read for `n` rows out of `rows`: four times the share, so page-level
+/// sampling rarely comes back short.
+fn sample_percent(n: u64, rows: i64) -> f64 {
+    (n as f64 * 4.0 / rows as f64 * 100.0).clamp(0.0001, 100.0)
+}
+
+/// Rows in the heap or clustered index, from metadata; `None` for a view.
+async fn estimated_rows(
+    client: &mut crate::db::session::SqlClient,
+    schema: &str,
+    table_name: &str,
+) -> Result<Option<i64>> {
+    let mut query = Query::new(
+        "SELECT SUM(p.rows) FROM sys.partitions p
+         WHERE p.object_id = OBJECT_ID(@P1) AND p.index_id IN (0, 1)
+           AND OBJECTPROPERTY(p.object_id, 'IsUserTable') = 1;",
+    );
+    query.bind(format!("{}.{}", bracket(schema), bracket(table_name)));
+    let result_sets = executor::run_query(query, client).await?;
+    Ok(result_sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .and_then(|row| match row.first() {
+            Some(Value::Int(rows)) => Some(*rows),
+            _ => None,
+        }))
+}
+
+/// Primary key columns in key order, or the clustered index key when there is no primary key.
+async fn key_columns(
+    client: &mut crate::db::session::SqlClient,
+    schema: &str,
+    table_name: &str,
+) -> Result<Vec<String>> {
+    let mut query = Query::new(
+        "SELECT c.name
+         FROM sys.index_columns ic
+         JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+         WHERE ic.object_id = OBJECT_ID(@P1)
+           AND ic.key_ordinal > 0
+           AND ic.index_id = (SELECT TOP (1) i.index_id FROM sys.indexes i
+                              WHERE i.object_id = ic.object_id
+                                AND (i.is_primary_key = 1 OR i.index_id = 1)
+                              ORDER BY i.is_primary_key DESC)
+         ORDER BY ic.key_ordinal;",
+    );
+    query.bind(format!("{}.{}", bracket(schema), bracket(table_name)));
+    let result_sets = executor::run_query(query, client).await?;
+    Ok(result_sets
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+        .rows
+        .iter()
+        .filter_map(|row| row.first().map(Value::as_display))
+        .collect())
+}
+
+/// The FROM source for `--as-of` or `--history`, after checking the table is system-versioned.
+async fn temporal_source(
+    client: &mut crate::db::session::SqlClient,
+    schema: &str,
+    table_name: &str,
+    cmd: &TableDataArgs,
+) -> Result<String> {
+    let mut query = Query::new(
+        "SELECT SCHEMA_NAME(h.schema_id), h.name
+         FROM sys.tables t
+         JOIN sys.schemas s ON s.schema_id = t.schema_id
+         JOIN sys.tables h ON h.object_id = t.history_table_id
+         WHERE s.name = @P1 AND t.name = @P2 AND t.temporal_type = 2;",
+    );
+    query.bind(schema);
+    query.bind(table_name);
+    let result_sets = executor::run_query(query, client).await?;
+    let history = result_sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .map(|row| {
+            let part = |idx: usize| row.get(idx).map(Value::as_display).unwrap_or_default();
+            (part(0), part(1))
+        })
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "{}.{} is not a system-versioned temporal table; --as-of and --history need one",
+                    schema, table_name
+                ),
+            )
+        })?;
+
+    let current = format!("{}.{}", bracket(schema), bracket(table_name));
+    Ok(match cmd.as_of {
+        // Parsed by the CLI, so the literal is always a plain timestamp.
+        Some(as_of) => format!(
+            "{} FOR SYSTEM_TIME AS OF '{}'",
+            current,
+            as_of.format("%Y-%m-%dT%H:%M:%S%.f")
+        ),
+        None => format!("{}.{}", bracket(&history.0), bracket(&history.1)),
+    })
+}
+
+struct FollowTarget<'a> {
+    table: &'a str,
+    schema_hint: Option<&'a str>,
+    column: &'a str,
+    allow_prompt: bool,
+    limit: u64,
+    params: &'a [sql_utils::SqlParam],
+}
+
+/// A `--paginate keyset` column and its type, which `--after` values are read as.
+#[derive(Debug, Clone)]
+struct KeyColumn {
+    name: String,
+    data_type: String,
+}
+
+/// Where a keyset page ended.
+struct KeysetPage {
+    has_more: bool,
+    next_key: Option<String>,
+}
+
+/// `--key` columns, then any primary key columns not among them so that rows sharing a `--key`
+/// value are neither skipped nor repeated between pages.
+async fn keyset_columns(
+    client: &mut crate::db::session::SqlClient,
+    schema: &str,
+    table_name: &str,
+    requested: Option<&str>,
+) -> Result<Vec<KeyColumn>> {
+    let mut query = Query::new(
+        "SELECT c.name, TYPE_NAME(c.system_type_id)
+         FROM sys.columns c
+         WHERE c.object_id = OBJECT_ID(@P1)
+         ORDER BY c.column_id;",
+    );
+    query.bind(format!("{}.{}", bracket(schema), bracket(table_name)));
+    let columns: Vec<KeyColumn> = first_set(executor::run_query(query, client).await?)
+        .rows
+        .iter()
+        .map(|row| KeyColumn {
+            name: row.first().map(Value::as_display).unwrap_or_default(),
+            data_type: row.get(1).map(Value::as_display).unwrap_or_default(),
+        })
+        .collect();
+    let find = |wanted: &str| {
+        let wanted = wanted.trim().trim_matches(|c| c == '[' || c == ']');
+        columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(wanted))
+            .cloned()
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorKind::Query,
+                    format!("Column '{}' not found on {}.{}", wanted, schema, table_name),
+                )
+            })
+    };
+
+    let mut keys: Vec<KeyColumn> = Vec::new();
+    let requested = requested
+        .unwrap_or_default()
+        .split(',')
+        .filter(|name| !name.trim().is_empty());
+    let primary = key_columns(client, schema, table_name).await?;
+    for name in requested.map(str::to_string).chain(primary) {
+        let column = find(&name)?;
+        if !keys.iter().any(|key| key.name == column.name) {
+            keys.push(column);
+        }
+    }
+    if keys.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Query,
+            format!(
+                "{}.{} has no primary key or clustered index for --paginate keyset; name unique columns with --key",
+                schema, table_name
+            ),
+        )
+        .into());
+    }
+    Ok(keys)
+}
+
+/// `(k1 > @a) OR (k1 = @a AND k2 > @b) ...`: rows after the given key values, in key order.
+fn keyset_predicate(keys: &[KeyColumn], first_placeholder: usize) -> String {
+    let exprs: Vec<String> = keys
+        .iter()
+        .enumerate()
+        .map(|(idx, key)| {
+            cursor_expr(
+                &format!("@P{}", first_placeholder + idx),
+                Some(&key.data_type),
+            )
+        })
+        .collect();
+    let branches = (0..keys.len())
+        .map(|last| {
+            let terms = (0..=last)
+                .map(|idx| {
+                    let op = if idx == last { ">" } else { "=" };
+                    format!("{} {} {}", bracket(&keys[idx].name), op, exprs[idx])
+                })
+                .collect::<Vec<_>>();
+            format!("({})", terms.join(" AND "))
+        })
+        .collect::<Vec<_>>();
+    format!("({})", branches.join(" OR "))
+}
+
+/// `nextKey`: the last row's key values as a JSON array, e.g. `[1042]`.
+fn key_token(rs: &ResultSet, keys: &[KeyColumn]) -> Result<String> {
+    let Some(row) = rs.rows.last() else {
+        return Ok("[]".to_string());
+    };
+    let values = keys
+        .iter()
+        .map(|key| {
+            let value = rs
+                .columns
+                .iter()
+                .position(|column| column.name.eq_ignore_ascii_case(&key.name))
+                .and_then(|idx| row.get(idx))
+                .unwrap_or(&Value::Null);
+            match Cursor::from_value(value)? {
+                Some(cursor) => Ok(cursor.to_json()),
+                None => Err(AppError::new(
+                    ErrorKind::Query,
+                    format!(
+                        "Key column '{}' is NULL on the last row; --paginate keyset needs non-null keys",
+                        key.name
+                    ),
+                )
+                .into()),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serde_json::Value::Array(values).to_string())
+}
+
+/// Key values from an `--after` token, typed by their columns.
+fn parse_key_token(token: &str, keys: &[KeyColumn]) -> Result<Vec<Cursor>> {
+    let invalid = |reason: String| {
+        AppError::new(
+            ErrorKind::Config,
+            format!("Invalid --after token '{}': {}", token, reason),
+        )
+    };
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(token).map_err(|err| invalid(err.to_string()))?;
+    if values.len() != keys.len() {
+        let names: Vec<&str> = keys.iter().map(|key| key.name.as_str()).collect();
+        return Err(invalid(format!(
+            "expected {} value(s) for {}",
+            keys.len(),
+            names.join(", ")
+        ))
+        .into());
+    }
+    values
+        .iter()
+        .zip(keys)
+        .map(|(value, key)| match value {
+            serde_json::Value::Number(number) => Ok(match number.as_i64() {
+                Some(int) => Cursor::Int(int),
+                None => Cursor::Text(number.to_string()),
+            }),
+            serde_json::Value::String(text) if is_temporal(&key.data_type) => {
+                NaiveDateTime::parse_from_str(text, CURSOR_DATETIME_FORMAT)
+                    .map(Cursor::DateTime)
+                    .map_err(|_| {
+                        invalid(format!("'{}' is not a date/time for {}", text, key.name)).into()
+                    })
+            }
+            serde_json::Value::String(text) => Ok(Cursor::Text(text.clone())),
+            other => Err(invalid(format!("unexpected value {} for {}", other, key.name)).into()),
+        })
+        .collect()
+}
+
+/// SQL for `--follow`: an initial tail (or high-water mark), then polls for rows past a cursor.
+#[derive(Debug, Clone)]
+struct FollowPlan {
+    select_list: String,
+    qualified_table: String,
+    where_clause: Option<String>,
+    column: String,
+}
+
+impl FollowPlan {
+    fn where_sql(&self, extra: Option<String>) -> String {
+        let clauses = self
+            .where_clause
+            .iter()
+            .map(|clause| format!("({})", clause))
+            .chain(extra)
+            .collect::<Vec<_>>();
+        if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        }
+    }
+
+    fn tail_sql(&self, limit: u64) -> String {
+        format!(
+            "SELECT * FROM (SELECT TOP ({limit}) {} FROM {} {} ORDER BY {col} DESC) AS tail ORDER BY {col};",
+            self.select_list,
+            self.qualified_table,
+            self.where_sql(None),
+            col = self.column,
+        )
+    }
+
+    fn high_water_sql(&self) -> String {
+        format!(
+            "SELECT MAX({}) AS cursorValue FROM {} {};",
+            self.column,
+            self.qualified_table,
+            self.where_sql(None)
+        )
+    }
+
+    /// `cursor` is the SQL expression for the last value seen, if any.
+    fn poll_sql(&self, cursor: Option<&str>) -> String {
+        let after = cursor.map(|expr| format!("{} > {}", self.column, expr));
+        format!(
+            "SELECT TOP ({}) {} FROM {} {} ORDER BY {};",
+            FOLLOW_BATCH_MAX,
+            self.select_list,
+            self.qualified_table,
+            self.where_sql(after),
+            self.column
+        )
+    }
+}
+
+/// Last value of the follow column that has been printed.
+#[derive(Debug, Clone, PartialEq)]
+enum Cursor {
+    Int(i64),
+    DateTime(NaiveDateTime),
+    Text(String),
+}
+
+impl Cursor {
+    fn from_value(value: &Value) -> Result<Option<Self>> {
+        match value {
+            Value::Null => Ok(None),
+            Value::Int(v) => Ok(Some(Cursor::Int(*v))),
+            // Offset-aware values carry UTC, which compares correctly once cast back.
+            Value::DateTime(ts) => Ok(Some(Cursor::DateTime(ts.datetime))),
+            Value::Date(date) => Ok(date.and_hms_opt(0, 0, 0).map(Cursor::DateTime)),
+            Value::Text(v) => Ok(Some(Cursor::Text(v.clone()))),
+            // Compared as text, which SQL Server converts back to the column's type.
+            Value::Decimal(_) | Value::Uuid(_) | Value::Time(_) => {
+                Ok(Some(Cursor::Text(value.as_csv())))
+            }
+            other => Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "--follow and --paginate keyset need integer, date/time, or text columns; got value {}",
+                    other.as_display()
+                ),
+            )
+            .into()),
+        }
+    }
+
+    fn bind(&self, query: &mut Query<'_>) {
+        match self {
+            Cursor::Int(v) => query.bind(*v),
+            Cursor::DateTime(v) => query.bind(*v),
+            Cursor::Text(v) => query.bind(v.clone()),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Cursor::Int(v) => serde_json::Value::from(*v),
+            Cursor::DateTime(v) => {
+                serde_json::Value::from(v.format(CURSOR_DATETIME_FORMAT).to_string())
+            }
+            Cursor::Text(v) => serde_json::Value::from(v.as_str()),
+        }
+    }
+}
+
+/// How date/time cursors appear in `nextKey` tokens.
+const CURSOR_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+fn is_temporal(data_type: &str) -> bool {
+    matches!(
+        data_type,
+        "datetime" | "smalldatetime" | "datetime2" | "datetimeoffset" | "date"
+    )
+}
+
+/// Parameter expression for the cursor, cast back to the column's type so values that were
+/// widened on the way out (`datetime` ticks, `datetimeoffset` in UTC) compare exactly.
+fn cursor_expr(placeholder: &str, data_type: Option<&str>) -> String {
+    match data_type {
+        Some(t) if is_temporal(t) => format!("CAST({} AS {})", placeholder, t),
+        _ => placeholder.to_string(),
+    }
+}
+
+fn run_follow(
+    args: &CliArgs,
+    cmd: &TableDataArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    target: FollowTarget<'_>,
+) -> Result<()> {
+    let mut options = if cmd.no_truncate {
+        TableOptions::unlimited()
+    } else {
+        TableOptions::truncated()
+    };
+    options.max_value_bytes = resolved.settings.output.max_value_bytes;
+    let interval = Duration::from_millis(cmd.interval_ms);
+    let params = target.params;
+    let placeholder = format!("@P{}", sql_utils::placeholder_count(params) + 1);
 
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let mut session = Session::connect(&resolved.connection).await?;
+        let (schema, table_name) = object_lookup::resolve_schema_for_object(
+            session.client_mut(),
+            resolved,
+            target.table,
+            target.schema_hint,
+            object_lookup::LookupScope::TablesAndViews,
+            "table",
+            target.allow_prompt,
+        )
+        .await?;
+        let (names, specials) = fetch_columns(session.client_mut(), &schema, &table_name).await?;
+        let wkt_max = resolved.settings.output.wkt_max_length;
+        let wanted = target.column.trim_matches(|c| c == '[' || c == ']');
+        let follow_name = names
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(wanted))
+            .cloned()
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorKind::Query,
+                    format!("Column '{}' not found on {}.{}", wanted, schema, table_name),
+                )
+            })?;
+        let follow_column = bracket(&follow_name);
+
+        let column_tokens = parse_columns(cmd.columns.as_deref());
+        let select_list = if column_tokens.len() == 1 && column_tokens[0] == "*" {
+            names
+                .iter()
+                .map(|name| select_item(&bracket(name), &specials, wkt_max))
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            let mut tokens = column_tokens;
+            let listed = tokens.iter().any(|token| {
+                token
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .eq_ignore_ascii_case(&follow_name)
+            });
+            // The cursor is read from the result, so the follow column is always selected.
+            if !listed {
+                tokens.push(follow_column.clone());
+            }
+            tokens
+                .iter()
+                .map(|token| select_item(token, &specials, wkt_max))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let plan = FollowPlan {
+            select_list,
+            qualified_table: format!("{}.{}", bracket(&schema), bracket(&table_name)),
+            where_clause: cmd
+                .where_clause
+                .as_deref()
+                .map(|clause| sql_utils::replace_named_params(clause, params, 1)),
+            column: follow_column,
+        };
+        let bind_params = |sql: &str| {
+            let mut query = Query::new(sql_utils::with_table_params(sql, params, 1));
+            sql_utils::bind_params(&mut query, params);
+            query
+        };
+
+        let mut cursor: Option<Cursor> = None;
+        let mut cursor_type: Option<String> = None;
+        if cmd.from_now {
+            let sql = plan.high_water_sql();
+            let rs = first_set(session.query_read(|| bind_params(&sql)).await?);
+            if let Some(value) = rs.rows.first().and_then(|row| row.first()) {
+                cursor = Cursor::from_value(value)?;
+            }
+            cursor_type = rs.columns.first().and_then(|c| c.data_type.clone());
+        } else {
+            let sql = plan.tail_sql(target.limit);
+            let mut rs = first_set(session.query_read(|| bind_params(&sql)).await?);
+            projection::unwrap_columns(&mut rs, &specials, wkt_max);
+            advance_cursor(&rs, &follow_name, &mut cursor, &mut cursor_type)?;
+            mask::apply(&mut rs, &resolved.policy.mask, Some((&schema, &table_name)));
+            emit_follow_rows(&rs, args, format, &options);
+        }
+
+        loop {
+            for warning in session.take_warnings() {
+                eprintln!("Warning: {}", warning);
+            }
+            let expr = cursor
+                .as_ref()
+                .map(|_| cursor_expr(&placeholder, cursor_type.as_deref()));
+            let sql = plan.poll_sql(expr.as_deref());
+            let mut rs = first_set(
+                session
+                    .query_read(|| {
+                        let mut query = bind_params(&sql);
+                        if let Some(cursor) = &cursor {
+                            cursor.bind(&mut query);
+                        }
+                        query
+                    })
+                    .await?,
+            );
+            projection::unwrap_columns(&mut rs, &specials, wkt_max);
+            advance_cursor(&rs, &follow_name, &mut cursor, &mut cursor_type)?;
+            mask::apply(&mut rs, &resolved.policy.mask, Some((&schema, &table_name)));
+            emit_follow_rows(&rs, args, format, &options);
+            if (rs.rows.len() as u64) < FOLLOW_BATCH_MAX {
+                tokio::time::sleep(interval).await;
+            }
+        }
+    })
+}
+
+fn first_set(result_sets: Vec<ResultSet>) -> ResultSet {
+    result_sets.into_iter().next().unwrap_or_default()
+}
+
+fn advance_cursor(
+    rs: &ResultSet,
+    follow_name: &str,
+    cursor: &mut Option<Cursor>,
+    cursor_type: &mut Option<String>,
+) -> Result<()> {
+    let Some(idx) = rs
+        .columns
+        .iter()
+        .position(|c| c.name.eq_ignore_ascii_case(follow_name))
+    else {
+        return Ok(());
+    };
+    if let Some(value) = rs.rows.last().and_then(|row| row.get(idx)) {
+        if let Some(next) = Cursor::from_value(value)? {
+            *cursor = Some(next);
+            *cursor_type = rs.columns[idx].data_type.clone();
+        }
+    }
     Ok(())
 }
 
+/// Print one batch: NDJSON (one object per row) for `--json`, otherwise a table.
+fn emit_follow_rows(rs: &ResultSet, args: &CliArgs, format: OutputFormat, options: &TableOptions) {
+    if rs.rows.is_empty() || args.quiet {
+        return;
+    }
+    let mut stdout = std::io::stdout().lock();
+    if matches!(format, OutputFormat::Json) {
+        for row in json_out::result_set_rows_to_objects(&rs.capped(options.max_value_bytes)) {
+            let _ = writeln!(stdout, "{}", row);
+        }
+    } else {
+        let result = table::render_result_set_table(rs, format, options);
+        let _ = writeln!(stdout, "{}", result.output);
+    }
+    let _ = stdout.flush();
+}
+
 fn parse_columns(raw: Option<&str>) -> Vec<String> {
     if let Some(raw) = raw {
         let list = raw
@@ -209,47 +1119,153 @@ fn parse_columns(raw: Option<&str>) -> Vec<String> {
     }
 }
 
-async fn fetch_column_names(
+/// Column names in table order, plus the columns the driver needs projected.
+async fn fetch_columns(
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
     schema: &str,
     table: &str,
-) -> Result<Vec<String>> {
+) -> Result<(Vec<String>, Vec<SpecialColumn>)> {
     let sql = r#"
-SELECT COLUMN_NAME
-FROM INFORMATION_SCHEMA.COLUMNS
-WHERE TABLE_NAME = @P1
-  AND (@P2 IS NULL OR TABLE_SCHEMA = @P2)
-ORDER BY ORDINAL_POSITION;
+SELECT c.name, CAST(c.system_type_id AS int) AS system_type_id, TYPE_NAME(c.user_type_id) AS type_name
+FROM sys.columns c
+WHERE c.object_id = OBJECT_ID(@P1)
+ORDER BY c.column_id;
 "#;
     let mut query = Query::new(sql);
-    query.bind(table);
-    query.bind(Some(schema));
+    query.bind(format!("{}.{}", bracket(schema), bracket(table)));
     let result_sets = executor::run_query(query, client).await?;
     let result_set = result_sets.into_iter().next().unwrap_or_default();
 
     if result_set.rows.is_empty() {
-        return Err(anyhow!("Table '{}' not found", table));
+        return Err(
+            AppError::new(ErrorKind::NotFound, format!("Table '{}' not found", table)).into(),
+        );
     }
 
-    Ok(result_set
-        .rows
-        .iter()
-        .filter_map(|row| row.first())
-        .map(|value| match value {
-            crate::db::types::Value::Text(s) => s.clone(),
-            _ => value.as_display(),
-        })
-        .collect())
+    let mut names = Vec::new();
+    let mut specials = Vec::new();
+    for row in &result_set.rows {
+        let text = |idx: usize| match row.get(idx) {
+            Some(Value::Text(s)) => s.clone(),
+            Some(other) => other.as_display(),
+            None => String::new(),
+        };
+        let name = text(0);
+        let type_id = match row.get(1) {
+            Some(Value::Int(id)) => *id,
+            _ => 0,
+        };
+        specials.extend(SpecialColumn::classify(&name, type_id, &text(2)));
+        names.push(name);
+    }
+    Ok((names, specials))
 }
 
-fn quote_identifier(input: &str) -> String {
-    if is_simple_identifier(input) {
-        format!("[{}]", input.replace(']', "]]"))
-    } else {
-        input.to_string()
+/// Select-list entry for a column or `--columns` token; types the driver cannot read are
+/// projected to text.
+fn select_item(token: &str, specials: &[SpecialColumn], wkt_max: Option<usize>) -> String {
+    let bare = token.trim_matches(|c| c == '[' || c == ']');
+    match specials
+        .iter()
+        .find(|special| special.name.eq_ignore_ascii_case(bare))
+    {
+        Some(special) => special.column_expression(&bracket(&special.name), wkt_max),
+        None => token.to_string(),
     }
 }
 
-fn is_simple_identifier(input: &str) -> bool {
-    input.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(where_clause: Option<&str>) -> FollowPlan {
+        FollowPlan {
+            select_list: "[Id], [Message]".to_string(),
+            qualified_table: "[dbo].[AppLog]".to_string(),
+            where_clause: where_clause.map(str::to_string),
+            column: "[Id]".to_string(),
+        }
+    }
+
+    #[test]
+    fn follow_poll_adds_cursor_predicate() {
+        let with_where = plan(Some("Level = @P1"));
+        assert_eq!(
+            with_where.poll_sql(Some("@P2")),
+            "SELECT TOP (500) [Id], [Message] FROM [dbo].[AppLog] WHERE (Level = @P1) AND [Id] > @P2 ORDER BY [Id];"
+        );
+        assert_eq!(
+            plan(None).poll_sql(None),
+            "SELECT TOP (500) [Id], [Message] FROM [dbo].[AppLog]  ORDER BY [Id];"
+        );
+        assert!(plan(None).tail_sql(10).contains("TOP (10)"));
+    }
+
+    #[test]
+    fn follow_cursor_casts_temporal_types() {
+        assert_eq!(
+            cursor_expr("@P1", Some("datetime")),
+            "CAST(@P1 AS datetime)"
+        );
+        assert_eq!(cursor_expr("@P1", Some("bigint")), "@P1");
+        assert!(Cursor::from_value(&Value::Float(1.5)).is_err());
+        assert_eq!(Cursor::from_value(&Value::Null).unwrap(), None);
+    }
+
+    #[test]
+    fn random_samples_oversample_pages() {
+        assert!((sample_percent(1_000, 10_000_000) - 0.04).abs() < 1e-9);
+        assert_eq!(sample_percent(1_000, 2_000), 100.0);
+        assert_eq!(sample_percent(1, i64::MAX), 0.0001);
+    }
+
+    #[test]
+    fn keyset_pages_past_the_last_key() {
+        let keys = vec![
+            KeyColumn {
+                name: "CreatedAt".to_string(),
+                data_type: "datetime".to_string(),
+            },
+            KeyColumn {
+                name: "Id".to_string(),
+                data_type: "int".to_string(),
+            },
+        ];
+        assert_eq!(
+            keyset_predicate(&keys, 2),
+            "(([CreatedAt] > CAST(@P2 AS datetime)) OR ([CreatedAt] = CAST(@P2 AS datetime) AND [Id] > @P3))"
+        );
+        let odd = [KeyColumn {
+            name: "Order Id".to_string(),
+            data_type: "int".to_string(),
+        }];
+        assert_eq!(keyset_predicate(&odd, 1), "(([Order Id] > @P1))");
+
+        let created = chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_milli_opt(9, 30, 0, 250)
+            .unwrap();
+        let rs = ResultSet {
+            columns: ["Id", "CreatedAt"]
+                .iter()
+                .map(|name| crate::db::types::Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: vec![vec![Value::Int(42), Value::Text("x".to_string())]],
+        };
+        let token = key_token(&rs, &keys[1..]).unwrap();
+        assert_eq!(token, "[42]");
+
+        let token = serde_json::json!([Cursor::DateTime(created).to_json(), 42]).to_string();
+        assert_eq!(token, r#"["2026-03-01T09:30:00.250",42]"#);
+        assert_eq!(
+            parse_key_token(&token, &keys).unwrap(),
+            vec![Cursor::DateTime(created), Cursor::Int(42)]
+        );
+        assert!(parse_key_token("[42]", &keys).is_err());
+        assert!(parse_key_token(r#"["soon", 42]"#, &keys).is_err());
+        assert!(parse_key_token("42", &keys).is_err());
+    }
 }
@@ -0,0 +1,825 @@
+//! Full `CREATE TABLE` scripts for `describe --ddl-full`, laid out like SSMS "Script Table As":
+//! the table with its primary key and unique constraints, then named defaults, CHECK
+//! constraints, foreign keys, and indexes, each as its own `GO`-separated statement.
+//!
+//! XML and spatial indexes are listed as comments rather than scripted.
+
+use anyhow::Result;
+use tiberius::Query;
+
+use crate::commands::describe;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Value};
+
+/// Where the table (or an index) is stored: a filegroup, or a partition scheme and its column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Storage {
+    data_space: String,
+    partition_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ColumnDef {
+    name: String,
+    /// Ready-to-use type, e.g. `nvarchar(50)` or `[dbo].[Phone]` for alias types.
+    type_spec: String,
+    collation: Option<String>,
+    nullable: bool,
+    identity: Option<(i64, i64)>,
+    computed: Option<String>,
+    persisted: bool,
+    rowguidcol: bool,
+    sparse: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct IndexDef {
+    name: String,
+    /// `sys.indexes.type`: 1 clustered, 2 nonclustered, 3 XML, 4 spatial, 5/6 columnstore.
+    kind: i64,
+    unique: bool,
+    primary_key: bool,
+    unique_constraint: bool,
+    /// Key columns with `true` for descending.
+    keys: Vec<(String, bool)>,
+    included: Vec<String>,
+    filter: Option<String>,
+    storage: Option<Storage>,
+    fill_factor: i64,
+    padded: bool,
+    ignore_dup_key: bool,
+    row_locks: bool,
+    page_locks: bool,
+    disabled: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CheckDef {
+    name: String,
+    definition: String,
+    disabled: bool,
+    not_trusted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ForeignKeyDef {
+    name: String,
+    columns: Vec<String>,
+    referenced_table: String,
+    referenced_columns: Vec<String>,
+    on_delete: String,
+    on_update: String,
+    not_for_replication: bool,
+    disabled: bool,
+    not_trusted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct TableScript {
+    /// Quoted `[schema].[table]`.
+    target: String,
+    columns: Vec<ColumnDef>,
+    storage: Storage,
+    textimage: Option<String>,
+    /// `(constraint, column, definition)`.
+    defaults: Vec<(String, String, String)>,
+    checks: Vec<CheckDef>,
+    foreign_keys: Vec<ForeignKeyDef>,
+    indexes: Vec<IndexDef>,
+}
+
+/// A table's statements with its foreign keys held apart, so a script covering many tables can
+/// add them once every referenced table exists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TableStatements {
+    /// `CREATE TABLE`, defaults, CHECK constraints, then indexes.
+    pub(crate) table: Vec<String>,
+    pub(crate) foreign_keys: Vec<String>,
+}
+
+/// Script `schema.table` with its constraints and indexes, or `None` if there is no such table.
+pub async fn script_table(
+    client: &mut SqlClient,
+    table: &str,
+    schema: &str,
+) -> Result<Option<String>> {
+    Ok(fetch_table(client, table, schema)
+        .await?
+        .map(|script| render(&script)))
+}
+
+/// [`script_table`] as separate statements, without `GO`.
+pub(crate) async fn table_statements(
+    client: &mut SqlClient,
+    table: &str,
+    schema: &str,
+) -> Result<Option<TableStatements>> {
+    Ok(fetch_table(client, table, schema).await?.map(|script| {
+        let (mut table, foreign_keys, indexes) = statements(&script);
+        table.extend(indexes);
+        TableStatements {
+            table,
+            foreign_keys,
+        }
+    }))
+}
+
+async fn fetch_table(
+    client: &mut SqlClient,
+    table: &str,
+    schema: &str,
+) -> Result<Option<TableScript>> {
+    let mut query = Query::new(
+        r#"
+SELECT t.object_id, ds.name, ds.type, pc.name, lob.name
+FROM sys.tables t
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+JOIN sys.indexes i ON i.object_id = t.object_id AND i.index_id IN (0, 1)
+JOIN sys.data_spaces ds ON ds.data_space_id = i.data_space_id
+LEFT JOIN sys.index_columns ic
+    ON ic.object_id = i.object_id AND ic.index_id = i.index_id AND ic.partition_ordinal = 1
+LEFT JOIN sys.columns pc ON pc.object_id = ic.object_id AND pc.column_id = ic.column_id
+LEFT JOIN sys.data_spaces lob ON lob.data_space_id = t.lob_data_space_id
+WHERE s.name = @P1 AND t.name = @P2
+"#,
+    );
+    query.bind(schema);
+    query.bind(table);
+    let table_rs = first_set(executor::run_query(query, client).await?);
+    let Some(row) = table_rs.rows.first() else {
+        return Ok(None);
+    };
+    let object_id = int(row.first()).unwrap_or_default();
+    let mut script = TableScript {
+        target: format!("{}.{}", bracket(schema), bracket(table)),
+        storage: storage(row.get(1), row.get(2), row.get(3)),
+        textimage: text(row.get(4)),
+        ..TableScript::default()
+    };
+
+    let columns = fetch(
+        client,
+        object_id,
+        r#"
+SELECT c.name, TYPE_NAME(c.user_type_id), ty.is_user_defined, SCHEMA_NAME(ty.schema_id),
+       c.max_length, c.precision, c.scale, c.collation_name, c.is_nullable, c.is_identity,
+       CAST(idc.seed_value AS bigint), CAST(idc.increment_value AS bigint),
+       cc.definition, cc.is_persisted, c.is_rowguidcol, c.is_sparse
+FROM sys.columns c
+JOIN sys.types ty ON ty.user_type_id = c.user_type_id
+LEFT JOIN sys.identity_columns idc ON idc.object_id = c.object_id AND idc.column_id = c.column_id
+LEFT JOIN sys.computed_columns cc ON cc.object_id = c.object_id AND cc.column_id = c.column_id
+WHERE c.object_id = @P1
+ORDER BY c.column_id
+"#,
+    )
+    .await?;
+    script.columns = columns
+        .rows
+        .iter()
+        .map(|row| {
+            let type_name = text(row.get(1)).unwrap_or_default();
+            let type_spec = if flag(row.get(2)) {
+                format!(
+                    "{}.{}",
+                    bracket(&text(row.get(3)).unwrap_or_default()),
+                    bracket(&type_name)
+                )
+            } else {
+                describe::format_type_spec(
+                    &type_name,
+                    int(row.get(4)),
+                    int(row.get(5)).and_then(|v| u8::try_from(v).ok()),
+                    int(row.get(6)).and_then(|v| u8::try_from(v).ok()),
+                )
+            };
+            ColumnDef {
+                name: text(row.first()).unwrap_or_default(),
+                type_spec,
+                collation: text(row.get(7)),
+                nullable: flag(row.get(8)),
+                identity: flag(row.get(9))
+                    .then(|| (int(row.get(10)).unwrap_or(1), int(row.get(11)).unwrap_or(1))),
+                computed: text(row.get(12)),
+                persisted: flag(row.get(13)),
+                rowguidcol: flag(row.get(14)),
+                sparse: flag(row.get(15)),
+            }
+        })
+        .collect();
+
+    let defaults = fetch(
+        client,
+        object_id,
+        r#"
+SELECT dc.name, c.name, dc.definition
+FROM sys.default_constraints dc
+JOIN sys.columns c ON c.object_id = dc.parent_object_id AND c.column_id = dc.parent_column_id
+WHERE dc.parent_object_id = @P1
+ORDER BY c.column_id
+"#,
+    )
+    .await?;
+    script.defaults = defaults
+        .rows
+        .iter()
+        .map(|row| {
+            (
+                text(row.first()).unwrap_or_default(),
+                text(row.get(1)).unwrap_or_default(),
+                text(row.get(2)).unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let checks = fetch(
+        client,
+        object_id,
+        r#"
+SELECT name, definition, is_disabled, is_not_trusted
+FROM sys.check_constraints
+WHERE parent_object_id = @P1
+ORDER BY name
+"#,
+    )
+    .await?;
+    script.checks = checks
+        .rows
+        .iter()
+        .map(|row| CheckDef {
+            name: text(row.first()).unwrap_or_default(),
+            definition: text(row.get(1)).unwrap_or_default(),
+            disabled: flag(row.get(2)),
+            not_trusted: flag(row.get(3)),
+        })
+        .collect();
+
+    let foreign_keys = fetch(
+        client,
+        object_id,
+        r#"
+SELECT fk.object_id, fk.name, SCHEMA_NAME(rt.schema_id), rt.name,
+       fk.delete_referential_action_desc, fk.update_referential_action_desc,
+       fk.is_not_for_replication, fk.is_disabled, fk.is_not_trusted
+FROM sys.foreign_keys fk
+JOIN sys.objects rt ON rt.object_id = fk.referenced_object_id
+WHERE fk.parent_object_id = @P1
+ORDER BY fk.name
+"#,
+    )
+    .await?;
+    let foreign_key_columns = fetch(
+        client,
+        object_id,
+        r#"
+SELECT fkc.constraint_object_id, pc.name, rc.name
+FROM sys.foreign_key_columns fkc
+JOIN sys.columns pc ON pc.object_id = fkc.parent_object_id AND pc.column_id = fkc.parent_column_id
+JOIN sys.columns rc
+    ON rc.object_id = fkc.referenced_object_id AND rc.column_id = fkc.referenced_column_id
+WHERE fkc.parent_object_id = @P1
+ORDER BY fkc.constraint_object_id, fkc.constraint_column_id
+"#,
+    )
+    .await?;
+    script.foreign_keys = foreign_keys
+        .rows
+        .iter()
+        .map(|row| {
+            let id = int(row.first());
+            let pairs = foreign_key_columns
+                .rows
+                .iter()
+                .filter(|col| int(col.first()) == id);
+            ForeignKeyDef {
+                name: text(row.get(1)).unwrap_or_default(),
+                columns: pairs
+                    .clone()
+                    .map(|col| text(col.get(1)).unwrap_or_default())
+                    .collect(),
+                referenced_table: format!(
+                    "{}.{}",
+                    bracket(&text(row.get(2)).unwrap_or_default()),
+                    bracket(&text(row.get(3)).unwrap_or_default())
+                ),
+                referenced_columns: pairs
+                    .map(|col| text(col.get(2)).unwrap_or_default())
+                    .collect(),
+                on_delete: text(row.get(4)).unwrap_or_default().replace('_', " "),
+                on_update: text(row.get(5)).unwrap_or_default().replace('_', " "),
+                not_for_replication: flag(row.get(6)),
+                disabled: flag(row.get(7)),
+                not_trusted: flag(row.get(8)),
+            }
+        })
+        .collect();
+
+    let indexes = fetch(
+        client,
+        object_id,
+        r#"
+SELECT i.index_id, i.name, i.type, i.is_unique, i.is_primary_key, i.is_unique_constraint,
+       i.filter_definition, ds.name, ds.type, pc.name, i.fill_factor, i.is_padded,
+       i.ignore_dup_key, i.allow_row_locks, i.allow_page_locks, i.is_disabled
+FROM sys.indexes i
+LEFT JOIN sys.data_spaces ds ON ds.data_space_id = i.data_space_id
+LEFT JOIN sys.index_columns pic
+    ON pic.object_id = i.object_id AND pic.index_id = i.index_id AND pic.partition_ordinal = 1
+LEFT JOIN sys.columns pc ON pc.object_id = pic.object_id AND pc.column_id = pic.column_id
+WHERE i.object_id = @P1 AND i.index_id > 0 AND i.is_hypothetical = 0
+ORDER BY i.index_id
+"#,
+    )
+    .await?;
+    let index_columns = fetch(
+        client,
+        object_id,
+        r#"
+SELECT ic.index_id, c.name, ic.is_descending_key, ic.is_included_column, ic.key_ordinal
+FROM sys.index_columns ic
+JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+WHERE ic.object_id = @P1
+ORDER BY ic.index_id, ic.key_ordinal, ic.index_column_id
+"#,
+    )
+    .await?;
+    script.indexes = indexes
+        .rows
+        .iter()
+        .map(|row| {
+            let id = int(row.first());
+            let columns: Vec<&Vec<Value>> = index_columns
+                .rows
+                .iter()
+                .filter(|col| int(col.first()) == id)
+                .collect();
+            IndexDef {
+                name: text(row.get(1)).unwrap_or_default(),
+                kind: int(row.get(2)).unwrap_or_default(),
+                unique: flag(row.get(3)),
+                primary_key: flag(row.get(4)),
+                unique_constraint: flag(row.get(5)),
+                keys: columns
+                    .iter()
+                    .filter(|col| int(col.get(4)).unwrap_or_default() > 0)
+                    .map(|col| (text(col.get(1)).unwrap_or_default(), flag(col.get(2))))
+                    .collect(),
+                included: columns
+                    .iter()
+                    .filter(|col| flag(col.get(3)))
+                    .map(|col| text(col.get(1)).unwrap_or_default())
+                    .collect(),
+                filter: text(row.get(6)),
+                storage: row
+                    .get(7)
+                    .filter(|value| !matches!(value, Value::Null))
+                    .map(|_| storage(row.get(7), row.get(8), row.get(9))),
+                fill_factor: int(row.get(10)).unwrap_or_default(),
+                padded: flag(row.get(11)),
+                ignore_dup_key: flag(row.get(12)),
+                row_locks: flag(row.get(13)),
+                page_locks: flag(row.get(14)),
+                disabled: flag(row.get(15)),
+            }
+        })
+        .collect();
+
+    Ok(Some(script))
+}
+
+fn render(script: &TableScript) -> String {
+    let (mut statements, foreign_keys, indexes) = statements(script);
+    statements.extend(foreign_keys);
+    statements.extend(indexes);
+    statements.join("\nGO\n") + "\nGO"
+}
+
+/// The table with its defaults and checks, its foreign keys, and its indexes.
+fn statements(script: &TableScript) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let target = &script.target;
+    let mut lines: Vec<String> = script.columns.iter().map(column_line).collect();
+    for index in script
+        .indexes
+        .iter()
+        .filter(|i| i.primary_key || i.unique_constraint)
+    {
+        lines.push(format!(
+            "CONSTRAINT {} {} {} ({}){}{}",
+            bracket(&index.name),
+            if index.primary_key {
+                "PRIMARY KEY"
+            } else {
+                "UNIQUE"
+            },
+            if index.kind == 1 {
+                "CLUSTERED"
+            } else {
+                "NONCLUSTERED"
+            },
+            key_list(&index.keys),
+            index_options(index),
+            storage_clause(index.storage.as_ref(), &script.storage),
+        ));
+    }
+
+    let mut statements = vec![format!(
+        "CREATE TABLE {} (\n    {}\n) ON {}{};",
+        target,
+        lines.join(",\n    "),
+        on_clause(&script.storage),
+        script
+            .textimage
+            .as_ref()
+            .map(|fg| format!(" TEXTIMAGE_ON {}", bracket(fg)))
+            .unwrap_or_default(),
+    )];
+    for (name, column, definition) in &script.defaults {
+        statements.push(format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} DEFAULT {} FOR {};",
+            target,
+            bracket(name),
+            definition,
+            bracket(column)
+        ));
+    }
+    for check in &script.checks {
+        statements.push(format!(
+            "ALTER TABLE {} WITH {} ADD CONSTRAINT {} CHECK {};",
+            target,
+            if check.not_trusted {
+                "NOCHECK"
+            } else {
+                "CHECK"
+            },
+            bracket(&check.name),
+            check.definition
+        ));
+        if check.disabled {
+            statements.push(format!(
+                "ALTER TABLE {} NOCHECK CONSTRAINT {};",
+                target,
+                bracket(&check.name)
+            ));
+        }
+    }
+    let mut foreign_keys = Vec::new();
+    for fk in &script.foreign_keys {
+        let mut statement = format!(
+            "ALTER TABLE {} WITH {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+            target,
+            if fk.not_trusted { "NOCHECK" } else { "CHECK" },
+            bracket(&fk.name),
+            name_list(&fk.columns),
+            fk.referenced_table,
+            name_list(&fk.referenced_columns),
+        );
+        for (action, rule) in [("DELETE", &fk.on_delete), ("UPDATE", &fk.on_update)] {
+            if !rule.is_empty() && rule != "NO ACTION" {
+                statement.push_str(&format!(" ON {} {}", action, rule));
+            }
+        }
+        if fk.not_for_replication {
+            statement.push_str(" NOT FOR REPLICATION");
+        }
+        statement.push(';');
+        foreign_keys.push(statement);
+        if fk.disabled {
+            foreign_keys.push(format!(
+                "ALTER TABLE {} NOCHECK CONSTRAINT {};",
+                target,
+                bracket(&fk.name)
+            ));
+        }
+    }
+    let mut indexes = Vec::new();
+    for index in script
+        .indexes
+        .iter()
+        .filter(|i| !i.primary_key && !i.unique_constraint)
+    {
+        indexes.push(match index.kind {
+            1 | 2 => format!(
+                "CREATE {}{} INDEX {} ON {} ({}){}{}{}{};",
+                if index.unique { "UNIQUE " } else { "" },
+                if index.kind == 1 {
+                    "CLUSTERED"
+                } else {
+                    "NONCLUSTERED"
+                },
+                bracket(&index.name),
+                target,
+                key_list(&index.keys),
+                if index.included.is_empty() {
+                    String::new()
+                } else {
+                    format!(" INCLUDE ({})", name_list(&index.included))
+                },
+                index
+                    .filter
+                    .as_ref()
+                    .map(|filter| format!(" WHERE {}", filter))
+                    .unwrap_or_default(),
+                index_options(index),
+                storage_clause(index.storage.as_ref(), &script.storage),
+            ),
+            5 => format!(
+                "CREATE CLUSTERED COLUMNSTORE INDEX {} ON {}{};",
+                bracket(&index.name),
+                target,
+                storage_clause(index.storage.as_ref(), &script.storage),
+            ),
+            6 => format!(
+                "CREATE NONCLUSTERED COLUMNSTORE INDEX {} ON {} ({}){}{};",
+                bracket(&index.name),
+                target,
+                name_list(&index.included),
+                index
+                    .filter
+                    .as_ref()
+                    .map(|filter| format!(" WHERE {}", filter))
+                    .unwrap_or_default(),
+                storage_clause(index.storage.as_ref(), &script.storage),
+            ),
+            3 => format!("-- XML index {} is not scripted", bracket(&index.name)),
+            4 => format!("-- Spatial index {} is not scripted", bracket(&index.name)),
+            _ => format!("-- Index {} is not scripted", bracket(&index.name)),
+        });
+        if index.disabled && matches!(index.kind, 1 | 2 | 5 | 6) {
+            indexes.push(format!(
+                "ALTER INDEX {} ON {} DISABLE;",
+                bracket(&index.name),
+                target
+            ));
+        }
+    }
+    (statements, foreign_keys, indexes)
+}
+
+fn column_line(column: &ColumnDef) -> String {
+    if let Some(definition) = &column.computed {
+        return format!(
+            "{} AS {}{}",
+            bracket(&column.name),
+            definition,
+            if column.persisted { " PERSISTED" } else { "" }
+        );
+    }
+    let mut line = format!("{} {}", bracket(&column.name), column.type_spec);
+    if column.sparse {
+        line.push_str(" SPARSE");
+    }
+    if let Some(collation) = &column.collation {
+        line.push_str(&format!(" COLLATE {}", collation));
+    }
+    if let Some((seed, increment)) = column.identity {
+        line.push_str(&format!(" IDENTITY({}, {})", seed, increment));
+    }
+    if column.rowguidcol {
+        line.push_str(" ROWGUIDCOL");
+    }
+    line.push_str(if column.nullable {
+        " NULL"
+    } else {
+        " NOT NULL"
+    });
+    line
+}
+
+fn key_list(keys: &[(String, bool)]) -> String {
+    keys.iter()
+        .map(|(name, descending)| {
+            format!(
+                "{} {}",
+                bracket(name),
+                if *descending { "DESC" } else { "ASC" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn name_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| bracket(name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `WITH (...)` for the options that differ from the defaults.
+fn index_options(index: &IndexDef) -> String {
+    let mut options = Vec::new();
+    if index.padded {
+        options.push("PAD_INDEX = ON".to_string());
+    }
+    if index.fill_factor > 0 && index.fill_factor != 100 {
+        options.push(format!("FILLFACTOR = {}", index.fill_factor));
+    }
+    if index.ignore_dup_key {
+        options.push("IGNORE_DUP_KEY = ON".to_string());
+    }
+    if !index.row_locks {
+        options.push("ALLOW_ROW_LOCKS = OFF".to_string());
+    }
+    if !index.page_locks {
+        options.push("ALLOW_PAGE_LOCKS = OFF".to_string());
+    }
+    if options.is_empty() {
+        String::new()
+    } else {
+        format!(" WITH ({})", options.join(", "))
+    }
+}
+
+/// ` ON ...` when an index is stored somewhere other than the table.
+fn storage_clause(storage: Option<&Storage>, table: &Storage) -> String {
+    match storage {
+        Some(storage) if storage != table => format!(" ON {}", on_clause(storage)),
+        _ => String::new(),
+    }
+}
+
+fn on_clause(storage: &Storage) -> String {
+    match &storage.partition_column {
+        Some(column) => format!("{}({})", bracket(&storage.data_space), bracket(column)),
+        None => bracket(&storage.data_space),
+    }
+}
+
+fn storage(name: Option<&Value>, kind: Option<&Value>, column: Option<&Value>) -> Storage {
+    let partitioned = text(kind).is_some_and(|kind| kind.trim() == "PS");
+    Storage {
+        data_space: text(name).unwrap_or_else(|| "PRIMARY".to_string()),
+        partition_column: if partitioned { text(column) } else { None },
+    }
+}
+
+async fn fetch(client: &mut SqlClient, object_id: i64, sql: &'static str) -> Result<ResultSet> {
+    let mut query = Query::new(sql);
+    query.bind(object_id as i32);
+    Ok(first_set(executor::run_query(query, client).await?))
+}
+
+fn first_set(result_sets: Vec<ResultSet>) -> ResultSet {
+    result_sets.into_iter().next().unwrap_or_default()
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    match value {
+        Some(Value::Text(v)) => Some(v.clone()),
+        Some(Value::Int(v)) => Some(v.to_string()),
+        _ => None,
+    }
+}
+
+fn int(value: Option<&Value>) -> Option<i64> {
+    match value {
+        Some(Value::Int(v)) => Some(*v),
+        Some(Value::Bool(v)) => Some(i64::from(*v)),
+        Some(Value::Text(v)) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn flag(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(v)) => *v,
+        Some(Value::Int(v)) => *v != 0,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_table_with_constraints_and_indexes() {
+        let primary = Storage {
+            data_space: "PRIMARY".to_string(),
+            partition_column: None,
+        };
+        let script = TableScript {
+            target: "[sales].[Orders]".to_string(),
+            columns: vec![
+                ColumnDef {
+                    name: "Id".to_string(),
+                    type_spec: "int".to_string(),
+                    identity: Some((1, 1)),
+                    ..ColumnDef::default()
+                },
+                ColumnDef {
+                    name: "Status".to_string(),
+                    type_spec: "nvarchar(20)".to_string(),
+                    collation: Some("Latin1_General_CI_AS".to_string()),
+                    ..ColumnDef::default()
+                },
+                ColumnDef {
+                    name: "CustomerId".to_string(),
+                    type_spec: "int".to_string(),
+                    nullable: true,
+                    ..ColumnDef::default()
+                },
+                ColumnDef {
+                    name: "Total".to_string(),
+                    computed: Some("([Qty]*[Price])".to_string()),
+                    persisted: true,
+                    ..ColumnDef::default()
+                },
+            ],
+            storage: primary.clone(),
+            textimage: None,
+            defaults: vec![(
+                "DF_Orders_Status".to_string(),
+                "Status".to_string(),
+                "(N'new')".to_string(),
+            )],
+            checks: vec![CheckDef {
+                name: "CK_Orders_Status".to_string(),
+                definition: "([Status]<>N'')".to_string(),
+                disabled: false,
+                not_trusted: true,
+            }],
+            foreign_keys: vec![ForeignKeyDef {
+                name: "FK_Orders_Customers".to_string(),
+                columns: vec!["CustomerId".to_string()],
+                referenced_table: "[sales].[Customers]".to_string(),
+                referenced_columns: vec!["Id".to_string()],
+                on_delete: "CASCADE".to_string(),
+                on_update: "NO ACTION".to_string(),
+                ..ForeignKeyDef::default()
+            }],
+            indexes: vec![
+                IndexDef {
+                    name: "PK_Orders".to_string(),
+                    kind: 1,
+                    unique: true,
+                    primary_key: true,
+                    keys: vec![("Id".to_string(), false)],
+                    storage: Some(primary.clone()),
+                    row_locks: true,
+                    page_locks: true,
+                    ..IndexDef::default()
+                },
+                IndexDef {
+                    name: "IX_Orders_Customer".to_string(),
+                    kind: 2,
+                    keys: vec![("CustomerId".to_string(), false), ("Id".to_string(), true)],
+                    included: vec!["Status".to_string()],
+                    filter: Some("([CustomerId] IS NOT NULL)".to_string()),
+                    storage: Some(Storage {
+                        data_space: "INDEXES".to_string(),
+                        partition_column: None,
+                    }),
+                    fill_factor: 90,
+                    row_locks: true,
+                    page_locks: true,
+                    ..IndexDef::default()
+                },
+                IndexDef {
+                    name: "XML_Orders".to_string(),
+                    kind: 3,
+                    ..IndexDef::default()
+                },
+            ],
+        };
+        assert_eq!(
+            render(&script),
+            "CREATE TABLE [sales].[Orders] (\n\
+             \x20   [Id] int IDENTITY(1, 1) NOT NULL,\n\
+             \x20   [Status] nvarchar(20) COLLATE Latin1_General_CI_AS NOT NULL,\n\
+             \x20   [CustomerId] int NULL,\n\
+             \x20   [Total] AS ([Qty]*[Price]) PERSISTED,\n\
+             \x20   CONSTRAINT [PK_Orders] PRIMARY KEY CLUSTERED ([Id] ASC)\n\
+             ) ON [PRIMARY];\n\
+             GO\n\
+             ALTER TABLE [sales].[Orders] ADD CONSTRAINT [DF_Orders_Status] DEFAULT (N'new') FOR [Status];\n\
+             GO\n\
+             ALTER TABLE [sales].[Orders] WITH NOCHECK ADD CONSTRAINT [CK_Orders_Status] CHECK ([Status]<>N'');\n\
+             GO\n\
+             ALTER TABLE [sales].[Orders] WITH CHECK ADD CONSTRAINT [FK_Orders_Customers] FOREIGN KEY ([CustomerId]) REFERENCES [sales].[Customers] ([Id]) ON DELETE CASCADE;\n\
+             GO\n\
+             CREATE NONCLUSTERED INDEX [IX_Orders_Customer] ON [sales].[Orders] ([CustomerId] ASC, [Id] DESC) INCLUDE ([Status]) WHERE ([CustomerId] IS NOT NULL) WITH (FILLFACTOR = 90) ON [INDEXES];\n\
+             GO\n\
+             -- XML index [XML_Orders] is not scripted\n\
+             GO"
+        );
+    }
+
+    #[test]
+    fn partitioned_storage_names_the_scheme_column() {
+        let storage = storage(
+            Some(&Value::Text("ps_Date".to_string())),
+            Some(&Value::Text("PS".to_string())),
+            Some(&Value::Text("OrderDate".to_string())),
+        );
+        assert_eq!(on_clause(&storage), "[ps_Date]([OrderDate])");
+        assert_eq!(
+            storage_clause(Some(&storage), &storage),
+            "",
+            "indexes aligned with the table need no ON clause"
+        );
+    }
+}
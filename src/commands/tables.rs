@@ -1,27 +1,37 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use serde_json::json;
 use tiberius::Query;
 use tracing::warn;
 
 use crate::cli::{CliArgs, DescribeArgs, TablesArgs};
-use crate::commands::{common, describe, paging};
+use crate::commands::{common, describe, paging, tables_audit, tables_summary};
 use crate::config::OutputFormat;
 use crate::db::client;
-use crate::db::executor;
+use crate::db::metadata_cache;
+use crate::db::pool::Pool;
 use crate::db::types::Value;
-use crate::output::{TableOptions, json as json_out, table};
+use crate::output::contract::TablesOutput;
+use crate::output::{TableOptions, json as json_out, shape, table};
 
 const LIMIT_DEFAULT: u64 = 200;
 const LIMIT_MAX: u64 = 500;
 const DESCRIBE_LIMIT_DEFAULT: u64 = 5;
+const OBJECT_ID_EXPR: &str = "OBJECT_ID(QUOTENAME(TABLE_SCHEMA) + '.' + QUOTENAME(TABLE_NAME))";
 
 pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
+    if cmd.orphaned {
+        return tables_audit::run(args, cmd);
+    }
+    if cmd.summary {
+        return tables_summary::run(args, cmd);
+    }
     let resolved = common::load_config(args)?;
     let format = common::output_format(args, &resolved);
 
     let include_views = cmd.include_views;
-    let summary = cmd.summary;
-    let with_counts = if summary { true } else { cmd.with_counts };
+    let with_counts = cmd.with_counts;
 
     // Use different default limit when --describe is set
     let default_limit = if cmd.describe {
@@ -31,7 +41,7 @@ pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
     };
     let (limit, limit_all) = parse_limit(cmd.limit.as_deref(), default_limit);
     let offset = common::parse_offset(cmd.offset);
-    let fetch_all = summary || limit_all;
+    let fetch_all = limit_all;
 
     let explicit_schema = cmd.schema.as_deref();
     let default_schemas = if explicit_schema.is_none() {
@@ -41,6 +51,7 @@ pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
     };
 
     let like = cmd.like.clone();
+    let since = cmd.modified_since.as_ref();
 
     let (rows, total) = tokio::runtime::Runtime::new()?.block_on(async {
         let mut client = client::connect(&resolved.connection).await?;
@@ -71,6 +82,19 @@ pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
         };
 
         let like_ph = next_param(&mut param_index);
+        // modify_date lives in sys.objects, not INFORMATION_SCHEMA.
+        let since_clause = match since {
+            Some(_) => format!(
+                "  AND EXISTS (SELECT 1 FROM sys.objects mo WHERE mo.object_id = {} {})\n",
+                OBJECT_ID_EXPR,
+                common::modified_since_clause(
+                    "mo.modify_date",
+                    since,
+                    &next_param(&mut param_index)
+                )
+            ),
+            None => String::new(),
+        };
         let offset_ph = if fetch_all {
             String::new()
         } else {
@@ -103,30 +127,40 @@ WITH base AS (
         TABLE_SCHEMA AS schemaName,
         TABLE_NAME AS name,
         TABLE_TYPE AS type,
-        ROW_NUMBER() OVER (ORDER BY TABLE_SCHEMA, TABLE_NAME) AS rownum
+        ROW_NUMBER() OVER (ORDER BY {}) AS rownum
     FROM INFORMATION_SCHEMA.TABLES
     WHERE ({} = 1 OR TABLE_TYPE = 'BASE TABLE')
       {}\
       AND ({} IS NULL OR TABLE_NAME LIKE {})
+    {}\
 )
 SELECT b.schemaName AS [schema],
        b.name AS [name],
        b.type AS [type],
-       {} AS [rowCount]
+       {} AS [rowCount]{}
 FROM base b
 {}
 {}
-ORDER BY b.schemaName, b.name;\
+ORDER BY {};\
 ",
+            common::order_keys(&resolved, "TABLE_SCHEMA, TABLE_NAME", OBJECT_ID_EXPR),
             include_ph,
             schema_clause.clone(),
             like_ph,
             like_ph,
+            since_clause,
             if with_counts {
                 "counts.row_count"
             } else {
                 "NULL"
             },
+            if since.is_some() {
+                ",
+       (SELECT modify_date FROM sys.objects
+        WHERE object_id = OBJECT_ID(QUOTENAME(b.schemaName) + '.' + QUOTENAME(b.name))) AS [modifiedAt]"
+            } else {
+                ""
+            },
             if with_counts {
                 "OUTER APPLY (\
      SELECT SUM(ps.row_count) AS row_count
@@ -138,6 +172,7 @@ ORDER BY b.schemaName, b.name;\
                 ""
             },
             rownum_clause,
+            common::order_keys(&resolved, "b.schemaName, b.name", "b.rownum"),
         );
 
         let mut list_query = Query::new(list_sql);
@@ -148,12 +183,14 @@ ORDER BY b.schemaName, b.name;\
             &default_schemas,
             like.clone(),
         );
+        common::bind_modified_since(&mut list_query, since);
         if !fetch_all {
             list_query.bind(offset as i64);
             list_query.bind(limit as i64);
         }
 
-        let list_sets = executor::run_query(list_query, &mut client).await?;
+        let cacheable = !with_counts && since.is_none();
+        let list_sets = metadata_cache::run_query_when(cacheable, list_query, &mut client).await?;
         let list_set = list_sets.into_iter().next().unwrap_or_default();
 
         let total = if fetch_all {
@@ -165,9 +202,14 @@ SELECT COUNT(*) AS total
 FROM INFORMATION_SCHEMA.TABLES
 WHERE ({} = 1 OR TABLE_TYPE = 'BASE TABLE')
   {}\
-  AND ({} IS NULL OR TABLE_NAME LIKE {});\
+  AND ({} IS NULL OR TABLE_NAME LIKE {})
+{};\
 ",
-                include_ph, schema_clause, like_ph, like_ph,
+                include_ph,
+                schema_clause,
+                like_ph,
+                like_ph,
+                since_clause.trim_end(),
             );
             let mut count_query = Query::new(count_sql);
             bind_base_params(
@@ -177,7 +219,9 @@ WHERE ({} = 1 OR TABLE_TYPE = 'BASE TABLE')
                 &default_schemas,
                 like.clone(),
             );
-            let count_sets = executor::run_query(count_query, &mut client).await?;
+            common::bind_modified_since(&mut count_query, since);
+            let count_sets =
+                metadata_cache::run_query_when(cacheable, count_query, &mut client).await?;
             count_sets
                 .first()
                 .and_then(|rs| rs.rows.first())
@@ -204,21 +248,30 @@ WHERE ({} = 1 OR TABLE_TYPE = 'BASE TABLE')
     // Handle --describe mode: describe each table instead of listing
     if cmd.describe {
         return run_describe_mode(
-            args, &rows, total, offset, limit, format, &resolved, &cmd.like,
+            args,
+            &rows,
+            total,
+            offset,
+            limit,
+            format,
+            &resolved,
+            &cmd.like,
+            cmd.concurrency,
         );
     }
 
+    let rows = shape::apply(rows, &args.output)?;
     if matches!(format, OutputFormat::Json) {
-        let payload = json!({
-            "total": paging.total,
-            "count": paging.count,
-            "offset": paging.offset,
-            "limit": paging.limit,
-            "hasMore": paging.has_more,
-            "nextOffset": paging.next_offset,
-            "tables": json_out::result_set_rows_to_objects(&rows),
-        });
-        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        let payload = TablesOutput {
+            total: paging.total,
+            count: paging.count,
+            offset: paging.offset,
+            limit: paging.limit,
+            has_more: paging.has_more,
+            next_offset: paging.next_offset,
+            tables: json_out::result_set_rows_to_maps(&rows),
+        };
+        let body = json_out::emit_json(&payload, common::json_pretty(&resolved))?;
         if !args.quiet {
             println!("{}", body);
         }
@@ -255,6 +308,7 @@ fn run_describe_mode(
     format: OutputFormat,
     resolved: &crate::config::ResolvedConfig,
     like_filter: &Option<String>,
+    concurrency: usize,
 ) -> Result<()> {
     let count = rows.rows.len() as u64;
 
@@ -284,13 +338,7 @@ fn run_describe_mode(
             };
             // Get object type (BASE TABLE or VIEW), default to Table
             let obj_type = match row.get(2) {
-                Some(Value::Text(s)) => {
-                    if s.to_uppercase().contains("VIEW") {
-                        "View"
-                    } else {
-                        "Table"
-                    }
-                }
+                Some(Value::Text(s)) if s.to_uppercase().contains("VIEW") => "View",
                 _ => "Table",
             }
             .to_string();
@@ -327,79 +375,109 @@ fn run_describe_mode(
         no_indexes: false,
         no_triggers: false,
         no_ddl: false,
+        ddl_full: false,
         include_fks: false,
         include_constraints: false,
+        permissions: false,
+        max_objects: usize::MAX,
     };
 
     let json_pretty = common::json_pretty(resolved);
 
-    // Use a single runtime and connection for all describes
-    // Collect errors per-table instead of failing on first error
-    let (json_results, errors) = tokio::runtime::Runtime::new()?.block_on(async {
-        let mut client = client::connect(&resolved.connection).await?;
-        let mut results: Vec<serde_json::Value> = Vec::new();
-        let mut errors: Vec<(String, String, String)> = Vec::new(); // (schema, name, error)
-
-        for (i, (schema, name, obj_type)) in tables.iter().enumerate() {
-            if matches!(format, OutputFormat::Json) {
-                // JSON mode: collect results
-                match describe::describe_table_async(
-                    &mut client,
-                    name,
-                    Some(schema.as_str()),
+    // Describe on a small pool of connections so latency overlaps; results are put back in
+    // listing order before printing. Errors are collected per table instead of failing the batch.
+    // Columns and the rarely-set versioning/partitioning facts are fetched once for every table.
+    let task_format = if matches!(format, OutputFormat::Json) {
+        OutputFormat::Json
+    } else {
+        format
+    };
+    let task_pretty = matches!(format, OutputFormat::Json) && json_pretty;
+    let outcomes = tokio::runtime::Runtime::new()?.block_on(async {
+        let pool = Pool::new(&resolved.connection, concurrency);
+        // Fail fast on bad credentials or an unreachable server before fanning out.
+        let mut client = pool.get().await?;
+        let names: Vec<(String, String)> = tables
+            .iter()
+            .map(|(schema, name, _)| (schema.clone(), name.clone()))
+            .collect();
+        let batch = Arc::new(
+            client
+                .run(async |client| describe::prefetch_tables(client, &names).await)
+                .await?,
+        );
+        drop(client);
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (idx, (schema, name, _)) in tables.iter().enumerate() {
+            let pool = Arc::clone(&pool);
+            let batch = Arc::clone(&batch);
+            let describe_args = describe_args.clone();
+            let (schema, name) = (schema.clone(), name.clone());
+            tasks.spawn(async move {
+                let outcome = describe_pooled(
+                    &pool,
+                    &name,
+                    &schema,
                     &describe_args,
-                    OutputFormat::Json,
-                    json_pretty,
+                    task_format,
+                    task_pretty,
+                    &batch,
                 )
-                .await
-                {
-                    Ok(result) => match serde_json::from_str::<serde_json::Value>(&result) {
-                        Ok(v) => results.push(v),
-                        Err(e) => {
-                            warn!(
-                                "Failed to parse describe output for {}.{}: {}",
-                                schema, name, e
-                            );
-                            errors.push((
-                                schema.clone(),
-                                name.clone(),
-                                format!("JSON parse error: {}", e),
-                            ));
-                        }
-                    },
+                .await;
+                (idx, outcome)
+            });
+        }
+
+        let mut outcomes: Vec<Option<Result<String, String>>> = vec![None; tables.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, outcome) = joined?;
+            outcomes[idx] = Some(outcome);
+        }
+        Ok::<_, anyhow::Error>(outcomes)
+    })?;
+
+    let mut json_results: Vec<serde_json::Value> = Vec::new();
+    let mut errors: Vec<(String, String, String)> = Vec::new(); // (schema, name, error)
+    for (i, ((schema, name, obj_type), outcome)) in tables.iter().zip(outcomes).enumerate() {
+        let outcome = outcome.unwrap_or_else(|| Err("describe task did not finish".to_string()));
+        if matches!(format, OutputFormat::Json) {
+            match outcome {
+                Ok(result) => match serde_json::from_str::<serde_json::Value>(&result) {
+                    Ok(v) => json_results.push(v),
                     Err(e) => {
-                        warn!("Failed to describe {}.{}: {}", schema, name, e);
-                        errors.push((schema.clone(), name.clone(), e.to_string()));
+                        warn!(
+                            "Failed to parse describe output for {}.{}: {}",
+                            schema, name, e
+                        );
+                        errors.push((
+                            schema.clone(),
+                            name.clone(),
+                            format!("JSON parse error: {}", e),
+                        ));
                     }
+                },
+                Err(e) => {
+                    warn!("Failed to describe {}.{}: {}", schema, name, e);
+                    errors.push((schema.clone(), name.clone(), e));
                 }
-            } else {
-                // Text mode: print with separators
-                if i > 0 {
-                    println!("\n---\n");
-                }
-                println!("## {}.{} ({})\n", schema, name, obj_type);
-                match describe::describe_table_async(
-                    &mut client,
-                    name,
-                    Some(schema.as_str()),
-                    &describe_args,
-                    format,
-                    false,
-                )
-                .await
-                {
-                    Ok(result) => print!("{}", result),
-                    Err(e) => {
-                        warn!("Failed to describe {}.{}: {}", schema, name, e);
-                        println!("Error: {}\n", e);
-                        errors.push((schema.clone(), name.clone(), e.to_string()));
-                    }
+            }
+        } else {
+            // Text mode: print with separators
+            if i > 0 {
+                println!("\n---\n");
+            }
+            println!("## {}.{} ({})\n", schema, name, obj_type);
+            match outcome {
+                Ok(result) => print!("{}", result),
+                Err(e) => {
+                    warn!("Failed to describe {}.{}: {}", schema, name, e);
+                    println!("Error: {}\n", e);
+                    errors.push((schema.clone(), name.clone(), e));
                 }
             }
         }
-
-        Ok::<_, anyhow::Error>((results, errors))
-    })?;
+    }
 
     // Calculate pagination values
     let has_more = total > offset + count;
@@ -463,6 +541,37 @@ fn run_describe_mode(
     Ok(())
 }
 
+/// Describe one table on a pooled connection.
+///
+/// A connection that saw an error is closed rather than reused, in case the error left it
+/// mid-stream.
+async fn describe_pooled(
+    pool: &Arc<Pool>,
+    name: &str,
+    schema: &str,
+    describe_args: &DescribeArgs,
+    format: OutputFormat,
+    json_pretty: bool,
+    batch: &describe::TableBatch,
+) -> Result<String, String> {
+    let mut client = pool.get().await.map_err(|e| e.to_string())?;
+    client
+        .run(async |client| {
+            describe::describe_table_async(
+                client,
+                name,
+                Some(schema),
+                describe_args,
+                format,
+                json_pretty,
+                Some(batch),
+            )
+            .await
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
 fn parse_limit(raw: Option<&str>, default: u64) -> (u64, bool) {
     if let Some(value) = raw {
         // "all" or "0" means fetch everything
@@ -0,0 +1,416 @@
+//! `tables --orphaned`: referential integrity audit.
+//!
+//! Reports schema debt that lets bad rows in or makes joins slow: tables without a primary
+//! key, foreign keys with no index on their columns, disabled or untrusted FOREIGN KEY and
+//! CHECK constraints, and `*_id`/`*Id` columns that no foreign key covers. Each check is its
+//! own catalog query: a batch would lose the position of any check that found nothing.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, TablesArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    severity: Severity,
+    check: &'static str,
+    schema: String,
+    table: String,
+    /// Constraint or column the finding is about; empty for table-level findings.
+    object: String,
+    detail: String,
+}
+
+pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let schemas = match &cmd.schema {
+        Some(schema) => vec![schema.clone()],
+        None => resolved.connection.default_schemas.clone(),
+    };
+    let checks = audit_sql(schemas.len());
+
+    let result_sets = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut result_sets = Vec::new();
+        for sql in checks {
+            let mut query = Query::new(sql);
+            query.bind(cmd.like.clone());
+            for schema in &schemas {
+                query.bind(schema.clone());
+            }
+            let sets = executor::run_query(query, &mut client).await?;
+            result_sets.push(sets.into_iter().next().unwrap_or_default());
+        }
+        Ok::<_, anyhow::Error>(result_sets)
+    })?;
+
+    let mut findings = collect_findings(&result_sets);
+    findings.sort_by(|a, b| {
+        (a.severity, &a.schema, &a.table, a.check, &a.object)
+            .cmp(&(b.severity, &b.schema, &b.table, b.check, &b.object))
+    });
+    let count = |severity: Severity| findings.iter().filter(|f| f.severity == severity).count();
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "schemas": schemas,
+            "like": cmd.like,
+            "counts": {
+                "high": count(Severity::High),
+                "medium": count(Severity::Medium),
+                "low": count(Severity::Low),
+            },
+            "findings": findings
+                .iter()
+                .map(|f| json!({
+                    "severity": f.severity.as_str(),
+                    "check": f.check,
+                    "schema": f.schema,
+                    "table": f.table,
+                    "object": (!f.object.is_empty()).then_some(&f.object),
+                    "detail": f.detail,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    if findings.is_empty() {
+        println!("No referential integrity findings.");
+        return Ok(());
+    }
+    let result = table::render_result_set_table(
+        &findings_to_result_set(&findings),
+        format,
+        &TableOptions::default(),
+    );
+    println!("{}", result.output);
+    println!(
+        "\n{} finding{}: {} high, {} medium, {} low",
+        findings.len(),
+        if findings.len() == 1 { "" } else { "s" },
+        count(Severity::High),
+        count(Severity::Medium),
+        count(Severity::Low)
+    );
+    Ok(())
+}
+
+/// The four audit queries; `@P1` is the optional LIKE pattern and `@P2..` the schemas.
+fn audit_sql(schema_count: usize) -> [String; 4] {
+    let schema_filter = if schema_count == 0 {
+        String::new()
+    } else {
+        let placeholders = (0..schema_count)
+            .map(|i| format!("@P{}", i + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("AND s.name IN ({})", placeholders)
+    };
+    let scope = format!(
+        "t.is_ms_shipped = 0 AND (@P1 IS NULL OR t.name LIKE @P1) {}",
+        schema_filter
+    );
+
+    let missing_primary_keys = format!(
+        r#"
+SELECT s.name AS schema_name, t.name AS table_name,
+       CASE WHEN EXISTS (SELECT 1 FROM sys.indexes i WHERE i.object_id = t.object_id AND i.index_id = 1)
+            THEN 0 ELSE 1 END AS is_heap
+FROM sys.tables t
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+WHERE {scope}
+  AND NOT EXISTS (SELECT 1 FROM sys.indexes i WHERE i.object_id = t.object_id AND i.is_primary_key = 1);
+"#
+    );
+
+    // An index supports a foreign key when every key column is among its leading key columns.
+    let unindexed_foreign_keys = format!(
+        r#"
+SELECT s.name AS schema_name, t.name AS table_name, fk.name AS constraint_name,
+       STRING_AGG(c.name, ', ') WITHIN GROUP (ORDER BY fkc.constraint_column_id) AS columns
+FROM sys.foreign_keys fk
+JOIN sys.tables t ON t.object_id = fk.parent_object_id
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id
+JOIN sys.columns c ON c.object_id = fkc.parent_object_id AND c.column_id = fkc.parent_column_id
+WHERE {scope}
+  AND NOT EXISTS (
+    SELECT 1
+    FROM sys.indexes i
+    WHERE i.object_id = fk.parent_object_id
+      AND i.index_id > 0
+      AND i.is_disabled = 0
+      AND NOT EXISTS (
+        SELECT 1
+        FROM sys.foreign_key_columns fkc2
+        WHERE fkc2.constraint_object_id = fk.object_id
+          AND NOT EXISTS (
+            SELECT 1
+            FROM sys.index_columns ic
+            WHERE ic.object_id = i.object_id
+              AND ic.index_id = i.index_id
+              AND ic.column_id = fkc2.parent_column_id
+              AND ic.key_ordinal BETWEEN 1 AND (
+                SELECT COUNT(*) FROM sys.foreign_key_columns n
+                WHERE n.constraint_object_id = fk.object_id
+              )
+          )
+      )
+  )
+GROUP BY s.name, t.name, fk.name;
+"#
+    );
+
+    let untrusted_constraints = format!(
+        r#"
+SELECT s.name AS schema_name, t.name AS table_name, k.name AS constraint_name, k.kind,
+       k.is_disabled, k.is_not_trusted
+FROM (
+    SELECT parent_object_id, name, 'FOREIGN KEY' AS kind, is_disabled, is_not_trusted
+    FROM sys.foreign_keys
+    UNION ALL
+    SELECT parent_object_id, name, 'CHECK', is_disabled, is_not_trusted
+    FROM sys.check_constraints
+) k
+JOIN sys.tables t ON t.object_id = k.parent_object_id
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+WHERE {scope}
+  AND (k.is_disabled = 1 OR k.is_not_trusted = 1);
+"#
+    );
+
+    // Binary collation keeps [a-z] to lower case, so `OrderId` matches but `Paid` does not.
+    let unreferenced_keys = format!(
+        r#"
+SELECT s.name AS schema_name, t.name AS table_name, c.name AS column_name
+FROM sys.columns c
+JOIN sys.tables t ON t.object_id = c.object_id
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+WHERE {scope}
+  AND (c.name LIKE '%[_]id' OR c.name COLLATE Latin1_General_BIN LIKE '%[a-z]Id')
+  AND NOT EXISTS (
+    SELECT 1 FROM sys.foreign_key_columns fkc
+    WHERE fkc.parent_object_id = c.object_id AND fkc.parent_column_id = c.column_id
+  )
+  AND NOT EXISTS (
+    SELECT 1
+    FROM sys.indexes i
+    JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+    WHERE i.object_id = c.object_id AND i.is_primary_key = 1 AND ic.column_id = c.column_id
+  );
+"#
+    );
+
+    [
+        missing_primary_keys,
+        unindexed_foreign_keys,
+        untrusted_constraints,
+        unreferenced_keys,
+    ]
+}
+
+fn collect_findings(result_sets: &[ResultSet]) -> Vec<Finding> {
+    let rows = |idx: usize| {
+        result_sets
+            .get(idx)
+            .map(|rs| rs.rows.as_slice())
+            .unwrap_or_default()
+    };
+    let mut findings = Vec::new();
+
+    for row in rows(0) {
+        let detail = if value_flag(row.get(2)) {
+            "no primary key (heap)"
+        } else {
+            "no primary key"
+        };
+        findings.push(Finding {
+            severity: Severity::High,
+            check: "missing-primary-key",
+            schema: value_text(row.first()),
+            table: value_text(row.get(1)),
+            object: String::new(),
+            detail: detail.to_string(),
+        });
+    }
+
+    for row in rows(1) {
+        findings.push(Finding {
+            severity: Severity::Medium,
+            check: "unindexed-foreign-key",
+            schema: value_text(row.first()),
+            table: value_text(row.get(1)),
+            object: value_text(row.get(2)),
+            detail: format!("no index leads with ({})", value_text(row.get(3))),
+        });
+    }
+
+    for row in rows(2) {
+        let kind = value_text(row.get(3));
+        let (severity, check, detail) = if value_flag(row.get(4)) {
+            (
+                Severity::High,
+                "disabled-constraint",
+                format!("{} constraint is disabled", kind),
+            )
+        } else {
+            (
+                Severity::Medium,
+                "untrusted-constraint",
+                format!(
+                    "{} constraint is not trusted; re-enable WITH CHECK to validate existing rows",
+                    kind
+                ),
+            )
+        };
+        findings.push(Finding {
+            severity,
+            check,
+            schema: value_text(row.first()),
+            table: value_text(row.get(1)),
+            object: value_text(row.get(2)),
+            detail,
+        });
+    }
+
+    for row in rows(3) {
+        findings.push(Finding {
+            severity: Severity::Low,
+            check: "missing-foreign-key",
+            schema: value_text(row.first()),
+            table: value_text(row.get(1)),
+            object: value_text(row.get(2)),
+            detail: "named like a key but no foreign key references another table".to_string(),
+        });
+    }
+    findings
+}
+
+fn findings_to_result_set(findings: &[Finding]) -> ResultSet {
+    let columns = ["severity", "check", "table", "object", "detail"]
+        .iter()
+        .map(|name| Column {
+            name: name.to_string(),
+            data_type: None,
+        })
+        .collect();
+    let rows = findings
+        .iter()
+        .map(|f| {
+            vec![
+                Value::Text(f.severity.as_str().to_string()),
+                Value::Text(f.check.to_string()),
+                Value::Text(format!("{}.{}", f.schema, f.table)),
+                Value::Text(f.object.clone()),
+                Value::Text(f.detail.clone()),
+            ]
+        })
+        .collect();
+    ResultSet { columns, rows }
+}
+
+fn value_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::Text(v)) => v.clone(),
+        Some(Value::Int(v)) => v.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn value_flag(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(v)) => *v,
+        Some(Value::Int(v)) => *v != 0,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rs(rows: Vec<Vec<&str>>) -> ResultSet {
+        ResultSet {
+            columns: Vec::new(),
+            rows: rows
+                .into_iter()
+                .map(|row| row.into_iter().map(|v| Value::Text(v.into())).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn findings_carry_severity_per_check() {
+        let mut constraints = rs(vec![
+            vec!["dbo", "Orders", "FK_Orders_Customers", "FOREIGN KEY"],
+            vec!["dbo", "Orders", "CK_Orders_Total", "CHECK"],
+        ]);
+        constraints.rows[0].push(Value::Bool(true));
+        constraints.rows[1].push(Value::Bool(false));
+        let mut heaps = rs(vec![vec!["dbo", "Staging"]]);
+        heaps.rows[0].push(Value::Int(1));
+        let sets = vec![
+            heaps,
+            rs(vec![vec!["dbo", "Lines", "FK_Lines_Orders", "OrderId"]]),
+            constraints,
+            rs(vec![vec!["dbo", "Lines", "product_id"]]),
+        ];
+
+        let findings = collect_findings(&sets);
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|f| (f.severity, f.check, f.object.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Severity::High, "missing-primary-key", ""),
+                (Severity::Medium, "unindexed-foreign-key", "FK_Lines_Orders"),
+                (Severity::High, "disabled-constraint", "FK_Orders_Customers"),
+                (Severity::Medium, "untrusted-constraint", "CK_Orders_Total"),
+                (Severity::Low, "missing-foreign-key", "product_id"),
+            ]
+        );
+        assert_eq!(findings[0].detail, "no primary key (heap)");
+        assert!(
+            audit_sql(2)
+                .iter()
+                .all(|sql| sql.contains("AND s.name IN (@P2, @P3)"))
+        );
+        assert!(audit_sql(0).iter().all(|sql| !sql.contains("s.name IN")));
+    }
+}
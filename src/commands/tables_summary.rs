@@ -0,0 +1,337 @@
+//! `tables --summary`: schema overview report.
+//!
+//! Totals per schema (tables, views, heaps, tables without a primary key, rows, reserved size),
+//! then the largest and widest tables and the biggest heaps and tables without a primary key.
+//! Like the orphaned audit, each section is its own query so an empty section keeps its place.
+
+use anyhow::Result;
+use serde_json::json;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, TablesArgs};
+use crate::commands::common;
+use crate::config::OutputFormat;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::types::{ResultSet, Value};
+use crate::output::{TableOptions, json as json_out, table};
+
+/// Rows in each ranked section.
+const TOP: usize = 10;
+
+/// Report sections after the per-schema totals: JSON key and text heading.
+const SECTIONS: [(&str, &str); 4] = [
+    ("largest", "Largest Tables"),
+    ("widest", "Widest Tables"),
+    ("heaps", "Largest Heaps"),
+    ("withoutPrimaryKey", "Largest Tables Without a Primary Key"),
+];
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Totals {
+    schemas: u64,
+    tables: i64,
+    views: i64,
+    heaps: i64,
+    without_primary_key: i64,
+    rows: i64,
+    size_mb: f64,
+}
+
+pub fn run(args: &CliArgs, cmd: &TablesArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+
+    let schemas = match &cmd.schema {
+        Some(schema) => vec![schema.clone()],
+        None => resolved.connection.default_schemas.clone(),
+    };
+    let queries = summary_sql(schemas.len());
+
+    let result_sets = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let mut result_sets = Vec::new();
+        for sql in queries {
+            let mut query = Query::new(sql);
+            query.bind(cmd.like.clone());
+            for schema in &schemas {
+                query.bind(schema.clone());
+            }
+            let sets = executor::run_query(query, &mut client).await?;
+            result_sets.push(sets.into_iter().next().unwrap_or_default());
+        }
+        Ok::<_, anyhow::Error>(result_sets)
+    })?;
+    let (by_schema, ranked) = result_sets.split_first().expect("summary queries");
+    let totals = totals(by_schema);
+
+    if matches!(format, OutputFormat::Json) {
+        let mut payload = json!({
+            "schemas": schemas,
+            "like": cmd.like,
+            "totals": {
+                "schemas": totals.schemas,
+                "tables": totals.tables,
+                "views": totals.views,
+                "heaps": totals.heaps,
+                "withoutPrimaryKey": totals.without_primary_key,
+                "rows": totals.rows,
+                "sizeMb": (totals.size_mb * 100.0).round() / 100.0,
+            },
+            "bySchema": json_out::result_set_rows_to_objects(by_schema),
+        });
+        for ((key, _), rs) in SECTIONS.iter().zip(ranked) {
+            payload[*key] = json!(json_out::result_set_rows_to_objects(rs));
+        }
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    if args.quiet {
+        return Ok(());
+    }
+    if by_schema.rows.is_empty() {
+        println!("No tables found.");
+        return Ok(());
+    }
+    let options = TableOptions::default();
+    println!(
+        "{}",
+        table::render_key_value_table("Summary", &totals_rows(&totals), format, &options).output
+    );
+    println!("\nBy Schema");
+    println!(
+        "{}",
+        table::render_result_set_table(by_schema, format, &options).output
+    );
+    for ((_, title), rs) in SECTIONS.iter().zip(ranked) {
+        if rs.rows.is_empty() {
+            continue;
+        }
+        println!("\n{}", title);
+        println!(
+            "{}",
+            table::render_result_set_table(rs, format, &options).output
+        );
+    }
+    Ok(())
+}
+
+/// The per-schema query followed by one query per entry in `SECTIONS`; `@P1` is the optional
+/// LIKE pattern on the table name and `@P2..` the schemas.
+fn summary_sql(schema_count: usize) -> [String; 5] {
+    let schema_filter = if schema_count == 0 {
+        String::new()
+    } else {
+        let placeholders = (0..schema_count)
+            .map(|i| format!("@P{}", i + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("AND s.name IN ({})", placeholders)
+    };
+    let scope = format!(
+        "t.is_ms_shipped = 0 AND (@P1 IS NULL OR t.name LIKE @P1) {}",
+        schema_filter
+    );
+    // Rows and reserved size per table, heap or clustered index only for the row count.
+    let sized = format!(
+        r#"
+WITH sized AS (
+    SELECT s.name AS schema_name, t.name AS table_name,
+           SUM(CASE WHEN ps.index_id IN (0, 1) THEN ps.row_count ELSE 0 END) AS row_count,
+           SUM(ps.reserved_page_count) * 8 / 1024.0 AS size_mb,
+           CASE WHEN EXISTS (SELECT 1 FROM sys.indexes i
+                             WHERE i.object_id = t.object_id AND i.index_id = 0)
+                THEN 1 ELSE 0 END AS is_heap,
+           CASE WHEN EXISTS (SELECT 1 FROM sys.indexes i
+                             WHERE i.object_id = t.object_id AND i.is_primary_key = 1)
+                THEN 1 ELSE 0 END AS has_pk
+    FROM sys.tables t
+    JOIN sys.schemas s ON s.schema_id = t.schema_id
+    LEFT JOIN sys.dm_db_partition_stats ps ON ps.object_id = t.object_id
+    WHERE {scope}
+    GROUP BY s.name, t.name, t.object_id
+)"#
+    );
+
+    let by_schema = format!(
+        r#"{sized}
+SELECT z.schema_name AS [schema],
+       COUNT(*) AS tables,
+       (SELECT COUNT(*) FROM sys.views t JOIN sys.schemas s ON s.schema_id = t.schema_id
+        WHERE s.name = z.schema_name AND {scope}) AS views,
+       SUM(z.is_heap) AS heaps,
+       SUM(1 - z.has_pk) AS withoutPrimaryKey,
+       SUM(z.row_count) AS [rows],
+       CAST(SUM(z.size_mb) AS decimal(18, 2)) AS sizeMb
+FROM sized z
+GROUP BY z.schema_name
+ORDER BY SUM(z.size_mb) DESC, z.schema_name;
+"#
+    );
+    let largest = format!(
+        r#"{sized}
+SELECT TOP ({TOP}) z.schema_name AS [schema], z.table_name AS [table], z.row_count AS [rows],
+       CAST(z.size_mb AS decimal(18, 2)) AS sizeMb
+FROM sized z
+ORDER BY z.size_mb DESC, z.row_count DESC, z.schema_name, z.table_name;
+"#
+    );
+    // Fixed-width bytes a row can take; (max) columns are counted separately.
+    let widest = format!(
+        r#"
+SELECT TOP ({TOP}) s.name AS [schema], t.name AS [table],
+       COUNT(*) AS columns,
+       SUM(CASE WHEN c.max_length = -1 THEN 0 ELSE c.max_length END) AS maxRowBytes,
+       SUM(CASE WHEN c.max_length = -1 THEN 1 ELSE 0 END) AS maxColumns
+FROM sys.tables t
+JOIN sys.schemas s ON s.schema_id = t.schema_id
+JOIN sys.columns c ON c.object_id = t.object_id
+WHERE {scope}
+GROUP BY s.name, t.name
+ORDER BY COUNT(*) DESC, SUM(CASE WHEN c.max_length = -1 THEN 0 ELSE c.max_length END) DESC,
+         s.name, t.name;
+"#
+    );
+    let heaps = format!(
+        r#"{sized}
+SELECT TOP ({TOP}) z.schema_name AS [schema], z.table_name AS [table], z.row_count AS [rows],
+       CAST(z.size_mb AS decimal(18, 2)) AS sizeMb
+FROM sized z
+WHERE z.is_heap = 1
+ORDER BY z.row_count DESC, z.schema_name, z.table_name;
+"#
+    );
+    let without_primary_key = format!(
+        r#"{sized}
+SELECT TOP ({TOP}) z.schema_name AS [schema], z.table_name AS [table], z.row_count AS [rows],
+       z.is_heap AS isHeap
+FROM sized z
+WHERE z.has_pk = 0
+ORDER BY z.row_count DESC, z.schema_name, z.table_name;
+"#
+    );
+
+    [by_schema, largest, widest, heaps, without_primary_key]
+}
+
+/// Sum the per-schema rows: `schema, tables, views, heaps, withoutPrimaryKey, rows, sizeMb`.
+fn totals(by_schema: &ResultSet) -> Totals {
+    let int = |value: Option<&Value>| match value {
+        Some(Value::Int(v)) => *v,
+        Some(Value::Float(v)) => *v as i64,
+        Some(Value::Text(s)) => s.parse().unwrap_or(0),
+        _ => 0,
+    };
+    let float = |value: Option<&Value>| match value {
+        Some(Value::Int(v)) => *v as f64,
+        Some(Value::Float(v)) => *v,
+        Some(Value::Decimal(v)) => v.to_f64(),
+        Some(Value::Text(s)) => s.parse().unwrap_or(0.0),
+        _ => 0.0,
+    };
+    by_schema
+        .rows
+        .iter()
+        .fold(Totals::default(), |mut totals, row| {
+            totals.schemas += 1;
+            totals.tables += int(row.get(1));
+            totals.views += int(row.get(2));
+            totals.heaps += int(row.get(3));
+            totals.without_primary_key += int(row.get(4));
+            totals.rows += int(row.get(5));
+            totals.size_mb += float(row.get(6));
+            totals
+        })
+}
+
+fn totals_rows(totals: &Totals) -> Vec<(String, String)> {
+    vec![
+        ("Schemas".to_string(), totals.schemas.to_string()),
+        ("Tables".to_string(), totals.tables.to_string()),
+        ("Views".to_string(), totals.views.to_string()),
+        ("Heaps".to_string(), totals.heaps.to_string()),
+        (
+            "Without primary key".to_string(),
+            totals.without_primary_key.to_string(),
+        ),
+        ("Rows".to_string(), totals.rows.to_string()),
+        ("Size (MB)".to_string(), format!("{:.2}", totals.size_mb)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn totals_sum_every_schema() {
+        let columns = [
+            "schema",
+            "tables",
+            "views",
+            "heaps",
+            "withoutPrimaryKey",
+            "rows",
+            "sizeMb",
+        ];
+        let by_schema = ResultSet {
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: None,
+                })
+                .collect(),
+            rows: vec![
+                vec![
+                    Value::Text("dbo".to_string()),
+                    Value::Int(12),
+                    Value::Int(3),
+                    Value::Int(2),
+                    Value::Int(1),
+                    Value::Int(1_000),
+                    Value::Text("10.50".to_string()),
+                ],
+                vec![
+                    Value::Text("sales".to_string()),
+                    Value::Int(4),
+                    Value::Int(0),
+                    Value::Int(0),
+                    Value::Int(2),
+                    Value::Int(250),
+                    Value::Float(1.25),
+                ],
+            ],
+        };
+        let totals = totals(&by_schema);
+        assert_eq!(
+            totals,
+            Totals {
+                schemas: 2,
+                tables: 16,
+                views: 3,
+                heaps: 2,
+                without_primary_key: 3,
+                rows: 1_250,
+                size_mb: 11.75,
+            }
+        );
+        assert_eq!(totals_rows(&totals)[6].1, "11.75");
+    }
+
+    #[test]
+    fn summary_queries_filter_by_schema() {
+        let queries = summary_sql(2);
+        assert!(
+            queries
+                .iter()
+                .all(|sql| sql.contains("s.name IN (@P2, @P3)"))
+        );
+        assert!(summary_sql(0).iter().all(|sql| !sql.contains("@P2")));
+    }
+}
@@ -0,0 +1,731 @@
+//! `trace start|tail|stop`: lightweight Extended Events tracing.
+//!
+//! `start` creates an event session with a `ring_buffer` target, so nothing is written to disk
+//! and memory is capped at `RING_BUFFER_KB`. `tail` polls the buffer's XML and prints events it
+//! has not printed before; `stop` stops and drops the session. Azure SQL Database only allows
+//! database-scoped sessions, so the scope follows the engine edition.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use regex::Regex;
+use serde_json::{Map, Value as JsonValue, json};
+use tiberius::Query;
+
+use crate::cli::{CliArgs, TraceArgs, TraceCommand, TraceStartArgs, TraceStopArgs, TraceTailArgs};
+use crate::commands::{common, confirm};
+use crate::config::{OutputFormat, ResolvedConfig};
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::{Session, SqlClient};
+use crate::db::types::Value;
+use crate::error::{AppError, ErrorKind};
+use crate::output::json as json_out;
+
+const RING_BUFFER_KB: u32 = 4096;
+/// Actions attached to every event so matches can be attributed.
+const ACTIONS: &[&str] = &[
+    "sqlserver.database_name",
+    "sqlserver.username",
+    "sqlserver.client_app_name",
+    "sqlserver.client_hostname",
+    "sqlserver.session_id",
+];
+/// Event fields that carry binary payloads rather than anything readable.
+const SKIPPED_FIELDS: &[&str] = &["data_stream", "output_parameters"];
+const TEXT_WIDTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Server,
+    /// Azure SQL Database (engine edition 5).
+    Database,
+}
+
+impl Scope {
+    fn keyword(self) -> &'static str {
+        match self {
+            Scope::Server => "SERVER",
+            Scope::Database => "DATABASE",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Server => "server",
+            Scope::Database => "database",
+        }
+    }
+
+    fn defined_sessions(self) -> &'static str {
+        match self {
+            Scope::Server => "sys.server_event_sessions",
+            Scope::Database => "sys.database_event_sessions",
+        }
+    }
+
+    fn running_sessions(self) -> &'static str {
+        match self {
+            Scope::Server => "sys.dm_xe_sessions",
+            Scope::Database => "sys.dm_xe_database_sessions",
+        }
+    }
+
+    fn session_targets(self) -> &'static str {
+        match self {
+            Scope::Server => "sys.dm_xe_session_targets",
+            Scope::Database => "sys.dm_xe_database_session_targets",
+        }
+    }
+}
+
+pub fn run(args: &CliArgs, cmd: &TraceArgs) -> Result<()> {
+    let resolved = common::load_config(args)?;
+    let format = common::output_format(args, &resolved);
+    match &cmd.command {
+        TraceCommand::Start(start) => run_start(args, &resolved, format, start),
+        TraceCommand::Tail(tail) => run_tail(args, &resolved, format, tail),
+        TraceCommand::Stop(stop) => run_stop(args, &resolved, format, stop),
+    }
+}
+
+fn run_start(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    cmd: &TraceStartArgs,
+) -> Result<()> {
+    let events = cmd
+        .events
+        .iter()
+        .map(|event| event_name(event))
+        .collect::<Result<Vec<_>>>()?;
+    let predicate = cmd.filter.as_deref().map(parse_filter).transpose()?;
+    allow_session_change(
+        args,
+        resolved,
+        "trace start",
+        &format!("create and start event session {}", bracket(&cmd.name)),
+    )?;
+
+    let scope = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let scope = detect_scope(&mut client).await?;
+        if session_defined(&mut client, scope, &cmd.name).await? {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!(
+                    "Event session '{}' already exists. Stop it with `sscli trace stop --name {}` or pick another --name.",
+                    cmd.name, cmd.name
+                ),
+            )
+            .into());
+        }
+        let sql = create_session_sql(&cmd.name, scope, &events, predicate.as_deref());
+        executor::run_query(Query::new(sql), &mut client).await?;
+        Ok::<_, anyhow::Error>(scope)
+    })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "session": cmd.name,
+            "scope": scope.as_str(),
+            "events": events,
+            "predicate": predicate,
+            "started": true,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if !args.quiet {
+        println!(
+            "Started trace session '{}' ({} events: {}{})",
+            cmd.name,
+            scope.as_str(),
+            events.join(", "),
+            predicate
+                .as_deref()
+                .map(|p| format!("; where {}", p))
+                .unwrap_or_default()
+        );
+        println!("Tail: sscli trace tail --name {}", cmd.name);
+        println!("Stop: sscli trace stop --name {}", cmd.name);
+    }
+    Ok(())
+}
+
+fn run_tail(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    cmd: &TraceTailArgs,
+) -> Result<()> {
+    let interval = Duration::from_millis(cmd.interval_ms);
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let mut session = Session::connect(&resolved.connection).await?;
+        let scope = detect_scope(session.client_mut()).await?;
+        let sql = format!(
+            "SELECT CAST(t.target_data AS nvarchar(max)) AS target_data\n\
+             FROM {} s\n\
+             JOIN {} t ON t.event_session_address = s.address\n\
+             WHERE s.name = @P1 AND t.target_name = 'ring_buffer';",
+            scope.running_sessions(),
+            scope.session_targets()
+        );
+        let mut cursor = TailCursor::default();
+        loop {
+            for warning in session.take_warnings() {
+                eprintln!("Warning: {}", warning);
+            }
+            let sets = session
+                .query_read(|| {
+                    let mut query = Query::new(sql.clone());
+                    query.bind(cmd.name.as_str());
+                    query
+                })
+                .await?;
+            let Some(xml) = sets
+                .into_iter()
+                .next()
+                .and_then(|rs| rs.rows.into_iter().next())
+                .and_then(|row| row.into_iter().next())
+            else {
+                return Err(AppError::new(
+                    ErrorKind::Query,
+                    format!(
+                        "Trace session '{}' is not running. Start it with `sscli trace start`.",
+                        cmd.name
+                    ),
+                )
+                .into());
+            };
+            let xml = match xml {
+                Value::Text(text) => text,
+                _ => String::new(),
+            };
+            let fresh = cursor.advance(parse_events(&xml)?);
+            emit_events(&fresh, args, format);
+            if cmd.once {
+                return Ok(());
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+fn run_stop(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    format: OutputFormat,
+    cmd: &TraceStopArgs,
+) -> Result<()> {
+    allow_session_change(
+        args,
+        resolved,
+        "trace stop",
+        &format!("stop and drop event session {}", bracket(&cmd.name)),
+    )?;
+    let scope = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut client = client::connect(&resolved.connection).await?;
+        let scope = detect_scope(&mut client).await?;
+        if !session_defined(&mut client, scope, &cmd.name).await? {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!("No event session named '{}'.", cmd.name),
+            )
+            .into());
+        }
+        let name = bracket(&cmd.name);
+        let sql = format!(
+            "IF EXISTS (SELECT 1 FROM {running} WHERE name = @P1)\n    ALTER EVENT SESSION {name} ON {scope} STATE = STOP;\nDROP EVENT SESSION {name} ON {scope};",
+            running = scope.running_sessions(),
+            name = name,
+            scope = scope.keyword()
+        );
+        let mut query = Query::new(sql);
+        query.bind(cmd.name.as_str());
+        executor::run_query(query, &mut client).await?;
+        Ok::<_, anyhow::Error>(scope)
+    })?;
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = json!({
+            "session": cmd.name,
+            "scope": scope.as_str(),
+            "stopped": true,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(resolved))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+    if !args.quiet {
+        println!("Stopped and dropped trace session '{}'", cmd.name);
+    }
+    Ok(())
+}
+
+/// Gate creating or dropping an event session: both change server state, so they need
+/// `--allow-write` and, on production profiles, a confirmation.
+fn allow_session_change(
+    args: &CliArgs,
+    resolved: &ResolvedConfig,
+    command: &str,
+    action: &str,
+) -> Result<()> {
+    common::require_allow_write(
+        args,
+        &format!("{} changes event sessions on the server", command),
+    )?;
+    confirm::confirm_write(args, resolved, action)
+}
+
+async fn detect_scope(client: &mut SqlClient) -> Result<Scope> {
+    let sets = executor::run_query(
+        Query::new("SELECT CAST(SERVERPROPERTY('EngineEdition') AS int) AS edition;"),
+        client,
+    )
+    .await?;
+    let edition = sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .and_then(|row| row.first());
+    Ok(match edition {
+        Some(Value::Int(5)) => Scope::Database,
+        _ => Scope::Server,
+    })
+}
+
+async fn session_defined(client: &mut SqlClient, scope: Scope, name: &str) -> Result<bool> {
+    let mut query = Query::new(format!(
+        "SELECT COUNT(*) AS sessions FROM {} WHERE name = @P1;",
+        scope.defined_sessions()
+    ));
+    query.bind(name);
+    let sets = executor::run_query(query, client).await?;
+    Ok(matches!(
+        sets.first()
+            .and_then(|rs| rs.rows.first())
+            .and_then(|row| row.first()),
+        Some(Value::Int(n)) if *n > 0
+    ))
+}
+
+/// `rpc_completed` becomes `sqlserver.rpc_completed`; `package.event` is kept as given.
+fn event_name(raw: &str) -> Result<String> {
+    let name = raw.trim().to_ascii_lowercase();
+    let valid = Regex::new(r"^[a-z_][a-z0-9_]*(\.[a-z_][a-z0-9_]*)?$").expect("valid regex");
+    if !valid.is_match(&name) {
+        return Err(AppError::new(
+            ErrorKind::Query,
+            format!("Invalid event name '{}'", raw.trim()),
+        )
+        .into());
+    }
+    Ok(if name.contains('.') {
+        name
+    } else {
+        format!("sqlserver.{}", name)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    /// Microseconds; values take `us`, `ms`, `s` or `m` suffixes, bare numbers are milliseconds.
+    Micros,
+    Number,
+    Text,
+}
+
+fn filter_field(name: &str) -> Option<(&'static str, FieldKind)> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "duration" => ("[duration]", FieldKind::Micros),
+        "cpu_time" | "cpu" => ("[cpu_time]", FieldKind::Micros),
+        "logical_reads" | "reads" => ("[logical_reads]", FieldKind::Number),
+        "physical_reads" => ("[physical_reads]", FieldKind::Number),
+        "writes" => ("[writes]", FieldKind::Number),
+        "row_count" | "rows" => ("[row_count]", FieldKind::Number),
+        "database" | "database_name" => ("[sqlserver].[database_name]", FieldKind::Text),
+        "username" | "user" => ("[sqlserver].[username]", FieldKind::Text),
+        "client_app" | "client_app_name" | "app" => {
+            ("[sqlserver].[client_app_name]", FieldKind::Text)
+        }
+        "client_host" | "client_hostname" | "host" => {
+            ("[sqlserver].[client_hostname]", FieldKind::Text)
+        }
+        "session_id" => ("[sqlserver].[session_id]", FieldKind::Number),
+        _ => return None,
+    })
+}
+
+/// Turn `duration > 500ms and database = Sales` into an XE predicate.
+fn parse_filter(raw: &str) -> Result<String> {
+    let invalid =
+        |detail: String| AppError::new(ErrorKind::Query, format!("Invalid --filter: {}", detail));
+    let splitter = Regex::new(r"(?i)\s+and\s+").expect("valid regex");
+    let clause =
+        Regex::new(r"^\s*([A-Za-z_]+)\s*(>=|<=|<>|!=|=|>|<)\s*(.+?)\s*$").expect("valid regex");
+    let mut terms = Vec::new();
+    for part in splitter.split(raw.trim()) {
+        let caps = clause
+            .captures(part)
+            .ok_or_else(|| invalid(format!("expected `field op value`, got '{}'", part.trim())))?;
+        let (column, kind) = filter_field(&caps[1])
+            .ok_or_else(|| invalid(format!("unknown field '{}'", &caps[1])))?;
+        let op = match &caps[2] {
+            "!=" => "<>",
+            other => other,
+        };
+        let value = caps[3].to_string();
+        let operand = match kind {
+            FieldKind::Micros => format!(
+                "({})",
+                parse_micros(&value).ok_or_else(|| {
+                    invalid(format!("'{}' is not a duration (e.g. 500ms, 2s)", value))
+                })?
+            ),
+            FieldKind::Number => format!(
+                "({})",
+                value
+                    .parse::<u64>()
+                    .map_err(|_| { invalid(format!("'{}' is not a whole number", value)) })?
+            ),
+            FieldKind::Text => {
+                if op != "=" && op != "<>" {
+                    return Err(invalid(format!("{} only supports = and <>", &caps[1])).into());
+                }
+                let text = value
+                    .strip_prefix('\'')
+                    .and_then(|v| v.strip_suffix('\''))
+                    .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+                    .unwrap_or(&value);
+                format!("N'{}'", text.replace('\'', "''"))
+            }
+        };
+        terms.push(format!("{} {} {}", column, op, operand));
+    }
+    Ok(format!("({})", terms.join(" AND ")))
+}
+
+fn parse_micros(raw: &str) -> Option<u64> {
+    let value = raw.trim().to_ascii_lowercase();
+    let (digits, factor) = if let Some(v) = value
+        .strip_suffix("us")
+        .or_else(|| value.strip_suffix("µs"))
+    {
+        (v, 1)
+    } else if let Some(v) = value.strip_suffix("ms") {
+        (v, 1_000)
+    } else if let Some(v) = value.strip_suffix('s') {
+        (v, 1_000_000)
+    } else if let Some(v) = value.strip_suffix('m') {
+        (v, 60_000_000)
+    } else {
+        (value.as_str(), 1_000)
+    };
+    digits.trim().parse::<u64>().ok()?.checked_mul(factor)
+}
+
+fn create_session_sql(
+    name: &str,
+    scope: Scope,
+    events: &[String],
+    predicate: Option<&str>,
+) -> String {
+    let name = bracket(name);
+    let actions = ACTIONS.join(", ");
+    let events = events
+        .iter()
+        .map(|event| {
+            format!(
+                "ADD EVENT {} (\n    ACTION ({}){})",
+                event,
+                actions,
+                predicate
+                    .map(|p| format!("\n    WHERE {}", p))
+                    .unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!(
+        "CREATE EVENT SESSION {name} ON {scope}\n{events}\n\
+         ADD TARGET package0.ring_buffer (SET max_memory = {kb})\n\
+         WITH (MAX_DISPATCH_LATENCY = 1 SECONDS, EVENT_RETENTION_MODE = ALLOW_SINGLE_EVENT_LOSS, STARTUP_STATE = OFF);\n\
+         ALTER EVENT SESSION {name} ON {scope} STATE = START;",
+        name = name,
+        scope = scope.keyword(),
+        events = events,
+        kb = RING_BUFFER_KB
+    )
+}
+
+/// Flatten `<RingBufferTarget><event>` elements into `{event, timestamp, <data>, <actions>}`.
+fn parse_events(xml: &str) -> Result<Vec<Map<String, JsonValue>>> {
+    if xml.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let document = roxmltree::Document::parse(xml)?;
+    let target = document.root_element();
+    if target.tag_name().name() != "RingBufferTarget" {
+        return Ok(Vec::new());
+    }
+    let child_text = |node: roxmltree::Node<'_, '_>, name: &str| {
+        node.children()
+            .find(|child| child.tag_name().name() == name)
+            .map(|child| child.text().unwrap_or_default().to_string())
+    };
+    let events = target
+        .children()
+        .filter(|node| node.tag_name().name() == "event")
+        .map(|event| {
+            let mut out = Map::new();
+            out.insert(
+                "event".to_string(),
+                JsonValue::from(event.attribute("name").unwrap_or_default()),
+            );
+            out.insert(
+                "timestamp".to_string(),
+                JsonValue::from(event.attribute("timestamp").unwrap_or_default()),
+            );
+            for item in event
+                .children()
+                .filter(|node| matches!(node.tag_name().name(), "data" | "action"))
+            {
+                let Some(name) = item.attribute("name") else {
+                    continue;
+                };
+                if SKIPPED_FIELDS.contains(&name) {
+                    continue;
+                }
+                // Map fields carry the readable label in <text>; plain fields only have <value>.
+                let value = child_text(item, "text")
+                    .filter(|text| !text.is_empty())
+                    .or_else(|| child_text(item, "value"))
+                    .unwrap_or_default();
+                if value.is_empty() {
+                    continue;
+                }
+                let value = value
+                    .parse::<i64>()
+                    .map(JsonValue::from)
+                    .unwrap_or(JsonValue::from(value));
+                out.insert(name.to_string(), value);
+            }
+            out
+        })
+        .collect();
+    Ok(events)
+}
+
+/// Events already printed; the ring buffer returns everything it still holds on each poll.
+#[derive(Debug, Default)]
+struct TailCursor {
+    last_timestamp: String,
+    /// Events printed with exactly `last_timestamp`, to tell them from new ones in the same tick.
+    at_last: HashSet<String>,
+}
+
+impl TailCursor {
+    fn advance(&mut self, events: Vec<Map<String, JsonValue>>) -> Vec<Map<String, JsonValue>> {
+        let mut fresh = Vec::new();
+        for event in events {
+            let timestamp = event
+                .get("timestamp")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let key = JsonValue::Object(event.clone()).to_string();
+            if timestamp < self.last_timestamp
+                || (timestamp == self.last_timestamp && self.at_last.contains(&key))
+            {
+                continue;
+            }
+            if timestamp > self.last_timestamp {
+                self.last_timestamp = timestamp;
+                self.at_last.clear();
+            }
+            self.at_last.insert(key);
+            fresh.push(event);
+        }
+        fresh
+    }
+}
+
+/// NDJSON for `--json`, otherwise one line per event.
+fn emit_events(events: &[Map<String, JsonValue>], args: &CliArgs, format: OutputFormat) {
+    if events.is_empty() || args.quiet {
+        return;
+    }
+    let mut stdout = std::io::stdout().lock();
+    for event in events {
+        if matches!(format, OutputFormat::Json) {
+            let _ = writeln!(stdout, "{}", JsonValue::Object(event.clone()));
+        } else {
+            let _ = writeln!(stdout, "{}", event_line(event));
+        }
+    }
+    let _ = stdout.flush();
+}
+
+fn event_line(event: &Map<String, JsonValue>) -> String {
+    let text = |key: &str| -> Option<String> {
+        event.get(key).map(|value| match value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    };
+    let millis = |key: &str| {
+        event
+            .get(key)
+            .and_then(JsonValue::as_i64)
+            .map(|us| format!("{:.1}ms", us as f64 / 1000.0))
+    };
+    let mut parts = vec![
+        text("timestamp").unwrap_or_default(),
+        text("event").unwrap_or_default(),
+    ];
+    if let Some(duration) = millis("duration") {
+        parts.push(duration);
+    }
+    if let Some(cpu) = millis("cpu_time") {
+        parts.push(format!("cpu {}", cpu));
+    }
+    if let Some(reads) = text("logical_reads") {
+        parts.push(format!("reads {}", reads));
+    }
+    if let Some(database) = text("database_name") {
+        parts.push(format!("[{}]", database));
+    }
+    if let Some(app) = text("client_app_name") {
+        parts.push(app);
+    }
+    let sql = text("statement")
+        .or_else(|| text("batch_text"))
+        .or_else(|| text("message"))
+        .map(|sql| sql.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(|sql| {
+            if sql.chars().count() > TEXT_WIDTH {
+                format!("{}...", sql.chars().take(TEXT_WIDTH).collect::<String>())
+            } else {
+                sql
+            }
+        });
+    match sql {
+        Some(sql) => format!("{}: {}", parts.join(" "), sql),
+        None => parts.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConnectionSettings, ProfilePolicy, SettingsResolved};
+
+    fn profile(read_only: bool) -> ResolvedConfig {
+        ResolvedConfig {
+            config_path: None,
+            profile_name: "prod".to_string(),
+            connection: ConnectionSettings::default(),
+            settings: SettingsResolved::default(),
+            policy: ProfilePolicy {
+                read_only,
+                ..ProfilePolicy::default()
+            },
+        }
+    }
+
+    #[test]
+    fn session_changes_need_allow_write_and_a_writable_profile() {
+        let action = "create and start event session [sscli_trace]";
+        let without = crate::cli::parse_from(["sscli", "trace", "start"]);
+        let err = allow_session_change(&without, &profile(false), "trace start", action)
+            .unwrap_err()
+            .to_string();
+        assert!(err.ends_with("re-run with --allow-write"), "{err}");
+
+        let with = crate::cli::parse_from(["sscli", "--allow-write", "trace", "stop"]);
+        assert!(allow_session_change(&with, &profile(false), "trace stop", action).is_ok());
+        let err = allow_session_change(&with, &profile(true), "trace stop", action)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Profile 'prod' is read-only"), "{err}");
+    }
+
+    #[test]
+    fn filter_becomes_xe_predicate() {
+        assert_eq!(
+            parse_filter("duration > 500ms AND database = 'Sales' and reads >= 1000").unwrap(),
+            "([duration] > (500000) AND [sqlserver].[database_name] = N'Sales' AND [logical_reads] >= (1000))"
+        );
+        assert_eq!(
+            parse_filter("cpu != 2s and app = O'Brien").unwrap(),
+            "([cpu_time] <> (2000000) AND [sqlserver].[client_app_name] = N'O''Brien')"
+        );
+        assert!(parse_filter("duration > fast").is_err());
+        assert!(parse_filter("color = red").is_err());
+        assert!(parse_filter("database > Sales").is_err());
+        assert_eq!(
+            event_name("RPC_Completed").unwrap(),
+            "sqlserver.rpc_completed"
+        );
+        assert!(event_name("x; DROP").is_err());
+    }
+
+    #[test]
+    fn session_sql_adds_actions_predicate_and_ring_buffer() {
+        let sql = create_session_sql(
+            "slow",
+            Scope::Database,
+            &["sqlserver.rpc_completed".to_string()],
+            Some("([duration] > (1000))"),
+        );
+        assert!(sql.starts_with(
+            "CREATE EVENT SESSION [slow] ON DATABASE\nADD EVENT sqlserver.rpc_completed ("
+        ));
+        assert!(sql.contains("WHERE ([duration] > (1000))"));
+        assert!(sql.contains("package0.ring_buffer (SET max_memory = 4096)"));
+        assert!(sql.ends_with("ALTER EVENT SESSION [slow] ON DATABASE STATE = START;"));
+    }
+
+    #[test]
+    fn ring_buffer_events_are_flattened_and_printed_once() {
+        let xml = r#"<RingBufferTarget truncated="0" eventCount="2">
+<event name="rpc_completed" package="sqlserver" timestamp="2024-05-01T12:30:00.123Z">
+  <data name="duration"><type name="uint64" package="package0"/><value>750000</value></data>
+  <data name="result"><type name="rpc_return_result" package="sqlserver"/><value>0</value><text>OK</text></data>
+  <data name="data_stream"><value>0x00</value></data>
+  <data name="statement"><value>exec dbo.GetOrders @Status = 5</value></data>
+  <action name="database_name" package="sqlserver"><value>Sales</value></action>
+</event>
+<event name="sql_batch_completed" package="sqlserver" timestamp="2024-05-01T12:30:01.000Z">
+  <data name="batch_text"><value>SELECT 1 &lt; 2</value></data>
+</event>
+</RingBufferTarget>"#;
+        let events = parse_events(xml).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["duration"], json!(750000));
+        assert_eq!(events[0]["result"], json!("OK"));
+        assert_eq!(events[0]["database_name"], json!("Sales"));
+        assert!(!events[0].contains_key("data_stream"));
+        assert_eq!(
+            event_line(&events[0]),
+            "2024-05-01T12:30:00.123Z rpc_completed 750.0ms [Sales]: exec dbo.GetOrders @Status = 5"
+        );
+
+        let mut cursor = TailCursor::default();
+        assert_eq!(cursor.advance(events.clone()).len(), 2);
+        assert!(cursor.advance(events.clone()).is_empty());
+        let mut later = events[1].clone();
+        later.insert("batch_text".to_string(), json!("SELECT 2"));
+        assert_eq!(cursor.advance(vec![events[1].clone(), later]).len(), 1);
+        assert!(parse_events("").unwrap().is_empty());
+    }
+}
@@ -19,7 +19,7 @@ pub(crate) fn maybe_emit(args: &CliArgs) {
         return;
     }
 
-    if matches!(args.command, CommandKind::Update(_)) {
+    if matches!(args.command, CommandKind::Update(_)) || args.command.is_fast_path() {
         return;
     }
 
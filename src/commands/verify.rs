@@ -0,0 +1,486 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use serde::Serialize;
+use tiberius::Query;
+
+use crate::cli::{CliArgs, VerifyArgs};
+use crate::commands::{common, compare};
+use crate::config::{ConnectionSettings, OutputFormat};
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{Column, ResultSet, Value};
+use crate::db::{client, executor};
+use crate::error::ExitCode;
+use crate::output::{TableOptions, json as json_out, table};
+
+const TABLES_SQL: &str = r#"
+SELECT s.name AS [schema], t.name AS [table]
+FROM sys.tables t
+INNER JOIN sys.schemas s ON s.schema_id = t.schema_id
+WHERE t.is_ms_shipped = 0
+ORDER BY s.name, t.name;
+"#;
+
+const PRIMARY_KEY_SQL: &str = r#"
+SELECT c.name AS columnName
+FROM sys.indexes i
+INNER JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+INNER JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+WHERE i.is_primary_key = 1 AND i.object_id = OBJECT_ID(@P1)
+ORDER BY ic.key_ordinal;
+"#;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TableStats {
+    rows: Option<i64>,
+    checksum: Option<i64>,
+    error: Option<String>,
+}
+
+/// Per-chunk `(rows, checksum)` keyed by chunk number, or why chunking was skipped.
+type ChunkStats = std::result::Result<BTreeMap<i64, (i64, i64)>, String>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableResult {
+    table: String,
+    status: &'static str,
+    source_rows: Option<i64>,
+    target_rows: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_checksum: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_checksum: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mismatched_chunks: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+/// Execute `verify`: count (and optionally checksum) matching tables on both sides.
+pub fn run(args: &CliArgs, cmd: &VerifyArgs) -> Result<()> {
+    let base_overrides = common::overrides_from_args(args);
+    let source_profile = cmd.source.clone().or_else(|| args.profile.clone());
+    let source_cfg = compare::apply_connection_override(
+        compare::resolve_profile(args, &base_overrides, source_profile.as_deref())?,
+        &cmd.source_connection,
+    )?;
+    let target_cfg = compare::apply_connection_override(
+        compare::resolve_profile(args, &base_overrides, Some(&cmd.target))?,
+        &cmd.target_connection,
+    )?;
+    let format = common::output_format(args, &source_cfg);
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let (source_stats, target_stats) = rt.block_on(async {
+        tokio::try_join!(
+            collect_stats(&source_cfg.connection, &cmd.tables, cmd.checksum),
+            collect_stats(&target_cfg.connection, &cmd.tables, cmd.checksum),
+        )
+    })?;
+
+    let mut results = compare_stats(&source_stats, &target_stats);
+
+    // Chunking is only worth its cost where the whole-table checksum already disagrees.
+    if let Some(chunk_size) = cmd.chunk_size {
+        let suspects: Vec<String> = results
+            .iter()
+            .filter(|r| r.status == "checksum-mismatch")
+            .map(|r| r.table.clone())
+            .collect();
+        if !suspects.is_empty() {
+            let (source_chunks, target_chunks) = rt.block_on(async {
+                tokio::try_join!(
+                    collect_chunks(&source_cfg.connection, &suspects, chunk_size),
+                    collect_chunks(&target_cfg.connection, &suspects, chunk_size),
+                )
+            })?;
+            for result in results.iter_mut() {
+                let (Some(left), Some(right)) = (
+                    source_chunks.get(&result.table),
+                    target_chunks.get(&result.table),
+                ) else {
+                    continue;
+                };
+                match (left, right) {
+                    (Ok(left), Ok(right)) => result.mismatched_chunks = diff_chunks(left, right),
+                    (Err(note), _) | (_, Err(note)) => result.note = Some(note.clone()),
+                }
+            }
+        }
+    }
+
+    let mismatched = results.iter().filter(|r| r.status != "match").count();
+    let status = if mismatched == 0 { "ok" } else { "mismatch" };
+
+    if matches!(format, OutputFormat::Json) {
+        let payload = serde_json::json!({
+            "source": source_cfg.profile_name,
+            "target": target_cfg.profile_name,
+            "checksum": cmd.checksum,
+            "chunkSize": cmd.chunk_size,
+            "status": status,
+            "summary": {
+                "checked": results.len(),
+                "matched": results.len() - mismatched,
+                "mismatched": mismatched,
+            },
+            "tables": results,
+        });
+        let body = json_out::emit_json_value(&payload, common::json_pretty(&source_cfg))?;
+        if !args.quiet {
+            println!("{}", body);
+        }
+    } else if !args.quiet {
+        if results.is_empty() {
+            println!("No tables matched.");
+        } else {
+            let rs = results_to_result_set(&results, cmd.checksum);
+            let rendered = table::render_result_set_table(&rs, format, &TableOptions::default());
+            println!("{}", rendered.output);
+            for result in &results {
+                if !result.mismatched_chunks.is_empty() {
+                    println!(
+                        "{}: chunks {} differ ({} rows per chunk, primary key order)",
+                        result.table,
+                        join_numbers(&result.mismatched_chunks),
+                        cmd.chunk_size.unwrap_or_default()
+                    );
+                }
+                if let Some(note) = &result.note {
+                    eprintln!("Warning: {}: {}", result.table, note);
+                }
+            }
+            println!(
+                "{} of {} tables match ({} -> {}).",
+                results.len() - mismatched,
+                results.len(),
+                source_cfg.profile_name,
+                target_cfg.profile_name
+            );
+        }
+    }
+
+    if mismatched > 0 {
+        ExitCode::CheckFailed.exit();
+    }
+    Ok(())
+}
+
+async fn collect_stats(
+    settings: &ConnectionSettings,
+    patterns: &[String],
+    checksum: bool,
+) -> Result<BTreeMap<String, TableStats>> {
+    let mut client = client::connect(settings).await?;
+    let tables = list_tables(&mut client, patterns).await?;
+
+    let mut stats = BTreeMap::new();
+    for name in tables {
+        let sql = if checksum {
+            format!(
+                "SELECT COUNT_BIG(*) AS [rows], CHECKSUM_AGG(BINARY_CHECKSUM(*)) AS [checksum] FROM {};",
+                name
+            )
+        } else {
+            format!("SELECT COUNT_BIG(*) AS [rows] FROM {};", name)
+        };
+        let entry = match executor::run_query(Query::new(sql), &mut client).await {
+            Ok(sets) => {
+                let row = sets
+                    .first()
+                    .and_then(|rs| rs.rows.first())
+                    .cloned()
+                    .unwrap_or_default();
+                TableStats {
+                    rows: int_value(row.first()),
+                    checksum: int_value(row.get(1)),
+                    error: None,
+                }
+            }
+            Err(err) => TableStats {
+                error: Some(err.to_string()),
+                ..TableStats::default()
+            },
+        };
+        stats.insert(name, entry);
+    }
+    Ok(stats)
+}
+
+async fn list_tables(client: &mut SqlClient, patterns: &[String]) -> Result<Vec<String>> {
+    let rs = executor::run_query(Query::new(TABLES_SQL), client)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    Ok(rs
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let schema = row.first().map(Value::as_display)?;
+            let table = row.get(1).map(Value::as_display)?;
+            table_matches(patterns, &schema, &table)
+                .then(|| format!("{}.{}", bracket(&schema), bracket(&table)))
+        })
+        .collect())
+}
+
+async fn collect_chunks(
+    settings: &ConnectionSettings,
+    tables: &[String],
+    chunk_size: u64,
+) -> Result<BTreeMap<String, ChunkStats>> {
+    let mut client = client::connect(settings).await?;
+    let mut chunks = BTreeMap::new();
+    for name in tables {
+        let mut query = Query::new(PRIMARY_KEY_SQL);
+        query.bind(name.as_str());
+        let key_columns: Vec<String> = executor::run_query(query, &mut client)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+            .rows
+            .iter()
+            .filter_map(|row| row.first().map(Value::as_display))
+            .map(|column| bracket(&column))
+            .collect();
+        if key_columns.is_empty() {
+            chunks.insert(
+                name.clone(),
+                Err("no primary key; chunked checksums skipped".to_string()),
+            );
+            continue;
+        }
+
+        let sql = chunk_sql(name, &key_columns, chunk_size);
+        let entry = match executor::run_query(Query::new(sql), &mut client).await {
+            Ok(sets) => Ok(sets
+                .into_iter()
+                .next()
+                .unwrap_or_default()
+                .rows
+                .iter()
+                .filter_map(|row| {
+                    Some((
+                        int_value(row.first())?,
+                        (
+                            int_value(row.get(1)).unwrap_or_default(),
+                            int_value(row.get(2)).unwrap_or_default(),
+                        ),
+                    ))
+                })
+                .collect()),
+            Err(err) => Err(err.to_string()),
+        };
+        chunks.insert(name.clone(), entry);
+    }
+    Ok(chunks)
+}
+
+fn chunk_sql(table: &str, key_columns: &[String], chunk_size: u64) -> String {
+    format!(
+        "SELECT chunk, COUNT_BIG(*) AS [rows], CHECKSUM_AGG(row_checksum) AS [checksum] \
+         FROM (SELECT (ROW_NUMBER() OVER (ORDER BY {}) - 1) / {} AS chunk, \
+         BINARY_CHECKSUM(*) AS row_checksum FROM {}) AS numbered \
+         GROUP BY chunk ORDER BY chunk;",
+        key_columns.join(", "),
+        chunk_size,
+        table
+    )
+}
+
+fn compare_stats(
+    source: &BTreeMap<String, TableStats>,
+    target: &BTreeMap<String, TableStats>,
+) -> Vec<TableResult> {
+    let names: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let left = source.get(name);
+            let right = target.get(name);
+            let status = match (left, right) {
+                (None, _) => "missing-in-source",
+                (_, None) => "missing-in-target",
+                (Some(l), Some(r)) if l.error.is_some() || r.error.is_some() => "error",
+                (Some(l), Some(r)) if l.rows != r.rows => "row-count-mismatch",
+                (Some(l), Some(r)) if l.checksum != r.checksum => "checksum-mismatch",
+                _ => "match",
+            };
+            let note = left
+                .and_then(|s| s.error.as_ref().map(|e| format!("source: {}", e)))
+                .or_else(|| right.and_then(|s| s.error.as_ref().map(|e| format!("target: {}", e))));
+            TableResult {
+                table: name.clone(),
+                status,
+                source_rows: left.and_then(|s| s.rows),
+                target_rows: right.and_then(|s| s.rows),
+                source_checksum: left.and_then(|s| s.checksum),
+                target_checksum: right.and_then(|s| s.checksum),
+                mismatched_chunks: Vec::new(),
+                note,
+            }
+        })
+        .collect()
+}
+
+/// Chunk numbers whose row count or checksum differ, including chunks only one side has.
+fn diff_chunks(left: &BTreeMap<i64, (i64, i64)>, right: &BTreeMap<i64, (i64, i64)>) -> Vec<i64> {
+    left.keys()
+        .chain(right.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|chunk| left.get(chunk) != right.get(chunk))
+        .copied()
+        .collect()
+}
+
+/// Match `schema.table` against patterns like `dbo.*`, `Orders`, or `sales.Order?`.
+///
+/// A pattern without a dot matches the table name in any schema. No patterns means all tables.
+pub(crate) fn table_matches(patterns: &[String], schema: &str, table: &str) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    patterns.iter().any(|pattern| {
+        let unquote = |part: &str| part.trim().replace(['[', ']'], "");
+        match pattern.split_once('.') {
+            Some((schema_pattern, table_pattern)) => {
+                glob_match(&unquote(schema_pattern), schema)
+                    && glob_match(&unquote(table_pattern), table)
+            }
+            None => glob_match(&unquote(pattern), table),
+        }
+    })
+}
+
+/// Case-insensitive glob supporting `*` and `?`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn results_to_result_set(results: &[TableResult], checksum: bool) -> ResultSet {
+    let mut names = vec!["table", "sourceRows", "targetRows"];
+    if checksum {
+        names.extend(["sourceChecksum", "targetChecksum"]);
+    }
+    names.push("status");
+    let int = |value: Option<i64>| value.map(Value::Int).unwrap_or(Value::Null);
+    ResultSet {
+        columns: names
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: None,
+            })
+            .collect(),
+        rows: results
+            .iter()
+            .map(|r| {
+                let mut row = vec![
+                    Value::Text(r.table.clone()),
+                    int(r.source_rows),
+                    int(r.target_rows),
+                ];
+                if checksum {
+                    row.extend([int(r.source_checksum), int(r.target_checksum)]);
+                }
+                row.push(Value::Text(r.status.to_string()));
+                row
+            })
+            .collect(),
+    }
+}
+
+fn int_value(value: Option<&Value>) -> Option<i64> {
+    match value {
+        Some(Value::Int(v)) => Some(*v),
+        Some(Value::Text(v)) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn join_numbers(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_schema_and_table_globs() {
+        let patterns = vec!["dbo.*".to_string(), "Order?".to_string()];
+        assert!(table_matches(&patterns, "dbo", "Anything"));
+        assert!(table_matches(&patterns, "sales", "orders"));
+        assert!(!table_matches(&patterns, "sales", "OrderLines"));
+        assert!(table_matches(&[], "x", "y"));
+        assert!(glob_match("a*b*c", "aXXbYc"));
+        assert!(!glob_match("a*b", "aXXc"));
+    }
+
+    #[test]
+    fn classifies_table_differences() {
+        let stats = |rows: i64, checksum: i64| TableStats {
+            rows: Some(rows),
+            checksum: Some(checksum),
+            error: None,
+        };
+        let source = BTreeMap::from([
+            ("[dbo].[A]".to_string(), stats(10, 1)),
+            ("[dbo].[B]".to_string(), stats(10, 1)),
+            ("[dbo].[C]".to_string(), stats(10, 1)),
+        ]);
+        let target = BTreeMap::from([
+            ("[dbo].[A]".to_string(), stats(10, 1)),
+            ("[dbo].[B]".to_string(), stats(10, 2)),
+            ("[dbo].[D]".to_string(), stats(3, 1)),
+        ]);
+        let statuses: Vec<_> = compare_stats(&source, &target)
+            .into_iter()
+            .map(|r| r.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                "match",
+                "checksum-mismatch",
+                "missing-in-target",
+                "missing-in-source"
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_chunks_on_either_side() {
+        let left = BTreeMap::from([(0, (100, 5)), (1, (100, 6)), (2, (20, 7))]);
+        let right = BTreeMap::from([(0, (100, 5)), (1, (100, 9))]);
+        assert_eq!(diff_chunks(&left, &right), vec![1, 2]);
+    }
+}
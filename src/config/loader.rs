@@ -5,8 +5,10 @@ use anyhow::{Context, Result, anyhow};
 
 use super::env::{Env, parse_bool};
 use super::schema::{
-    ConfigFile, CsvMultiResultNaming, JsonContractVersion, OutputFormat, OutputSettings, Profile,
-    Settings,
+    BinaryFormat, BooleanStyle, ColorMode, CommaStyle, ConfigFile, CsvMultiResultNaming,
+    DeadlockPriority, DisplayTimeZone, HyperlinkMode, IsolationLevel, JsonContractVersion,
+    JsonLargeIntegers, KeywordCase, MaskStrategy, OutputFormat, OutputSettings, Profile,
+    SessionProfile, Settings, ThemeColor, TlsVersion,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -20,8 +22,23 @@ pub struct CliOverrides {
     pub user: Option<String>,
     pub password: Option<String>,
     pub timeout_ms: Option<u64>,
+    pub isolation_level: Option<IsolationLevel>,
+    pub lock_timeout_ms: Option<i64>,
+    /// `--run-id`: CONTEXT_INFO for this run's connections.
+    pub run_id: Option<String>,
+    /// Subcommand path, for the `{command}` part of the application name.
+    pub command: Option<String>,
     pub encrypt: Option<bool>,
     pub trust_cert: Option<bool>,
+    pub time_zone: Option<DisplayTimeZone>,
+    pub source_time_zone: Option<DisplayTimeZone>,
+    pub null_display: Option<String>,
+    pub booleans: Option<BooleanStyle>,
+    pub max_cell_width: Option<usize>,
+    pub max_output_chars: Option<usize>,
+    pub binary: Option<BinaryFormat>,
+    pub max_value_bytes: Option<usize>,
+    pub contract_version: Option<JsonContractVersion>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +55,49 @@ pub struct ResolvedConfig {
     pub profile_name: String,
     pub connection: ConnectionSettings,
     pub settings: SettingsResolved,
+    pub policy: ProfilePolicy,
+}
+
+/// The active profile's `permissions`, `environment` tag, and `mask` rules; the default allows
+/// everything and masks nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfilePolicy {
+    pub deny: Vec<String>,
+    pub read_only: bool,
+    /// The profile's `environment` tag, as written.
+    pub environment: Option<String>,
+    /// The profile's `mask` rules: `schema.table.column` pattern and strategy.
+    pub mask: Vec<(String, MaskStrategy)>,
+}
+
+impl ProfilePolicy {
+    pub fn is_unrestricted(&self) -> bool {
+        self.deny.is_empty() && !self.read_only
+    }
+
+    /// Whether writes against this profile need confirmation (`environment: production`).
+    pub fn is_production(&self) -> bool {
+        self.environment
+            .as_deref()
+            .is_some_and(|env| env.trim().eq_ignore_ascii_case("production"))
+    }
+
+    /// The deny entry covering `command_path`, matched whole words from the front.
+    pub fn denied_by(&self, command_path: &str) -> Option<&str> {
+        let words: Vec<&str> = command_path.split_whitespace().collect();
+        self.deny
+            .iter()
+            .find(|entry| {
+                let entry_words: Vec<&str> = entry.split_whitespace().collect();
+                !entry_words.is_empty()
+                    && entry_words.len() <= words.len()
+                    && entry_words
+                        .iter()
+                        .zip(&words)
+                        .all(|(a, b)| a.eq_ignore_ascii_case(b))
+            })
+            .map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,8 +109,118 @@ pub struct ConnectionSettings {
     pub password: Option<String>,
     pub encrypt: bool,
     pub trust_cert: bool,
+    pub tls: TlsSettings,
     pub timeout_ms: u64,
     pub default_schemas: Vec<String>,
+    pub session: SessionSettings,
+    /// Program name sent at login (`program_name` in `sys.dm_exec_sessions`).
+    pub application_name: Option<String>,
+}
+
+/// A profile's `tls` block, enforced when connecting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsSettings {
+    pub ca_cert: Option<PathBuf>,
+    pub strict: bool,
+    pub min_version: Option<TlsVersion>,
+}
+
+/// A profile's `session` block, issued by `db::client::connect` after every login.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSettings {
+    pub isolation_level: Option<IsolationLevel>,
+    pub lock_timeout_ms: Option<i64>,
+    pub deadlock_priority: Option<DeadlockPriority>,
+    /// ON/OFF options, names upper-cased.
+    pub options: Vec<(String, bool)>,
+    /// Run id written to CONTEXT_INFO.
+    pub context_info: Option<String>,
+}
+
+/// Application name when the profile sets none.
+pub const DEFAULT_APPLICATION_NAME: &str = "sscli/{version} {user}@{host} {command}";
+/// Longest application name the login packet carries.
+const APPLICATION_NAME_MAX: usize = 128;
+/// CONTEXT_INFO is `varbinary(128)`.
+const CONTEXT_INFO_MAX: usize = 128;
+
+/// ON/OFF options `session.options` may set. Ones that would stop statements running
+/// (`NOEXEC`, `FMTONLY`) or leave transactions open (`IMPLICIT_TRANSACTIONS`) are left out.
+pub const SESSION_OPTIONS: &[&str] = &[
+    "ANSI_NULLS",
+    "ANSI_NULL_DFLT_ON",
+    "ANSI_PADDING",
+    "ANSI_WARNINGS",
+    "ARITHABORT",
+    "ARITHIGNORE",
+    "CONCAT_NULL_YIELDS_NULL",
+    "NOCOUNT",
+    "NUMERIC_ROUNDABORT",
+    "QUOTED_IDENTIFIER",
+    "XACT_ABORT",
+];
+
+impl SessionSettings {
+    /// The SET statements to issue, in a fixed order; empty when nothing is configured.
+    pub fn statements(&self) -> Vec<String> {
+        let mut statements = Vec::new();
+        if let Some(level) = self.isolation_level {
+            statements.push(format!("SET TRANSACTION ISOLATION LEVEL {};", level.sql()));
+        }
+        if let Some(ms) = self.lock_timeout_ms {
+            statements.push(format!("SET LOCK_TIMEOUT {};", ms));
+        }
+        if let Some(priority) = self.deadlock_priority {
+            statements.push(format!(
+                "SET DEADLOCK_PRIORITY {};",
+                priority.as_str().to_uppercase()
+            ));
+        }
+        for (name, on) in &self.options {
+            statements.push(format!("SET {} {};", name, if *on { "ON" } else { "OFF" }));
+        }
+        if let Some(run_id) = &self.context_info {
+            let hex: String = run_id.bytes().map(|byte| format!("{:02X}", byte)).collect();
+            statements.push(format!("SET CONTEXT_INFO 0x{};", hex));
+        }
+        statements
+    }
+
+    fn from_profile(profile_name: &str, session: &SessionProfile) -> Result<Self> {
+        if session.lock_timeout.is_some_and(|ms| ms < -1) {
+            return Err(anyhow!(
+                "Profile '{}': session.lockTimeout must be -1 (wait indefinitely) or more",
+                profile_name
+            ));
+        }
+        let mut options = session
+            .options
+            .iter()
+            .flatten()
+            .map(|(name, on)| {
+                let upper = name.trim().to_ascii_uppercase();
+                if SESSION_OPTIONS.contains(&upper.as_str()) {
+                    Ok((upper, *on))
+                } else {
+                    Err(anyhow!(
+                        "Profile '{}': session.options.{} is not a supported SET option; use one of {}",
+                        profile_name,
+                        name,
+                        SESSION_OPTIONS.join(", ")
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        options.sort();
+        options.dedup_by(|a, b| a.0 == b.0);
+        Ok(Self {
+            isolation_level: session.isolation_level,
+            lock_timeout_ms: session.lock_timeout,
+            deadlock_priority: session.deadlock_priority,
+            options,
+            context_info: None,
+        })
+    }
 }
 
 impl Default for ConnectionSettings {
@@ -63,8 +233,11 @@ impl Default for ConnectionSettings {
             password: None,
             encrypt: true,
             trust_cert: true,
+            tls: TlsSettings::default(),
             timeout_ms: 30_000,
             default_schemas: vec!["dbo".to_string()],
+            session: SessionSettings::default(),
+            application_name: None,
         }
     }
 }
@@ -72,19 +245,92 @@ impl Default for ConnectionSettings {
 #[derive(Debug, Clone)]
 pub struct SettingsResolved {
     pub output: OutputSettingsResolved,
+    pub migrations: MigrationSettingsResolved,
+    pub serve: ServeSettingsResolved,
+    pub audit: AuditSettingsResolved,
+    pub metadata_cache: MetadataCacheSettingsResolved,
+    pub formatting: FormattingSettingsResolved,
+}
+
+/// Auditing is on when either sink is configured.
+#[derive(Debug, Clone, Default)]
+pub struct AuditSettingsResolved {
+    pub file: Option<PathBuf>,
+    pub syslog: bool,
+}
+
+/// Off unless `settings.metadataCache.enabled` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataCacheSettingsResolved {
+    pub enabled: bool,
+    pub ttl_seconds: u64,
+}
+
+impl Default for MetadataCacheSettingsResolved {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_seconds: 600,
+        }
+    }
+}
+
+/// Style for `fmt`, `sql --format-sql`, and `compare --format-sql`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormattingSettingsResolved {
+    pub keyword_case: KeywordCase,
+    pub commas: CommaStyle,
+    pub indent: usize,
+}
+
+impl Default for FormattingSettingsResolved {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Upper,
+            commas: CommaStyle::Trailing,
+            indent: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationSettingsResolved {
+    pub directory: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServeSettingsResolved {
+    pub bind: String,
+    pub port: u16,
+    pub token_env: String,
+    pub profiles: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct OutputSettingsResolved {
     pub default_format: OutputFormat,
+    pub time_zone: DisplayTimeZone,
+    /// Zone of zone-less timestamps (`sourceTimeZone`); `None` leaves them unconverted.
+    pub source_time_zone: Option<DisplayTimeZone>,
+    /// Append an object_id tie-breaker to metadata listing order (`stableOrdering`).
+    pub stable_ordering: bool,
+    /// Cap on `geography`/`geometry` WKT text, in characters (`wktMaxLength`); `None` is no cap.
+    pub wkt_max_length: Option<usize>,
+    pub binary: BinaryFormat,
+    /// Cap on text and binary cells, in bytes (`maxValueBytes`); `None` is no cap.
+    pub max_value_bytes: Option<usize>,
     pub json: JsonSettingsResolved,
     pub csv: CsvSettingsResolved,
+    pub render: RenderSettingsResolved,
+    pub theme: ThemeSettingsResolved,
+    pub table: TableSettingsResolved,
 }
 
 #[derive(Debug, Clone)]
 pub struct JsonSettingsResolved {
     pub contract_version: JsonContractVersion,
     pub pretty: bool,
+    pub large_integers: JsonLargeIntegers,
 }
 
 #[derive(Debug, Clone)]
@@ -92,19 +338,105 @@ pub struct CsvSettingsResolved {
     pub multi_result_naming: CsvMultiResultNaming,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettingsResolved {
+    pub detect_values: bool,
+    pub hyperlinks: HyperlinkMode,
+    pub pretty_json: bool,
+    pub currency_symbol: String,
+}
+
+impl Default for RenderSettingsResolved {
+    fn default() -> Self {
+        Self {
+            detect_values: true,
+            hyperlinks: HyperlinkMode::Auto,
+            pretty_json: false,
+            currency_symbol: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeSettingsResolved {
+    pub color: ColorMode,
+    pub header_color: ThemeColor,
+    pub dim_nulls: bool,
+    /// Right-align numeric columns in pretty tables; applies with or without color.
+    pub align_numbers: bool,
+    pub stripe_rows: bool,
+    pub highlight_errors: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSettingsResolved {
+    pub null_display: String,
+    pub booleans: BooleanStyle,
+    /// Cell cap for query output (`sql`, `table-data`); `None` is no cap.
+    pub max_cell_width: Option<usize>,
+    /// Whole-output cap for query output; `None` is no cap.
+    pub max_output_chars: Option<usize>,
+}
+
+impl Default for TableSettingsResolved {
+    fn default() -> Self {
+        Self {
+            null_display: "—".to_string(),
+            booleans: BooleanStyle::TrueFalse,
+            max_cell_width: Some(140),
+            max_output_chars: Some(25_000),
+        }
+    }
+}
+
+impl Default for ThemeSettingsResolved {
+    fn default() -> Self {
+        Self {
+            color: ColorMode::Auto,
+            header_color: ThemeColor::Cyan,
+            dim_nulls: true,
+            align_numbers: true,
+            stripe_rows: false,
+            highlight_errors: true,
+        }
+    }
+}
+
 impl Default for SettingsResolved {
     fn default() -> Self {
         Self {
             output: OutputSettingsResolved {
                 default_format: OutputFormat::Pretty,
+                time_zone: DisplayTimeZone::Original,
+                source_time_zone: None,
+                stable_ordering: false,
+                wkt_max_length: None,
+                binary: BinaryFormat::Hex,
+                max_value_bytes: None,
                 json: JsonSettingsResolved {
                     contract_version: JsonContractVersion::V1,
                     pretty: true,
+                    large_integers: JsonLargeIntegers::String,
                 },
                 csv: CsvSettingsResolved {
                     multi_result_naming: CsvMultiResultNaming::SuffixNumber,
                 },
+                render: RenderSettingsResolved::default(),
+                theme: ThemeSettingsResolved::default(),
+                table: TableSettingsResolved::default(),
+            },
+            migrations: MigrationSettingsResolved {
+                directory: PathBuf::from("migrations"),
             },
+            serve: ServeSettingsResolved {
+                bind: "127.0.0.1".to_string(),
+                port: 8080,
+                token_env: "SSCLI_SERVE_TOKEN".to_string(),
+                profiles: Vec::new(),
+            },
+            audit: AuditSettingsResolved::default(),
+            metadata_cache: MetadataCacheSettingsResolved::default(),
+            formatting: FormattingSettingsResolved::default(),
         }
     }
 }
@@ -126,8 +458,35 @@ pub fn load_config(options: &LoadOptions, env: &Env) -> Result<ResolvedConfig> {
         apply_settings(&mut settings, settings_cfg);
     }
 
-    if let Some(profile) = config_file.profiles.get(&profile_name) {
+    let mut policy = ProfilePolicy::default();
+    let profile = config_file.profiles.get(&profile_name);
+    if let Some(profile) = profile {
+        if profile
+            .tls
+            .as_ref()
+            .is_some_and(|tls| tls.client_cert.is_some() || tls.client_key.is_some())
+        {
+            return Err(anyhow!(
+                "Profile '{}': tls.clientCert and tls.clientKey are not supported; the TDS \
+                 driver cannot present a client certificate",
+                profile_name
+            ));
+        }
         apply_profile(&mut connection, &mut settings, profile, env);
+        if let Some(session) = &profile.session {
+            connection.session = SessionSettings::from_profile(&profile_name, session)?;
+        }
+        policy.environment = profile.environment.clone();
+        policy.mask = profile
+            .mask
+            .iter()
+            .flatten()
+            .map(|(pattern, strategy)| (pattern.clone(), *strategy))
+            .collect();
+        if let Some(permissions) = &profile.permissions {
+            policy.deny = permissions.deny.clone().unwrap_or_default();
+            policy.read_only = permissions.read_only.unwrap_or(false);
+        }
     }
 
     // Only let ambient env vars override when the profile was NOT explicitly set via CLI.
@@ -138,14 +497,93 @@ pub fn load_config(options: &LoadOptions, env: &Env) -> Result<ResolvedConfig> {
     }
     apply_cli_overrides(&mut connection, &mut settings, &options.cli);
 
+    let run_id = options
+        .cli
+        .run_id
+        .clone()
+        .or_else(|| env.get("SSCLI_RUN_ID"))
+        .filter(|id| !id.trim().is_empty())
+        .or_else(|| {
+            profile
+                .and_then(|profile| profile.context_info)
+                .unwrap_or(false)
+                .then(generated_run_id)
+        });
+    if let Some(run_id) = &run_id
+        && run_id.len() > CONTEXT_INFO_MAX
+    {
+        return Err(anyhow!(
+            "Run id is {} bytes; CONTEXT_INFO holds at most {}",
+            run_id.len(),
+            CONTEXT_INFO_MAX
+        ));
+    }
+    let template = profile
+        .and_then(|profile| profile.application_name.as_deref())
+        .unwrap_or(DEFAULT_APPLICATION_NAME);
+    connection.application_name = Some(application_name(
+        template,
+        env,
+        options.cli.command.as_deref(),
+        run_id.as_deref(),
+    ));
+    connection.session.context_info = run_id;
+
     Ok(ResolvedConfig {
         config_path,
         profile_name,
         connection,
         settings,
+        policy,
     })
 }
 
+/// Fill in an `applicationName` template, cut to what the login packet carries.
+fn application_name(
+    template: &str,
+    env: &Env,
+    command: Option<&str>,
+    run_id: Option<&str>,
+) -> String {
+    let user = env
+        .get_any(&["USER", "USERNAME", "LOGNAME"])
+        .unwrap_or_else(|| "unknown".to_string());
+    // HOSTNAME is usually a shell variable rather than an exported one, hence the file.
+    let host = env
+        .get_any(&["HOSTNAME", "COMPUTERNAME"])
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    let name = template
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+        .replace("{user}", &user)
+        .replace("{host}", &host)
+        .replace("{command}", command.unwrap_or_default())
+        .replace("{runId}", run_id.unwrap_or_default());
+    name.trim().chars().take(APPLICATION_NAME_MAX).collect()
+}
+
+/// A random id for this process's runs, shared by every config it loads.
+fn generated_run_id() -> String {
+    static RUN_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    RUN_ID
+        .get_or_init(|| {
+            let mut bytes = [0u8; 8];
+            // A clock-derived id is good enough if the system RNG is unavailable.
+            if ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut bytes).is_err()
+            {
+                let nanos = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_nanos() as u64)
+                    .unwrap_or_default();
+                bytes = (nanos ^ u64::from(std::process::id())).to_be_bytes();
+            }
+            bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+        })
+        .clone()
+}
+
 fn resolve_profile_name(options: &LoadOptions, env: &Env, default_profile: Option<&str>) -> String {
     if let Some(profile) = options.cli.profile.as_deref() {
         return profile.to_string();
@@ -232,7 +670,7 @@ fn find_global_config(xdg_config: Option<&Path>) -> Option<PathBuf> {
     None
 }
 
-fn load_config_file(path: &Path) -> Result<ConfigFile> {
+pub fn load_config_file(path: &Path) -> Result<ConfigFile> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
@@ -276,6 +714,18 @@ fn apply_profile(
     if let Some(trust_cert) = profile.trust_cert {
         connection.trust_cert = trust_cert;
     }
+    if let Some(tls) = &profile.tls {
+        connection.tls = TlsSettings {
+            ca_cert: tls.ca_cert.clone(),
+            strict: tls.strict.unwrap_or(false),
+            min_version: tls.min_version,
+        };
+        // Both exist to validate the certificate, so they drop the trust-all default;
+        // an explicit `trustCert: true` is left for the connect-time check to reject.
+        if (tls.ca_cert.is_some() || connection.tls.strict) && profile.trust_cert.is_none() {
+            connection.trust_cert = false;
+        }
+    }
     if let Some(timeout) = profile.timeout {
         connection.timeout_ms = timeout;
     }
@@ -292,12 +742,76 @@ fn apply_settings(settings: &mut SettingsResolved, overrides: &Settings) {
     if let Some(output) = &overrides.output {
         apply_output_settings(&mut settings.output, output);
     }
+    if let Some(migrations) = &overrides.migrations {
+        if let Some(directory) = &migrations.directory {
+            settings.migrations.directory = directory.clone();
+        }
+    }
+    if let Some(serve) = &overrides.serve {
+        if let Some(bind) = &serve.bind {
+            settings.serve.bind = bind.clone();
+        }
+        if let Some(port) = serve.port {
+            settings.serve.port = port;
+        }
+        if let Some(token_env) = &serve.token_env {
+            settings.serve.token_env = token_env.clone();
+        }
+        if let Some(profiles) = &serve.profiles {
+            settings.serve.profiles = profiles.clone();
+        }
+    }
+    if let Some(audit) = &overrides.audit {
+        if let Some(file) = &audit.file {
+            settings.audit.file = Some(file.clone());
+        }
+        if let Some(syslog) = audit.syslog {
+            settings.audit.syslog = syslog;
+        }
+    }
+    if let Some(cache) = &overrides.metadata_cache {
+        if let Some(enabled) = cache.enabled {
+            settings.metadata_cache.enabled = enabled;
+        }
+        if let Some(ttl_seconds) = cache.ttl_seconds {
+            settings.metadata_cache.ttl_seconds = ttl_seconds;
+        }
+    }
+    if let Some(formatting) = &overrides.formatting {
+        if let Some(keyword_case) = formatting.keyword_case {
+            settings.formatting.keyword_case = keyword_case;
+        }
+        if let Some(commas) = formatting.commas {
+            settings.formatting.commas = commas;
+        }
+        if let Some(indent) = formatting.indent {
+            settings.formatting.indent = indent;
+        }
+    }
 }
 
 fn apply_output_settings(settings: &mut OutputSettingsResolved, overrides: &OutputSettings) {
     if let Some(default_format) = overrides.default_format {
         settings.default_format = default_format;
     }
+    if let Some(time_zone) = overrides.time_zone {
+        settings.time_zone = time_zone;
+    }
+    if let Some(source_time_zone) = overrides.source_time_zone {
+        settings.source_time_zone = Some(source_time_zone);
+    }
+    if let Some(stable_ordering) = overrides.stable_ordering {
+        settings.stable_ordering = stable_ordering;
+    }
+    if let Some(wkt_max_length) = overrides.wkt_max_length {
+        settings.wkt_max_length = Some(wkt_max_length).filter(|max| *max > 0);
+    }
+    if let Some(binary) = overrides.binary {
+        settings.binary = binary;
+    }
+    if let Some(max_value_bytes) = overrides.max_value_bytes {
+        settings.max_value_bytes = Some(max_value_bytes).filter(|max| *max > 0);
+    }
     if let Some(json) = &overrides.json {
         if let Some(contract_version) = json.contract_version {
             settings.json.contract_version = contract_version;
@@ -305,12 +819,63 @@ fn apply_output_settings(settings: &mut OutputSettingsResolved, overrides: &Outp
         if let Some(pretty) = json.pretty {
             settings.json.pretty = pretty;
         }
+        if let Some(large_integers) = json.large_integers {
+            settings.json.large_integers = large_integers;
+        }
     }
     if let Some(csv) = &overrides.csv {
         if let Some(multi_result_naming) = csv.multi_result_naming {
             settings.csv.multi_result_naming = multi_result_naming;
         }
     }
+    if let Some(render) = &overrides.render {
+        if let Some(detect_values) = render.detect_values {
+            settings.render.detect_values = detect_values;
+        }
+        if let Some(hyperlinks) = render.hyperlinks {
+            settings.render.hyperlinks = hyperlinks;
+        }
+        if let Some(pretty_json) = render.pretty_json {
+            settings.render.pretty_json = pretty_json;
+        }
+        if let Some(symbol) = &render.currency_symbol {
+            settings.render.currency_symbol = symbol.clone();
+        }
+    }
+    if let Some(theme) = &overrides.theme {
+        if let Some(color) = theme.color {
+            settings.theme.color = color;
+        }
+        if let Some(header_color) = theme.header_color {
+            settings.theme.header_color = header_color;
+        }
+        if let Some(dim_nulls) = theme.dim_nulls {
+            settings.theme.dim_nulls = dim_nulls;
+        }
+        if let Some(align_numbers) = theme.align_numbers {
+            settings.theme.align_numbers = align_numbers;
+        }
+        if let Some(stripe_rows) = theme.stripe_rows {
+            settings.theme.stripe_rows = stripe_rows;
+        }
+        if let Some(highlight_errors) = theme.highlight_errors {
+            settings.theme.highlight_errors = highlight_errors;
+        }
+    }
+    if let Some(table) = &overrides.table {
+        if let Some(null_display) = &table.null_display {
+            settings.table.null_display = null_display.clone();
+        }
+        if let Some(booleans) = table.booleans {
+            settings.table.booleans = booleans;
+        }
+        if let Some(max_cell_width) = table.max_cell_width {
+            settings.table.max_cell_width = Some(max_cell_width).filter(|max| *max > 0);
+        }
+        if let Some(max_output_chars) = table.max_output_chars {
+            settings.table.max_output_chars = Some(max_output_chars).filter(|max| *max > 0);
+        }
+    }
 }
 
 fn apply_env_overrides(
@@ -400,9 +965,36 @@ fn apply_env_overrides(
 
 fn apply_cli_overrides(
     connection: &mut ConnectionSettings,
-    _settings: &mut SettingsResolved,
+    settings: &mut SettingsResolved,
     cli: &CliOverrides,
 ) {
+    if let Some(time_zone) = cli.time_zone {
+        settings.output.time_zone = time_zone;
+    }
+    if let Some(source_time_zone) = cli.source_time_zone {
+        settings.output.source_time_zone = Some(source_time_zone);
+    }
+    if let Some(null_display) = &cli.null_display {
+        settings.output.table.null_display = null_display.clone();
+    }
+    if let Some(booleans) = cli.booleans {
+        settings.output.table.booleans = booleans;
+    }
+    if let Some(max_cell_width) = cli.max_cell_width {
+        settings.output.table.max_cell_width = Some(max_cell_width).filter(|max| *max > 0);
+    }
+    if let Some(max_output_chars) = cli.max_output_chars {
+        settings.output.table.max_output_chars = Some(max_output_chars).filter(|max| *max > 0);
+    }
+    if let Some(binary) = cli.binary {
+        settings.output.binary = binary;
+    }
+    if let Some(max_value_bytes) = cli.max_value_bytes {
+        settings.output.max_value_bytes = Some(max_value_bytes).filter(|max| *max > 0);
+    }
+    if let Some(version) = cli.contract_version {
+        settings.output.json.contract_version = version;
+    }
     if let Some(server) = &cli.server {
         connection.server = server.clone();
     }
@@ -410,7 +1002,14 @@ fn apply_cli_overrides(
         connection.port = port;
     }
     if let Some(database) = &cli.database {
-        connection.database = database.clone();
+        // `--database [host].Db` also picks the server, unless `--server` already did.
+        match split_server_database(database) {
+            (Some(server), database) if cli.server.is_none() => {
+                connection.server = server;
+                connection.database = database;
+            }
+            (_, database) => connection.database = database,
+        }
     }
     if let Some(user) = &cli.user {
         connection.user = Some(user.clone());
@@ -421,6 +1020,12 @@ fn apply_cli_overrides(
     if let Some(timeout_ms) = cli.timeout_ms {
         connection.timeout_ms = timeout_ms;
     }
+    if let Some(level) = cli.isolation_level {
+        connection.session.isolation_level = Some(level);
+    }
+    if let Some(ms) = cli.lock_timeout_ms {
+        connection.session.lock_timeout_ms = Some(ms);
+    }
     if let Some(encrypt) = cli.encrypt {
         connection.encrypt = encrypt;
     }
@@ -429,6 +1034,44 @@ fn apply_cli_overrides(
     }
 }
 
+/// Split `[server].database`: only a bracketed first part names a server, so a plain dotted name
+/// (`Sales.Archive`) is one database and never moves the connection. `]]` escapes a `]` inside
+/// the brackets, as in T-SQL.
+fn split_server_database(value: &str) -> (Option<String>, String) {
+    let value = value.trim();
+    if let Some(rest) = value.strip_prefix('[') {
+        let mut server = String::new();
+        let mut chars = rest.char_indices().peekable();
+        while let Some((index, ch)) = chars.next() {
+            if ch != ']' {
+                server.push(ch);
+                continue;
+            }
+            if chars.peek().is_some_and(|&(_, next)| next == ']') {
+                chars.next();
+                server.push(']');
+                continue;
+            }
+            return match rest[index + 1..].strip_prefix('.') {
+                Some(database) if !server.is_empty() && !database.trim().is_empty() => {
+                    (Some(server), unbracket(database))
+                }
+                _ => (None, unbracket(value)),
+            };
+        }
+    }
+    (None, unbracket(value))
+}
+
+fn unbracket(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(value)
+        .to_string()
+}
+
 #[derive(Debug, Default)]
 struct ParsedUrl {
     server: Option<String>,
@@ -549,6 +1192,362 @@ mod tests {
         assert_eq!(resolved.connection.server, "example");
     }
 
+    #[test]
+    fn cli_time_zone_overrides_config_setting() {
+        let dir = temp_dir("time-zone");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  output:\n    timeZone: \"+02:00\"\n",
+        )
+        .expect("write config");
+
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(
+            resolved.settings.output.time_zone,
+            DisplayTimeZone::Fixed(120)
+        );
+
+        options.cli.time_zone = Some(DisplayTimeZone::Utc);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.settings.output.time_zone, DisplayTimeZone::Utc);
+    }
+
+    #[test]
+    fn binary_settings_come_from_config_and_cli() {
+        let dir = temp_dir("binary");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  output:\n    binary: skip\n    maxValueBytes: 1024\n",
+        )
+        .expect("write config");
+
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.settings.output.binary, BinaryFormat::Skip);
+        assert_eq!(resolved.settings.output.max_value_bytes, Some(1024));
+
+        options.cli.binary = Some(BinaryFormat::Hex);
+        options.cli.max_value_bytes = Some(0);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.settings.output.binary, BinaryFormat::Hex);
+        assert_eq!(resolved.settings.output.max_value_bytes, None);
+    }
+
+    #[test]
+    fn table_settings_come_from_config_and_cli() {
+        let dir = temp_dir("table-settings");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  output:\n    table:\n      nullDisplay: 'NULL'\n      booleans: yes-no\n      maxCellWidth: 0\n",
+        )
+        .expect("write config");
+
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        let table = &resolved.settings.output.table;
+        assert_eq!(table.null_display, "NULL");
+        assert_eq!(table.booleans, BooleanStyle::YesNo);
+        assert_eq!(table.max_cell_width, None);
+        assert_eq!(table.max_output_chars, Some(25_000));
+
+        options.cli.booleans = Some(BooleanStyle::TrueFalse);
+        options.cli.max_output_chars = Some(0);
+        let resolved = load_config(&options, &env).expect("load config");
+        let table = &resolved.settings.output.table;
+        assert_eq!(table.booleans, BooleanStyle::TrueFalse);
+        assert_eq!(table.max_output_chars, None);
+    }
+
+    #[test]
+    fn metadata_cache_is_opt_in() {
+        let dir = temp_dir("metadata-cache");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  metadataCache:\n    enabled: true\n    ttlSeconds: 60\n",
+        )
+        .expect("write config");
+        let env = Env::from_pairs(&[]);
+        let load = |config_path: Option<PathBuf>| {
+            let options = LoadOptions {
+                cli: CliOverrides {
+                    config_path,
+                    ..CliOverrides::default()
+                },
+                cwd: dir.clone(),
+                home_dir: None,
+                xdg_config_dir: None,
+            };
+            load_config(&options, &env)
+                .expect("load config")
+                .settings
+                .metadata_cache
+        };
+
+        assert_eq!(load(None), MetadataCacheSettingsResolved::default());
+        assert!(!MetadataCacheSettingsResolved::default().enabled);
+        let cache = load(Some(config_path.clone()));
+        assert!(cache.enabled);
+        assert_eq!(cache.ttl_seconds, 60);
+    }
+
+    #[test]
+    fn cli_database_can_name_the_server() {
+        let dir = temp_dir("server-database");
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                database: Some("[db.example.com].Sales".to_string()),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "db.example.com");
+        assert_eq!(resolved.connection.database, "Sales");
+
+        options.cli.database = Some("Sales.Archive".to_string());
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "localhost");
+        assert_eq!(resolved.connection.database, "Sales.Archive");
+
+        options.cli.database = Some("[Sales.Archive]".to_string());
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "localhost");
+        assert_eq!(resolved.connection.database, "Sales.Archive");
+
+        options.cli.database = Some("[prod]].lab].[Sales.Archive]".to_string());
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "prod].lab");
+        assert_eq!(resolved.connection.database, "Sales.Archive");
+
+        options.cli.database = Some("[prod].[Sales.Archive]".to_string());
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "prod");
+        assert_eq!(resolved.connection.database, "Sales.Archive");
+
+        options.cli.server = Some("other".to_string());
+        options.cli.database = Some("[prod].OtherDb".to_string());
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(resolved.connection.server, "other");
+        assert_eq!(resolved.connection.database, "OtherDb");
+    }
+
+    #[test]
+    fn cli_contract_overrides_config_setting() {
+        let dir = temp_dir("contract");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  output:\n    json:\n      contractVersion: v2\n",
+        )
+        .expect("write config");
+
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(
+            resolved.settings.output.json.contract_version,
+            JsonContractVersion::V2
+        );
+
+        options.cli.contract_version = Some(JsonContractVersion::V1);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(
+            resolved.settings.output.json.contract_version,
+            JsonContractVersion::V1
+        );
+    }
+
+    #[test]
+    fn profile_settings_enable_stable_ordering() {
+        let dir = temp_dir("stable-ordering");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "defaultProfile: ci\nprofiles:\n  ci:\n    server: example\n    settings:\n      output:\n        stableOrdering: true\n",
+        )
+        .expect("write config");
+
+        let options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert!(resolved.settings.output.stable_ordering);
+        assert!(!SettingsResolved::default().output.stable_ordering);
+    }
+
+    #[test]
+    fn migrations_directory_defaults_and_overrides() {
+        let dir = temp_dir("migrations-dir");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  migrations:\n    directory: db/migrations\nprofiles:\n  default:\n    server: example\n",
+        )
+        .expect("write config");
+
+        let options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let resolved = load_config(&options, &env).expect("load config");
+        assert_eq!(
+            resolved.settings.migrations.directory,
+            PathBuf::from("db/migrations")
+        );
+        assert_eq!(
+            SettingsResolved::default().migrations.directory,
+            PathBuf::from("migrations")
+        );
+    }
+
+    #[test]
+    fn serve_settings_default_and_override() {
+        let dir = temp_dir("serve-settings");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  serve:\n    port: 9000\n    profiles: [prod]\nprofiles:\n  default:\n    server: example\n",
+        )
+        .expect("write config");
+
+        let options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let serve = load_config(&options, &env)
+            .expect("load config")
+            .settings
+            .serve;
+        assert_eq!(serve.port, 9000);
+        assert_eq!(serve.bind, "127.0.0.1");
+        assert_eq!(serve.token_env, "SSCLI_SERVE_TOKEN");
+        assert_eq!(serve.profiles, vec!["prod".to_string()]);
+    }
+
+    #[test]
+    fn audit_settings_apply_per_profile() {
+        let dir = temp_dir("audit-settings");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "profiles:\n  dev:\n    server: dev\n  prod:\n    server: prod\n    settings:\n      audit:\n        file: audit.log\n",
+        )
+        .expect("write config");
+
+        let load = |profile: &str| {
+            let options = LoadOptions {
+                cli: CliOverrides {
+                    config_path: Some(config_path.clone()),
+                    profile: Some(profile.to_string()),
+                    ..CliOverrides::default()
+                },
+                cwd: dir.clone(),
+                home_dir: None,
+                xdg_config_dir: None,
+            };
+            load_config(&options, &Env::from_pairs(&[]))
+                .expect("load config")
+                .settings
+                .audit
+        };
+        assert_eq!(load("prod").file, Some(PathBuf::from("audit.log")));
+        assert!(!load("prod").syslog);
+        assert_eq!(load("dev").file, None);
+    }
+
+    #[test]
+    fn theme_settings_override_defaults() {
+        let dir = temp_dir("theme");
+        let config_path = dir.join("config.yaml");
+        fs::write(
+            &config_path,
+            "settings:\n  output:\n    theme:\n      color: never\n      headerColor: green\n      stripeRows: true\ndefaultProfile: dev\nprofiles:\n  dev:\n    server: example\n",
+        )
+        .expect("write config");
+
+        let options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let theme = load_config(&options, &env)
+            .expect("load config")
+            .settings
+            .output
+            .theme;
+        assert_eq!(theme.color, ColorMode::Never);
+        assert_eq!(theme.header_color, ThemeColor::Green);
+        assert!(theme.stripe_rows);
+        assert!(theme.dim_nulls && theme.align_numbers && theme.highlight_errors);
+    }
+
     #[test]
     fn env_overrides_config_profile() {
         let dir = temp_dir("env-override");
@@ -609,4 +1608,198 @@ mod tests {
         let resolved = load_config(&options, &env).expect("load config");
         assert_eq!(resolved.profile_name, "default");
     }
+
+    #[test]
+    fn profile_permissions_become_policy() {
+        let dir = temp_dir("permissions");
+        let config_path = dir.join("config.yml");
+        fs::write(
+            &config_path,
+            "defaultProfile: prod\nprofiles:\n  prod:\n    permissions:\n      deny: [sql, migrate]\n      readOnly: true\n    environment: Production\n    mask:\n      dbo.Users.Email: hash\n      \"*.SSN\": redact\n  dev:\n    server: localhost\n",
+        )
+        .expect("write config");
+
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+        let policy = load_config(&options, &env).expect("load config").policy;
+        assert!(policy.read_only && policy.is_production());
+        assert_eq!(policy.denied_by("sql"), Some("sql"));
+        assert_eq!(policy.denied_by("migrate up"), Some("migrate"));
+        assert_eq!(policy.denied_by("stored-procs"), None);
+        assert_eq!(policy.denied_by("sqlx"), None);
+        assert_eq!(
+            policy.mask,
+            vec![
+                ("*.SSN".to_string(), MaskStrategy::Redact),
+                ("dbo.Users.Email".to_string(), MaskStrategy::Hash),
+            ]
+        );
+
+        options.cli.profile = Some("dev".to_string());
+        let policy = load_config(&options, &env).expect("load config").policy;
+        assert!(policy.is_unrestricted() && !policy.is_production() && policy.mask.is_empty());
+    }
+
+    #[test]
+    fn tls_block_validates_and_drops_trust_all() {
+        let dir = temp_dir("tls");
+        let config_path = dir.join("config.yml");
+        let options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path.clone()),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    tls:\n      caCert: ca.pem\n      strict: true\n      minVersion: 1.2\n",
+        )
+        .expect("write config");
+        let connection = load_config(&options, &env).expect("load config").connection;
+        assert!(!connection.trust_cert);
+        assert!(connection.tls.strict);
+        assert_eq!(connection.tls.ca_cert, Some(PathBuf::from("ca.pem")));
+        assert_eq!(connection.tls.min_version, Some(TlsVersion::V1_2));
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    tls:\n      minVersion: \"1.3\"\n",
+        )
+        .expect("write config");
+        let err = load_config(&options, &env).expect_err("1.3");
+        assert!(format!("{:#}", err).contains("cannot be enforced"));
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    tls:\n      clientCert: me.pem\n",
+        )
+        .expect("write config");
+        let err = load_config(&options, &env).expect_err("client cert");
+        assert!(err.to_string().contains("not supported"));
+    }
+
+    #[test]
+    fn session_block_builds_set_statements_and_flags_override() {
+        let dir = temp_dir("session");
+        let config_path = dir.join("config.yml");
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path.clone()),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[]);
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    session:\n      isolationLevel: read-uncommitted\n      lockTimeout: 5000\n      deadlockPriority: low\n      options:\n        arithabort: true\n        XACT_ABORT: false\n",
+        )
+        .expect("write config");
+        let session = load_config(&options, &env)
+            .expect("load config")
+            .connection
+            .session;
+        assert_eq!(
+            session.statements(),
+            vec![
+                "SET TRANSACTION ISOLATION LEVEL READ UNCOMMITTED;",
+                "SET LOCK_TIMEOUT 5000;",
+                "SET DEADLOCK_PRIORITY LOW;",
+                "SET ARITHABORT ON;",
+                "SET XACT_ABORT OFF;",
+            ]
+        );
+
+        options.cli.isolation_level = Some(IsolationLevel::Snapshot);
+        options.cli.lock_timeout_ms = Some(-1);
+        let session = load_config(&options, &env)
+            .expect("load config")
+            .connection
+            .session;
+        assert_eq!(session.isolation_level, Some(IsolationLevel::Snapshot));
+        assert_eq!(session.statements()[1], "SET LOCK_TIMEOUT -1;");
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    session:\n      options:\n        NOEXEC: true\n",
+        )
+        .expect("write config");
+        let err = load_config(&options, &env).expect_err("NOEXEC");
+        assert!(err.to_string().contains("not a supported SET option"));
+        assert!(
+            ConnectionSettings::default()
+                .session
+                .statements()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn application_name_and_run_id_identify_the_run() {
+        let dir = temp_dir("appname");
+        let config_path = dir.join("config.yml");
+        let mut options = LoadOptions {
+            cli: CliOverrides {
+                config_path: Some(config_path.clone()),
+                command: Some("table-data".to_string()),
+                ..CliOverrides::default()
+            },
+            cwd: dir,
+            home_dir: None,
+            xdg_config_dir: None,
+        };
+        let env = Env::from_pairs(&[("USER", "ana"), ("HOSTNAME", "build-7")]);
+
+        fs::write(&config_path, "profiles:\n  default:\n    server: db\n").expect("write config");
+        let connection = load_config(&options, &env).expect("load config").connection;
+        assert_eq!(
+            connection.application_name.as_deref(),
+            Some(format!("sscli/{} ana@build-7 table-data", env!("CARGO_PKG_VERSION")).as_str())
+        );
+        assert!(connection.session.statements().is_empty());
+
+        fs::write(
+            &config_path,
+            "profiles:\n  default:\n    applicationName: nightly-etl {runId}\n    contextInfo: true\n",
+        )
+        .expect("write config");
+        let first = load_config(&options, &env).expect("load config").connection;
+        let run_id = first
+            .session
+            .context_info
+            .clone()
+            .expect("generated run id");
+        assert_eq!(run_id.len(), 16);
+        assert_eq!(
+            first.application_name,
+            Some(format!("nightly-etl {}", run_id))
+        );
+        let again = load_config(&options, &env).expect("load config").connection;
+        assert_eq!(again.session.context_info, Some(run_id));
+
+        options.cli.run_id = Some("job-1".to_string());
+        let connection = load_config(&options, &env).expect("load config").connection;
+        assert_eq!(
+            connection.session.statements(),
+            vec!["SET CONTEXT_INFO 0x6A6F622D31;"]
+        );
+        options.cli.run_id = Some("x".repeat(129));
+        assert!(load_config(&options, &env).is_err());
+    }
 }
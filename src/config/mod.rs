@@ -1,15 +1,26 @@
 mod env;
 mod loader;
+mod profile_edit;
 mod schema;
 
 pub use env::{Env, parse_bool};
 pub use loader::{
-    CliOverrides, ConnectionSettings, LoadOptions, OutputSettingsResolved, ResolvedConfig,
-    SettingsResolved, load_config,
+    AuditSettingsResolved, CliOverrides, ConnectionSettings, FormattingSettingsResolved,
+    LoadOptions, MetadataCacheSettingsResolved, MigrationSettingsResolved, OutputSettingsResolved,
+    ProfilePolicy, RenderSettingsResolved, ResolvedConfig, ServeSettingsResolved, SessionSettings,
+    SettingsResolved, TableSettingsResolved, ThemeSettingsResolved, TlsSettings, load_config,
+    load_config_file,
+};
+pub use profile_edit::{
+    ProfileField, add_profile, remove_profile, set_default_profile, update_profile,
 };
 pub use schema::{
-    ConfigFile, CsvMultiResultNaming, JsonContractVersion, JsonSettings, OutputFormat,
-    OutputSettings, Profile, Settings,
+    AuditSettings, BinaryFormat, BooleanStyle, ColorMode, CommaStyle, ConfigFile,
+    CsvMultiResultNaming, DeadlockPriority, DisplayTimeZone, FormattingSettings, HyperlinkMode,
+    IsolationLevel, JsonContractVersion, JsonLargeIntegers, JsonSettings, KeywordCase,
+    MaskStrategy, MetadataCacheSettings, MigrationSettings, OutputFormat, OutputSettings, Profile,
+    ProfilePermissions, RenderSettings, ServeSettings, SessionProfile, Settings, ThemeColor,
+    ThemeSettings, TlsProfile, TlsVersion, format_offset,
 };
 
 pub fn load_from_system(cli: &CliOverrides) -> anyhow::Result<ResolvedConfig> {
@@ -0,0 +1,351 @@
+//! Line-oriented editing of the `profiles:` section of a YAML config.
+//!
+//! Round-tripping through serde would drop comments and reorder keys, so these
+//! helpers splice text in place and only touch the lines of the profile being
+//! changed.
+
+use anyhow::{Result, anyhow};
+
+/// A single `key: value` entry to write under a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileField {
+    pub key: &'static str,
+    pub value: serde_yaml::Value,
+}
+
+impl ProfileField {
+    pub fn new(key: &'static str, value: impl Into<serde_yaml::Value>) -> Self {
+        Self {
+            key,
+            value: value.into(),
+        }
+    }
+}
+
+/// Append a new profile block to the `profiles:` section.
+///
+/// # Errors
+///
+/// Returns an error if a profile with the same name already exists.
+pub fn add_profile(content: &str, name: &str, fields: &[ProfileField]) -> Result<String> {
+    let mut lines = split_lines(content);
+    let section = ensure_profiles_section(&mut lines);
+    let layout = ProfilesLayout::scan(&lines, section);
+
+    if layout.find(&lines, name).is_some() {
+        return Err(anyhow!("Profile '{}' already exists", name));
+    }
+
+    let indent = layout.child_indent.unwrap_or(2);
+    let mut block = vec![format!("{}{}:", " ".repeat(indent), format_key(name))];
+    for field in fields {
+        block.push(format_field(indent + 2, field.key, &field.value));
+    }
+
+    let insert_at = layout.content_end;
+    lines.splice(insert_at..insert_at, block);
+    Ok(join_lines(&lines, content))
+}
+
+/// Remove a profile block, including its nested keys.
+///
+/// # Errors
+///
+/// Returns an error if the profile does not exist.
+pub fn remove_profile(content: &str, name: &str) -> Result<String> {
+    let mut lines = split_lines(content);
+    let section =
+        find_profiles_section(&lines).ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+    let layout = ProfilesLayout::scan(&lines, section);
+    let (start, end) = layout
+        .find(&lines, name)
+        .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+
+    lines.drain(start..end);
+    Ok(join_lines(&lines, content))
+}
+
+/// Set (or insert) keys on an existing profile, leaving other lines untouched.
+///
+/// # Errors
+///
+/// Returns an error if the profile does not exist.
+pub fn update_profile(content: &str, name: &str, fields: &[ProfileField]) -> Result<String> {
+    let mut lines = split_lines(content);
+    let section =
+        find_profiles_section(&lines).ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+    let layout = ProfilesLayout::scan(&lines, section);
+    let (start, mut end) = layout
+        .find(&lines, name)
+        .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+
+    let profile_indent = indent_of(&lines[start]);
+    let field_indent = lines[start + 1..end]
+        .iter()
+        .find(|line| is_content(line))
+        .map(|line| indent_of(line))
+        .unwrap_or(profile_indent + 2);
+
+    for field in fields {
+        let existing = (start + 1..end).find(|&idx| {
+            indent_of(&lines[idx]) == field_indent && key_of(&lines[idx]) == Some(field.key)
+        });
+        let rendered = format_field(field_indent, field.key, &field.value);
+        match existing {
+            Some(idx) => {
+                let comment = trailing_comment(&lines[idx]);
+                lines[idx] = match comment {
+                    Some(comment) => format!("{} {}", rendered, comment),
+                    None => rendered,
+                };
+            }
+            None => {
+                lines.insert(end, rendered);
+                end += 1;
+            }
+        }
+    }
+
+    Ok(join_lines(&lines, content))
+}
+
+/// Set the top-level `defaultProfile` key, inserting it at the top when missing.
+pub fn set_default_profile(content: &str, name: &str) -> String {
+    let mut lines = split_lines(content);
+    let rendered = format!("defaultProfile: {}", format_scalar(&name.into()));
+    match lines
+        .iter()
+        .position(|line| indent_of(line) == 0 && key_of(line) == Some("defaultProfile"))
+    {
+        Some(idx) => lines[idx] = rendered,
+        None => {
+            let insert_at = lines
+                .iter()
+                .position(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+                .unwrap_or(lines.len());
+            lines.insert(insert_at, rendered);
+        }
+    }
+    join_lines(&lines, content)
+}
+
+struct ProfilesLayout {
+    /// Index of the first line after the `profiles:` header.
+    body_start: usize,
+    /// One past the last content line belonging to the section.
+    content_end: usize,
+    child_indent: Option<usize>,
+}
+
+impl ProfilesLayout {
+    fn scan(lines: &[String], header: usize) -> Self {
+        let body_start = header + 1;
+        let mut content_end = body_start;
+        let mut child_indent = None;
+        for (idx, line) in lines.iter().enumerate().skip(body_start) {
+            if !is_content(line) {
+                continue;
+            }
+            let indent = indent_of(line);
+            if indent == 0 {
+                break;
+            }
+            if child_indent.is_none() {
+                child_indent = Some(indent);
+            }
+            content_end = idx + 1;
+        }
+        Self {
+            body_start,
+            content_end,
+            child_indent,
+        }
+    }
+
+    /// Return the `[start, end)` line range of the named profile.
+    fn find(&self, lines: &[String], name: &str) -> Option<(usize, usize)> {
+        let child_indent = self.child_indent?;
+        let start = (self.body_start..self.content_end).find(|&idx| {
+            let line = &lines[idx];
+            is_content(line)
+                && indent_of(line) == child_indent
+                && key_of(line).map(unquote) == Some(name)
+        })?;
+
+        let mut end = start + 1;
+        for (idx, line) in lines
+            .iter()
+            .enumerate()
+            .take(self.content_end)
+            .skip(start + 1)
+        {
+            if !is_content(line) {
+                continue;
+            }
+            if indent_of(line) <= child_indent {
+                break;
+            }
+            end = idx + 1;
+        }
+        Some((start, end))
+    }
+}
+
+fn find_profiles_section(lines: &[String]) -> Option<usize> {
+    lines
+        .iter()
+        .position(|line| indent_of(line) == 0 && key_of(line) == Some("profiles"))
+}
+
+fn ensure_profiles_section(lines: &mut Vec<String>) -> usize {
+    if let Some(idx) = find_profiles_section(lines) {
+        // `profiles: {}` cannot take nested entries; switch it to block style.
+        if lines[idx].split_once(':').is_some_and(|(_, rest)| {
+            let rest = strip_comment(rest).trim();
+            rest == "{}" || rest == "~" || rest == "null"
+        }) {
+            lines[idx] = "profiles:".to_string();
+        }
+        return idx;
+    }
+    if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+        lines.push(String::new());
+    }
+    lines.push("profiles:".to_string());
+    lines.len() - 1
+}
+
+fn split_lines(content: &str) -> Vec<String> {
+    content.lines().map(str::to_string).collect()
+}
+
+fn join_lines(lines: &[String], original: &str) -> String {
+    let newline = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = lines.join(newline);
+    out.push_str(newline);
+    out
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn key_of(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let (key, _) = trimmed.split_once(':')?;
+    Some(key.trim_end())
+}
+
+fn unquote(key: &str) -> &str {
+    key.strip_prefix('"')
+        .and_then(|k| k.strip_suffix('"'))
+        .or_else(|| key.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')))
+        .unwrap_or(key)
+}
+
+fn strip_comment(value: &str) -> &str {
+    match value.find(" #") {
+        Some(idx) => &value[..idx],
+        None => value,
+    }
+}
+
+fn trailing_comment(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once(':')?;
+    rest.find(" #").map(|idx| rest[idx + 1..].trim_end())
+}
+
+fn format_key(name: &str) -> String {
+    format_scalar(&name.into())
+}
+
+fn format_field(indent: usize, key: &str, value: &serde_yaml::Value) -> String {
+    format!("{}{}: {}", " ".repeat(indent), key, format_scalar(value))
+}
+
+fn format_scalar(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Sequence(items) => {
+            let rendered: Vec<String> = items.iter().map(format_scalar).collect();
+            format!("[{}]", rendered.join(", "))
+        }
+        _ => serde_yaml::to_string(value)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# top comment\ndefaultProfile: dev\nprofiles:\n  # dev box\n  dev:\n    server: localhost # local\n    database: app\n\n  prod:\n    server: prod-host\n";
+
+    #[test]
+    fn add_profile_appends_block_and_keeps_comments() {
+        let updated = add_profile(
+            SAMPLE,
+            "staging",
+            &[
+                ProfileField::new("server", "stage-host"),
+                ProfileField::new("port", 1444u64),
+            ],
+        )
+        .expect("add");
+        assert!(updated.starts_with("# top comment\n"));
+        assert!(updated.contains("  # dev box\n"));
+        assert!(updated.ends_with("  staging:\n    server: stage-host\n    port: 1444\n"));
+    }
+
+    #[test]
+    fn add_profile_rejects_duplicates() {
+        let err = add_profile(SAMPLE, "dev", &[]).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn add_profile_creates_missing_section() {
+        let updated = add_profile("defaultProfile: dev\n", "dev", &[]).expect("add");
+        assert_eq!(updated, "defaultProfile: dev\n\nprofiles:\n  dev:\n");
+    }
+
+    #[test]
+    fn remove_profile_drops_only_that_block() {
+        let updated = remove_profile(SAMPLE, "dev").expect("remove");
+        assert!(!updated.contains("server: localhost"));
+        assert!(updated.contains("  prod:\n    server: prod-host\n"));
+        assert!(updated.contains("# top comment"));
+    }
+
+    #[test]
+    fn update_profile_replaces_and_inserts_keys() {
+        let updated = update_profile(
+            SAMPLE,
+            "dev",
+            &[
+                ProfileField::new("server", "db01"),
+                ProfileField::new("encrypt", false),
+            ],
+        )
+        .expect("update");
+        assert!(updated.contains("    server: db01 # local\n"));
+        assert!(updated.contains("    database: app\n    encrypt: false\n"));
+        assert!(updated.contains("    server: prod-host\n"));
+    }
+
+    #[test]
+    fn set_default_profile_rewrites_key() {
+        let updated = set_default_profile(SAMPLE, "prod");
+        assert!(updated.contains("defaultProfile: prod\n"));
+        assert!(!updated.contains("defaultProfile: dev"));
+    }
+}
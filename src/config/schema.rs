@@ -1,5 +1,8 @@
+use chrono::{Local, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -14,14 +17,78 @@ pub struct ConfigFile {
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     pub output: Option<OutputSettings>,
+    pub migrations: Option<MigrationSettings>,
+    pub serve: Option<ServeSettings>,
+    pub audit: Option<AuditSettings>,
+    pub metadata_cache: Option<MetadataCacheSettings>,
+    pub formatting: Option<FormattingSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationSettings {
+    /// Where `migrate` finds versioned `.sql` files, relative to the working directory.
+    pub directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditSettings {
+    /// Append one JSON line per connection and statement to this file.
+    pub file: Option<PathBuf>,
+    /// Also send each record to the local syslog (Unix only).
+    pub syslog: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataCacheSettings {
+    /// Keep catalog query results on disk for `tables`, `columns`, `stored-procs`, `describe`.
+    pub enabled: Option<bool>,
+    /// How long a cached result is used before the server is asked again (default 600).
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattingSettings {
+    /// Case of T-SQL keywords in `fmt` and `sql --format-sql` output (default upper).
+    pub keyword_case: Option<KeywordCase>,
+    /// Where list commas go: end of the line or start of the next (default trailing).
+    pub commas: Option<CommaStyle>,
+    /// Spaces per indent level (default 4).
+    pub indent: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServeSettings {
+    /// Address `serve` listens on (default 127.0.0.1).
+    pub bind: Option<String>,
+    /// HTTP port for `serve` (default 8080).
+    pub port: Option<u16>,
+    /// Environment variable holding the bearer token (default SSCLI_SERVE_TOKEN).
+    pub token_env: Option<String>,
+    /// Profiles requests may select with `?profile=`; empty serves only the active profile.
+    pub profiles: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputSettings {
     pub default_format: Option<OutputFormat>,
+    pub time_zone: Option<DisplayTimeZone>,
+    /// Zone `datetime`/`datetime2` values were written in; unset leaves them as stored.
+    pub source_time_zone: Option<DisplayTimeZone>,
+    pub stable_ordering: Option<bool>,
+    pub wkt_max_length: Option<usize>,
+    pub binary: Option<BinaryFormat>,
+    pub max_value_bytes: Option<usize>,
     pub json: Option<JsonSettings>,
     pub csv: Option<CsvSettings>,
+    pub render: Option<RenderSettings>,
+    pub theme: Option<ThemeSettings>,
+    pub table: Option<TableSettings>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -29,6 +96,7 @@ pub struct OutputSettings {
 pub struct JsonSettings {
     pub contract_version: Option<JsonContractVersion>,
     pub pretty: Option<bool>,
+    pub large_integers: Option<JsonLargeIntegers>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -37,6 +105,40 @@ pub struct CsvSettings {
     pub multi_result_naming: Option<CsvMultiResultNaming>,
 }
 
+/// How table output presents recognised values (JSON, URLs, GUIDs, money).
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderSettings {
+    pub detect_values: Option<bool>,
+    pub hyperlinks: Option<HyperlinkMode>,
+    pub pretty_json: Option<bool>,
+    pub currency_symbol: Option<String>,
+}
+
+/// ANSI styling of pretty and expanded tables.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeSettings {
+    pub color: Option<ColorMode>,
+    pub header_color: Option<ThemeColor>,
+    pub dim_nulls: Option<bool>,
+    pub align_numbers: Option<bool>,
+    pub stripe_rows: Option<bool>,
+    pub highlight_errors: Option<bool>,
+}
+
+/// How cells read in pretty, markdown, and expanded tables.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSettings {
+    pub null_display: Option<String>,
+    pub booleans: Option<BooleanStyle>,
+    /// 0 means no limit.
+    pub max_cell_width: Option<usize>,
+    /// 0 means no limit.
+    pub max_output_chars: Option<usize>,
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Profile {
@@ -48,9 +150,192 @@ pub struct Profile {
     pub password: Option<String>,
     pub encrypt: Option<bool>,
     pub trust_cert: Option<bool>,
+    pub tls: Option<TlsProfile>,
     pub timeout: Option<u64>,
     pub default_schemas: Option<Vec<String>>,
     pub settings: Option<Settings>,
+    pub permissions: Option<ProfilePermissions>,
+    /// Free-form tag; `production` makes writes ask for confirmation.
+    pub environment: Option<String>,
+    /// Column masks for `sql` and `table-data` output, keyed by `schema.table.column` patterns.
+    pub mask: Option<BTreeMap<String, MaskStrategy>>,
+    /// SET options issued on every connection after login.
+    pub session: Option<SessionProfile>,
+    /// Program name sent at login; `{version}`, `{user}`, `{host}`, `{command}`, and `{runId}`
+    /// are filled in.
+    pub application_name: Option<String>,
+    /// SET CONTEXT_INFO to this run's id on every connection.
+    pub context_info: Option<bool>,
+}
+
+/// Certificate validation beyond `trustCert`.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TlsProfile {
+    /// CA certificate (`.pem`, `.crt`, or `.der`, one certificate) trusted instead of the system roots.
+    pub ca_cert: Option<PathBuf>,
+    /// Refuse to connect unless the connection is encrypted and the certificate chain and
+    /// hostname are verified.
+    pub strict: Option<bool>,
+    pub min_version: Option<TlsVersion>,
+    /// Not supported by the TDS driver; present only to reject it with a clear message.
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+}
+
+/// Lowest TLS version a profile accepts. The TLS stack never offers anything below 1.2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "TlsVersionRepr")]
+pub enum TlsVersion {
+    V1_0,
+    V1_1,
+    V1_2,
+}
+
+impl TlsVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsVersion::V1_0 => "1.0",
+            TlsVersion::V1_1 => "1.1",
+            TlsVersion::V1_2 => "1.2",
+        }
+    }
+}
+
+/// `minVersion: 1.2` reads as a number in YAML, `"1.2"` as a string; accept both.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TlsVersionRepr {
+    Text(String),
+    Number(f64),
+}
+
+impl TryFrom<TlsVersionRepr> for TlsVersion {
+    type Error = String;
+
+    fn try_from(value: TlsVersionRepr) -> Result<Self, Self::Error> {
+        let text = match value {
+            TlsVersionRepr::Text(text) => text.trim().to_string(),
+            TlsVersionRepr::Number(number) => format!("{:.1}", number),
+        };
+        match text.as_str() {
+            "1.0" => Ok(TlsVersion::V1_0),
+            "1.1" => Ok(TlsVersion::V1_1),
+            "1.2" => Ok(TlsVersion::V1_2),
+            "1.3" => Err(
+                "tls.minVersion 1.3 cannot be enforced: the driver negotiates TLS 1.2 or 1.3 \
+                 and offers no way to refuse 1.2"
+                    .to_string(),
+            ),
+            other => Err(format!(
+                "Invalid tls.minVersion '{}': expected 1.0, 1.1, or 1.2",
+                other
+            )),
+        }
+    }
+}
+
+/// A profile's `session` block.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SessionProfile {
+    pub isolation_level: Option<IsolationLevel>,
+    /// `SET LOCK_TIMEOUT`, in milliseconds; -1 waits indefinitely.
+    pub lock_timeout: Option<i64>,
+    pub deadlock_priority: Option<DeadlockPriority>,
+    /// ON/OFF options by name, e.g. `ARITHABORT: true`.
+    pub options: Option<BTreeMap<String, bool>>,
+}
+
+/// `SET TRANSACTION ISOLATION LEVEL` for a profile's sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Snapshot,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "read-uncommitted",
+            IsolationLevel::ReadCommitted => "read-committed",
+            IsolationLevel::RepeatableRead => "repeatable-read",
+            IsolationLevel::Snapshot => "snapshot",
+            IsolationLevel::Serializable => "serializable",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "read-uncommitted" => IsolationLevel::ReadUncommitted,
+            "read-committed" => IsolationLevel::ReadCommitted,
+            "repeatable-read" => IsolationLevel::RepeatableRead,
+            "snapshot" => IsolationLevel::Snapshot,
+            "serializable" => IsolationLevel::Serializable,
+            _ => return None,
+        })
+    }
+
+    /// The level as T-SQL spells it: `READ UNCOMMITTED`.
+    pub fn sql(&self) -> String {
+        self.as_str().replace('-', " ").to_uppercase()
+    }
+}
+
+/// `SET DEADLOCK_PRIORITY`: which session SQL Server prefers to roll back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadlockPriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl DeadlockPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeadlockPriority::Low => "low",
+            DeadlockPriority::Normal => "normal",
+            DeadlockPriority::High => "high",
+        }
+    }
+}
+
+/// How a masked column's values are shown; later variants hide more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskStrategy {
+    /// Keep the last four characters.
+    Partial,
+    /// A stable SHA-256 prefix, so equal values still group and join.
+    Hash,
+    Redact,
+    Nullify,
+}
+
+impl MaskStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaskStrategy::Partial => "partial",
+            MaskStrategy::Hash => "hash",
+            MaskStrategy::Redact => "redact",
+            MaskStrategy::Nullify => "nullify",
+        }
+    }
+}
+
+/// Commands a profile refuses to run, checked before dispatch.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePermissions {
+    /// Command names (or aliases) to refuse; `migrate` also covers `migrate up`.
+    pub deny: Option<Vec<String>>,
+    /// Refuse `--allow-write` for every command.
+    pub read_only: Option<bool>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -59,6 +344,8 @@ pub enum OutputFormat {
     Pretty,
     Markdown,
     Json,
+    /// One name/value block per row, like psql's `\x`.
+    Expanded,
 }
 
 impl OutputFormat {
@@ -67,6 +354,7 @@ impl OutputFormat {
             OutputFormat::Pretty => "pretty",
             OutputFormat::Markdown => "markdown",
             OutputFormat::Json => "json",
+            OutputFormat::Expanded => "expanded",
         }
     }
 }
@@ -74,14 +362,59 @@ impl OutputFormat {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum JsonContractVersion {
+    /// Each command's own payload at the top level.
     #[serde(rename = "v1")]
     V1,
+    /// Every payload wrapped in `{ meta, data, warnings, errors }`.
+    #[serde(rename = "v2")]
+    V2,
 }
 
 impl JsonContractVersion {
     pub fn as_str(&self) -> &'static str {
         match self {
             JsonContractVersion::V1 => "v1",
+            JsonContractVersion::V2 => "v2",
+        }
+    }
+}
+
+/// How JSON output writes integers that a double cannot hold exactly (beyond ±2^53 - 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonLargeIntegers {
+    /// Quote them, so JavaScript and other double-based parsers keep every digit.
+    String,
+    /// Plain JSON numbers, for consumers that parse integers exactly.
+    Number,
+}
+
+impl JsonLargeIntegers {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JsonLargeIntegers::String => "string",
+            JsonLargeIntegers::Number => "number",
+        }
+    }
+}
+
+/// How `binary`/`varbinary`/`image` cells are written in tables, JSON, and CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryFormat {
+    /// `0x`-prefixed upper-case hex, as SQL Server prints it.
+    Hex,
+    Base64,
+    /// Leave the bytes out: tables show the length, JSON `null`, CSV an empty field.
+    Skip,
+}
+
+impl BinaryFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryFormat::Hex => "hex",
+            BinaryFormat::Base64 => "base64",
+            BinaryFormat::Skip => "skip",
         }
     }
 }
@@ -103,3 +436,320 @@ impl CsvMultiResultNaming {
         }
     }
 }
+
+/// When URL cells are wrapped in OSC-8 hyperlink escapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HyperlinkMode {
+    /// Only when stdout is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+impl HyperlinkMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HyperlinkMode::Auto => "auto",
+            HyperlinkMode::Always => "always",
+            HyperlinkMode::Never => "never",
+        }
+    }
+}
+
+/// When tables carry ANSI colors. `NO_COLOR` and `--no-color` turn them off in every mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Only when stdout is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorMode::Auto => "auto",
+            ColorMode::Always => "always",
+            ColorMode::Never => "never",
+        }
+    }
+}
+
+/// Keyword case the SQL formatter writes; `preserve` keeps the script's own spelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+    Preserve,
+}
+
+impl KeywordCase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeywordCase::Upper => "upper",
+            KeywordCase::Lower => "lower",
+            KeywordCase::Preserve => "preserve",
+        }
+    }
+}
+
+/// Whether the SQL formatter ends list lines with commas or starts them with one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommaStyle {
+    Trailing,
+    Leading,
+}
+
+impl CommaStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommaStyle::Trailing => "trailing",
+            CommaStyle::Leading => "leading",
+        }
+    }
+}
+
+/// How booleans read in tables; JSON and CSV always use `true`/`false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BooleanStyle {
+    TrueFalse,
+    YesNo,
+}
+
+impl BooleanStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BooleanStyle::TrueFalse => "true-false",
+            BooleanStyle::YesNo => "yes-no",
+        }
+    }
+
+    pub fn render(&self, value: bool) -> &'static str {
+        match (self, value) {
+            (BooleanStyle::TrueFalse, true) => "true",
+            (BooleanStyle::TrueFalse, false) => "false",
+            (BooleanStyle::YesNo, true) => "yes",
+            (BooleanStyle::YesNo, false) => "no",
+        }
+    }
+}
+
+/// Terminal color for a theme element; `none` leaves the terminal default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeColor {
+    None,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+}
+
+impl ThemeColor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeColor::None => "none",
+            ThemeColor::Black => "black",
+            ThemeColor::Red => "red",
+            ThemeColor::Green => "green",
+            ThemeColor::Yellow => "yellow",
+            ThemeColor::Blue => "blue",
+            ThemeColor::Magenta => "magenta",
+            ThemeColor::Cyan => "cyan",
+            ThemeColor::White => "white",
+            ThemeColor::Grey => "grey",
+        }
+    }
+}
+
+/// Zone that `datetimeoffset` values (and zoned `datetime`/`datetime2` values) are shown in,
+/// in tables, JSON, and CSV alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum DisplayTimeZone {
+    /// Keep the offset stored with each value.
+    Original,
+    Utc,
+    Local,
+    /// Fixed offset east of UTC, in minutes.
+    Fixed(i32),
+    /// An IANA zone such as `America/Chicago`.
+    Named(Tz),
+}
+
+impl DisplayTimeZone {
+    pub fn as_str(&self) -> String {
+        match self {
+            DisplayTimeZone::Original => "original".to_string(),
+            DisplayTimeZone::Utc => "utc".to_string(),
+            DisplayTimeZone::Local => "local".to_string(),
+            DisplayTimeZone::Fixed(minutes) => format_offset(*minutes),
+            DisplayTimeZone::Named(zone) => zone.name().to_string(),
+        }
+    }
+
+    /// Minutes east of UTC at a UTC instant; `None` for `original`, which has no offset of
+    /// its own.
+    pub fn offset_minutes_at(&self, utc: NaiveDateTime) -> Option<i32> {
+        match self {
+            DisplayTimeZone::Original => None,
+            DisplayTimeZone::Utc => Some(0),
+            DisplayTimeZone::Fixed(minutes) => Some(*minutes),
+            DisplayTimeZone::Local => {
+                Some(Local.offset_from_utc_datetime(&utc).local_minus_utc() / 60)
+            }
+            DisplayTimeZone::Named(zone) => {
+                Some(zone.offset_from_utc_datetime(&utc).fix().local_minus_utc() / 60)
+            }
+        }
+    }
+
+    /// Minutes east of UTC for a wall-clock time in this zone; `None` for `original`.
+    pub fn offset_minutes_for_local(&self, local: NaiveDateTime) -> Option<i32> {
+        match self {
+            DisplayTimeZone::Local => Local
+                .offset_from_local_datetime(&local)
+                .earliest()
+                .map(|offset| offset.local_minus_utc() / 60),
+            DisplayTimeZone::Named(zone) => {
+                // A time skipped by a transition takes the offset in effect at that instant in UTC.
+                let offset = zone
+                    .offset_from_local_datetime(&local)
+                    .earliest()
+                    .unwrap_or_else(|| zone.offset_from_utc_datetime(&local));
+                Some(offset.fix().local_minus_utc() / 60)
+            }
+            other => other.offset_minutes_at(local),
+        }
+    }
+}
+
+impl std::str::FromStr for DisplayTimeZone {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        match trimmed.to_ascii_lowercase().as_str() {
+            "original" | "stored" => return Ok(DisplayTimeZone::Original),
+            "utc" | "z" | "gmt" => return Ok(DisplayTimeZone::Utc),
+            "local" => return Ok(DisplayTimeZone::Local),
+            _ => {}
+        }
+        if let Some(minutes) = parse_offset(trimmed) {
+            return Ok(DisplayTimeZone::Fixed(minutes));
+        }
+        if trimmed.contains('/') {
+            return trimmed
+                .parse::<Tz>()
+                .map(DisplayTimeZone::Named)
+                .map_err(|_| format!("Unknown time zone '{}'", trimmed));
+        }
+        Err(format!(
+            "Invalid time zone '{}'; use utc, local, original, an offset like +05:30, or an IANA \
+             zone like America/Chicago",
+            value
+        ))
+    }
+}
+
+impl TryFrom<String> for DisplayTimeZone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Parse `+05:30`, `-0800`, `+2`, or `UTC+01:00` into minutes east of UTC.
+fn parse_offset(value: &str) -> Option<i32> {
+    let upper = value.to_ascii_uppercase();
+    let rest = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    let (sign, digits) = match rest.as_bytes().first()? {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    if hours.is_empty()
+        || !hours
+            .chars()
+            .chain(minutes.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+pub fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let abs = minutes.abs();
+    format!("{}{:02}:{:02}", sign, abs / 60, abs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_zones_and_offsets() {
+        assert_eq!("UTC".parse(), Ok(DisplayTimeZone::Utc));
+        assert_eq!("local".parse(), Ok(DisplayTimeZone::Local));
+        assert_eq!("+05:30".parse(), Ok(DisplayTimeZone::Fixed(330)));
+        assert_eq!("-0800".parse(), Ok(DisplayTimeZone::Fixed(-480)));
+        assert_eq!("UTC+2".parse(), Ok(DisplayTimeZone::Fixed(120)));
+        let zone = "America/Chicago"
+            .parse::<DisplayTimeZone>()
+            .expect("IANA zone");
+        assert_eq!(zone.as_str(), "America/Chicago");
+        assert!("Chicago".parse::<DisplayTimeZone>().is_err());
+        assert!("Nowhere/Chicago".parse::<DisplayTimeZone>().is_err());
+        assert!("+25:00".parse::<DisplayTimeZone>().is_err());
+    }
+
+    #[test]
+    fn named_zones_follow_daylight_saving() {
+        let zone: DisplayTimeZone = "America/Chicago".parse().expect("IANA zone");
+        let at = |text: &str| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(zone.offset_minutes_at(at("2024-01-15 12:00")), Some(-360));
+        assert_eq!(zone.offset_minutes_at(at("2024-07-15 12:00")), Some(-300));
+        // 02:30 on 2024-03-10 does not exist in Chicago; 01:30 on 2024-11-03 happens twice.
+        assert!(
+            zone.offset_minutes_for_local(at("2024-03-10 02:30"))
+                .is_some()
+        );
+        assert_eq!(
+            zone.offset_minutes_for_local(at("2024-11-03 01:30")),
+            Some(-300)
+        );
+    }
+
+    #[test]
+    fn formats_offsets() {
+        assert_eq!(format_offset(330), "+05:30");
+        assert_eq!(format_offset(-480), "-08:00");
+        assert_eq!(DisplayTimeZone::Fixed(0).as_str(), "+00:00");
+    }
+}
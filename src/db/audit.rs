@@ -0,0 +1,253 @@
+//! Opt-in audit log of everything sent to the server (`settings.audit`).
+//!
+//! Each connection and statement becomes one JSON line with the profile, logins, timing, row
+//! count, and whether `--allow-write` was given, appended to a file and/or sent to the local
+//! syslog. A log that cannot be opened stops the command; a failed write after that is only
+//! reported, so auditing never aborts work already under way.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::config::{AuditSettingsResolved, ConnectionSettings};
+use crate::error::{AppError, ErrorKind};
+
+/// Who and what a record is attributed to, fixed once config is resolved.
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub command: String,
+    pub profile: String,
+    pub allow_write: bool,
+}
+
+struct AuditLog {
+    context: AuditContext,
+    file: Option<File>,
+    #[cfg(unix)]
+    syslog: Option<std::os::unix::net::UnixDatagram>,
+}
+
+static AUDIT: Mutex<Option<AuditLog>> = Mutex::new(None);
+/// Checked before any record is built, so an unaudited run never formats statement text.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Start (or stop) auditing for this process according to `settings`.
+pub fn configure(settings: &AuditSettingsResolved, context: AuditContext) -> Result<()> {
+    let file = match &settings.file {
+        Some(path) => Some(open_file(path)?),
+        None => None,
+    };
+    #[cfg(unix)]
+    let syslog = if settings.syslog {
+        Some(open_syslog()?)
+    } else {
+        None
+    };
+    #[cfg(not(unix))]
+    if settings.syslog {
+        return Err(AppError::new(
+            ErrorKind::Config,
+            "settings.audit.syslog is only supported on Unix; use settings.audit.file",
+        )
+        .into());
+    }
+
+    let enabled = settings.file.is_some() || settings.syslog;
+    if let Ok(mut guard) = AUDIT.lock() {
+        *guard = enabled.then_some(AuditLog {
+            context,
+            file,
+            #[cfg(unix)]
+            syslog,
+        });
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record a login attempt against `settings`.
+pub fn record_connect(
+    settings: &ConnectionSettings,
+    elapsed: Duration,
+    outcome: Result<(), String>,
+) {
+    if !enabled() {
+        return;
+    }
+    let mut fields = Map::new();
+    fields.insert("event".to_string(), json!("connect"));
+    fields.insert("server".to_string(), json!(settings.server));
+    fields.insert("port".to_string(), json!(settings.port));
+    fields.insert("database".to_string(), json!(settings.database));
+    fields.insert("login".to_string(), json!(settings.user));
+    if let Some(run_id) = &settings.session.context_info {
+        fields.insert("runId".to_string(), json!(run_id));
+    }
+    fields.insert("durationMs".to_string(), json!(elapsed.as_millis() as u64));
+    if let Err(message) = outcome {
+        fields.insert("error".to_string(), json!(message));
+    }
+    write_record(fields);
+}
+
+/// Record one statement (a batch or a parameterized query) and how it went.
+pub fn record_statement(statement: &str, elapsed: Duration, outcome: Result<usize, String>) {
+    if !enabled() {
+        return;
+    }
+    let mut fields = Map::new();
+    fields.insert("event".to_string(), json!("statement"));
+    fields.insert("statement".to_string(), json!(statement));
+    fields.insert("durationMs".to_string(), json!(elapsed.as_millis() as u64));
+    match outcome {
+        Ok(rows) => {
+            fields.insert("rows".to_string(), json!(rows));
+        }
+        Err(message) => {
+            fields.insert("error".to_string(), json!(message));
+        }
+    }
+    write_record(fields);
+}
+
+/// The SQL text of a query. tiberius keeps it private, so it is read back from the `Debug`
+/// form, `Query { sql: "...", params: [...] }`.
+pub fn query_text(query: &tiberius::Query<'_>) -> String {
+    let debug = format!("{:?}", query);
+    debug
+        .split_once("sql: \"")
+        .map(|(_, rest)| unescape_debug(rest))
+        .unwrap_or(debug)
+}
+
+/// Undo `str`'s `Debug` escaping up to the closing quote.
+fn unescape_debug(escaped: &str) -> String {
+    let mut text = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some('0') => text.push('\0'),
+                Some('u') => {
+                    let code: String = chars
+                        .by_ref()
+                        .skip_while(|c| *c == '{')
+                        .take_while(|c| *c != '}')
+                        .collect();
+                    if let Some(decoded) =
+                        u32::from_str_radix(&code, 16).ok().and_then(char::from_u32)
+                    {
+                        text.push(decoded);
+                    }
+                }
+                Some(other) => text.push(other),
+                None => break,
+            },
+            other => text.push(other),
+        }
+    }
+    text
+}
+
+fn write_record(fields: Map<String, JsonValue>) {
+    let Ok(mut guard) = AUDIT.lock() else {
+        return;
+    };
+    let Some(log) = guard.as_mut() else {
+        return;
+    };
+    let mut record = Map::new();
+    record.insert(
+        "timestamp".to_string(),
+        json!(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+    );
+    record.insert("command".to_string(), json!(log.context.command));
+    record.insert("profile".to_string(), json!(log.context.profile));
+    record.insert("osUser".to_string(), json!(os_user()));
+    record.insert("allowWrite".to_string(), json!(log.context.allow_write));
+    record.extend(fields);
+    let line = JsonValue::Object(record).to_string();
+
+    if let Some(file) = log.file.as_mut() {
+        // One write per record keeps lines whole when several processes share the file.
+        if let Err(err) = file.write_all(format!("{}\n", line).as_bytes()) {
+            tracing::warn!("Audit log write failed: {err}");
+        }
+    }
+    #[cfg(unix)]
+    if let Some(socket) = log.syslog.as_ref() {
+        // <134> is facility local0, severity info.
+        let message = format!("<134>sscli[{}]: {}", std::process::id(), line);
+        if let Err(err) = socket.send(message.as_bytes()) {
+            tracing::warn!("Audit syslog write failed: {err}");
+        }
+    }
+}
+
+fn os_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+}
+
+fn open_file(path: &Path) -> Result<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|err| audit_error(path, err))?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| audit_error(path, err))
+}
+
+fn audit_error(path: &Path, err: std::io::Error) -> anyhow::Error {
+    AppError::new(
+        ErrorKind::Config,
+        format!("Cannot open audit log {}: {}", path.display(), err),
+    )
+    .into()
+}
+
+#[cfg(unix)]
+fn open_syslog() -> Result<std::os::unix::net::UnixDatagram> {
+    let socket = std::os::unix::net::UnixDatagram::unbound()?;
+    for path in ["/dev/log", "/var/run/syslog"] {
+        if socket.connect(path).is_ok() {
+            return Ok(socket);
+        }
+    }
+    Err(AppError::new(
+        ErrorKind::Config,
+        "settings.audit.syslog is on but no syslog socket was found (/dev/log, /var/run/syslog)",
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_query_text_back_from_debug() {
+        let sql = "SELECT N'it''s', \"quoted\"\n\tFROM dbo.[T\\x] -- é\u{7}";
+        let mut query = tiberius::Query::new(sql);
+        query.bind("param");
+        assert_eq!(query_text(&query), sql);
+    }
+}
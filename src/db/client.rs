@@ -1,12 +1,13 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket, lookup_host};
 use tokio::time::timeout;
 use tokio_util::compat::TokioAsyncWriteCompatExt;
 
-use crate::config::ConnectionSettings;
-use crate::db::connection::build_config;
+use crate::config::{ConnectionSettings, SessionSettings};
+use crate::db::audit;
+use crate::db::connection::{browser_lookup, build_config};
 use crate::error::{AppError, ErrorKind};
 
 /// Connect to SQL Server using resolved settings.
@@ -21,18 +22,68 @@ use crate::error::{AppError, ErrorKind};
 ///
 /// # Errors
 ///
-/// Returns an error if DNS resolution, TCP connect, or login fails, or if the
-/// configured timeout elapses during connect or login.
+/// Returns an error if DNS resolution, TCP connect, or login fails, if the
+/// configured timeout elapses during connect or login, or if the profile's
+/// `session` SET options are refused.
 pub async fn connect(
     settings: &ConnectionSettings,
 ) -> Result<tiberius::Client<tokio_util::compat::Compat<TcpStream>>> {
-    let config =
+    let started = Instant::now();
+    let result = login(settings).await;
+    audit::record_connect(
+        settings,
+        started.elapsed(),
+        result.as_ref().map(|_| ()).map_err(|err| err.to_string()),
+    );
+    let mut client = result?;
+    apply_session(&mut client, &settings.session).await?;
+    Ok(client)
+}
+
+/// Issue the profile's `session` SET options; they hold for the life of the connection.
+async fn apply_session(
+    client: &mut tiberius::Client<tokio_util::compat::Compat<TcpStream>>,
+    session: &SessionSettings,
+) -> Result<()> {
+    let statements = session.statements();
+    if statements.is_empty() {
+        return Ok(());
+    }
+    let failed = |err: tiberius::error::Error| {
+        AppError::new(
+            ErrorKind::Config,
+            format!("Session options ({}) failed: {}", statements.join(" "), err),
+        )
+    };
+    client
+        .simple_query(statements.join("\n"))
+        .await
+        .map_err(failed)?
+        .into_results()
+        .await
+        .map_err(failed)?;
+    Ok(())
+}
+
+/// SQL Server Browser's UDP port.
+const BROWSER_PORT: u16 = 1434;
+/// How long to wait for the browser when the connection timeout is 0 (unlimited).
+const BROWSER_TIMEOUT_MS: u64 = 5_000;
+
+async fn login(
+    settings: &ConnectionSettings,
+) -> Result<tiberius::Client<tokio_util::compat::Compat<TcpStream>>> {
+    let mut config =
         build_config(settings).map_err(|err| AppError::new(ErrorKind::Config, err.to_string()))?;
     let timeout_duration = if settings.timeout_ms == 0 {
         None
     } else {
         Some(Duration::from_millis(settings.timeout_ms))
     };
+    if let Some((host, instance)) = browser_lookup(settings) {
+        let wait = timeout_duration.unwrap_or(Duration::from_millis(BROWSER_TIMEOUT_MS));
+        config.port(instance_port(host, instance, wait).await?);
+    }
 
     // `timeout` wraps the future in an outer Result for Elapsed vs IO errors.
     let tcp = if let Some(duration) = timeout_duration {
@@ -63,11 +114,140 @@ pub async fn connect(
                 format!("Login timed out after {} ms", settings.timeout_ms),
             )
         })?
-        .map_err(|err| AppError::new(ErrorKind::Connection, err.to_string()))?
+        .map_err(login_error)?
     } else {
         tiberius::Client::connect(config, tcp.compat_write())
             .await
-            .map_err(|err| AppError::new(ErrorKind::Connection, err.to_string()))?
+            .map_err(login_error)?
     };
     Ok(client)
 }
+
+/// Ask the SQL Server Browser on `host` (UDP 1434) which TCP port `instance` listens on.
+pub(crate) async fn instance_port(host: &str, instance: &str, wait: Duration) -> Result<u16> {
+    let unreachable = |detail: String| {
+        AppError::new(
+            ErrorKind::Connection,
+            format!(
+                "Could not resolve instance '{}' on {}: {}. Check the instance name, start the SQL \
+                 Server Browser service and allow UDP {} through the firewall, or set the \
+                 instance's TCP port with `port` / --port.",
+                instance, host, detail, BROWSER_PORT
+            ),
+        )
+    };
+    let addr = lookup_host((host, BROWSER_PORT))
+        .await
+        .map_err(|err| AppError::new(ErrorKind::Connection, err.to_string()))?
+        .next()
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorKind::Connection,
+                format!("No address found for host '{}'", host),
+            )
+        })?;
+    let local = if addr.is_ipv6() {
+        "[::]:0"
+    } else {
+        "0.0.0.0:0"
+    };
+    let socket = UdpSocket::bind(local)
+        .await
+        .map_err(|err| AppError::new(ErrorKind::Connection, err.to_string()))?;
+
+    // CLNT_UCAST_INST: 0x04, then the instance name, NUL-terminated.
+    let mut request = vec![0x04];
+    request.extend_from_slice(instance.as_bytes());
+    request.push(0);
+    socket
+        .send_to(&request, addr)
+        .await
+        .map_err(|err| unreachable(err.to_string()))?;
+
+    let mut buf = vec![0u8; 4096];
+    let len = timeout(wait, socket.recv(&mut buf))
+        .await
+        .map_err(|_| {
+            unreachable(format!(
+                "SQL Server Browser did not answer within {} ms",
+                wait.as_millis()
+            ))
+        })?
+        .map_err(|err| unreachable(err.to_string()))?;
+    parse_browser_response(&buf[..len])
+        .map_err(|detail| AppError::new(ErrorKind::Connection, detail).into())
+}
+
+/// The `tcp` port from an SVR_RESP: 0x05, a little-endian length, then
+/// `ServerName;HOST;InstanceName;NAME;...;tcp;PORT;;`.
+fn parse_browser_response(response: &[u8]) -> std::result::Result<u16, String> {
+    if response.len() < 3 || response[0] != 0x05 {
+        return Err("SQL Server Browser sent an unexpected response".to_string());
+    }
+    let size = u16::from_le_bytes([response[1], response[2]]) as usize;
+    let data = &response[3..response.len().min(3 + size)];
+    let text = String::from_utf8_lossy(data);
+    let fields: Vec<&str> = text.split(';').collect();
+    let value = |key: &str| {
+        fields
+            .chunks(2)
+            .find(|pair| pair[0].eq_ignore_ascii_case(key))
+            .and_then(|pair| pair.get(1).copied())
+    };
+    let instance = value("InstanceName").unwrap_or("?");
+    match value("tcp") {
+        Some(port) => port.trim().parse::<u16>().map_err(|_| {
+            format!(
+                "SQL Server Browser reported an invalid TCP port '{}' for instance '{}'",
+                port, instance
+            )
+        }),
+        None => Err(format!(
+            "Instance '{}' does not listen on TCP (enable TCP/IP in SQL Server Configuration Manager)",
+            instance
+        )),
+    }
+}
+
+/// Server error numbers for a rejected login: wrong password, unknown or disabled login,
+/// password expired or locked out.
+pub(crate) const AUTH_ERRORS: &[u32] = &[18452, 18456, 18470, 18486, 18487, 18488];
+
+/// Login failures the server reports are auth errors; anything else during login is a
+/// connection error.
+fn login_error(err: tiberius::error::Error) -> AppError {
+    let kind = match &err {
+        tiberius::error::Error::Server(token) if AUTH_ERRORS.contains(&token.code()) => {
+            ErrorKind::Auth
+        }
+        _ => ErrorKind::Connection,
+    };
+    AppError::new(kind, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_browser_response;
+
+    fn response(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0x05];
+        bytes.extend_from_slice(&(text.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn browser_response_yields_the_tcp_port() {
+        let ok = response(
+            "ServerName;DB01;InstanceName;SQLEXPRESS;IsClustered;No;Version;16.0.1000.6;tcp;50123;;",
+        );
+        assert_eq!(parse_browser_response(&ok), Ok(50123));
+
+        let pipes_only = response(
+            "ServerName;DB01;InstanceName;SQLEXPRESS;IsClustered;No;Version;16.0.1000.6;np;\\\\DB01\\pipe\\sql\\query;;",
+        );
+        let err = parse_browser_response(&pipes_only).expect_err("no tcp");
+        assert!(err.contains("'SQLEXPRESS' does not listen on TCP"));
+        assert!(parse_browser_response(&[0x04, 0, 0]).is_err());
+    }
+}
@@ -1,12 +1,48 @@
+use std::fs;
+
 use anyhow::{Result, anyhow};
 
 use crate::config::ConnectionSettings;
 
+/// The port SQL Server listens on unless configured otherwise.
+pub const DEFAULT_PORT: u16 = 1433;
+
+/// Split `HOST\INSTANCE` into the host and the instance name.
+pub fn split_instance(server: &str) -> (&str, Option<&str>) {
+    match server.split_once('\\') {
+        Some((host, instance)) if !instance.trim().is_empty() => (host, Some(instance.trim())),
+        Some((host, _)) => (host, None),
+        None => (server, None),
+    }
+}
+
+/// The host and instance to ask the SQL Server Browser about: a named instance left on the
+/// default port. An explicit non-default port always wins over the lookup.
+pub fn browser_lookup(settings: &ConnectionSettings) -> Option<(&str, &str)> {
+    match split_instance(&settings.server) {
+        (host, Some(instance)) if settings.port == DEFAULT_PORT => Some((host, instance)),
+        _ => None,
+    }
+}
+
+/// `server{sep}port`, or just `HOST\INSTANCE` when the driver should resolve the port itself.
+fn server_address(settings: &ConnectionSettings, sep: char) -> String {
+    if browser_lookup(settings).is_some() {
+        settings.server.clone()
+    } else {
+        format!("{}{}{}", settings.server, sep, settings.port)
+    }
+}
+
+/// Tiberius config for `settings`; for a named instance the caller sets the resolved port.
 pub fn build_config(settings: &ConnectionSettings) -> Result<tiberius::Config> {
     let mut config = tiberius::Config::new();
-    config.host(&settings.server);
+    config.host(split_instance(&settings.server).0);
     config.port(settings.port);
     config.database(&settings.database);
+    if let Some(name) = &settings.application_name {
+        config.application_name(name);
+    }
 
     match (&settings.user, &settings.password) {
         (Some(user), Some(pass)) => {
@@ -21,6 +57,7 @@ pub fn build_config(settings: &ConnectionSettings) -> Result<tiberius::Config> {
         _ => {}
     }
 
+    check_tls(settings)?;
     if settings.encrypt {
         config.encryption(tiberius::EncryptionLevel::Required);
     } else {
@@ -29,22 +66,78 @@ pub fn build_config(settings: &ConnectionSettings) -> Result<tiberius::Config> {
 
     if settings.trust_cert {
         config.trust_cert();
+    } else if let Some(path) = &settings.tls.ca_cert {
+        config.trust_cert_ca(path.display());
     }
 
     Ok(config)
 }
 
+/// Reject TLS settings that contradict each other, and CA files the driver cannot load.
+fn check_tls(settings: &ConnectionSettings) -> Result<()> {
+    let tls = &settings.tls;
+    if tls.strict && !settings.encrypt {
+        return Err(anyhow!(
+            "tls.strict requires encryption; remove encrypt: false (or --encrypt false)"
+        ));
+    }
+    if tls.strict && settings.trust_cert {
+        return Err(anyhow!(
+            "tls.strict requires certificate validation; set trustCert: false and unset \
+             SQL_TRUST_SERVER_CERTIFICATE / --trust-cert"
+        ));
+    }
+    let Some(path) = &tls.ca_cert else {
+        return Ok(());
+    };
+    if settings.trust_cert {
+        return Err(anyhow!(
+            "tls.caCert is only used when the certificate is validated; set trustCert: false"
+        ));
+    }
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let content = fs::read(path)
+        .map_err(|err| anyhow!("Failed to read tls.caCert {}: {}", path.display(), err))?;
+    match extension.as_deref() {
+        Some("pem") | Some("crt") => {
+            let certs = String::from_utf8_lossy(&content)
+                .matches("-----BEGIN CERTIFICATE-----")
+                .count();
+            if certs != 1 {
+                return Err(anyhow!(
+                    "tls.caCert {} holds {} certificates; it must hold exactly one (the CA that \
+                     signed the server certificate)",
+                    path.display(),
+                    certs
+                ));
+            }
+        }
+        Some("der") => {}
+        _ => {
+            return Err(anyhow!(
+                "tls.caCert {} must be a .pem, .crt, or .der file",
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn build_ado_string(settings: &ConnectionSettings) -> String {
     let mut parts = vec![
-        format!("Server={},{}", settings.server, settings.port),
-        format!("Database={}", settings.database),
+        format!("Server={}", server_address(settings, ',')),
+        format!("Database={}", ado_value(&settings.database)),
     ];
 
-    if let Some(user) = &settings.user {
-        parts.push(format!("User ID={}", user));
+    match &settings.user {
+        Some(user) => parts.push(format!("User ID={}", ado_value(user))),
+        None => parts.push("Integrated Security=true".to_string()),
     }
     if let Some(password) = &settings.password {
-        parts.push(format!("Password={}", password));
+        parts.push(format!("Password={}", ado_value(password)));
     }
     if settings.encrypt {
         parts.push("Encrypt=true".to_string());
@@ -58,9 +151,112 @@ pub fn build_ado_string(settings: &ConnectionSettings) -> String {
     parts.join(";")
 }
 
+/// Microsoft JDBC driver URL.
+pub fn build_jdbc_string(settings: &ConnectionSettings) -> String {
+    let mut parts = vec![
+        format!("jdbc:sqlserver://{}", server_address(settings, ':')),
+        format!("databaseName={}", braced(&settings.database)),
+    ];
+    match &settings.user {
+        Some(user) => parts.push(format!("user={}", braced(user))),
+        None => parts.push("integratedSecurity=true".to_string()),
+    }
+    if let Some(password) = &settings.password {
+        parts.push(format!("password={}", braced(password)));
+    }
+    parts.push(format!("encrypt={}", settings.encrypt));
+    if settings.trust_cert {
+        parts.push("trustServerCertificate=true".to_string());
+    }
+    parts.push(format!("loginTimeout={}", settings.timeout_ms / 1000));
+    parts.join(";")
+}
+
+/// ODBC connection string for Microsoft ODBC Driver 18.
+pub fn build_odbc_string(settings: &ConnectionSettings) -> String {
+    let mut parts = vec![
+        "Driver={ODBC Driver 18 for SQL Server}".to_string(),
+        format!("Server=tcp:{}", server_address(settings, ',')),
+        format!("Database={}", braced(&settings.database)),
+    ];
+    match &settings.user {
+        Some(user) => parts.push(format!("Uid={}", braced(user))),
+        None => parts.push("Trusted_Connection=yes".to_string()),
+    }
+    if let Some(password) = &settings.password {
+        parts.push(format!("Pwd={}", braced(password)));
+    }
+    parts.push(format!(
+        "Encrypt={}",
+        if settings.encrypt { "yes" } else { "no" }
+    ));
+    if settings.trust_cert {
+        parts.push("TrustServerCertificate=yes".to_string());
+    }
+    parts.push(format!("Connection Timeout={}", settings.timeout_ms / 1000));
+    parts.join(";")
+}
+
+/// `sqlcmd` invocation, quoted for a POSIX shell.
+pub fn build_sqlcmd_command(settings: &ConnectionSettings) -> String {
+    let mut parts = vec![
+        "sqlcmd".to_string(),
+        "-S".to_string(),
+        shell_quote(&format!("tcp:{}", server_address(settings, ','))),
+        "-d".to_string(),
+        shell_quote(&settings.database),
+    ];
+    match &settings.user {
+        Some(user) => parts.extend(["-U".to_string(), shell_quote(user)]),
+        None => parts.push("-E".to_string()),
+    }
+    if let Some(password) = &settings.password {
+        parts.extend(["-P".to_string(), shell_quote(password)]);
+    }
+    if settings.encrypt {
+        parts.push("-N".to_string());
+    }
+    if settings.trust_cert {
+        parts.push("-C".to_string());
+    }
+    parts.extend(["-l".to_string(), (settings.timeout_ms / 1000).to_string()]);
+    parts.join(" ")
+}
+
+/// ADO.NET quotes values containing `;`, quotes, or edge whitespace.
+fn ado_value(value: &str) -> String {
+    if value.contains([';', '"', '\'']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// ODBC and JDBC brace values containing `;` or braces, doubling `}`.
+fn braced(value: &str) -> String {
+    if value.contains([';', '{', '}']) || value.trim() != value {
+        format!("{{{}}}", value.replace('}', "}}"))
+    } else {
+        value.to_string()
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_.,:/@".contains(ch))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TlsSettings;
 
     #[test]
     fn builds_ado_string() {
@@ -81,4 +277,96 @@ mod tests {
         assert!(ado.contains("Encrypt=true"));
         assert!(ado.contains("TrustServerCertificate=true"));
     }
+
+    #[test]
+    fn builds_jdbc_odbc_and_sqlcmd() {
+        let settings = ConnectionSettings {
+            server: "db.example".to_string(),
+            port: 1444,
+            database: "main".to_string(),
+            user: Some("app".to_string()),
+            password: Some("p;w'd}".to_string()),
+            ..ConnectionSettings::default()
+        };
+
+        assert_eq!(
+            build_jdbc_string(&settings),
+            "jdbc:sqlserver://db.example:1444;databaseName=main;user=app;password={p;w'd}}};\
+             encrypt=true;trustServerCertificate=true;loginTimeout=30"
+        );
+        assert_eq!(
+            build_odbc_string(&settings),
+            "Driver={ODBC Driver 18 for SQL Server};Server=tcp:db.example,1444;Database=main;\
+             Uid=app;Pwd={p;w'd}}};Encrypt=yes;TrustServerCertificate=yes;Connection Timeout=30"
+        );
+        assert_eq!(
+            build_sqlcmd_command(&settings),
+            "sqlcmd -S tcp:db.example,1444 -d main -U app -P 'p;w'\\''d}' -N -C -l 30"
+        );
+        assert!(build_ado_string(&settings).contains("Password=\"p;w'd}\""));
+    }
+
+    #[test]
+    fn integrated_security_without_a_user() {
+        let settings = ConnectionSettings::default();
+        assert!(build_ado_string(&settings).contains("Integrated Security=true"));
+        assert!(build_odbc_string(&settings).contains("Trusted_Connection=yes"));
+        assert!(build_sqlcmd_command(&settings).contains(" -E "));
+    }
+
+    #[test]
+    fn named_instances_resolve_through_the_browser_unless_a_port_is_set() {
+        let mut settings = ConnectionSettings {
+            server: "db01\\SQLEXPRESS".to_string(),
+            ..ConnectionSettings::default()
+        };
+        assert_eq!(split_instance("db01"), ("db01", None));
+        assert_eq!(split_instance("db01\\"), ("db01", None));
+        assert_eq!(browser_lookup(&settings), Some(("db01", "SQLEXPRESS")));
+        assert!(build_ado_string(&settings).contains("Server=db01\\SQLEXPRESS;"));
+        assert!(build_jdbc_string(&settings).starts_with("jdbc:sqlserver://db01\\SQLEXPRESS;"));
+
+        settings.port = 50123;
+        assert_eq!(browser_lookup(&settings), None);
+        assert!(build_odbc_string(&settings).contains("Server=tcp:db01\\SQLEXPRESS,50123;"));
+    }
+
+    #[test]
+    fn tls_settings_must_agree() {
+        let strict = ConnectionSettings {
+            trust_cert: false,
+            tls: TlsSettings {
+                strict: true,
+                ..TlsSettings::default()
+            },
+            ..ConnectionSettings::default()
+        };
+        assert!(build_config(&strict).is_ok());
+        let trusting = ConnectionSettings {
+            trust_cert: true,
+            ..strict.clone()
+        };
+        let err = build_config(&trusting).expect_err("trust-all under strict");
+        assert!(err.to_string().contains("trustCert: false"));
+
+        let dir = std::env::temp_dir().join(format!("sscli-tls-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("dir");
+        let bundle = dir.join("bundle.pem");
+        let cert = "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n";
+        fs::write(&bundle, cert.repeat(2)).expect("write");
+        let mut with_ca = ConnectionSettings {
+            trust_cert: false,
+            tls: TlsSettings {
+                ca_cert: Some(bundle.clone()),
+                ..TlsSettings::default()
+            },
+            ..ConnectionSettings::default()
+        };
+        let err = build_config(&with_ca).expect_err("two certs");
+        assert!(err.to_string().contains("holds 2 certificates"));
+        fs::write(&bundle, cert).expect("write");
+        assert!(build_config(&with_ca).is_ok());
+        with_ca.trust_cert = true;
+        assert!(build_config(&with_ca).is_err());
+    }
 }
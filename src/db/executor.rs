@@ -1,17 +1,42 @@
+use std::time::Instant;
+
 use anyhow::Result;
 
-use crate::db::types::{Column, ResultSet, Value};
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::db::audit;
+use crate::db::types::{Column, Decimal, ResultSet, Timestamp, Value};
 use crate::error::{AppError, ErrorKind};
 
 pub async fn run_query(
     query: tiberius::Query<'_>,
     client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
 ) -> Result<Vec<ResultSet>> {
-    let stream = query
-        .query(client)
-        .await
-        .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
-    collect_result_sets(stream).await
+    let audited = audit::enabled().then(|| (audit::query_text(&query), Instant::now()));
+    let result = async {
+        let stream = query
+            .query(client)
+            .await
+            .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
+        collect_result_sets(stream).await
+    }
+    .await;
+    if let Some((statement, started)) = audited {
+        audit::record_statement(
+            &statement,
+            started.elapsed(),
+            result
+                .as_ref()
+                .map(|sets| row_count(sets))
+                .map_err(|err| err.to_string()),
+        );
+    }
+    result
+}
+
+/// Rows across every result set, as the audit log reports them.
+pub fn row_count(result_sets: &[ResultSet]) -> usize {
+    result_sets.iter().map(|set| set.rows.len()).sum()
 }
 
 pub async fn collect_result_sets(stream: tiberius::QueryStream<'_>) -> Result<Vec<ResultSet>> {
@@ -19,6 +44,11 @@ pub async fn collect_result_sets(stream: tiberius::QueryStream<'_>) -> Result<Ve
         .into_results()
         .await
         .map_err(|err| AppError::new(ErrorKind::Query, err.to_string()))?;
+    Ok(convert_results(result_sets))
+}
+
+/// Convert raw tiberius rows into result sets.
+pub fn convert_results(result_sets: Vec<Vec<tiberius::Row>>) -> Vec<ResultSet> {
     let mut output = Vec::new();
 
     for rows in result_sets {
@@ -29,7 +59,7 @@ pub async fn collect_result_sets(stream: tiberius::QueryStream<'_>) -> Result<Ve
                     .iter()
                     .map(|col| Column {
                         name: col.name().to_string(),
-                        data_type: None,
+                        data_type: Some(column_type_name(col.column_type()).to_string()),
                     })
                     .collect::<Vec<_>>()
             })
@@ -37,7 +67,10 @@ pub async fn collect_result_sets(stream: tiberius::QueryStream<'_>) -> Result<Ve
 
         let mut converted_rows = Vec::new();
         for row in rows {
-            let values = row.cells().map(|(_, data)| map_column_data(data)).collect();
+            let values = row
+                .cells()
+                .map(|(column, data)| map_cell(column.column_type(), data))
+                .collect();
             converted_rows.push(values);
         }
 
@@ -47,7 +80,65 @@ pub async fn collect_result_sets(stream: tiberius::QueryStream<'_>) -> Result<Ve
         });
     }
 
-    Ok(output)
+    output
+}
+
+/// SQL Server type family for a result column, as far as the TDS metadata reveals it.
+///
+/// Nullable variants share one wire type, so `smallmoney` reports as `money` and sized
+/// integers as `int` when their width is unknown.
+fn column_type_name(column_type: tiberius::ColumnType) -> &'static str {
+    use tiberius::ColumnType::*;
+    match column_type {
+        Null => "null",
+        Bit | Bitn => "bit",
+        Int1 => "tinyint",
+        Int2 => "smallint",
+        Int4 | Intn => "int",
+        Int8 => "bigint",
+        Float4 => "real",
+        Float8 | Floatn => "float",
+        Money => "money",
+        Money4 => "smallmoney",
+        Guid => "uniqueidentifier",
+        Decimaln | Numericn => "decimal",
+        Datetime4 => "smalldatetime",
+        Datetime | Datetimen => "datetime",
+        Daten => "date",
+        Timen => "time",
+        Datetime2 => "datetime2",
+        DatetimeOffsetn => "datetimeoffset",
+        BigVarBin => "varbinary",
+        BigBinary => "binary",
+        BigVarChar => "varchar",
+        BigChar => "char",
+        NVarchar => "nvarchar",
+        NChar => "nchar",
+        Xml => "xml",
+        Udt => "udt",
+        Text => "text",
+        NText => "ntext",
+        Image => "image",
+        SSVariant => "sql_variant",
+    }
+}
+
+/// `money`/`smallmoney` arrive as doubles; keep the type's fixed four places as a decimal so
+/// JSON and CSV show the digits SQL Server does.
+///
+/// Only values under about 9e11 survive the double exactly. Larger amounts should be cast to
+/// `decimal(19,4)` in the query.
+fn map_cell(column_type: tiberius::ColumnType, data: &tiberius::ColumnData<'_>) -> Value {
+    match (column_type, data) {
+        (
+            tiberius::ColumnType::Money | tiberius::ColumnType::Money4,
+            tiberius::ColumnData::F64(value),
+        ) => value
+            .and_then(|v| format!("{:.4}", v).parse().ok())
+            .map(Value::Decimal)
+            .unwrap_or(Value::Null),
+        _ => map_column_data(data),
+    }
 }
 
 fn map_column_data(data: &tiberius::ColumnData<'_>) -> Value {
@@ -64,17 +155,13 @@ fn map_column_data(data: &tiberius::ColumnData<'_>) -> Value {
             .as_ref()
             .map(|v| Value::Text(v.to_string()))
             .unwrap_or(Value::Null),
-        Guid(value) => value
-            .as_ref()
-            .map(|v| Value::Text(v.to_string()))
-            .unwrap_or(Value::Null),
+        Guid(value) => value.map(Value::Uuid).unwrap_or(Value::Null),
         Binary(value) => value
             .as_ref()
-            .map(|v| Value::Text(format!("{:?}", v)))
+            .map(|v| Value::Bytes(v.to_vec()))
             .unwrap_or(Value::Null),
         Numeric(value) => value
-            .as_ref()
-            .map(|v| Value::Text(v.to_string()))
+            .map(|v| Value::Decimal(Decimal::new(v.value(), v.scale())))
             .unwrap_or(Value::Null),
         Xml(value) => value
             .as_ref()
@@ -84,171 +171,119 @@ fn map_column_data(data: &tiberius::ColumnData<'_>) -> Value {
             .as_ref()
             .map(|v| {
                 // tiberius DateTime: days since 1900-01-01, seconds_fragments in 1/300th seconds
-                let (y, m, d) = days_to_ymd(v.days() as i64);
-                let total_secs = v.seconds_fragments() / 300;
-                let hours = total_secs / 3600;
-                let mins = (total_secs % 3600) / 60;
-                let secs = total_secs % 60;
-                Value::Text(format!(
-                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                    y, m, d, hours, mins, secs
-                ))
+                let millis = (i64::from(v.seconds_fragments()) * 1000 + 150) / 300;
+                naive_datetime(1900, v.days() as i64, millis * 1_000_000)
             })
             .unwrap_or(Value::Null),
         SmallDateTime(value) => value
             .as_ref()
             .map(|v| {
                 // SmallDateTime: days since 1900-01-01, seconds_fragments in minutes
-                let (y, m, d) = days_to_ymd(v.days() as i64);
-                let total_mins = v.seconds_fragments();
-                let hours = total_mins / 60;
-                let mins = total_mins % 60;
-                Value::Text(format!(
-                    "{:04}-{:02}-{:02} {:02}:{:02}:00",
-                    y, m, d, hours, mins
-                ))
+                let nanos = i64::from(v.seconds_fragments()) * 60 * 1_000_000_000;
+                naive_datetime(1900, v.days() as i64, nanos)
             })
             .unwrap_or(Value::Null),
         #[cfg(feature = "tds73")]
         Time(value) => value
-            .map(|v| Value::Text(format_tds_time(v)))
+            .and_then(|v| {
+                let nanos = tds_time_nanos(v);
+                NaiveTime::from_num_seconds_from_midnight_opt(
+                    (nanos / 1_000_000_000) as u32,
+                    (nanos % 1_000_000_000) as u32,
+                )
+            })
+            .map(Value::Time)
             .unwrap_or(Value::Null),
         #[cfg(feature = "tds73")]
         Date(value) => value
-            .map(|v| {
-                let (y, m, d) = days_to_ymd_from_year1(v.days() as i64);
-                Value::Text(format!("{:04}-{:02}-{:02}", y, m, d))
-            })
+            .and_then(|v| date_from_days(1, i64::from(v.days())))
+            .map(Value::Date)
             .unwrap_or(Value::Null),
         #[cfg(feature = "tds73")]
         DateTime2(value) => value
-            .map(|v| {
-                let (y, m, d) = days_to_ymd_from_year1(v.date().days() as i64);
-                let time_str = format_tds_time(v.time());
-                Value::Text(format!("{:04}-{:02}-{:02} {}", y, m, d, time_str))
-            })
+            .map(|v| naive_datetime(1, v.date().days() as i64, tds_time_nanos(v.time())))
             .unwrap_or(Value::Null),
         #[cfg(feature = "tds73")]
         DateTimeOffset(value) => value
             .map(|v| {
-                let (y, m, d) = days_to_ymd_from_year1(v.datetime2().date().days() as i64);
-                let time_str = format_tds_time(v.datetime2().time());
-                let offset_mins = v.offset();
-                let sign = if offset_mins >= 0 { '+' } else { '-' };
-                let abs_mins = offset_mins.abs();
-                Value::Text(format!(
-                    "{:04}-{:02}-{:02} {} {}{:02}:{:02}",
-                    y,
-                    m,
-                    d,
-                    time_str,
-                    sign,
-                    abs_mins / 60,
-                    abs_mins % 60
-                ))
+                // The datetime2 part of a datetimeoffset is already UTC; the offset is
+                // only what the value was written with.
+                let dt = v.datetime2();
+                match naive_datetime(1, dt.date().days() as i64, tds_time_nanos(dt.time())) {
+                    Value::DateTime(ts) => {
+                        Value::DateTime(Timestamp::with_offset(ts.datetime, i32::from(v.offset())))
+                    }
+                    other => other,
+                }
             })
             .unwrap_or(Value::Null),
     }
 }
 
-/// Convert days since 1900-01-01 to (year, month, day)
-fn days_to_ymd(days: i64) -> (i32, u32, u32) {
-    // Start from 1900-01-01
-    let mut year = 1900i32;
-    let mut remaining = days;
-
-    // Fast-forward years
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining < days_in_year {
-            break;
-        }
-        remaining -= days_in_year;
-        year += 1;
-    }
-
-    // Find month
-    let leap = is_leap_year(year);
-    let days_in_months: [i64; 12] = if leap {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1u32;
-    for &dim in &days_in_months {
-        if remaining < dim {
-            break;
-        }
-        remaining -= dim;
-        month += 1;
-    }
-
-    let day = (remaining + 1) as u32;
-    (year, month, day)
+/// The date `days` after Jan 1 of `epoch_year`.
+fn date_from_days(epoch_year: i32, days: i64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(epoch_year, 1, 1)
+        .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(days)))
 }
 
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+/// Build a zone-less timestamp from a day count since Jan 1 of `epoch_year`.
+fn naive_datetime(epoch_year: i32, days: i64, nanos: i64) -> Value {
+    date_from_days(epoch_year, days)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.checked_add_signed(chrono::Duration::nanoseconds(nanos)))
+        .map(|datetime| Value::DateTime(Timestamp::naive(datetime)))
+        .unwrap_or(Value::Null)
 }
 
-/// Convert days since year 1 (Jan 1, year 1) to (year, month, day)
-/// Used for TDS 7.3+ Date/DateTime2/DateTimeOffset types
 #[cfg(feature = "tds73")]
-fn days_to_ymd_from_year1(days: i64) -> (i32, u32, u32) {
-    let mut year = 1i32;
-    let mut remaining = days;
-
-    // Fast-forward years
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining < days_in_year {
-            break;
-        }
-        remaining -= days_in_year;
-        year += 1;
-    }
+fn tds_time_nanos(time: tiberius::time::Time) -> i64 {
+    (time.increments() * 10u64.pow(9 - time.scale() as u32)) as i64
+}
 
-    // Find month
-    let leap = is_leap_year(year);
-    let days_in_months: [i64; 12] = if leap {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1u32;
-    for &dim in &days_in_months {
-        if remaining < dim {
-            break;
-        }
-        remaining -= dim;
-        month += 1;
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiberius::numeric::Numeric;
+    use tiberius::{ColumnData, ColumnType};
 
-    let day = (remaining + 1) as u32;
-    (year, month, day)
-}
+    #[test]
+    fn integers_and_decimals_keep_every_digit() {
+        assert_eq!(
+            map_column_data(&ColumnData::I64(Some(i64::MAX))),
+            Value::Int(i64::MAX)
+        );
+        assert_eq!(
+            map_column_data(&ColumnData::I64(Some(i64::MIN))),
+            Value::Int(i64::MIN)
+        );
+        let numeric = |value: i128, scale: u8| {
+            map_column_data(&ColumnData::Numeric(Some(Numeric::new_with_scale(
+                value, scale,
+            ))))
+        };
+        let max = 10i128.pow(38) - 1;
+        assert_eq!(numeric(max, 0).as_csv(), max.to_string());
+        assert_eq!(
+            numeric(-max, 4).as_csv(),
+            "-9999999999999999999999999999999999.9999"
+        );
+        assert_eq!(numeric(-5, 1).as_csv(), "-0.5");
+        assert_eq!(numeric(7, 3).as_csv(), "0.007");
+        assert_eq!(numeric(0, 2).as_csv(), "0.00");
+    }
 
-/// Format TDS 7.3 Time type to string
-#[cfg(feature = "tds73")]
-fn format_tds_time(time: tiberius::time::Time) -> String {
-    let increments = time.increments();
-    let scale = time.scale();
-    // Convert increments to nanoseconds
-    let nanos = increments * 10u64.pow(9 - scale as u32);
-    let total_secs = nanos / 1_000_000_000;
-    let frac_nanos = nanos % 1_000_000_000;
-    let hours = total_secs / 3600;
-    let mins = (total_secs % 3600) / 60;
-    let secs = total_secs % 60;
-    if frac_nanos > 0 {
-        // Trim trailing zeros from fractional part
-        let frac_str = format!("{:09}", frac_nanos)
-            .trim_end_matches('0')
-            .to_string();
-        format!("{:02}:{:02}:{:02}.{}", hours, mins, secs, frac_str)
-    } else {
-        format!("{:02}:{:02}:{:02}", hours, mins, secs)
+    #[test]
+    fn money_keeps_four_places() {
+        let money = |value: f64| map_cell(ColumnType::Money, &ColumnData::F64(Some(value)));
+        assert_eq!(money(12.5), Value::Decimal(Decimal::new(125_000, 4)));
+        assert_eq!(money(-922337203685.4775).as_csv(), "-922337203685.4775");
+        assert_eq!(
+            map_cell(ColumnType::Money4, &ColumnData::F64(None)),
+            Value::Null
+        );
+        assert_eq!(
+            map_cell(ColumnType::Float8, &ColumnData::F64(Some(12.5))),
+            Value::Float(12.5)
+        );
     }
 }
@@ -0,0 +1,327 @@
+//! Opt-in on-disk cache for catalog queries (`settings.metadataCache`).
+//!
+//! `tables`, `columns`, `stored-procs`, and `describe` read slow-changing catalogs. With the cache
+//! enabled, each of their queries is stored per profile and database, keyed by its SQL text and
+//! parameters, and answered from disk until the TTL runs out. `--refresh` skips the lookup and
+//! rewrites the entry.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tiberius::{Client, Query, Uuid};
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::config::MetadataCacheSettingsResolved;
+use crate::db::executor;
+use crate::db::types::{Column, Decimal, ResultSet, Timestamp, Value};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+static CACHE: OnceLock<Option<MetadataCache>> = OnceLock::new();
+
+/// Where and for how long catalog results are kept for the current connection.
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    dir: PathBuf,
+    server: String,
+    database: String,
+    ttl_secs: u64,
+    refresh: bool,
+}
+
+impl MetadataCache {
+    /// `dir` is the profile's cache directory; entries go under `metadata/<database>/`.
+    pub fn new(
+        settings: &MetadataCacheSettingsResolved,
+        dir: PathBuf,
+        server: &str,
+        database: &str,
+        refresh: bool,
+    ) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        Some(Self {
+            dir: dir.join("metadata").join(file_safe(database)),
+            server: server.to_string(),
+            database: database.to_string(),
+            ttl_secs: settings.ttl_seconds,
+            refresh,
+        })
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+
+    fn read(&self, key: &str, now: u64) -> Option<Vec<ResultSet>> {
+        if self.refresh {
+            return None;
+        }
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        let fresh = now.saturating_sub(entry.generated_at_unix) <= self.ttl_secs;
+        let same = entry.key == key
+            && entry.server.eq_ignore_ascii_case(&self.server)
+            && entry.database.eq_ignore_ascii_case(&self.database);
+        (fresh && same).then(|| entry.result_sets.into_iter().map(Into::into).collect())
+    }
+
+    fn write(&self, key: &str, result_sets: &[ResultSet], now: u64) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            key: key.to_string(),
+            server: self.server.clone(),
+            database: self.database.clone(),
+            generated_at_unix: now,
+            result_sets: result_sets.iter().map(Into::into).collect(),
+        };
+        fs::write(self.entry_path(key), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Install the cache for this process; `None` leaves caching off. Later calls are ignored.
+pub fn configure(cache: Option<MetadataCache>) {
+    let _ = CACHE.set(cache);
+}
+
+fn cache() -> Option<&'static MetadataCache> {
+    CACHE.get().and_then(Option::as_ref)
+}
+
+/// Run a catalog query, answering from the cache when it is enabled and fresh.
+///
+/// Cache read and write failures fall back to the server; they never fail the command.
+pub async fn run_query(
+    query: Query<'_>,
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Vec<ResultSet>> {
+    run_query_when(true, query, client).await
+}
+
+/// [`run_query`] when `cacheable`, otherwise straight to the server. Queries whose results
+/// move quickly (row counts) or whose parameters differ every run (`--modified-since 7d`)
+/// would only fill the cache with entries nothing reads.
+pub async fn run_query_when(
+    cacheable: bool,
+    query: Query<'_>,
+    client: &mut Client<Compat<TcpStream>>,
+) -> Result<Vec<ResultSet>> {
+    let Some(cache) = cache().filter(|_| cacheable) else {
+        return executor::run_query(query, client).await;
+    };
+    // The Debug form carries both the SQL text and the bound parameters.
+    let key = format!("{:?}", query);
+    let now = now_unix();
+    if let Some(result_sets) = cache.read(&key, now) {
+        return Ok(result_sets);
+    }
+    let result_sets = executor::run_query(query, client).await?;
+    if let Err(err) = cache.write(&key, &result_sets, now) {
+        tracing::debug!("metadata cache write failed: {}", err);
+    }
+    Ok(result_sets)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    key: String,
+    server: String,
+    database: String,
+    generated_at_unix: u64,
+    result_sets: Vec<CachedResultSet>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResultSet {
+    columns: Vec<(String, Option<String>)>,
+    rows: Vec<Vec<CachedValue>>,
+}
+
+/// [`Value`] in a form that reads back exactly; `Value`'s own serializer is for output.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "t", content = "v")]
+enum CachedValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Decimal(String, u8),
+    Text(String),
+    DateTime(String, Option<i32>),
+    Date(String),
+    Time(String),
+    Uuid(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<&ResultSet> for CachedResultSet {
+    fn from(rs: &ResultSet) -> Self {
+        Self {
+            columns: rs
+                .columns
+                .iter()
+                .map(|col| (col.name.clone(), col.data_type.clone()))
+                .collect(),
+            rows: rs
+                .rows
+                .iter()
+                .map(|row| row.iter().map(Into::into).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<CachedResultSet> for ResultSet {
+    fn from(rs: CachedResultSet) -> Self {
+        Self {
+            columns: rs
+                .columns
+                .into_iter()
+                .map(|(name, data_type)| Column { name, data_type })
+                .collect(),
+            rows: rs
+                .rows
+                .into_iter()
+                .map(|row| row.into_iter().map(Into::into).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<&Value> for CachedValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => CachedValue::Null,
+            Value::Bool(v) => CachedValue::Bool(*v),
+            Value::Int(v) => CachedValue::Int(*v),
+            Value::Float(v) => CachedValue::Float(*v),
+            Value::Decimal(v) => CachedValue::Decimal(v.value.to_string(), v.scale),
+            Value::Text(v) => CachedValue::Text(v.clone()),
+            Value::DateTime(v) => CachedValue::DateTime(
+                v.datetime.format(DATETIME_FORMAT).to_string(),
+                v.offset_minutes,
+            ),
+            Value::Date(v) => CachedValue::Date(v.to_string()),
+            Value::Time(v) => CachedValue::Time(v.to_string()),
+            Value::Uuid(v) => CachedValue::Uuid(v.to_string()),
+            Value::Bytes(v) => CachedValue::Bytes(v.clone()),
+        }
+    }
+}
+
+impl From<CachedValue> for Value {
+    fn from(value: CachedValue) -> Self {
+        match value {
+            CachedValue::Null => Value::Null,
+            CachedValue::Bool(v) => Value::Bool(v),
+            CachedValue::Int(v) => Value::Int(v),
+            CachedValue::Float(v) => Value::Float(v),
+            CachedValue::Decimal(v, scale) => v
+                .parse()
+                .map_or(Value::Null, |v| Value::Decimal(Decimal::new(v, scale))),
+            CachedValue::Text(v) => Value::Text(v),
+            CachedValue::DateTime(v, offset_minutes) => {
+                NaiveDateTime::parse_from_str(&v, DATETIME_FORMAT).map_or(Value::Null, |datetime| {
+                    Value::DateTime(Timestamp {
+                        datetime,
+                        offset_minutes,
+                    })
+                })
+            }
+            CachedValue::Date(v) => v.parse::<NaiveDate>().map_or(Value::Null, Value::Date),
+            CachedValue::Time(v) => v.parse::<NaiveTime>().map_or(Value::Null, Value::Time),
+            CachedValue::Uuid(v) => v.parse::<Uuid>().map_or(Value::Null, Value::Uuid),
+            CachedValue::Bytes(v) => Value::Bytes(v),
+        }
+    }
+}
+
+/// Stable across builds, unlike `DefaultHasher`, so entries survive an upgrade.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn file_safe(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if safe.is_empty() {
+        "default".to_string()
+    } else {
+        safe
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn cache(dir: &Path, ttl_seconds: u64, refresh: bool) -> MetadataCache {
+        let settings = MetadataCacheSettingsResolved {
+            enabled: true,
+            ttl_seconds,
+        };
+        MetadataCache::new(&settings, dir.to_path_buf(), "db1", "Sales", refresh).expect("cache")
+    }
+
+    #[test]
+    fn entries_round_trip_and_expire() {
+        let dir = std::env::temp_dir().join(format!("sscli-metadata-{}", std::process::id()));
+        let sets = vec![ResultSet {
+            columns: vec![Column {
+                name: "name".to_string(),
+                data_type: Some("nvarchar".to_string()),
+            }],
+            rows: vec![vec![
+                Value::Text("Orders".to_string()),
+                Value::Decimal(Decimal::new(12345, 2)),
+                Value::DateTime(Timestamp::with_offset(
+                    NaiveDateTime::parse_from_str("2024-05-01T12:30:00.5", DATETIME_FORMAT)
+                        .expect("datetime"),
+                    -300,
+                )),
+                Value::Null,
+            ]],
+        }];
+
+        let fresh = cache(&dir, 60, false);
+        fresh.write("SELECT 1", &sets, 1_000).expect("write");
+        assert_eq!(fresh.read("SELECT 1", 1_030), Some(sets.clone()));
+        assert_eq!(fresh.read("SELECT 1", 1_061), None);
+        assert_eq!(fresh.read("SELECT 2", 1_030), None);
+        assert_eq!(cache(&dir, 60, true).read("SELECT 1", 1_030), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn disabled_settings_build_no_cache() {
+        let settings = MetadataCacheSettingsResolved::default();
+        assert!(MetadataCache::new(&settings, PathBuf::from("."), "db1", "Sales", false).is_none());
+    }
+}
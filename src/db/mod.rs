@@ -1,5 +1,11 @@
+pub mod audit;
 pub mod client;
 pub mod connection;
 pub mod executor;
+pub mod metadata_cache;
+pub mod permissions;
+pub mod pool;
+pub mod projection;
 pub mod queries;
+pub mod session;
 pub mod types;
@@ -0,0 +1,250 @@
+//! Permission pre-checks for commands that read server-scoped DMVs.
+//!
+//! Without the right grant SQL Server either fails the query or silently narrows it to the
+//! caller's own rows. Checking first lets commands degrade on purpose and tell the user which
+//! `GRANT` to ask for. [`Right`] models the wider set of rights `permissions check` reports on.
+
+use anyhow::Result;
+use tiberius::Query;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::Value;
+
+pub const VIEW_SERVER_STATE: &str = "VIEW SERVER STATE";
+
+const SERVER_PERMISSION_SQL: &str = r#"
+SELECT
+    CAST(ISNULL(HAS_PERMS_BY_NAME(NULL, NULL, @P1), 0) AS int) AS granted,
+    SUSER_SNAME() AS loginName;
+"#;
+
+/// Outcome of a server-level permission check for the current login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionCheck {
+    pub permission: String,
+    pub login: String,
+    pub granted: bool,
+}
+
+impl PermissionCheck {
+    /// The statement an administrator runs to grant the permission.
+    pub fn grant_statement(&self) -> String {
+        format!("GRANT {} TO {};", self.permission, bracket(&self.login))
+    }
+
+    /// Warning explaining what is missing from the output and how to fix it.
+    pub fn warning(&self, impact: &str) -> String {
+        format!(
+            "{} is not granted to login '{}'; {}. Ask an administrator to run: {}",
+            self.permission,
+            self.login,
+            impact,
+            self.grant_statement()
+        )
+    }
+}
+
+/// Check a server-scoped permission such as [`VIEW_SERVER_STATE`] for the current login.
+pub async fn check_server_permission(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    permission: &str,
+) -> Result<PermissionCheck> {
+    let mut query = Query::new(SERVER_PERMISSION_SQL);
+    query.bind(permission);
+    let result_sets = executor::run_query(query, client).await?;
+    let row = result_sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .cloned()
+        .unwrap_or_default();
+    Ok(PermissionCheck {
+        permission: permission.to_string(),
+        login: row.get(1).map(Value::as_display).unwrap_or_default(),
+        granted: matches!(row.first(), Some(Value::Int(1))),
+    })
+}
+
+/// A right a command depends on: how to test for it and how to grant it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Right {
+    /// Server-level permission such as `VIEW SERVER STATE`.
+    ServerPermission(&'static str),
+    /// Fixed server role membership.
+    ServerRole(&'static str),
+    /// Permission in the connection's database.
+    DatabasePermission(&'static str),
+    /// Role in the connection's database, tested through a permission it confers.
+    DatabaseRole {
+        role: &'static str,
+        permission: &'static str,
+    },
+    /// Role in msdb, tested through `SELECT` on an object it grants.
+    MsdbRole {
+        role: &'static str,
+        object: &'static str,
+    },
+}
+
+impl Right {
+    pub fn describe(&self) -> String {
+        match self {
+            Right::ServerPermission(permission) => permission.to_string(),
+            Right::ServerRole(role) => format!("server role {}", role),
+            Right::DatabasePermission(permission) => format!("{} (database)", permission),
+            Right::DatabaseRole { role, .. } => role.to_string(),
+            Right::MsdbRole { role, .. } => format!("msdb {}", role),
+        }
+    }
+
+    /// T-SQL expression returning 1 when the current login holds the right.
+    fn check_expr(&self) -> String {
+        match self {
+            Right::ServerPermission(permission) => {
+                format!("HAS_PERMS_BY_NAME(NULL, NULL, '{}')", permission)
+            }
+            Right::ServerRole(role) => format!("IS_SRVROLEMEMBER('{}')", role),
+            Right::DatabasePermission(permission) | Right::DatabaseRole { permission, .. } => {
+                format!("HAS_PERMS_BY_NAME(DB_NAME(), 'DATABASE', '{}')", permission)
+            }
+            Right::MsdbRole { object, .. } => {
+                format!(
+                    "HAS_PERMS_BY_NAME('msdb.dbo.{}', 'OBJECT', 'SELECT')",
+                    object
+                )
+            }
+        }
+    }
+
+    /// The statement an administrator runs to grant this right to `principal`.
+    pub fn grant_statement(&self, principal: &Principal) -> String {
+        let login = bracket(&principal.login);
+        match self {
+            Right::ServerPermission(permission) => format!("GRANT {} TO {};", permission, login),
+            Right::ServerRole(role) => {
+                format!("ALTER SERVER ROLE {} ADD MEMBER {};", bracket(role), login)
+            }
+            Right::DatabasePermission(permission) => format!(
+                "USE {}; GRANT {} TO {};",
+                bracket(&principal.database),
+                permission,
+                bracket(&principal.user)
+            ),
+            Right::DatabaseRole { role, .. } => format!(
+                "USE {}; ALTER ROLE {} ADD MEMBER {};",
+                bracket(&principal.database),
+                bracket(role),
+                bracket(&principal.user)
+            ),
+            Right::MsdbRole { role, .. } => format!(
+                "USE [msdb]; IF USER_ID(N'{}') IS NULL CREATE USER {} FOR LOGIN {}; ALTER ROLE {} ADD MEMBER {};",
+                principal.login.replace('\'', "''"),
+                login,
+                login,
+                bracket(role),
+                login
+            ),
+        }
+    }
+}
+
+/// Who the checks ran as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub login: String,
+    pub user: String,
+    pub database: String,
+    pub sysadmin: bool,
+}
+
+/// Test every right in one round trip. Members of sysadmin hold them all.
+pub async fn check_rights(
+    client: &mut tiberius::Client<Compat<TcpStream>>,
+    rights: &[Right],
+) -> Result<(Principal, Vec<bool>)> {
+    let mut sql = String::from(
+        "SELECT SUSER_SNAME() AS loginName, USER_NAME() AS userName, DB_NAME() AS databaseName, \
+         CAST(ISNULL(IS_SRVROLEMEMBER('sysadmin'), 0) AS int) AS isSysadmin",
+    );
+    for (idx, right) in rights.iter().enumerate() {
+        sql.push_str(&format!(
+            ", CAST(ISNULL({}, 0) AS int) AS r{}",
+            right.check_expr(),
+            idx
+        ));
+    }
+    sql.push(';');
+
+    let result_sets = executor::run_query(Query::new(sql), client).await?;
+    let row = result_sets
+        .first()
+        .and_then(|rs| rs.rows.first())
+        .cloned()
+        .unwrap_or_default();
+    let text = |idx: usize| row.get(idx).map(Value::as_display).unwrap_or_default();
+    let principal = Principal {
+        login: text(0),
+        user: text(1),
+        database: text(2),
+        sysadmin: matches!(row.get(3), Some(Value::Int(1))),
+    };
+    let granted = (0..rights.len())
+        .map(|idx| principal.sysadmin || matches!(row.get(4 + idx), Some(Value::Int(1))))
+        .collect();
+    Ok((principal, granted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grant_statement_quotes_login() {
+        let check = PermissionCheck {
+            permission: VIEW_SERVER_STATE.to_string(),
+            login: r"CORP\app]svc".to_string(),
+            granted: false,
+        };
+        assert_eq!(
+            check.grant_statement(),
+            r"GRANT VIEW SERVER STATE TO [CORP\app]]svc];"
+        );
+        assert!(
+            check
+                .warning("usage columns are omitted")
+                .contains("usage columns")
+        );
+    }
+
+    #[test]
+    fn right_grants_target_login_or_database_user() {
+        let principal = Principal {
+            login: "app".to_string(),
+            user: "app_user".to_string(),
+            database: "sales".to_string(),
+            sysadmin: false,
+        };
+        let reader = Right::DatabaseRole {
+            role: "db_datareader",
+            permission: "SELECT",
+        };
+        assert_eq!(
+            reader.grant_statement(&principal),
+            "USE [sales]; ALTER ROLE [db_datareader] ADD MEMBER [app_user];"
+        );
+        assert!(
+            Right::MsdbRole {
+                role: "SQLAgentReaderRole",
+                object: "sysjobs",
+            }
+            .grant_statement(&principal)
+            .contains("CREATE USER [app] FOR LOGIN [app]")
+        );
+        assert_eq!(
+            Right::ServerPermission(VIEW_SERVER_STATE).check_expr(),
+            "HAS_PERMS_BY_NAME(NULL, NULL, 'VIEW SERVER STATE')"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! A small bounded connection pool for fan-out metadata work.
+//!
+//! Commands that issue one round trip per object (`tables --describe`) spend most of their time
+//! waiting on latency. [`Pool`] opens up to `size` connections lazily and hands them out one
+//! task at a time, so a batch runs with bounded parallelism instead of strictly in sequence.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::ConnectionSettings;
+use crate::db::client;
+use crate::db::session::SqlClient;
+
+/// Default number of connections for batch metadata jobs.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+/// Upper bound, so a typo cannot open hundreds of sessions on the server.
+pub const MAX_CONCURRENCY: usize = 16;
+
+pub struct Pool {
+    settings: ConnectionSettings,
+    idle: Mutex<Vec<SqlClient>>,
+    permits: Arc<Semaphore>,
+}
+
+impl Pool {
+    /// Create a pool of at most `size` connections; none are opened until first use.
+    ///
+    /// The pool is shared through an [`Arc`] so checked-out clients can move into spawned tasks.
+    pub fn new(settings: &ConnectionSettings, size: usize) -> Arc<Self> {
+        Arc::new(Self {
+            settings: settings.clone(),
+            idle: Mutex::new(Vec::new()),
+            permits: Arc::new(Semaphore::new(size.clamp(1, MAX_CONCURRENCY))),
+        })
+    }
+
+    /// Wait for a free slot and return an idle connection, opening one if none is idle.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`client::connect`].
+    pub async fn get(self: &Arc<Self>) -> Result<PooledClient> {
+        let permit = self.permits.clone().acquire_owned().await?;
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        let client = match idle {
+            Some(client) => client,
+            None => client::connect(&self.settings).await?,
+        };
+        Ok(PooledClient {
+            pool: Arc::clone(self),
+            client: Some(client),
+            poisoned: false,
+            _permit: permit,
+        })
+    }
+}
+
+/// A checked-out connection, returned to the pool on drop unless it is poisoned.
+pub struct PooledClient {
+    pool: Arc<Pool>,
+    client: Option<SqlClient>,
+    /// Set while work is running and kept when it fails. A connection whose work errored, or
+    /// was dropped mid-query by a timeout, may still have rows or a transaction open.
+    poisoned: bool,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledClient {
+    /// Run `work` on the connection. It goes back to the pool only if `work` returns `Ok`; an
+    /// error, or a `run` future dropped before it finished, closes it instead.
+    pub async fn run<T>(
+        &mut self,
+        work: impl AsyncFnOnce(&mut SqlClient) -> Result<T>,
+    ) -> Result<T> {
+        let client = self
+            .client
+            .as_mut()
+            .expect("pooled client is present until drop");
+        self.poisoned = true;
+        let result = work(client).await;
+        self.poisoned = result.is_err();
+        result
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if self.poisoned {
+            return;
+        }
+        if let (Some(client), Ok(mut idle)) = (self.client.take(), self.pool.idle.lock()) {
+            idle.push(client);
+        }
+    }
+}
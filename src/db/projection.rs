@@ -0,0 +1,327 @@
+//! Columns the driver cannot decode: `sql_variant` and CLR types (`geography`, `geometry`,
+//! `hierarchyid`, user CLR types).
+//!
+//! tiberius aborts on their column metadata, so commands that build their own select list
+//! project them to text with [`SpecialColumn::column_expression`] and type the text again with
+//! [`unwrap_columns`]. `sql_variant` carries its base type in a companion column; spatial values
+//! become WKT, capped at `settings.output.wktMaxLength` characters when set.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use tiberius::Query;
+
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::session::SqlClient;
+use crate::db::types::{ResultSet, Timestamp, Value};
+
+/// `sys.types.system_type_id` of `sql_variant`.
+pub const SQL_VARIANT_TYPE_ID: i64 = 98;
+/// `sys.types.system_type_id` shared by every CLR type.
+pub const CLR_TYPE_ID: i64 = 240;
+
+/// Suffix of the column carrying a `sql_variant`'s base type; removed by [`unwrap_columns`].
+const BASE_TYPE_SUFFIX: &str = "$baseType";
+/// Marks WKT cut at the length cap.
+const TRUNCATED_MARKER: &str = "...";
+
+const DESCRIBE_SQL: &str = r#"
+SELECT d.name, d.system_type_id, d.system_type_name
+FROM sys.dm_exec_describe_first_result_set(@P1, NULL, 0) d
+WHERE d.system_type_id IN (98, 240);
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialKind {
+    Variant,
+    /// `geography` or `geometry`, read as WKT.
+    Spatial,
+    /// Any other CLR type, read through its `ToString()`.
+    Clr,
+}
+
+/// A result column that has to be projected before the driver sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecialColumn {
+    pub name: String,
+    pub type_name: String,
+    pub kind: SpecialKind,
+}
+
+impl SpecialColumn {
+    /// `None` for types the driver reads natively.
+    pub fn classify(name: &str, system_type_id: i64, type_name: &str) -> Option<Self> {
+        let kind = match system_type_id {
+            SQL_VARIANT_TYPE_ID => SpecialKind::Variant,
+            CLR_TYPE_ID if matches!(type_name, "geography" | "geometry") => SpecialKind::Spatial,
+            CLR_TYPE_ID => SpecialKind::Clr,
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            kind,
+        })
+    }
+
+    /// Select-list entry for `quoted` (a bracketed column reference) aliased back to its name.
+    pub fn column_expression(&self, quoted: &str, wkt_max: Option<usize>) -> String {
+        let alias = bracket(&self.name);
+        match self.kind {
+            SpecialKind::Variant => format!(
+                "CASE WHEN SQL_VARIANT_PROPERTY({col}, 'BaseType') IN ('binary', 'varbinary') \
+                 THEN CONVERT(nvarchar(4000), CONVERT(varbinary(8000), {col}), 1) \
+                 ELSE CONVERT(nvarchar(4000), {col}, 126) END AS {alias}, \
+                 CAST(SQL_VARIANT_PROPERTY({col}, 'BaseType') AS nvarchar(128)) AS {base}",
+                col = quoted,
+                alias = alias,
+                base = bracket(&format!("{}{}", self.name, BASE_TYPE_SUFFIX))
+            ),
+            // One extra character tells `unwrap_columns` the text was cut.
+            SpecialKind::Spatial => match wkt_max {
+                Some(max) => format!("LEFT({}.STAsText(), {}) AS {}", quoted, max + 1, alias),
+                None => format!("{}.STAsText() AS {}", quoted, alias),
+            },
+            SpecialKind::Clr => format!("{}.ToString() AS {}", quoted, alias),
+        }
+    }
+
+    /// What to select instead, for error messages about ad-hoc queries.
+    pub fn suggestion(&self) -> String {
+        let quoted = bracket(&self.name);
+        match self.kind {
+            SpecialKind::Variant => format!("CAST({} AS nvarchar(4000))", quoted),
+            SpecialKind::Spatial => format!("{}.STAsText()", quoted),
+            SpecialKind::Clr => format!("{}.ToString()", quoted),
+        }
+    }
+}
+
+/// Columns of the first result set of `sql` that the driver cannot read. Batches the server
+/// cannot describe (temp tables, dynamic SQL) report none.
+pub async fn describe_special_columns(
+    client: &mut SqlClient,
+    sql: &str,
+) -> Result<Vec<SpecialColumn>> {
+    let mut query = Query::new(DESCRIBE_SQL);
+    query.bind(sql);
+    let Ok(sets) = executor::run_query(query, client).await else {
+        return Ok(Vec::new());
+    };
+    Ok(sets
+        .first()
+        .map(|rs| {
+            rs.rows
+                .iter()
+                .filter_map(|row| {
+                    let name = text(row.first())?;
+                    let type_id = match row.get(1) {
+                        Some(Value::Int(id)) => *id,
+                        _ => return None,
+                    };
+                    let type_name = text(row.get(2))?;
+                    SpecialColumn::classify(&name, type_id, &type_name)
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Error for a query whose result includes `columns`, naming what to select instead.
+pub fn unreadable_message(columns: &[SpecialColumn]) -> String {
+    let listed = columns
+        .iter()
+        .map(|column| {
+            format!(
+                "'{}' ({}): select {}",
+                column.name,
+                column.type_name,
+                column.suggestion()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("The driver cannot read these result columns. {}", listed)
+}
+
+/// Restore values projected by [`SpecialColumn::column_expression`]: `sql_variant` text is
+/// parsed back into its base type, cut WKT gets a trailing `...`, and companion columns are
+/// dropped.
+pub fn unwrap_columns(rs: &mut ResultSet, specials: &[SpecialColumn], wkt_max: Option<usize>) {
+    let mut companions = Vec::new();
+    for special in specials {
+        let Some(idx) = rs.columns.iter().position(|c| c.name == special.name) else {
+            continue;
+        };
+        rs.columns[idx].data_type = Some(special.type_name.clone());
+        match special.kind {
+            SpecialKind::Variant => {
+                let base_name = format!("{}{}", special.name, BASE_TYPE_SUFFIX);
+                let Some(base_idx) = rs.columns.iter().position(|c| c.name == base_name) else {
+                    continue;
+                };
+                for row in &mut rs.rows {
+                    let base_type = text(row.get(base_idx)).unwrap_or_default();
+                    if let Some(value) = row.get_mut(idx) {
+                        *value = variant_value(value, &base_type);
+                    }
+                }
+                companions.push(base_idx);
+            }
+            SpecialKind::Spatial => {
+                let Some(max) = wkt_max else {
+                    continue;
+                };
+                for row in &mut rs.rows {
+                    if let Some(Value::Text(wkt)) = row.get_mut(idx) {
+                        if wkt.chars().count() > max {
+                            *wkt = format!(
+                                "{}{}",
+                                wkt.chars().take(max).collect::<String>(),
+                                TRUNCATED_MARKER
+                            );
+                        }
+                    }
+                }
+            }
+            SpecialKind::Clr => {}
+        }
+    }
+    companions.sort_unstable_by(|a, b| b.cmp(a));
+    for idx in companions {
+        rs.columns.remove(idx);
+        for row in &mut rs.rows {
+            if idx < row.len() {
+                row.remove(idx);
+            }
+        }
+    }
+}
+
+/// Parse `CONVERT(nvarchar, value, 126)` text back into the value's base type, typed the way a
+/// column of that type is read.
+fn variant_value(value: &Value, base_type: &str) -> Value {
+    let Value::Text(raw) = value else {
+        return value.clone();
+    };
+    let parsed = match base_type {
+        "tinyint" | "smallint" | "int" | "bigint" => raw.parse::<i64>().ok().map(Value::Int),
+        "bit" => match raw.as_str() {
+            "1" => Some(Value::Bool(true)),
+            "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        "float" | "real" => raw.parse::<f64>().ok().map(Value::Float),
+        "decimal" | "numeric" | "money" | "smallmoney" => raw.parse().ok().map(Value::Decimal),
+        "date" => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .ok()
+            .map(Value::Date),
+        "time" => NaiveTime::parse_from_str(raw, "%H:%M:%S%.f")
+            .ok()
+            .map(Value::Time),
+        "uniqueidentifier" => raw.parse().ok().map(Value::Uuid),
+        "datetime" | "datetime2" | "smalldatetime" => {
+            NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|dt| Value::DateTime(Timestamp::naive(dt)))
+        }
+        "datetimeoffset" => DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f%:z")
+            .ok()
+            .map(|dt| {
+                Value::DateTime(Timestamp::with_offset(
+                    dt.naive_utc(),
+                    dt.offset().local_minus_utc() / 60,
+                ))
+            }),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| value.clone())
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    match value {
+        Some(Value::Text(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DisplayTimeZone;
+    use crate::db::types::{Column, Decimal};
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: Some("nvarchar".to_string()),
+        }
+    }
+
+    #[test]
+    fn special_columns_project_to_text() {
+        let location = SpecialColumn::classify("Location", CLR_TYPE_ID, "geography").unwrap();
+        assert_eq!(
+            location.column_expression("[Location]", Some(50)),
+            "LEFT([Location].STAsText(), 51) AS [Location]"
+        );
+        let node = SpecialColumn::classify("Node", CLR_TYPE_ID, "hierarchyid").unwrap();
+        assert_eq!(
+            node.column_expression("[Node]", None),
+            "[Node].ToString() AS [Node]"
+        );
+        let setting = SpecialColumn::classify("Value", SQL_VARIANT_TYPE_ID, "sql_variant").unwrap();
+        assert!(
+            setting
+                .column_expression("[Value]", None)
+                .ends_with("AS [Value$baseType]")
+        );
+        assert!(SpecialColumn::classify("Id", 56, "int").is_none());
+        assert_eq!(
+            unreadable_message(&[location]),
+            "The driver cannot read these result columns. 'Location' (geography): select [Location].STAsText()"
+        );
+    }
+
+    #[test]
+    fn variants_unwrap_to_base_types_and_wkt_is_capped() {
+        let specials = [
+            SpecialColumn::classify("Value", SQL_VARIANT_TYPE_ID, "sql_variant").unwrap(),
+            SpecialColumn::classify("Shape", CLR_TYPE_ID, "geometry").unwrap(),
+        ];
+        let text = |s: &str| Value::Text(s.to_string());
+        let mut rs = ResultSet {
+            columns: vec![column("Value"), column("Value$baseType"), column("Shape")],
+            rows: vec![
+                vec![text("42"), text("int"), text("POINT (1 2)")],
+                vec![text("1.5E+0"), text("float"), text("LINESTRING (0 0, 1 1)")],
+                vec![
+                    text("2024-05-01T12:30:00.1230000+02:00"),
+                    text("datetimeoffset"),
+                    Value::Null,
+                ],
+                vec![text("12.5000"), text("money"), Value::Null],
+                vec![Value::Null, Value::Null, Value::Null],
+            ],
+        };
+        unwrap_columns(&mut rs, &specials, Some(11));
+
+        assert_eq!(rs.columns.len(), 2);
+        assert_eq!(rs.columns[0].data_type.as_deref(), Some("sql_variant"));
+        assert_eq!(rs.columns[1].data_type.as_deref(), Some("geometry"));
+        assert_eq!(rs.rows[0], vec![Value::Int(42), text("POINT (1 2)")]);
+        assert_eq!(rs.rows[1], vec![Value::Float(1.5), text("LINESTRING ...")]);
+        match &rs.rows[2][0] {
+            Value::DateTime(ts) => {
+                assert_eq!(
+                    ts.to_iso8601_in(DisplayTimeZone::Utc, DisplayTimeZone::Original),
+                    "2024-05-01T10:30:00.123Z"
+                );
+                assert_eq!(ts.offset_minutes, Some(120));
+            }
+            other => panic!("expected datetimeoffset, got {:?}", other),
+        }
+        assert_eq!(rs.rows[3][0], Value::Decimal(Decimal::new(125_000, 4)));
+        assert_eq!(rs.rows[4], vec![Value::Null, Value::Null]);
+    }
+}
@@ -33,3 +33,8 @@ FROM sys.foreign_keys fk
 JOIN sys.tables tp ON fk.parent_object_id = tp.object_id
 JOIN sys.tables tr ON fk.referenced_object_id = tr.object_id
 "#;
+
+/// `[name]` with any `]` doubled: a catalog or user-supplied name quoted for T-SQL.
+pub fn bracket(name: &str) -> String {
+    format!("[{}]", name.replace(']', "]]"))
+}
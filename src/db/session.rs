@@ -0,0 +1,248 @@
+//! A connection that survives dropped TCP sessions.
+//!
+//! Long-running commands hold one connection across many queries. When the server or network
+//! drops it, [`Session`] reconnects, replays the database context and `SET` options it has
+//! seen, and retries the failed read once. Each recovery is recorded as a warning so callers can
+//! surface it instead of failing the whole command.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use tiberius::Query;
+use tiberius::error::{Error as TdsError, IoErrorKind};
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use crate::config::ConnectionSettings;
+use crate::db::audit;
+use crate::db::client;
+use crate::db::executor;
+use crate::db::queries::bracket;
+use crate::db::types::ResultSet;
+use crate::error::{AppError, ErrorKind};
+
+pub type SqlClient = tiberius::Client<Compat<TcpStream>>;
+
+/// Session state that must be replayed on a fresh connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionState {
+    pub database: Option<String>,
+    pub set_options: Vec<String>,
+}
+
+pub struct Session {
+    settings: ConnectionSettings,
+    client: SqlClient,
+    state: SessionState,
+    warnings: Vec<String>,
+}
+
+impl Session {
+    /// Open a connection using resolved settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`client::connect`].
+    pub async fn connect(settings: &ConnectionSettings) -> Result<Self> {
+        let client = client::connect(settings).await?;
+        Ok(Self {
+            settings: settings.clone(),
+            client,
+            state: SessionState::default(),
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Switch database context and remember it for reconnects.
+    pub async fn use_database(&mut self, database: &str) -> Result<()> {
+        let statement = format!("USE {}", bracket(database));
+        self.execute_batch(&statement).await?;
+        self.state.database = Some(database.to_string());
+        Ok(())
+    }
+
+    /// Apply a `SET ...` statement and remember it for reconnects.
+    pub async fn set_option(&mut self, statement: &str) -> Result<()> {
+        let statement = statement.trim().trim_end_matches(';').to_string();
+        if !statement.to_ascii_uppercase().starts_with("SET ") {
+            return Err(AppError::new(
+                ErrorKind::Query,
+                format!("Expected a SET statement, got: {}", statement),
+            )
+            .into());
+        }
+        self.execute_batch(&statement).await?;
+        self.state.set_options.push(statement);
+        Ok(())
+    }
+
+    /// Run a read-only query, reconnecting and retrying once if the connection was lost.
+    ///
+    /// `build` is called again for the retry because a bound [`Query`] is consumed on use.
+    pub async fn query_read<'a, F>(&mut self, build: F) -> Result<Vec<ResultSet>>
+    where
+        F: Fn() -> Query<'a>,
+    {
+        match self.try_query(build()).await {
+            Ok(sets) => Ok(sets),
+            Err(err) if is_connection_lost(&err) => {
+                self.reconnect(&err).await?;
+                self.try_query(build()).await.map_err(query_error)
+            }
+            Err(err) => Err(query_error(err)),
+        }
+    }
+
+    /// Run a query that may write, without the reconnect-and-retry of [`Session::query_read`]:
+    /// when the connection drops mid-batch the server may already have committed it, so running
+    /// it again could apply the write twice.
+    pub async fn query_once(&mut self, query: Query<'_>) -> Result<Vec<ResultSet>> {
+        self.try_query(query).await.map_err(query_error)
+    }
+
+    /// The underlying client, for one-off calls that do not need reconnect handling.
+    pub fn client_mut(&mut self) -> &mut SqlClient {
+        &mut self.client
+    }
+
+    pub fn state(&self) -> &SessionState {
+        &self.state
+    }
+
+    /// Warnings recorded by reconnects, oldest first.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    async fn try_query(
+        &mut self,
+        query: Query<'_>,
+    ) -> std::result::Result<Vec<ResultSet>, TdsError> {
+        let audited = audit::enabled().then(|| (audit::query_text(&query), Instant::now()));
+        let result = async {
+            let stream = query.query(&mut self.client).await?;
+            let rows = stream.into_results().await?;
+            Ok::<_, TdsError>(executor::convert_results(rows))
+        }
+        .await;
+        if let Some((statement, started)) = audited {
+            audit::record_statement(
+                &statement,
+                started.elapsed(),
+                result
+                    .as_ref()
+                    .map(|sets| executor::row_count(sets))
+                    .map_err(|err| err.to_string()),
+            );
+        }
+        result
+    }
+
+    async fn execute_batch(&mut self, statement: &str) -> Result<()> {
+        match run_simple(&mut self.client, statement).await {
+            Ok(()) => Ok(()),
+            Err(err) if is_connection_lost(&err) => {
+                self.reconnect(&err).await?;
+                run_simple(&mut self.client, statement)
+                    .await
+                    .map_err(query_error)
+            }
+            Err(err) => Err(query_error(err)),
+        }
+    }
+
+    async fn reconnect(&mut self, cause: &TdsError) -> Result<()> {
+        self.client = client::connect(&self.settings).await.map_err(|err| {
+            AppError::new(
+                ErrorKind::Connection,
+                format!("Connection lost ({}) and reconnect failed: {}", cause, err),
+            )
+        })?;
+        for statement in replay_statements(&self.state) {
+            run_simple(&mut self.client, &statement)
+                .await
+                .map_err(query_error)?;
+        }
+        self.warnings.push(format!(
+            "Connection lost ({}); reconnected and restored session state",
+            cause
+        ));
+        Ok(())
+    }
+}
+
+/// Run a statement whose results are not needed, recording it in the audit log.
+async fn run_simple(client: &mut SqlClient, statement: &str) -> std::result::Result<(), TdsError> {
+    let started = Instant::now();
+    let result = match client.simple_query(statement).await {
+        Ok(stream) => stream.into_results().await.map(|_| ()),
+        Err(err) => Err(err),
+    };
+    audit::record_statement(
+        statement,
+        started.elapsed(),
+        result.as_ref().map(|_| 0).map_err(|err| err.to_string()),
+    );
+    result
+}
+
+/// Statements that rebuild `state` on a new connection, database context first.
+fn replay_statements(state: &SessionState) -> Vec<String> {
+    let mut statements = Vec::new();
+    if let Some(database) = &state.database {
+        statements.push(format!("USE {}", bracket(database)));
+    }
+    statements.extend(state.set_options.iter().cloned());
+    statements
+}
+
+fn is_connection_lost(err: &TdsError) -> bool {
+    match err {
+        TdsError::Io { kind, .. } => matches!(
+            kind,
+            IoErrorKind::ConnectionReset
+                | IoErrorKind::ConnectionAborted
+                | IoErrorKind::BrokenPipe
+                | IoErrorKind::UnexpectedEof
+                | IoErrorKind::NotConnected
+                | IoErrorKind::TimedOut
+        ),
+        TdsError::Protocol(message) => message.contains("closed"),
+        _ => false,
+    }
+}
+
+fn query_error(err: TdsError) -> anyhow::Error {
+    AppError::new(ErrorKind::Query, err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_database_before_set_options() {
+        let state = SessionState {
+            database: Some("sales]db".to_string()),
+            set_options: vec!["SET LOCK_TIMEOUT 5000".to_string()],
+        };
+        assert_eq!(
+            replay_statements(&state),
+            vec!["USE [sales]]db]", "SET LOCK_TIMEOUT 5000"]
+        );
+    }
+
+    #[test]
+    fn classifies_dropped_connections() {
+        let reset = TdsError::Io {
+            kind: IoErrorKind::ConnectionReset,
+            message: "reset".to_string(),
+        };
+        assert!(is_connection_lost(&reset));
+        assert!(!is_connection_lost(&TdsError::Protocol("bad token".into())));
+    }
+}
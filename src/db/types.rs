@@ -1,13 +1,91 @@
-use serde::Serialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
-#[serde(untagged)]
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Serialize, Serializer};
+use tiberius::Uuid;
+
+use crate::config::{BinaryFormat, DisplayTimeZone, JsonLargeIntegers, format_offset};
+
+static DISPLAY_TIME_ZONE: OnceLock<DisplayTimeZone> = OnceLock::new();
+static SOURCE_TIME_ZONE: OnceLock<DisplayTimeZone> = OnceLock::new();
+static JSON_LARGE_INTEGERS: OnceLock<JsonLargeIntegers> = OnceLock::new();
+static BINARY_FORMAT: OnceLock<BinaryFormat> = OnceLock::new();
+
+/// Largest magnitude every IEEE double holds exactly (2^53 - 1).
+pub const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+/// Set the zone [`Value::DateTime`] values are shown in, in tables, JSON, and CSV.
+///
+/// Values format themselves without a session at hand, so the zone is held here;
+/// `common::load_config` sets it before the first row is fetched and ignores later calls.
+pub fn set_display_time_zone(zone: DisplayTimeZone) {
+    let _ = DISPLAY_TIME_ZONE.set(zone);
+}
+
+fn display_time_zone() -> DisplayTimeZone {
+    DISPLAY_TIME_ZONE
+        .get()
+        .copied()
+        .unwrap_or(DisplayTimeZone::Original)
+}
+
+/// Set the zone `datetime`/`datetime2`/`smalldatetime` values were written in, so they can be
+/// converted like `datetimeoffset` values. `None` leaves them as stored. Set together with
+/// [`set_display_time_zone`], and like it only the first value is kept.
+pub fn set_source_time_zone(zone: Option<DisplayTimeZone>) {
+    let _ = SOURCE_TIME_ZONE.set(zone.unwrap_or(DisplayTimeZone::Original));
+}
+
+fn source_time_zone() -> DisplayTimeZone {
+    SOURCE_TIME_ZONE
+        .get()
+        .copied()
+        .unwrap_or(DisplayTimeZone::Original)
+}
+
+/// Set how [`Value::Int`] serializes when it is beyond [`MAX_SAFE_INTEGER`]
+/// (`settings.output.json.largeIntegers`). Unset, such integers are written as strings, which
+/// no JSON consumer can round to a different number.
+pub fn set_json_large_integers(mode: JsonLargeIntegers) {
+    let _ = JSON_LARGE_INTEGERS.set(mode);
+}
+
+fn json_large_integers() -> JsonLargeIntegers {
+    JSON_LARGE_INTEGERS
+        .get()
+        .copied()
+        .unwrap_or(JsonLargeIntegers::String)
+}
+
+/// Set how [`Value::Bytes`] is written in tables, JSON, and CSV (`--binary`). Unset means hex;
+/// the first call fixes the encoding for every table, JSON document, and CSV file of the run.
+pub fn set_binary_format(format: BinaryFormat) {
+    let _ = BINARY_FORMAT.set(format);
+}
+
+fn binary_format() -> BinaryFormat {
+    BINARY_FORMAT.get().copied().unwrap_or(BinaryFormat::Hex)
+}
+
+/// A cell value, typed closely enough that JSON and CSV need no re-parsing downstream.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Null,
     Bool(bool),
     Int(i64),
     Float(f64),
+    /// `decimal`/`numeric`/`money`, kept exact rather than squeezed through a double.
+    Decimal(Decimal),
     Text(String),
+    DateTime(Timestamp),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Uuid(Uuid),
+    /// `binary`/`varbinary`/`image`/`timestamp`, encoded per [`set_binary_format`].
+    Bytes(Vec<u8>),
 }
 
 impl Value {
@@ -17,7 +95,14 @@ impl Value {
             Value::Bool(value) => value.to_string(),
             Value::Int(value) => format_number(*value),
             Value::Float(value) => value.to_string(),
+            Value::Decimal(value) => value.to_string(),
             Value::Text(value) => value.clone(),
+            Value::DateTime(value) => value.display_in(display_time_zone(), source_time_zone()),
+            Value::Date(value) => value.to_string(),
+            Value::Time(value) => value.format(TIME_FORMAT).to_string(),
+            Value::Uuid(value) => value.to_string(),
+            Value::Bytes(value) => encode_bytes(value, binary_format(), None)
+                .unwrap_or_else(|| format!("<{} bytes>", format_number(value.len() as i64))),
         }
     }
 
@@ -27,9 +112,300 @@ impl Value {
             Value::Bool(value) => value.to_string(),
             Value::Int(value) => value.to_string(),
             Value::Float(value) => value.to_string(),
+            Value::Decimal(value) => value.to_string(),
             Value::Text(value) => value.clone(),
+            Value::DateTime(value) => value.to_iso8601(),
+            Value::Date(value) => value.to_string(),
+            Value::Time(value) => value.format(TIME_FORMAT).to_string(),
+            Value::Uuid(value) => value.to_string(),
+            Value::Bytes(value) => encode_bytes(value, binary_format(), None).unwrap_or_default(),
+        }
+    }
+
+    /// Numeric value as a double, parsing text; `None` for non-numbers.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            Value::Decimal(value) => Some(value.to_f64()),
+            Value::Text(value) => value.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// This value with text or binary over `max` bytes cut short and marked, for output under
+    /// `--max-value-bytes`; a cut binary value becomes its encoded text. Borrowed when nothing
+    /// is cut.
+    pub fn capped(&self, max: Option<usize>) -> Cow<'_, Value> {
+        match (self, max) {
+            (Value::Text(value), Some(max)) if value.len() > max => {
+                Cow::Owned(Value::Text(cap_text(value, max)))
+            }
+            (Value::Bytes(value), Some(max)) if value.len() > max => {
+                match encode_bytes(value, binary_format(), Some(max)) {
+                    Some(encoded) => Cow::Owned(Value::Text(encoded)),
+                    None => Cow::Borrowed(self),
+                }
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Size of a text or binary value as stored on output, in bytes.
+    pub fn byte_len(&self) -> Option<usize> {
+        match self {
+            Value::Text(value) => Some(value.len()),
+            Value::Bytes(value) => Some(value.len()),
+            _ => None,
+        }
+    }
+}
+
+/// `time` values and the time of day in timestamps: seconds, then only the digits stored.
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+/// Marker appended to a value cut by `--max-value-bytes`, so truncation is never silent.
+fn truncation_marker(total: usize) -> String {
+    format!("... ({} bytes)", format_number(total as i64))
+}
+
+fn cap_text(value: &str, max: usize) -> String {
+    if value.len() <= max {
+        return value.to_string();
+    }
+    let mut end = max;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &value[..end], truncation_marker(value.len()))
+}
+
+/// `None` when binary values are skipped.
+fn encode_bytes(value: &[u8], format: BinaryFormat, max: Option<usize>) -> Option<String> {
+    let shown = &value[..max.map_or(value.len(), |max| max.min(value.len()))];
+    let mut encoded = match format {
+        BinaryFormat::Skip => return None,
+        BinaryFormat::Hex => {
+            let mut out = String::with_capacity(2 + shown.len() * 2);
+            out.push_str("0x");
+            for byte in shown {
+                out.push_str(&format!("{:02X}", byte));
+            }
+            out
+        }
+        BinaryFormat::Base64 => base64(shown),
+    };
+    if shown.len() < value.len() {
+        encoded.push_str(&truncation_marker(value.len()));
+    }
+    Some(encoded)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (idx, byte)| {
+            word | u32::from(*byte) << (16 - idx * 8)
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                out.push(ALPHABET[(word >> (18 - idx * 6)) as usize & 0x3F] as char);
+            } else {
+                out.push('=');
+            }
         }
     }
+    out
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Int(value) => {
+                if value.unsigned_abs() > MAX_SAFE_INTEGER as u64
+                    && json_large_integers() == JsonLargeIntegers::String
+                {
+                    serializer.serialize_str(&value.to_string())
+                } else {
+                    serializer.serialize_i64(*value)
+                }
+            }
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::Decimal(value) => value.serialize(serializer),
+            Value::Text(value) => serializer.serialize_str(value),
+            Value::DateTime(value) => value.serialize(serializer),
+            Value::Date(_) | Value::Time(_) | Value::Uuid(_) => {
+                serializer.serialize_str(&self.as_csv())
+            }
+            Value::Bytes(value) => match encode_bytes(value, binary_format(), None) {
+                Some(encoded) => serializer.serialize_str(&encoded),
+                None => serializer.serialize_none(),
+            },
+        }
+    }
+}
+
+/// An exact decimal: `value` scaled down by ten to the power `scale`, as SQL Server stores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    pub value: i128,
+    pub scale: u8,
+}
+
+/// Significant digits every double reproduces exactly.
+const DOUBLE_DIGITS: usize = 15;
+
+impl Decimal {
+    pub fn new(value: i128, scale: u8) -> Self {
+        Self { value, scale }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Whether a JSON number parsed as a double gives back every digit.
+    fn fits_double(&self) -> bool {
+        let digits = self.value.unsigned_abs().to_string();
+        digits.trim_matches('0').len() <= DOUBLE_DIGITS
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = usize::from(self.scale);
+        let digits = self.value.unsigned_abs().to_string();
+        let sign = if self.value < 0 { "-" } else { "" };
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    /// Plain decimal text such as `-12.5000`; the scale is the number of digits after the point.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (int_part, frac_part) = text.split_once('.').unwrap_or((text, ""));
+        let unsigned = int_part.trim_start_matches(['-', '+']);
+        let valid = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (unsigned.is_empty() && frac_part.is_empty()) || !valid(unsigned) || !valid(frac_part) {
+            return Err(format!("'{}' is not a decimal number", text));
+        }
+        let scale = u8::try_from(frac_part.len())
+            .map_err(|_| format!("'{}' has too many decimal places", text))?;
+        let value = format!("{}{}", unsigned, frac_part)
+            .parse::<i128>()
+            .map_err(|_| format!("'{}' is out of range", text))?;
+        let value = if int_part.starts_with('-') {
+            -value
+        } else {
+            value
+        };
+        Ok(Self { value, scale })
+    }
+}
+
+/// A JSON number when a double holds it exactly; longer values follow `json.largeIntegers`.
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.fits_double() && json_large_integers() == JsonLargeIntegers::String {
+            return serializer.serialize_str(&self.to_string());
+        }
+        let unit = 10i128.pow(u32::from(self.scale));
+        match i64::try_from(self.value / unit) {
+            Ok(whole) if self.value % unit == 0 => serializer.serialize_i64(whole),
+            _ => serializer.serialize_f64(self.to_f64()),
+        }
+    }
+}
+
+/// A `datetime`/`datetime2`/`smalldatetime`/`datetimeoffset` value.
+///
+/// For `datetimeoffset`, `datetime` holds the UTC instant and `offset_minutes` the offset
+/// it was stored with. The other types carry no zone, so they are kept as wall-clock time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp {
+    pub datetime: NaiveDateTime,
+    pub offset_minutes: Option<i32>,
+}
+
+impl Timestamp {
+    pub fn naive(datetime: NaiveDateTime) -> Self {
+        Self {
+            datetime,
+            offset_minutes: None,
+        }
+    }
+
+    pub fn with_offset(utc: NaiveDateTime, offset_minutes: i32) -> Self {
+        Self {
+            datetime: utc,
+            offset_minutes: Some(offset_minutes),
+        }
+    }
+
+    /// ISO-8601 in the configured display zone, such as `2024-05-01T07:30:00-05:00`; values
+    /// without a zone stay as stored (`2024-05-01T12:30:00`).
+    pub fn to_iso8601(&self) -> String {
+        self.to_iso8601_in(display_time_zone(), source_time_zone())
+    }
+
+    /// ISO-8601 in `zone`, with naive values read as wall-clock time in `source`. UTC ends
+    /// in `Z`.
+    pub fn to_iso8601_in(&self, zone: DisplayTimeZone, source: DisplayTimeZone) -> String {
+        let (local, offset) = self.local_in(zone, source);
+        let base = local.format("%Y-%m-%dT%H:%M:%S%.f").to_string();
+        match offset {
+            Some(0) => format!("{}Z", base),
+            Some(minutes) => format!("{}{}", base, format_offset(minutes)),
+            None => base,
+        }
+    }
+
+    /// Human-readable form in `zone`, such as `2024-05-01 07:30:00 -05:00`.
+    pub fn display_in(&self, zone: DisplayTimeZone, source: DisplayTimeZone) -> String {
+        let (local, offset) = self.local_in(zone, source);
+        let base = local.format("%Y-%m-%d %H:%M:%S%.f").to_string();
+        match offset {
+            Some(minutes) => format!("{} {}", base, format_offset(minutes)),
+            None => base,
+        }
+    }
+
+    /// Wall-clock time and offset in `zone`. `original` keeps the stored offset; values with
+    /// no zone of their own and no `source` zone are returned as stored.
+    fn local_in(
+        &self,
+        zone: DisplayTimeZone,
+        source: DisplayTimeZone,
+    ) -> (NaiveDateTime, Option<i32>) {
+        let zoned = match self.offset_minutes {
+            Some(stored) => Some((self.datetime, stored)),
+            None => source
+                .offset_minutes_for_local(self.datetime)
+                .map(|stored| (self.datetime - Duration::minutes(i64::from(stored)), stored)),
+        };
+        let Some((utc, stored)) = zoned else {
+            return (self.datetime, None);
+        };
+        let offset = zone.offset_minutes_at(utc).unwrap_or(stored);
+        (utc + Duration::minutes(i64::from(offset)), Some(offset))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_iso8601())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -47,8 +423,42 @@ pub struct ResultSet {
     pub rows: Vec<Vec<Value>>,
 }
 
+impl ResultSet {
+    /// Every cell through [`Value::capped`]; borrowed when there is no cap.
+    pub fn capped(&self, max: Option<usize>) -> Cow<'_, ResultSet> {
+        if max.is_none() {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(ResultSet {
+            columns: self.columns.clone(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|value| value.capped(max).into_owned())
+                        .collect()
+                })
+                .collect(),
+        })
+    }
+
+    /// [`ResultSet::capped`], taking the set so one that needs no cut is not copied.
+    pub fn into_capped(self, max: Option<usize>) -> ResultSet {
+        match max {
+            Some(_) => self.capped(max).into_owned(),
+            None => self,
+        }
+    }
+}
+
 fn format_number(value: i64) -> String {
-    let digits = value.abs().to_string().chars().rev().collect::<Vec<_>>();
+    let digits = value
+        .unsigned_abs()
+        .to_string()
+        .chars()
+        .rev()
+        .collect::<Vec<_>>();
     let mut out = String::new();
     for (idx, ch) in digits.iter().enumerate() {
         if idx > 0 && idx % 3 == 0 {
@@ -67,9 +477,168 @@ fn format_number(value: i64) -> String {
 mod tests {
     use super::*;
 
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").expect("datetime")
+    }
+
+    #[test]
+    fn offset_timestamps_serialize_with_their_offset() {
+        let ts = Timestamp::with_offset(at("2024-03-10 07:30:00.5"), -300);
+        assert_eq!(
+            serde_json::to_value(Value::DateTime(ts)).unwrap(),
+            "2024-03-10T02:30:00.500-05:00"
+        );
+        let original = DisplayTimeZone::Original;
+        assert_eq!(
+            ts.to_iso8601_in(DisplayTimeZone::Utc, original),
+            "2024-03-10T07:30:00.500Z"
+        );
+        let naive = Timestamp::naive(at("2024-03-10 07:30:00"));
+        assert_eq!(naive.to_iso8601(), "2024-03-10T07:30:00");
+    }
+
+    #[test]
+    fn timestamps_display_in_requested_zone() {
+        let original = DisplayTimeZone::Original;
+        let ts = Timestamp::with_offset(at("2024-03-10 07:30:00"), -300);
+        assert_eq!(
+            ts.display_in(original, original),
+            "2024-03-10 02:30:00 -05:00"
+        );
+        assert_eq!(
+            ts.display_in(DisplayTimeZone::Utc, original),
+            "2024-03-10 07:30:00 +00:00"
+        );
+        assert_eq!(
+            ts.display_in(DisplayTimeZone::Fixed(330), original),
+            "2024-03-10 13:00:00 +05:30"
+        );
+        let naive = Timestamp::naive(at("2024-03-10 07:30:00"));
+        assert_eq!(
+            naive.display_in(DisplayTimeZone::Utc, original),
+            "2024-03-10 07:30:00"
+        );
+        // With a source zone, naive values convert like offset-aware ones.
+        assert_eq!(
+            naive.to_iso8601_in(DisplayTimeZone::Fixed(-300), DisplayTimeZone::Utc),
+            "2024-03-10T02:30:00-05:00"
+        );
+        assert_eq!(
+            naive.display_in(original, DisplayTimeZone::Fixed(60)),
+            "2024-03-10 07:30:00 +01:00"
+        );
+    }
+
+    #[test]
+    fn named_zones_follow_daylight_saving() {
+        let chicago = "America/Chicago"
+            .parse::<DisplayTimeZone>()
+            .expect("chrono-tz compiles the zone database in");
+        let original = DisplayTimeZone::Original;
+        let winter = Timestamp::with_offset(at("2024-01-15 18:00:00"), 0);
+        let summer = Timestamp::with_offset(at("2024-07-15 18:00:00"), 0);
+        assert_eq!(
+            winter.to_iso8601_in(chicago, original),
+            "2024-01-15T12:00:00-06:00"
+        );
+        assert_eq!(
+            summer.to_iso8601_in(chicago, original),
+            "2024-07-15T13:00:00-05:00"
+        );
+        let naive = Timestamp::naive(at("2024-07-15 13:00:00"));
+        assert_eq!(
+            naive.to_iso8601_in(DisplayTimeZone::Utc, chicago),
+            "2024-07-15T18:00:00Z"
+        );
+    }
+
+    #[test]
+    fn typed_values_serialize_without_reparsing() {
+        let json = |value: Value| serde_json::to_value(value).unwrap();
+        assert_eq!(json(Value::Decimal(Decimal::new(125_000, 4))), 12.5);
+        assert_eq!(json(Value::Decimal(Decimal::new(-5, 1))), -0.5);
+        assert_eq!(json(Value::Decimal(Decimal::new(4200, 2))), 42);
+        let long = Decimal::new(10i128.pow(20) + 1, 2);
+        assert_eq!(json(Value::Decimal(long)), "1000000000000000000.01");
+        assert_eq!(Value::Decimal(Decimal::new(125_000, 4)).as_csv(), "12.5000");
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).expect("date");
+        assert_eq!(json(Value::Date(date)), "2024-02-29");
+        let time = NaiveTime::from_hms_nano_opt(23, 59, 1, 500_000_000).expect("time");
+        assert_eq!(json(Value::Time(time)), "23:59:01.500");
+        let guid: Uuid = "6f9619ff-8b86-d011-b42d-00c04fc964ff"
+            .parse()
+            .expect("uuid");
+        assert_eq!(
+            json(Value::Uuid(guid)),
+            "6f9619ff-8b86-d011-b42d-00c04fc964ff"
+        );
+    }
+
+    #[test]
+    fn decimals_parse_exact_text() {
+        assert_eq!("-12.5000".parse(), Ok(Decimal::new(-125_000, 4)));
+        assert_eq!(".5".parse(), Ok(Decimal::new(5, 1)));
+        assert_eq!("42".parse(), Ok(Decimal::new(42, 0)));
+        assert!("1e5".parse::<Decimal>().is_err());
+        assert!("-".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn binary_values_encode_and_cap() {
+        let bytes = [0xDEu8, 0xAD, 0xBE, 0xEF, 0x01];
+        assert_eq!(
+            encode_bytes(&bytes, BinaryFormat::Hex, None).as_deref(),
+            Some("0xDEADBEEF01")
+        );
+        assert_eq!(
+            encode_bytes(&bytes, BinaryFormat::Base64, None).as_deref(),
+            Some("3q2+7wE=")
+        );
+        assert_eq!(encode_bytes(&bytes, BinaryFormat::Skip, None), None);
+        assert_eq!(
+            encode_bytes(&bytes, BinaryFormat::Hex, Some(2)).as_deref(),
+            Some("0xDEAD... (5 bytes)")
+        );
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+        assert_eq!(cap_text("héllo", 2), "h... (6 bytes)");
+        assert_eq!(cap_text("hello", 5), "hello");
+
+        let long = Value::Text("hello world".to_string());
+        assert_eq!(long.as_display(), "hello world");
+        assert_eq!(
+            long.capped(Some(5)).into_owned(),
+            Value::Text("hello... (11 bytes)".to_string())
+        );
+        assert!(matches!(long.capped(None), Cow::Borrowed(_)));
+        assert_eq!(
+            Value::Bytes(bytes.to_vec()).capped(Some(2)).into_owned(),
+            Value::Text("0xDEAD... (5 bytes)".to_string())
+        );
+    }
+
     #[test]
     fn formats_numbers_with_commas() {
         assert_eq!(format_number(1234567), "1,234,567");
         assert_eq!(format_number(-9876543), "-9,876,543");
+        assert_eq!(format_number(i64::MIN), "-9,223,372,036,854,775,808");
+    }
+
+    #[test]
+    fn integers_beyond_double_precision_serialize_as_strings() {
+        let json = |value: i64| serde_json::to_value(Value::Int(value)).unwrap();
+        assert_eq!(
+            json(MAX_SAFE_INTEGER),
+            serde_json::json!(9_007_199_254_740_991i64)
+        );
+        assert_eq!(
+            json(-MAX_SAFE_INTEGER),
+            serde_json::json!(-9_007_199_254_740_991i64)
+        );
+        assert_eq!(json(MAX_SAFE_INTEGER + 1), "9007199254740992");
+        assert_eq!(json(i64::MAX), "9223372036854775807");
+        assert_eq!(json(i64::MIN), "-9223372036854775808");
+        assert_eq!(Value::Int(i64::MAX).as_csv(), "9223372036854775807");
     }
 }
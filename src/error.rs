@@ -4,6 +4,12 @@ use std::fmt;
 pub enum ErrorKind {
     Config,
     Connection,
+    /// The server rejected the login.
+    Auth,
+    /// A named object, table, or procedure does not exist.
+    NotFound,
+    /// A safety check refused to run the request.
+    Safety,
     Query,
     Internal,
 }
@@ -13,10 +19,72 @@ impl ErrorKind {
         match self {
             ErrorKind::Config => "Config",
             ErrorKind::Connection => "Connection",
+            ErrorKind::Auth => "Auth",
+            ErrorKind::NotFound => "NotFound",
+            ErrorKind::Safety => "Safety",
             ErrorKind::Query => "Query",
             ErrorKind::Internal => "Internal",
         }
     }
+
+    pub fn exit_code(self) -> ExitCode {
+        match self {
+            ErrorKind::Config => ExitCode::Usage,
+            ErrorKind::Connection => ExitCode::Connection,
+            ErrorKind::Auth => ExitCode::Auth,
+            ErrorKind::NotFound => ExitCode::NotFound,
+            ErrorKind::Safety => ExitCode::Safety,
+            ErrorKind::Query | ErrorKind::Internal => ExitCode::Error,
+        }
+    }
+}
+
+/// Process exit codes. The numbers are part of the CLI contract: scripts branch on them, so
+/// they never change meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success,
+    /// A query or internal failure.
+    Error,
+    /// Bad arguments or configuration (clap's own usage errors exit 2 as well).
+    Usage,
+    /// The command ran but its check failed: drift, data mismatch, unhealthy, missing grants.
+    CheckFailed,
+    NotFound,
+    Connection,
+    Auth,
+    /// Refused by a safety check, such as destructive changes without `--allow-destructive`.
+    Safety,
+    /// Some batches failed under `--continue-on-error`; the rest ran.
+    PartialFailure,
+    /// `status --deep` ran, but a database is not online, an availability group is unhealthy,
+    /// or a section could not be read.
+    Degraded,
+    /// Stopped by Ctrl+C, following the shell's 128 + SIGINT.
+    Interrupted,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::Error => 1,
+            ExitCode::Usage => 2,
+            ExitCode::CheckFailed => 3,
+            ExitCode::NotFound => 4,
+            ExitCode::Connection => 5,
+            ExitCode::Auth => 6,
+            ExitCode::Safety => 7,
+            ExitCode::PartialFailure => 8,
+            ExitCode::Degraded => 9,
+            ExitCode::Interrupted => 130,
+        }
+    }
+
+    /// End the process with this code. Output already written is flushed by `exit` itself.
+    pub fn exit(self) -> ! {
+        std::process::exit(self.code())
+    }
 }
 
 #[derive(Debug)]
@@ -48,3 +116,24 @@ pub fn classify_error(err: &anyhow::Error) -> ErrorKind {
     }
     ErrorKind::Internal
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_kinds_map_to_stable_exit_codes() {
+        let code = |kind: ErrorKind| kind.exit_code().code();
+        assert_eq!(code(ErrorKind::Query), 1);
+        assert_eq!(code(ErrorKind::Config), 2);
+        assert_eq!(code(ErrorKind::NotFound), 4);
+        assert_eq!(code(ErrorKind::Connection), 5);
+        assert_eq!(code(ErrorKind::Auth), 6);
+        assert_eq!(code(ErrorKind::Safety), 7);
+
+        let err: anyhow::Error = AppError::new(ErrorKind::Auth, "Login failed").into();
+        assert_eq!(classify_error(&err), ErrorKind::Auth);
+        let err = anyhow::anyhow!("boom");
+        assert_eq!(classify_error(&err), ErrorKind::Internal);
+    }
+}
@@ -9,28 +9,26 @@ use sscli::error;
 use sscli::output::json;
 
 fn main() {
-    if let Err(err) = run() {
+    let args = cli::parse();
+    json::begin_run(&args.command_path, args.contract);
+    // Help and completions run on every shell prompt; keep their startup to parsing alone.
+    if !args.command.is_fast_path() || args.verbose > 0 {
+        init_logging(args.verbose);
+    }
+    if let Err(err) = commands::dispatch(&args) {
         let message = err.to_string();
-        let args = cli::parse();
         let kind = error::classify_error(&err);
         if args.output.json {
-            let payload = json::error_json(&message, kind.as_str());
-            if let Ok(body) = json::emit_json_value(&payload, true) {
+            if let Ok(body) = json::emit_error_json(&message, kind.as_str(), true) {
                 let _ = writeln!(io::stderr(), "{}", body);
             }
         } else {
-            print_error(&message);
+            print_error(&message, args.output.no_color);
         }
-        std::process::exit(1);
+        kind.exit_code().exit();
     }
 }
 
-fn run() -> anyhow::Result<()> {
-    let args = cli::parse();
-    init_logging(args.verbose);
-    commands::dispatch(&args)
-}
-
 fn init_logging(verbose: u8) {
     let filter = match verbose {
         0 => "warn,tiberius=error",
@@ -46,8 +44,8 @@ fn init_logging(verbose: u8) {
         .try_init();
 }
 
-fn print_error(message: &str) {
-    if should_color_stderr() {
+fn print_error(message: &str, no_color: bool) {
+    if should_color_stderr(no_color) {
         let line = format!("Error: {}", message);
         let _ = writeln!(io::stderr(), "{}", line.red());
     } else {
@@ -55,8 +53,8 @@ fn print_error(message: &str) {
     }
 }
 
-fn should_color_stderr() -> bool {
-    if std::env::var_os("NO_COLOR").is_some() {
+fn should_color_stderr(no_color: bool) -> bool {
+    if no_color || std::env::var_os("NO_COLOR").is_some() {
         return false;
     }
     io::stderr().is_terminal()
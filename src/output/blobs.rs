@@ -0,0 +1,104 @@
+//! `--save-blobs DIR`: move large text and binary cells out of the output into files.
+//!
+//! Each cell over the threshold is written to `DIR` as-is (raw bytes, or UTF-8 text) and replaced
+//! by the file's path, so tables, JSON, and CSV all carry a short reference instead of the value.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::db::types::{ResultSet, Value};
+
+/// Threshold when `--max-value-bytes` is not set: the largest non-`MAX` `varbinary`/`varchar`.
+pub const BLOB_BYTES_DEFAULT: usize = 8000;
+
+/// Replace every text or binary cell longer than `threshold` bytes with the path of a file in
+/// `dir` holding its value. Returns the files written.
+pub fn save_blobs(dir: &Path, sets: &mut [ResultSet], threshold: usize) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (set_idx, rs) in sets.iter_mut().enumerate() {
+        let names: Vec<String> = rs.columns.iter().map(|c| file_safe(&c.name)).collect();
+        for (row_idx, row) in rs.rows.iter_mut().enumerate() {
+            for (col_idx, cell) in row.iter_mut().enumerate() {
+                if cell.byte_len().is_none_or(|len| len <= threshold) {
+                    continue;
+                }
+                if written.is_empty() {
+                    fs::create_dir_all(dir)
+                        .with_context(|| format!("Failed to create {}", dir.display()))?;
+                }
+                let (ext, contents): (&str, &[u8]) = match cell {
+                    Value::Bytes(bytes) => ("bin", bytes),
+                    Value::Text(text) => ("txt", text.as_bytes()),
+                    _ => continue,
+                };
+                let column = names.get(col_idx).map_or("column", String::as_str);
+                let path = dir.join(format!(
+                    "set{}-row{}-{}.{}",
+                    set_idx + 1,
+                    row_idx + 1,
+                    column,
+                    ext
+                ));
+                fs::write(&path, contents)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                *cell = Value::Text(path.display().to_string());
+                written.push(path);
+            }
+        }
+    }
+    Ok(written)
+}
+
+fn file_safe(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if safe.is_empty() {
+        "column".to_string()
+    } else {
+        safe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn large_cells_become_file_paths() {
+        let dir = std::env::temp_dir().join(format!("sscli-blobs-{}", std::process::id()));
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let mut sets = vec![ResultSet {
+            columns: vec![column("Id"), column("Photo"), column("Notes / text")],
+            rows: vec![vec![
+                Value::Int(1),
+                Value::Bytes(vec![1, 2, 3, 4]),
+                Value::Text("hi".to_string()),
+            ]],
+        }];
+
+        let written = save_blobs(&dir, &mut sets, 4).expect("save");
+        assert!(written.is_empty());
+
+        let written = save_blobs(&dir, &mut sets, 3).expect("save");
+        let path = dir.join("set1-row1-Photo.bin");
+        assert_eq!(written, vec![path.clone()]);
+        assert_eq!(fs::read(&path).expect("read"), vec![1, 2, 3, 4]);
+        assert_eq!(sets[0].rows[0][1], Value::Text(path.display().to_string()));
+        assert_eq!(file_safe("Notes / text"), "Notes___text");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
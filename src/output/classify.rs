@@ -0,0 +1,294 @@
+//! Lightweight value classification for table output.
+//!
+//! Columns are classified once per result set, from the reported SQL type when it is decisive
+//! (`money`, `uniqueidentifier`) and otherwise from the shape of the first few values. JSON
+//! output is never affected; this only changes how cells look in pretty and markdown tables.
+
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::config::{HyperlinkMode, OutputFormat, RenderSettingsResolved};
+use crate::db::types::{ResultSet, Value};
+
+/// Values inspected per column before deciding on a class.
+const SAMPLE_ROWS: usize = 50;
+const GUID_LEN: usize = 36;
+
+static RENDER_SETTINGS: OnceLock<RenderSettingsResolved> = OnceLock::new();
+
+/// Install `settings.output.render`: value detection, hyperlinks, JSON pretty-printing, and the
+/// currency symbol that table cells are classified with. `common::load_config` sets it; a later
+/// call is ignored, so every table printed by one run is classified the same way.
+pub fn set_render_settings(settings: RenderSettingsResolved) {
+    let _ = RENDER_SETTINGS.set(settings);
+}
+
+pub fn render_settings() -> RenderSettingsResolved {
+    RENDER_SETTINGS.get().cloned().unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueClass {
+    Plain,
+    Json,
+    Url,
+    Guid,
+    Money,
+}
+
+/// Classify column `index` of `result_set`.
+///
+/// Text columns need every sampled non-null value to agree, so one free-form row keeps the
+/// column plain.
+pub fn classify_column(result_set: &ResultSet, index: usize) -> ValueClass {
+    let data_type = result_set
+        .columns
+        .get(index)
+        .and_then(|c| c.data_type.as_deref());
+    match data_type {
+        Some("money" | "smallmoney") => return ValueClass::Money,
+        Some("uniqueidentifier") => return ValueClass::Guid,
+        _ => {}
+    }
+
+    let mut sampled = result_set
+        .rows
+        .iter()
+        .filter_map(|row| match row.get(index) {
+            Some(Value::Text(text)) => Some(text.as_str()),
+            _ => None,
+        })
+        .take(SAMPLE_ROWS)
+        .peekable();
+    let Some(first) = sampled.peek().copied() else {
+        return ValueClass::Plain;
+    };
+    let class = if looks_like_json(first) {
+        ValueClass::Json
+    } else if looks_like_url(first) {
+        ValueClass::Url
+    } else if looks_like_guid(first) {
+        ValueClass::Guid
+    } else {
+        return ValueClass::Plain;
+    };
+    let check: fn(&str) -> bool = match class {
+        ValueClass::Json => looks_like_json,
+        ValueClass::Url => looks_like_url,
+        _ => looks_like_guid,
+    };
+    if sampled.all(check) {
+        class
+    } else {
+        ValueClass::Plain
+    }
+}
+
+pub fn looks_like_json(text: &str) -> bool {
+    let trimmed = text.trim();
+    let bracketed = (trimmed.starts_with('{') && trimmed.ends_with('}'))
+        || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+    bracketed && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+}
+
+pub fn looks_like_url(text: &str) -> bool {
+    let lower = text.get(..8).unwrap_or(text).to_ascii_lowercase();
+    let rest = if lower.starts_with("https://") {
+        &text[8..]
+    } else if lower.starts_with("http://") {
+        &text[7..]
+    } else {
+        return false;
+    };
+    !rest.is_empty() && !text.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+pub fn looks_like_guid(text: &str) -> bool {
+    text.len() == GUID_LEN
+        && text.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Format a money value with thousands separators and at least two decimals.
+///
+/// Extra scale (money keeps four places) is shown only when it is non-zero.
+pub fn format_money(value: &Value, symbol: &str) -> Option<String> {
+    let raw = match value {
+        Value::Float(f) if f.is_finite() => format!("{:.4}", f),
+        Value::Int(i) => i.to_string(),
+        Value::Decimal(d) => d.to_string(),
+        Value::Text(text) => text.trim().to_string(),
+        _ => return None,
+    };
+    let (negative, digits) = match raw.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, raw.as_str()),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty()
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mut fraction = fraction.trim_end_matches('0').to_string();
+    while fraction.len() < 2 {
+        fraction.push('0');
+    }
+    let is_zero = whole.bytes().all(|b| b == b'0') && fraction.bytes().all(|b| b == b'0');
+    let sign = if negative && !is_zero { "-" } else { "" };
+    Some(format!(
+        "{}{}{}.{}",
+        sign,
+        symbol,
+        group_thousands(whole.trim_start_matches('0')),
+        fraction
+    ))
+}
+
+fn group_thousands(digits: &str) -> String {
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Re-serialize a JSON cell, compact by default or indented when `pretty` is set.
+pub fn format_json(text: &str, pretty: bool) -> String {
+    match serde_json::from_str::<serde_json::Value>(text.trim()) {
+        Ok(value) if pretty => serde_json::to_string_pretty(&value).unwrap_or_default(),
+        Ok(value) => value.to_string(),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// Whether URL cells should become OSC-8 hyperlinks for this output.
+///
+/// Markdown output is meant to be pasted elsewhere, so it never carries escapes.
+pub fn hyperlinks_enabled(mode: HyperlinkMode, format: OutputFormat) -> bool {
+    if !matches!(format, OutputFormat::Pretty | OutputFormat::Expanded) {
+        return false;
+    }
+    match mode {
+        HyperlinkMode::Always => true,
+        HyperlinkMode::Never => false,
+        HyperlinkMode::Auto => {
+            std::io::stdout().is_terminal()
+                && std::env::var("TERM").map_or(true, |term| term != "dumb")
+        }
+    }
+}
+
+/// Wrap each occurrence of `urls` in `output` with OSC-8 hyperlink escapes.
+///
+/// Runs after layout so the escapes do not count toward column widths; URLs that were
+/// truncated or wrapped no longer match and stay plain.
+pub fn linkify(output: &str, urls: &[String]) -> String {
+    let mut urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+    urls.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    urls.dedup();
+    if urls.is_empty() {
+        return output.to_string();
+    }
+    let pattern = urls
+        .iter()
+        .map(|url| regex::escape(url))
+        .collect::<Vec<_>>()
+        .join("|");
+    let Ok(re) = Regex::new(&pattern) else {
+        return output.to_string();
+    };
+    re.replace_all(output, |caps: &regex::Captures<'_>| {
+        let url = &caps[0];
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, url)
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn column(name: &str, data_type: Option<&str>) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn classifies_by_type_then_by_shape() {
+        let rs = ResultSet {
+            columns: vec![
+                column("price", Some("money")),
+                column("payload", Some("nvarchar")),
+                column("link", Some("nvarchar")),
+                column("note", Some("nvarchar")),
+            ],
+            rows: vec![
+                vec![
+                    Value::Float(12.5),
+                    Value::Text(r#"{"a":1}"#.to_string()),
+                    Value::Text("https://example.com/a".to_string()),
+                    Value::Text("https://example.com".to_string()),
+                ],
+                vec![
+                    Value::Null,
+                    Value::Null,
+                    Value::Text("http://example.com/b?x=1".to_string()),
+                    Value::Text("see the docs".to_string()),
+                ],
+            ],
+        };
+        assert_eq!(classify_column(&rs, 0), ValueClass::Money);
+        assert_eq!(classify_column(&rs, 1), ValueClass::Json);
+        assert_eq!(classify_column(&rs, 2), ValueClass::Url);
+        assert_eq!(classify_column(&rs, 3), ValueClass::Plain);
+    }
+
+    #[test]
+    fn detects_guid_text() {
+        assert!(looks_like_guid("6F9619FF-8B86-D011-B42D-00C04FC964FF"));
+        assert!(!looks_like_guid("6F9619FF-8B86-D011-B42D-00C04FC964F"));
+        assert!(!looks_like_json("{not json}"));
+        assert!(!looks_like_url("https://"));
+    }
+
+    #[test]
+    fn formats_money() {
+        assert_eq!(
+            format_money(&Value::Float(1234567.5), "").as_deref(),
+            Some("1,234,567.50")
+        );
+        assert_eq!(
+            format_money(&Value::Float(-0.1234), "$").as_deref(),
+            Some("-$0.1234")
+        );
+        assert_eq!(
+            format_money(&Value::Text("100.0000".to_string()), "").as_deref(),
+            Some("100.00")
+        );
+        assert_eq!(format_money(&Value::Text("n/a".to_string()), ""), None);
+    }
+
+    #[test]
+    fn linkifies_longest_match_first() {
+        let urls = vec!["https://a.io".to_string(), "https://a.io/x".to_string()];
+        let out = linkify("│https://a.io/x│https://a.io│", &urls);
+        assert!(out.contains("\x1b]8;;https://a.io/x\x1b\\https://a.io/x\x1b]8;;\x1b\\"));
+        assert!(out.contains("\x1b]8;;https://a.io\x1b\\https://a.io\x1b]8;;\x1b\\"));
+    }
+}
@@ -0,0 +1,408 @@
+//! Typed JSON output contracts and the JSON Schema published for them (`help --schema`).
+//!
+//! Commands with a contract here serialize these structs rather than building `json!` values,
+//! so the schema and the output come from the same field list and cannot drift apart.
+
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::config::JsonContractVersion;
+use crate::db::types::Value;
+
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Listing keys that [`crate::output::json`] moves to `meta.paging` under contract v2.
+pub(crate) const PAGING_KEYS: &[&str] = &[
+    "total",
+    "count",
+    "offset",
+    "limit",
+    "hasMore",
+    "nextOffset",
+    "nextKey",
+];
+
+/// A type whose serialized form can be described as a JSON Schema.
+pub trait JsonSchema {
+    fn json_schema() -> JsonValue;
+}
+
+impl JsonSchema for String {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "string" })
+    }
+}
+
+impl JsonSchema for bool {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "boolean" })
+    }
+}
+
+impl JsonSchema for u64 {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "integer", "minimum": 0 })
+    }
+}
+
+impl JsonSchema for u128 {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "integer", "minimum": 0 })
+    }
+}
+
+impl JsonSchema for i64 {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "integer" })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    fn json_schema() -> JsonValue {
+        json!({ "anyOf": [T::json_schema(), { "type": "null" }] })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Vec<T> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "array", "items": T::json_schema() })
+    }
+}
+
+/// A row object keyed by column name.
+impl JsonSchema for Map<String, JsonValue> {
+    fn json_schema() -> JsonValue {
+        json!({ "type": "object" })
+    }
+}
+
+/// A cell: large integers may be strings (`largeIntegers: string`), dates are ISO-8601 text.
+impl JsonSchema for Value {
+    fn json_schema() -> JsonValue {
+        json!({ "type": ["string", "number", "boolean", "null"] })
+    }
+}
+
+/// Declare a contract struct: serialized camelCase, with a [`JsonSchema`] built from the same
+/// fields and their doc comments. Every field is required; `Option` fields are nullable.
+macro_rules! contract {
+    (
+        $(#[doc = $doc:literal])*
+        pub struct $name:ident {
+            $(
+                $(#[doc = $field_doc:literal])*
+                pub $field:ident: $ty:ty,
+            )*
+        }
+    ) => {
+        $(#[doc = $doc])*
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct $name {
+            $(
+                $(#[doc = $field_doc])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl JsonSchema for $name {
+            fn json_schema() -> JsonValue {
+                object_schema(
+                    concat!($($doc),*),
+                    vec![$((
+                        stringify!($field),
+                        concat!($($field_doc),*),
+                        <$ty as JsonSchema>::json_schema(),
+                    )),*],
+                )
+            }
+        }
+    };
+}
+
+contract! {
+    /// Connection check.
+    pub struct StatusOutput {
+        /// Always `ok`; failures are reported as errors.
+        pub status: String,
+        /// Round trip of the probe query, in milliseconds.
+        pub latency_ms: u128,
+        pub server_name: String,
+        pub server_version: String,
+        pub current_database: String,
+        /// Server time, ISO-8601 with offset.
+        pub timestamp: String,
+        pub warnings: Vec<String>,
+    }
+}
+
+contract! {
+    /// One page of databases.
+    pub struct DatabasesOutput {
+        pub total: u64,
+        pub count: u64,
+        pub offset: u64,
+        pub limit: u64,
+        pub has_more: bool,
+        pub next_offset: Option<u64>,
+        /// One object per database, keyed by column name (after `--fields`).
+        pub databases: Vec<Map<String, JsonValue>>,
+        /// Details that could not be read, such as backup history without msdb access.
+        pub warnings: Vec<String>,
+    }
+}
+
+contract! {
+    /// One page of tables.
+    pub struct TablesOutput {
+        pub total: u64,
+        pub count: u64,
+        pub offset: u64,
+        pub limit: u64,
+        pub has_more: bool,
+        pub next_offset: Option<u64>,
+        /// One object per table, keyed by column name (after `--fields`).
+        pub tables: Vec<Map<String, JsonValue>>,
+    }
+}
+
+contract! {
+    /// Schema and name of a table.
+    pub struct TableRef {
+        pub schema: String,
+        pub name: String,
+    }
+}
+
+contract! {
+    /// One page of rows from a table or view.
+    pub struct TableDataOutput {
+        pub table: TableRef,
+        /// Column names, in the order of each row's values.
+        pub columns: Vec<String>,
+        pub rows: Vec<Vec<Value>>,
+        /// Rows matching `--where`; null with `--paginate keyset`, which does not count them.
+        pub total: Option<u64>,
+        pub offset: u64,
+        pub limit: u64,
+        pub has_more: bool,
+        pub next_offset: Option<u64>,
+        /// `--paginate keyset` continuation token for `--after`; null on the last page.
+        pub next_key: Option<String>,
+        /// Files written by `--csv`.
+        pub csv_paths: Option<Vec<String>>,
+        /// Files written by `--out`.
+        pub out_paths: Option<Vec<String>>,
+        /// Object URLs written by `--to`.
+        pub uploaded: Option<Vec<String>>,
+        /// The `--sample` mode the rows were picked with, e.g. `random:1000`.
+        pub sample: Option<String>,
+    }
+}
+
+contract! {
+    /// A failure, as printed to stderr.
+    pub struct ErrorDetail {
+        pub message: String,
+        /// `Config`, `Connection`, `Auth`, `NotFound`, `Safety`, `Query`, or `Internal`.
+        pub kind: String,
+    }
+}
+
+contract! {
+    /// Contract v1 error document, written to stderr.
+    pub struct ErrorOutput {
+        pub error: ErrorDetail,
+    }
+}
+
+/// Commands with a published schema, in `help --schema` order. Other commands' JSON is documented
+/// in the README only; `help --schema` and its help text name just these.
+pub const COMMANDS: &[&str] = &["status", "databases", "tables", "table-data"];
+
+/// The output schema of `command` under `contract`, or `None` when none is published.
+pub fn command_schema(command: &str, contract: JsonContractVersion) -> Option<JsonValue> {
+    let data = match command {
+        "status" => StatusOutput::json_schema(),
+        "databases" => DatabasesOutput::json_schema(),
+        "tables" => TablesOutput::json_schema(),
+        "table-data" => TableDataOutput::json_schema(),
+        _ => return None,
+    };
+    let body = match contract {
+        JsonContractVersion::V1 => data,
+        JsonContractVersion::V2 => envelope_schema(data),
+    };
+    Some(document(
+        &format!("sscli {} ({})", command, contract.as_str()),
+        body,
+    ))
+}
+
+/// The schema of the error document for `contract`.
+pub fn error_schema(contract: JsonContractVersion) -> JsonValue {
+    let body = match contract {
+        JsonContractVersion::V1 => ErrorOutput::json_schema(),
+        JsonContractVersion::V2 => {
+            let mut envelope = envelope_schema(json!({ "type": "null" }));
+            envelope["properties"]["errors"]["minItems"] = json!(1);
+            envelope
+        }
+    };
+    document(&format!("sscli error ({})", contract.as_str()), body)
+}
+
+fn document(title: &str, body: JsonValue) -> JsonValue {
+    let mut doc = json!({ "$schema": SCHEMA_DIALECT, "title": title });
+    if let (Some(doc_map), JsonValue::Object(body)) = (doc.as_object_mut(), body) {
+        doc_map.extend(body);
+    }
+    doc
+}
+
+fn object_schema(description: &str, fields: Vec<(&str, &str, JsonValue)>) -> JsonValue {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (field, doc, mut schema) in fields {
+        let name = camel_case(field);
+        let doc = doc.trim();
+        if !doc.is_empty() {
+            schema["description"] = json!(doc);
+        }
+        required.push(json!(name));
+        properties.insert(name, schema);
+    }
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    });
+    let description = description.trim();
+    if !description.is_empty() {
+        schema["description"] = json!(description);
+    }
+    schema
+}
+
+/// The v2 envelope around `data`, mirroring how [`crate::output::json`] builds it: paging keys
+/// move to `meta.paging` and a `warnings` array moves to the top level.
+fn envelope_schema(mut data: JsonValue) -> JsonValue {
+    let mut paging = Map::new();
+    let mut warnings = json!({ "type": "array" });
+    if let Some(properties) = data["properties"].as_object_mut() {
+        if properties.contains_key("hasMore") {
+            for key in PAGING_KEYS {
+                if let Some(schema) = properties.remove(*key) {
+                    paging.insert(key.to_string(), schema);
+                }
+            }
+        }
+        if let Some(schema) = properties.remove("warnings") {
+            warnings = schema;
+        }
+    }
+    if let Some(required) = data["required"].as_array_mut() {
+        required.retain(|name| {
+            name.as_str()
+                .is_some_and(|name| name != "warnings" && !paging.contains_key(name))
+        });
+    }
+    let paging = if paging.is_empty() {
+        json!({ "type": "null" })
+    } else {
+        let keys: Vec<&String> = paging.keys().collect();
+        json!({ "type": "object", "properties": paging, "required": keys })
+    };
+    json!({
+        "type": "object",
+        "properties": {
+            "meta": {
+                "type": "object",
+                "properties": {
+                    "contract": { "const": "v2" },
+                    "command": { "type": "string" },
+                    "profile": { "type": ["string", "null"] },
+                    "elapsedMs": { "type": "integer", "minimum": 0 },
+                    "paging": paging,
+                },
+                "required": ["contract", "command", "profile", "elapsedMs", "paging"],
+            },
+            "data": data,
+            "warnings": warnings,
+            "errors": { "type": "array", "items": ErrorDetail::json_schema() },
+        },
+        "required": ["meta", "data", "warnings", "errors"],
+        "additionalProperties": false,
+    })
+}
+
+/// `snake_case` to `camelCase`, as `#[serde(rename_all = "camelCase")]` renames fields.
+fn camel_case(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut upper = false;
+    for ch in field.chars() {
+        if ch == '_' {
+            upper = true;
+        } else if upper {
+            out.push(ch.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_matches_serialized_fields() {
+        let status = StatusOutput {
+            status: "ok".to_string(),
+            latency_ms: 3,
+            server_name: "db".to_string(),
+            server_version: "16.0".to_string(),
+            current_database: "master".to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            warnings: Vec::new(),
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        let schema = command_schema("status", JsonContractVersion::V1).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        let mut schema_keys: Vec<&String> = properties.keys().collect();
+        let mut value_keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        schema_keys.sort();
+        value_keys.sort();
+        assert_eq!(schema_keys, value_keys);
+        assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+        assert_eq!(
+            properties["latencyMs"]["description"],
+            "Round trip of the probe query, in milliseconds."
+        );
+        assert!(command_schema("sessions", JsonContractVersion::V1).is_none());
+    }
+
+    #[test]
+    fn v2_schema_moves_paging_and_warnings_into_the_envelope() {
+        let schema = command_schema("tables", JsonContractVersion::V2).unwrap();
+        let paging = &schema["properties"]["meta"]["properties"]["paging"];
+        assert_eq!(paging["properties"]["hasMore"]["type"], "boolean");
+        let data = &schema["properties"]["data"];
+        assert!(data["properties"].get("total").is_none());
+        assert_eq!(data["required"], json!(["tables"]));
+
+        let status = command_schema("status", JsonContractVersion::V2).unwrap();
+        assert_eq!(status["properties"]["warnings"]["items"]["type"], "string");
+        assert_eq!(
+            status["properties"]["meta"]["properties"]["paging"]["type"],
+            "null"
+        );
+        assert_eq!(
+            error_schema(JsonContractVersion::V1)["properties"]["error"]["required"],
+            json!(["message", "kind"])
+        );
+    }
+}
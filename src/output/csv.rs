@@ -5,35 +5,74 @@ use anyhow::Result;
 use crate::config::CsvMultiResultNaming;
 use crate::db::types::ResultSet;
 
+/// Write each result set to its own CSV file; text and binary cells over `max_value_bytes` are
+/// cut and marked.
 pub fn write_result_sets(
     base_path: &Path,
     result_sets: &[ResultSet],
     naming: CsvMultiResultNaming,
+    max_value_bytes: Option<usize>,
 ) -> Result<Vec<PathBuf>> {
     let multiple = result_sets.len() > 1;
     let mut paths = Vec::new();
 
     for (index, result_set) in result_sets.iter().enumerate() {
         let target = expand_csv_path(base_path, index + 1, multiple, naming);
-        let mut writer = csv::Writer::from_path(&target)?;
-        let headers = result_set
-            .columns
-            .iter()
-            .map(|col| col.name.as_str())
-            .collect::<Vec<_>>();
-        writer.write_record(headers)?;
-        for row in &result_set.rows {
-            let record = row.iter().map(|value| value.as_csv()).collect::<Vec<_>>();
-            writer.write_record(record)?;
-        }
-        writer.flush()?;
+        let writer = csv::Writer::from_path(&target)?;
+        write_records(writer, result_set, max_value_bytes)?;
         paths.push(target);
     }
 
     Ok(paths)
 }
 
-fn expand_csv_path(
+/// Render each result set as CSV in memory, named like [`write_result_sets`] names files.
+pub fn render_result_sets(
+    base_name: &str,
+    result_sets: &[ResultSet],
+    naming: CsvMultiResultNaming,
+    max_value_bytes: Option<usize>,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    let multiple = result_sets.len() > 1;
+    result_sets
+        .iter()
+        .enumerate()
+        .map(|(index, result_set)| {
+            let name = expand_csv_path(Path::new(base_name), index + 1, multiple, naming);
+            let mut buffer = Vec::new();
+            write_records(
+                csv::Writer::from_writer(&mut buffer),
+                result_set,
+                max_value_bytes,
+            )?;
+            Ok((name.to_string_lossy().into_owned(), buffer))
+        })
+        .collect()
+}
+
+fn write_records<W: std::io::Write>(
+    mut writer: csv::Writer<W>,
+    result_set: &ResultSet,
+    max_value_bytes: Option<usize>,
+) -> Result<()> {
+    let headers = result_set
+        .columns
+        .iter()
+        .map(|col| col.name.as_str())
+        .collect::<Vec<_>>();
+    writer.write_record(headers)?;
+    for row in &result_set.rows {
+        let record = row
+            .iter()
+            .map(|value| value.capped(max_value_bytes).as_csv())
+            .collect::<Vec<_>>();
+        writer.write_record(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn expand_csv_path(
     base_path: &Path,
     index: usize,
     multiple: bool,
@@ -66,36 +105,21 @@ fn expand_csv_path(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::types::{Column, ResultSet, Value};
-    use std::env;
-    use std::fs;
-
-    fn temp_dir(name: &str) -> PathBuf {
-        let mut dir = env::temp_dir();
-        dir.push(format!("sscli-csv-{}-{}", name, std::process::id()));
-        let _ = fs::remove_dir_all(&dir);
-        fs::create_dir_all(&dir).expect("create temp dir");
-        dir
-    }
-
-    fn sample_result_set() -> ResultSet {
-        ResultSet {
-            columns: vec![Column {
-                name: "id".to_string(),
-                data_type: None,
-            }],
-            rows: vec![vec![Value::Int(1)]],
-        }
-    }
+    use crate::output::fixtures::sample_result_set;
 
     #[test]
     fn writes_multiple_csv_files_with_suffix() {
-        let dir = temp_dir("suffix");
-        let base = dir.join("results.csv");
+        let dir = tempfile::tempdir().expect("temp dir");
+        let base = dir.path().join("results.csv");
         let result_sets = vec![sample_result_set(), sample_result_set()];
 
-        let paths = write_result_sets(&base, &result_sets, CsvMultiResultNaming::SuffixNumber)
-            .expect("write csv");
+        let paths = write_result_sets(
+            &base,
+            &result_sets,
+            CsvMultiResultNaming::SuffixNumber,
+            None,
+        )
+        .expect("write csv");
 
         assert_eq!(paths.len(), 2);
         assert!(paths[0].ends_with("results-1.csv"));
@@ -104,11 +128,11 @@ mod tests {
 
     #[test]
     fn writes_csv_with_placeholder() {
-        let dir = temp_dir("placeholder");
-        let base = dir.join("results-{n}.csv");
+        let dir = tempfile::tempdir().expect("temp dir");
+        let base = dir.path().join("results-{n}.csv");
         let result_sets = vec![sample_result_set(), sample_result_set()];
 
-        let paths = write_result_sets(&base, &result_sets, CsvMultiResultNaming::Placeholder)
+        let paths = write_result_sets(&base, &result_sets, CsvMultiResultNaming::Placeholder, None)
             .expect("write csv");
 
         assert!(paths[0].ends_with("results-1.csv"));
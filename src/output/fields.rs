@@ -0,0 +1,90 @@
+//! `--fields`: pick and reorder the columns of a listing before it is rendered.
+
+use crate::db::types::ResultSet;
+use crate::error::{AppError, ErrorKind};
+
+/// Keep only `fields`, in the order given. Names match case-insensitively; an empty list
+/// returns `result_set` unchanged. Empty results carry no column metadata, so they pass
+/// through unchecked.
+pub fn project(result_set: ResultSet, fields: &[String]) -> Result<ResultSet, AppError> {
+    if fields.is_empty() || result_set.columns.is_empty() {
+        return Ok(result_set);
+    }
+    let indexes = fields
+        .iter()
+        .map(|field| {
+            result_set
+                .columns
+                .iter()
+                .position(|column| column.name.eq_ignore_ascii_case(field))
+                .ok_or_else(|| unknown_field(&result_set, field))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ResultSet {
+        columns: indexes
+            .iter()
+            .map(|&idx| result_set.columns[idx].clone())
+            .collect(),
+        rows: result_set
+            .rows
+            .iter()
+            .map(|row| {
+                indexes
+                    .iter()
+                    .filter_map(|&idx| row.get(idx).cloned())
+                    .collect()
+            })
+            .collect(),
+    })
+}
+
+/// Error for a column name that `result_set` does not have, listing the ones it does.
+pub(crate) fn unknown_field(result_set: &ResultSet, field: &str) -> AppError {
+    let available = result_set
+        .columns
+        .iter()
+        .map(|column| column.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    AppError::new(
+        ErrorKind::Config,
+        format!("Unknown field '{}'. Available: {}", field, available),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{Column, Value};
+
+    #[test]
+    fn projects_and_reorders_columns() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let result_set = ResultSet {
+            columns: vec![column("schema"), column("name"), column("rowCount")],
+            rows: vec![vec![
+                Value::Text("dbo".to_string()),
+                Value::Text("Orders".to_string()),
+                Value::Int(12),
+            ]],
+        };
+        let fields = vec!["ROWCOUNT".to_string(), "name".to_string()];
+        let projected = project(result_set.clone(), &fields).unwrap();
+        assert_eq!(projected.columns[0].name, "rowCount");
+        assert_eq!(
+            projected.rows[0],
+            vec![Value::Int(12), Value::Text("Orders".to_string())]
+        );
+
+        assert_eq!(project(result_set.clone(), &[]).unwrap(), result_set);
+        let err = project(result_set, &["size".to_string()]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Available: schema, name, rowCount")
+        );
+    }
+}
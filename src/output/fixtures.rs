@@ -0,0 +1,20 @@
+//! Result sets shared by the output writers' tests.
+
+use crate::db::types::{Column, ResultSet, Value};
+
+/// Columns `id` and `name`, one row: `1, 'Ada'`.
+pub(crate) fn sample_result_set() -> ResultSet {
+    ResultSet {
+        columns: vec![
+            Column {
+                name: "id".to_string(),
+                data_type: None,
+            },
+            Column {
+                name: "name".to_string(),
+                data_type: None,
+            },
+        ],
+        rows: vec![vec![Value::Int(1), Value::Text("Ada".to_string())]],
+    }
+}
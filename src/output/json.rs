@@ -1,18 +1,118 @@
+//! JSON output. Under contract v2 every document is wrapped in one envelope,
+//! `{ meta: {contract, command, profile, elapsedMs, paging}, data, warnings, errors }`,
+//! so consumers can rely on the same top-level keys whatever the command.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::Instant;
+
 use serde::Serialize;
 use serde_json::json;
 
-use crate::config::ResolvedConfig;
-use crate::db::types::ResultSet;
+use crate::config::{JsonContractVersion, ResolvedConfig};
+use crate::db::types::{ResultSet, Value};
+use crate::output::contract::{ErrorDetail, ErrorOutput, PAGING_KEYS};
 
-pub fn emit_json<T: Serialize>(value: &T, pretty: bool) -> anyhow::Result<String> {
-    if pretty {
-        Ok(serde_json::to_string_pretty(value)?)
-    } else {
-        Ok(serde_json::to_string(value)?)
+/// What a v2 envelope reports about the current invocation.
+#[derive(Debug, Clone)]
+struct Run {
+    command: String,
+    started: Instant,
+    /// `--contract`, used until (or unless) config is resolved.
+    cli_contract: Option<JsonContractVersion>,
+    /// `settings.output.json.contractVersion` after CLI overrides.
+    contract: Option<JsonContractVersion>,
+    profile: Option<String>,
+}
+
+static RUN: Mutex<Option<Run>> = Mutex::new(None);
+
+/// Record the command being run; `main` calls this once, right after parsing.
+pub fn begin_run(command: &str, cli_contract: Option<JsonContractVersion>) {
+    if let Ok(mut run) = RUN.lock() {
+        *run = Some(Run {
+            command: command.to_string(),
+            started: Instant::now(),
+            cli_contract,
+            contract: None,
+            profile: None,
+        });
     }
 }
 
+/// Record the resolved contract version and profile once a command has loaded config.
+pub fn set_run_config(contract: JsonContractVersion, profile: String) {
+    if let Ok(mut guard) = RUN.lock() {
+        if let Some(run) = guard.as_mut() {
+            run.contract = Some(contract);
+            run.profile = Some(profile);
+        }
+    }
+}
+
+fn current_run() -> Option<Run> {
+    RUN.lock().ok().and_then(|run| run.clone())
+}
+
+fn contract_version(run: &Run) -> JsonContractVersion {
+    run.contract
+        .or(run.cli_contract)
+        .unwrap_or(JsonContractVersion::V1)
+}
+
+/// The run to wrap output for, if contract v2 is in effect.
+fn v2_run() -> Option<Run> {
+    current_run().filter(|run| contract_version(run) == JsonContractVersion::V2)
+}
+
+fn meta(run: &Run, paging: serde_json::Value) -> serde_json::Value {
+    json!({
+        "contract": JsonContractVersion::V2.as_str(),
+        "command": run.command,
+        "profile": run.profile,
+        "elapsedMs": run.started.elapsed().as_millis() as u64,
+        "paging": paging,
+    })
+}
+
+/// Wrap a command payload in the v2 envelope. Paging keys of a paged listing (one carrying
+/// `hasMore`) move to `meta.paging`, and a top-level `warnings` array moves out of `data`.
+fn envelope(run: &Run, mut payload: serde_json::Value) -> serde_json::Value {
+    let mut paging = serde_json::Value::Null;
+    let mut warnings = json!([]);
+    if let Some(map) = payload.as_object_mut() {
+        if map.contains_key("hasMore") {
+            let moved: serde_json::Map<_, _> = PAGING_KEYS
+                .iter()
+                .filter_map(|key| map.remove(*key).map(|value| (key.to_string(), value)))
+                .collect();
+            paging = serde_json::Value::Object(moved);
+        }
+        if map.get("warnings").is_some_and(serde_json::Value::is_array) {
+            warnings = map.remove("warnings").unwrap_or_default();
+        }
+    }
+    json!({
+        "meta": meta(run, paging),
+        "data": payload,
+        "warnings": warnings,
+        "errors": [],
+    })
+}
+
+pub fn emit_json<T: Serialize>(value: &T, pretty: bool) -> anyhow::Result<String> {
+    emit_json_value(&serde_json::to_value(value)?, pretty)
+}
+
 pub fn emit_json_value(value: &serde_json::Value, pretty: bool) -> anyhow::Result<String> {
+    let wrapped;
+    let value = match v2_run() {
+        Some(run) => {
+            wrapped = envelope(&run, value.clone());
+            &wrapped
+        }
+        None => value,
+    };
     if pretty {
         Ok(serde_json::to_string_pretty(value)?)
     } else {
@@ -21,22 +121,96 @@ pub fn emit_json_value(value: &serde_json::Value, pretty: bool) -> anyhow::Resul
 }
 
 pub fn error_json(message: &str, kind: &str) -> serde_json::Value {
+    json!(ErrorOutput {
+        error: error_detail(message, kind),
+    })
+}
+
+fn error_detail(message: &str, kind: &str) -> ErrorDetail {
+    ErrorDetail {
+        message: message.to_string(),
+        kind: kind.to_string(),
+    }
+}
+
+fn error_envelope(run: &Run, message: &str, kind: &str) -> serde_json::Value {
     json!({
-        "error": {
-            "message": message,
-            "kind": kind,
-        }
+        "meta": meta(run, serde_json::Value::Null),
+        "data": null,
+        "warnings": [],
+        "errors": [error_detail(message, kind)],
     })
 }
 
-pub fn result_set_to_json(result_set: &ResultSet) -> serde_json::Value {
+/// The JSON document for a failed command: [`error_json`] under v1, an envelope with
+/// `data: null` and the error in `errors` under v2.
+pub fn emit_error_json(message: &str, kind: &str, pretty: bool) -> anyhow::Result<String> {
+    let value = match v2_run() {
+        Some(run) => error_envelope(&run, message, kind),
+        None => error_json(message, kind),
+    };
+    if pretty {
+        Ok(serde_json::to_string_pretty(&value)?)
+    } else {
+        Ok(serde_json::to_string(&value)?)
+    }
+}
+
+/// `columns` and `rows`, with text and binary cells over `max_value_bytes` cut and marked.
+pub fn result_set_to_json(
+    result_set: &ResultSet,
+    max_value_bytes: Option<usize>,
+) -> serde_json::Value {
+    let result_set = result_set.capped(max_value_bytes);
     json!({
         "columns": result_set.columns,
         "rows": result_set.rows,
     })
 }
 
+/// Like [`result_set_to_json`], but keep at most `max_rows` rows and say so explicitly.
+///
+/// Every result set carries `truncated`; when rows were dropped it also carries
+/// `totalRowsAvailable` and `omittedRows` so consumers know the data is partial.
+pub fn result_set_to_json_sampled(
+    result_set: &ResultSet,
+    max_rows: usize,
+    max_value_bytes: Option<usize>,
+) -> serde_json::Value {
+    let total = result_set.rows.len();
+    if total <= max_rows {
+        let mut value = result_set_to_json(result_set, max_value_bytes);
+        value["truncated"] = json!(false);
+        return value;
+    }
+    let rows: Vec<Vec<Cow<'_, Value>>> = result_set.rows[..max_rows]
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| value.capped(max_value_bytes))
+                .collect()
+        })
+        .collect();
+    json!({
+        "columns": result_set.columns,
+        "rows": rows,
+        "truncated": true,
+        "totalRowsAvailable": total,
+        "omittedRows": total - max_rows,
+    })
+}
+
 pub fn result_set_rows_to_objects(result_set: &ResultSet) -> Vec<serde_json::Value> {
+    result_set_rows_to_maps(result_set)
+        .into_iter()
+        .map(serde_json::Value::Object)
+        .collect()
+}
+
+/// Rows as column-keyed maps, for the typed contracts in [`crate::output::contract`].
+pub fn result_set_rows_to_maps(
+    result_set: &ResultSet,
+) -> Vec<serde_json::Map<String, serde_json::Value>> {
     result_set
         .rows
         .iter()
@@ -46,7 +220,7 @@ pub fn result_set_rows_to_objects(result_set: &ResultSet) -> Vec<serde_json::Val
                 let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
                 map.insert(col.name.clone(), value);
             }
-            serde_json::Value::Object(map)
+            map
         })
         .collect()
 }
@@ -55,6 +229,7 @@ pub fn config_to_json(resolved: &ResolvedConfig) -> serde_json::Value {
     json!({
         "configPath": resolved.config_path.as_ref().map(|p| p.display().to_string()),
         "profileName": resolved.profile_name,
+        "environment": resolved.policy.environment,
         "connection": {
             "server": resolved.connection.server,
             "port": resolved.connection.port,
@@ -63,19 +238,97 @@ pub fn config_to_json(resolved: &ResolvedConfig) -> serde_json::Value {
             "password": resolved.connection.password,
             "encrypt": resolved.connection.encrypt,
             "trustCert": resolved.connection.trust_cert,
+            "tls": {
+                "caCert": resolved.connection.tls.ca_cert.as_ref().map(|p| p.display().to_string()),
+                "strict": resolved.connection.tls.strict,
+                "minVersion": resolved.connection.tls.min_version.map(|v| v.as_str()),
+            },
             "timeoutMs": resolved.connection.timeout_ms,
             "defaultSchemas": resolved.connection.default_schemas,
+            "applicationName": resolved.connection.application_name,
+            "runId": resolved.connection.session.context_info,
+            "session": {
+                "isolationLevel": resolved.connection.session.isolation_level.map(|level| level.as_str()),
+                "lockTimeout": resolved.connection.session.lock_timeout_ms,
+                "deadlockPriority": resolved.connection.session.deadlock_priority.map(|priority| priority.as_str()),
+                "options": resolved
+                    .connection
+                    .session
+                    .options
+                    .iter()
+                    .map(|(name, on)| (name.clone(), json!(on)))
+                    .collect::<serde_json::Map<_, _>>(),
+            },
         },
+        "permissions": {
+            "deny": resolved.policy.deny,
+            "readOnly": resolved.policy.read_only,
+        },
+        "mask": resolved
+            .policy
+            .mask
+            .iter()
+            .map(|(pattern, strategy)| (pattern.clone(), json!(strategy.as_str())))
+            .collect::<serde_json::Map<_, _>>(),
         "settings": {
             "output": {
                 "defaultFormat": resolved.settings.output.default_format.as_str(),
+                "timeZone": resolved.settings.output.time_zone.as_str(),
+                "sourceTimeZone": resolved.settings.output.source_time_zone.map(|zone| zone.as_str()),
+                "stableOrdering": resolved.settings.output.stable_ordering,
+                "wktMaxLength": resolved.settings.output.wkt_max_length,
+                "binary": resolved.settings.output.binary.as_str(),
+                "maxValueBytes": resolved.settings.output.max_value_bytes,
                 "json": {
                     "contractVersion": resolved.settings.output.json.contract_version.as_str(),
                     "pretty": resolved.settings.output.json.pretty,
+                    "largeIntegers": resolved.settings.output.json.large_integers.as_str(),
                 },
                 "csv": {
                     "multiResultNaming": resolved.settings.output.csv.multi_result_naming.as_str(),
+                },
+                "render": {
+                    "detectValues": resolved.settings.output.render.detect_values,
+                    "hyperlinks": resolved.settings.output.render.hyperlinks.as_str(),
+                    "prettyJson": resolved.settings.output.render.pretty_json,
+                    "currencySymbol": resolved.settings.output.render.currency_symbol,
+                },
+                "theme": {
+                    "color": resolved.settings.output.theme.color.as_str(),
+                    "headerColor": resolved.settings.output.theme.header_color.as_str(),
+                    "dimNulls": resolved.settings.output.theme.dim_nulls,
+                    "alignNumbers": resolved.settings.output.theme.align_numbers,
+                    "stripeRows": resolved.settings.output.theme.stripe_rows,
+                    "highlightErrors": resolved.settings.output.theme.highlight_errors,
+                },
+                "table": {
+                    "nullDisplay": resolved.settings.output.table.null_display,
+                    "booleans": resolved.settings.output.table.booleans.as_str(),
+                    "maxCellWidth": resolved.settings.output.table.max_cell_width,
+                    "maxOutputChars": resolved.settings.output.table.max_output_chars,
                 }
+            },
+            "migrations": {
+                "directory": resolved.settings.migrations.directory.display().to_string(),
+            },
+            "serve": {
+                "bind": resolved.settings.serve.bind,
+                "port": resolved.settings.serve.port,
+                "tokenEnv": resolved.settings.serve.token_env,
+                "profiles": resolved.settings.serve.profiles,
+            },
+            "audit": {
+                "file": resolved.settings.audit.file.as_ref().map(|path| path.display().to_string()),
+                "syslog": resolved.settings.audit.syslog,
+            },
+            "metadataCache": {
+                "enabled": resolved.settings.metadata_cache.enabled,
+                "ttlSeconds": resolved.settings.metadata_cache.ttl_seconds,
+            },
+            "formatting": {
+                "keywordCase": resolved.settings.formatting.keyword_case.as_str(),
+                "commas": resolved.settings.formatting.commas.as_str(),
+                "indent": resolved.settings.formatting.indent,
             }
         }
     })
@@ -84,7 +337,7 @@ pub fn config_to_json(resolved: &ResolvedConfig) -> serde_json::Value {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{ConnectionSettings, SettingsResolved};
+    use crate::config::{ConnectionSettings, ProfilePolicy, SettingsResolved};
     use crate::db::types::{Column, ResultSet, Value};
 
     #[test]
@@ -94,6 +347,40 @@ mod tests {
         assert_eq!(value["error"]["kind"], "Internal");
     }
 
+    #[test]
+    fn v2_envelope_moves_paging_and_warnings_out_of_data() {
+        let run = Run {
+            command: "tables".to_string(),
+            started: Instant::now(),
+            cli_contract: Some(JsonContractVersion::V2),
+            contract: None,
+            profile: Some("dev".to_string()),
+        };
+        assert_eq!(contract_version(&run), JsonContractVersion::V2);
+
+        let payload = json!({
+            "total": 3, "count": 1, "offset": 0, "limit": 1, "hasMore": true, "nextOffset": 1,
+            "tables": [{"name": "Orders"}],
+            "warnings": ["partial"],
+        });
+        let value = envelope(&run, payload);
+        assert_eq!(value["meta"]["command"], "tables");
+        assert_eq!(value["meta"]["profile"], "dev");
+        assert_eq!(value["meta"]["paging"]["hasMore"], true);
+        assert_eq!(value["meta"]["paging"]["total"], 3);
+        assert_eq!(value["data"], json!({"tables": [{"name": "Orders"}]}));
+        assert_eq!(value["warnings"], json!(["partial"]));
+        assert_eq!(value["errors"], json!([]));
+
+        let value = envelope(&run, json!({"status": "ok"}));
+        assert!(value["meta"]["paging"].is_null());
+        assert_eq!(value["warnings"], json!([]));
+
+        let value = error_envelope(&run, "boom", "Query");
+        assert!(value["data"].is_null());
+        assert_eq!(value["errors"][0]["kind"], "Query");
+    }
+
     #[test]
     fn config_json_includes_defaults() {
         let resolved = ResolvedConfig {
@@ -101,12 +388,42 @@ mod tests {
             profile_name: "default".to_string(),
             connection: ConnectionSettings::default(),
             settings: SettingsResolved::default(),
+            policy: ProfilePolicy::default(),
         };
         let value = config_to_json(&resolved);
         assert_eq!(value["profileName"], "default");
         assert_eq!(value["settings"]["output"]["defaultFormat"], "pretty");
     }
 
+    #[test]
+    fn sampled_result_set_reports_omitted_rows() {
+        let result_set = ResultSet {
+            columns: vec![Column {
+                name: "n".to_string(),
+                data_type: None,
+            }],
+            rows: (0..5).map(|n| vec![Value::Int(n)]).collect(),
+        };
+        let value = result_set_to_json_sampled(&result_set, 2, None);
+        assert_eq!(value["truncated"], true);
+        assert_eq!(value["rows"].as_array().map(Vec::len), Some(2));
+        assert_eq!(value["totalRowsAvailable"], 5);
+        assert_eq!(value["omittedRows"], 3);
+
+        let value = result_set_to_json_sampled(&result_set, 10, None);
+        assert_eq!(value["truncated"], false);
+        assert!(value.get("omittedRows").is_none());
+
+        let text = ResultSet {
+            columns: result_set.columns.clone(),
+            rows: vec![vec![Value::Text("abcdef".to_string())]; 2],
+        };
+        let value = result_set_to_json_sampled(&text, 1, Some(2));
+        assert_eq!(value["rows"][0][0], "ab... (6 bytes)");
+        let value = result_set_to_json(&text, None);
+        assert_eq!(value["rows"][0][0], "abcdef");
+    }
+
     #[test]
     fn result_set_rows_to_objects_builds_maps() {
         let result_set = ResultSet {
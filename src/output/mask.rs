@@ -0,0 +1,207 @@
+//! Column masking from the profile's `mask` rules.
+//!
+//! Rules are keyed by `schema.table.column` patterns; leading parts may be left out (`Email`,
+//! `Users.Email`) and any part may use `*` and `?`. Result sets are masked before anything is
+//! written, so tables, JSON, `--out` files, and `--to` uploads all carry the masked values. When
+//! the rows' source table is not known (`sql`), only the column part of a rule is compared. When
+//! several rules match a column, the strategy that hides the most wins.
+
+use regex::Regex;
+
+use crate::config::MaskStrategy;
+use crate::db::types::{ResultSet, Value};
+
+const REDACTED: &str = "****";
+/// Characters `partial` leaves visible at the end of a value.
+const PARTIAL_KEEP: usize = 4;
+/// Hex digits of the SHA-256 digest `hash` shows.
+const HASH_DIGITS: usize = 16;
+
+/// Mask the columns of `rs` that a rule covers; `source` is `(schema, table)` when known.
+pub fn apply(rs: &mut ResultSet, rules: &[(String, MaskStrategy)], source: Option<(&str, &str)>) {
+    if rules.is_empty() {
+        return;
+    }
+    let strategies: Vec<Option<MaskStrategy>> = rs
+        .columns
+        .iter()
+        .map(|column| strategy_for(rules, &column.name, source))
+        .collect();
+    if strategies.iter().all(Option::is_none) {
+        return;
+    }
+    for (column, strategy) in rs.columns.iter_mut().zip(&strategies) {
+        if strategy.is_some() {
+            // The values are no longer of the reported type (money, GUIDs, ...).
+            column.data_type = None;
+        }
+    }
+    for row in &mut rs.rows {
+        for (value, strategy) in row.iter_mut().zip(&strategies) {
+            if let Some(strategy) = strategy {
+                *value = mask_value(value, *strategy);
+            }
+        }
+    }
+}
+
+/// Whether any rule masks `column`; `source` as for [`apply`].
+pub fn covers(
+    rules: &[(String, MaskStrategy)],
+    column: &str,
+    source: Option<(&str, &str)>,
+) -> bool {
+    strategy_for(rules, column, source).is_some()
+}
+
+fn strategy_for(
+    rules: &[(String, MaskStrategy)],
+    column: &str,
+    source: Option<(&str, &str)>,
+) -> Option<MaskStrategy> {
+    rules
+        .iter()
+        .filter(|(pattern, _)| rule_matches(pattern, column, source))
+        .map(|(_, strategy)| *strategy)
+        .max()
+}
+
+fn rule_matches(pattern: &str, column: &str, source: Option<(&str, &str)>) -> bool {
+    // Column first, then table, then schema.
+    let parts: Vec<&str> = pattern.rsplitn(3, '.').collect();
+    if !glob_match(parts[0], column) {
+        return false;
+    }
+    let Some((schema, table)) = source else {
+        return true;
+    };
+    parts.get(1).is_none_or(|part| glob_match(part, table))
+        && parts.get(2).is_none_or(|part| glob_match(part, schema))
+}
+
+/// Case-insensitive glob with `*` and `?`; brackets around the name are ignored.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches('[').trim_end_matches(']');
+    let regex = format!(
+        "(?i)^{}$",
+        regex::escape(pattern)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".")
+    );
+    Regex::new(&regex).is_ok_and(|re| re.is_match(text))
+}
+
+fn mask_value(value: &Value, strategy: MaskStrategy) -> Value {
+    if matches!(value, Value::Null) || strategy == MaskStrategy::Nullify {
+        return Value::Null;
+    }
+    let text = match value {
+        Value::Text(text) => text.clone(),
+        other => other.as_csv(),
+    };
+    Value::Text(match strategy {
+        MaskStrategy::Redact | MaskStrategy::Nullify => REDACTED.to_string(),
+        MaskStrategy::Hash => {
+            let digest = ring::digest::digest(&ring::digest::SHA256, text.as_bytes());
+            digest
+                .as_ref()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()[..HASH_DIGITS]
+                .to_string()
+        }
+        MaskStrategy::Partial => {
+            let chars: Vec<char> = text.chars().collect();
+            // Too short to show any of it without showing most of it.
+            let hidden = if chars.len() <= PARTIAL_KEEP {
+                chars.len()
+            } else {
+                chars.len() - PARTIAL_KEEP
+            };
+            "*".repeat(hidden) + &chars[hidden..].iter().collect::<String>()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn rules(entries: &[(&str, MaskStrategy)]) -> Vec<(String, MaskStrategy)> {
+        entries
+            .iter()
+            .map(|(pattern, strategy)| (pattern.to_string(), *strategy))
+            .collect()
+    }
+
+    fn result_set(columns: &[&str], row: Vec<Value>) -> ResultSet {
+        ResultSet {
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: Some("nvarchar".to_string()),
+                })
+                .collect(),
+            rows: vec![row],
+        }
+    }
+
+    #[test]
+    fn strategies_mask_each_value() {
+        let text = Value::Text("123-45-6789".to_string());
+        assert_eq!(
+            mask_value(&text, MaskStrategy::Partial),
+            Value::Text("*******6789".to_string())
+        );
+        assert_eq!(
+            mask_value(&Value::Text("abc".to_string()), MaskStrategy::Partial),
+            Value::Text("***".to_string())
+        );
+        assert_eq!(
+            mask_value(&text, MaskStrategy::Redact),
+            Value::Text("****".to_string())
+        );
+        assert_eq!(mask_value(&text, MaskStrategy::Nullify), Value::Null);
+        assert_eq!(mask_value(&Value::Null, MaskStrategy::Redact), Value::Null);
+        let Value::Text(hash) = mask_value(&text, MaskStrategy::Hash) else {
+            panic!("hash should be text");
+        };
+        assert_eq!(hash.len(), HASH_DIGITS);
+        assert_eq!(mask_value(&text, MaskStrategy::Hash), Value::Text(hash));
+    }
+
+    #[test]
+    fn rules_match_by_table_when_known_and_by_column_otherwise() {
+        let rules = rules(&[
+            ("dbo.Users.Email", MaskStrategy::Hash),
+            ("*.SSN", MaskStrategy::Redact),
+            ("ssn", MaskStrategy::Partial),
+        ]);
+        let row = vec![
+            Value::Int(1),
+            Value::Text("a@example.com".to_string()),
+            Value::Text("123-45-6789".to_string()),
+        ];
+
+        let mut rs = result_set(&["Id", "Email", "SSN"], row.clone());
+        apply(&mut rs, &rules, Some(("sales", "Users")));
+        assert_eq!(rs.rows[0][1], row[1]);
+        assert_eq!(rs.rows[0][2], Value::Text("****".to_string()));
+        assert_eq!(rs.columns[0].data_type.as_deref(), Some("nvarchar"));
+        assert!(rs.columns[2].data_type.is_none());
+
+        let mut rs = result_set(&["Id", "Email", "SSN"], row.clone());
+        apply(&mut rs, &rules, Some(("DBO", "users")));
+        assert_ne!(rs.rows[0][1], row[1]);
+
+        let mut rs = result_set(&["Id", "email", "SSN"], row.clone());
+        apply(&mut rs, &rules, None);
+        assert_eq!(rs.rows[0][0], Value::Int(1));
+        assert_ne!(rs.rows[0][1], row[1]);
+
+        assert!(covers(&rules, "ssn", Some(("sales", "People"))));
+        assert!(!covers(&rules, "Email", Some(("sales", "Users"))));
+    }
+}
@@ -1,6 +1,18 @@
+pub mod blobs;
+pub mod classify;
+pub mod contract;
 pub mod csv;
+pub mod fields;
+#[cfg(test)]
+pub(crate) mod fixtures;
 pub mod json;
+pub mod mask;
+pub mod patch;
+pub mod remote;
+pub mod shape;
+pub mod sink;
 pub mod table;
+pub mod theme;
 
 use std::io::IsTerminal;
 
@@ -16,6 +28,9 @@ pub fn select_format(flags: &OutputFlags, settings: &SettingsResolved) -> Output
     if flags.markdown {
         return OutputFormat::Markdown;
     }
+    if flags.expanded {
+        return OutputFormat::Expanded;
+    }
     if flags.pretty {
         return OutputFormat::Pretty;
     }
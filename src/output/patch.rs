@@ -0,0 +1,170 @@
+//! `git apply`-compatible unified diffs of SQL scripts.
+//!
+//! Each side is written out as a script closed with `GO`, and hunk context stops at `GO` lines,
+//! so a hunk never reaches into a neighbouring batch and reviewers see whole-batch changes.
+
+use similar::{DiffOp, DiffTag, TextDiff};
+
+/// Unchanged lines kept around each change, as `git diff` does.
+const CONTEXT: usize = 3;
+
+/// Script text for a definition: CRLF normalized, trailing whitespace trimmed, closed with `GO`.
+pub fn script(definition: &str) -> String {
+    format!("{}\nGO\n", definition.replace("\r\n", "\n").trim_end())
+}
+
+/// A `diff --git` section for `path`. `None` on one side makes it a file creation or deletion;
+/// identical sides (or two missing ones) produce an empty string.
+pub fn file_diff(path: &str, old: Option<&str>, new: Option<&str>) -> String {
+    let header = match (old, new) {
+        (None, None) => return String::new(),
+        (None, Some(_)) => format!("new file mode 100644\n--- /dev/null\n+++ b/{path}\n"),
+        (Some(_), None) => format!("deleted file mode 100644\n--- a/{path}\n+++ /dev/null\n"),
+        (Some(_), Some(_)) => format!("--- a/{path}\n+++ b/{path}\n"),
+    };
+    let old_text = old.map(script).unwrap_or_default();
+    let new_text = new.map(script).unwrap_or_default();
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+    let (old_lines, new_lines) = (diff.old_slices(), diff.new_slices());
+    let hunks = batch_hunks(diff.ops(), old_lines, CONTEXT);
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("diff --git a/{path} b/{path}\n{header}");
+    for hunk in hunks {
+        let old_range = hunk_range(&hunk, DiffOp::old_range);
+        let new_range = hunk_range(&hunk, DiffOp::new_range);
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range_spec(old_range),
+            range_spec(new_range)
+        ));
+        for op in &hunk {
+            let (tag, old_r, new_r) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                push_lines(&mut out, ' ', &old_lines[old_r]);
+                continue;
+            }
+            push_lines(&mut out, '-', &old_lines[old_r]);
+            push_lines(&mut out, '+', &new_lines[new_r]);
+        }
+    }
+    out
+}
+
+/// Group `ops` into hunks with up to `context` lines either side of each change. Context never
+/// crosses a `GO` line: trailing context ends on the first one, leading context starts after
+/// the last one, and a `GO` between two changes always splits them into separate hunks.
+fn batch_hunks(ops: &[DiffOp], old_lines: &[&str], context: usize) -> Vec<Vec<DiffOp>> {
+    let is_go = |idx: usize| old_lines[idx].trim().eq_ignore_ascii_case("GO");
+    let mut hunks = Vec::new();
+    let mut current: Vec<DiffOp> = Vec::new();
+    for (pos, op) in ops.iter().enumerate() {
+        let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = *op
+        else {
+            current.push(*op);
+            continue;
+        };
+        let (first, last) = (pos == 0, pos + 1 == ops.len());
+        let go_lines: Vec<usize> = (0..len).filter(|i| is_go(old_index + i)).collect();
+        if !first && !last && go_lines.is_empty() && len <= context * 2 {
+            current.push(*op);
+            continue;
+        }
+        if !first {
+            let take = go_lines
+                .first()
+                .map_or(context, |&go| context.min(go + 1))
+                .min(len);
+            if take > 0 {
+                current.push(DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len: take,
+                });
+            }
+            hunks.push(std::mem::take(&mut current));
+        }
+        if !last {
+            let after_go = go_lines.last().map_or(0, |&go| go + 1);
+            let take = (len - after_go).min(context);
+            if take > 0 {
+                current.push(DiffOp::Equal {
+                    old_index: old_index + len - take,
+                    new_index: new_index + len - take,
+                    len: take,
+                });
+            }
+        }
+    }
+    if current.iter().any(|op| op.tag() != DiffTag::Equal) {
+        hunks.push(current);
+    }
+    hunks
+}
+
+fn hunk_range(
+    hunk: &[DiffOp],
+    range: fn(&DiffOp) -> std::ops::Range<usize>,
+) -> std::ops::Range<usize> {
+    let start = hunk.first().map_or(0, |op| range(op).start);
+    let end = hunk.last().map_or(0, |op| range(op).end);
+    start..end
+}
+
+/// `start,len` in 1-based lines; an empty range names the line before it.
+fn range_spec(range: std::ops::Range<usize>) -> String {
+    match range.len() {
+        0 => format!("{},0", range.start),
+        1 => format!("{}", range.start + 1),
+        len => format!("{},{}", range.start + 1, len),
+    }
+}
+
+fn push_lines(out: &mut String, prefix: char, lines: &[&str]) {
+    for line in lines {
+        out.push(prefix);
+        out.push_str(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_git_headers_and_batch_scoped_hunks() {
+        let old = "CREATE PROCEDURE dbo.p AS\nSELECT 1;\n";
+        let new = "CREATE PROCEDURE dbo.p AS\r\nSELECT 2;\r\n";
+        assert_eq!(
+            file_diff("dbo.p.sql", Some(old), Some(new)),
+            "diff --git a/dbo.p.sql b/dbo.p.sql\n--- a/dbo.p.sql\n+++ b/dbo.p.sql\n\
+             @@ -1,3 +1,3 @@\n CREATE PROCEDURE dbo.p AS\n-SELECT 1;\n+SELECT 2;\n GO\n"
+        );
+        assert_eq!(file_diff("dbo.p.sql", Some(old), Some(old)), "");
+
+        let created = file_diff("dbo.q.sql", None, Some("SELECT 1;"));
+        assert!(created.contains("new file mode 100644\n--- /dev/null\n+++ b/dbo.q.sql\n"));
+        assert!(created.ends_with("@@ -0,0 +1,2 @@\n+SELECT 1;\n+GO\n"));
+        let deleted = file_diff("dbo.q.sql", Some("SELECT 1;"), None);
+        assert!(deleted.ends_with("@@ -1,2 +0,0 @@\n-SELECT 1;\n-GO\n"));
+
+        // Changes either side of a GO get separate hunks whose context stops at it.
+        let old = "A\nB\nGO\nC\nD";
+        let new = "A\nB2\nGO\nC2\nD";
+        let diff = file_diff("x.sql", Some(old), Some(new));
+        let hunks: Vec<&str> = diff.split("@@ -").skip(1).collect();
+        assert_eq!(
+            hunks,
+            vec![
+                "1,3 +1,3 @@\n A\n-B\n+B2\n GO\n",
+                "4,3 +4,3 @@\n-C\n+C2\n D\n GO\n"
+            ]
+        );
+    }
+}
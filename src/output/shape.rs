@@ -0,0 +1,268 @@
+//! `--filter`, `--sort`, and `--fields`: client-side reshaping of a listing before it is
+//! rendered. They only see the rows already fetched, so on paged commands they apply to the
+//! current page.
+
+use std::cmp::Ordering;
+
+use crate::cli::OutputFlags;
+use crate::db::types::{ResultSet, Value};
+use crate::error::{AppError, ErrorKind};
+use crate::output::fields;
+
+/// Filter, then sort, then project `result_set` as the global output flags ask.
+pub fn apply(mut result_set: ResultSet, flags: &OutputFlags) -> Result<ResultSet, AppError> {
+    // Empty results carry no column metadata, so there is nothing to check names against.
+    if result_set.columns.is_empty() {
+        return Ok(result_set);
+    }
+    for raw in &flags.filters {
+        let filter = Filter::parse(raw)?;
+        let idx = column_index(&result_set, &filter.column)?;
+        result_set
+            .rows
+            .retain(|row| row.get(idx).is_some_and(|value| filter.matches(value)));
+    }
+    if !flags.sort.is_empty() {
+        let keys = flags
+            .sort
+            .iter()
+            .map(|raw| {
+                let (name, descending) = parse_sort_key(raw)?;
+                Ok((column_index(&result_set, name)?, descending))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        result_set.rows.sort_by(|a, b| {
+            keys.iter()
+                .map(|&(idx, descending)| match (&a[idx], &b[idx]) {
+                    // Nulls stay last whichever way the key sorts.
+                    (Value::Null, Value::Null) => Ordering::Equal,
+                    (Value::Null, _) => Ordering::Greater,
+                    (_, Value::Null) => Ordering::Less,
+                    (a, b) if descending => compare_values(a, b).reverse(),
+                    (a, b) => compare_values(a, b),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+    fields::project(result_set, &flags.fields)
+}
+
+fn column_index(result_set: &ResultSet, name: &str) -> Result<usize, AppError> {
+    result_set
+        .columns
+        .iter()
+        .position(|column| column.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| fields::unknown_field(result_set, name))
+}
+
+/// `rowCount:desc` is `("rowCount", true)`; `name` and `name:asc` sort ascending.
+fn parse_sort_key(raw: &str) -> Result<(&str, bool), AppError> {
+    let (name, direction) = raw.split_once(':').unwrap_or((raw, "asc"));
+    let descending = match direction.trim().to_ascii_lowercase().as_str() {
+        "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(AppError::new(
+                ErrorKind::Config,
+                format!("Invalid --sort direction '{}' (use asc or desc)", other),
+            ));
+        }
+    };
+    Ok((name.trim(), descending))
+}
+
+/// Numbers compare numerically and text case-insensitively.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::DateTime(a), Value::DateTime(b)) => a.datetime.cmp(&b.datetime),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        _ => match (number(a), number(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => a.as_csv().to_lowercase().cmp(&b.as_csv().to_lowercase()),
+        },
+    }
+}
+
+/// Numeric view of a cell, parsing text.
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// `~`: case-insensitive substring.
+    Contains,
+}
+
+/// One `--filter` clause: `column op value`.
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    column: String,
+    op: Op,
+    value: String,
+}
+
+impl Filter {
+    fn parse(raw: &str) -> Result<Self, AppError> {
+        const OPS: &[(&str, Op)] = &[
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("!=", Op::Ne),
+            ("<>", Op::Ne),
+            ("=", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("~", Op::Contains),
+        ];
+        let found = raw
+            .char_indices()
+            .find_map(|(pos, _)| {
+                OPS.iter()
+                    .find(|(token, _)| raw[pos..].starts_with(token))
+                    .map(|(token, op)| (pos, *token, *op))
+            })
+            .filter(|(pos, _, _)| *pos > 0);
+        let Some((pos, token, op)) = found else {
+            return Err(AppError::new(
+                ErrorKind::Config,
+                format!(
+                    "Invalid --filter '{}' (expected column, operator =, !=, >, >=, <, <=, or ~, then a value)",
+                    raw
+                ),
+            ));
+        };
+        let value = raw[pos + token.len()..].trim();
+        let value = value
+            .strip_prefix('\'')
+            .and_then(|v| v.strip_suffix('\''))
+            .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+            .unwrap_or(value);
+        Ok(Self {
+            column: raw[..pos].trim().to_string(),
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    /// `null` matches nulls with `=` and non-nulls with `!=`; other comparisons skip nulls.
+    fn matches(&self, cell: &Value) -> bool {
+        if self.value.eq_ignore_ascii_case("null") && matches!(self.op, Op::Eq | Op::Ne) {
+            return matches!(cell, Value::Null) == (self.op == Op::Eq);
+        }
+        if matches!(cell, Value::Null) {
+            return false;
+        }
+        let text = cell.as_csv();
+        if self.op == Op::Contains {
+            return text.to_lowercase().contains(&self.value.to_lowercase());
+        }
+        let ordering = match (number(cell), self.value.parse::<f64>()) {
+            (Some(a), Ok(b)) => a.total_cmp(&b),
+            _ => text.to_lowercase().cmp(&self.value.to_lowercase()),
+        };
+        match self.op {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Contains => unreachable!("handled above"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    fn tables() -> ResultSet {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let row = |name: &str, rows: i64, size: &str| {
+            vec![
+                Value::Text(name.to_string()),
+                Value::Int(rows),
+                Value::Text(size.to_string()),
+            ]
+        };
+        ResultSet {
+            columns: vec![column("name"), column("rowCount"), column("sizeMb")],
+            rows: vec![
+                row("Orders", 5000, "12.50"),
+                row("audit_log", 900, "3.00"),
+                row("Customers", 1200, "100.25"),
+                vec![Value::Text("Empty".to_string()), Value::Null, Value::Null],
+            ],
+        }
+    }
+
+    fn flags(filters: &[&str], sort: &[&str], fields: &[&str]) -> OutputFlags {
+        let owned = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        OutputFlags {
+            json: false,
+            markdown: false,
+            pretty: false,
+            expanded: false,
+            no_color: false,
+            fields: owned(fields),
+            sort: owned(sort),
+            filters: owned(filters),
+        }
+    }
+
+    fn names(result_set: &ResultSet) -> Vec<String> {
+        result_set.rows.iter().map(|row| row[0].as_csv()).collect()
+    }
+
+    #[test]
+    fn filters_and_sorts_rows() {
+        let shaped = apply(tables(), &flags(&["rowCount>1000"], &["sizeMb:desc"], &[])).unwrap();
+        assert_eq!(names(&shaped), vec!["Customers", "Orders"]);
+
+        let shaped = apply(tables(), &flags(&["name ~ LOG"], &[], &[])).unwrap();
+        assert_eq!(names(&shaped), vec!["audit_log"]);
+
+        let shaped = apply(tables(), &flags(&["rowCount = null"], &[], &[])).unwrap();
+        assert_eq!(names(&shaped), vec!["Empty"]);
+
+        // Text sorts case-insensitively and nulls go last in either direction.
+        let shaped = apply(tables(), &flags(&[], &["name"], &[])).unwrap();
+        assert_eq!(
+            names(&shaped),
+            vec!["audit_log", "Customers", "Empty", "Orders"]
+        );
+        let shaped = apply(tables(), &flags(&[], &["ROWCOUNT:desc"], &["name"])).unwrap();
+        assert_eq!(
+            names(&shaped),
+            vec!["Orders", "Customers", "audit_log", "Empty"]
+        );
+        assert_eq!(shaped.columns.len(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_columns_and_bad_syntax() {
+        let err = apply(tables(), &flags(&["size>1"], &[], &[])).unwrap_err();
+        assert!(err.message.contains("Unknown field 'size'"));
+        assert!(apply(tables(), &flags(&["rowCount"], &[], &[])).is_err());
+        assert!(apply(tables(), &flags(&[], &["name:down"], &[])).is_err());
+        assert_eq!(
+            Filter::parse("status != 'sleeping'").unwrap(),
+            Filter {
+                column: "status".to_string(),
+                op: Op::Ne,
+                value: "sleeping".to_string(),
+            }
+        );
+    }
+}
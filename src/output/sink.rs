@@ -0,0 +1,202 @@
+//! `--out` sinks: files that receive a command's result sets alongside what it prints.
+//!
+//! The format comes from the file extension, so one run can write `results.json` and
+//! `results.csv` without re-running the query. Multiple result sets go into one JSON or
+//! Markdown file; CSV and JSON Lines write one file per result set, named as `--csv` names them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::config::{CsvMultiResultNaming, OutputFormat};
+use crate::db::types::ResultSet;
+use crate::error::{AppError, ErrorKind};
+use crate::output::{TableOptions, csv, json as json_out, table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkFormat {
+    Csv,
+    Json,
+    JsonLines,
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sink {
+    pub path: PathBuf,
+    pub format: SinkFormat,
+}
+
+impl Sink {
+    /// A sink for `path`, with the format taken from its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        let format = match extension.as_deref() {
+            Some("csv") => SinkFormat::Csv,
+            Some("json") => SinkFormat::Json,
+            Some("jsonl") | Some("ndjson") => SinkFormat::JsonLines,
+            Some("md") | Some("markdown") => SinkFormat::Markdown,
+            _ => {
+                return Err(AppError::new(
+                    ErrorKind::Config,
+                    format!(
+                        "Cannot tell the format of --out {}; use a .csv, .json, .jsonl, or .md file",
+                        path.display()
+                    ),
+                )
+                .into());
+            }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+        })
+    }
+
+    /// Write `result_sets` to this sink, returning the files written. Text and binary cells over
+    /// `max_value_bytes` are cut and marked.
+    pub fn write(
+        &self,
+        result_sets: &[ResultSet],
+        naming: CsvMultiResultNaming,
+        max_value_bytes: Option<usize>,
+    ) -> Result<Vec<PathBuf>> {
+        match self.format {
+            SinkFormat::Csv => {
+                csv::write_result_sets(&self.path, result_sets, naming, max_value_bytes)
+            }
+            SinkFormat::Json => {
+                let sets: Vec<_> = result_sets
+                    .iter()
+                    .map(|result_set| json_out::result_set_to_json(result_set, max_value_bytes))
+                    .collect();
+                let body = serde_json::to_string_pretty(&json!({ "resultSets": sets }))?;
+                write_file(&self.path, format!("{}\n", body))
+            }
+            SinkFormat::JsonLines => {
+                let multiple = result_sets.len() > 1;
+                let mut paths = Vec::new();
+                for (index, result_set) in result_sets.iter().enumerate() {
+                    let target = csv::expand_csv_path(&self.path, index + 1, multiple, naming);
+                    let mut body = String::new();
+                    let result_set = result_set.capped(max_value_bytes);
+                    for row in json_out::result_set_rows_to_objects(&result_set) {
+                        body.push_str(&row.to_string());
+                        body.push('\n');
+                    }
+                    paths.extend(write_file(&target, body)?);
+                }
+                Ok(paths)
+            }
+            SinkFormat::Markdown => {
+                let mut options = TableOptions::unlimited();
+                options.max_value_bytes = max_value_bytes;
+                let body = result_sets
+                    .iter()
+                    .enumerate()
+                    .map(|(index, result_set)| {
+                        let rendered = table::render_result_set_table(
+                            result_set,
+                            OutputFormat::Markdown,
+                            &options,
+                        )
+                        .output;
+                        if result_sets.len() > 1 {
+                            format!("## Result set {}\n\n{}\n", index + 1, rendered)
+                        } else {
+                            format!("{}\n", rendered)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                write_file(&self.path, body)
+            }
+        }
+    }
+}
+
+fn write_file(path: &Path, body: String) -> Result<Vec<PathBuf>> {
+    fs::write(path, body).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(vec![path.to_path_buf()])
+}
+
+/// Parse every `--out` path up front, so a bad extension fails before the query runs.
+pub fn parse_sinks(paths: &[PathBuf]) -> Result<Vec<Sink>> {
+    paths.iter().map(|path| Sink::from_path(path)).collect()
+}
+
+/// Write `result_sets` to each sink in turn, returning every file written.
+pub fn write_sinks(
+    sinks: &[Sink],
+    result_sets: &[ResultSet],
+    naming: CsvMultiResultNaming,
+    max_value_bytes: Option<usize>,
+) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for sink in sinks {
+        paths.extend(sink.write(result_sets, naming, max_value_bytes)?);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::fixtures::sample_result_set;
+
+    #[test]
+    fn infers_format_from_extension() {
+        let format = |path: &str| Sink::from_path(Path::new(path)).map(|sink| sink.format);
+        assert_eq!(format("out/results.CSV").unwrap(), SinkFormat::Csv);
+        assert_eq!(format("results.json").unwrap(), SinkFormat::Json);
+        assert_eq!(format("results.ndjson").unwrap(), SinkFormat::JsonLines);
+        assert_eq!(format("results.md").unwrap(), SinkFormat::Markdown);
+        assert!(format("results.xlsx").is_err());
+        assert!(format("results").is_err());
+    }
+
+    #[test]
+    fn writes_every_sink_from_one_run() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        let sinks = parse_sinks(&[
+            dir.join("results.json"),
+            dir.join("results.csv"),
+            dir.join("results.jsonl"),
+            dir.join("results.md"),
+        ])
+        .expect("sinks");
+        let result_sets = vec![sample_result_set()];
+
+        let paths = write_sinks(
+            &sinks,
+            &result_sets,
+            CsvMultiResultNaming::SuffixNumber,
+            None,
+        )
+        .expect("write sinks");
+        assert_eq!(paths.len(), 4);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("results.json")).unwrap()).unwrap();
+        assert_eq!(json["resultSets"][0]["rows"][0][1], "Ada");
+        assert_eq!(
+            fs::read_to_string(dir.join("results.csv")).unwrap(),
+            "id,name\n1,Ada\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("results.jsonl")).unwrap(),
+            "{\"id\":1,\"name\":\"Ada\"}\n"
+        );
+        assert!(
+            fs::read_to_string(dir.join("results.md"))
+                .unwrap()
+                .contains("| id | name |")
+        );
+    }
+}
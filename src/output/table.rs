@@ -1,12 +1,15 @@
-use comfy_table::{ContentArrangement, Table, presets};
+use std::sync::OnceLock;
 
-use crate::config::OutputFormat;
+use comfy_table::{CellAlignment, ContentArrangement, Table, presets};
+
+use crate::config::{BooleanStyle, OutputFormat, RenderSettingsResolved, TableSettingsResolved};
 use crate::db::types::{ResultSet, Value};
+use crate::output::classify::{self, ValueClass};
+use crate::output::theme;
 
-const NULL_DISPLAY: &str = "—";
 const ELLIPSIS: &str = "…";
-const QUERY_MAX_CELL_WIDTH: usize = 140;
-const QUERY_MAX_OUTPUT_CHARS: usize = 25_000;
+/// Separator between names and values in expanded output.
+const EXPANDED_SEPARATOR: &str = " | ";
 
 #[derive(Debug, Clone)]
 pub struct Pagination {
@@ -33,11 +36,31 @@ pub struct RenderResult {
     pub truncation: TruncationInfo,
 }
 
+static TABLE_SETTINGS: OnceLock<TableSettingsResolved> = OnceLock::new();
+
+/// Install the resolved `output.table` settings; later calls are ignored.
+pub fn set_table_settings(settings: TableSettingsResolved) {
+    let _ = TABLE_SETTINGS.set(settings);
+}
+
+pub fn table_settings() -> TableSettingsResolved {
+    TABLE_SETTINGS.get().cloned().unwrap_or_default()
+}
+
+/// Render a boolean the way tables do, for commands that build text by hand.
+pub fn format_bool(value: bool) -> &'static str {
+    table_settings().booleans.render(value)
+}
+
 #[derive(Debug, Clone)]
 pub struct TableOptions {
     pub max_cell_width: usize,
     pub max_output_chars: usize,
+    pub null_display: String,
+    pub booleans: BooleanStyle,
     pub pagination: Option<Pagination>,
+    /// Text and binary cells over this many bytes are cut and marked (`--max-value-bytes`).
+    pub max_value_bytes: Option<usize>,
 }
 
 impl Default for TableOptions {
@@ -47,21 +70,30 @@ impl Default for TableOptions {
 }
 
 impl TableOptions {
-    /// Create options with query-oriented truncation limits.
+    /// Create options with query-oriented truncation limits (`output.table.maxCellWidth` and
+    /// `maxOutputChars`).
     pub fn truncated() -> Self {
+        let settings = table_settings();
         Self {
-            max_cell_width: QUERY_MAX_CELL_WIDTH,
-            max_output_chars: QUERY_MAX_OUTPUT_CHARS,
+            max_cell_width: settings.max_cell_width.unwrap_or(usize::MAX),
+            max_output_chars: settings.max_output_chars.unwrap_or(usize::MAX),
+            null_display: settings.null_display,
+            booleans: settings.booleans,
             pagination: None,
+            max_value_bytes: None,
         }
     }
 
     /// Create options with no truncation limits.
     pub fn unlimited() -> Self {
+        let settings = table_settings();
         Self {
             max_cell_width: usize::MAX,
             max_output_chars: usize::MAX,
+            null_display: settings.null_display,
+            booleans: settings.booleans,
             pagination: None,
+            max_value_bytes: None,
         }
     }
 }
@@ -71,6 +103,9 @@ pub fn render_result_set_table(
     format: OutputFormat,
     options: &TableOptions,
 ) -> RenderResult {
+    if matches!(format, OutputFormat::Expanded) {
+        return render_expanded(result_set, options);
+    }
     let mut table = Table::new();
     match format {
         OutputFormat::Markdown => {
@@ -86,20 +121,46 @@ pub fn render_result_set_table(
         column.set_padding((0, 0));
     }
 
+    let styling = theme::styling(format);
     let headers = result_set
         .columns
         .iter()
-        .map(|col| col.name.clone())
+        .map(|col| styling.header_cell(col.name.clone()))
         .collect::<Vec<_>>();
     table.set_header(headers);
+    if styling.theme.align_numbers && matches!(format, OutputFormat::Pretty) {
+        for idx in 0..result_set.columns.len() {
+            if theme::is_numeric_column(result_set, idx) {
+                if let Some(column) = table.column_mut(idx) {
+                    column.set_cell_alignment(CellAlignment::Right);
+                }
+            }
+        }
+    }
 
-    for row in &result_set.rows {
+    let settings = classify::render_settings();
+    let classes = column_classes(result_set, &settings);
+    let mut urls = Vec::new();
+    for (row_idx, row) in result_set.rows.iter().enumerate() {
         let cells = row
             .iter()
-            .map(|value| format_cell(value, options.max_cell_width))
+            .enumerate()
+            .map(|(idx, value)| {
+                let class = classes.get(idx).copied().unwrap_or(ValueClass::Plain);
+                if class == ValueClass::Url {
+                    if let Value::Text(url) = value {
+                        urls.push(url.clone());
+                    }
+                }
+                let text = format_classified_cell(value, class, format, &settings, options);
+                styling.body_cell(text, value, row_idx)
+            })
             .collect::<Vec<_>>();
         table.add_row(cells);
     }
+    if styling.color {
+        table.enforce_styling();
+    }
 
     let mut output = table.to_string();
     if let Some(pagination) = &options.pagination {
@@ -108,7 +169,111 @@ pub fn render_result_set_table(
         output.push_str(&footer);
     }
 
-    truncate_output(output, options.max_output_chars)
+    let mut result = truncate_output(output, options.max_output_chars);
+    if !urls.is_empty() && classify::hyperlinks_enabled(settings.hyperlinks, format) {
+        result.output = classify::linkify(&result.output, &urls);
+    }
+    result
+}
+
+/// psql `\x` layout: a `-[ RECORD n ]-` rule per row, then one `name | value` line per
+/// column. Multi-line values continue under the value column.
+fn render_expanded(result_set: &ResultSet, options: &TableOptions) -> RenderResult {
+    let styling = theme::styling(OutputFormat::Expanded);
+    let settings = classify::render_settings();
+    let classes = column_classes(result_set, &settings);
+    let name_width = result_set
+        .columns
+        .iter()
+        .map(|col| col.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut urls = Vec::new();
+    let mut blocks = Vec::new();
+    for (number, row) in result_set.rows.iter().enumerate() {
+        let mut lines = Vec::new();
+        for (idx, value) in row.iter().enumerate() {
+            let class = classes.get(idx).copied().unwrap_or(ValueClass::Plain);
+            if class == ValueClass::Url {
+                if let Value::Text(url) = value {
+                    urls.push(url.clone());
+                }
+            }
+            let name = result_set
+                .columns
+                .get(idx)
+                .map(|col| col.name.as_str())
+                .unwrap_or("");
+            let cell =
+                format_classified_cell(value, class, OutputFormat::Expanded, &settings, options);
+            for (line_no, line) in cell.split('\n').enumerate() {
+                let label = if line_no == 0 { name } else { "" };
+                lines.push(ExpandedLine {
+                    plain_width: name_width + EXPANDED_SEPARATOR.len() + line.chars().count(),
+                    text: format!(
+                        "{}{}{}",
+                        styling.paint_name(&format!("{:<width$}", label, width = name_width)),
+                        EXPANDED_SEPARATOR,
+                        styling.paint_value(line, value)
+                    ),
+                });
+            }
+        }
+        blocks.push((format!("-[ RECORD {} ]", number + 1), lines));
+    }
+
+    // Every rule spans the widest line of any record, as psql does.
+    let rule_width = blocks
+        .iter()
+        .flat_map(|(title, lines)| {
+            std::iter::once(title.chars().count()).chain(lines.iter().map(|l| l.plain_width))
+        })
+        .max()
+        .unwrap_or(0);
+    let mut output = if blocks.is_empty() {
+        "(0 rows)".to_string()
+    } else {
+        blocks
+            .iter()
+            .map(|(title, lines)| {
+                let body = lines
+                    .iter()
+                    .map(|line| line.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("{:-<width$}\n{}", title, body, width = rule_width)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    if let Some(pagination) = &options.pagination {
+        output.push('\n');
+        output.push_str(&pagination_footer(pagination));
+    }
+    let mut result = truncate_output(output, options.max_output_chars);
+    if !urls.is_empty() && classify::hyperlinks_enabled(settings.hyperlinks, OutputFormat::Expanded)
+    {
+        result.output = classify::linkify(&result.output, &urls);
+    }
+    result
+}
+
+/// One line of expanded output; `plain_width` leaves out any ANSI escapes in `text`.
+struct ExpandedLine {
+    text: String,
+    plain_width: usize,
+}
+
+fn column_classes(result_set: &ResultSet, settings: &RenderSettingsResolved) -> Vec<ValueClass> {
+    (0..result_set.columns.len())
+        .map(|idx| {
+            if settings.detect_values {
+                classify::classify_column(result_set, idx)
+            } else {
+                ValueClass::Plain
+            }
+        })
+        .collect()
 }
 
 pub fn render_key_value_table(
@@ -130,7 +295,14 @@ pub fn render_key_value_table(
     for column in table.column_iter_mut() {
         column.set_padding((0, 0));
     }
-    table.set_header(vec![title.to_string(), "Value".to_string()]);
+    let styling = theme::styling(format);
+    table.set_header(vec![
+        styling.header_cell(title.to_string()),
+        styling.header_cell("Value".to_string()),
+    ]);
+    if styling.color {
+        table.enforce_styling();
+    }
 
     for (key, value) in rows {
         let key = truncate_string(key, options.max_cell_width);
@@ -141,12 +313,47 @@ pub fn render_key_value_table(
     truncate_output(table.to_string(), options.max_output_chars)
 }
 
-fn format_cell(value: &Value, max_cell_width: usize) -> String {
+fn format_cell(value: &Value, options: &TableOptions) -> String {
     let raw = match value {
-        Value::Null => NULL_DISPLAY.to_string(),
+        Value::Null => options.null_display.clone(),
+        Value::Bool(b) => options.booleans.render(*b).to_string(),
         _ => value.as_display(),
     };
-    truncate_string(&raw, max_cell_width)
+    truncate_string(&raw, options.max_cell_width)
+}
+
+fn format_classified_cell(
+    value: &Value,
+    class: ValueClass,
+    format: OutputFormat,
+    settings: &RenderSettingsResolved,
+    options: &TableOptions,
+) -> String {
+    let max_cell_width = options.max_cell_width;
+    if matches!(value, Value::Null) {
+        return options.null_display.clone();
+    }
+    let value = &*value.capped(options.max_value_bytes);
+    match class {
+        ValueClass::Money => match classify::format_money(value, &settings.currency_symbol) {
+            Some(money) => truncate_string(&money, max_cell_width),
+            None => format_cell(value, options),
+        },
+        ValueClass::Json => {
+            // Markdown rows cannot span lines, so only pretty tables get indented JSON.
+            let pretty = settings.pretty_json
+                && matches!(format, OutputFormat::Pretty | OutputFormat::Expanded);
+            let json = classify::format_json(&value.as_display(), pretty);
+            if pretty {
+                json
+            } else {
+                truncate_string(&json, max_cell_width)
+            }
+        }
+        // A shortened identifier is useless, so GUIDs are never truncated.
+        ValueClass::Guid => value.as_display(),
+        ValueClass::Url | ValueClass::Plain => format_cell(value, options),
+    }
 }
 
 fn truncate_string(input: &str, max_len: usize) -> String {
@@ -206,8 +413,57 @@ mod tests {
     #[test]
     fn truncates_cells() {
         let value = Value::Text("abcdefghijklmnopqrstuvwxyz".to_string());
-        let out = format_cell(&value, 8);
+        let options = TableOptions {
+            max_cell_width: 8,
+            ..TableOptions::default()
+        };
+        let out = format_cell(&value, &options);
         assert_eq!(out, "abcdefg…");
+
+        let rs = ResultSet {
+            columns: vec![Column {
+                name: "note".to_string(),
+                data_type: None,
+            }],
+            rows: vec![vec![value]],
+        };
+        let options = TableOptions {
+            max_value_bytes: Some(3),
+            ..TableOptions::default()
+        };
+        let result = render_result_set_table(&rs, OutputFormat::Markdown, &options);
+        assert!(result.output.contains("abc... (26 bytes)"));
+        assert_eq!(rs.rows[0][0].as_display(), "abcdefghijklmnopqrstuvwxyz");
+    }
+
+    #[test]
+    fn null_and_boolean_display_follow_options() {
+        let rs = ResultSet {
+            columns: vec![
+                Column {
+                    name: "flag".to_string(),
+                    data_type: None,
+                },
+                Column {
+                    name: "note".to_string(),
+                    data_type: None,
+                },
+            ],
+            rows: vec![vec![Value::Bool(true), Value::Null]],
+        };
+        let defaults = render_result_set_table(&rs, OutputFormat::Pretty, &TableOptions::default());
+        assert!(defaults.output.contains("true"));
+        assert!(defaults.output.contains("—"));
+
+        let options = TableOptions {
+            null_display: "NULL".to_string(),
+            booleans: BooleanStyle::YesNo,
+            ..TableOptions::default()
+        };
+        let result = render_result_set_table(&rs, OutputFormat::Pretty, &options);
+        assert!(result.output.contains("yes"));
+        assert!(result.output.contains("NULL"));
+        assert!(!result.output.contains("true"));
     }
 
     #[test]
@@ -224,6 +480,39 @@ mod tests {
         assert!(!result.truncation.truncated);
     }
 
+    #[test]
+    fn renders_expanded_records() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: None,
+        };
+        let rs = ResultSet {
+            columns: vec![column("id"), column("customerName")],
+            rows: vec![
+                vec![Value::Int(1), Value::Text("Contoso".to_string())],
+                vec![Value::Int(2), Value::Null],
+            ],
+        };
+        let result = render_result_set_table(&rs, OutputFormat::Expanded, &TableOptions::default());
+        assert_eq!(
+            result.output,
+            "-[ RECORD 1 ]---------\n\
+             id           | 1\n\
+             customerName | Contoso\n\
+             -[ RECORD 2 ]---------\n\
+             id           | 2\n\
+             customerName | —"
+        );
+
+        let empty = ResultSet {
+            columns: rs.columns.clone(),
+            rows: Vec::new(),
+        };
+        let result =
+            render_result_set_table(&empty, OutputFormat::Expanded, &TableOptions::default());
+        assert_eq!(result.output, "(0 rows)");
+    }
+
     #[test]
     fn adds_pagination_footer() {
         let rs = ResultSet {
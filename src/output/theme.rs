@@ -0,0 +1,240 @@
+//! ANSI styling for pretty and expanded tables (`settings.output.theme`).
+//!
+//! Markdown and JSON are never colored, and neither is anything when `NO_COLOR` is set or
+//! `--no-color` is passed; with `color: auto` stdout must also be a terminal. Right-aligning
+//! numbers is layout rather than color, so it follows `alignNumbers` alone.
+
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use comfy_table::{Attribute, Cell, Color};
+
+use crate::config::{ColorMode, OutputFormat, ThemeColor, ThemeSettingsResolved};
+use crate::db::types::{ResultSet, Value};
+
+/// Background of shaded rows: a dark grey from the 256-color palette.
+const STRIPE_BACKGROUND: u8 = 236;
+const SGR_RESET: &str = "\x1b[0m";
+
+/// Status words shown in red with `highlightErrors`, compared case-insensitively. They cover
+/// the states the DMV-backed commands report (backups, AGs, replication, database state).
+const ERROR_VALUES: &[&str] = &[
+    "error",
+    "failed",
+    "failure",
+    "suspect",
+    "offline",
+    "not_healthy",
+    "not healthy",
+    "unhealthy",
+    "critical",
+    "recovery_pending",
+    "emergency",
+    "deadlocked",
+];
+
+const NUMERIC_TYPES: &[&str] = &[
+    "tinyint",
+    "smallint",
+    "int",
+    "bigint",
+    "decimal",
+    "numeric",
+    "money",
+    "smallmoney",
+    "float",
+    "real",
+];
+
+static THEME: OnceLock<(ThemeSettingsResolved, bool)> = OnceLock::new();
+
+/// Set the theme used by table output; `no_color` is `--no-color`. Until it is set (help, or an
+/// error before config loads) tables use the default theme; once set it is fixed for the run.
+pub fn set_theme(theme: ThemeSettingsResolved, no_color: bool) {
+    let _ = THEME.set((theme, no_color));
+}
+
+/// The theme in effect for one rendered table.
+#[derive(Debug, Clone)]
+pub struct Styling {
+    pub theme: ThemeSettingsResolved,
+    /// Whether ANSI styles are emitted at all.
+    pub color: bool,
+}
+
+pub fn styling(format: OutputFormat) -> Styling {
+    let (theme, no_color) = THEME.get().cloned().unwrap_or_default();
+    let color = matches!(format, OutputFormat::Pretty | OutputFormat::Expanded)
+        && !no_color
+        && std::env::var_os("NO_COLOR").is_none()
+        && match theme.color {
+            ColorMode::Auto => std::io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        };
+    Styling { theme, color }
+}
+
+impl Styling {
+    pub fn header_cell(&self, text: String) -> Cell {
+        match self.header_color() {
+            Some(color) => Cell::new(text).fg(color).add_attribute(Attribute::Bold),
+            None => Cell::new(text),
+        }
+    }
+
+    /// Cell for `value`, already formatted as `text`, in zero-based body row `row_idx`.
+    pub fn body_cell(&self, text: String, value: &Value, row_idx: usize) -> Cell {
+        let mut cell = Cell::new(text);
+        if !self.color {
+            return cell;
+        }
+        if self.theme.dim_nulls && matches!(value, Value::Null) {
+            cell = cell.add_attribute(Attribute::Dim);
+        }
+        if self.theme.highlight_errors && is_error_value(value) {
+            cell = cell.fg(Color::Red);
+        }
+        if self.theme.stripe_rows && row_idx % 2 == 1 {
+            cell = cell.bg(Color::AnsiValue(STRIPE_BACKGROUND));
+        }
+        cell
+    }
+
+    /// A column name in expanded output, which is laid out by hand rather than by comfy-table.
+    pub fn paint_name(&self, text: &str) -> String {
+        match sgr_color(self.theme.header_color).filter(|_| self.color) {
+            Some(code) => format!("\x1b[1;{}m{}{}", code, text, SGR_RESET),
+            None => text.to_string(),
+        }
+    }
+
+    /// A value in expanded output.
+    pub fn paint_value(&self, text: &str, value: &Value) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        if self.theme.dim_nulls && matches!(value, Value::Null) {
+            return format!("\x1b[2m{}{}", text, SGR_RESET);
+        }
+        if self.theme.highlight_errors && is_error_value(value) {
+            return format!("\x1b[31m{}{}", text, SGR_RESET);
+        }
+        text.to_string()
+    }
+
+    fn header_color(&self) -> Option<Color> {
+        if !self.color {
+            return None;
+        }
+        match self.theme.header_color {
+            ThemeColor::None => None,
+            ThemeColor::Black => Some(Color::Black),
+            ThemeColor::Red => Some(Color::Red),
+            ThemeColor::Green => Some(Color::Green),
+            ThemeColor::Yellow => Some(Color::Yellow),
+            ThemeColor::Blue => Some(Color::Blue),
+            ThemeColor::Magenta => Some(Color::Magenta),
+            ThemeColor::Cyan => Some(Color::Cyan),
+            ThemeColor::White => Some(Color::White),
+            ThemeColor::Grey => Some(Color::DarkGrey),
+        }
+    }
+}
+
+fn sgr_color(color: ThemeColor) -> Option<u8> {
+    match color {
+        ThemeColor::None => None,
+        ThemeColor::Black => Some(30),
+        ThemeColor::Red => Some(31),
+        ThemeColor::Green => Some(32),
+        ThemeColor::Yellow => Some(33),
+        ThemeColor::Blue => Some(34),
+        ThemeColor::Magenta => Some(35),
+        ThemeColor::Cyan => Some(36),
+        ThemeColor::White => Some(37),
+        ThemeColor::Grey => Some(90),
+    }
+}
+
+pub fn is_error_value(value: &Value) -> bool {
+    match value {
+        Value::Text(text) => {
+            let text = text.trim();
+            ERROR_VALUES
+                .iter()
+                .any(|candidate| text.eq_ignore_ascii_case(candidate))
+        }
+        _ => false,
+    }
+}
+
+/// Whether column `index` holds numbers: a numeric SQL type, or only integer and float values.
+pub fn is_numeric_column(result_set: &ResultSet, index: usize) -> bool {
+    let data_type = result_set
+        .columns
+        .get(index)
+        .and_then(|column| column.data_type.as_deref());
+    if let Some(data_type) = data_type {
+        return NUMERIC_TYPES
+            .iter()
+            .any(|numeric| data_type.eq_ignore_ascii_case(numeric));
+    }
+    let mut values = result_set
+        .rows
+        .iter()
+        .filter_map(|row| row.get(index))
+        .filter(|value| !matches!(value, Value::Null))
+        .peekable();
+    values.peek().is_some()
+        && values.all(|value| matches!(value, Value::Int(_) | Value::Float(_) | Value::Decimal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::Column;
+
+    #[test]
+    fn classifies_numeric_columns_and_error_values() {
+        let column = |name: &str, data_type: Option<&str>| Column {
+            name: name.to_string(),
+            data_type: data_type.map(str::to_string),
+        };
+        let rs = ResultSet {
+            columns: vec![
+                column("id", None),
+                column("total", Some("decimal")),
+                column("state", Some("nvarchar")),
+            ],
+            rows: vec![
+                vec![
+                    Value::Int(1),
+                    Value::Text("12.50".to_string()),
+                    Value::Text("ONLINE".to_string()),
+                ],
+                vec![Value::Null, Value::Null, Value::Text("Suspect".to_string())],
+            ],
+        };
+        assert!(is_numeric_column(&rs, 0));
+        assert!(is_numeric_column(&rs, 1));
+        assert!(!is_numeric_column(&rs, 2));
+        assert!(!is_error_value(&rs.rows[0][2]));
+        assert!(is_error_value(&rs.rows[1][2]));
+
+        let styling = Styling {
+            theme: ThemeSettingsResolved::default(),
+            color: true,
+        };
+        assert_eq!(styling.paint_name("id"), "\x1b[1;36mid\x1b[0m");
+        assert_eq!(
+            styling.paint_value("Suspect", &rs.rows[1][2]),
+            "\x1b[31mSuspect\x1b[0m"
+        );
+        let plain = Styling {
+            color: false,
+            ..styling
+        };
+        assert_eq!(plain.paint_value("—", &Value::Null), "—");
+    }
+}
@@ -20,6 +20,7 @@ const ALLOWED_PROCS: &[&str] = &[
     "sp_datatype_info",
     "sp_special_columns",
     "sp_describe_first_result_set",
+    "xp_readerrorlog",
 ];
 
 pub fn allowed_procedures() -> Vec<&'static str> {
@@ -43,6 +44,9 @@ pub fn validate_read_only(sql: &str) -> Result<()> {
                 normalized
             ));
         }
+        if normalized == "xp_readerrorlog" {
+            validate_readerrorlog_args(exec_arguments(cleaned, &target))?;
+        }
     } else if lead_upper != "SELECT" && lead_upper != "WITH" {
         return Err(anyhow!(
             "Only read-only queries (SELECT/CTE/EXEC allowlist) are permitted"
@@ -140,6 +144,50 @@ fn extract_exec_target(input: &str) -> Option<String> {
     Some(target)
 }
 
+/// Text following the procedure name in an EXEC statement.
+fn exec_arguments<'a>(statement: &'a str, target: &str) -> &'a str {
+    statement
+        .find(target)
+        .map(|idx| &statement[idx + target.len()..])
+        .unwrap_or("")
+}
+
+/// `xp_readerrorlog` also accepts file paths and sort options on some versions; only allow the
+/// log number, log type, and up to two search strings.
+fn validate_readerrorlog_args(raw: &str) -> Result<()> {
+    let raw = raw.trim().trim_end_matches(';').trim();
+    if raw.is_empty() {
+        return Ok(());
+    }
+    let args: Vec<&str> = raw.split(',').map(str::trim).collect();
+    if args.len() > 4 {
+        return Err(anyhow!("xp_readerrorlog accepts at most 4 arguments here"));
+    }
+    for (idx, arg) in args.iter().enumerate() {
+        let ok = match idx {
+            0 => arg.parse::<u32>().is_ok_and(|n| n <= 99),
+            1 => matches!(*arg, "1" | "2"),
+            _ => is_string_literal(arg),
+        };
+        if !ok {
+            return Err(anyhow!(
+                "xp_readerrorlog argument {} is not allowed: {}",
+                idx + 1,
+                arg
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_string_literal(arg: &str) -> bool {
+    let body = arg.strip_prefix(['N', 'n']).unwrap_or(arg);
+    body.len() >= 2
+        && body.starts_with('\'')
+        && body.ends_with('\'')
+        && !body[1..body.len() - 1].contains('\'')
+}
+
 fn normalize_proc_name(raw: &str) -> Option<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -198,6 +246,17 @@ mod tests {
         assert!(validate_read_only("EXEC [dbo].[sp_helptext]").is_ok());
     }
 
+    #[test]
+    fn restricts_readerrorlog_arguments() {
+        assert!(validate_read_only("EXEC sys.xp_readerrorlog").is_ok());
+        assert!(validate_read_only("EXEC xp_readerrorlog 0, 1, N'deadlock'").is_ok());
+        let err = validate_read_only("EXEC xp_readerrorlog 0, 1, N'a', N'b', NULL, NULL, N'desc'")
+            .unwrap_err();
+        assert!(err.to_string().contains("at most"));
+        let err = validate_read_only("EXEC xp_readerrorlog 0, 3").unwrap_err();
+        assert!(err.to_string().contains("argument 2"));
+    }
+
     #[test]
     fn blocks_exec_unknown_proc() {
         let err = validate_read_only("EXEC sp_configure").unwrap_err();
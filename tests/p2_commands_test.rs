@@ -29,3 +29,13 @@ fn backups_json_smoke() {
     let value = common::run_json(["backups", "--json", "--limit", "1"]);
     assert!(value.get("backups").is_some());
 }
+
+#[test]
+fn assemblies_json_smoke() {
+    if !common::integration_enabled() {
+        return;
+    }
+
+    let value = common::run_json(["assemblies", "--json"]);
+    assert!(value.get("assemblies").is_some());
+}
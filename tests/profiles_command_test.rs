@@ -0,0 +1,84 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use std::fs;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"# team config
+defaultProfile: dev
+profiles:
+  # local docker
+  dev:
+    server: localhost
+    database: app
+"#;
+
+fn run_in(dir: &TempDir, args: &[&str]) -> serde_json::Value {
+    let mut cmd = cargo_bin_cmd!("sscli");
+    cmd.current_dir(dir.path()).env_clear().args(args);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    serde_json::from_slice(&output).expect("json")
+}
+
+#[test]
+fn profiles_add_edit_remove_round_trip() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let config_dir = temp_dir.path().join(".sql-server");
+    fs::create_dir_all(&config_dir).expect("config dir");
+    let config_path = config_dir.join("config.yaml");
+    fs::write(&config_path, CONFIG).expect("write config");
+
+    let added = run_in(
+        &temp_dir,
+        &[
+            "profiles",
+            "add",
+            "prod",
+            "--json",
+            "--server",
+            "prod-host",
+            "--database",
+            "sales",
+            "--password-env",
+            "PROD_PASSWORD",
+        ],
+    );
+    assert_eq!(added["action"], "added");
+
+    let content = fs::read_to_string(&config_path).expect("read config");
+    assert!(content.contains("# team config"));
+    assert!(content.contains("  # local docker"));
+    assert!(content.contains("  prod:\n    server: prod-host\n    database: sales\n"));
+
+    run_in(
+        &temp_dir,
+        &["profiles", "edit", "prod", "--json", "--database", "sales2"],
+    );
+
+    let listed = run_in(&temp_dir, &["profiles", "list", "--json"]);
+    assert_eq!(listed["defaultProfile"], "dev");
+    let profiles = listed["profiles"].as_array().expect("profiles array");
+    assert_eq!(profiles.len(), 2);
+    assert_eq!(profiles[1]["name"], "prod");
+    assert_eq!(profiles[1]["database"], "sales2");
+
+    run_in(
+        &temp_dir,
+        &["config", "profiles", "remove", "prod", "--json"],
+    );
+    let content = fs::read_to_string(&config_path).expect("read config");
+    assert_eq!(content, CONFIG);
+}
+
+#[test]
+fn profiles_add_rejects_duplicate_name() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(&config_path, CONFIG).expect("write config");
+
+    let mut cmd = cargo_bin_cmd!("sscli");
+    cmd.current_dir(temp_dir.path())
+        .env_clear()
+        .args(["profiles", "add", "dev", "--server", "other", "--config"])
+        .arg(&config_path);
+    let output = cmd.assert().failure().get_output().stderr.clone();
+    assert!(String::from_utf8_lossy(&output).contains("already exists"));
+}